@@ -31,6 +31,7 @@ use crate::{
     fyrox::{
         asset::{
             manager::ResourceManager,
+            registry,
             state::ResourceState,
             untyped::{ResourceHeader, ResourceKind, UntypedResource},
         },
@@ -234,6 +235,18 @@ impl ContextMenu {
             {
                 if message.destination() == self.delete {
                     Log::verify(std::fs::remove_file(&item.path));
+                    let metadata_path = registry::metadata_path(&item.path);
+                    let io = engine.resource_manager.resource_io();
+                    engine.resource_manager.task_pool().spawn_task(async move {
+                        if io.exists(&metadata_path).await {
+                            Log::verify(io.delete_file(&metadata_path).await);
+                        }
+                    });
+                    engine
+                        .resource_manager
+                        .state()
+                        .registry
+                        .unregister_path(&item.path);
                     return true;
                 } else if message.destination() == self.show_in_explorer {
                     if let Ok(canonical_path) = item.path.canonicalize() {
@@ -492,6 +505,7 @@ impl ResourceCreator {
                             let resource = UntypedResource(Arc::new(Mutex::new(ResourceHeader {
                                 kind: ResourceKind::External(path.clone()),
                                 type_uuid: instance.type_uuid(),
+                                resource_uuid: Default::default(),
                                 state: ResourceState::Ok(instance),
                             })));
 
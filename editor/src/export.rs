@@ -20,6 +20,7 @@
 
 use crate::{
     fyrox::{
+        asset::pak::{PakCompression, PakWriter, PAK_EXTENSION},
         core::{
             log::{Log, LogMessage, MessageKind},
             pool::Handle,
@@ -60,8 +61,8 @@ use fyrox::gui::utils::make_dropdown_list_option;
 use std::{
     ffi::OsStr,
     fmt::{Display, Formatter},
-    fs,
-    io::{self, BufRead, BufReader},
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter},
     path::{Path, PathBuf},
     process::Stdio,
     sync::{
@@ -82,6 +83,13 @@ struct ExportOptions {
     include_used_assets: bool,
     assets_folders: Vec<PathBuf>,
     ignored_extensions: Vec<String>,
+    #[reflect(
+        description = "Pack the assets into a single archive instead of copying them. \
+        Only PC builds support archives at the moment."
+    )]
+    pack_assets: bool,
+    #[reflect(description = "Compress the assets in the archive.")]
+    compress_assets: bool,
     #[reflect(hidden)]
     build_targets: Vec<String>,
     #[reflect(hidden)]
@@ -98,6 +106,8 @@ impl Default for ExportOptions {
             assets_folders: vec!["./data/".into()],
             include_used_assets: false,
             ignored_extensions: vec!["log".to_string()],
+            pack_assets: false,
+            compress_assets: true,
             build_targets: vec!["default".to_string()],
             selected_build_target: 0,
             run_after_build: false,
@@ -171,6 +181,25 @@ where
     Ok(())
 }
 
+fn pack_assets(
+    assets_folders: &[PathBuf],
+    archive_path: &Path,
+    compression: PakCompression,
+) -> io::Result<()> {
+    let mut writer = PakWriter::new(BufWriter::new(File::create(archive_path)?))?;
+    for folder in assets_folders {
+        let count = writer.add_directory(folder, |_| true, compression)?;
+        Log::info(format!(
+            "{} files from {} successfully packed to {}",
+            count,
+            folder.display(),
+            archive_path.display()
+        ));
+    }
+    writer.finish()?;
+    Ok(())
+}
+
 fn make_command(program: &str) -> std::process::Command {
     let mut command = std::process::Command::new(program);
     // Remove the `RUSTFLAGS` environment variable, which could be added to the child process
@@ -546,7 +575,33 @@ fn export(export_options: ExportOptions, cancel_flag: Arc<AtomicBool>) -> Result
 
     // Copy assets
     match export_options.target_platform {
+        TargetPlatform::PC if export_options.pack_assets => {
+            let archive_path = export_options
+                .destination_folder
+                .join(format!("data.{PAK_EXTENSION}"));
+
+            Log::info(format!(
+                "Trying to pack the assets to {}...",
+                archive_path.display()
+            ));
+
+            let compression = if export_options.compress_assets {
+                PakCompression::Deflate
+            } else {
+                PakCompression::None
+            };
+
+            pack_assets(&export_options.assets_folders, &archive_path, compression)
+                .map_err(|err| format!("Unable to pack the assets. Reason: {err}"))?;
+        }
         TargetPlatform::PC | TargetPlatform::WebAssembly => {
+            if export_options.pack_assets {
+                Log::warn(
+                    "Packing the assets is not supported for the target platform, \
+                    the assets will be copied instead.",
+                );
+            }
+
             Log::info("Trying to copy the assets...");
 
             for folder in export_options.assets_folders {
@@ -564,6 +619,13 @@ fn export(export_options: ExportOptions, cancel_flag: Arc<AtomicBool>) -> Result
             }
         }
         TargetPlatform::Android => {
+            if export_options.pack_assets {
+                Log::warn(
+                    "Packing the assets is not supported for the target platform, \
+                    the assets will be copied instead.",
+                );
+            }
+
             // Asset management on Android is quite annoying, because all other target platforms
             // uses the workspace manifest path as a root directory and all paths in code/assets
             // stored relatively to it. On Android, however, all your assets must be in unified
@@ -248,6 +248,8 @@ impl SceneRenderPass for HighlightRenderPass {
                     fallback_resources: ctx.fallback_resources,
                     ambient_light: Default::default(),
                     scene_depth: Some(&ctx.depth_texture),
+                    fog: Default::default(),
+                    motion: None,
                     viewport: ctx.viewport,
                     uniform_memory_allocator: ctx.uniform_memory_allocator,
                 },
@@ -24,23 +24,27 @@ use crate::fyrox::{
     core::{
         algebra::{Vector2, Vector3},
         color::Color,
+        log::Log,
         math::{ray::CylinderKind, TriangleEdge},
         pool::Handle,
         uuid::{uuid, Uuid},
         TypeUuidProvider,
     },
     engine::Engine,
+    fxhash::FxHashSet,
     gui::{
         button::{ButtonBuilder, ButtonMessage},
         grid::{Column, GridBuilder, Row},
         message::{KeyCode, MessageDirection, UiMessage},
         stack_panel::StackPanelBuilder,
+        utils::make_simple_tooltip,
         widget::{WidgetBuilder, WidgetMessage},
         window::{WindowBuilder, WindowMessage, WindowTitle},
         BuildContext, Orientation, Thickness, UiNode, UserInterface,
     },
     gui::{HorizontalAlignment, VerticalAlignment},
     scene::{camera::Camera, navmesh::NavigationalMesh},
+    utils::navmesh::generator::NavmeshInputData,
 };
 use crate::scene::SelectionContainer;
 use crate::{
@@ -58,7 +62,7 @@ use crate::{
         commands::{
             navmesh::{
                 AddNavmeshEdgeCommand, ConnectNavmeshEdgesCommand, DeleteNavmeshVertexCommand,
-                MoveNavmeshVertexCommand,
+                MoveNavmeshVertexCommand, SetNavmeshCommand,
             },
             ChangeSelectionCommand,
         },
@@ -76,6 +80,7 @@ pub mod selection;
 pub struct NavmeshPanel {
     pub window: Handle<UiNode>,
     connect_edges: Handle<UiNode>,
+    bake: Handle<UiNode>,
     sender: MessageSender,
     scene_frame: Handle<UiNode>,
 }
@@ -93,20 +98,42 @@ fn fetch_selection(editor_selection: &Selection) -> Option<NavmeshSelection> {
 impl NavmeshPanel {
     pub fn new(scene_frame: Handle<UiNode>, ctx: &mut BuildContext, sender: MessageSender) -> Self {
         let connect_edges;
+        let bake;
         let window = WindowBuilder::new(WidgetBuilder::new().with_name("NavmeshPanel"))
             .open(false)
             .with_title(WindowTitle::text("Navmesh"))
             .with_content(
                 GridBuilder::new(
                     WidgetBuilder::new().with_child(
-                        StackPanelBuilder::new(WidgetBuilder::new().with_child({
-                            connect_edges = ButtonBuilder::new(
-                                WidgetBuilder::new().with_margin(Thickness::uniform(1.0)),
-                            )
-                            .with_text("Connect Edges")
-                            .build(ctx);
-                            connect_edges
-                        }))
+                        StackPanelBuilder::new(
+                            WidgetBuilder::new()
+                                .with_child({
+                                    connect_edges = ButtonBuilder::new(
+                                        WidgetBuilder::new().with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .with_text("Connect Edges")
+                                    .build(ctx);
+                                    connect_edges
+                                })
+                                .with_child({
+                                    bake = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_margin(Thickness::uniform(1.0))
+                                            .with_tooltip(make_simple_tooltip(
+                                                ctx,
+                                                "Generates the navmesh from the selected \
+                                                meshes, terrains and colliders (select the \
+                                                navmesh first), using the settings from the \
+                                                Navmesh section of the editor settings. \
+                                                Enable Bake Whole Scene in the settings to \
+                                                use every such node in the scene instead.",
+                                            )),
+                                    )
+                                    .with_text("Bake")
+                                    .build(ctx);
+                                    bake
+                                }),
+                        )
                         .with_orientation(Orientation::Horizontal)
                         .build(ctx),
                     ),
@@ -121,11 +148,19 @@ impl NavmeshPanel {
             window,
             sender,
             connect_edges,
+            bake,
             scene_frame,
         }
     }
 
-    pub fn handle_message(&mut self, message: &UiMessage, editor_selection: &Selection) {
+    pub fn handle_message(
+        &mut self,
+        message: &UiMessage,
+        editor_selection: &Selection,
+        game_scene: &GameScene,
+        engine: &Engine,
+        settings: &Settings,
+    ) {
         if let Some(ButtonMessage::Click) = message.data::<ButtonMessage>() {
             if message.destination() == self.connect_edges {
                 if let Some(selection) = fetch_selection(editor_selection) {
@@ -146,6 +181,54 @@ impl NavmeshPanel {
                         [vertices[0], vertices[1]],
                     ));
                 }
+            } else if message.destination() == self.bake {
+                if let Some(selection) = fetch_selection(editor_selection) {
+                    let graph = &engine.scenes[game_scene.scene].graph;
+                    let navmesh_node = selection.navmesh_node();
+                    if graph
+                        .try_get_of_type::<NavigationalMesh>(navmesh_node)
+                        .is_some()
+                    {
+                        let navmesh = if settings.navmesh.bake_whole_scene {
+                            let editor_nodes = graph
+                                .traverse_handle_iter(game_scene.editor_objects_root)
+                                .collect::<FxHashSet<_>>();
+                            NavmeshInputData::from_graph(graph, |handle, _| {
+                                handle != navmesh_node && !editor_nodes.contains(&handle)
+                            })
+                        } else {
+                            let sources = editor_selection
+                                .as_graph()
+                                .map(|selection| {
+                                    selection
+                                        .nodes
+                                        .iter()
+                                        .filter(|handle| **handle != navmesh_node)
+                                        .cloned()
+                                        .collect::<FxHashSet<_>>()
+                                })
+                                .unwrap_or_default();
+                            if sources.is_empty() {
+                                Log::warn(
+                                    "Unable to bake the navmesh: select the navmesh and then \
+                                    the meshes, terrains or colliders to bake it from.",
+                                );
+                                return;
+                            }
+                            NavmeshInputData::from_graph(graph, |handle, _| {
+                                sources.contains(&handle)
+                            })
+                        }
+                        .bake(&settings.navmesh.bake);
+
+                        self.sender.do_command(CommandGroup::from(vec![
+                            Command::new(ChangeSelectionCommand::new(Selection::new(
+                                NavmeshSelection::empty(navmesh_node),
+                            ))),
+                            Command::new(SetNavmeshCommand::new(navmesh_node, navmesh)),
+                        ]));
+                    }
+                }
             }
         }
     }
@@ -65,7 +65,9 @@ use crate::{
     configurator::Configurator,
     export::ExportWindow,
     fyrox::{
-        asset::{io::FsResourceIo, manager::ResourceManager, untyped::ResourceKind},
+        asset::{
+            cache::ImportCache, io::FsResourceIo, manager::ResourceManager, untyped::ResourceKind,
+        },
         core::{
             algebra::{Matrix3, Vector2},
             color::Color,
@@ -139,10 +141,19 @@ use crate::{
     particle::ParticleSystemPreviewControlPanel,
     plugin::{EditorPlugin, EditorPluginsContainer},
     plugins::{
-        absm::AbsmEditor, absm::AbsmEditorPlugin, animation::AnimationEditorPlugin,
-        collider::ColliderPlugin, curve_editor::CurveEditorPlugin, material::MaterialPlugin,
-        path_fixer::PathFixerPlugin, ragdoll::RagdollPlugin, settings::SettingsPlugin,
-        stats::UiStatisticsPlugin, tilemap::TileMapEditorPlugin,
+        absm::AbsmEditor,
+        absm::AbsmEditorPlugin,
+        animation::AnimationEditorPlugin,
+        collider::ColliderPlugin,
+        curve_editor::CurveEditorPlugin,
+        material::MaterialPlugin,
+        path_fixer::PathFixerPlugin,
+        ragdoll::RagdollPlugin,
+        settings::SettingsPlugin,
+        spline::SplinePlugin,
+        stats::{ResourceStatisticsPlugin, UiStatisticsPlugin},
+        tilemap::TileMapEditorPlugin,
+        vehicle::VehiclePlugin,
     },
     scene::{
         commands::{
@@ -961,10 +972,13 @@ impl Editor {
                 .with(TileMapEditorPlugin::default())
                 .with(MaterialPlugin::default())
                 .with(RagdollPlugin::default())
+                .with(SplinePlugin::default())
+                .with(VehiclePlugin::default())
                 .with(SettingsPlugin::default())
                 .with(AnimationEditorPlugin::default())
                 .with(AbsmEditorPlugin::default())
                 .with(UiStatisticsPlugin::default())
+                .with(ResourceStatisticsPlugin::default())
                 .with(CurveEditorPlugin::default())
                 .with(PathFixerPlugin::default())
                 .with(inspector_plugin),
@@ -1382,8 +1396,13 @@ impl Editor {
                 self.scene_settings
                     .handle_ui_message(message, &self.message_sender);
 
-                self.navmesh_panel
-                    .handle_message(message, &current_scene_entry.selection);
+                self.navmesh_panel.handle_message(
+                    message,
+                    &current_scene_entry.selection,
+                    game_scene,
+                    engine,
+                    &self.settings,
+                );
 
                 if let Some(interaction_mode) = current_scene_entry
                     .current_interaction_mode
@@ -2104,6 +2123,19 @@ impl Editor {
 
         engine.resource_manager.state().destroy_unused_resources();
 
+        // Make sure that every resource of the project has a unique id, so references to it
+        // will survive moving the resource.
+        engine
+            .resource_manager
+            .update_registry_in_background(".", true);
+
+        // Source assets of the project (models, textures, etc.) are imported only once, the next
+        // time they will be loaded from the cache.
+        engine
+            .resource_manager
+            .state()
+            .set_import_cache(Some(ImportCache::default()));
+
         self.asset_browser
             .set_working_directory(engine, &working_directory, &self.message_sender);
 
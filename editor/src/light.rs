@@ -20,6 +20,8 @@
 
 use crate::fyrox::{
     core::{log::Log, pool::Handle, reflect::prelude::*},
+    engine::GraphicsContext,
+    graph::SceneGraph,
     gui::{
         button::{ButtonBuilder, ButtonMessage},
         formatted_text::WrapMode,
@@ -34,6 +36,7 @@ use crate::fyrox::{
         window::{WindowBuilder, WindowMessage, WindowTitle},
         BuildContext, HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
+    scene::reflection_probe::ReflectionProbe,
     utils::lightmap::{
         CancellationToken, Lightmap, LightmapGenerationError, LightmapInputData, ProgressIndicator,
     },
@@ -202,10 +205,44 @@ impl ProgressWindow {
     }
 }
 
+fn bake_reflection_probes(game_scene: &GameScene, engine: &mut Engine) {
+    let GraphicsContext::Initialized(ref mut graphics_context) = engine.graphics_context else {
+        Log::err("Unable to bake reflection probes - graphics context is not initialized!");
+        return;
+    };
+
+    let scene = &mut engine.scenes[game_scene.scene];
+
+    let probes = scene
+        .graph
+        .pair_iter()
+        .filter_map(|(handle, node)| node.cast::<ReflectionProbe>().map(|_| handle))
+        .collect::<Vec<_>>();
+
+    for probe in probes {
+        match graphics_context
+            .renderer
+            .bake_reflection_probe(scene, probe)
+        {
+            Ok(cube_map) => {
+                if let Some(probe) = scene.graph.try_get_mut_of_type::<ReflectionProbe>(probe) {
+                    probe.set_baked_cube_map(Some(cube_map));
+                }
+            }
+            Err(err) => {
+                Log::err(format!(
+                    "Failed to bake a reflection probe. Reason: {err:?}"
+                ));
+            }
+        }
+    }
+}
+
 pub struct LightPanel {
     pub window: Handle<UiNode>,
     inspector: Handle<UiNode>,
     generate: Handle<UiNode>,
+    bake_reflection_probes: Handle<UiNode>,
     settings: LightmapperSettings,
     progress_window: Option<ProgressWindow>,
     sender: Sender<Result<Lightmap, LightmapGenerationError>>,
@@ -218,6 +255,7 @@ impl LightPanel {
         let container = Arc::new(make_property_editors_container(sender));
 
         let generate;
+        let bake_reflection_probes;
         let inspector;
         let ctx = &mut engine.user_interfaces.first_mut().build_ctx();
         let window = WindowBuilder::new(
@@ -267,11 +305,23 @@ impl LightPanel {
                         .with_text("Generate Lightmap")
                         .build(ctx);
                         generate
+                    })
+                    .with_child({
+                        bake_reflection_probes = ButtonBuilder::new(
+                            WidgetBuilder::new()
+                                .on_row(2)
+                                .on_column(0)
+                                .with_margin(Thickness::uniform(1.0)),
+                        )
+                        .with_text("Bake Reflection Probes")
+                        .build(ctx);
+                        bake_reflection_probes
                     }),
             )
             .add_column(Column::stretch())
             .add_row(Row::stretch())
             .add_row(Row::strict(25.0))
+            .add_row(Row::strict(25.0))
             .build(ctx),
         )
         .build(ctx);
@@ -282,6 +332,7 @@ impl LightPanel {
             window,
             inspector,
             generate,
+            bake_reflection_probes,
             settings,
             progress_window: None,
             sender,
@@ -352,6 +403,8 @@ impl LightPanel {
                         ))
                     }
                 }
+            } else if message.destination() == self.bake_reflection_probes {
+                bake_reflection_probes(game_scene, engine);
             }
 
             if let Some(progress_window) = self.progress_window.as_ref() {
@@ -278,13 +278,24 @@ impl ParticleSystemPreviewControlPanel {
         let node_overrides = game_scene.graph_switches.node_overrides.as_mut().unwrap();
 
         if let Some(new_graph_selection) = editor_selection.as_graph() {
-            // Enable particle systems from new selection.
-            for &node_handle in &new_graph_selection.nodes {
-                if scene
-                    .graph
-                    .try_get_of_type::<ParticleSystem>(node_handle)
-                    .is_some()
+            // Enable particle systems from new selection, as well as the particle systems that
+            // are used by their sub-emitters.
+            let mut stack = new_graph_selection.nodes.clone();
+            while let Some(node_handle) = stack.pop() {
+                if node_overrides.contains(&node_handle) {
+                    continue;
+                }
+
+                if let Some(particle_system) =
+                    scene.graph.try_get_of_type::<ParticleSystem>(node_handle)
                 {
+                    stack.extend(
+                        particle_system
+                            .sub_emitters
+                            .iter()
+                            .map(|sub_emitter| sub_emitter.particle_system),
+                    );
+
                     self.particle_systems_state
                         .push((node_handle, scene.graph[node_handle].clone_box()));
 
@@ -27,7 +27,7 @@ use crate::fyrox::{
 };
 use crate::plugins::absm::command::fetch_machine;
 use crate::{
-    command::CommandTrait, define_push_element_to_collection_command,
+    command::CommandTrait, define_absm_swap_command, define_push_element_to_collection_command,
     define_set_collection_element_command,
 };
 use std::fmt::Debug;
@@ -93,6 +93,30 @@ define_set_collection_element_command!(
     }
 );
 
+define_set_collection_element_command!(
+    SetBlendAdditivePoseSourceCommand<Handle<PoseNode<Handle<N>>>, Handle<PoseNode<Handle<N>>>>(self, context) {
+        let machine = fetch_machine(context, self.node_handle);
+        if let PoseNode::BlendAdditive(ref mut definition) = machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+            let pose_source = if self.index == 0 {
+                &mut definition.base_pose
+            } else {
+                &mut definition.additive_pose
+            };
+            std::mem::swap(pose_source, &mut self.value);
+        }
+    }
+);
+
+define_absm_swap_command!(SetIkPoseSourceCommand<Handle<PoseNode<Handle<N>>>, Handle<PoseNode<Handle<N>>>>[layer_index: usize](self, context) {
+    let machine = fetch_machine(context, self.node_handle);
+    match &mut machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+        PoseNode::TwoBoneIk(definition) => &mut definition.input,
+        PoseNode::FabrikChain(definition) => &mut definition.input,
+        PoseNode::LookAt(definition) => &mut definition.input,
+        _ => unreachable!(),
+    }
+});
+
 #[derive(Debug)]
 pub struct RemoveBlendSpacePointCommand<N: Debug + 'static> {
     pub scene_node_handle: Handle<N>,
@@ -695,6 +695,12 @@ impl AbsmEditor {
                                             BlendSpacePoint::default(),
                                         ));
                                     }
+                                    PoseNode::BlendAdditive(_)
+                                    | PoseNode::TwoBoneIk(_)
+                                    | PoseNode::FabrikChain(_)
+                                    | PoseNode::LookAt(_) => {
+                                        // Fixed amount of input sockets
+                                    }
                                 }
                             }
                         }
@@ -23,7 +23,8 @@ use crate::fyrox::{
     core::{algebra::Vector2, pool::Handle},
     generic_animation::machine::{
         node::{blendspace::BlendSpace, blendspace::BlendSpacePoint, BasePoseNode},
-        BlendAnimations, BlendAnimationsByIndex, MachineLayer, PlayAnimation, PoseNode, State,
+        BlendAdditive, BlendAnimations, BlendAnimationsByIndex, FabrikChain, LookAt, MachineLayer,
+        PlayAnimation, PoseNode, State, TwoBoneIk,
     },
     graph::BaseSceneGraph,
     gui::{
@@ -38,8 +39,9 @@ use crate::fyrox::{
 use crate::plugins::absm::{
     command::{
         blend::{
-            SetBlendAnimationByIndexInputPoseSourceCommand, SetBlendAnimationsPoseSourceCommand,
-            SetBlendSpacePoseSourceCommand,
+            SetBlendAdditivePoseSourceCommand, SetBlendAnimationByIndexInputPoseSourceCommand,
+            SetBlendAnimationsPoseSourceCommand, SetBlendSpacePoseSourceCommand,
+            SetIkPoseSourceCommand,
         },
         AddPoseNodeCommand, DeletePoseNodeCommand, SetStateRootPoseCommand,
     },
@@ -60,6 +62,10 @@ pub struct CanvasContextMenu {
     create_blend_animations: Handle<UiNode>,
     create_blend_by_index: Handle<UiNode>,
     create_blend_space: Handle<UiNode>,
+    create_blend_additive: Handle<UiNode>,
+    create_two_bone_ik: Handle<UiNode>,
+    create_fabrik_chain: Handle<UiNode>,
+    create_look_at: Handle<UiNode>,
     pub menu: RcUiNodeHandle,
     pub canvas: Handle<UiNode>,
     pub node_context_menu: Option<RcUiNodeHandle>,
@@ -71,6 +77,10 @@ impl CanvasContextMenu {
         let create_blend_animations;
         let create_blend_by_index;
         let create_blend_space;
+        let create_blend_additive;
+        let create_two_bone_ik;
+        let create_fabrik_chain;
+        let create_look_at;
         let menu = ContextMenuBuilder::new(
             PopupBuilder::new(
                 WidgetBuilder::new()
@@ -96,6 +106,22 @@ impl CanvasContextMenu {
                         .with_child({
                             create_blend_space = create_menu_item("Blend Space", vec![], ctx);
                             create_blend_space
+                        })
+                        .with_child({
+                            create_blend_additive = create_menu_item("Blend Additive", vec![], ctx);
+                            create_blend_additive
+                        })
+                        .with_child({
+                            create_two_bone_ik = create_menu_item("Two Bone IK", vec![], ctx);
+                            create_two_bone_ik
+                        })
+                        .with_child({
+                            create_fabrik_chain = create_menu_item("FABRIK Chain", vec![], ctx);
+                            create_fabrik_chain
+                        })
+                        .with_child({
+                            create_look_at = create_menu_item("Look At", vec![], ctx);
+                            create_look_at
                         }),
                 )
                 .build(ctx),
@@ -109,6 +135,10 @@ impl CanvasContextMenu {
             create_blend_animations,
             create_blend_by_index,
             create_blend_space,
+            create_blend_additive,
+            create_two_bone_ik,
+            create_fabrik_chain,
+            create_look_at,
             menu,
             canvas: Default::default(),
             node_context_menu: Default::default(),
@@ -184,6 +214,26 @@ impl CanvasContextMenu {
                 ]);
 
                 Some(PoseNode::BlendSpace(blend_space))
+            } else if message.destination() == self.create_blend_additive {
+                let mut blend_additive = BlendAdditive::default();
+                blend_additive.position = position;
+                blend_additive.parent_state = current_state;
+                Some(PoseNode::BlendAdditive(blend_additive))
+            } else if message.destination() == self.create_two_bone_ik {
+                let mut two_bone_ik = TwoBoneIk::default();
+                two_bone_ik.position = position;
+                two_bone_ik.parent_state = current_state;
+                Some(PoseNode::TwoBoneIk(two_bone_ik))
+            } else if message.destination() == self.create_fabrik_chain {
+                let mut fabrik_chain = FabrikChain::default();
+                fabrik_chain.position = position;
+                fabrik_chain.parent_state = current_state;
+                Some(PoseNode::FabrikChain(fabrik_chain))
+            } else if message.destination() == self.create_look_at {
+                let mut look_at = LookAt::default();
+                look_at.position = position;
+                look_at.parent_state = current_state;
+                Some(PoseNode::LookAt(look_at))
             } else {
                 None
             };
@@ -381,6 +431,23 @@ impl ConnectionContextMenu {
                         index,
                         value: Default::default(),
                     }),
+                    PoseNode::BlendAdditive(_) => {
+                        sender.do_command(SetBlendAdditivePoseSourceCommand {
+                            node_handle: absm_node_handle,
+                            layer_index,
+                            handle: model_handle,
+                            index,
+                            value: Default::default(),
+                        })
+                    }
+                    PoseNode::TwoBoneIk(_) | PoseNode::FabrikChain(_) | PoseNode::LookAt(_) => {
+                        sender.do_command(SetIkPoseSourceCommand {
+                            node_handle: absm_node_handle,
+                            layer_index,
+                            handle: model_handle,
+                            value: Default::default(),
+                        })
+                    }
                 }
             }
         } else if let Some(PopupMessage::Placement(Placement::Cursor(target))) = message.data() {
@@ -25,7 +25,7 @@ use crate::fyrox::graph::{PrefabData, SceneGraphNode};
 use crate::fyrox::{
     core::pool::{ErasedHandle, Handle},
     generic_animation::{
-        machine::{MachineLayer, PoseNode, State},
+        machine::{MachineLayer, PoseNode, PoseWeight, State},
         Animation,
     },
     graph::{BaseSceneGraph, SceneGraph},
@@ -42,8 +42,9 @@ use crate::plugins::absm::{
     canvas::{AbsmCanvasBuilder, AbsmCanvasMessage},
     command::{
         blend::{
-            SetBlendAnimationByIndexInputPoseSourceCommand, SetBlendAnimationsPoseSourceCommand,
-            SetBlendSpacePoseSourceCommand,
+            SetBlendAdditivePoseSourceCommand, SetBlendAnimationByIndexInputPoseSourceCommand,
+            SetBlendAnimationsPoseSourceCommand, SetBlendSpacePoseSourceCommand,
+            SetIkPoseSourceCommand,
         },
         MovePoseNodeCommand,
     },
@@ -163,6 +164,17 @@ where
         PoseNode::BlendSpace(blend_space) => {
             format!("Blend Space: {:?} animations", blend_space.points().len())
         }
+        PoseNode::BlendAdditive(blend_additive) => match blend_additive.weight {
+            PoseWeight::Constant(weight) => format!("Blend Additive: {weight}"),
+            PoseWeight::Parameter(ref parameter) => format!("Blend Additive: {parameter}"),
+        },
+        PoseNode::TwoBoneIk(two_bone_ik) => format!("Two Bone IK: {}", two_bone_ik.target),
+        PoseNode::FabrikChain(fabrik_chain) => format!(
+            "FABRIK Chain: {} bones, {}",
+            fabrik_chain.bones.len(),
+            fabrik_chain.target
+        ),
+        PoseNode::LookAt(look_at) => format!("Look At: {}", look_at.target),
     }
 }
 
@@ -376,6 +388,25 @@ impl StateViewer {
                                         value: source_node,
                                     });
                                 }
+                                PoseNode::BlendAdditive(_) => {
+                                    sender.do_command(SetBlendAdditivePoseSourceCommand {
+                                        node_handle: absm_node_handle,
+                                        layer_index,
+                                        handle: dest_node,
+                                        index: dest_socket_ref.index,
+                                        value: source_node,
+                                    });
+                                }
+                                PoseNode::TwoBoneIk(_)
+                                | PoseNode::FabrikChain(_)
+                                | PoseNode::LookAt(_) => {
+                                    sender.do_command(SetIkPoseSourceCommand {
+                                        node_handle: absm_node_handle,
+                                        layer_index,
+                                        handle: dest_node,
+                                        value: source_node,
+                                    });
+                                }
                             }
                         }
                         _ => (),
@@ -512,6 +543,12 @@ impl StateViewer {
                                     PoseNode::BlendSpace(blend_space) => {
                                         (blend_space.points().len(), "Blend Space", true, true)
                                     }
+                                    PoseNode::BlendAdditive(_) => {
+                                        (2, "Blend Additive", false, false)
+                                    }
+                                    PoseNode::TwoBoneIk(_) => (1, "Two Bone IK", false, false),
+                                    PoseNode::FabrikChain(_) => (1, "FABRIK Chain", false, false),
+                                    PoseNode::LookAt(_) => (1, "Look At", false, false),
                                 };
 
                             let node_view = AbsmNodeBuilder::new(
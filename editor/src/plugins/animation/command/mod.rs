@@ -27,8 +27,8 @@ use crate::fyrox::{
         variable::InheritableVariable,
     },
     generic_animation::{
-        signal::AnimationSignal, track::Track, value::ValueBinding, Animation, AnimationContainer,
-        RootMotionSettings,
+        signal::AnimationSignal, track::Track, value::ValueBinding, AdditiveSettings, Animation,
+        AnimationContainer, RootMotionSettings,
     },
     graph::{BaseSceneGraph, SceneGraphNode},
 };
@@ -523,6 +523,13 @@ define_animation_swap_command!(SetAnimationRootMotionSettingsCommand<Option<Root
     self.value = old;
 });
 
+define_animation_swap_command!(SetAnimationAdditiveSettingsCommand<Option<AdditiveSettings>>(self, context) {
+    let animation = fetch_animation(self.node_handle, self.animation_handle, context);
+    let old = animation.additive_settings_ref().cloned();
+    animation.set_additive_settings(self.value.clone());
+    self.value = old;
+});
+
 #[derive(Debug)]
 pub struct AddAnimationSignal<N: Debug + 'static> {
     pub animation_player_handle: Handle<N>,
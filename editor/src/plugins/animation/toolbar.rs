@@ -24,7 +24,7 @@ use crate::fyrox::{
         algebra::Vector2, futures::executor::block_on, log::Log, math::Rect, pool::ErasedHandle,
         pool::Handle,
     },
-    generic_animation::{Animation, AnimationContainer, RootMotionSettings},
+    generic_animation::{AdditiveSettings, Animation, AnimationContainer, RootMotionSettings},
     graph::{BaseSceneGraph, PrefabData, SceneGraph, SceneGraphNode},
     gui::{
         border::BorderBuilder,
@@ -52,9 +52,9 @@ use crate::fyrox::{
 use crate::plugins::animation::{
     command::{
         AddAnimationCommand, RemoveAnimationCommand, ReplaceAnimationCommand,
-        SetAnimationEnabledCommand, SetAnimationLoopingCommand, SetAnimationNameCommand,
-        SetAnimationRootMotionSettingsCommand, SetAnimationSpeedCommand,
-        SetAnimationTimeSliceCommand,
+        SetAnimationAdditiveSettingsCommand, SetAnimationEnabledCommand,
+        SetAnimationLoopingCommand, SetAnimationNameCommand, SetAnimationRootMotionSettingsCommand,
+        SetAnimationSpeedCommand, SetAnimationTimeSliceCommand,
     },
     selection::AnimationSelection,
 };
@@ -100,6 +100,7 @@ pub struct Toolbar {
     pub selected_import_root: ErasedHandle,
     pub looping: Handle<UiNode>,
     pub enabled: Handle<UiNode>,
+    pub additive: Handle<UiNode>,
     root_motion_dropdown_area: RootMotionDropdownArea,
     pub root_motion: Handle<UiNode>,
     import_mode: ImportMode,
@@ -422,6 +423,7 @@ impl Toolbar {
         let reimport;
         let looping;
         let enabled;
+        let additive;
         let root_motion;
         let panel = BorderBuilder::new(
             WidgetBuilder::new()
@@ -643,6 +645,28 @@ impl Toolbar {
                                 .build(ctx);
                                 enabled
                             })
+                            .with_child({
+                                additive = CheckBoxBuilder::new(
+                                    WidgetBuilder::new()
+                                        .with_margin(Thickness::uniform(1.0))
+                                        .with_tooltip(make_simple_tooltip(
+                                            ctx,
+                                            "Makes the animation additive. Additive animation \
+                                            produces a difference between its current pose and \
+                                            the pose at the start of the time slice.",
+                                        )),
+                                )
+                                .with_content(
+                                    TextBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_vertical_alignment(VerticalAlignment::Center),
+                                    )
+                                    .with_text("Additive")
+                                    .build(ctx),
+                                )
+                                .build(ctx);
+                                additive
+                            })
                             .with_child(
                                 ImageBuilder::new(
                                     WidgetBuilder::new()
@@ -859,6 +883,7 @@ impl Toolbar {
             selected_import_root: Default::default(),
             looping,
             enabled,
+            additive,
             root_motion,
             root_motion_dropdown_area,
             import_mode: ImportMode::Import,
@@ -1002,6 +1027,18 @@ impl Toolbar {
                         animation_handle: selection.animation,
                         value: *checked,
                     });
+                } else if message.destination() == self.additive {
+                    sender.do_command(SetAnimationAdditiveSettingsCommand {
+                        node_handle: animation_player_handle,
+                        animation_handle: selection.animation,
+                        value: if *checked {
+                            Some(AdditiveSettings {
+                                reference_time: animations[selection.animation].time_slice().start,
+                            })
+                        } else {
+                            None
+                        },
+                    });
                 }
             }
         } else if let Some(NumericUpDownMessage::<f32>::Value(value)) = message.data() {
@@ -1286,6 +1323,15 @@ impl Toolbar {
                     Some(animation.is_enabled()),
                 ),
             );
+
+            send_sync_message(
+                ui,
+                CheckBoxMessage::checked(
+                    self.additive,
+                    MessageDirection::ToWidget,
+                    Some(animation.is_additive()),
+                ),
+            );
         }
 
         for widget in [
@@ -1298,6 +1344,7 @@ impl Toolbar {
             self.clone_current_animation,
             self.looping,
             self.enabled,
+            self.additive,
             self.root_motion,
         ] {
             send_sync_message(
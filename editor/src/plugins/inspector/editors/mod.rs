@@ -42,7 +42,10 @@ use crate::plugins::{
 use crate::{
     fyrox::{
         asset::{untyped::UntypedResource, Resource},
-        core::pool::{ErasedHandle, Handle},
+        core::{
+            algebra::Vector2,
+            pool::{ErasedHandle, Handle},
+        },
         gui::{
             self,
             font::FontResource,
@@ -72,9 +75,11 @@ use crate::{
                 Base, LevelOfDetail, LodGroup, Mobility, Property, PropertyValue, ScriptRecord,
             },
             camera::{
-                ColorGradingLut, Exposure, OrthographicProjection, PerspectiveProjection,
-                Projection, SkyBox,
+                AntiAliasing, ColorGradingLut, DepthOfField, Exposure, MotionBlur,
+                OrthographicProjection, PerspectiveProjection, Projection, ScreenSpaceReflections,
+                SkyBox,
             },
+            cloth::{ClothPinning, ColorChannel},
             collider::{
                 BallShape, BitMask, CapsuleShape, ColliderShape, ConeShape, ConvexPolyhedronShape,
                 CuboidShape, CylinderShape, GeometrySource, HeightfieldShape, InteractionGroups,
@@ -94,13 +99,17 @@ use crate::{
             node::Node,
             particle_system::CoordinateSystem,
             particle_system::{
+                collision::{ParticleCollision, ParticleCollisionResponse},
                 emitter::{
                     base::BaseEmitter, cuboid::CuboidEmitter, cylinder::CylinderEmitter,
-                    sphere::SphereEmitter, Emitter,
+                    mesh::MeshEmitter, sphere::SphereEmitter, Emitter,
                 },
-                ParticleSystemRng,
+                force::ForceField,
+                sub_emitter::{SubEmitter, SubEmitterTrigger},
+                ParticleSimulation, ParticleSystemRng,
             },
             ragdoll::Limb,
+            reflection_probe::{ReflectionProbeShape, ReflectionProbeUpdateMode},
             rigidbody::RigidBodyType,
             sound::{
                 self,
@@ -112,11 +121,13 @@ use crate::{
                 Attenuate, AudioBus, Biquad, DistanceModel, Effect, SoundBuffer,
                 SoundBufferResource, Status,
             },
+            spline::{SplineExtrusion, SplineKind, SplinePoint},
             terrain::{Chunk, Layer},
             tilemap::brush::{TileMapBrush, TileMapBrushResource},
-            tilemap::TileCollider,
             tilemap::{tileset::TileSet, Tile},
+            tilemap::{TileCollider, TileGridLayout},
             transform::Transform,
+            vehicle::VehicleWheel,
         },
     },
     message::MessageSender,
@@ -161,8 +172,9 @@ where
         },
         state::{StateAction, StateActionWrapper},
         transition::{AndNode, LogicNode, NotNode, OrNode, XorNode},
-        BlendAnimations, BlendAnimationsByIndex, BlendPose, IndexedBlendInput, Machine,
-        PlayAnimation, PoseNode, PoseWeight, State,
+        BlendAdditive, BlendAnimations, BlendAnimationsByIndex, BlendPose, FabrikChain,
+        IndexedBlendInput, LayerBlendMode, LookAt, Machine, PlayAnimation, PoseNode, PoseWeight,
+        State, TwoBoneIk,
     };
 
     container.insert(InspectablePropertyEditorDefinition::<BasePoseNode<Handle<T>>>::new());
@@ -198,6 +210,13 @@ where
     container.insert(InspectablePropertyEditorDefinition::<
         PlayAnimation<Handle<T>>,
     >::new());
+    container.insert(InspectablePropertyEditorDefinition::<
+        BlendAdditive<Handle<T>>,
+    >::new());
+    container.insert(EnumPropertyEditorDefinition::<LayerBlendMode>::new());
+    container.insert(InspectablePropertyEditorDefinition::<TwoBoneIk<Handle<T>>>::new());
+    container.insert(InspectablePropertyEditorDefinition::<FabrikChain<Handle<T>>>::new());
+    container.insert(InspectablePropertyEditorDefinition::<LookAt<Handle<T>>>::new());
 
     container.insert(InspectablePropertyEditorDefinition::<
         Handle<PoseNode<Handle<T>>>,
@@ -264,12 +283,26 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_vec_collection::<Property>();
     container.register_inheritable_inspectable::<Property>();
 
+    container.register_inheritable_inspectable::<ScreenSpaceReflections>();
+    container.register_inheritable_inspectable::<DepthOfField>();
+    container.register_inheritable_inspectable::<MotionBlur>();
+
     container.register_inheritable_vec_collection::<GeometrySource>();
     container.register_inheritable_inspectable::<GeometrySource>();
 
     container.register_inheritable_vec_collection::<dim2::collider::GeometrySource>();
     container.register_inheritable_inspectable::<dim2::collider::GeometrySource>();
 
+    container.register_inheritable_vec_collection::<SplinePoint>();
+    container.register_inheritable_inspectable::<SplinePoint>();
+    container.register_inheritable_vec_collection::<VehicleWheel>();
+    container.register_inheritable_inspectable::<VehicleWheel>();
+
+    container.register_inheritable_vec_collection::<ForceField>();
+    container.register_inheritable_vec_collection::<SubEmitter>();
+    container.register_inheritable_inspectable::<SubEmitter>();
+    container.register_inheritable_inspectable::<ParticleCollision>();
+
     container.insert(make_status_enum_editor_definition());
 
     container.insert(EnumPropertyEditorDefinition::<LodGroup>::new_optional());
@@ -351,6 +384,8 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
 
     container.register_inheritable_inspectable::<Base>();
     container.register_inheritable_inspectable::<BaseLight>();
+    container.register_inheritable_inspectable::<dim2::light::BaseLight>();
+    container.register_inheritable_vec_collection::<Vector2<f32>>();
 
     container.insert(EnumPropertyEditorDefinition::<Effect>::new());
     container.insert(VecCollectionPropertyEditorDefinition::<Effect>::new());
@@ -371,6 +406,7 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_inspectable::<BaseEmitter>();
     container.register_inheritable_inspectable::<SphereEmitter>();
     container.register_inheritable_inspectable::<CylinderEmitter>();
+    container.register_inheritable_inspectable::<MeshEmitter>();
     container.register_inheritable_inspectable::<CuboidEmitter>();
     container.register_inheritable_inspectable::<PerspectiveProjection>();
     container.register_inheritable_inspectable::<OrthographicProjection>();
@@ -396,17 +432,28 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_enum::<TextureMagnificationFilter, _>();
     container.register_inheritable_enum::<TextureMinificationFilter, _>();
     container.register_inheritable_enum::<Projection, _>();
+    container.register_inheritable_enum::<ReflectionProbeShape, _>();
+    container.register_inheritable_enum::<ReflectionProbeUpdateMode, _>();
+    container.register_inheritable_enum::<SplineKind, _>();
+    container.register_inheritable_enum::<SplineExtrusion, _>();
     container.register_inheritable_enum::<ColliderShape, _>();
     container.register_inheritable_enum::<PropertyValue, _>();
     container.register_inheritable_enum::<Mobility, _>();
     container.register_inheritable_enum::<RigidBodyType, _>();
     container.register_inheritable_enum::<Exposure, _>();
+    container.register_inheritable_enum::<AntiAliasing, _>();
     container.register_inheritable_enum::<FrustumSplitOptions, _>();
     container.register_inheritable_enum::<MaterialSearchOptions, _>();
     container.register_inheritable_enum::<DistanceModel, _>();
     container.register_inheritable_enum::<sound::Renderer, _>();
     container.register_inheritable_enum::<RenderPath, _>();
     container.register_inheritable_enum::<CoordinateSystem, _>();
+    container.register_inheritable_enum::<ForceField, _>();
+    container.register_inheritable_enum::<ParticleCollisionResponse, _>();
+    container.register_inheritable_enum::<SubEmitterTrigger, _>();
+    container.register_inheritable_enum::<ParticleSimulation, _>();
+    container.register_inheritable_enum::<ClothPinning, _>();
+    container.register_inheritable_enum::<ColorChannel, _>();
 
     container.insert(EnumPropertyEditorDefinition::<Vec<ScriptRecord>>::new_optional());
     container.insert(VecCollectionPropertyEditorDefinition::<ScriptRecord>::new());
@@ -485,6 +532,7 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_vec_collection::<Tile>();
 
     container.register_inheritable_enum::<TileCollider, _>();
+    container.register_inheritable_enum::<TileGridLayout, _>();
     container.register_inheritable_enum::<RigidBodyMassPropertiesType, _>();
 
     container
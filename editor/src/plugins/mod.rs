@@ -27,5 +27,7 @@ pub mod material;
 pub mod path_fixer;
 pub mod ragdoll;
 pub mod settings;
+pub mod spline;
 pub mod stats;
 pub mod tilemap;
+pub mod vehicle;
@@ -48,6 +48,7 @@ use crate::{
             HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
         },
         renderer::{CsmSettings, QualitySettings, ShadowMapPrecision},
+        utils::navmesh::generator::NavmeshBakeSettings,
     },
     menu::create_menu_item,
     message::MessageSender,
@@ -95,6 +96,7 @@ fn make_property_editors_container(
     >::new());
     container.insert(InspectablePropertyEditorDefinition::<ModelSettings>::new());
     container.insert(InspectablePropertyEditorDefinition::<NavmeshSettings>::new());
+    container.insert(InspectablePropertyEditorDefinition::<NavmeshBakeSettings>::new());
     container.insert(InspectablePropertyEditorDefinition::<KeyBindings>::new());
     container.insert(InspectablePropertyEditorDefinition::<TerrainKeyBindings>::new());
     container.insert(InspectablePropertyEditorDefinition::<BuildSettings>::new());
@@ -0,0 +1,543 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Spline editing plugin. It allows editing control points of splines (and tangents of Bezier
+//! splines) directly in the scene viewer. `Insert` key adds a new point after the selected one,
+//! `Delete` key removes the selected point.
+
+use crate::{
+    camera::PickingOptions,
+    command::SetPropertyCommand,
+    fyrox::{
+        core::{
+            algebra::{Point3, Vector2, Vector3},
+            color::Color,
+            pool::Handle,
+            some_or_return,
+            type_traits::prelude::*,
+            Uuid,
+        },
+        engine::Engine,
+        graph::{BaseSceneGraph, SceneGraph, SceneGraphNode},
+        gui::{message::KeyCode, BuildContext, UiNode},
+        scene::{
+            node::Node,
+            spline::{Spline, SplineKind, SplinePoint},
+            sprite::Sprite,
+            Scene,
+        },
+    },
+    interaction::{
+        calculate_gizmo_distance_scaling, gizmo::move_gizmo::MoveGizmo,
+        make_interaction_mode_button, plane::PlaneKind, InteractionMode,
+    },
+    message::MessageSender,
+    plugin::EditorPlugin,
+    plugins::collider::make_handle,
+    scene::{commands::GameSceneContext, controller::SceneController, GameScene, Selection},
+    settings::Settings,
+    Editor, Message,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum SplineHandleKind {
+    Position,
+    InTangent,
+    OutTangent,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct SplineHandle {
+    index: usize,
+    kind: SplineHandleKind,
+}
+
+impl SplineHandle {
+    fn local_position(&self, points: &[SplinePoint]) -> Option<Vector3<f32>> {
+        let point = points.get(self.index)?;
+        Some(match self.kind {
+            SplineHandleKind::Position => point.position,
+            SplineHandleKind::InTangent => point.position + point.in_tangent,
+            SplineHandleKind::OutTangent => point.position + point.out_tangent,
+        })
+    }
+
+    fn apply_offset(&self, points: &mut [SplinePoint], offset: Vector3<f32>) {
+        let Some(point) = points.get_mut(self.index) else {
+            return;
+        };
+        match self.kind {
+            SplineHandleKind::Position => point.position += offset,
+            SplineHandleKind::InTangent => point.in_tangent += offset,
+            SplineHandleKind::OutTangent => point.out_tangent += offset,
+        }
+    }
+}
+
+fn required_handles(spline: &Spline) -> Vec<SplineHandle> {
+    let mut handles = Vec::new();
+    for index in 0..spline.points().len() {
+        handles.push(SplineHandle {
+            index,
+            kind: SplineHandleKind::Position,
+        });
+        if spline.kind() == SplineKind::Bezier {
+            for kind in [SplineHandleKind::InTangent, SplineHandleKind::OutTangent] {
+                handles.push(SplineHandle { index, kind });
+            }
+        }
+    }
+    handles
+}
+
+struct DragContext {
+    plane_kind: PlaneKind,
+    initial_points: Vec<SplinePoint>,
+}
+
+#[derive(TypeUuidProvider)]
+#[type_uuid(id = "b4a2f89c-0d64-49aa-9dd9-b9a557572cf0")]
+pub struct SplineInteractionMode {
+    spline: Handle<Node>,
+    handles: Vec<(Handle<Node>, SplineHandle)>,
+    move_gizmo: MoveGizmo,
+    selected_handle: Option<SplineHandle>,
+    drag_context: Option<DragContext>,
+    visible: bool,
+    message_sender: MessageSender,
+}
+
+impl SplineInteractionMode {
+    fn destroy_handles(&mut self, scene: &mut Scene) {
+        for (handle, _) in self.handles.drain(..) {
+            scene.graph.remove_node(handle);
+        }
+    }
+
+    fn destroy(mut self, scene: &mut Scene) {
+        self.destroy_handles(scene);
+        self.move_gizmo.destroy(&mut scene.graph);
+    }
+
+    fn set_visibility(
+        &mut self,
+        controller: &dyn SceneController,
+        engine: &mut Engine,
+        visibility: bool,
+    ) {
+        let Some(game_scene) = controller.downcast_ref::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        self.visible = visibility;
+        for (handle, _) in self.handles.iter() {
+            scene.graph[*handle].set_visibility(visibility);
+        }
+        if !visibility {
+            self.move_gizmo.set_visible(&mut scene.graph, false);
+        }
+    }
+
+    fn find_handle(&self, node: Handle<Node>) -> Option<SplineHandle> {
+        self.handles
+            .iter()
+            .find_map(|(handle, spline_handle)| (*handle == node).then_some(*spline_handle))
+    }
+
+    // Recreates the handles if the amount of points or the kind of the spline has changed and
+    // moves them to the actual positions of the points.
+    fn sync_handles(&mut self, game_scene: &GameScene, scene: &mut Scene) {
+        let Some(spline) = scene.graph.try_get_of_type::<Spline>(self.spline) else {
+            return;
+        };
+        let required = required_handles(spline);
+        let points = spline.points().to_vec();
+        let transform = spline.global_transform();
+
+        if self.handles.len() != required.len()
+            || self
+                .handles
+                .iter()
+                .zip(required.iter())
+                .any(|(a, b)| a.1 != *b)
+        {
+            self.destroy_handles(scene);
+            for spline_handle in required {
+                let handle = make_handle(scene, game_scene.editor_objects_root, self.visible);
+                self.handles.push((handle, spline_handle));
+            }
+        }
+
+        if self
+            .selected_handle
+            .is_some_and(|h| h.local_position(&points).is_none())
+        {
+            self.selected_handle = None;
+        }
+
+        for (handle, spline_handle) in self.handles.iter() {
+            let Some(local_position) = spline_handle.local_position(&points) else {
+                continue;
+            };
+            let scale = calculate_gizmo_distance_scaling(
+                &scene.graph,
+                game_scene.camera_controller.camera,
+                *handle,
+            );
+            let node = &mut scene.graph[*handle];
+            node.local_transform_mut()
+                .set_position(
+                    transform
+                        .transform_point(&Point3::from(local_position))
+                        .coords,
+                )
+                .set_scale(scale);
+            if let Some(sprite) = node.component_mut::<Sprite>() {
+                let (size, color) = if spline_handle.kind == SplineHandleKind::Position {
+                    (0.05, Color::MAROON)
+                } else {
+                    (0.035, Color::ORANGE)
+                };
+                sprite.set_size(size * scale.x);
+                if self.selected_handle == Some(*spline_handle) {
+                    sprite.set_color(Color::GREEN);
+                } else if sprite.color() != Color::RED {
+                    sprite.set_color(color);
+                }
+            }
+        }
+    }
+
+    fn set_points(&self, points: Vec<SplinePoint>) {
+        let spline = self.spline;
+        let command = SetPropertyCommand::new("points".into(), Box::new(points), move |ctx| {
+            ctx.get_mut::<GameSceneContext>()
+                .scene
+                .graph
+                .node_mut(spline)
+        });
+        self.message_sender.do_command(command);
+    }
+}
+
+impl InteractionMode for SplineInteractionMode {
+    fn on_left_mouse_button_down(
+        &mut self,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        mouse_position: Vector2<f32>,
+        _frame_size: Vector2<f32>,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        let Some(result) = game_scene.camera_controller.pick(
+            &scene.graph,
+            PickingOptions {
+                cursor_pos: mouse_position,
+                editor_only: true,
+                filter: Some(&mut |handle, _| handle != self.move_gizmo.origin),
+                ..Default::default()
+            },
+        ) else {
+            return;
+        };
+
+        if let Some(spline_handle) = self.find_handle(result.node) {
+            self.selected_handle = Some(spline_handle);
+        } else if let Some(plane_kind) = self.move_gizmo.handle_pick(result.node, &mut scene.graph)
+        {
+            if self.selected_handle.is_some() {
+                if let Some(spline) = scene.graph.try_get_of_type::<Spline>(self.spline) {
+                    self.drag_context = Some(DragContext {
+                        plane_kind,
+                        initial_points: spline.points().to_vec(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn on_left_mouse_button_up(
+        &mut self,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        _mouse_pos: Vector2<f32>,
+        _frame_size: Vector2<f32>,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        if let Some(drag_context) = self.drag_context.take() {
+            if let Some(spline) = scene.graph.try_get_mut_of_type::<Spline>(self.spline) {
+                // Revert the changes made during dragging and commit them using a command.
+                let points = spline.set_points(drag_context.initial_points);
+                self.set_points(points);
+            }
+        }
+    }
+
+    fn on_mouse_move(
+        &mut self,
+        mouse_offset: Vector2<f32>,
+        mouse_position: Vector2<f32>,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        frame_size: Vector2<f32>,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        for (handle, _) in self.handles.iter() {
+            scene.graph[*handle]
+                .as_sprite_mut()
+                .set_color(Color::MAROON);
+        }
+        self.move_gizmo.reset_state(&mut scene.graph);
+
+        if let Some(result) = game_scene.camera_controller.pick(
+            &scene.graph,
+            PickingOptions {
+                cursor_pos: mouse_position,
+                editor_only: true,
+                filter: Some(&mut |handle, _| handle != self.move_gizmo.origin),
+                ..Default::default()
+            },
+        ) {
+            if self.find_handle(result.node).is_some() {
+                scene.graph[result.node]
+                    .as_sprite_mut()
+                    .set_color(Color::RED);
+            }
+
+            self.move_gizmo.handle_pick(result.node, &mut scene.graph);
+        }
+
+        let Some(drag_context) = self.drag_context.as_ref() else {
+            return;
+        };
+        let Some(selected_handle) = self.selected_handle else {
+            return;
+        };
+
+        let global_offset = self.move_gizmo.calculate_offset(
+            &scene.graph,
+            game_scene.camera_controller.camera,
+            mouse_offset,
+            mouse_position,
+            frame_size,
+            drag_context.plane_kind,
+        );
+
+        if let Some(spline) = scene.graph.try_get_mut_of_type::<Spline>(self.spline) {
+            let local_offset = spline
+                .global_transform()
+                .try_inverse()
+                .unwrap_or_default()
+                .transform_vector(&global_offset);
+            selected_handle.apply_offset(spline.points_mut(), local_offset);
+        }
+    }
+
+    fn update(
+        &mut self,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        self.sync_handles(game_scene, scene);
+
+        let selected_position = self.selected_handle.and_then(|selected_handle| {
+            let spline = scene.graph.try_get_of_type::<Spline>(self.spline)?;
+            let local_position = selected_handle.local_position(spline.points())?;
+            Some(
+                spline
+                    .global_transform()
+                    .transform_point(&Point3::from(local_position))
+                    .coords,
+            )
+        });
+
+        self.move_gizmo.set_visible(
+            &mut scene.graph,
+            self.visible && selected_position.is_some(),
+        );
+
+        if let Some(position) = selected_position {
+            let scale = calculate_gizmo_distance_scaling(
+                &scene.graph,
+                game_scene.camera_controller.camera,
+                self.move_gizmo.origin,
+            );
+            self.move_gizmo
+                .transform(&mut scene.graph)
+                .set_position(position)
+                .set_scale(scale);
+        }
+    }
+
+    fn activate(&mut self, controller: &dyn SceneController, engine: &mut Engine) {
+        self.set_visibility(controller, engine, true)
+    }
+
+    fn deactivate(&mut self, controller: &dyn SceneController, engine: &mut Engine) {
+        self.set_visibility(controller, engine, false)
+    }
+
+    fn make_button(&mut self, ctx: &mut BuildContext, selected: bool) -> Handle<UiNode> {
+        make_interaction_mode_button(
+            ctx,
+            include_bytes!("../../resources/curve.png"),
+            "Edit Spline Points",
+            selected,
+        )
+    }
+
+    fn on_key_down(
+        &mut self,
+        key: KeyCode,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+    ) -> bool {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return false;
+        };
+
+        let scene = &engine.scenes[game_scene.scene];
+
+        let Some(spline) = scene.graph.try_get_of_type::<Spline>(self.spline) else {
+            return false;
+        };
+
+        let mut points = spline.points().to_vec();
+        let selected_index = self.selected_handle.map(|h| h.index);
+
+        match key {
+            KeyCode::Insert => {
+                let index = selected_index.unwrap_or(points.len().saturating_sub(1));
+                let count = points.len();
+                let position = if count == 0 {
+                    Vector3::default()
+                } else if index + 1 < count || spline.is_closed() {
+                    // Put the new point in the middle of the segment.
+                    let segments = if spline.is_closed() { count } else { count - 1 };
+                    spline.sample((index as f32 + 0.5) / segments as f32)
+                } else if count > 1 {
+                    // Continue the open spline in the direction of its last segment.
+                    points[index].position * 2.0 - points[index - 1].position
+                } else {
+                    points[index].position + Vector3::x()
+                };
+                let tangent =
+                    spline.tangent_at_distance(spline.closest_point(position).distance) * 0.5;
+                let insert_at = if count == 0 { 0 } else { index + 1 };
+                points.insert(insert_at, SplinePoint::new(position).with_tangent(tangent));
+                self.selected_handle = Some(SplineHandle {
+                    index: insert_at,
+                    kind: SplineHandleKind::Position,
+                });
+                self.set_points(points);
+                true
+            }
+            KeyCode::Delete => {
+                let Some(index) = selected_index else {
+                    return false;
+                };
+                if index < points.len() {
+                    points.remove(index);
+                    self.selected_handle = None;
+                    self.set_points(points);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn uuid(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+#[derive(Default)]
+pub struct SplinePlugin;
+
+impl EditorPlugin for SplinePlugin {
+    fn on_message(&mut self, message: &Message, editor: &mut Editor) {
+        let entry = some_or_return!(editor.scenes.current_scene_entry_mut());
+        let selection = some_or_return!(entry.selection.as_graph());
+        let game_scene = some_or_return!(entry.controller.downcast_mut::<GameScene>());
+
+        let scene = &mut editor.engine.scenes[game_scene.scene];
+
+        if let Message::SelectionChanged { .. } = message {
+            if let Some(mode) = entry
+                .interaction_modes
+                .remove_typed::<SplineInteractionMode>()
+            {
+                mode.destroy(scene);
+            }
+
+            let first_selected_spline = selection
+                .nodes()
+                .iter()
+                .find(|h| scene.graph.try_get_of_type::<Spline>(**h).is_some());
+
+            if let Some(first_selected_spline) = first_selected_spline {
+                let move_gizmo = MoveGizmo::new(game_scene, &mut editor.engine);
+
+                entry.interaction_modes.add(SplineInteractionMode {
+                    spline: *first_selected_spline,
+                    handles: Default::default(),
+                    move_gizmo,
+                    selected_handle: None,
+                    drag_context: None,
+                    visible: false,
+                    message_sender: editor.message_sender.clone(),
+                });
+            }
+        }
+    }
+}
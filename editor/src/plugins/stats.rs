@@ -24,6 +24,7 @@ use crate::{
         gui::{
             menu::MenuItemMessage,
             message::{MessageDirection, UiMessage},
+            scroll_viewer::ScrollViewerBuilder,
             stack_panel::StackPanelBuilder,
             text::{TextBuilder, TextMessage},
             widget::{WidgetBuilder, WidgetMessage},
@@ -36,6 +37,7 @@ use crate::{
     Editor,
 };
 use fyrox::gui::window::WindowTitle;
+use std::fmt::Write;
 
 /// Editor UI statistics, useful to track number of active widgets and memory consumption.
 
@@ -127,3 +129,117 @@ impl EditorPlugin for UiStatisticsPlugin {
         ));
     }
 }
+
+/// Memory statistics of the resources, useful to track memory consumption and tune memory budgets
+/// of resource types.
+#[derive(Default)]
+pub struct ResourceStatisticsPlugin {
+    window: Handle<UiNode>,
+    text: Handle<UiNode>,
+    open_resource_stats: Handle<UiNode>,
+}
+
+fn megabytes(bytes: usize) -> f32 {
+    bytes as f32 / (1024.0 * 1024.0)
+}
+
+impl EditorPlugin for ResourceStatisticsPlugin {
+    fn on_start(&mut self, editor: &mut Editor) {
+        let ui = editor.engine.user_interfaces.first_mut();
+        let ctx = &mut ui.build_ctx();
+        self.open_resource_stats = create_menu_item("Resource Statistics", vec![], ctx);
+        ui.send_message(MenuItemMessage::add_item(
+            editor.menu.utils_menu.menu,
+            MessageDirection::ToWidget,
+            self.open_resource_stats,
+        ));
+    }
+
+    fn on_ui_message(&mut self, message: &mut UiMessage, editor: &mut Editor) {
+        let ui = editor.engine.user_interfaces.first_mut();
+
+        if let Some(MenuItemMessage::Click) = message.data() {
+            if message.destination() == self.open_resource_stats && self.window.is_none() {
+                let ctx = &mut ui.build_ctx();
+                self.text =
+                    TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(1.0)))
+                        .build(ctx);
+                self.window =
+                    WindowBuilder::new(WidgetBuilder::new().with_width(400.0).with_height(300.0))
+                        .with_title(WindowTitle::text("Resource Statistics"))
+                        .with_content(
+                            ScrollViewerBuilder::new(WidgetBuilder::new())
+                                .with_content(self.text)
+                                .build(ctx),
+                        )
+                        .open(false)
+                        .build(ctx);
+
+                ui.send_message(WindowMessage::open_and_align(
+                    self.window,
+                    MessageDirection::ToWidget,
+                    editor.scene_viewer.frame(),
+                    HorizontalAlignment::Right,
+                    VerticalAlignment::Bottom,
+                    Thickness::uniform(1.0),
+                    false,
+                    true,
+                ));
+            }
+        }
+
+        if let Some(WindowMessage::Close) = message.data() {
+            if message.destination() == self.window {
+                ui.send_message(WidgetMessage::remove(
+                    self.window,
+                    MessageDirection::ToWidget,
+                ));
+                self.window = Handle::NONE;
+            }
+        }
+    }
+
+    fn on_update(&mut self, editor: &mut Editor) {
+        if self.window.is_none() {
+            return;
+        }
+
+        let mut text = String::new();
+        for statistics in editor.engine.resource_manager.memory_statistics() {
+            let name = if statistics.type_name.is_empty() {
+                statistics.type_uuid.to_string()
+            } else {
+                statistics.type_name
+            };
+            let budget = match statistics.budget {
+                Some(budget) => format!("{:.3} Mb.", megabytes(budget)),
+                None => "None".to_string(),
+            };
+            write!(
+                text,
+                "{}\n\tCount: {} ({} unused)\n\tCPU Memory: {:.3} Mb.\n\
+                \tGPU Memory: {:.3} Mb.\n\tUnused Memory: {:.3} Mb.\n\
+                \tBudget: {}\n\tEvicted: {}\n",
+                name,
+                statistics.count,
+                statistics.unused_count,
+                megabytes(statistics.memory_usage.cpu),
+                megabytes(statistics.memory_usage.gpu),
+                megabytes(statistics.unused_memory_usage.total()),
+                budget,
+                statistics.evicted_count,
+            )
+            .unwrap();
+        }
+
+        editor
+            .engine
+            .user_interfaces
+            .first()
+            .send_message(TextMessage::text(
+                self.text,
+                MessageDirection::ToWidget,
+                text,
+            ));
+    }
+}
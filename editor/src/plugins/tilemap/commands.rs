@@ -37,7 +37,7 @@ use fyrox::{
                 TileSetResource,
             },
             OrthoTransform, OrthoTransformation, TileCollider, TileDefinitionHandle, TileMap,
-            TileSetUpdate, TilesUpdate,
+            TileSetTerrain, TileSetUpdate, TilesUpdate,
         },
     },
 };
@@ -111,6 +111,34 @@ impl CommandTrait for SetPropertyLayerNameCommand {
     }
 }
 
+#[derive(Debug)]
+pub struct SetTileSetTerrainCommand {
+    pub tile_set: TileSetResource,
+    pub terrain: TileSetTerrain,
+}
+
+impl SetTileSetTerrainCommand {
+    fn swap(&mut self) {
+        let mut tile_set = self.tile_set.data_ref();
+        std::mem::swap(&mut tile_set.terrain, &mut self.terrain);
+        tile_set.change_count.set();
+    }
+}
+
+impl CommandTrait for SetTileSetTerrainCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Set Tile Set Terrain".into()
+    }
+
+    fn execute(&mut self, _context: &mut dyn CommandContext) {
+        self.swap();
+    }
+
+    fn revert(&mut self, _context: &mut dyn CommandContext) {
+        self.swap();
+    }
+}
+
 #[derive(Debug)]
 pub struct SetColliderLayerColorCommand {
     pub tile_set: TileSetResource,
@@ -842,6 +870,7 @@ impl MoveMapTileCommand {
         let tile_map = context.scene.graph[self.tile_map]
             .cast_mut::<TileMap>()
             .expect("Cast to TileMap failed!");
+        let layout = tile_map.grid_layout();
         let Some(mut tiles) = tile_map.tiles().map(|r| r.data_ref()) else {
             return;
         };
@@ -850,11 +879,11 @@ impl MoveMapTileCommand {
         };
         for (i, p) in self.tiles.iter().enumerate() {
             let data = &mut self.data[i];
-            *data = tiles.replace(*p + self.start_offset, *data);
+            *data = tiles.replace(layout.translate(*p, self.start_offset), *data);
         }
         for (i, p) in self.tiles.iter().enumerate() {
             let data = &mut self.data[i];
-            *data = tiles.replace(*p + self.end_offset, *data);
+            *data = tiles.replace(layout.translate(*p, self.end_offset), *data);
         }
         std::mem::swap(&mut self.start_offset, &mut self.end_offset);
     }
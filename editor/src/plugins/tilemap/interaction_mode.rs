@@ -24,9 +24,9 @@ use commands::{MoveMapTileCommand, SetMapTilesCommand};
 use fyrox::{
     fxhash::FxHashMap,
     scene::tilemap::{
-        tileset::TileSetRef, OptionTileRect, TileCursorEffect, TileEraseEffect, TileMapData,
-        TileOverlayEffect, TileSelectionEffect, TileSource, TileUpdateEffect, TilesUpdate,
-        TransTilesUpdate,
+        tileset::TileSetRef, OptionTileRect, TerrainAutotiler, TileCursorEffect, TileEraseEffect,
+        TileMapData, TileOverlayEffect, TileRect, TileSelectionEffect, TileSource,
+        TileUpdateEffect, TilesUpdate, TransTilesUpdate,
     },
 };
 
@@ -257,12 +257,15 @@ fn update_select(
     let Some(tiles) = tiles.as_loaded_ref() else {
         return;
     };
-    state.update_stamp(tile_map.tile_set().cloned(), |p| tiles.get(p));
+    state.update_stamp_in_layout(tile_map.tile_set().cloned(), tile_map.grid_layout(), |p| {
+        tiles.get(p)
+    });
 }
 
 fn draw(
     update: &mut TransTilesUpdate,
     tiles: &TileMapData,
+    layout: TileGridLayout,
     tool: DrawingMode,
     state: &TileDrawStateGuard<'_>,
     start: Vector2<i32>,
@@ -272,12 +275,12 @@ fn draw(
     match tool {
         DrawingMode::Pick => (),
         DrawingMode::Editor => (),
-        DrawingMode::Draw => update.draw_tiles(end, stamp),
+        DrawingMode::Draw => update.draw_tiles_in_layout(layout, end, stamp),
         DrawingMode::Erase => {
             if stamp.is_empty() {
                 update.erase(end);
             } else {
-                update.erase_stamp(end, stamp);
+                update.erase_stamp_in_layout(layout, end, stamp);
             }
         }
         DrawingMode::RectFill => {
@@ -311,6 +314,23 @@ fn draw(
                 update.flood_fill(tiles, end, &stamp.repeat_anywhere());
             }
         }
+        DrawingMode::Terrain => {
+            let Some(tile_set) = state.tile_set.as_ref() else {
+                return;
+            };
+            let mut tile_set = TileSetRef::new(tile_set);
+            let tile_set = tile_set.as_loaded();
+            let Some(autotiler) = tile_set.as_ref().and_then(TerrainAutotiler::new) else {
+                return;
+            };
+            if let Some((_, handle)) = stamp.iter().next() {
+                let terrain = autotiler.terrain_of(*handle);
+                let positions = stamp.keys().map(|p| layout.translate(end, p));
+                update.draw_terrain(tiles, &autotiler, positions, terrain);
+            } else {
+                update.draw_terrain(tiles, &autotiler, [end], 0);
+            }
+        }
     }
 }
 
@@ -367,7 +387,8 @@ impl InteractionMode for TileMapInteractionMode {
                                 continue;
                             };
                             let _ = erased_area.insert(*pos);
-                            let _ = overlay.tiles.insert(*pos - grid_coord, handle);
+                            let offset = tile_map.grid_layout().offset(grid_coord, *pos);
+                            let _ = overlay.tiles.insert(offset, handle);
                         }
                     } else {
                         drop(tiles_guard);
@@ -392,6 +413,7 @@ impl InteractionMode for TileMapInteractionMode {
                     draw(
                         &mut self.update_effect.lock().update,
                         tiles,
+                        tile_map.grid_layout(),
                         mode,
                         &state,
                         grid_coord,
@@ -429,16 +451,16 @@ impl InteractionMode for TileMapInteractionMode {
             MouseMode::Dragging => {
                 let overlay = &mut self.overlay_effect.lock().tiles;
                 if let (Some(start), Some(end)) = (start, end) {
-                    let offset = end - start;
+                    let layout = tile_map.grid_layout();
+                    let offset = layout.offset(start, end);
                     if offset != Vector2::new(0, 0) {
                         let tiles = overlay
                             .keys()
-                            .copied()
-                            .map(|p| p + start)
+                            .map(|p| layout.translate(start, *p))
                             .collect::<Vec<_>>();
                         let selected = &mut self.select_effect.lock().positions;
                         selected.clear();
-                        selected.extend(tiles.iter().map(|p| p + offset));
+                        selected.extend(tiles.iter().map(|p| layout.translate(*p, offset)));
                         self.sender.do_command(MoveMapTileCommand::new(
                             tile_map_handle,
                             tiles,
@@ -547,6 +569,7 @@ impl InteractionMode for TileMapInteractionMode {
                     draw(
                         &mut self.update_effect.lock().update,
                         tiles,
+                        tile_map.grid_layout(),
                         self.current_tool,
                         &state,
                         start,
@@ -588,28 +611,64 @@ impl InteractionMode for TileMapInteractionMode {
             return;
         };
 
-        let transform = tile_map.global_transform();
+        let transform = tile_map.tile_map_transform();
+        let layout = tile_map.grid_layout();
         let ctx = &mut scene.drawing_context;
 
-        let mut draw_line = |begin: Vector2<i32>, end: Vector2<i32>, color: Color| {
+        let mut draw_line = |begin: Vector2<f32>, end: Vector2<f32>, color: Color| {
             ctx.add_line(Line {
                 begin: transform
-                    .transform_point(&Vector3::new(begin.x as f32, begin.y as f32, -0.01).into())
+                    .transform_point(&Vector3::new(begin.x, begin.y, -0.01).into())
                     .coords,
                 end: transform
-                    .transform_point(&Vector3::new(end.x as f32, end.y as f32, -0.01).into())
+                    .transform_point(&Vector3::new(end.x, end.y, -0.01).into())
                     .coords,
                 color,
             });
         };
 
         // TODO: Is there a better way to make a grid?
-        let size = 1000i32;
-        for y in -size..size {
-            draw_line(Vector2::new(-size, y), Vector2::new(size, y), Color::WHITE);
-        }
-        for x in -size..size {
-            draw_line(Vector2::new(x, -size), Vector2::new(x, size), Color::WHITE);
+        match layout {
+            TileGridLayout::Orthogonal => {
+                let size = 1000.0;
+                for y in -1000..1000 {
+                    let y = y as f32;
+                    draw_line(Vector2::new(-size, y), Vector2::new(size, y), Color::WHITE);
+                }
+                for x in -1000..1000 {
+                    let x = x as f32;
+                    draw_line(Vector2::new(x, -size), Vector2::new(x, size), Color::WHITE);
+                }
+            }
+            TileGridLayout::Isometric | TileGridLayout::StaggeredIsometric => {
+                // Edges of diamond cells lie on the lines x + y = k + 0.5 and y - x = k + 0.5.
+                let size = 1000.0;
+                for k in -2000..2000 {
+                    let c = k as f32 + 0.5;
+                    draw_line(
+                        Vector2::new(-size, c + size),
+                        Vector2::new(size, c - size),
+                        Color::WHITE,
+                    );
+                    draw_line(
+                        Vector2::new(-size, c - size),
+                        Vector2::new(size, c + size),
+                        Color::WHITE,
+                    );
+                }
+            }
+            TileGridLayout::HexagonalPointyTop | TileGridLayout::HexagonalFlatTop => {
+                // Hexagonal edges do not form continuous lines, so only the cells near the cursor
+                // are outlined.
+                let center = self.cursor_effect.lock().position.unwrap_or_default();
+                for cell in TileRect::new(center.x - 50, center.y - 50, 101, 101).iter() {
+                    let polygon = layout.cell_polygon(cell);
+                    // Every edge is shared by two cells, so half of the edges is enough.
+                    for i in 0..polygon.len() / 2 {
+                        draw_line(polygon[i], polygon[i + 1], Color::WHITE);
+                    }
+                }
+            }
         }
     }
 
@@ -90,8 +90,8 @@ use crate::fyrox::{
         node::Node,
         tilemap::{
             tileset::{TileSet, TileSetResource},
-            RandomTileSource, Stamp, TileBook, TileCollider, TileDefinitionHandle, TileMap,
-            TilePaletteStage,
+            RandomTileSource, Stamp, TileBook, TileCollider, TileDefinitionHandle, TileGridLayout,
+            TileMap, TilePaletteStage,
         },
         Scene,
     },
@@ -124,6 +124,8 @@ lazy_static! {
     static ref NINE_SLICE_IMAGE: Option<TextureResource> =
         load_image!("../../../resources/nine_slice.png");
     static ref LINE_IMAGE: Option<TextureResource> = load_image!("../../../resources/line.png");
+    static ref TERRAIN_IMAGE: Option<TextureResource> =
+        load_image!("../../../resources/terrain.png");
     static ref TURN_LEFT_IMAGE: Option<TextureResource> =
         load_image!("../../../resources/turn_left.png");
     static ref TURN_RIGHT_IMAGE: Option<TextureResource> =
@@ -200,6 +202,13 @@ pub enum DrawingMode {
     NineSlice,
     /// Drag the mouse to draw a line with the currently selected tiles.
     Line,
+    /// Paint the terrain of the currently selected tile in the shape of the selected tiles, automatically
+    /// choosing matching tiles for the painted cells and their neighbours. Erases the terrain if no tiles
+    /// are selected. This requires the tile set to have a terrain property, see [`TileSetTerrain`].
+    /// For tile set and brush editing, this operation behaves exactly like [`DrawingMode::Draw`].
+    ///
+    /// [`TileSetTerrain`]: fyrox::scene::tilemap::TileSetTerrain
+    Terrain,
     /// Use the currently active tile set editor field to modify the data of tiles in a tile set.
     /// This does nothing to tile maps or brushes.
     Editor,
@@ -465,9 +474,24 @@ impl TileDrawState {
     pub fn update_stamp<F>(&mut self, tile_set: Option<TileSetResource>, tile_handle: F)
     where
         F: Fn(Vector2<i32>) -> Option<TileDefinitionHandle>,
+    {
+        self.update_stamp_in_layout(tile_set, TileGridLayout::Orthogonal, tile_handle);
+    }
+    /// Update the stamp stored within this state to reflect the current selection
+    /// and tile set, where the selection positions are arranged using the given grid layout.
+    /// See [`Stamp::build_in_layout`].
+    #[inline]
+    pub fn update_stamp_in_layout<F>(
+        &mut self,
+        tile_set: Option<TileSetResource>,
+        layout: TileGridLayout,
+        tile_handle: F,
+    ) where
+        F: Fn(Vector2<i32>) -> Option<TileDefinitionHandle>,
     {
         self.tile_set = tile_set;
-        self.stamp.build(
+        self.stamp.build_in_layout(
+            layout,
             self.selection
                 .positions
                 .iter()
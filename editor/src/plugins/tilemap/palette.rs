@@ -346,6 +346,7 @@ impl PaletteWidget {
         let icon = match drawing_mode {
             DrawingMode::Pick => Some(CursorIcon::Pointer),
             DrawingMode::Draw => Some(CursorIcon::Crosshair),
+            DrawingMode::Terrain => Some(CursorIcon::Crosshair),
             DrawingMode::Erase => Some(CursorIcon::Crosshair),
             DrawingMode::FloodFill => Some(CursorIcon::Crosshair),
             DrawingMode::RectFill => Some(CursorIcon::Crosshair),
@@ -893,7 +894,9 @@ impl PaletteWidget {
         let stamp = &state.stamp;
         match mode {
             DrawingMode::Pick => (),
-            DrawingMode::Draw | DrawingMode::FloodFill => self.update.draw_tiles(end, stamp),
+            DrawingMode::Draw | DrawingMode::FloodFill | DrawingMode::Terrain => {
+                self.update.draw_tiles(end, stamp)
+            }
             DrawingMode::Erase => {
                 if stamp.is_empty() {
                     self.update.erase(end);
@@ -957,6 +960,7 @@ impl PaletteWidget {
         match mode {
             DrawingMode::Pick => (),
             DrawingMode::Draw => self.send_update(),
+            DrawingMode::Terrain => self.send_update(),
             DrawingMode::Erase => self.send_update(),
             DrawingMode::Line => self.send_update(),
             DrawingMode::FloodFill => self.send_update(),
@@ -1249,7 +1253,9 @@ impl PaletteWidget {
             DrawingMode::Pick
         };
         match drawing_mode {
-            DrawingMode::Draw => self.is_mouse_directly_over && self.mode == MouseMode::None,
+            DrawingMode::Draw | DrawingMode::Terrain => {
+                self.is_mouse_directly_over && self.mode == MouseMode::None
+            }
             DrawingMode::Erase => self.is_mouse_directly_over,
             DrawingMode::FloodFill => self.is_mouse_directly_over,
             DrawingMode::Pick => matches!(self.mode, MouseMode::Dragging { .. }),
@@ -149,6 +149,8 @@ pub struct TileMapPanel {
     nine_slice_button: Handle<UiNode>,
     /// Tool selection button for the line tool.
     line_button: Handle<UiNode>,
+    /// Tool selection button for the terrain tool.
+    terrain_button: Handle<UiNode>,
     /// Button that toggles the tools into random mode.
     random_button: Handle<UiNode>,
     /// Button to rotate the selected tiles counter-clockwise by 90 degrees.
@@ -282,13 +284,21 @@ impl TileMapPanel {
             "Draw a line using tiles from the given brush.",
             Some(6),
         );
+        let terrain_button = make_drawing_mode_button(
+            ctx,
+            width,
+            height,
+            TERRAIN_IMAGE.clone(),
+            "Paint the terrain of the selected tile, matching tiles are chosen automatically.",
+            Some(7),
+        );
         let left_button = make_drawing_mode_button(
             ctx,
             width,
             height,
             TURN_LEFT_IMAGE.clone(),
             "Rotate left 90 degrees.",
-            Some(7),
+            Some(8),
         );
         let right_button = make_drawing_mode_button(
             ctx,
@@ -296,7 +306,7 @@ impl TileMapPanel {
             height,
             TURN_RIGHT_IMAGE.clone(),
             "Rotate right 90 degrees.",
-            Some(8),
+            Some(9),
         );
         let flip_x_button = make_drawing_mode_button(
             ctx,
@@ -304,7 +314,7 @@ impl TileMapPanel {
             height,
             FLIP_X_IMAGE.clone(),
             "Flip along x axis.",
-            Some(9),
+            Some(10),
         );
         let flip_y_button = make_drawing_mode_button(
             ctx,
@@ -312,7 +322,7 @@ impl TileMapPanel {
             height,
             FLIP_Y_IMAGE.clone(),
             "Flip along y axis.",
-            Some(10),
+            Some(11),
         );
         let random_button = make_drawing_mode_button(
             ctx,
@@ -320,7 +330,7 @@ impl TileMapPanel {
             height,
             RANDOM_IMAGE.clone(),
             "Toggle random fill mode.",
-            Some(11),
+            Some(12),
         );
 
         let drawing_modes_panel = WrapPanelBuilder::new(
@@ -331,7 +341,8 @@ impl TileMapPanel {
                 .with_child(pick_button)
                 .with_child(rect_fill_button)
                 .with_child(nine_slice_button)
-                .with_child(line_button),
+                .with_child(line_button)
+                .with_child(terrain_button),
         )
         .with_orientation(Orientation::Horizontal)
         .build(ctx);
@@ -403,6 +414,7 @@ impl TileMapPanel {
             rect_fill_button,
             nine_slice_button,
             line_button,
+            terrain_button,
             left_button,
             right_button,
             flip_x_button,
@@ -602,6 +614,8 @@ impl TileMapPanel {
             self.state.lock_mut("tool button").drawing_mode = DrawingMode::NineSlice;
         } else if button == self.line_button {
             self.state.lock_mut("tool button").drawing_mode = DrawingMode::Line;
+        } else if button == self.terrain_button {
+            self.state.lock_mut("tool button").drawing_mode = DrawingMode::Terrain;
         } else if button == self.random_button {
             let mut state = self.state.lock_mut("random button");
             state.random_mode = !state.random_mode;
@@ -721,6 +735,7 @@ impl TileMapPanel {
             self.rect_fill_button,
             self.nine_slice_button,
             self.line_button,
+            self.terrain_button,
         ];
         let state = self.state.lock();
         highlight_tool_button(self.random_button, state.random_mode, ui);
@@ -746,6 +761,9 @@ impl TileMapPanel {
             DrawingMode::Line { .. } => {
                 highlight_all_except(self.line_button, &buttons, true, ui);
             }
+            DrawingMode::Terrain => {
+                highlight_all_except(self.terrain_button, &buttons, true, ui);
+            }
             _ => {
                 highlight_all(&buttons, false, ui);
             }
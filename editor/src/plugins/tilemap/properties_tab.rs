@@ -26,6 +26,7 @@ use fyrox::{
     fxhash::FxHashMap,
     gui::{
         button::ButtonMessage,
+        check_box::{CheckBoxBuilder, CheckBoxMessage},
         color::{ColorFieldBuilder, ColorFieldMessage},
         grid::*,
         list_view::{ListView, ListViewBuilder, ListViewMessage},
@@ -62,6 +63,8 @@ pub struct PropertiesTab {
     add_string_button: Handle<UiNode>,
     add_nine_button: Handle<UiNode>,
     name_field: Handle<UiNode>,
+    terrain_check: Handle<UiNode>,
+    weight_check: Handle<UiNode>,
     name_list: Handle<UiNode>,
     name_up: Handle<UiNode>,
     name_down: Handle<UiNode>,
@@ -113,6 +116,28 @@ fn make_button(
     .build(ctx)
 }
 
+fn make_check_box(
+    title: &str,
+    tooltip: &str,
+    ctx: &mut BuildContext,
+    column: usize,
+) -> Handle<UiNode> {
+    CheckBoxBuilder::new(
+        WidgetBuilder::new()
+            .on_column(column)
+            .with_margin(Thickness::uniform(2.0))
+            .with_tooltip(make_simple_tooltip(ctx, tooltip)),
+    )
+    .with_content(
+        TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::left(4.0)))
+            .with_vertical_text_alignment(VerticalAlignment::Center)
+            .with_text(title)
+            .build(ctx),
+    )
+    .checked(Some(false))
+    .build(ctx)
+}
+
 fn make_type_widget(ctx: &mut BuildContext, prop_type: TileSetPropertyType) -> Handle<UiNode> {
     let type_name = match prop_type {
         TileSetPropertyType::I32 => "INTEGER",
@@ -344,7 +369,30 @@ impl PropertiesTab {
         .add_column(Column::strict(100.0))
         .add_row(Row::auto())
         .build(ctx);
-        let name_list = ListViewBuilder::new(WidgetBuilder::new().on_row(1))
+        let terrain_check = make_check_box(
+            "Terrain",
+            "Use the nine-slice values of this property as terrain peering bits for autotiling.",
+            ctx,
+            0,
+        );
+        let weight_check = make_check_box(
+            "Weight",
+            "Use the value of this property as the weight of a tile when choosing between terrain variants.",
+            ctx,
+            1,
+        );
+        let terrain_row = GridBuilder::new(
+            WidgetBuilder::new()
+                .on_row(1)
+                .with_margin(Thickness::uniform(2.0))
+                .with_child(terrain_check)
+                .with_child(weight_check),
+        )
+        .add_row(Row::auto())
+        .add_column(Column::stretch())
+        .add_column(Column::stretch())
+        .build(ctx);
+        let name_list = ListViewBuilder::new(WidgetBuilder::new().on_row(2))
             .with_scroll_viewer(ScrollViewerBuilder::new(WidgetBuilder::new()).build(ctx))
             .build(ctx);
         let name_up = make_arrow_button(ctx, ArrowDirection::Top, 0, 0);
@@ -365,7 +413,7 @@ impl PropertiesTab {
         );
         let name_up_down = GridBuilder::new(
             WidgetBuilder::new()
-                .on_row(2)
+                .on_row(3)
                 .with_child(name_up)
                 .with_child(name_down)
                 .with_child(name_add),
@@ -430,7 +478,7 @@ impl PropertiesTab {
         let name_edit_panel = BorderBuilder::new(
             WidgetBuilder::new()
                 .with_margin(Thickness::uniform(2.0))
-                .on_row(3)
+                .on_row(4)
                 .with_child(
                     StackPanelBuilder::new(
                         WidgetBuilder::new()
@@ -447,11 +495,13 @@ impl PropertiesTab {
                 .with_margin(Thickness::uniform(2.0))
                 .on_column(1)
                 .with_child(header)
+                .with_child(terrain_row)
                 .with_child(name_list)
                 .with_child(name_up_down)
                 .with_child(name_edit_panel),
         )
         .add_row(Row::auto())
+        .add_row(Row::auto())
         .add_row(Row::stretch())
         .add_row(Row::auto())
         .add_row(Row::auto())
@@ -477,6 +527,8 @@ impl PropertiesTab {
             add_nine_button,
             remove_button,
             name_field,
+            terrain_check,
+            weight_check,
             name_list,
             name_up,
             name_down,
@@ -529,8 +581,46 @@ impl PropertiesTab {
             TextMessage::text(self.name_field, MessageDirection::ToWidget, name),
         );
         send_enabled(ui, self.name_list, sel_index.is_some());
+        self.sync_terrain(tile_set, ui);
         self.sync_name_edit(sel_index.is_some(), tile_set, ui);
     }
+    fn sync_terrain(&self, tile_set: &OptionTileSet, ui: &mut UserInterface) {
+        let prop = self.property(tile_set, ui);
+        let terrain = tile_set.as_ref().map(|t| &t.terrain);
+        let uuid = prop.map(|p| p.uuid);
+        let prop_type = prop.map(|p| p.prop_type);
+        let is_terrain = uuid.is_some() && terrain.and_then(|t| t.peering_property) == uuid;
+        let is_weight = uuid.is_some() && terrain.and_then(|t| t.weight_property) == uuid;
+        send_sync_message(
+            ui,
+            CheckBoxMessage::checked(
+                self.terrain_check,
+                MessageDirection::ToWidget,
+                Some(is_terrain),
+            ),
+        );
+        send_sync_message(
+            ui,
+            CheckBoxMessage::checked(
+                self.weight_check,
+                MessageDirection::ToWidget,
+                Some(is_weight),
+            ),
+        );
+        send_enabled(
+            ui,
+            self.terrain_check,
+            prop_type == Some(TileSetPropertyType::NineSlice),
+        );
+        send_enabled(
+            ui,
+            self.weight_check,
+            matches!(
+                prop_type,
+                Some(TileSetPropertyType::I32 | TileSetPropertyType::F32)
+            ),
+        );
+    }
     fn sync_name_edit(&mut self, enabled: bool, tile_set: &OptionTileSet, ui: &mut UserInterface) {
         let prop = self.property(tile_set, ui);
         let name_index = self.name_selection_index(ui);
@@ -633,6 +723,12 @@ impl PropertiesTab {
             if message.destination() == self.f32_field {
                 self.update_value(tile_set, NamableValue::F32(value), ui, sender);
             }
+        } else if let Some(&CheckBoxMessage::Check(Some(value))) = message.data() {
+            if message.destination() == self.terrain_check {
+                self.update_terrain(tile_set, false, value, ui, sender);
+            } else if message.destination() == self.weight_check {
+                self.update_terrain(tile_set, true, value, ui, sender);
+            }
         } else if let Some(&ColorFieldMessage::Color(value)) = message.data() {
             if message.destination() == self.color_field {
                 self.update_color(tile_set, value, ui, sender);
@@ -708,6 +804,43 @@ impl PropertiesTab {
             name: name.into(),
         });
     }
+    fn update_terrain(
+        &self,
+        resource: TileSetResource,
+        weight: bool,
+        checked: bool,
+        ui: &UserInterface,
+        sender: &MessageSender,
+    ) {
+        let tile_set = resource.data_ref();
+        let Some(uuid) = self
+            .selection_index(ui)
+            .and_then(|i| tile_set.properties.get(i))
+            .map(|l| l.uuid)
+        else {
+            return;
+        };
+        let mut terrain = tile_set.terrain.clone();
+        let target = if weight {
+            &mut terrain.weight_property
+        } else {
+            &mut terrain.peering_property
+        };
+        if checked {
+            *target = Some(uuid);
+        } else if *target == Some(uuid) {
+            *target = None;
+        } else {
+            return;
+        }
+        if terrain == tile_set.terrain {
+            return;
+        }
+        sender.do_command(SetTileSetTerrainCommand {
+            tile_set: resource.clone(),
+            terrain,
+        });
+    }
     fn update_value_name(
         &self,
         resource: TileSetResource,
@@ -0,0 +1,510 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Vehicle editing plugin. It shows the wheels of the selected vehicle in the scene viewer and
+//! allows moving their connection points. `Insert` key adds a new wheel (a mirrored copy of the
+//! selected one), `Delete` key removes the selected wheel.
+
+use crate::{
+    camera::PickingOptions,
+    command::SetPropertyCommand,
+    fyrox::{
+        core::{
+            algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3},
+            color::Color,
+            pool::Handle,
+            some_or_return,
+            type_traits::prelude::*,
+            Uuid,
+        },
+        engine::Engine,
+        graph::{BaseSceneGraph, SceneGraph, SceneGraphNode},
+        gui::{message::KeyCode, BuildContext, UiNode},
+        scene::{
+            debug::Line,
+            node::Node,
+            sprite::Sprite,
+            vehicle::{Vehicle, VehicleWheel},
+            Scene,
+        },
+    },
+    interaction::{
+        calculate_gizmo_distance_scaling, gizmo::move_gizmo::MoveGizmo,
+        make_interaction_mode_button, plane::PlaneKind, InteractionMode,
+    },
+    message::MessageSender,
+    plugin::EditorPlugin,
+    plugins::collider::make_handle,
+    scene::{commands::GameSceneContext, controller::SceneController, GameScene, Selection},
+    settings::Settings,
+    Editor, Message,
+};
+
+struct DragContext {
+    plane_kind: PlaneKind,
+    initial_wheels: Vec<VehicleWheel>,
+}
+
+#[derive(TypeUuidProvider)]
+#[type_uuid(id = "0f7d4c52-93a1-4b8e-a4f6-5d2e8c1b7a39")]
+pub struct VehicleInteractionMode {
+    vehicle: Handle<Node>,
+    handles: Vec<Handle<Node>>,
+    move_gizmo: MoveGizmo,
+    selected_wheel: Option<usize>,
+    drag_context: Option<DragContext>,
+    visible: bool,
+    message_sender: MessageSender,
+}
+
+// Returns the wheels of the vehicle and the global transform of its chassis, wheels are defined
+// in the local coordinates of the chassis.
+fn wheels_and_chassis_transform(
+    scene: &Scene,
+    vehicle: Handle<Node>,
+) -> Option<(&[VehicleWheel], Matrix4<f32>)> {
+    let vehicle = scene.graph.try_get_of_type::<Vehicle>(vehicle)?;
+    let chassis = scene.graph.try_get(vehicle.chassis())?;
+    Some((vehicle.wheels(), chassis.global_transform()))
+}
+
+impl VehicleInteractionMode {
+    fn destroy_handles(&mut self, scene: &mut Scene) {
+        for handle in self.handles.drain(..) {
+            scene.graph.remove_node(handle);
+        }
+    }
+
+    fn destroy(mut self, scene: &mut Scene) {
+        self.destroy_handles(scene);
+        self.move_gizmo.destroy(&mut scene.graph);
+    }
+
+    fn set_visibility(
+        &mut self,
+        controller: &dyn SceneController,
+        engine: &mut Engine,
+        visibility: bool,
+    ) {
+        let Some(game_scene) = controller.downcast_ref::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        self.visible = visibility;
+        for handle in self.handles.iter() {
+            scene.graph[*handle].set_visibility(visibility);
+        }
+        if !visibility {
+            self.move_gizmo.set_visible(&mut scene.graph, false);
+        }
+    }
+
+    fn find_wheel(&self, node: Handle<Node>) -> Option<usize> {
+        self.handles.iter().position(|handle| *handle == node)
+    }
+
+    // Recreates the handles if the amount of wheels has changed, moves them to the connection
+    // points of the wheels and draws the wheels.
+    fn sync_handles(&mut self, game_scene: &GameScene, scene: &mut Scene) {
+        let Some((wheels, transform)) = wheels_and_chassis_transform(scene, self.vehicle) else {
+            return;
+        };
+        let wheels = wheels.to_vec();
+
+        if self.handles.len() != wheels.len() {
+            self.destroy_handles(scene);
+            for _ in 0..wheels.len() {
+                let handle = make_handle(scene, game_scene.editor_objects_root, self.visible);
+                self.handles.push(handle);
+            }
+        }
+
+        if self
+            .selected_wheel
+            .is_some_and(|index| index >= wheels.len())
+        {
+            self.selected_wheel = None;
+        }
+
+        for (index, (handle, wheel)) in self.handles.iter().zip(wheels.iter()).enumerate() {
+            let scale = calculate_gizmo_distance_scaling(
+                &scene.graph,
+                game_scene.camera_controller.camera,
+                *handle,
+            );
+            let node = &mut scene.graph[*handle];
+            node.local_transform_mut()
+                .set_position(
+                    transform
+                        .transform_point(&Point3::from(wheel.connection_point))
+                        .coords,
+                )
+                .set_scale(scale);
+            if let Some(sprite) = node.component_mut::<Sprite>() {
+                sprite.set_size(0.05 * scale.x);
+                if self.selected_wheel == Some(index) {
+                    sprite.set_color(Color::GREEN);
+                } else if sprite.color() != Color::RED {
+                    sprite.set_color(Color::MAROON);
+                }
+            }
+
+            if !self.visible {
+                continue;
+            }
+
+            // Suspension at rest and the wheel itself.
+            let color = if self.selected_wheel == Some(index) {
+                Color::GREEN
+            } else {
+                Color::ORANGE
+            };
+            let (center, rotation) = wheel.local_transform(wheel.rest_length, 0.0, 0.0);
+            scene.drawing_context.add_line(Line {
+                begin: transform
+                    .transform_point(&Point3::from(wheel.connection_point))
+                    .coords,
+                end: transform.transform_point(&Point3::from(center)).coords,
+                color,
+            });
+            let axle = rotation * wheel.axle.try_normalize(f32::EPSILON).unwrap_or_default();
+            let circle_rotation = UnitQuaternion::rotation_between(&Vector3::z(), &axle)
+                .unwrap_or_else(|| UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.0));
+            scene.drawing_context.draw_circle(
+                Default::default(),
+                wheel.radius,
+                24,
+                transform * Matrix4::new_translation(&center) * circle_rotation.to_homogeneous(),
+                color,
+            );
+        }
+    }
+
+    fn set_wheels(&self, wheels: Vec<VehicleWheel>) {
+        let vehicle = self.vehicle;
+        let command = SetPropertyCommand::new("wheels".into(), Box::new(wheels), move |ctx| {
+            ctx.get_mut::<GameSceneContext>()
+                .scene
+                .graph
+                .node_mut(vehicle)
+        });
+        self.message_sender.do_command(command);
+    }
+}
+
+impl InteractionMode for VehicleInteractionMode {
+    fn on_left_mouse_button_down(
+        &mut self,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        mouse_position: Vector2<f32>,
+        _frame_size: Vector2<f32>,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        let Some(result) = game_scene.camera_controller.pick(
+            &scene.graph,
+            PickingOptions {
+                cursor_pos: mouse_position,
+                editor_only: true,
+                filter: Some(&mut |handle, _| handle != self.move_gizmo.origin),
+                ..Default::default()
+            },
+        ) else {
+            return;
+        };
+
+        if let Some(index) = self.find_wheel(result.node) {
+            self.selected_wheel = Some(index);
+        } else if let Some(plane_kind) = self.move_gizmo.handle_pick(result.node, &mut scene.graph)
+        {
+            if self.selected_wheel.is_some() {
+                if let Some(vehicle) = scene.graph.try_get_of_type::<Vehicle>(self.vehicle) {
+                    self.drag_context = Some(DragContext {
+                        plane_kind,
+                        initial_wheels: vehicle.wheels().to_vec(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn on_left_mouse_button_up(
+        &mut self,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        _mouse_pos: Vector2<f32>,
+        _frame_size: Vector2<f32>,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        if let Some(drag_context) = self.drag_context.take() {
+            if let Some(vehicle) = scene.graph.try_get_mut_of_type::<Vehicle>(self.vehicle) {
+                // Revert the changes made during dragging and commit them using a command.
+                let wheels = vehicle.set_wheels(drag_context.initial_wheels);
+                self.set_wheels(wheels);
+            }
+        }
+    }
+
+    fn on_mouse_move(
+        &mut self,
+        mouse_offset: Vector2<f32>,
+        mouse_position: Vector2<f32>,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        frame_size: Vector2<f32>,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        for handle in self.handles.iter() {
+            scene.graph[*handle]
+                .as_sprite_mut()
+                .set_color(Color::MAROON);
+        }
+        self.move_gizmo.reset_state(&mut scene.graph);
+
+        if let Some(result) = game_scene.camera_controller.pick(
+            &scene.graph,
+            PickingOptions {
+                cursor_pos: mouse_position,
+                editor_only: true,
+                filter: Some(&mut |handle, _| handle != self.move_gizmo.origin),
+                ..Default::default()
+            },
+        ) {
+            if self.find_wheel(result.node).is_some() {
+                scene.graph[result.node]
+                    .as_sprite_mut()
+                    .set_color(Color::RED);
+            }
+
+            self.move_gizmo.handle_pick(result.node, &mut scene.graph);
+        }
+
+        let Some(drag_context) = self.drag_context.as_ref() else {
+            return;
+        };
+        let Some(selected_wheel) = self.selected_wheel else {
+            return;
+        };
+
+        let global_offset = self.move_gizmo.calculate_offset(
+            &scene.graph,
+            game_scene.camera_controller.camera,
+            mouse_offset,
+            mouse_position,
+            frame_size,
+            drag_context.plane_kind,
+        );
+
+        let Some((_, chassis_transform)) = wheels_and_chassis_transform(scene, self.vehicle) else {
+            return;
+        };
+        let local_offset = chassis_transform
+            .try_inverse()
+            .unwrap_or_default()
+            .transform_vector(&global_offset);
+        if let Some(wheel) = scene
+            .graph
+            .try_get_mut_of_type::<Vehicle>(self.vehicle)
+            .and_then(|vehicle| vehicle.wheels_mut().get_mut(selected_wheel))
+        {
+            wheel.connection_point += local_offset;
+        }
+    }
+
+    fn update(
+        &mut self,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+
+        let scene = &mut engine.scenes[game_scene.scene];
+
+        self.sync_handles(game_scene, scene);
+
+        let selected_position = self.selected_wheel.and_then(|selected_wheel| {
+            let (wheels, transform) = wheels_and_chassis_transform(scene, self.vehicle)?;
+            let wheel = wheels.get(selected_wheel)?;
+            Some(
+                transform
+                    .transform_point(&Point3::from(wheel.connection_point))
+                    .coords,
+            )
+        });
+
+        self.move_gizmo.set_visible(
+            &mut scene.graph,
+            self.visible && selected_position.is_some(),
+        );
+
+        if let Some(position) = selected_position {
+            let scale = calculate_gizmo_distance_scaling(
+                &scene.graph,
+                game_scene.camera_controller.camera,
+                self.move_gizmo.origin,
+            );
+            self.move_gizmo
+                .transform(&mut scene.graph)
+                .set_position(position)
+                .set_scale(scale);
+        }
+    }
+
+    fn activate(&mut self, controller: &dyn SceneController, engine: &mut Engine) {
+        self.set_visibility(controller, engine, true)
+    }
+
+    fn deactivate(&mut self, controller: &dyn SceneController, engine: &mut Engine) {
+        self.set_visibility(controller, engine, false)
+    }
+
+    fn make_button(&mut self, ctx: &mut BuildContext, selected: bool) -> Handle<UiNode> {
+        make_interaction_mode_button(
+            ctx,
+            include_bytes!("../../resources/rigid_body.png"),
+            "Edit Vehicle Wheels",
+            selected,
+        )
+    }
+
+    fn on_key_down(
+        &mut self,
+        key: KeyCode,
+        _editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+    ) -> bool {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return false;
+        };
+
+        let scene = &engine.scenes[game_scene.scene];
+
+        let Some(vehicle) = scene.graph.try_get_of_type::<Vehicle>(self.vehicle) else {
+            return false;
+        };
+
+        let mut wheels = vehicle.wheels().to_vec();
+
+        match key {
+            KeyCode::Insert => {
+                // Mirror the selected wheel to the other side of the chassis, it is the most
+                // common case for vehicles.
+                let wheel = match self.selected_wheel.and_then(|index| wheels.get(index)) {
+                    Some(selected) => {
+                        let mut wheel = selected.clone();
+                        wheel.connection_point.x = -wheel.connection_point.x;
+                        wheel.visual = Handle::NONE;
+                        wheel
+                    }
+                    None => VehicleWheel::default(),
+                };
+                wheels.push(wheel);
+                self.selected_wheel = Some(wheels.len() - 1);
+                self.set_wheels(wheels);
+                true
+            }
+            KeyCode::Delete => {
+                let Some(index) = self.selected_wheel else {
+                    return false;
+                };
+                if index < wheels.len() {
+                    wheels.remove(index);
+                    self.selected_wheel = None;
+                    self.set_wheels(wheels);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn uuid(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+#[derive(Default)]
+pub struct VehiclePlugin;
+
+impl EditorPlugin for VehiclePlugin {
+    fn on_message(&mut self, message: &Message, editor: &mut Editor) {
+        let entry = some_or_return!(editor.scenes.current_scene_entry_mut());
+        let selection = some_or_return!(entry.selection.as_graph());
+        let game_scene = some_or_return!(entry.controller.downcast_mut::<GameScene>());
+
+        let scene = &mut editor.engine.scenes[game_scene.scene];
+
+        if let Message::SelectionChanged { .. } = message {
+            if let Some(mode) = entry
+                .interaction_modes
+                .remove_typed::<VehicleInteractionMode>()
+            {
+                mode.destroy(scene);
+            }
+
+            let first_selected_vehicle = selection
+                .nodes()
+                .iter()
+                .find(|h| scene.graph.try_get_of_type::<Vehicle>(**h).is_some());
+
+            if let Some(first_selected_vehicle) = first_selected_vehicle {
+                let move_gizmo = MoveGizmo::new(game_scene, &mut editor.engine);
+
+                entry.interaction_modes.add(VehicleInteractionMode {
+                    vehicle: *first_selected_vehicle,
+                    handles: Default::default(),
+                    move_gizmo,
+                    selected_wheel: None,
+                    drag_context: None,
+                    visible: false,
+                    message_sender: editor.message_sender.clone(),
+                });
+            }
+        }
+    }
+}
@@ -44,7 +44,7 @@ use crate::{
                 physics::{IntegrationParameters, PhysicsWorld},
                 Graph, NodePool,
             },
-            SceneRenderingOptions,
+            FogMode, FogSettings, SceneRenderingOptions,
         },
         utils::lightmap::Lightmap,
     },
@@ -90,6 +90,8 @@ impl SceneSettingsWindow {
         container.register_inheritable_inspectable::<PhysicsWorld>();
         container.register_inheritable_inspectable::<dim2::physics::PhysicsWorld>();
         container.register_inheritable_inspectable::<SceneRenderingOptions>();
+        container.register_inheritable_inspectable::<FogSettings>();
+        container.register_inheritable_enum::<FogMode, _>();
         container.insert(EnumPropertyEditorDefinition::<Color>::new_optional());
 
         Self {
@@ -18,7 +18,7 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::fyrox::core::reflect::prelude::*;
+use crate::fyrox::{core::reflect::prelude::*, utils::navmesh::generator::NavmeshBakeSettings};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, PartialEq, Clone, Debug, Reflect)]
@@ -30,6 +30,16 @@ pub struct NavmeshSettings {
 
     #[reflect(description = "Radius of a nav mesh vertex.")]
     pub vertex_radius: f32,
+
+    #[serde(default)]
+    #[reflect(description = "Parameters of automatic navmesh generation (baking).")]
+    pub bake: NavmeshBakeSettings,
+
+    #[serde(default)]
+    #[reflect(
+        description = "Bake the navmesh from every mesh, terrain and collider in the scene, instead of the selected nodes."
+    )]
+    pub bake_whole_scene: bool,
 }
 
 impl Default for NavmeshSettings {
@@ -37,6 +47,8 @@ impl Default for NavmeshSettings {
         Self {
             draw_all: true,
             vertex_radius: 0.2,
+            bake: Default::default(),
+            bake_whole_scene: false,
         }
     }
 }
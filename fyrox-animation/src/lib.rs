@@ -201,6 +201,13 @@ pub type AnimationTracksDataResource = Resource<AnimationTracksData>;
 /// Sometimes there's a need to disable/enable an animation or check if it is enabled or not, you can do this by using the pair
 /// of respective methods - [`Animation::set_enabled`] and [`Animation::is_enabled`].
 ///
+/// # Additive animations
+///
+/// An animation can be marked as additive using [`Animation::set_additive_settings`]. Additive animation produces a
+/// difference between its current pose and a reference pose (taken from the same animation at a specific time) instead
+/// of an absolute pose. Such pose could then be added on top of some other pose, which is useful for breathing, recoil,
+/// leaning and similar animations, that should be layered over locomotion. See [`AdditiveSettings`] docs for more info.
+///
 /// # Signals
 ///
 /// Signal is a named marker on specific time position on the animation timeline. Signal will emit an event if the animation playback
@@ -275,6 +282,7 @@ pub struct Animation<T: EntityId> {
     enabled: bool,
     signals: Vec<AnimationSignal>,
     root_motion_settings: Option<RootMotionSettings<T>>,
+    additive_settings: Option<AdditiveSettings>,
     max_event_capacity: usize,
 
     #[reflect(hidden)]
@@ -343,6 +351,9 @@ impl<T: EntityId> Visit for Animation<T> {
         let _ = self
             .root_motion_settings
             .visit("RootMotionSettings", &mut region);
+        let _ = self
+            .additive_settings
+            .visit("AdditiveSettings", &mut region);
 
         Ok(())
     }
@@ -381,6 +392,16 @@ pub struct RootMotionSettings<T: EntityId> {
     pub ignore_rotations: bool,
 }
 
+/// Additive animation settings. Additive animation produces a difference (delta) between its current pose and
+/// the reference pose, instead of an absolute pose. Additive poses are meant to be added on top of other poses,
+/// for example using [`machine::node::blend::BlendAdditive`] nodes or additive layers of a state machine
+/// (see [`machine::LayerBlendMode`]).
+#[derive(Default, Debug, Clone, PartialEq, Reflect, Visit)]
+pub struct AdditiveSettings {
+    /// Time position (in seconds) of the reference pose. Usually it is the first frame of the animation.
+    pub reference_time: f32,
+}
+
 /// Motion of a root node of an hierarchy of nodes. It contains relative rotation and translation in local
 /// space of the node. To transform this data into velocity and orientation you need to multiply these
 /// parts with some global transform, usually with the global transform of the mesh that is being animated.
@@ -424,6 +445,7 @@ impl<T: EntityId> Clone for Animation<T> {
             pose: Default::default(),
             signals: self.signals.clone(),
             root_motion_settings: self.root_motion_settings.clone(),
+            additive_settings: self.additive_settings.clone(),
             events: Default::default(),
             time_slice: self.time_slice.clone(),
             root_motion: self.root_motion.clone(),
@@ -743,6 +765,27 @@ impl<T: EntityId> Animation<T> {
         self.root_motion.as_ref()
     }
 
+    /// Sets new additive settings. `Some` makes the animation additive, `None` - makes it absolute (default).
+    /// See [`AdditiveSettings`] docs for more info.
+    pub fn set_additive_settings(&mut self, settings: Option<AdditiveSettings>) {
+        self.additive_settings = settings;
+    }
+
+    /// Returns a reference to the additive settings (if any).
+    pub fn additive_settings_ref(&self) -> Option<&AdditiveSettings> {
+        self.additive_settings.as_ref()
+    }
+
+    /// Returns a reference to the additive settings (if any).
+    pub fn additive_settings_mut(&mut self) -> Option<&mut AdditiveSettings> {
+        self.additive_settings.as_mut()
+    }
+
+    /// Returns `true` if the animation is additive, `false` - otherwise.
+    pub fn is_additive(&self) -> bool {
+        self.additive_settings.is_some()
+    }
+
     /// Extracts a first event from the events queue of the animation.
     pub fn pop_event(&mut self) -> Option<AnimationEvent> {
         self.events.pop_front()
@@ -968,7 +1011,12 @@ impl<T: EntityId> Animation<T> {
             };
 
             if binding.is_enabled() {
-                if let Some(bound_value) = track.fetch(self.time_position) {
+                if let Some(mut bound_value) = track.fetch(self.time_position) {
+                    if let Some(additive_settings) = self.additive_settings.as_ref() {
+                        if let Some(reference) = track.fetch(additive_settings.reference_time) {
+                            bound_value.value.make_additive(&reference.value);
+                        }
+                    }
                     self.pose.add_to_node_pose(binding.target(), bound_value);
                 }
             }
@@ -993,6 +1041,7 @@ impl<T: EntityId> Default for Animation<T> {
             pose: Default::default(),
             signals: Default::default(),
             root_motion_settings: None,
+            additive_settings: None,
             events: Default::default(),
             time_slice: Default::default(),
             root_motion: None,
@@ -34,7 +34,26 @@ use crate::{
     },
     Animation, AnimationContainer, AnimationEvent, AnimationPose, EntityId,
 };
-use fyrox_core::{find_by_name_mut, find_by_name_ref, NameProvider};
+use fyrox_core::{find_by_name_mut, find_by_name_ref, uuid_provider, NameProvider};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines how the pose of a layer is combined with the poses of previous layers of a state machine.
+#[derive(
+    Default, Debug, Visit, Reflect, Clone, Copy, PartialEq, Eq, VariantNames, EnumString, AsRefStr,
+)]
+pub enum LayerBlendMode {
+    /// The pose of the layer is blended with the poses of previous layers using the weight of the layer.
+    #[default]
+    Override,
+
+    /// The pose of the layer is added on top of the poses of previous layers using the weight of the
+    /// layer. The layer should contain additive poses only (see [`crate::AdditiveSettings`]), otherwise
+    /// the result will be incorrect. Additive layers are usually used for breathing, recoil, leaning
+    /// and similar animations, that should be layered over locomotion.
+    Additive,
+}
+
+uuid_provider!(LayerBlendMode = "866fb2d9-a632-4504-9eba-90bdab3ada85");
 
 /// Layer is a separate state graph. Layers mainly used to animate different parts of humanoid (but not only) characters. For
 /// example there could a layer for upper body and a layer for lower body. Upper body layer could contain animations for aiming,
@@ -87,6 +106,9 @@ pub struct MachineLayer<T: EntityId> {
 
     weight: f32,
 
+    #[visit(optional)]
+    blend_mode: LayerBlendMode,
+
     mask: LayerMask<T>,
 
     #[reflect(hidden)]
@@ -178,6 +200,7 @@ impl<T: EntityId> MachineLayer<T> {
             entry_state: Default::default(),
             active_transition: Default::default(),
             weight: 1.0,
+            blend_mode: Default::default(),
             events: FixedEventQueue::new(2048),
             debug: false,
             mask: Default::default(),
@@ -536,6 +559,18 @@ impl<T: EntityId> MachineLayer<T> {
         self.weight
     }
 
+    /// Sets new blend mode of the layer. See [`LayerBlendMode`] docs for more info.
+    #[inline]
+    pub fn set_blend_mode(&mut self, blend_mode: LayerBlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    /// Returns current blend mode of the layer.
+    #[inline]
+    pub fn blend_mode(&self) -> LayerBlendMode {
+        self.blend_mode
+    }
+
     /// Sets new layer mask. See docs of [`LayerMask`] for more info about layer masks.
     #[inline]
     pub fn set_mask(&mut self, mask: LayerMask<T>) -> LayerMask<T> {
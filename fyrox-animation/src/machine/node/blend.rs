@@ -448,3 +448,133 @@ impl<T: EntityId> AnimationPoseSource<T> for BlendAnimationsByIndex<T> {
         Default::default()
     }
 }
+
+/// A node that adds an additive pose on top of a base pose. The additive pose should be produced by additive
+/// animations (see [`crate::AdditiveSettings`]), it contains differences relative to a reference pose, which are
+/// then scaled by the weight and added to the base pose. For example, it could be used to add breathing, recoil or
+/// lean animations on top of locomotion animations.
+#[derive(Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct BlendAdditive<T: EntityId> {
+    /// Base node.
+    pub base: BasePoseNode<T>,
+
+    /// A source of the base pose.
+    #[reflect(hidden)]
+    pub base_pose: Handle<PoseNode<T>>,
+
+    /// A source of the additive pose.
+    #[reflect(hidden)]
+    pub additive_pose: Handle<PoseNode<T>>,
+
+    /// Weight of the additive pose. Zero means that the base pose will be passed through unchanged.
+    pub weight: PoseWeight,
+
+    /// Output pose of the node.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub output_pose: RefCell<AnimationPose<T>>,
+}
+
+impl<T: EntityId> Default for BlendAdditive<T> {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            base_pose: Default::default(),
+            additive_pose: Default::default(),
+            weight: PoseWeight::Constant(1.0),
+            output_pose: Default::default(),
+        }
+    }
+}
+
+impl<T: EntityId> Deref for BlendAdditive<T> {
+    type Target = BasePoseNode<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl<T: EntityId> DerefMut for BlendAdditive<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl<T: EntityId> BlendAdditive<T> {
+    /// Creates new additive blend node with given base and additive poses.
+    pub fn new(
+        base_pose: Handle<PoseNode<T>>,
+        additive_pose: Handle<PoseNode<T>>,
+        weight: PoseWeight,
+    ) -> Self {
+        Self {
+            base: Default::default(),
+            base_pose,
+            additive_pose,
+            weight,
+            output_pose: Default::default(),
+        }
+    }
+
+    /// Returns a set of handles to children pose nodes. The first one is the base pose, the second one
+    /// is the additive pose.
+    pub fn children(&self) -> Vec<Handle<PoseNode<T>>> {
+        vec![self.base_pose, self.additive_pose]
+    }
+}
+
+impl<T: EntityId> AnimationPoseSource<T> for BlendAdditive<T> {
+    fn eval_pose(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        dt: f32,
+    ) -> Ref<AnimationPose<T>> {
+        self.output_pose.borrow_mut().reset();
+
+        if let Some(base_pose) = nodes.try_borrow(self.base_pose) {
+            base_pose
+                .eval_pose(nodes, params, animations, dt)
+                .clone_into(&mut self.output_pose.borrow_mut());
+        }
+
+        if let Some(additive_pose) = nodes.try_borrow(self.additive_pose) {
+            let weight = self.weight.value(params).unwrap_or_default();
+            self.output_pose.borrow_mut().add_with(
+                &additive_pose.eval_pose(nodes, params, animations, dt),
+                weight,
+            );
+        }
+
+        self.output_pose.borrow()
+    }
+
+    fn pose(&self) -> Ref<AnimationPose<T>> {
+        self.output_pose.borrow()
+    }
+
+    fn collect_animation_events(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        strategy: AnimationEventCollectionStrategy,
+    ) -> Vec<(Handle<Animation<T>>, AnimationEvent)> {
+        let sources = match strategy {
+            AnimationEventCollectionStrategy::All => vec![self.base_pose, self.additive_pose],
+            // The base pose always has full weight.
+            AnimationEventCollectionStrategy::MaxWeight => vec![self.base_pose],
+            AnimationEventCollectionStrategy::MinWeight => vec![self.additive_pose],
+        };
+
+        let mut events = Vec::new();
+        for source in sources {
+            if let Some(source) = nodes.try_borrow(source) {
+                events.extend(source.collect_animation_events(nodes, params, animations, strategy));
+            }
+        }
+        events
+    }
+}
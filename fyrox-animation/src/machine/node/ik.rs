@@ -0,0 +1,851 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Inverse kinematics (IK) pose nodes. They take an input pose and modify rotations of a chain of
+//! bones so the end of the chain reaches (or looks at) a target. See [`TwoBoneIk`], [`FabrikChain`]
+//! and [`LookAt`] docs for more info.
+//!
+//! # Coordinate space
+//!
+//! Animation pose contains only local transforms of bones, so IK nodes solve everything in the
+//! local coordinate space of the **parent** of the first bone of a chain (chain space). Targets and
+//! poles must be defined in this space, using [`Parameter::Position`] parameters. To convert a
+//! world-space point to chain space, multiply it by the inverse of the global transform of the
+//! parent of the first bone.
+//!
+//! Every bone of a chain must have both position and rotation in the input pose, otherwise the
+//! chain is skipped and the input pose is passed through unchanged.
+
+use crate::{
+    core::{
+        algebra::{Unit, UnitQuaternion, Vector3},
+        pool::{Handle, Pool},
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
+    machine::{
+        node::{AnimationEventCollectionStrategy, AnimationPoseSource, BasePoseNode},
+        Parameter, ParameterContainer, PoseNode, PoseWeight,
+    },
+    value::{nlerp, BoundValue, TrackValue, ValueBinding},
+    Animation, AnimationContainer, AnimationEvent, AnimationPose, EntityId,
+};
+use std::{
+    cell::{Ref, RefCell},
+    ops::{Deref, DerefMut},
+};
+
+#[derive(Clone)]
+struct Joint {
+    position: Vector3<f32>,
+    rotation: UnitQuaternion<f32>,
+    scale: Vector3<f32>,
+}
+
+struct GlobalJoint {
+    position: Vector3<f32>,
+    rotation: UnitQuaternion<f32>,
+}
+
+/// A chain of bones extracted from an animation pose.
+struct Chain {
+    joints: Vec<Joint>,
+}
+
+impl Chain {
+    fn from_pose<T: EntityId>(pose: &AnimationPose<T>, bones: &[T]) -> Option<Self> {
+        let mut joints = Vec::with_capacity(bones.len());
+        for bone in bones {
+            let node_pose = pose.poses().get(bone)?;
+            let mut position = None;
+            let mut rotation = None;
+            let mut scale = Vector3::repeat(1.0);
+            for bound_value in node_pose.values.values.iter() {
+                match (&bound_value.binding, &bound_value.value) {
+                    (ValueBinding::Position, TrackValue::Vector3(v)) => position = Some(*v),
+                    (ValueBinding::Rotation, TrackValue::UnitQuaternion(v)) => rotation = Some(*v),
+                    (ValueBinding::Scale, TrackValue::Vector3(v)) => scale = *v,
+                    _ => (),
+                }
+            }
+            joints.push(Joint {
+                position: position?,
+                rotation: rotation?,
+                scale,
+            });
+        }
+        Some(Self { joints })
+    }
+
+    /// Calculates transforms of every joint in chain space.
+    fn global_joints(&self) -> Vec<GlobalJoint> {
+        let mut globals = Vec::<GlobalJoint>::with_capacity(self.joints.len());
+        let mut scale = Vector3::repeat(1.0);
+        for joint in self.joints.iter() {
+            let global = match globals.last() {
+                Some(parent) => GlobalJoint {
+                    position: parent.position
+                        + parent
+                            .rotation
+                            .transform_vector(&joint.position.component_mul(&scale)),
+                    rotation: parent.rotation * joint.rotation,
+                },
+                None => GlobalJoint {
+                    position: joint.position,
+                    rotation: joint.rotation,
+                },
+            };
+            scale.component_mul_assign(&joint.scale);
+            globals.push(global);
+        }
+        globals
+    }
+
+    /// Rotates a joint (and all its descendants) by the given rotation defined in chain space.
+    fn rotate(&mut self, index: usize, rotation: UnitQuaternion<f32>) {
+        let globals = self.global_joints();
+        let parent_rotation = index
+            .checked_sub(1)
+            .map(|parent| globals[parent].rotation)
+            .unwrap_or_default();
+        let joint = &mut self.joints[index];
+        joint.rotation = parent_rotation.inverse() * rotation * parent_rotation * joint.rotation;
+    }
+
+    /// Writes rotations of the chain back to the pose, blending them with the original rotations
+    /// using the given weight.
+    fn write_to_pose<T: EntityId>(
+        &self,
+        original: &Chain,
+        bones: &[T],
+        pose: &mut AnimationPose<T>,
+        weight: f32,
+    ) {
+        for ((bone, joint), original_joint) in bones
+            .iter()
+            .zip(self.joints.iter())
+            .zip(original.joints.iter())
+        {
+            let rotation = nlerp(original_joint.rotation, &joint.rotation, weight);
+            if let Some(node_pose) = pose.poses_mut().get_mut(bone) {
+                for bound_value in node_pose.values.values.iter_mut() {
+                    if let BoundValue {
+                        binding: ValueBinding::Rotation,
+                        value: TrackValue::UnitQuaternion(value),
+                    } = bound_value
+                    {
+                        *value = rotation;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn position_parameter(params: &ParameterContainer, name: &str) -> Option<Vector3<f32>> {
+    if let Some(Parameter::Position(position)) = params.get(name) {
+        Some(*position)
+    } else {
+        None
+    }
+}
+
+fn any_orthogonal(v: &Vector3<f32>) -> Vector3<f32> {
+    let axis = if v.x.abs() < 0.9 {
+        Vector3::x()
+    } else {
+        Vector3::y()
+    };
+    v.cross(&axis)
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector3::z)
+}
+
+/// Solves two-bone IK analytically. Indices 0, 1, 2 of the chain are upper, middle and end bones
+/// respectively.
+fn solve_two_bone(chain: &mut Chain, target: Vector3<f32>, pole: Option<Vector3<f32>>) {
+    const EPSILON: f32 = 1.0e-5;
+
+    let globals = chain.global_joints();
+    let (a, b, c) = (
+        globals[0].position,
+        globals[1].position,
+        globals[2].position,
+    );
+
+    let lab = (b - a).norm();
+    let lcb = (c - b).norm();
+    if lab < EPSILON || lcb < EPSILON {
+        return;
+    }
+
+    // Bend the middle joint, so the distance between the upper and the end joints will be equal
+    // to the distance to the target (if reachable).
+    let lat = (target - a)
+        .norm()
+        .clamp(EPSILON, (lab + lcb - EPSILON).max(EPSILON));
+    let bend_axis = (b - a)
+        .cross(&(c - b))
+        .try_normalize(EPSILON)
+        .unwrap_or_else(|| any_orthogonal(&(b - a)));
+    let current_angle = (a - b).angle(&(c - b));
+    let desired_angle = ((lat * lat - lab * lab - lcb * lcb) / (-2.0 * lab * lcb))
+        .clamp(-1.0, 1.0)
+        .acos();
+    chain.rotate(
+        1,
+        UnitQuaternion::from_axis_angle(
+            &Unit::new_unchecked(bend_axis),
+            current_angle - desired_angle,
+        ),
+    );
+
+    // Swing the upper joint, so the end joint will point at the target.
+    let c = chain.global_joints()[2].position;
+    if let Some(swing) = UnitQuaternion::rotation_between(&(c - a), &(target - a)) {
+        chain.rotate(0, swing);
+    }
+
+    // Twist the chain around the upper-target axis, so the middle joint will look at the pole.
+    if let (Some(pole), Some(direction)) = (pole, (target - a).try_normalize(EPSILON)) {
+        let b = chain.global_joints()[1].position;
+        let project = |v: Vector3<f32>| v - direction.scale(v.dot(&direction));
+        let projected_middle = project(b - a);
+        let projected_pole = project(pole - a);
+        if projected_middle.norm() > EPSILON && projected_pole.norm() > EPSILON {
+            if let Some(twist) =
+                UnitQuaternion::rotation_between(&projected_middle, &projected_pole)
+            {
+                chain.rotate(0, twist);
+            }
+        }
+    }
+}
+
+/// Solves IK for a chain of arbitrary length using FABRIK (Forward And Backward Reaching Inverse
+/// Kinematics) algorithm.
+fn solve_fabrik(chain: &mut Chain, target: Vector3<f32>, iterations: u32, tolerance: f32) {
+    let globals = chain.global_joints();
+    let mut positions = globals.iter().map(|g| g.position).collect::<Vec<_>>();
+    let lengths = positions
+        .windows(2)
+        .map(|w| (w[1] - w[0]).norm())
+        .collect::<Vec<_>>();
+    let total_length = lengths.iter().sum::<f32>();
+    let root = positions[0];
+    let last = positions.len() - 1;
+
+    if (target - root).norm() >= total_length {
+        // Target is unreachable - stretch the chain towards it.
+        let direction = (target - root)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default();
+        for i in 0..last {
+            positions[i + 1] = positions[i] + direction.scale(lengths[i]);
+        }
+    } else {
+        for _ in 0..iterations {
+            if (positions[last] - target).norm() <= tolerance {
+                break;
+            }
+
+            // Backward reaching.
+            positions[last] = target;
+            for i in (0..last).rev() {
+                let direction = (positions[i] - positions[i + 1])
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default();
+                positions[i] = positions[i + 1] + direction.scale(lengths[i]);
+            }
+
+            // Forward reaching.
+            positions[0] = root;
+            for i in 0..last {
+                let direction = (positions[i + 1] - positions[i])
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default();
+                positions[i + 1] = positions[i] + direction.scale(lengths[i]);
+            }
+        }
+    }
+
+    // Convert positions back to rotations.
+    for i in 0..last {
+        let globals = chain.global_joints();
+        let current = globals[i + 1].position - globals[i].position;
+        let desired = positions[i + 1] - positions[i];
+        if let Some(rotation) = UnitQuaternion::rotation_between(&current, &desired) {
+            chain.rotate(i, rotation);
+        }
+    }
+}
+
+/// Rotates a single joint, so its forward axis (defined in the local space of the joint) points at
+/// the target.
+fn solve_look_at(chain: &mut Chain, forward: Vector3<f32>, target: Vector3<f32>) {
+    let joint = &chain.joints[0];
+    let forward = joint.rotation.transform_vector(&forward);
+    if let Some(rotation) = UnitQuaternion::rotation_between(&forward, &(target - joint.position)) {
+        chain.rotate(0, rotation);
+    }
+}
+
+macro_rules! define_ik_node_common {
+    ($name:ident) => {
+        impl<T: EntityId> Deref for $name<T> {
+            type Target = BasePoseNode<T>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.base
+            }
+        }
+
+        impl<T: EntityId> DerefMut for $name<T> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.base
+            }
+        }
+
+        impl<T: EntityId> $name<T> {
+            /// Returns a set of handles to children pose nodes.
+            pub fn children(&self) -> Vec<Handle<PoseNode<T>>> {
+                vec![self.input]
+            }
+
+            fn eval_input_pose(
+                &self,
+                nodes: &Pool<PoseNode<T>>,
+                params: &ParameterContainer,
+                animations: &AnimationContainer<T>,
+                dt: f32,
+            ) {
+                let mut output_pose = self.output_pose.borrow_mut();
+                if let Some(input) = nodes.try_borrow(self.input) {
+                    input
+                        .eval_pose(nodes, params, animations, dt)
+                        .clone_into(&mut output_pose);
+                } else {
+                    output_pose.reset();
+                }
+            }
+
+            fn collect_input_animation_events(
+                &self,
+                nodes: &Pool<PoseNode<T>>,
+                params: &ParameterContainer,
+                animations: &AnimationContainer<T>,
+                strategy: AnimationEventCollectionStrategy,
+            ) -> Vec<(Handle<Animation<T>>, AnimationEvent)> {
+                nodes
+                    .try_borrow(self.input)
+                    .map(|input| {
+                        input.collect_animation_events(nodes, params, animations, strategy)
+                    })
+                    .unwrap_or_default()
+            }
+        }
+    };
+}
+
+/// Two-bone IK node modifies rotations of a chain of three bones (for example - thigh, calf and foot
+/// or upper arm, forearm and hand), so the end bone reaches a target. The middle bone bends towards
+/// an optional pole, which allows you to control the direction of knees or elbows. The solution is
+/// analytical, which makes this node very fast and stable. It is the best choice for foot placement
+/// and hand reach.
+///
+/// See the [module docs](self) for more info about the coordinate space of targets and poles.
+#[derive(Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct TwoBoneIk<T: EntityId> {
+    /// Base node.
+    pub base: BasePoseNode<T>,
+
+    /// A source of the pose, that will be modified by the node.
+    #[reflect(hidden)]
+    pub input: Handle<PoseNode<T>>,
+
+    /// The first bone of the chain (thigh, upper arm, etc.).
+    pub upper: T,
+
+    /// The second bone of the chain (calf, forearm, etc.). Must be a child of the upper bone.
+    pub middle: T,
+
+    /// The last bone of the chain (foot, hand, etc.). Must be a child of the middle bone.
+    pub end: T,
+
+    /// A name of a position parameter, that defines the target for the end bone.
+    pub target: String,
+
+    /// A name of a position parameter, that defines the direction of bending of the middle bone.
+    /// Could be empty, in this case the current bending direction is preserved.
+    pub pole: String,
+
+    /// Influence of the node. Zero means that the input pose will be passed through unchanged,
+    /// one means that the IK solution will fully override the rotations of the bones.
+    pub weight: PoseWeight,
+
+    /// Output pose of the node.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub output_pose: RefCell<AnimationPose<T>>,
+}
+
+impl<T: EntityId> Default for TwoBoneIk<T> {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            input: Default::default(),
+            upper: Default::default(),
+            middle: Default::default(),
+            end: Default::default(),
+            target: Default::default(),
+            pole: Default::default(),
+            weight: PoseWeight::Constant(1.0),
+            output_pose: Default::default(),
+        }
+    }
+}
+
+define_ik_node_common!(TwoBoneIk);
+
+impl<T: EntityId> TwoBoneIk<T> {
+    /// Creates new two-bone IK node for the given input pose, bones and target parameter.
+    pub fn new(
+        input: Handle<PoseNode<T>>,
+        upper: T,
+        middle: T,
+        end: T,
+        target: impl Into<String>,
+    ) -> Self {
+        Self {
+            input,
+            upper,
+            middle,
+            end,
+            target: target.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: EntityId> AnimationPoseSource<T> for TwoBoneIk<T> {
+    fn eval_pose(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        dt: f32,
+    ) -> Ref<AnimationPose<T>> {
+        self.eval_input_pose(nodes, params, animations, dt);
+
+        let weight = self
+            .weight
+            .value(params)
+            .unwrap_or_default()
+            .clamp(0.0, 1.0);
+        if let (Some(target), true) = (position_parameter(params, &self.target), weight > 0.0) {
+            let mut output_pose = self.output_pose.borrow_mut();
+            let bones = [self.upper, self.middle, self.end];
+            if let Some(original) = Chain::from_pose(&output_pose, &bones) {
+                let mut chain = Chain {
+                    joints: original.joints.clone(),
+                };
+                solve_two_bone(&mut chain, target, position_parameter(params, &self.pole));
+                chain.write_to_pose(&original, &bones, &mut output_pose, weight);
+            }
+        }
+
+        self.output_pose.borrow()
+    }
+
+    fn pose(&self) -> Ref<AnimationPose<T>> {
+        self.output_pose.borrow()
+    }
+
+    fn collect_animation_events(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        strategy: AnimationEventCollectionStrategy,
+    ) -> Vec<(Handle<Animation<T>>, AnimationEvent)> {
+        self.collect_input_animation_events(nodes, params, animations, strategy)
+    }
+}
+
+/// FABRIK (Forward And Backward Reaching Inverse Kinematics) node modifies rotations of a chain of
+/// bones of arbitrary length, so the last bone of the chain reaches a target. It is an iterative
+/// solver that works well for tails, tentacles, spines, etc.
+///
+/// See the [module docs](self) for more info about the coordinate space of targets.
+#[derive(Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct FabrikChain<T: EntityId> {
+    /// Base node.
+    pub base: BasePoseNode<T>,
+
+    /// A source of the pose, that will be modified by the node.
+    #[reflect(hidden)]
+    pub input: Handle<PoseNode<T>>,
+
+    /// A chain of bones, starting from the root of the chain. Every bone must be a child of the
+    /// previous one.
+    pub bones: Vec<T>,
+
+    /// A name of a position parameter, that defines the target for the last bone.
+    pub target: String,
+
+    /// Max amount of iterations of the solver.
+    #[reflect(min_value = 1.0)]
+    pub iterations: u32,
+
+    /// Max distance between the last bone and the target, at which the solver stops iterating.
+    #[reflect(min_value = 0.0)]
+    pub tolerance: f32,
+
+    /// Influence of the node. Zero means that the input pose will be passed through unchanged,
+    /// one means that the IK solution will fully override the rotations of the bones.
+    pub weight: PoseWeight,
+
+    /// Output pose of the node.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub output_pose: RefCell<AnimationPose<T>>,
+}
+
+impl<T: EntityId> Default for FabrikChain<T> {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            input: Default::default(),
+            bones: Default::default(),
+            target: Default::default(),
+            iterations: 10,
+            tolerance: 0.001,
+            weight: PoseWeight::Constant(1.0),
+            output_pose: Default::default(),
+        }
+    }
+}
+
+define_ik_node_common!(FabrikChain);
+
+impl<T: EntityId> FabrikChain<T> {
+    /// Creates new FABRIK node for the given input pose, chain of bones and target parameter.
+    pub fn new(input: Handle<PoseNode<T>>, bones: Vec<T>, target: impl Into<String>) -> Self {
+        Self {
+            input,
+            bones,
+            target: target.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: EntityId> AnimationPoseSource<T> for FabrikChain<T> {
+    fn eval_pose(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        dt: f32,
+    ) -> Ref<AnimationPose<T>> {
+        self.eval_input_pose(nodes, params, animations, dt);
+
+        let weight = self
+            .weight
+            .value(params)
+            .unwrap_or_default()
+            .clamp(0.0, 1.0);
+        if let (Some(target), true) = (
+            position_parameter(params, &self.target),
+            weight > 0.0 && self.bones.len() >= 2,
+        ) {
+            let mut output_pose = self.output_pose.borrow_mut();
+            if let Some(original) = Chain::from_pose(&output_pose, &self.bones) {
+                let mut chain = Chain {
+                    joints: original.joints.clone(),
+                };
+                solve_fabrik(&mut chain, target, self.iterations, self.tolerance);
+                chain.write_to_pose(&original, &self.bones, &mut output_pose, weight);
+            }
+        }
+
+        self.output_pose.borrow()
+    }
+
+    fn pose(&self) -> Ref<AnimationPose<T>> {
+        self.output_pose.borrow()
+    }
+
+    fn collect_animation_events(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        strategy: AnimationEventCollectionStrategy,
+    ) -> Vec<(Handle<Animation<T>>, AnimationEvent)> {
+        self.collect_input_animation_events(nodes, params, animations, strategy)
+    }
+}
+
+/// Look-at node rotates a single bone, so its forward axis points at a target. It could be used to
+/// make a character look at something (head), aim a weapon (spine, hands), etc.
+///
+/// See the [module docs](self) for more info about the coordinate space of targets.
+#[derive(Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct LookAt<T: EntityId> {
+    /// Base node.
+    pub base: BasePoseNode<T>,
+
+    /// A source of the pose, that will be modified by the node.
+    #[reflect(hidden)]
+    pub input: Handle<PoseNode<T>>,
+
+    /// A bone that will be rotated.
+    pub bone: T,
+
+    /// An axis in the local space of the bone, that should point at the target.
+    pub forward: Vector3<f32>,
+
+    /// A name of a position parameter, that defines the target.
+    pub target: String,
+
+    /// Influence of the node. Zero means that the input pose will be passed through unchanged,
+    /// one means that the bone will point exactly at the target.
+    pub weight: PoseWeight,
+
+    /// Output pose of the node.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub output_pose: RefCell<AnimationPose<T>>,
+}
+
+impl<T: EntityId> Default for LookAt<T> {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            input: Default::default(),
+            bone: Default::default(),
+            forward: Vector3::z(),
+            target: Default::default(),
+            weight: PoseWeight::Constant(1.0),
+            output_pose: Default::default(),
+        }
+    }
+}
+
+define_ik_node_common!(LookAt);
+
+impl<T: EntityId> LookAt<T> {
+    /// Creates new look-at node for the given input pose, bone and target parameter.
+    pub fn new(input: Handle<PoseNode<T>>, bone: T, target: impl Into<String>) -> Self {
+        Self {
+            input,
+            bone,
+            target: target.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: EntityId> AnimationPoseSource<T> for LookAt<T> {
+    fn eval_pose(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        dt: f32,
+    ) -> Ref<AnimationPose<T>> {
+        self.eval_input_pose(nodes, params, animations, dt);
+
+        let weight = self
+            .weight
+            .value(params)
+            .unwrap_or_default()
+            .clamp(0.0, 1.0);
+        if let (Some(target), true) = (position_parameter(params, &self.target), weight > 0.0) {
+            let mut output_pose = self.output_pose.borrow_mut();
+            let bones = [self.bone];
+            if let Some(original) = Chain::from_pose(&output_pose, &bones) {
+                let mut chain = Chain {
+                    joints: original.joints.clone(),
+                };
+                solve_look_at(&mut chain, self.forward, target);
+                chain.write_to_pose(&original, &bones, &mut output_pose, weight);
+            }
+        }
+
+        self.output_pose.borrow()
+    }
+
+    fn pose(&self) -> Ref<AnimationPose<T>> {
+        self.output_pose.borrow()
+    }
+
+    fn collect_animation_events(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        strategy: AnimationEventCollectionStrategy,
+    ) -> Vec<(Handle<Animation<T>>, AnimationEvent)> {
+        self.collect_input_animation_events(nodes, params, animations, strategy)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{solve_fabrik, solve_look_at, solve_two_bone, Chain};
+    use crate::{
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            pool::ErasedHandle,
+        },
+        value::{BoundValue, BoundValueCollection, TrackValue, ValueBinding},
+        AnimationPose, NodePose,
+    };
+
+    fn make_pose(offsets: &[Vector3<f32>]) -> (AnimationPose<ErasedHandle>, Vec<ErasedHandle>) {
+        let mut pose = AnimationPose::default();
+        let mut bones = Vec::new();
+        for (i, offset) in offsets.iter().enumerate() {
+            let bone = ErasedHandle::new(i as u32, 1);
+            pose.poses_mut().insert(
+                bone,
+                NodePose {
+                    node: bone,
+                    values: BoundValueCollection {
+                        values: vec![
+                            BoundValue {
+                                binding: ValueBinding::Position,
+                                value: TrackValue::Vector3(*offset),
+                            },
+                            BoundValue {
+                                binding: ValueBinding::Rotation,
+                                value: TrackValue::UnitQuaternion(UnitQuaternion::identity()),
+                            },
+                        ],
+                    },
+                },
+            );
+            bones.push(bone);
+        }
+        (pose, bones)
+    }
+
+    fn end_position(pose: &AnimationPose<ErasedHandle>, bones: &[ErasedHandle]) -> Vector3<f32> {
+        Chain::from_pose(pose, bones)
+            .unwrap()
+            .global_joints()
+            .last()
+            .unwrap()
+            .position
+    }
+
+    fn solve_and_write(
+        pose: &mut AnimationPose<ErasedHandle>,
+        bones: &[ErasedHandle],
+        solver: impl FnOnce(&mut Chain),
+    ) {
+        let original = Chain::from_pose(pose, bones).unwrap();
+        let mut chain = Chain {
+            joints: original.joints.clone(),
+        };
+        solver(&mut chain);
+        chain.write_to_pose(&original, bones, pose, 1.0);
+    }
+
+    #[test]
+    fn test_two_bone_ik_reaches_target() {
+        let (mut pose, bones) = make_pose(&[
+            Vector3::new(0.0, 2.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+        ]);
+
+        let target = Vector3::new(0.5, 0.8, 0.3);
+        solve_and_write(&mut pose, &bones, |chain| {
+            solve_two_bone(chain, target, Some(Vector3::new(0.0, 1.0, 5.0)))
+        });
+
+        assert!((end_position(&pose, &bones) - target).norm() < 1.0e-3);
+
+        // The middle joint must bend towards the pole.
+        let middle = Chain::from_pose(&pose, &bones).unwrap().global_joints()[1].position;
+        assert!(middle.z > 0.0);
+    }
+
+    #[test]
+    fn test_two_bone_ik_unreachable_target() {
+        let (mut pose, bones) = make_pose(&[
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        ]);
+
+        solve_and_write(&mut pose, &bones, |chain| {
+            solve_two_bone(chain, Vector3::new(10.0, 0.0, 0.0), None)
+        });
+
+        let end = end_position(&pose, &bones);
+        assert!((end - Vector3::new(2.0, 0.0, 0.0)).norm() < 1.0e-2);
+    }
+
+    #[test]
+    fn test_fabrik_reaches_target() {
+        let (mut pose, bones) = make_pose(&[
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        ]);
+
+        let target = Vector3::new(1.5, 1.5, 0.5);
+        solve_and_write(&mut pose, &bones, |chain| {
+            solve_fabrik(chain, target, 32, 0.0001)
+        });
+
+        assert!((end_position(&pose, &bones) - target).norm() < 1.0e-2);
+    }
+
+    #[test]
+    fn test_look_at_points_forward_axis_at_target() {
+        let (mut pose, bones) = make_pose(&[Vector3::new(1.0, 0.0, 0.0)]);
+
+        let target = Vector3::new(1.0, 5.0, 0.0);
+        solve_and_write(&mut pose, &bones, |chain| {
+            solve_look_at(chain, Vector3::z(), target)
+        });
+
+        let rotation = Chain::from_pose(&pose, &bones).unwrap().joints[0].rotation;
+        let forward = rotation.transform_vector(&Vector3::z());
+        assert!((forward - Vector3::y()).norm() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_chain_without_rotation_is_skipped() {
+        let (mut pose, bones) = make_pose(&[Vector3::default(), Vector3::y()]);
+        pose.poses_mut()
+            .get_mut(&bones[1])
+            .unwrap()
+            .values
+            .values
+            .retain(|v| v.binding != ValueBinding::Rotation);
+        assert!(Chain::from_pose(&pose, &bones).is_none());
+    }
+}
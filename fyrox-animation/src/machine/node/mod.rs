@@ -29,8 +29,14 @@ use crate::{
         visitor::prelude::*,
     },
     machine::{
-        node::{blend::BlendAnimations, blendspace::BlendSpace, play::PlayAnimation},
-        BlendAnimationsByIndex, BlendPose, IndexedBlendInput, ParameterContainer, State,
+        node::{
+            blend::{BlendAdditive, BlendAnimations},
+            blendspace::BlendSpace,
+            ik::{FabrikChain, LookAt, TwoBoneIk},
+            play::PlayAnimation,
+        },
+        BlendAnimationsByIndex, BlendPose, IndexedBlendInput, ParameterContainer, PoseWeight,
+        State,
     },
     Animation, AnimationContainer, AnimationEvent, AnimationPose, EntityId,
 };
@@ -42,6 +48,7 @@ use std::{
 
 pub mod blend;
 pub mod blendspace;
+pub mod ik;
 pub mod play;
 
 /// A set of common data fields that is used in every node.
@@ -69,6 +76,18 @@ pub enum PoseNode<T: EntityId> {
 
     /// See doc for [`BlendSpace`]
     BlendSpace(BlendSpace<T>),
+
+    /// See docs for [`TwoBoneIk`].
+    TwoBoneIk(TwoBoneIk<T>),
+
+    /// See docs for [`FabrikChain`].
+    FabrikChain(FabrikChain<T>),
+
+    /// See docs for [`LookAt`].
+    LookAt(LookAt<T>),
+
+    /// See docs for [`BlendAdditive`].
+    BlendAdditive(BlendAdditive<T>),
 }
 
 impl<T: EntityId> Default for PoseNode<T> {
@@ -97,6 +116,43 @@ impl<T: EntityId> PoseNode<T> {
         Self::BlendAnimationsByIndex(BlendAnimationsByIndex::new(index_parameter, inputs))
     }
 
+    /// Creates new node that adds an additive pose on top of a base pose.
+    pub fn make_blend_additive(
+        base_pose: Handle<PoseNode<T>>,
+        additive_pose: Handle<PoseNode<T>>,
+        weight: PoseWeight,
+    ) -> Self {
+        Self::BlendAdditive(BlendAdditive::new(base_pose, additive_pose, weight))
+    }
+
+    /// Creates new node that modifies rotations of three bones of the input pose, so the end bone
+    /// reaches a target defined by a position parameter.
+    pub fn make_two_bone_ik(
+        input: Handle<PoseNode<T>>,
+        upper: T,
+        middle: T,
+        end: T,
+        target: impl Into<String>,
+    ) -> Self {
+        Self::TwoBoneIk(TwoBoneIk::new(input, upper, middle, end, target))
+    }
+
+    /// Creates new node that modifies rotations of a chain of bones of the input pose, so the last
+    /// bone reaches a target defined by a position parameter.
+    pub fn make_fabrik_chain(
+        input: Handle<PoseNode<T>>,
+        bones: Vec<T>,
+        target: impl Into<String>,
+    ) -> Self {
+        Self::FabrikChain(FabrikChain::new(input, bones, target))
+    }
+
+    /// Creates new node that rotates a bone of the input pose, so it points at a target defined by
+    /// a position parameter.
+    pub fn make_look_at(input: Handle<PoseNode<T>>, bone: T, target: impl Into<String>) -> Self {
+        Self::LookAt(LookAt::new(input, bone, target))
+    }
+
     /// Returns a set of handles to children pose nodes.
     pub fn children(&self) -> Vec<Handle<PoseNode<T>>> {
         match self {
@@ -107,6 +163,10 @@ impl<T: EntityId> PoseNode<T> {
             Self::BlendAnimations(blend_animations) => blend_animations.children(),
             Self::BlendAnimationsByIndex(blend_by_index) => blend_by_index.children(),
             Self::BlendSpace(blend_space) => blend_space.children(),
+            Self::TwoBoneIk(two_bone_ik) => two_bone_ik.children(),
+            Self::FabrikChain(fabrik_chain) => fabrik_chain.children(),
+            Self::LookAt(look_at) => look_at.children(),
+            Self::BlendAdditive(blend_additive) => blend_additive.children(),
         }
     }
 
@@ -141,6 +201,16 @@ impl<T: EntityId> PoseNode<T> {
                     }
                 }
             }
+            PoseNode::TwoBoneIk(_)
+            | PoseNode::FabrikChain(_)
+            | PoseNode::LookAt(_)
+            | PoseNode::BlendAdditive(_) => {
+                for child in self.children() {
+                    if let Some(source) = nodes.try_borrow(child) {
+                        source.collect_animations(nodes, animations)
+                    }
+                }
+            }
         }
     }
 }
@@ -152,6 +222,10 @@ macro_rules! static_dispatch {
             PoseNode::BlendAnimations(v) => v.$func($($args),*),
             PoseNode::BlendAnimationsByIndex(v) => v.$func($($args),*),
             PoseNode::BlendSpace(v) => v.$func($($args),*),
+            PoseNode::TwoBoneIk(v) => v.$func($($args),*),
+            PoseNode::FabrikChain(v) => v.$func($($args),*),
+            PoseNode::LookAt(v) => v.$func($($args),*),
+            PoseNode::BlendAdditive(v) => v.$func($($args),*),
         }
     };
 }
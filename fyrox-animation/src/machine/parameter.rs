@@ -20,7 +20,11 @@
 
 //! Parameter is a name variable of a fixed type. See [`Parameter`] docs for more info.
 
-use crate::core::{algebra::Vector2, reflect::prelude::*, visitor::prelude::*};
+use crate::core::{
+    algebra::{Vector2, Vector3},
+    reflect::prelude::*,
+    visitor::prelude::*,
+};
 use fxhash::FxHashMap;
 use fyrox_core::uuid_provider;
 use std::{
@@ -45,6 +49,9 @@ pub enum Parameter {
 
     /// A sampling point. Usually it is used together with BlendSpace nodes.
     SamplingPoint(Vector2<f32>),
+
+    /// A position. Usually it is used as a target or a pole for inverse kinematics nodes.
+    Position(Vector3<f32>),
 }
 
 uuid_provider!(Parameter = "ace1b8ea-15ee-444d-97be-1682cd9e4245");
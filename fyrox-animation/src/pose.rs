@@ -41,6 +41,12 @@ impl<T: EntityId> NodePose<T> {
     pub fn blend_with(&mut self, other: &NodePose<T>, weight: f32) {
         self.values.blend_with(&other.values, weight)
     }
+
+    /// Adds an additive pose on top of the current pose. See [`super::value::TrackValue::add_with`] docs for more
+    /// info.
+    pub fn add_with(&mut self, other: &NodePose<T>, weight: f32) {
+        self.values.add_with(&other.values, weight)
+    }
 }
 
 /// Animations pose is a set of node poses. See [`NodePose`] docs for more info.
@@ -87,6 +93,17 @@ impl<T: EntityId> AnimationPose<T> {
             .blend_with(&other.root_motion.clone().unwrap_or_default(), weight);
     }
 
+    /// Adds an additive animation pose (a pose of an additive animation, see [`crate::AdditiveSettings`]) on top of
+    /// the current pose using a weight coefficient. Node poses that are missing in the current pose are ignored, since
+    /// there's nothing to add the difference to. Root motion is left untouched.
+    pub fn add_with(&mut self, other: &AnimationPose<T>, weight: f32) {
+        for (handle, other_pose) in other.poses.iter() {
+            if let Some(current_pose) = self.poses.get_mut(handle) {
+                current_pose.add_with(other_pose, weight);
+            }
+        }
+    }
+
     fn add_node_pose(&mut self, local_pose: NodePose<T>) {
         self.poses.insert(local_pose.node, local_pose);
     }
@@ -233,6 +233,35 @@ impl TrackValue {
         }
     }
 
+    /// Turns the current value into a difference between the value and the given reference value. Numeric values and
+    /// vectors are subtracted, rotations are converted to a relative rotation (`reference^-1 * self`). The result is
+    /// called an additive value and it could be added on top of some other value using [`Self::add_with`].
+    pub fn make_additive(&mut self, reference: &Self) {
+        match (self, reference) {
+            (Self::Real(a), Self::Real(b)) => *a -= *b,
+            (Self::Vector2(a), Self::Vector2(b)) => *a -= *b,
+            (Self::Vector3(a), Self::Vector3(b)) => *a -= *b,
+            (Self::Vector4(a), Self::Vector4(b)) => *a -= *b,
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => *a = b.inverse() * *a,
+            _ => (),
+        }
+    }
+
+    /// Adds an additive value (see [`Self::make_additive`]) scaled by the given weight to the current value. Adding is
+    /// possible only if the types are the same.
+    pub fn add_with(&mut self, additive: &Self, weight: f32) {
+        match (self, additive) {
+            (Self::Real(a), Self::Real(b)) => *a += *b * weight,
+            (Self::Vector2(a), Self::Vector2(b)) => *a += b.scale(weight),
+            (Self::Vector3(a), Self::Vector3(b)) => *a += b.scale(weight),
+            (Self::Vector4(a), Self::Vector4(b)) => *a += b.scale(weight),
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => {
+                *a *= nlerp(UnitQuaternion::identity(), b, weight)
+            }
+            _ => (),
+        }
+    }
+
     /// Tries to perform a numeric type casting of the current value to some other and returns a boxed value, that can
     /// be used to set the value using reflection.
     pub fn apply_to_any(&self, any: &mut dyn Any, value_type: ValueType) {
@@ -401,6 +430,13 @@ impl BoundValue {
         self.value.blend_with(&other.value, weight);
     }
 
+    /// Adds an additive value to the current value using the given weight. See [`TrackValue::add_with`] for more
+    /// info.
+    pub fn add_with(&mut self, other: &Self, weight: f32) {
+        assert_eq!(self.binding, other.binding);
+        self.value.add_with(&other.value, weight);
+    }
+
     /// Sets a property of the given object.
     pub fn apply_to_object(
         &self,
@@ -440,6 +476,16 @@ impl BoundValueCollection {
             }
         }
     }
+
+    /// Tries to add each additive value of the other collection to a respective (by binding) value in the current
+    /// collection. See [`TrackValue::add_with`] docs for more info.
+    pub fn add_with(&mut self, other: &Self, weight: f32) {
+        for value in self.values.iter_mut() {
+            if let Some(other_value) = other.values.iter().find(|v| v.binding == value.binding) {
+                value.add_with(other_value, weight);
+            }
+        }
+    }
 }
 
 /// Interpolates from `a` to `b` using nlerp, including an additional check to ensure
@@ -459,7 +505,10 @@ pub fn negate_unit_quaternion(a: &UnitQuaternion<f32>) -> UnitQuaternion<f32> {
 #[cfg(test)]
 mod test {
     use crate::value::{BoundValue, TrackValue, ValueBinding, ValueType};
-    use fyrox_core::reflect::prelude::*;
+    use fyrox_core::{
+        algebra::{UnitQuaternion, Vector3},
+        reflect::prelude::*,
+    };
 
     #[derive(Default, Reflect, Debug, PartialEq)]
     struct OtherStruct {
@@ -510,4 +559,29 @@ mod test {
         field_value.apply_to_object(&mut object, "other_struct.field", ValueType::U32);
         assert_eq!(object.other_struct.field, 123);
     }
+
+    #[test]
+    fn test_additive_value() {
+        let mut position = TrackValue::Vector3(Vector3::new(2.0, 3.0, 4.0));
+        position.make_additive(&TrackValue::Vector3(Vector3::new(1.0, 1.0, 1.0)));
+        assert_eq!(position, TrackValue::Vector3(Vector3::new(1.0, 2.0, 3.0)));
+
+        let mut base = TrackValue::Vector3(Vector3::new(10.0, 0.0, 0.0));
+        base.add_with(&position, 0.5);
+        assert_eq!(base, TrackValue::Vector3(Vector3::new(10.5, 1.0, 1.5)));
+
+        let reference = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.5);
+        let mut rotation =
+            TrackValue::UnitQuaternion(UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 1.5));
+        rotation.make_additive(&TrackValue::UnitQuaternion(reference));
+
+        let base_rotation = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 0.3);
+        let mut base = TrackValue::UnitQuaternion(base_rotation);
+        base.add_with(&rotation, 1.0);
+        let TrackValue::UnitQuaternion(result) = base else {
+            unreachable!()
+        };
+        let expected = base_rotation * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 1.0);
+        assert!(result.angle_to(&expected) < 1.0e-5);
+    }
 }
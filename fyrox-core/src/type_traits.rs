@@ -18,7 +18,7 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::math::Rect;
+use crate::{algebra::Vector2, math::Rect};
 pub use fyrox_core_derive::ComponentProvider;
 pub use fyrox_core_derive::TypeUuidProvider;
 use std::any::{Any, TypeId};
@@ -101,6 +101,15 @@ impl<T: TypeUuidProvider> TypeUuidProvider for Vec<T> {
     }
 }
 
+impl<T: TypeUuidProvider> TypeUuidProvider for Vector2<T> {
+    fn type_uuid() -> Uuid {
+        combine_uuids(
+            uuid::uuid!("81ae53e8-cc4e-4509-9d2d-aa089d9a0588"),
+            T::type_uuid(),
+        )
+    }
+}
+
 #[inline]
 pub fn combine_uuids(a: Uuid, b: Uuid) -> Uuid {
     let mut combined_bytes = a.into_bytes();
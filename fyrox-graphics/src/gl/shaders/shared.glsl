@@ -59,6 +59,15 @@ vec3 S_UnProject(vec3 screenPos, mat4 matrix)
     return position.xyz / position.w;
 }
 
+// Returns screen-space velocity (in texture coordinates) of a point using its clip-space
+// positions in the current and previous frames.
+vec2 S_ComputeVelocity(vec4 currentClipPosition, vec4 previousClipPosition)
+{
+    vec2 current = currentClipPosition.xy / currentClipPosition.w;
+    vec2 previous = previousClipPosition.xy / previousClipPosition.w;
+    return (current - previous) * 0.5;
+}
+
 float S_DistributionGGX(vec3 N, vec3 H, float roughness)
 {
     float a = roughness * roughness;
@@ -372,4 +381,50 @@ vec2 S_RotateVec2(vec2 v, float angle)
     float s = sin(angle);
     mat2 m = mat2(c, -s, s, c);
     return m * v;
-}
\ No newline at end of file
+}
+// Calculates fog amount in [0; 1] range for the given fragment. Fog modes: 0 - none, 1 - linear,
+// 2 - exponential, 3 - exponential squared. Exponential modes could also be height-based, if
+// heightFalloff is greater than zero.
+float S_FogFactor(
+    int mode,
+    vec3 cameraPosition,
+    vec3 fragmentPosition,
+    float start,
+    float end,
+    float density,
+    float height,
+    float heightFalloff)
+{
+    if (mode == 0) {
+        return 0.0;
+    }
+
+    vec3 ray = fragmentPosition - cameraPosition;
+    float distance = length(ray);
+
+    if (mode == 1) {
+        return clamp((distance - start) / max(end - start, 0.0001), 0.0, 1.0);
+    }
+
+    float fogDistance = max(distance - start, 0.0);
+
+    // Average the density along the ray in case of height-based fog. The density at a height
+    // h is: density * exp(-heightFalloff * (h - height)), integrating it along the ray gives
+    // the expression below.
+    float densityScale = 1.0;
+    if (heightFalloff > 0.0) {
+        float k = heightFalloff * ray.y;
+        densityScale = exp(-heightFalloff * (cameraPosition.y - height));
+        if (abs(k) > 0.0001) {
+            densityScale *= (1.0 - exp(-k)) / k;
+        }
+        densityScale = min(densityScale, 10000.0);
+    }
+
+    float amount = density * densityScale * fogDistance;
+    if (mode == 3) {
+        amount *= amount;
+    }
+
+    return clamp(1.0 - exp(-amount), 0.0, 1.0);
+}
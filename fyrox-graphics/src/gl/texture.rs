@@ -188,6 +188,7 @@ impl PixelKind {
             PixelKind::RGBA32F => (glow::FLOAT, glow::RGBA, glow::RGBA32F, None),
             PixelKind::RGBA16F => (glow::HALF_FLOAT, glow::RGBA, glow::RGBA16F, None),
             PixelKind::RGB16F => (glow::HALF_FLOAT, glow::RGB, glow::RGB16F, None),
+            PixelKind::RG16F => (glow::HALF_FLOAT, glow::RG, glow::RG16F, None),
             PixelKind::R11G11B10F => (glow::FLOAT, glow::RGB, glow::R11F_G11F_B10F, None),
             PixelKind::L8 => (
                 glow::UNSIGNED_BYTE,
@@ -347,6 +348,12 @@ impl TempBinding {
                 .tex_parameter_f32(self.target, glow::TEXTURE_LOD_BIAS, bias);
         }
     }
+
+    fn generate_mipmap(&mut self) {
+        unsafe {
+            self.server.gl.generate_mipmap(self.target);
+        }
+    }
 }
 
 impl Drop for TempBinding {
@@ -915,4 +922,22 @@ impl GpuTextureTrait for GlTexture {
     fn lod_bias(&self) -> f32 {
         self.lod_bias.get()
     }
+
+    fn generate_mipmap(&self) {
+        let size = match self.kind.get() {
+            GpuTextureKind::Line { length } => length,
+            GpuTextureKind::Rectangle { width, height }
+            | GpuTextureKind::Cube { width, height } => width.max(height),
+            GpuTextureKind::Volume {
+                width,
+                height,
+                depth,
+            } => width.max(height).max(depth),
+        };
+        let max_level = size.max(1).ilog2() as usize;
+        let mut temp_binding = self.make_temp_binding();
+        temp_binding.set_max_level(max_level);
+        temp_binding.generate_mipmap();
+        self.max_level.set(max_level);
+    }
 }
@@ -128,6 +128,8 @@ pub enum PixelKind {
     RGB16F,
     /// Floating-point RGBA texture with 16-bit depth.
     RGBA16F,
+    /// Floating-point RG texture with 16-bit depth.
+    RG16F,
     /// Compressed R8 texture (RGTC).
     R8RGTC,
     /// Compressed RG8 texture (RGTC).
@@ -168,6 +170,7 @@ impl PixelKind {
             | Self::D32F
             | Self::R32F
             | Self::R32UI
+            | Self::RG16F
             | Self::RGB10A2 => Some(4),
             Self::RG8 | Self::LA8 | Self::D16 | Self::R16F | Self::L16 | Self::R16 => Some(2),
             Self::R8
@@ -222,6 +225,7 @@ impl PixelKind {
             | Self::RGBA32F
             | Self::R11G11B10F
             | Self::RGB10A2
+            | Self::RG16F
             | Self::L8
             | Self::LA8
             | Self::L16
@@ -238,6 +242,7 @@ impl PixelKind {
             | Self::RGBA32F
             | Self::RGBA16F
             | Self::RGB16F
+            | Self::RG16F
             | Self::D32F
             | Self::R11G11B10F => PixelElementKind::Float,
             Self::D16
@@ -298,6 +303,7 @@ pub fn image_3d_size_bytes(
         | PixelKind::R32F
         | PixelKind::R32UI
         | PixelKind::R11G11B10F
+        | PixelKind::RG16F
         | PixelKind::RGB10A2 => 4 * pixel_count,
         PixelKind::RGB8 | PixelKind::SRGB8 | PixelKind::BGR8 => 3 * pixel_count,
         PixelKind::RG8
@@ -337,6 +343,7 @@ pub fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -
         | PixelKind::R32F
         | PixelKind::R32UI
         | PixelKind::R11G11B10F
+        | PixelKind::RG16F
         | PixelKind::RGB10A2 => 4 * pixel_count,
         PixelKind::RGB8 | PixelKind::SRGB8 | PixelKind::BGR8 => 3 * pixel_count,
         PixelKind::RG8
@@ -375,6 +382,7 @@ pub fn image_1d_size_bytes(pixel_kind: PixelKind, length: usize) -> usize {
         | PixelKind::R32F
         | PixelKind::R32UI
         | PixelKind::R11G11B10F
+        | PixelKind::RG16F
         | PixelKind::RGB10A2 => 4 * length,
         PixelKind::RGB8 | PixelKind::SRGB8 | PixelKind::BGR8 => 3 * length,
         PixelKind::RG8
@@ -704,6 +712,11 @@ pub trait GpuTextureTrait: Downcast {
     /// Returns a fixed bias value that is to be added to the level-of-detail parameter for the
     /// texture before texture sampling. See [`Self::set_lod_bias`] for more info.
     fn lod_bias(&self) -> f32;
+
+    /// Generates a full chain of mip levels from the main level of the texture and sets the max
+    /// level accordingly. It is useful for textures that are used as render targets, since their
+    /// content is produced on GPU.
+    fn generate_mipmap(&self);
 }
 
 impl dyn GpuTextureTrait {
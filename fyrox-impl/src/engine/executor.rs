@@ -238,6 +238,35 @@ impl Executor {
             }
         }
 
+        // Exported games could have their assets packed into archives. Loose files have the
+        // highest priority, then the archives in reverse alphabetical order, so a patch archive
+        // (for example `data_patch.pak`) overrides the entries of the main one (`data.pak`).
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        {
+            use crate::asset::{
+                io::{FsResourceIo, LayeredResourceIo},
+                pak::PakResourceIo,
+            };
+
+            let archives = PakResourceIo::open_all(".");
+            if !archives.is_empty() {
+                let mut resource_io = LayeredResourceIo::new().with_layer(Arc::new(FsResourceIo));
+                for archive in archives {
+                    Log::info(format!("Using {archive:?} archive."));
+                    resource_io.add_layer(Arc::new(archive));
+                }
+                engine
+                    .resource_manager
+                    .state()
+                    .set_resource_io(Arc::new(resource_io));
+            }
+        }
+
+        // Find actual locations of the resources, that were moved after the scenes were saved.
+        engine
+            .resource_manager
+            .update_registry_in_background(".", false);
+
         let args = Args::try_parse().unwrap_or_default();
 
         engine.enable_plugins(args.override_scene.as_deref(), true, Some(&event_loop));
@@ -295,6 +324,9 @@ impl Executor {
                     previous = Instant::now();
                     lag += elapsed.as_secs_f32();
 
+                    #[cfg(feature = "gamepad")]
+                    engine.handle_gamepad_events(fixed_time_step, window_target, &mut lag);
+
                     // Update rate stabilization loop.
                     while lag >= fixed_time_step {
                         let time_step;
@@ -0,0 +1,694 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Gamepad input support. See [`GamepadManager`] docs for more info and usage examples.
+//!
+//! The support is optional and available only with `gamepad` feature of the engine. On Linux, the
+//! feature requires libudev development files (for example, `libudev-dev` on Debian/Ubuntu or
+//! `systemd-devel` on Fedora) and pkg-config.
+
+use crate::{
+    core::{
+        algebra::Vector2,
+        instant::Instant,
+        log::{Log, MessageKind},
+    },
+    gui::{
+        message::{ButtonState, KeyCode, OsEvent},
+        navigation::NavigationDirection,
+        UserInterface,
+    },
+};
+use fxhash::FxHashMap;
+use gilrs::{
+    ev::filter::{axis_dpad_to_button, Filter, Jitter},
+    ff::{BaseEffect, BaseEffectType, EffectBuilder, Repeat, Replay, Ticks},
+    Axis, Button, EventType, Gilrs,
+};
+use std::{
+    error::Error,
+    fmt::{Debug, Display, Formatter},
+    time::Duration,
+};
+
+/// Unique identifier of a gamepad. It stays the same for entire lifetime of [`GamepadManager`],
+/// even if the gamepad was disconnected and then connected again.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GamepadId(pub usize);
+
+impl Display for GamepadId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// A button on a gamepad. Buttons are named by their position on a standard gamepad layout, so,
+/// for example, [`GamepadButton::South`] is `A` on Xbox controllers and `Cross` on PlayStation
+/// controllers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// The bottom button of the action pad.
+    South,
+    /// The right button of the action pad.
+    East,
+    /// The top button of the action pad.
+    North,
+    /// The left button of the action pad.
+    West,
+    /// Additional action button, that can be found on some gamepads.
+    C,
+    /// Additional action button, that can be found on some gamepads.
+    Z,
+    /// Left bumper.
+    LeftTrigger,
+    /// Left trigger.
+    LeftTrigger2,
+    /// Right bumper.
+    RightTrigger,
+    /// Right trigger.
+    RightTrigger2,
+    /// Select (or Back, Share, etc.) button.
+    Select,
+    /// Start (or Options, Menu, etc.) button.
+    Start,
+    /// Vendor-specific button (Xbox, PS, Home, etc.).
+    Mode,
+    /// Left stick click.
+    LeftThumb,
+    /// Right stick click.
+    RightThumb,
+    /// Up button of the D-pad.
+    DPadUp,
+    /// Down button of the D-pad.
+    DPadDown,
+    /// Left button of the D-pad.
+    DPadLeft,
+    /// Right button of the D-pad.
+    DPadRight,
+    /// A button that could not be mapped to any of the standard buttons.
+    Unknown,
+}
+
+impl From<Button> for GamepadButton {
+    fn from(button: Button) -> Self {
+        match button {
+            Button::South => Self::South,
+            Button::East => Self::East,
+            Button::North => Self::North,
+            Button::West => Self::West,
+            Button::C => Self::C,
+            Button::Z => Self::Z,
+            Button::LeftTrigger => Self::LeftTrigger,
+            Button::LeftTrigger2 => Self::LeftTrigger2,
+            Button::RightTrigger => Self::RightTrigger,
+            Button::RightTrigger2 => Self::RightTrigger2,
+            Button::Select => Self::Select,
+            Button::Start => Self::Start,
+            Button::Mode => Self::Mode,
+            Button::LeftThumb => Self::LeftThumb,
+            Button::RightThumb => Self::RightThumb,
+            Button::DPadUp => Self::DPadUp,
+            Button::DPadDown => Self::DPadDown,
+            Button::DPadLeft => Self::DPadLeft,
+            Button::DPadRight => Self::DPadRight,
+            Button::Unknown => Self::Unknown,
+        }
+    }
+}
+
+/// An axis on a gamepad. Values of stick axes are in `[-1.0; 1.0]` range, where positive values of
+/// Y axes means "up".
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    /// Horizontal axis of the left stick.
+    LeftStickX,
+    /// Vertical axis of the left stick.
+    LeftStickY,
+    /// Additional axis of the left side of a gamepad (usually an analog trigger).
+    LeftZ,
+    /// Horizontal axis of the right stick.
+    RightStickX,
+    /// Vertical axis of the right stick.
+    RightStickY,
+    /// Additional axis of the right side of a gamepad (usually an analog trigger).
+    RightZ,
+    /// Horizontal axis of the D-pad.
+    DPadX,
+    /// Vertical axis of the D-pad.
+    DPadY,
+    /// An axis that could not be mapped to any of the standard axes.
+    Unknown,
+}
+
+impl From<Axis> for GamepadAxis {
+    fn from(axis: Axis) -> Self {
+        match axis {
+            Axis::LeftStickX => Self::LeftStickX,
+            Axis::LeftStickY => Self::LeftStickY,
+            Axis::LeftZ => Self::LeftZ,
+            Axis::RightStickX => Self::RightStickX,
+            Axis::RightStickY => Self::RightStickY,
+            Axis::RightZ => Self::RightZ,
+            Axis::DPadX => Self::DPadX,
+            Axis::DPadY => Self::DPadY,
+            Axis::Unknown => Self::Unknown,
+        }
+    }
+}
+
+/// An event, that is produced by a gamepad.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GamepadEvent {
+    /// A gamepad was connected.
+    Connected(GamepadId),
+    /// A gamepad was disconnected. Its state is kept in [`GamepadManager`], but it won't produce
+    /// any new events until it is connected again.
+    Disconnected(GamepadId),
+    /// A button was pressed.
+    ButtonPressed {
+        /// An id of the gamepad.
+        gamepad: GamepadId,
+        /// The button that was pressed.
+        button: GamepadButton,
+    },
+    /// A button was released.
+    ButtonReleased {
+        /// An id of the gamepad.
+        gamepad: GamepadId,
+        /// The button that was released.
+        button: GamepadButton,
+    },
+    /// A value of an analog button (for example - a trigger) was changed.
+    ButtonChanged {
+        /// An id of the gamepad.
+        gamepad: GamepadId,
+        /// The button which value was changed.
+        button: GamepadButton,
+        /// New value of the button in `[0.0; 1.0]` range.
+        value: f32,
+    },
+    /// A value of an axis was changed. The value has dead zone applied, see
+    /// [`GamepadManager::set_dead_zone`] for more info.
+    AxisChanged {
+        /// An id of the gamepad.
+        gamepad: GamepadId,
+        /// The axis which value was changed.
+        axis: GamepadAxis,
+        /// New value of the axis in `[-1.0; 1.0]` range.
+        value: f32,
+    },
+}
+
+/// An error, that may occur when trying to play a rumble effect.
+#[derive(Debug)]
+pub enum GamepadError {
+    /// Gamepad support is not available on the current platform.
+    Unavailable,
+    /// There is no connected gamepad with the given id.
+    NoSuchGamepad(GamepadId),
+    /// The gamepad does not support force feedback.
+    ForceFeedbackNotSupported(GamepadId),
+    /// Underlying force feedback error.
+    ForceFeedback(gilrs::ff::Error),
+}
+
+impl Display for GamepadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GamepadError::Unavailable => {
+                write!(f, "Gamepad support is not available on this platform.")
+            }
+            GamepadError::NoSuchGamepad(id) => {
+                write!(f, "There is no connected gamepad with id {id}.")
+            }
+            GamepadError::ForceFeedbackNotSupported(id) => {
+                write!(f, "Gamepad {id} does not support force feedback.")
+            }
+            GamepadError::ForceFeedback(v) => Display::fmt(v, f),
+        }
+    }
+}
+
+impl Error for GamepadError {}
+
+impl From<gilrs::ff::Error> for GamepadError {
+    fn from(error: gilrs::ff::Error) -> Self {
+        Self::ForceFeedback(error)
+    }
+}
+
+/// State of a single gamepad.
+#[derive(Default, Debug)]
+pub struct Gamepad {
+    name: String,
+    connected: bool,
+    supports_rumble: bool,
+    buttons: FxHashMap<GamepadButton, f32>,
+    axes: FxHashMap<GamepadAxis, f32>,
+}
+
+impl Gamepad {
+    /// Returns a name of the gamepad, reported by its driver.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the gamepad is connected, `false` - otherwise.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Returns `true` if the gamepad supports rumble, `false` - otherwise.
+    pub fn supports_rumble(&self) -> bool {
+        self.supports_rumble
+    }
+
+    /// Returns `true` if the given button is pressed, `false` - otherwise.
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.button_value(button) > 0.0
+    }
+
+    /// Returns a value of the given button in `[0.0; 1.0]` range. Digital buttons have either
+    /// `0.0` or `1.0` values, analog buttons (triggers) could have any value in the range.
+    pub fn button_value(&self, button: GamepadButton) -> f32 {
+        self.buttons.get(&button).cloned().unwrap_or_default()
+    }
+
+    /// Returns a value of the given axis in `[-1.0; 1.0]` range. The value has dead zone applied.
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes.get(&axis).cloned().unwrap_or_default()
+    }
+
+    /// Returns a position of the left stick.
+    pub fn left_stick(&self) -> Vector2<f32> {
+        Vector2::new(
+            self.axis(GamepadAxis::LeftStickX),
+            self.axis(GamepadAxis::LeftStickY),
+        )
+    }
+
+    /// Returns a position of the right stick.
+    pub fn right_stick(&self) -> Vector2<f32> {
+        Vector2::new(
+            self.axis(GamepadAxis::RightStickX),
+            self.axis(GamepadAxis::RightStickY),
+        )
+    }
+}
+
+struct RumbleEffect {
+    // Keeps the effect alive, dropping it stops the effect.
+    _effect: gilrs::ff::Effect,
+    end_time: Instant,
+}
+
+/// Gamepad manager tracks connected gamepads, their buttons and axes and produces [`GamepadEvent`]s.
+/// The engine owns an instance of the manager, it polls the events every frame and passes them to
+/// [`crate::plugin::Plugin::on_gamepad_event`]. The manager itself is available in plugins via
+/// [`crate::plugin::PluginContext::gamepads`]:
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{reflect::prelude::*, visitor::prelude::*},
+/// #     engine::gamepad::{GamepadAxis, GamepadButton, GamepadEvent},
+/// #     plugin::{Plugin, PluginContext},
+/// # };
+/// # use std::time::Duration;
+/// #[derive(Visit, Reflect, Debug, Default)]
+/// struct MyGame;
+///
+/// impl Plugin for MyGame {
+///     fn update(&mut self, context: &mut PluginContext) {
+///         // Polling the state.
+///         for (_, gamepad) in context.gamepads.gamepads() {
+///             let movement = gamepad.left_stick();
+///             let is_jumping = gamepad.is_pressed(GamepadButton::South);
+///         }
+///     }
+///
+///     fn on_gamepad_event(&mut self, event: &GamepadEvent, context: PluginContext) {
+///         // Reacting to the events.
+///         if let GamepadEvent::ButtonPressed { gamepad, button } = event {
+///             if *button == GamepadButton::West {
+///                 let _ = context
+///                     .gamepads
+///                     .rumble(*gamepad, 1.0, 0.5, Duration::from_millis(200));
+///             }
+///         }
+///     }
+/// }
+/// ```
+///
+/// ## Dead zones
+///
+/// Analog sticks are rarely perfectly centered, so all axis values are processed using a dead zone
+/// (see [`Self::set_dead_zone`]). Values inside the dead zone are reported as zero, values outside
+/// are rescaled to cover the entire `[-1.0; 1.0]` range.
+///
+/// ## User interface
+///
+/// By default, the manager drives keyboard navigation of user interfaces: D-pad buttons move the
+/// focus using [`UserInterface::navigate`] (see [`crate::gui::navigation::NavigationLayer`] docs),
+/// [`GamepadButton::South`] acts as `Enter` key and [`GamepadButton::East`] acts as `Escape` key.
+/// This allows to use the same menus for keyboard and controllers. The behaviour can be disabled
+/// using [`Self::set_ui_navigation_enabled`].
+pub struct GamepadManager {
+    gilrs: Option<Gilrs>,
+    jitter: Jitter,
+    gamepads: FxHashMap<GamepadId, Gamepad>,
+    dead_zone: f32,
+    ui_navigation: bool,
+    rumble_effects: Vec<RumbleEffect>,
+}
+
+impl Debug for GamepadManager {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GamepadManager")
+            .field("gamepads", &self.gamepads)
+            .field("dead_zone", &self.dead_zone)
+            .field("ui_navigation", &self.ui_navigation)
+            .finish()
+    }
+}
+
+impl Default for GamepadManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GamepadManager {
+    /// Default dead zone of gamepad axes.
+    pub const DEFAULT_DEAD_ZONE: f32 = 0.1;
+
+    /// Creates new gamepad manager. If gamepads are not supported on the current platform, the
+    /// manager will still be created, but it won't produce any events.
+    pub fn new() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(gilrs::Error::NotImplemented(gilrs)) => {
+                Log::warn("Gamepads are not supported on this platform.");
+                Some(gilrs)
+            }
+            Err(err) => {
+                Log::writeln(
+                    MessageKind::Error,
+                    format!("Unable to initialize gamepad support. Reason: {err}"),
+                );
+                None
+            }
+        };
+
+        let mut manager = Self {
+            gilrs,
+            jitter: Jitter::new(),
+            gamepads: Default::default(),
+            dead_zone: Self::DEFAULT_DEAD_ZONE,
+            ui_navigation: true,
+            rumble_effects: Default::default(),
+        };
+
+        // Register gamepads that were connected before the manager was created.
+        if let Some(gilrs) = manager.gilrs.as_ref() {
+            for (id, gamepad) in gilrs.gamepads() {
+                manager.gamepads.insert(
+                    GamepadId(id.into()),
+                    Gamepad {
+                        name: gamepad.name().to_string(),
+                        connected: gamepad.is_connected(),
+                        supports_rumble: gamepad.is_ff_supported(),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
+        manager
+    }
+
+    /// Returns `true` if gamepad support is available, `false` - otherwise.
+    pub fn is_available(&self) -> bool {
+        self.gilrs.is_some()
+    }
+
+    /// Returns a reference to a gamepad with the given id.
+    pub fn gamepad(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.gamepads.get(&id)
+    }
+
+    /// Returns an iterator over all connected gamepads.
+    pub fn gamepads(&self) -> impl Iterator<Item = (GamepadId, &Gamepad)> {
+        self.gamepads
+            .iter()
+            .filter(|(_, gamepad)| gamepad.connected)
+            .map(|(id, gamepad)| (*id, gamepad))
+    }
+
+    /// Sets new dead zone for every axis of every gamepad. The value must be in `[0.0; 1.0)` range,
+    /// default value is [`Self::DEFAULT_DEAD_ZONE`].
+    pub fn set_dead_zone(&mut self, dead_zone: f32) {
+        self.dead_zone = dead_zone.clamp(0.0, 0.99);
+    }
+
+    /// Returns current dead zone of gamepad axes.
+    pub fn dead_zone(&self) -> f32 {
+        self.dead_zone
+    }
+
+    /// Enables or disables user interface navigation using gamepads. See [`Self`] docs for more info.
+    pub fn set_ui_navigation_enabled(&mut self, enabled: bool) {
+        self.ui_navigation = enabled;
+    }
+
+    /// Returns `true` if user interface navigation using gamepads is enabled, `false` - otherwise.
+    pub fn is_ui_navigation_enabled(&self) -> bool {
+        self.ui_navigation
+    }
+
+    /// Plays a rumble effect on the given gamepad. `strong` and `weak` are magnitudes (in `[0.0; 1.0]`
+    /// range) of the low-frequency and high-frequency motors respectively.
+    pub fn rumble(
+        &mut self,
+        id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), GamepadError> {
+        let gilrs = self.gilrs.as_mut().ok_or(GamepadError::Unavailable)?;
+
+        let (gilrs_id, gamepad) = gilrs
+            .gamepads()
+            .find(|(gilrs_id, _)| usize::from(*gilrs_id) == id.0)
+            .ok_or(GamepadError::NoSuchGamepad(id))?;
+
+        if !gamepad.is_ff_supported() {
+            return Err(GamepadError::ForceFeedbackNotSupported(id));
+        }
+
+        let ticks = Ticks::from_ms(duration.as_millis().min(u32::MAX as u128) as u32);
+        let scheduling = Replay {
+            play_for: ticks,
+            ..Default::default()
+        };
+        let magnitude = |value: f32| (value.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong {
+                    magnitude: magnitude(strong),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Weak {
+                    magnitude: magnitude(weak),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .repeat(Repeat::For(ticks))
+            .gamepads(&[gilrs_id])
+            .finish(gilrs)?;
+
+        effect.play()?;
+
+        self.rumble_effects.push(RumbleEffect {
+            _effect: effect,
+            end_time: Instant::now() + duration,
+        });
+
+        Ok(())
+    }
+
+    /// Polls all pending events of gamepads, updates their state and returns the events. This method
+    /// is called by the engine every frame, there's no need to call it manually unless you're
+    /// running your own game loop.
+    pub fn poll(&mut self) -> Vec<GamepadEvent> {
+        let now = Instant::now();
+        self.rumble_effects.retain(|effect| effect.end_time > now);
+
+        let mut events = Vec::new();
+
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return events;
+        };
+
+        while let Some(event) = gilrs.next_event() {
+            let Some(event) = Some(event)
+                .filter_ev(&axis_dpad_to_button, gilrs)
+                .filter_ev(&self.jitter, gilrs)
+            else {
+                continue;
+            };
+
+            let id = GamepadId(event.id.into());
+
+            match event.event {
+                EventType::Connected => {
+                    let gamepad = gilrs.gamepad(event.id);
+                    let state = self.gamepads.entry(id).or_default();
+                    state.name = gamepad.name().to_string();
+                    state.connected = true;
+                    state.supports_rumble = gamepad.is_ff_supported();
+                    events.push(GamepadEvent::Connected(id));
+                }
+                EventType::Disconnected => {
+                    if let Some(state) = self.gamepads.get_mut(&id) {
+                        state.connected = false;
+                        state.buttons.clear();
+                        state.axes.clear();
+                    }
+                    events.push(GamepadEvent::Disconnected(id));
+                }
+                EventType::ButtonPressed(button, _) => {
+                    let button = GamepadButton::from(button);
+                    self.gamepads
+                        .entry(id)
+                        .or_default()
+                        .buttons
+                        .insert(button, 1.0);
+                    events.push(GamepadEvent::ButtonPressed {
+                        gamepad: id,
+                        button,
+                    });
+                }
+                EventType::ButtonReleased(button, _) => {
+                    let button = GamepadButton::from(button);
+                    self.gamepads.entry(id).or_default().buttons.remove(&button);
+                    events.push(GamepadEvent::ButtonReleased {
+                        gamepad: id,
+                        button,
+                    });
+                }
+                EventType::ButtonChanged(button, value, _) => {
+                    let button = GamepadButton::from(button);
+                    let buttons = &mut self.gamepads.entry(id).or_default().buttons;
+                    if value > 0.0 {
+                        buttons.insert(button, value);
+                    } else {
+                        buttons.remove(&button);
+                    }
+                    events.push(GamepadEvent::ButtonChanged {
+                        gamepad: id,
+                        button,
+                        value,
+                    });
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    let axis = GamepadAxis::from(axis);
+                    let value = apply_dead_zone(value, self.dead_zone);
+                    let axes = &mut self.gamepads.entry(id).or_default().axes;
+                    // Do not spam the events when an axis stays inside the dead zone.
+                    if axes.get(&axis).cloned().unwrap_or_default() != value {
+                        axes.insert(axis, value);
+                        events.push(GamepadEvent::AxisChanged {
+                            gamepad: id,
+                            axis,
+                            value,
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        events
+    }
+}
+
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= dead_zone {
+        0.0
+    } else {
+        // Rescale the value so it still covers the entire range.
+        value.signum() * ((magnitude - dead_zone) / (1.0 - dead_zone)).min(1.0)
+    }
+}
+
+/// Translates the given gamepad event into user interface actions. See [`GamepadManager`] docs for
+/// more info.
+pub fn process_ui_navigation(ui: &mut UserInterface, event: &GamepadEvent) {
+    let (button, state) = match event {
+        GamepadEvent::ButtonPressed { button, .. } => (*button, ButtonState::Pressed),
+        GamepadEvent::ButtonReleased { button, .. } => (*button, ButtonState::Released),
+        _ => return,
+    };
+
+    let key = match button {
+        GamepadButton::DPadDown | GamepadButton::DPadRight => {
+            if state == ButtonState::Pressed {
+                ui.navigate(NavigationDirection::Next);
+            }
+            return;
+        }
+        GamepadButton::DPadUp | GamepadButton::DPadLeft => {
+            if state == ButtonState::Pressed {
+                ui.navigate(NavigationDirection::Previous);
+            }
+            return;
+        }
+        GamepadButton::South => KeyCode::Enter,
+        GamepadButton::East => KeyCode::Escape,
+        _ => return,
+    };
+
+    ui.process_os_event(&OsEvent::KeyboardInput {
+        button: key,
+        state,
+        text: Default::default(),
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::apply_dead_zone;
+
+    #[test]
+    fn test_dead_zone() {
+        assert_eq!(apply_dead_zone(0.05, 0.1), 0.0);
+        assert_eq!(apply_dead_zone(-0.1, 0.1), 0.0);
+        assert_eq!(apply_dead_zone(1.0, 0.1), 1.0);
+        assert_eq!(apply_dead_zone(-1.0, 0.1), -1.0);
+        assert!((apply_dead_zone(0.55, 0.1) - 0.5).abs() < 1.0e-6);
+        assert!((apply_dead_zone(-0.55, 0.1) + 0.5).abs() < 1.0e-6);
+    }
+}
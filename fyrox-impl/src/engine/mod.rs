@@ -25,10 +25,14 @@
 
 pub mod error;
 pub mod executor;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
 pub mod task;
 
 mod hotreload;
 
+#[cfg(feature = "gamepad")]
+use crate::engine::gamepad::GamepadManager;
 use crate::resource::texture::{
     CompressionOptions, TextureImportOptions, TextureMinificationFilter, TextureResource,
     TextureResourceExtension,
@@ -473,6 +477,11 @@ pub struct Engine {
     /// Task pool for asynchronous task management.
     pub task_pool: TaskPoolHandler,
 
+    #[cfg(feature = "gamepad")]
+    /// Gamepad manager, that tracks connected gamepads and their state. See [`GamepadManager`] docs
+    /// for more info.
+    pub gamepads: GamepadManager,
+
     performance_statistics: PerformanceStatistics,
 
     model_events_receiver: Receiver<ResourceEvent>,
@@ -1225,7 +1234,7 @@ pub(crate) fn initialize_resource_manager_loaders(
 ) {
     let model_loader = ModelLoader {
         resource_manager: resource_manager.clone(),
-        serialization_context,
+        serialization_context: serialization_context.clone(),
         default_import_options: Default::default(),
     };
 
@@ -1233,6 +1242,7 @@ pub(crate) fn initialize_resource_manager_loaders(
 
     let gltf_loader = super::resource::gltf::GltfLoader {
         resource_manager: resource_manager.clone(),
+        serialization_context,
         default_import_options: Default::default(),
     };
     state.loaders.set(gltf_loader);
@@ -1401,6 +1411,8 @@ impl Engine {
             plugins_enabled: false,
             elapsed_time: 0.0,
             task_pool: TaskPoolHandler::new(task_pool),
+            #[cfg(feature = "gamepad")]
+            gamepads: GamepadManager::new(),
         })
     }
 
@@ -1631,6 +1643,8 @@ impl Engine {
                             async_scene_loader: &mut self.async_scene_loader,
                             window_target: Some(window_target),
                             task_pool: &mut self.task_pool,
+                            #[cfg(feature = "gamepad")]
+                            gamepads: &mut self.gamepads,
                         };
 
                         for plugin in self.plugins.iter_mut() {
@@ -1664,6 +1678,8 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    #[cfg(feature = "gamepad")]
+                    gamepads: &mut self.gamepads,
                 };
 
                 match loading_result.result {
@@ -1913,6 +1929,8 @@ impl Engine {
                         async_scene_loader: &mut self.async_scene_loader,
                         window_target: Some(window_target),
                         task_pool: &mut self.task_pool,
+                        #[cfg(feature = "gamepad")]
+                        gamepads: &mut self.gamepads,
                     },
                 )
             } else if let Some(node_task_handler) = self.task_pool.pop_node_task_handler(result.id)
@@ -2007,6 +2025,8 @@ impl Engine {
                 async_scene_loader: &mut self.async_scene_loader,
                 window_target: Some(window_target),
                 task_pool: &mut self.task_pool,
+                #[cfg(feature = "gamepad")]
+                gamepads: &mut self.gamepads,
             };
 
             for plugin in self.plugins.iter_mut() {
@@ -2040,6 +2060,8 @@ impl Engine {
                         async_scene_loader: &mut self.async_scene_loader,
                         window_target: Some(window_target),
                         task_pool: &mut self.task_pool,
+                        #[cfg(feature = "gamepad")]
+                        gamepads: &mut self.gamepads,
                     };
 
                     for plugin in self.plugins.iter_mut() {
@@ -2076,6 +2098,8 @@ impl Engine {
                 async_scene_loader: &mut self.async_scene_loader,
                 window_target: Some(window_target),
                 task_pool: &mut self.task_pool,
+                #[cfg(feature = "gamepad")]
+                gamepads: &mut self.gamepads,
             };
 
             for plugin in self.plugins.iter_mut() {
@@ -2112,12 +2136,58 @@ impl Engine {
                         async_scene_loader: &mut self.async_scene_loader,
                         window_target: Some(window_target),
                         task_pool: &mut self.task_pool,
+                        #[cfg(feature = "gamepad")]
+                        gamepads: &mut self.gamepads,
                     },
                 );
             }
         }
     }
 
+    #[cfg(feature = "gamepad")]
+    /// Polls events of gamepads and passes them to user interfaces (see [`GamepadManager`] docs
+    /// for more info) and plugins.
+    pub(crate) fn handle_gamepad_events(
+        &mut self,
+        dt: f32,
+        window_target: &EventLoopWindowTarget<()>,
+        lag: &mut f32,
+    ) {
+        for event in self.gamepads.poll() {
+            if self.gamepads.is_ui_navigation_enabled() {
+                for ui in self.user_interfaces.iter_mut() {
+                    gamepad::process_ui_navigation(ui, &event);
+                }
+            }
+
+            if self.plugins_enabled {
+                for plugin in self.plugins.iter_mut() {
+                    plugin.on_gamepad_event(
+                        &event,
+                        PluginContext {
+                            scenes: &mut self.scenes,
+                            resource_manager: &self.resource_manager,
+                            graphics_context: &mut self.graphics_context,
+                            dt,
+                            lag,
+                            user_interfaces: &mut self.user_interfaces,
+                            serialization_context: &self.serialization_context,
+                            widget_constructors: &self.widget_constructors,
+                            performance_statistics: &self.performance_statistics,
+                            elapsed_time: self.elapsed_time,
+                            script_processor: &self.script_processor,
+                            async_scene_loader: &mut self.async_scene_loader,
+                            window_target: Some(window_target),
+                            task_pool: &mut self.task_pool,
+                            #[cfg(feature = "gamepad")]
+                            gamepads: &mut self.gamepads,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
     pub(crate) fn handle_graphics_context_created_by_plugins(
         &mut self,
         dt: f32,
@@ -2141,6 +2211,8 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    #[cfg(feature = "gamepad")]
+                    gamepads: &mut self.gamepads,
                 });
             }
         }
@@ -2169,6 +2241,8 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    #[cfg(feature = "gamepad")]
+                    gamepads: &mut self.gamepads,
                 });
             }
         }
@@ -2197,6 +2271,8 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    #[cfg(feature = "gamepad")]
+                    gamepads: &mut self.gamepads,
                 });
             }
         }
@@ -2327,6 +2403,8 @@ impl Engine {
                             async_scene_loader: &mut self.async_scene_loader,
                             window_target,
                             task_pool: &mut self.task_pool,
+                            #[cfg(feature = "gamepad")]
+                            gamepads: &mut self.gamepads,
                         },
                     );
                 }
@@ -2350,6 +2428,8 @@ impl Engine {
                         async_scene_loader: &mut self.async_scene_loader,
                         window_target,
                         task_pool: &mut self.task_pool,
+                        #[cfg(feature = "gamepad")]
+                        gamepads: &mut self.gamepads,
                     });
                 }
             }
@@ -2655,6 +2735,8 @@ impl Engine {
             async_scene_loader: &mut self.async_scene_loader,
             window_target: Some(window_target),
             task_pool: &mut self.task_pool,
+            #[cfg(feature = "gamepad")]
+            gamepads: &mut self.gamepads,
         });
 
         Log::info(format!("Plugin {plugin_index} was successfully reloaded!"));
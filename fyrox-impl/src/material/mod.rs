@@ -776,6 +776,11 @@ impl Material {
         Self::from_shader(ShaderResource::standard_particle_system())
     }
 
+    /// Creates new instance of standard material for particle systems that are simulated on GPU.
+    pub fn standard_gpu_particle_system() -> Self {
+        Self::from_shader(ShaderResource::standard_gpu_particle_system())
+    }
+
     /// Creates new instance of standard sprite material.
     pub fn standard_sprite() -> Self {
         Self::from_shader(ShaderResource::standard_sprite())
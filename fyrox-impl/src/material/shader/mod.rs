@@ -256,13 +256,15 @@
 //!
 //! Property group. Provided for each rendered surface instance.
 //!
-//! | Name                 | Type       | Description                                 |
-//! |----------------------|------------|---------------------------------------------|
-//! | worldMatrix          | `mat4`     | Local-to-world transformation.              |
-//! | worldViewProjection  | `mat4`     | Local-to-clip-space transform.              |
-//! | blendShapesCount     | `int`      | Total amount of blend shapes.               |
-//! | useSkeletalAnimation | `bool`     | Whether skinned meshes is rendering or not. |
-//! | blendShapesWeights   | `vec4[32]` | Blend shape weights.                        |
+//! | Name                          | Type       | Description                                                     |
+//! |-------------------------------|------------|-----------------------------------------------------------------|
+//! | worldMatrix                   | `mat4`     | Local-to-world transformation.                                  |
+//! | worldViewProjection           | `mat4`     | Local-to-clip-space transform.                                  |
+//! | blendShapesCount              | `int`      | Total amount of blend shapes.                                   |
+//! | useSkeletalAnimation          | `bool`     | Whether skinned meshes is rendering or not.                     |
+//! | blendShapesWeights            | `vec4[32]` | Blend shape weights.                                            |
+//! | unjitteredWorldViewProjection | `mat4`     | Local-to-clip-space transform without camera jitter.            |
+//! | previousWorldViewProjection   | `mat4`     | Unjittered local-to-clip-space transform of the previous frame. |
 //!
 //! ### `fyrox_boneMatrices`
 //!
@@ -310,6 +312,23 @@
 //! | lightsPosition    | `vec3[16]` | World-space light position.                             |
 //! | lightsDirection   | `vec3[16]` | World-space light direction                             |
 //!
+//! ### `fyrox_lights2DBlock`
+//!
+//! Property group. Information about visible 2D light sources (see [`crate::scene::dim2::light`]).
+//! Each light has a polar shadow map of 128 elements, the element with index `i` of the light
+//! `n` is stored in `lightsShadowMap[(n * 128 + i) / 4][(n * 128 + i) % 4]`. Element `i` contains
+//! distance (normalized by the light radius) to the closest occluder in the direction with angle
+//! `(i + 0.5) / 128 * 2 * PI - PI`.
+//!
+//! | Name                 | Type        | Description                                                     |
+//! |----------------------|-------------|-----------------------------------------------------------------|
+//! | lightCount           | `int`       | Total amount of 2D light sources visible on screen.             |
+//! | ambientColor         | `vec4`      | Sum of the colors of all 2D global lights.                      |
+//! | lightsPositionRadius | `vec4[16]`  | World-space position (xy), height (z) and radius (w).           |
+//! | lightsColor          | `vec4[16]`  | Linear color of the light multiplied by its intensity.          |
+//! | lightsParameters     | `vec4[16]`  | Direction (xy), hotspot cone angle cos (z), cone angle cos (w). |
+//! | lightsShadowMap      | `vec4[512]` | Packed polar shadow maps of the lights.                         |
+//!
 //! ### `fyrox_graphicsSettings`
 //!
 //! Property group. Contains graphics options of the renderer.
@@ -318,6 +337,29 @@
 //! |--------|------------|---------------------------------------------------|
 //! | usePom | `bool`     | Whether to use parallax occlusion mapping or not. |
 //!
+//! ### `fyrox_fogData`
+//!
+//! Property group. Contains global fog settings of the scene (see [`crate::scene::FogSettings`]).
+//! Typical usage is to call `S_FogFactor` function from the shared shader library and mix the
+//! final color of a fragment with the fog color:
+//!
+//! ```glsl
+//! float fogFactor = S_FogFactor(fyrox_fogData.mode, fyrox_cameraData.position, worldPosition,
+//!     fyrox_fogData.start, fyrox_fogData.end, fyrox_fogData.density, fyrox_fogData.height,
+//!     fyrox_fogData.heightFalloff) * fyrox_fogData.color.a;
+//! FragColor.rgb = mix(FragColor.rgb, fyrox_fogData.color.rgb, fogFactor);
+//! ```
+//!
+//! | Name          | Type    | Description                                                         |
+//! |---------------|---------|---------------------------------------------------------------------|
+//! | mode          | `int`   | 0 - no fog, 1 - linear, 2 - exponential, 3 - exponential squared.   |
+//! | color         | `vec4`  | Linear color of the fog, alpha defines maximum fog amount.          |
+//! | start         | `float` | Distance from the camera at which the fog starts.                   |
+//! | end           | `float` | Distance at which the fog reaches its maximum (linear mode only).   |
+//! | density       | `float` | Fog density (exponential modes only).                               |
+//! | height        | `float` | World-space height at which the fog has its nominal density.        |
+//! | heightFalloff | `float` | How fast the fog density decreases with height. 0 - no height fog.  |
+//!
 //! ### `fyrox_sceneDepth`
 //!
 //! Texture. Contains depth values of scene. Available **only** after opaque geometry is
@@ -495,6 +537,13 @@ pub const STANDARD_PARTICLE_SYSTEM_SHADER_NAME: &str = "StandardParticleSystem";
 pub const STANDARD_PARTICLE_SYSTEM_SHADER_SRC: &str =
     include_str!("standard/standard_particle_system.shader");
 
+/// A name of the standard shader for particle systems that are simulated on GPU.
+pub const STANDARD_GPU_PARTICLE_SYSTEM_SHADER_NAME: &str = "StandardGpuParticleSystem";
+
+/// A source code of the standard shader for particle systems that are simulated on GPU.
+pub const STANDARD_GPU_PARTICLE_SYSTEM_SHADER_SRC: &str =
+    include_str!("standard/standard_gpu_particle_system.shader");
+
 /// A source code of the standard sprite shader.
 pub const STANDARD_SPRITE_SHADER_SRC: &str = include_str!("standard/standard_sprite.shader");
 
@@ -517,20 +566,22 @@ pub const STANDARD_SPRITE_SHADER_NAME: &str = "StandardSprite";
 pub const STANDARD_TERRAIN_SHADER_SRC: &str = include_str!("standard/terrain.shader");
 
 /// A list of names of standard shaders.
-pub const STANDARD_SHADER_NAMES: [&str; 6] = [
+pub const STANDARD_SHADER_NAMES: [&str; 7] = [
     STANDARD_SHADER_NAME,
     STANDARD_2D_SHADER_NAME,
     STANDARD_PARTICLE_SYSTEM_SHADER_NAME,
+    STANDARD_GPU_PARTICLE_SYSTEM_SHADER_NAME,
     STANDARD_SPRITE_SHADER_NAME,
     STANDARD_TWOSIDES_SHADER_NAME,
     STANDARD_TERRAIN_SHADER_NAME,
 ];
 
 /// A list of source code of standard shaders.
-pub const STANDARD_SHADER_SOURCES: [&str; 6] = [
+pub const STANDARD_SHADER_SOURCES: [&str; 7] = [
     STANDARD_SHADER_SRC,
     STANDARD_2D_SHADER_SRC,
     STANDARD_PARTICLE_SYSTEM_SHADER_SRC,
+    STANDARD_GPU_PARTICLE_SYSTEM_SHADER_SRC,
     STANDARD_SPRITE_SHADER_SRC,
     STANDARD_TWOSIDES_SHADER_SRC,
     STANDARD_TERRAIN_SHADER_SRC,
@@ -592,6 +643,13 @@ impl ShaderDefinition {
     /// block.
     pub const MAX_LIGHTS: usize = 16;
 
+    /// Maximum amount of simultaneous 2D light sources that can be passed into a 2D lights data
+    /// block.
+    pub const MAX_LIGHTS_2D: usize = 16;
+
+    /// Amount of elements in a polar shadow map of a 2D light source.
+    pub const LIGHT_2D_SHADOW_MAP_SIZE: usize = 128;
+
     /// Maximum amount of bone matrices per shader.
     pub const MAX_BONE_MATRICES: usize = 255;
 
@@ -644,6 +702,18 @@ impl ShaderDefinition {
                     properties.clear();
                     properties.extend([ShaderProperty::new("usePOM", Bool(false))]);
                 }
+                "fyrox_fogData" => {
+                    properties.clear();
+                    properties.extend([
+                        ShaderProperty::new("mode", Int(0)),
+                        ShaderProperty::new("color", Vector4(Default::default())),
+                        ShaderProperty::new("start", Float(0.0)),
+                        ShaderProperty::new("end", Float(0.0)),
+                        ShaderProperty::new("density", Float(0.0)),
+                        ShaderProperty::new("height", Float(0.0)),
+                        ShaderProperty::new("heightFalloff", Float(0.0)),
+                    ]);
+                }
                 "fyrox_lightsBlock" => {
                     properties.clear();
                     properties.extend([
@@ -678,6 +748,41 @@ impl ShaderDefinition {
                         ),
                     ])
                 }
+                "fyrox_lights2DBlock" => {
+                    properties.clear();
+                    properties.extend([
+                        ShaderProperty::new("lightCount", Int(0)),
+                        ShaderProperty::new("ambientColor", Vector4(Default::default())),
+                        ShaderProperty::new(
+                            "lightsPositionRadius",
+                            Vector4Array {
+                                value: Default::default(),
+                                max_len: Self::MAX_LIGHTS_2D,
+                            },
+                        ),
+                        ShaderProperty::new(
+                            "lightsColor",
+                            Vector4Array {
+                                value: Default::default(),
+                                max_len: Self::MAX_LIGHTS_2D,
+                            },
+                        ),
+                        ShaderProperty::new(
+                            "lightsParameters",
+                            Vector4Array {
+                                value: Default::default(),
+                                max_len: Self::MAX_LIGHTS_2D,
+                            },
+                        ),
+                        ShaderProperty::new(
+                            "lightsShadowMap",
+                            Vector4Array {
+                                value: Default::default(),
+                                max_len: Self::MAX_LIGHTS_2D * Self::LIGHT_2D_SHADOW_MAP_SIZE / 4,
+                            },
+                        ),
+                    ])
+                }
                 "fyrox_instanceData" => {
                     properties.clear();
                     properties.extend([
@@ -695,6 +800,14 @@ impl ShaderDefinition {
                                 max_len: Self::MAX_BLEND_SHAPE_WEIGHT_GROUPS,
                             },
                         ),
+                        ShaderProperty::new(
+                            "unjitteredWorldViewProjection",
+                            Matrix4(algebra::Matrix4::identity()),
+                        ),
+                        ShaderProperty::new(
+                            "previousWorldViewProjection",
+                            Matrix4(algebra::Matrix4::identity()),
+                        ),
                     ]);
                 }
                 "fyrox_boneMatrices" => {
@@ -823,6 +936,9 @@ pub trait ShaderResourceExtension: Sized {
     /// Returns an instance of standard particle system shader.
     fn standard_particle_system() -> Self;
 
+    /// Returns an instance of standard shader for particle systems that are simulated on GPU.
+    fn standard_gpu_particle_system() -> Self;
+
     /// Returns an instance of standard sprite shader.
     fn standard_sprite() -> Self;
 
@@ -836,7 +952,7 @@ pub trait ShaderResourceExtension: Sized {
     fn standard_twosides() -> Self;
 
     /// Returns a list of standard shader.
-    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 7];
+    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 8];
 }
 
 impl ShaderResourceExtension for ShaderResource {
@@ -856,6 +972,10 @@ impl ShaderResourceExtension for ShaderResource {
         STANDARD_PARTICLE_SYSTEM.resource()
     }
 
+    fn standard_gpu_particle_system() -> Self {
+        STANDARD_GPU_PARTICLE_SYSTEM.resource()
+    }
+
     fn standard_sprite() -> Self {
         STANDARD_SPRITE.resource()
     }
@@ -872,11 +992,12 @@ impl ShaderResourceExtension for ShaderResource {
         STANDARD_TWOSIDES.resource()
     }
 
-    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 7] {
+    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 8] {
         [
             &STANDARD,
             &STANDARD_2D,
             &STANDARD_PARTICLE_SYSTEM,
+            &STANDARD_GPU_PARTICLE_SYSTEM,
             &STANDARD_SPRITE,
             &STANDARD_TERRAIN,
             &STANDARD_TWOSIDES,
@@ -907,6 +1028,13 @@ lazy_static! {
             Shader::from_string_bytes(data).unwrap(),
         )
     );
+    static ref STANDARD_GPU_PARTICLE_SYSTEM: BuiltInResource<Shader> = BuiltInResource::new(
+        embedded_data_source!("standard/standard_gpu_particle_system.shader"),
+        |data| ShaderResource::new_ok(
+            STANDARD_GPU_PARTICLE_SYSTEM_SHADER_NAME.into(),
+            Shader::from_string_bytes(data).unwrap(),
+        )
+    );
     static ref STANDARD_SPRITE: BuiltInResource<Shader> = BuiltInResource::new(
         embedded_data_source!("standard/standard_sprite.shader"),
         |data| ShaderResource::new_ok(
@@ -1008,6 +1136,7 @@ mod test {
                 vertex_shader: "<CODE>".to_string(),
                 fragment_shader: "<CODE>".to_string(),
             }],
+            disabled_passes: vec![],
         };
 
         assert_eq!(data.definition, reference_definition);
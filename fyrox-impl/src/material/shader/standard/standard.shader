@@ -112,6 +112,13 @@
             ]),
             binding: 5
         ),
+        (
+            name: "fyrox_fogData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 6
+        ),
     ],
 
     passes: [
@@ -153,6 +160,8 @@
                 out vec3 tangent;
                 out vec3 binormal;
                 out vec2 secondTexCoord;
+                out vec4 currentClipPosition;
+                out vec4 previousClipPosition;
 
                 void main()
                 {
@@ -215,6 +224,8 @@
                     secondTexCoord = vertexSecondTexCoord;
 
                     gl_Position = fyrox_instanceData.worldViewProjection * localPosition;
+                    currentClipPosition = fyrox_instanceData.unjitteredWorldViewProjection * localPosition;
+                    previousClipPosition = fyrox_instanceData.previousWorldViewProjection * localPosition;
                 }
                 "#,
             fragment_shader:
@@ -224,6 +235,7 @@
                 layout(location = 2) out vec4 outAmbient;
                 layout(location = 3) out vec4 outMaterial;
                 layout(location = 4) out uint outDecalMask;
+                layout(location = 5) out vec2 outVelocity;
 
                 in vec3 position;
                 in vec3 normal;
@@ -231,6 +243,8 @@
                 in vec3 tangent;
                 in vec3 binormal;
                 in vec2 secondTexCoord;
+                in vec4 currentClipPosition;
+                in vec4 previousClipPosition;
 
                 void main()
                 {
@@ -271,6 +285,8 @@
                     outAmbient.a = 1.0;
 
                     outDecalMask = properties.layerIndex;
+
+                    outVelocity = S_ComputeVelocity(currentClipPosition, previousClipPosition);
                 }
                 "#,
         ),
@@ -351,6 +367,7 @@
                         localPosition = inputPosition;
                     }
                     gl_Position = fyrox_instanceData.worldViewProjection * localPosition;
+                    position = (fyrox_instanceData.worldMatrix * localPosition).xyz;
                     texCoord = vertexTexCoord;
                 }
                "#,
@@ -359,11 +376,26 @@
                r#"
                 out vec4 FragColor;
 
+                in vec3 position;
                 in vec2 texCoord;
 
                 void main()
                 {
                     FragColor = properties.diffuseColor * texture(diffuseTexture, texCoord);
+                    FragColor.rgb = mix(
+                        FragColor.rgb,
+                        fyrox_fogData.color.rgb,
+                        S_FogFactor(
+                            fyrox_fogData.mode,
+                            fyrox_cameraData.position,
+                            position,
+                            fyrox_fogData.start,
+                            fyrox_fogData.end,
+                            fyrox_fogData.density,
+                            fyrox_fogData.height,
+                            fyrox_fogData.heightFalloff
+                        ) * fyrox_fogData.color.a
+                    );
                 }
                "#,
         ),
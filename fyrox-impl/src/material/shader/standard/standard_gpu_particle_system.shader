@@ -0,0 +1,220 @@
+(
+    name: "StandardGpuParticleSystemShader",
+
+    resources: [
+        (
+            name: "diffuseTexture",
+            kind: Texture(kind: Sampler2D, fallback: White),
+            binding: 0
+        ),
+        (
+            name: "fyrox_sceneDepth",
+            kind: Texture(kind: Sampler2D, fallback: White),
+            binding: 1
+        ),
+        (
+            name: "particleState",
+            kind: Texture(kind: Sampler2D, fallback: Black),
+            binding: 2
+        ),
+        (
+            name: "properties",
+            kind: PropertyGroup([
+                (
+                    name: "softBoundarySharpnessFactor",
+                    kind: Float(100.0),
+                ),
+                (
+                    name: "useLighting",
+                    kind: Bool(false),
+                ),
+                (
+                    name: "alphaFactor",
+                    kind: Float(1.0),
+                ),
+            ]),
+            binding: 0
+        ),
+        (
+            name: "fyrox_instanceData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 1
+        ),
+        (
+            name: "fyrox_cameraData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 2
+        ),
+        (
+            name: "fyrox_lightsBlock",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 3
+        ),
+        (
+            name: "fyrox_lightData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 4
+        ),
+        (
+            name: "fyrox_fogData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 5
+        ),
+    ],
+
+    disabled_passes: ["GBuffer", "DirectionalShadow", "PointShadow", "SpotShadow"],
+
+    passes: [
+        (
+            name: "Forward",
+            draw_parameters: DrawParameters(
+                cull_face: None,
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: Some(BlendParameters(
+                    func: BlendFunc(
+                        sfactor: SrcAlpha,
+                        dfactor: OneMinusSrcAlpha,
+                        alpha_sfactor: SrcAlpha,
+                        alpha_dfactor: OneMinusSrcAlpha,
+                    ),
+                    equation: BlendEquation(
+                        rgb: Add,
+                        alpha: Add
+                    )
+                )),
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            ),
+            vertex_shader:
+               r#"
+               // xy - coordinates of the first texel of the particle in the state texture.
+               layout(location = 0) in vec3 vertexPosition;
+               layout(location = 1) in vec2 vertexTexCoord;
+
+               out vec2 texCoord;
+               out vec4 color;
+               out vec3 fragmentPosition;
+
+               void main()
+               {
+                   ivec2 stateTexel = ivec2(vertexPosition.xy);
+                   vec4 positionAge = texelFetch(particleState, stateTexel, 0);
+                   vec4 velocityLifetime = texelFetch(particleState, stateTexel + ivec2(1, 0), 0);
+                   vec4 sizeRotation = texelFetch(particleState, stateTexel + ivec2(2, 0), 0);
+                   vec4 particleColor = texelFetch(particleState, stateTexel + ivec2(3, 0), 0);
+
+                   // Move dead particles out of the view volume.
+                   if (positionAge.w >= velocityLifetime.w) {
+                       texCoord = vec2(0.0);
+                       color = vec4(0.0);
+                       fragmentPosition = vec3(0.0);
+                       gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+                       return;
+                   }
+
+                   vec3 particlePosition = positionAge.xyz;
+                   float particleSize = sizeRotation.x;
+                   float particleRotation = sizeRotation.z;
+
+                   color = S_SRGBToLinear(particleColor);
+                   color.a *= properties.alphaFactor;
+                   texCoord = vertexTexCoord;
+                   vec2 vertexOffset = S_RotateVec2(vertexTexCoord * 2.0 - 1.0, particleRotation);
+                   vec4 worldPosition = fyrox_instanceData.worldMatrix * vec4(particlePosition, 1.0);
+                   vec3 offset = (vertexOffset.x * fyrox_cameraData.sideVector + vertexOffset.y * fyrox_cameraData.upVector) * particleSize;
+                   vec4 finalPosition = worldPosition + vec4(offset.x, offset.y, offset.z, 0.0);
+                   fragmentPosition = finalPosition.xyz;
+                   gl_Position = fyrox_cameraData.viewProjectionMatrix * finalPosition;
+               }
+               "#,
+
+           fragment_shader:
+               r#"
+               out vec4 FragColor;
+
+               in vec2 texCoord;
+               in vec4 color;
+               in vec3 fragmentPosition;
+
+               float toProjSpace(float z)
+               {
+                   return (fyrox_cameraData.zFar * fyrox_cameraData.zNear) / (fyrox_cameraData.zFar - z * fyrox_cameraData.zRange);
+               }
+
+               void main()
+               {
+                   ivec2 depthTextureSize = textureSize(fyrox_sceneDepth, 0);
+                   vec2 pixelSize = vec2(1.0 / float(depthTextureSize.x), 1.0 / float(depthTextureSize.y));
+                   float sceneDepth = toProjSpace(texture(fyrox_sceneDepth, gl_FragCoord.xy * pixelSize).r);
+                   float fragmentDepth = toProjSpace(gl_FragCoord.z);
+                   float depthOpacity = smoothstep((sceneDepth - fragmentDepth) * properties.softBoundarySharpnessFactor, 0.0, 1.0);
+
+                   vec3 lighting;
+                   if (properties.useLighting) {
+                      lighting = fyrox_lightData.ambientLightColor.xyz;
+                      for(int i = 0; i < min(fyrox_lightsBlock.lightCount, 16); ++i) {
+                          // "Unpack" light parameters.
+                          float halfHotspotAngleCos = fyrox_lightsBlock.lightsParameters[i].x;
+                          float halfConeAngleCos = fyrox_lightsBlock.lightsParameters[i].y;
+                          vec3 lightColor = fyrox_lightsBlock.lightsColorRadius[i].xyz;
+                          float radius = fyrox_lightsBlock.lightsColorRadius[i].w;
+                          vec3 lightPosition = fyrox_lightsBlock.lightsPosition[i];
+                          vec3 direction = fyrox_lightsBlock.lightsDirection[i];
+
+                          // Calculate lighting.
+                          vec3 toFragment = fragmentPosition - lightPosition;
+                          float distance = length(toFragment);
+                          vec3 toFragmentNormalized = toFragment / distance;
+                          float distanceAttenuation = S_LightDistanceAttenuation(distance, radius);
+                          float spotAngleCos = dot(toFragmentNormalized, direction);
+                          float directionalAttenuation = smoothstep(halfConeAngleCos, halfHotspotAngleCos, spotAngleCos);
+                          lighting += lightColor * (distanceAttenuation * directionalAttenuation);
+                      }
+                   } else {
+                      lighting = vec3(1.0);
+                   }
+
+                   FragColor = vec4(lighting, 1.0) * color * S_SRGBToLinear(texture(diffuseTexture, texCoord)).r;
+                   FragColor.a *= depthOpacity;
+                   FragColor.rgb = mix(
+                       FragColor.rgb,
+                       fyrox_fogData.color.rgb,
+                       S_FogFactor(
+                           fyrox_fogData.mode,
+                           fyrox_cameraData.position,
+                           fragmentPosition,
+                           fyrox_fogData.start,
+                           fyrox_fogData.end,
+                           fyrox_fogData.density,
+                           fyrox_fogData.height,
+                           fyrox_fogData.heightFalloff
+                       ) * fyrox_fogData.color.a
+                   );
+               }
+               "#,
+        )
+    ],
+)
\ No newline at end of file
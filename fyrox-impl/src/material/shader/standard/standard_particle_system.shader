@@ -54,6 +54,13 @@
             ]),
             binding: 4
         ),
+        (
+            name: "fyrox_fogData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 5
+        ),
     ],
 
     disabled_passes: ["GBuffer", "DirectionalShadow", "PointShadow", "SpotShadow"],
@@ -165,6 +172,20 @@
 
                    FragColor = vec4(lighting, 1.0) * color * S_SRGBToLinear(texture(diffuseTexture, texCoord)).r;
                    FragColor.a *= depthOpacity;
+                   FragColor.rgb = mix(
+                       FragColor.rgb,
+                       fyrox_fogData.color.rgb,
+                       S_FogFactor(
+                           fyrox_fogData.mode,
+                           fyrox_cameraData.position,
+                           fragmentPosition,
+                           fyrox_fogData.start,
+                           fyrox_fogData.end,
+                           fyrox_fogData.density,
+                           fyrox_fogData.height,
+                           fyrox_fogData.heightFalloff
+                       ) * fyrox_fogData.color.a
+                   );
                }
                "#,
         )
@@ -21,6 +21,13 @@
             ]),
             binding: 1
         ),
+        (
+            name: "fyrox_fogData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 2
+        ),
     ],
 
     disabled_passes: ["GBuffer", "DirectionalShadow", "PointShadow", "SpotShadow"],
@@ -68,6 +75,7 @@
 
                 out vec2 texCoord;
                 out vec4 color;
+                out vec3 fragmentPosition;
 
                 void main()
                 {
@@ -79,7 +87,9 @@
                     vec2 vertexOffset = S_RotateVec2(vertexTexCoord * 2.0 - 1.0, rotation);
                     vec4 worldPosition = fyrox_instanceData.worldMatrix * vec4(vertexPosition, 1.0);
                     vec3 offset = (vertexOffset.x * fyrox_cameraData.sideVector + vertexOffset.y * fyrox_cameraData.upVector) * size;
-                    gl_Position = fyrox_cameraData.viewProjectionMatrix * (worldPosition + vec4(offset.x, offset.y, offset.z, 0.0));
+                    vec4 finalPosition = worldPosition + vec4(offset.x, offset.y, offset.z, 0.0);
+                    fragmentPosition = finalPosition.xyz;
+                    gl_Position = fyrox_cameraData.viewProjectionMatrix * finalPosition;
                 }
                "#,
 
@@ -89,10 +99,25 @@
 
                 in vec2 texCoord;
                 in vec4 color;
+                in vec3 fragmentPosition;
 
                 void main()
                 {
                     FragColor = color * S_SRGBToLinear(texture(diffuseTexture, texCoord));
+                    FragColor.rgb = mix(
+                        FragColor.rgb,
+                        fyrox_fogData.color.rgb,
+                        S_FogFactor(
+                            fyrox_fogData.mode,
+                            fyrox_cameraData.position,
+                            fragmentPosition,
+                            fyrox_fogData.start,
+                            fyrox_fogData.end,
+                            fyrox_fogData.density,
+                            fyrox_fogData.height,
+                            fyrox_fogData.heightFalloff
+                        ) * fyrox_fogData.color.a
+                    );
                 }
                "#,
         )
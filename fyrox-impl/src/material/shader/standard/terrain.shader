@@ -169,6 +169,8 @@
                 out vec3 tangent;
                 out vec3 binormal;
                 out vec2 secondTexCoord;
+                out vec4 currentClipPosition;
+                out vec4 previousClipPosition;
 
                 void main()
                 {
@@ -197,6 +199,8 @@
                     position = vec3(fyrox_instanceData.worldMatrix * finalVertexPosition);
                     secondTexCoord = vertexSecondTexCoord;
                     gl_Position = fyrox_instanceData.worldViewProjection * finalVertexPosition;
+                    currentClipPosition = fyrox_instanceData.unjitteredWorldViewProjection * finalVertexPosition;
+                    previousClipPosition = fyrox_instanceData.previousWorldViewProjection * finalVertexPosition;
                 }
                 "#,
             fragment_shader:
@@ -206,6 +210,7 @@
                 layout(location = 2) out vec4 outAmbient;
                 layout(location = 3) out vec4 outMaterial;
                 layout(location = 4) out uint outDecalMask;
+                layout(location = 5) out vec2 outVelocity;
 
                 in vec3 position;
                 in vec3 normal;
@@ -213,6 +218,8 @@
                 in vec3 tangent;
                 in vec3 binormal;
                 in vec2 secondTexCoord;
+                in vec4 currentClipPosition;
+                in vec4 previousClipPosition;
 
                 void main()
                 {
@@ -250,6 +257,8 @@
 
                     outDecalMask = properties.layerIndex;
 
+                    outVelocity = S_ComputeVelocity(currentClipPosition, previousClipPosition);
+
                     float mask = texture(maskTexture, texCoord).r;
 
                     outColor.a = mask;
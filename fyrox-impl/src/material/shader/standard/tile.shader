@@ -7,6 +7,11 @@
             kind: Texture(kind: Sampler2D, fallback: White),
             binding: 0
         ),
+        (
+            name: "normalTexture",
+            kind: Texture(kind: Sampler2D, fallback: Normal),
+            binding: 1
+        ),
         (
             name: "fyrox_instanceData",
             kind: PropertyGroup([
@@ -28,6 +33,13 @@
             ]),
             binding: 2
         ),
+        (
+            name: "fyrox_lights2DBlock",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 3
+        ),
     ],
 
     disabled_passes: ["GBuffer", "DirectionalShadow", "PointShadow", "SpotShadow"],
@@ -93,8 +105,31 @@
                 in vec4 color;
                 in vec3 fragmentPosition;
 
+                float Shadow2D(int lightIndex, int bin, float distance)
+                {
+                    int index = lightIndex * 128 + (bin & 127);
+                    float occluderDistance = fyrox_lights2DBlock.lightsShadowMap[index / 4][index % 4];
+                    return 1.0 - smoothstep(occluderDistance, occluderDistance + 0.02, distance);
+                }
+
                 void main()
                 {
+                    // Reconstruct world-space directions of texture axes to be able to use normal maps
+                    // on rotated and flipped sprites.
+                    vec3 n = texture(normalTexture, texCoord).xyz * 2.0 - 1.0;
+                    vec3 normal = vec3(0.0, 0.0, 1.0);
+                    vec2 dpx = dFdx(fragmentPosition.xy);
+                    vec2 dpy = dFdy(fragmentPosition.xy);
+                    vec2 duvx = dFdx(texCoord);
+                    vec2 duvy = dFdy(texCoord);
+                    float det = duvx.x * duvy.y - duvx.y * duvy.x;
+                    if (abs(det) > 1.0e-12) {
+                        vec2 tangent = normalize((dpx * duvy.y - dpy * duvx.y) / det);
+                        vec2 binormal = normalize((dpy * duvx.x - dpx * duvy.x) / det);
+                        // Green channel points up in the image, while V axis points down.
+                        normal = normalize(vec3(tangent * n.x - binormal * n.y, n.z));
+                    }
+
                     vec3 lighting = fyrox_lightData.ambientLightColor.xyz;
                     for(int i = 0; i < min(fyrox_lightsBlock.lightCount, 16); ++i) {
                         // "Unpack" light parameters.
@@ -115,6 +150,36 @@
                         lighting += lightColor * (distanceAttenuation * directionalAttenuation);
                     }
 
+                    lighting += fyrox_lights2DBlock.ambientColor.xyz;
+                    for(int i = 0; i < min(fyrox_lights2DBlock.lightCount, 16); ++i) {
+                        vec4 positionRadius = fyrox_lights2DBlock.lightsPositionRadius[i];
+                        vec4 parameters = fyrox_lights2DBlock.lightsParameters[i];
+                        vec3 lightColor = fyrox_lights2DBlock.lightsColor[i].xyz;
+                        float radius = positionRadius.w;
+
+                        vec2 toFragment = fragmentPosition.xy - positionRadius.xy;
+                        float distance = max(length(toFragment), 1.0e-6);
+                        vec2 toFragmentNormalized = toFragment / distance;
+                        float distanceAttenuation = S_LightDistanceAttenuation(distance, radius);
+                        float directionalAttenuation = smoothstep(parameters.w, parameters.z, dot(toFragmentNormalized, parameters.xy));
+
+                        // Flat surfaces are lit fully, normal maps only add details.
+                        vec3 toLight = normalize(vec3(-toFragment, positionRadius.z));
+                        float normalFactor = clamp(dot(normal, toLight) + 1.0 - toLight.z, 0.0, 1.0);
+
+                        // Polar shadow map lookup with linear filtering between adjacent elements.
+                        float angle = atan(toFragment.y, toFragment.x);
+                        float bin = (angle + PI) / (2.0 * PI) * 128.0 - 0.5;
+                        float binFloor = floor(bin);
+                        float normalizedDistance = distance / radius;
+                        float shadow = mix(
+                            Shadow2D(i, int(binFloor) + 128, normalizedDistance),
+                            Shadow2D(i, int(binFloor) + 129, normalizedDistance),
+                            bin - binFloor);
+
+                        lighting += lightColor * (distanceAttenuation * directionalAttenuation * normalFactor * shadow);
+                    }
+
                     FragColor = vec4(lighting, 1.0) * color * S_SRGBToLinear(texture(diffuseTexture, texCoord));
                 }
                "#,
@@ -24,6 +24,8 @@
 
 pub mod dylib;
 
+#[cfg(feature = "gamepad")]
+use crate::engine::gamepad::{GamepadEvent, GamepadManager};
 use crate::{
     asset::manager::ResourceManager,
     core::{
@@ -185,6 +187,11 @@ pub struct PluginContext<'a, 'b> {
 
     /// Task pool for asynchronous task management.
     pub task_pool: &'a mut TaskPoolHandler,
+
+    #[cfg(feature = "gamepad")]
+    /// A reference to the gamepad manager, it can be used to fetch the state of connected gamepads
+    /// or to play rumble effects. See [`GamepadManager`] docs for more info.
+    pub gamepads: &'a mut GamepadManager,
 }
 
 impl dyn Plugin {
@@ -283,7 +290,7 @@ pub trait Plugin: Downcast + Visit + Reflect {
 
     /// The method is called when the main window receives an event from the OS. The main use of
     /// the method is to respond to some external events, for example an event from keyboard or
-    /// gamepad. See [`Event`] docs for more info.
+    /// mouse. See [`Event`] docs for more info.
     fn on_os_event(
         &mut self,
         #[allow(unused_variables)] event: &Event<()>,
@@ -291,6 +298,16 @@ pub trait Plugin: Downcast + Visit + Reflect {
     ) {
     }
 
+    #[cfg(feature = "gamepad")]
+    /// The method is called when any of connected gamepads produces an event (connection,
+    /// disconnection, button press, etc.). See [`GamepadEvent`] docs for more info.
+    fn on_gamepad_event(
+        &mut self,
+        #[allow(unused_variables)] event: &GamepadEvent,
+        #[allow(unused_variables)] context: PluginContext,
+    ) {
+    }
+
     /// The method is called when a graphics context was successfully created. It could be useful
     /// to catch the moment when it was just created and do something in response.
     fn on_graphics_context_initialized(
@@ -55,6 +55,7 @@ use crate::{
             uniform::{ByteStorage, UniformBuffer},
             ElementRange,
         },
+        light_2d::Light2DData,
         FallbackResources, LightData, RenderPassStatistics,
     },
     resource::texture::TextureResource,
@@ -75,6 +76,7 @@ use crate::{
             RenderPath,
         },
         node::{Node, RdcControlFlow},
+        FogSettings,
     },
 };
 use fxhash::{FxBuildHasher, FxHashMap, FxHasher};
@@ -141,6 +143,19 @@ impl RenderContext<'_> {
     }
 }
 
+/// Motion data is used to calculate screen-space velocity of each rendered surface. It is required
+/// for temporal effects (such as temporal anti-aliasing) only.
+#[derive(Clone, Copy)]
+pub struct MotionData<'a> {
+    /// View-projection matrix of the observer without any sub-pixel jitter.
+    pub view_projection: Matrix4<f32>,
+    /// View-projection matrix of the observer from the previous frame (without jitter).
+    pub previous_view_projection: Matrix4<f32>,
+    /// World transforms of the scene nodes from the previous frame. If a node is missing in the
+    /// map, its current transform will be used instead.
+    pub previous_transforms: &'a FxHashMap<Handle<Node>, Matrix4<f32>>,
+}
+
 #[allow(missing_docs)] // TODO
 pub struct BundleRenderContext<'a> {
     pub texture_cache: &'a mut TextureCache,
@@ -156,7 +171,9 @@ pub struct BundleRenderContext<'a> {
     // TODO: Add depth pre-pass to remove Option here. Current architecture allows only forward
     // renderer to have access to depth buffer that is available from G-Buffer.
     pub scene_depth: Option<&'a GpuTexture>,
+    pub fog: FogSettings,
     pub fallback_resources: &'a FallbackResources,
+    pub motion: Option<MotionData<'a>>,
 }
 
 /// A set of data of a surface for rendering.
@@ -228,6 +245,10 @@ pub struct GlobalUniformData {
     pub lights_block: UniformBlockLocation,
     /// Graphics settings block location.
     pub graphics_settings_block: UniformBlockLocation,
+    /// Fog settings block location.
+    pub fog_block: UniformBlockLocation,
+    /// 2D light sources data block location.
+    pub lights_2d_block: UniformBlockLocation,
 }
 
 fn write_with_material<T: ByteStorage>(
@@ -400,15 +421,31 @@ impl RenderDataBundle {
                 packed_blend_shape_weights[n][c] = *blend_shape_weight;
             }
 
+            let world_view_projection = view_projection_matrix * instance.world_transform;
+            let (unjittered_world_view_projection, previous_world_view_projection) =
+                match render_context.motion {
+                    Some(ref motion) => (
+                        motion.view_projection * instance.world_transform,
+                        motion.previous_view_projection
+                            * motion
+                                .previous_transforms
+                                .get(&instance.node_handle)
+                                .unwrap_or(&instance.world_transform),
+                    ),
+                    None => (world_view_projection, world_view_projection),
+                };
+
             let instance_buffer = StaticUniformBuffer::<1024>::new()
                 .with(&instance.world_transform)
-                .with(&(view_projection_matrix * instance.world_transform))
+                .with(&world_view_projection)
                 .with(&(instance.blend_shapes_weights.len() as i32))
                 .with(&(!instance.bone_matrices.is_empty()))
                 .with_slice_with_max_size(
                     &packed_blend_shape_weights,
                     ShaderDefinition::MAX_BLEND_SHAPE_WEIGHT_GROUPS,
-                );
+                )
+                .with(&unjittered_world_view_projection)
+                .with(&previous_world_view_projection);
 
             let mut instance_uniform_data = InstanceUniformData {
                 instance_block: render_context
@@ -565,6 +602,22 @@ impl RenderDataBundle {
                         ),
                     );
                 }
+                "fyrox_fogData" => {
+                    material_bindings.push(
+                        render_context.uniform_memory_allocator.block_to_binding(
+                            global_uniform_data.fog_block,
+                            resource_definition.binding,
+                        ),
+                    );
+                }
+                "fyrox_lights2DBlock" => {
+                    material_bindings.push(
+                        render_context.uniform_memory_allocator.block_to_binding(
+                            global_uniform_data.lights_2d_block,
+                            resource_definition.binding,
+                        ),
+                    );
+                }
                 _ => match resource_definition.kind {
                     ShaderResourceKind::Texture { fallback, .. } => {
                         let fallback = render_context.fallback_resources.sampler_fallback(fallback);
@@ -773,6 +826,9 @@ pub struct RenderDataBundleStorage {
     /// A sorted list of bundles.
     pub bundles: Vec<RenderDataBundle>,
     pub light_sources: Vec<LightSource>,
+    /// Visible 2D light sources. Collected only if [`RenderDataBundleStorageOptions::collect_lights`]
+    /// is set.
+    pub lights_2d: Light2DData,
 }
 
 pub struct RenderDataBundleStorageOptions {
@@ -794,6 +850,7 @@ impl RenderDataBundleStorage {
             observer_info,
             bundles: Default::default(),
             light_sources: Default::default(),
+            lights_2d: Default::default(),
         }
     }
 
@@ -814,6 +871,7 @@ impl RenderDataBundleStorage {
             observer_info: observer_info.clone(),
             bundles: Vec::with_capacity(capacity),
             light_sources: Default::default(),
+            lights_2d: Default::default(),
         };
 
         let frustum = Frustum::from_view_projection_matrix(
@@ -889,6 +947,10 @@ impl RenderDataBundleStorage {
             }
         }
 
+        if options.collect_lights {
+            storage.lights_2d = Light2DData::from_graph(graph, &frustum);
+        }
+
         let mut ctx = RenderContext {
             elapsed_time,
             observer_info: &observer_info,
@@ -1008,10 +1070,41 @@ impl RenderDataBundleStorage {
             .uniform_memory_allocator
             .allocate(graphics_settings);
 
+        let fog = &render_context.fog;
+        let fog_data = StaticUniformBuffer::<256>::new()
+            .with(
+                &(if fog.is_enabled() {
+                    fog.mode_index()
+                } else {
+                    0
+                }),
+            )
+            .with(&fog.color.srgb_to_linear_f32())
+            .with(&fog.start)
+            .with(&fog.end)
+            .with(&fog.density)
+            .with(&fog.height)
+            .with(&fog.height_falloff);
+        let fog_block = render_context.uniform_memory_allocator.allocate(fog_data);
+
+        let lights_2d = &self.lights_2d;
+        let lights_2d_data = StaticUniformBuffer::<10240>::new()
+            .with(&(lights_2d.count as i32))
+            .with(&lights_2d.ambient_color)
+            .with_slice(&lights_2d.position_radius)
+            .with_slice(&lights_2d.color)
+            .with_slice(&lights_2d.parameters)
+            .with_slice(&lights_2d.shadow_map);
+        let lights_2d_block = render_context
+            .uniform_memory_allocator
+            .allocate(lights_2d_data);
+
         GlobalUniformData {
             camera_block,
             lights_block,
             graphics_settings_block,
+            fog_block,
+            lights_2d_block,
         }
     }
 
@@ -0,0 +1,230 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Depth of field post effect. It simulates a thin lens: the size of the blur of each pixel is
+//! defined by its circle of confusion, the blur itself is a disc-shaped (bokeh) gather blur.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector2},
+        math::Rect,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        cache::uniform::UniformBufferCache,
+        framework::{
+            buffer::BufferUsage,
+            error::FrameworkError,
+            framebuffer::{BufferLocation, GpuFrameBuffer, ResourceBindGroup, ResourceBinding},
+            geometry_buffer::GpuGeometryBuffer,
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::GpuTexture,
+            server::GraphicsServer,
+            uniform::StaticUniformBuffer,
+            DrawParameters, ElementRange, GeometryBufferExt,
+        },
+        make_viewport_matrix, RenderPassStatistics,
+    },
+    scene::{
+        camera::{DepthOfField, Projection},
+        mesh::surface::SurfaceData,
+    },
+};
+
+/// Height of full-frame 35 mm sensor, in meters.
+const SENSOR_HEIGHT: f32 = 0.024;
+
+/// Focal length (in meters) that is used for the cameras with orthographic projection.
+const DEFAULT_FOCAL_LENGTH: f32 = 0.05;
+
+/// Calculates a scale, that converts `|distance - focus_distance| / distance` to the diameter of
+/// the circle of confusion in pixels.
+fn circle_of_confusion_scale(
+    projection: &Projection,
+    settings: &DepthOfField,
+    viewport_height: f32,
+) -> f32 {
+    let focal_length = match projection {
+        Projection::Perspective(perspective) => {
+            0.5 * SENSOR_HEIGHT / (0.5 * perspective.fov).tan().max(f32::EPSILON)
+        }
+        Projection::Orthographic(_) => DEFAULT_FOCAL_LENGTH,
+    };
+    let focus_distance = settings.focus_distance.max(focal_length + f32::EPSILON);
+    let aperture = focal_length / settings.f_stop.max(f32::EPSILON);
+    let coc_on_sensor = aperture * focal_length / (focus_distance - focal_length);
+    coc_on_sensor / SENSOR_HEIGHT * viewport_height
+}
+
+struct DepthOfFieldShader {
+    program: GpuProgram,
+    uniform_buffer_binding: usize,
+    frame_texture: UniformLocation,
+    depth_texture: UniformLocation,
+}
+
+impl DepthOfFieldShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/dof_fs.glsl");
+        let vertex_source = include_str!("shaders/dof_vs.glsl");
+
+        let program =
+            server.create_program("DepthOfFieldShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            frame_texture: program.uniform_location(&ImmutableString::new("frameTexture"))?,
+            depth_texture: program.uniform_location(&ImmutableString::new("depthTexture"))?,
+            program,
+        })
+    }
+}
+
+pub(crate) struct DepthOfFieldRenderContext<'a> {
+    pub viewport: Rect<i32>,
+    pub frame_texture: GpuTexture,
+    pub depth_texture: GpuTexture,
+    pub frame_buffer: &'a GpuFrameBuffer,
+    pub projection: &'a Projection,
+    pub projection_matrix: Matrix4<f32>,
+    pub settings: &'a DepthOfField,
+    pub uniform_buffer_cache: &'a mut UniformBufferCache,
+}
+
+pub struct DepthOfFieldRenderer {
+    shader: DepthOfFieldShader,
+    quad: GpuGeometryBuffer,
+}
+
+impl DepthOfFieldRenderer {
+    pub fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: DepthOfFieldShader::new(server)?,
+            quad: GpuGeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                BufferUsage::StaticDraw,
+                server,
+            )?,
+        })
+    }
+
+    pub(crate) fn render(
+        &self,
+        args: DepthOfFieldRenderContext,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let DepthOfFieldRenderContext {
+            viewport,
+            frame_texture,
+            depth_texture,
+            frame_buffer,
+            projection,
+            projection_matrix,
+            settings,
+            uniform_buffer_cache,
+        } = args;
+
+        let mut statistics = RenderPassStatistics::default();
+
+        let coc_scale = circle_of_confusion_scale(projection, settings, viewport.h() as f32);
+
+        statistics += frame_buffer.draw(
+            &*self.quad,
+            viewport,
+            &*self.shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: None,
+                blend: None,
+                stencil_op: Default::default(),
+                scissor_box: None,
+            },
+            &[ResourceBindGroup {
+                bindings: &[
+                    ResourceBinding::texture(&frame_texture, &self.shader.frame_texture),
+                    ResourceBinding::texture(&depth_texture, &self.shader.depth_texture),
+                    ResourceBinding::Buffer {
+                        buffer: uniform_buffer_cache.write(
+                            StaticUniformBuffer::<256>::new()
+                                .with(&make_viewport_matrix(viewport))
+                                .with(&projection_matrix.try_inverse().unwrap_or_default())
+                                .with(&Vector2::new(
+                                    1.0 / viewport.w() as f32,
+                                    1.0 / viewport.h() as f32,
+                                ))
+                                .with(&settings.focus_distance)
+                                .with(&coc_scale)
+                                .with(&settings.max_blur_radius),
+                        )?,
+                        binding: BufferLocation::Auto {
+                            shader_location: self.shader.uniform_buffer_binding,
+                        },
+                        data_usage: Default::default(),
+                    },
+                ],
+            }],
+            ElementRange::Full,
+        )?;
+
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        renderer::dof::circle_of_confusion_scale,
+        scene::camera::{DepthOfField, OrthographicProjection, PerspectiveProjection, Projection},
+    };
+
+    #[test]
+    fn test_circle_of_confusion_scale() {
+        let projection = Projection::Perspective(PerspectiveProjection::default());
+        let settings = DepthOfField {
+            enabled: true,
+            focus_distance: 5.0,
+            f_stop: 2.8,
+            max_blur_radius: 16.0,
+        };
+        let scale = circle_of_confusion_scale(&projection, &settings, 1080.0);
+        assert!(scale > 0.0);
+
+        // Wider aperture gives stronger blur.
+        let wide = circle_of_confusion_scale(
+            &projection,
+            &DepthOfField {
+                f_stop: 1.4,
+                ..settings
+            },
+            1080.0,
+        );
+        assert!((wide - 2.0 * scale).abs() < 1.0e-3 * scale);
+
+        // The scale is in pixels, so it depends on the viewport height linearly.
+        let half = circle_of_confusion_scale(&projection, &settings, 540.0);
+        assert!((half - 0.5 * scale).abs() < 1.0e-3 * scale);
+
+        // Orthographic cameras use a fixed focal length.
+        let orthographic = Projection::Orthographic(OrthographicProjection::default());
+        assert!(circle_of_confusion_scale(&orthographic, &settings, 1080.0) > 0.0);
+    }
+}
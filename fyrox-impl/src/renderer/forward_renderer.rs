@@ -28,7 +28,7 @@ use crate::{
         framework::{error::FrameworkError, server::GraphicsServer},
         FallbackResources, GeometryCache, QualitySettings, RenderPassStatistics,
     },
-    scene::mesh::RenderPath,
+    scene::{mesh::RenderPath, FogSettings},
 };
 use fyrox_graphics::framebuffer::GpuFrameBuffer;
 use fyrox_graphics::gpu_texture::GpuTexture;
@@ -49,6 +49,7 @@ pub(crate) struct ForwardRenderContext<'a> {
     pub fallback_resources: &'a FallbackResources,
     pub scene_depth: GpuTexture,
     pub ambient_light: Color,
+    pub fog: FogSettings,
     pub uniform_memory_allocator: &'a mut UniformMemoryAllocator,
 }
 
@@ -77,6 +78,7 @@ impl ForwardRenderer {
             fallback_resources,
             scene_depth,
             ambient_light,
+            fog,
             uniform_memory_allocator,
         } = args;
 
@@ -97,6 +99,8 @@ impl ForwardRenderer {
                 fallback_resources,
                 ambient_light,
                 scene_depth: Some(&scene_depth),
+                fog,
+                motion: None,
             },
         )?;
 
@@ -25,6 +25,7 @@
 //! RT2: RGBA16F - Ambient light + emission (both in xyz)
 //! RT3: RGBA8 - Metallic (x) + Roughness (y) + Ambient Occlusion (z)
 //! RT4: R8UI - Decal mask (x)
+//! RT5: RG16F - Screen-space velocity (xy), used by temporal anti-aliasing
 //!
 //! Every alpha channel is used for layer blending for terrains. This is inefficient, but for
 //! now I don't know better solution.
@@ -37,7 +38,7 @@ use crate::{
         sstorage::ImmutableString,
     },
     renderer::{
-        bundle::{BundleRenderContext, RenderDataBundleStorage, SurfaceInstanceData},
+        bundle::{BundleRenderContext, MotionData, RenderDataBundleStorage, SurfaceInstanceData},
         cache::{
             shader::ShaderCache,
             uniform::{UniformBufferCache, UniformMemoryAllocator},
@@ -101,6 +102,8 @@ pub(crate) struct GBufferRenderContext<'a, 'b> {
     #[allow(dead_code)]
     pub screen_space_debug_renderer: &'a mut DebugRenderer,
     pub unit_quad: &'a dyn GpuGeometryBufferTrait,
+    /// Motion data for velocity buffer. If it is `None`, the velocity will be zero.
+    pub motion: Option<MotionData<'b>>,
 }
 
 impl GBuffer {
@@ -137,6 +140,10 @@ impl GBuffer {
                     kind: AttachmentKind::Color,
                     texture: server.create_2d_render_target(PixelKind::R8UI, width, height)?,
                 },
+                Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: server.create_2d_render_target(PixelKind::RG16F, width, height)?,
+                },
             ],
         )?;
 
@@ -198,6 +205,10 @@ impl GBuffer {
         self.framebuffer.color_attachments()[4].texture.clone()
     }
 
+    pub fn velocity_texture(&self) -> GpuTexture {
+        self.framebuffer.color_attachments()[5].texture.clone()
+    }
+
     pub(crate) fn fill(
         &mut self,
         args: GBufferRenderContext,
@@ -217,6 +228,7 @@ impl GBuffer {
             uniform_buffer_cache,
             unit_quad,
             uniform_memory_allocator,
+            motion,
             ..
         } = args;
 
@@ -261,6 +273,8 @@ impl GBuffer {
                 fallback_resources,
                 ambient_light: Color::WHITE, // TODO
                 scene_depth: None,           // TODO. Add z-pre-pass.
+                fog: Default::default(),
+                motion,
             },
         )?;
 
@@ -0,0 +1,425 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Simulation of particle systems on GPU. The state of every particle system is stored in a
+//! floating-point texture, which is updated by a fragment shader using two textures in ping-pong
+//! fashion. See [`crate::scene::particle_system::ParticleSimulation`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Vector2, Vector4},
+        color::Color,
+        math::Rect,
+        pool::Handle,
+        sstorage::ImmutableString,
+    },
+    graph::SceneGraph,
+    renderer::{
+        cache::{texture::TextureCache, uniform::UniformBufferCache},
+        framework::{
+            buffer::BufferUsage,
+            error::FrameworkError,
+            framebuffer::{
+                Attachment, AttachmentKind, BufferLocation, GpuFrameBuffer, ResourceBindGroup,
+                ResourceBinding,
+            },
+            geometry_buffer::{
+                AttributeDefinition, AttributeKind, ElementsDescriptor, GeometryBufferDescriptor,
+                GpuGeometryBuffer, VertexBufferData, VertexBufferDescriptor,
+            },
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{GpuTexture, PixelKind},
+            server::GraphicsServer,
+            uniform::StaticUniformBuffer,
+            DrawParameters, ElementRange, GeometryBufferExt,
+        },
+        make_viewport_matrix, RenderPassStatistics, Renderer,
+    },
+    scene::{
+        mesh::surface::SurfaceData,
+        node::Node,
+        particle_system::{
+            gpu::{particle_texel, state_texture_size, GpuParticleSpawn, GpuParticleUpdate},
+            ParticleSimulation, ParticleSystem,
+        },
+        Scene,
+    },
+};
+use bytemuck::{Pod, Zeroable};
+use fxhash::FxHashMap;
+
+struct SimulationShader {
+    program: GpuProgram,
+    uniform_buffer_binding: usize,
+    state_texture: UniformLocation,
+}
+
+impl SimulationShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/particle_simulation_fs.glsl");
+        let vertex_source = include_str!("shaders/particle_simulation_vs.glsl");
+        let program =
+            server.create_program("ParticleSimulationShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            state_texture: program.uniform_location(&ImmutableString::new("stateTexture"))?,
+            program,
+        })
+    }
+}
+
+struct SpawnShader {
+    program: GpuProgram,
+    uniform_buffer_binding: usize,
+}
+
+impl SpawnShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/particle_spawn_fs.glsl");
+        let vertex_source = include_str!("shaders/particle_spawn_vs.glsl");
+        let program =
+            server.create_program("ParticleSpawnShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            program,
+        })
+    }
+}
+
+/// A single texel of the state texture, that must be written when a particle is spawned.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct SpawnVertex {
+    texel: Vector2<f32>,
+    value: Vector4<f32>,
+}
+
+/// A pair of render targets of a particle system.
+struct ParticleSystemTargets {
+    state: GpuFrameBuffer,
+    scratch: GpuFrameBuffer,
+    capacity: u32,
+}
+
+impl ParticleSystemTargets {
+    fn new(server: &dyn GraphicsServer, capacity: u32) -> Result<Self, FrameworkError> {
+        let (width, height) = state_texture_size(capacity);
+        let make_frame_buffer = || {
+            server.create_frame_buffer(
+                None,
+                vec![Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: server.create_2d_render_target(
+                        PixelKind::RGBA32F,
+                        width as usize,
+                        height as usize,
+                    )?,
+                }],
+            )
+        };
+        Ok(Self {
+            state: make_frame_buffer()?,
+            scratch: make_frame_buffer()?,
+            capacity,
+        })
+    }
+
+    fn state_texture(&self) -> GpuTexture {
+        self.state.color_attachments()[0].texture.clone()
+    }
+
+    fn viewport(&self) -> Rect<i32> {
+        let (width, height) = state_texture_size(self.capacity);
+        Rect::new(0, 0, width as i32, height as i32)
+    }
+}
+
+/// Stores the state of all particle systems of all scenes, that are simulated on GPU.
+pub(crate) struct GpuParticleStorage {
+    simulation_shader: SimulationShader,
+    spawn_shader: SpawnShader,
+    quad: GpuGeometryBuffer,
+    spawn_geometry: GpuGeometryBuffer,
+    spawn_vertices: Vec<SpawnVertex>,
+    spawn_indices: Vec<u32>,
+    targets: FxHashMap<(Handle<Scene>, Handle<Node>), ParticleSystemTargets>,
+}
+
+impl GpuParticleStorage {
+    pub(crate) fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            simulation_shader: SimulationShader::new(server)?,
+            spawn_shader: SpawnShader::new(server)?,
+            quad: GpuGeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                BufferUsage::StaticDraw,
+                server,
+            )?,
+            spawn_geometry: server.create_geometry_buffer(GeometryBufferDescriptor {
+                elements: ElementsDescriptor::Points(&[]),
+                buffers: &[VertexBufferDescriptor {
+                    usage: BufferUsage::DynamicDraw,
+                    attributes: &[
+                        AttributeDefinition {
+                            location: 0,
+                            kind: AttributeKind::Float,
+                            component_count: 2,
+                            normalized: false,
+                            divisor: 0,
+                        },
+                        AttributeDefinition {
+                            location: 1,
+                            kind: AttributeKind::Float,
+                            component_count: 4,
+                            normalized: false,
+                            divisor: 0,
+                        },
+                    ],
+                    data: VertexBufferData::new::<SpawnVertex>(None),
+                }],
+                usage: BufferUsage::DynamicDraw,
+            })?,
+            spawn_vertices: Default::default(),
+            spawn_indices: Default::default(),
+            targets: Default::default(),
+        })
+    }
+
+    pub(crate) fn retain_scenes(&mut self, mut func: impl FnMut(Handle<Scene>) -> bool) {
+        self.targets.retain(|(scene, _), _| func(*scene))
+    }
+
+    fn draw_parameters() -> DrawParameters {
+        DrawParameters {
+            cull_face: None,
+            color_write: Default::default(),
+            depth_write: false,
+            stencil_test: None,
+            depth_test: None,
+            blend: None,
+            stencil_op: Default::default(),
+            scissor_box: None,
+        }
+    }
+
+    fn spawn(
+        &mut self,
+        targets: &ParticleSystemTargets,
+        spawns: &[GpuParticleSpawn],
+        uniform_buffer_cache: &mut UniformBufferCache,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let mut statistics = RenderPassStatistics::default();
+
+        self.spawn_vertices.clear();
+        for spawn in spawns {
+            let first_texel = particle_texel(spawn.slot);
+            for (i, value) in spawn.state.iter().enumerate() {
+                self.spawn_vertices.push(SpawnVertex {
+                    texel: Vector2::new((first_texel.x + i as u32) as f32, first_texel.y as f32),
+                    value: *value,
+                });
+            }
+        }
+
+        if self.spawn_vertices.is_empty() {
+            return Ok(statistics);
+        }
+
+        self.spawn_indices.clear();
+        self.spawn_indices
+            .extend(0..self.spawn_vertices.len() as u32);
+        self.spawn_geometry
+            .set_buffer_data_of_type(0, &self.spawn_vertices);
+        self.spawn_geometry.set_points(&self.spawn_indices);
+
+        let viewport = targets.viewport();
+        statistics += targets.state.draw(
+            &*self.spawn_geometry,
+            viewport,
+            &*self.spawn_shader.program,
+            &Self::draw_parameters(),
+            &[ResourceBindGroup {
+                bindings: &[ResourceBinding::Buffer {
+                    buffer: uniform_buffer_cache.write(StaticUniformBuffer::<256>::new().with(
+                        &Vector2::new(1.0 / viewport.w() as f32, 1.0 / viewport.h() as f32),
+                    ))?,
+                    binding: BufferLocation::Auto {
+                        shader_location: self.spawn_shader.uniform_buffer_binding,
+                    },
+                    data_usage: Default::default(),
+                }],
+            }],
+            ElementRange::Full,
+        )?;
+
+        Ok(statistics)
+    }
+
+    fn simulate(
+        &self,
+        targets: &ParticleSystemTargets,
+        update: &GpuParticleUpdate,
+        dt: f32,
+        uniform_buffer_cache: &mut UniformBufferCache,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let mut statistics = RenderPassStatistics::default();
+
+        let viewport = targets.viewport();
+        let state_texture = targets.state_texture();
+        statistics += targets.scratch.draw(
+            &*self.quad,
+            viewport,
+            &*self.simulation_shader.program,
+            &Self::draw_parameters(),
+            &[ResourceBindGroup {
+                bindings: &[
+                    ResourceBinding::texture(&state_texture, &self.simulation_shader.state_texture),
+                    ResourceBinding::Buffer {
+                        buffer: uniform_buffer_cache.write(
+                            StaticUniformBuffer::<1024>::new()
+                                .with(&make_viewport_matrix(viewport))
+                                .with(&update.acceleration.scale(dt * dt).push(dt))
+                                .with_slice(&update.color_over_lifetime),
+                        )?,
+                        binding: BufferLocation::Auto {
+                            shader_location: self.simulation_shader.uniform_buffer_binding,
+                        },
+                        data_usage: Default::default(),
+                    },
+                ],
+            }],
+            ElementRange::Full,
+        )?;
+
+        targets.scratch.blit_to(
+            &*targets.state,
+            0,
+            0,
+            viewport.w(),
+            viewport.h(),
+            0,
+            0,
+            viewport.w(),
+            viewport.h(),
+            true,
+            false,
+            false,
+        );
+
+        Ok(statistics)
+    }
+
+    fn execute_commands(
+        &mut self,
+        targets: &ParticleSystemTargets,
+        update: &GpuParticleUpdate,
+        uniform_buffer_cache: &mut UniformBufferCache,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let mut statistics = RenderPassStatistics::default();
+
+        // Spawns are sorted by simulation step, so each step takes a contiguous range of them.
+        let spawns = &update.commands.spawns;
+        let mut begin = 0;
+        for (step, dt) in update.commands.steps.iter().enumerate() {
+            let count = spawns[begin..]
+                .iter()
+                .take_while(|spawn| spawn.step == step)
+                .count();
+            statistics +=
+                self.spawn(targets, &spawns[begin..begin + count], uniform_buffer_cache)?;
+            statistics += self.simulate(targets, update, *dt, uniform_buffer_cache)?;
+            begin += count;
+        }
+
+        Ok(statistics)
+    }
+
+    fn update(
+        &mut self,
+        server: &dyn GraphicsServer,
+        uniform_buffer_cache: &mut UniformBufferCache,
+        texture_cache: &mut TextureCache,
+        scene_handle: Handle<Scene>,
+        scene: &Scene,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let mut statistics = RenderPassStatistics::default();
+
+        // Remove the targets of deleted particle systems, or the ones that are simulated on CPU now.
+        self.targets.retain(|(targets_scene, particle_system), _| {
+            *targets_scene != scene_handle
+                || scene
+                    .graph
+                    .try_get_of_type::<ParticleSystem>(*particle_system)
+                    .is_some_and(|particle_system| {
+                        matches!(particle_system.simulation(), ParticleSimulation::Gpu { .. })
+                    })
+        });
+
+        for (handle, node) in scene.graph.pair_iter() {
+            let Some(update) = node
+                .cast::<ParticleSystem>()
+                .and_then(|particle_system| particle_system.take_gpu_update())
+            else {
+                continue;
+            };
+
+            let key = (scene_handle, handle);
+            let (targets, is_new) = match self.targets.remove(&key) {
+                Some(targets) if targets.capacity == update.capacity => (targets, false),
+                _ => (ParticleSystemTargets::new(server, update.capacity)?, true),
+            };
+
+            if is_new || update.commands.reset {
+                targets
+                    .state
+                    .clear(targets.viewport(), Some(Color::TRANSPARENT), None, None);
+            }
+
+            let result = self.execute_commands(&targets, &update, uniform_buffer_cache);
+
+            texture_cache.try_register(&update.texture, targets.state_texture());
+            self.targets.insert(key, targets);
+            statistics += result?;
+        }
+
+        Ok(statistics)
+    }
+}
+
+impl Renderer {
+    /// Executes the simulation commands of every particle system of the scene, that is simulated
+    /// on GPU.
+    pub(crate) fn update_gpu_particles(
+        &mut self,
+        scene_handle: Handle<Scene>,
+        scene: &Scene,
+    ) -> Result<(), FrameworkError> {
+        self.statistics += self.gpu_particles.update(
+            &*self.server,
+            &mut self.uniform_buffer_cache,
+            &mut self.texture_cache,
+            scene_handle,
+            scene,
+        )?;
+        Ok(())
+    }
+}
@@ -20,8 +20,9 @@
 
 use crate::{
     core::sstorage::ImmutableString,
-    renderer::framework::{
-        error::FrameworkError, gpu_program::UniformLocation, server::GraphicsServer,
+    renderer::{
+        framework::{error::FrameworkError, gpu_program::UniformLocation, server::GraphicsServer},
+        reflection_probe::MAX_REFLECTION_PROBES,
     },
 };
 use fyrox_graphics::gpu_program::GpuProgram;
@@ -32,6 +33,11 @@ pub struct AmbientLightShader {
     pub diffuse_texture: UniformLocation,
     pub ao_sampler: UniformLocation,
     pub ambient_texture: UniformLocation,
+    pub depth_texture: UniformLocation,
+    pub normal_texture: UniformLocation,
+    pub material_texture: UniformLocation,
+    pub environment_texture: UniformLocation,
+    pub probe_textures: [UniformLocation; MAX_REFLECTION_PROBES],
 }
 
 impl AmbientLightShader {
@@ -46,6 +52,17 @@ impl AmbientLightShader {
             diffuse_texture: program.uniform_location(&ImmutableString::new("diffuseTexture"))?,
             ao_sampler: program.uniform_location(&ImmutableString::new("aoSampler"))?,
             ambient_texture: program.uniform_location(&ImmutableString::new("ambientTexture"))?,
+            depth_texture: program.uniform_location(&ImmutableString::new("depthTexture"))?,
+            normal_texture: program.uniform_location(&ImmutableString::new("normalTexture"))?,
+            material_texture: program.uniform_location(&ImmutableString::new("materialTexture"))?,
+            environment_texture: program
+                .uniform_location(&ImmutableString::new("environmentTexture"))?,
+            probe_textures: [
+                program.uniform_location(&ImmutableString::new("probeTexture0"))?,
+                program.uniform_location(&ImmutableString::new("probeTexture1"))?,
+                program.uniform_location(&ImmutableString::new("probeTexture2"))?,
+                program.uniform_location(&ImmutableString::new("probeTexture3"))?,
+            ],
             program,
         })
     }
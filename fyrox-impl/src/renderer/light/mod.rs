@@ -35,6 +35,7 @@ use crate::{
             buffer::BufferUsage,
             error::FrameworkError,
             framebuffer::{ResourceBindGroup, ResourceBinding},
+            gpu_texture::PixelElementKind,
             server::GraphicsServer,
             uniform::StaticUniformBuffer,
             BlendFactor, BlendFunc, BlendParameters, ColorMask, CompareFunc, CullFace,
@@ -42,10 +43,11 @@ use crate::{
         },
         gbuffer::GBuffer,
         light::{
-            ambient::AmbientLightShader, directional::DirectionalLightShader,
+            ambient::AmbientLightShader, directional::DirectionalLightShader, fog::FogShader,
             point::PointLightShader, spot::SpotLightShader,
         },
         light_volume::LightVolumeRenderer,
+        reflection_probe::{ReflectionProbeData, MAX_REFLECTION_PROBES},
         shadow::{
             csm::{CsmRenderContext, CsmRenderer},
             point::{PointShadowMapRenderContext, PointShadowMapRenderer},
@@ -72,6 +74,7 @@ use fyrox_graphics::geometry_buffer::GpuGeometryBuffer;
 
 pub mod ambient;
 pub mod directional;
+pub mod fog;
 pub mod point;
 pub mod spot;
 
@@ -81,6 +84,7 @@ pub struct DeferredLightRenderer {
     point_light_shader: PointLightShader,
     directional_light_shader: DirectionalLightShader,
     ambient_light_shader: AmbientLightShader,
+    fog_shader: FogShader,
     quad: GpuGeometryBuffer,
     sphere: GpuGeometryBuffer,
     cone: GpuGeometryBuffer,
@@ -105,11 +109,16 @@ pub(crate) struct DeferredRendererContext<'a> {
     pub textures: &'a mut TextureCache,
     pub geometry_cache: &'a mut GeometryCache,
     pub frame_buffer: &'a GpuFrameBuffer,
+    /// Optional frame buffer for the ambient lighting pass. Its first attachment must be the
+    /// texture of `frame_buffer` and the second one receives image-based specular lighting (it is
+    /// used by screen-space reflections). If `None`, `frame_buffer` is used.
+    pub ambient_frame_buffer: Option<&'a GpuFrameBuffer>,
     pub shader_cache: &'a mut ShaderCache,
     pub fallback_resources: &'a FallbackResources,
     pub uniform_buffer_cache: &'a mut UniformBufferCache,
     pub visibility_cache: &'a mut ObserverVisibilityCache,
     pub uniform_memory_allocator: &'a mut UniformMemoryAllocator,
+    pub reflection_probes: &'a [ReflectionProbeData],
 }
 
 impl DeferredLightRenderer {
@@ -163,6 +172,7 @@ impl DeferredLightRenderer {
             point_light_shader: PointLightShader::new(server)?,
             directional_light_shader: DirectionalLightShader::new(server)?,
             ambient_light_shader: AmbientLightShader::new(server)?,
+            fog_shader: FogShader::new(server)?,
             quad: GpuGeometryBuffer::from_surface_data(
                 &SurfaceData::make_unit_xy_quad(),
                 BufferUsage::StaticDraw,
@@ -294,10 +304,12 @@ impl DeferredLightRenderer {
             textures,
             geometry_cache,
             frame_buffer,
+            ambient_frame_buffer,
             fallback_resources,
             uniform_buffer_cache,
             visibility_cache,
             uniform_memory_allocator,
+            reflection_probes,
         } = args;
 
         let viewport = Rect::new(0, 0, gbuffer.width, gbuffer.height);
@@ -378,7 +390,50 @@ impl DeferredLightRenderer {
         let gbuffer_ambient_map = gbuffer.ambient_texture();
         let ao_map = self.ssao_renderer.ao_map();
 
-        pass_stats += frame_buffer.draw(
+        // Environment map is used as a source of reflections for the pixels that are not covered
+        // by reflection probes.
+        let (environment_map, environment_is_srgb) = match camera
+            .environment_ref()
+            .and_then(|environment| textures.get(server, environment))
+        {
+            Some(environment) => (
+                environment.clone(),
+                // HDR environment maps are already in linear space.
+                !matches!(
+                    environment.pixel_kind().element_kind(),
+                    PixelElementKind::Float
+                ),
+            ),
+            None => (fallback_resources.environment_dummy.clone(), false),
+        };
+
+        let reflection_probes =
+            &reflection_probes[..reflection_probes.len().min(MAX_REFLECTION_PROBES)];
+        let probe_world_to_local = reflection_probes
+            .iter()
+            .map(|probe| probe.world_to_local)
+            .collect::<Vec<_>>();
+        let probe_position = reflection_probes
+            .iter()
+            .map(|probe| probe.position)
+            .collect::<Vec<_>>();
+        let probe_shape = reflection_probes
+            .iter()
+            .map(|probe| probe.shape)
+            .collect::<Vec<_>>();
+        let probe_params = reflection_probes
+            .iter()
+            .map(|probe| probe.params)
+            .collect::<Vec<_>>();
+        let probe_texture = |index: usize| {
+            reflection_probes
+                .get(index)
+                .map_or(&fallback_resources.environment_dummy, |probe| {
+                    &probe.cube_map
+                })
+        };
+
+        pass_stats += ambient_frame_buffer.unwrap_or(frame_buffer).draw(
             &*self.quad,
             viewport,
             &*self.ambient_light_shader.program,
@@ -413,11 +468,55 @@ impl DeferredLightRenderer {
                         &gbuffer_ambient_map,
                         &self.ambient_light_shader.ambient_texture,
                     ),
+                    ResourceBinding::texture(
+                        &gbuffer_depth_map,
+                        &self.ambient_light_shader.depth_texture,
+                    ),
+                    ResourceBinding::texture(
+                        &gbuffer_normal_map,
+                        &self.ambient_light_shader.normal_texture,
+                    ),
+                    ResourceBinding::texture(
+                        &gbuffer_material_map,
+                        &self.ambient_light_shader.material_texture,
+                    ),
+                    ResourceBinding::texture(
+                        &environment_map,
+                        &self.ambient_light_shader.environment_texture,
+                    ),
+                    ResourceBinding::texture(
+                        probe_texture(0),
+                        &self.ambient_light_shader.probe_textures[0],
+                    ),
+                    ResourceBinding::texture(
+                        probe_texture(1),
+                        &self.ambient_light_shader.probe_textures[1],
+                    ),
+                    ResourceBinding::texture(
+                        probe_texture(2),
+                        &self.ambient_light_shader.probe_textures[2],
+                    ),
+                    ResourceBinding::texture(
+                        probe_texture(3),
+                        &self.ambient_light_shader.probe_textures[3],
+                    ),
                     ResourceBinding::Buffer {
                         buffer: uniform_buffer_cache.write(
-                            StaticUniformBuffer::<256>::new()
+                            StaticUniformBuffer::<1024>::new()
                                 .with(&frame_matrix)
-                                .with(&ambient_color.srgb_to_linear_f32()),
+                                .with(&inv_view_projection)
+                                .with(&ambient_color.srgb_to_linear_f32())
+                                .with(&camera_global_position)
+                                .with(&(reflection_probes.len() as i32))
+                                .with(&(environment_map.max_level() as f32))
+                                .with(&environment_is_srgb)
+                                .with_slice_with_max_size(
+                                    &probe_world_to_local,
+                                    MAX_REFLECTION_PROBES,
+                                )
+                                .with_slice_with_max_size(&probe_position, MAX_REFLECTION_PROBES)
+                                .with_slice_with_max_size(&probe_shape, MAX_REFLECTION_PROBES)
+                                .with_slice_with_max_size(&probe_params, MAX_REFLECTION_PROBES),
                         )?,
                         binding: BufferLocation::Auto {
                             shader_location: self.ambient_light_shader.uniform_buffer_binding,
@@ -999,6 +1098,58 @@ impl DeferredLightRenderer {
             }
         }
 
+        // Global fog is applied on top of the lit scene, the forward renderer applies the same fog
+        // to transparent objects by itself.
+        let fog = &scene.rendering_options.fog;
+        if fog.is_enabled() {
+            pass_stats += frame_buffer.draw(
+                &*self.quad,
+                viewport,
+                &*self.fog_shader.program,
+                &DrawParameters {
+                    cull_face: None,
+                    color_write: Default::default(),
+                    depth_write: false,
+                    stencil_test: None,
+                    depth_test: None,
+                    blend: Some(BlendParameters {
+                        func: BlendFunc::new(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha),
+                        ..Default::default()
+                    }),
+                    stencil_op: Default::default(),
+                    scissor_box: None,
+                },
+                &[ResourceBindGroup {
+                    bindings: &[
+                        ResourceBinding::texture(
+                            &gbuffer_depth_map,
+                            &self.fog_shader.depth_sampler,
+                        ),
+                        ResourceBinding::Buffer {
+                            buffer: uniform_buffer_cache.write(
+                                StaticUniformBuffer::<512>::new()
+                                    .with(&frame_matrix)
+                                    .with(&inv_view_projection)
+                                    .with(&fog.color.srgb_to_linear_f32())
+                                    .with(&camera_global_position)
+                                    .with(&fog.mode_index())
+                                    .with(&fog.start)
+                                    .with(&fog.end)
+                                    .with(&fog.density)
+                                    .with(&fog.height)
+                                    .with(&fog.height_falloff),
+                            )?,
+                            binding: BufferLocation::Auto {
+                                shader_location: self.fog_shader.uniform_buffer_binding,
+                            },
+                            data_usage: Default::default(),
+                        },
+                    ],
+                }],
+                ElementRange::Full,
+            )?;
+        }
+
         Ok((pass_stats, light_stats))
     }
 }
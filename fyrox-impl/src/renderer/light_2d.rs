@@ -0,0 +1,293 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! 2D lighting. 2D light sources are collected from a graph and packed in a form, that could be
+//! uploaded to GPU (see `fyrox_lights2DBlock` built-in property group of the shaders). Shadows of
+//! 2D lights are calculated on CPU: each light has a tiny polar shadow map, where each element
+//! contains normalized distance from the light to the closest occluder in the respective direction.
+
+use crate::{
+    core::{
+        algebra::{Vector2, Vector3, Vector4},
+        math::{frustum::Frustum, segment::LineSegment2, Rect},
+    },
+    graph::SceneGraphNode,
+    material::shader::ShaderDefinition,
+    scene::{
+        dim2::{
+            light::{cone::ConeLight, global::GlobalLight, point::PointLight, BaseLight},
+            occluder::Occluder,
+        },
+        graph::Graph,
+        tilemap::TileMap,
+    },
+};
+use std::f32::consts::{PI, TAU};
+
+const SHADOW_MAP_SIZE: usize = ShaderDefinition::LIGHT_2D_SHADOW_MAP_SIZE;
+
+struct Light2DSource {
+    position: Vector2<f32>,
+    height: f32,
+    radius: f32,
+    color: Vector3<f32>,
+    direction: Vector2<f32>,
+    hotspot_cone_angle_cos: f32,
+    cone_angle_cos: f32,
+    cast_shadows: bool,
+}
+
+/// A set of visible 2D light sources prepared for rendering.
+pub struct Light2DData {
+    /// Total amount of light sources.
+    pub count: usize,
+    /// Sum of the colors of all global lights in linear space.
+    pub ambient_color: Vector4<f32>,
+    /// World-space position (xy), height (z) and radius (w) of each light source.
+    pub position_radius: [Vector4<f32>; ShaderDefinition::MAX_LIGHTS_2D],
+    /// Linear color of each light source premultiplied by its intensity.
+    pub color: [Vector4<f32>; ShaderDefinition::MAX_LIGHTS_2D],
+    /// Direction (xy), hotspot cone angle cos (z) and cone angle cos (w) of each light source.
+    pub parameters: [Vector4<f32>; ShaderDefinition::MAX_LIGHTS_2D],
+    /// Polar shadow maps of each light source, packed in vectors of four elements.
+    pub shadow_map: Vec<Vector4<f32>>,
+}
+
+impl Default for Light2DData {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            ambient_color: Default::default(),
+            position_radius: Default::default(),
+            color: Default::default(),
+            parameters: Default::default(),
+            shadow_map: vec![
+                Vector4::repeat(1.0);
+                ShaderDefinition::MAX_LIGHTS_2D * SHADOW_MAP_SIZE / 4
+            ],
+        }
+    }
+}
+
+fn ray_segment_intersection(
+    direction: Vector2<f32>,
+    a: Vector2<f32>,
+    b: Vector2<f32>,
+) -> Option<f32> {
+    let edge = b - a;
+    let denominator = direction.perp(&edge);
+    if denominator.abs() <= f32::EPSILON {
+        return None;
+    }
+    let t = a.perp(&edge) / denominator;
+    let s = a.perp(&direction) / denominator;
+    (t >= 0.0 && (0.0..=1.0).contains(&s)).then_some(t)
+}
+
+fn bin_angle(bin: usize) -> f32 {
+    (bin as f32 + 0.5) / SHADOW_MAP_SIZE as f32 * TAU - PI
+}
+
+fn angle_to_bin(angle: f32) -> f32 {
+    (angle + PI) / TAU * SHADOW_MAP_SIZE as f32 - 0.5
+}
+
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// Calculates polar shadow map of a light located at the given position. Each element of the
+/// shadow map contains distance to the closest occluder (divided by the radius of the light) in
+/// the direction of the bin.
+fn rasterize_shadow_map(
+    position: Vector2<f32>,
+    radius: f32,
+    segments: &[LineSegment2<f32>],
+    shadow_map: &mut [f32],
+) {
+    shadow_map.fill(1.0);
+
+    if radius <= f32::EPSILON {
+        return;
+    }
+
+    for segment in segments {
+        if segment.distance(&position) >= radius {
+            continue;
+        }
+
+        let a = segment.start - position;
+        let b = segment.end - position;
+        let angle_a = a.y.atan2(a.x);
+        let angle_b = b.y.atan2(b.x);
+        let delta = wrap_angle(angle_b - angle_a);
+        let (begin, span) = if delta >= 0.0 {
+            (angle_a, delta)
+        } else {
+            (angle_b, -delta)
+        };
+
+        let first = angle_to_bin(begin).floor() as i32;
+        let last = angle_to_bin(begin + span).ceil() as i32;
+        for bin in first..=last {
+            let bin = bin.rem_euclid(SHADOW_MAP_SIZE as i32) as usize;
+            let angle = bin_angle(bin);
+            let direction = Vector2::new(angle.cos(), angle.sin());
+            if let Some(distance) = ray_segment_intersection(direction, a, b) {
+                let value = &mut shadow_map[bin];
+                *value = value.min(distance / radius);
+            }
+        }
+    }
+}
+
+impl Light2DData {
+    /// Collects visible 2D light sources and calculates their shadows. Only the first
+    /// [`ShaderDefinition::MAX_LIGHTS_2D`] light sources will be used, the rest is ignored.
+    pub fn from_graph(graph: &Graph, frustum: &Frustum) -> Self {
+        let mut data = Self::default();
+        let mut lights = Vec::new();
+
+        for node in graph.linear_iter() {
+            let Some(base_light) = node.component_ref::<BaseLight>() else {
+                continue;
+            };
+
+            if !base_light.global_visibility() || !base_light.is_globally_enabled() {
+                continue;
+            }
+
+            let color = base_light.color().as_frgb() * base_light.intensity();
+
+            if node.cast::<GlobalLight>().is_some() {
+                data.ambient_color += color.to_homogeneous();
+                continue;
+            }
+
+            if lights.len() >= ShaderDefinition::MAX_LIGHTS_2D
+                || !frustum.is_intersects_aabb(&node.world_bounding_box())
+            {
+                continue;
+            }
+
+            let position = base_light.global_position().xy();
+            let cast_shadows = base_light.cast_shadows();
+            if let Some(point_light) = node.cast::<PointLight>() {
+                lights.push(Light2DSource {
+                    position,
+                    height: point_light.height(),
+                    radius: point_light.radius(),
+                    color,
+                    direction: Vector2::default(),
+                    hotspot_cone_angle_cos: -1.0,
+                    cone_angle_cos: -2.0,
+                    cast_shadows,
+                });
+            } else if let Some(cone_light) = node.cast::<ConeLight>() {
+                let cone_angle = cone_light.cone_angle();
+                let hotspot_cone_angle_cos =
+                    (cone_light.hotspot_cone_angle().min(cone_angle) * 0.5).cos();
+                // Make sure that the angles are not equal, otherwise smoothstep is undefined.
+                let cone_angle_cos = (cone_angle * 0.5).cos().min(hotspot_cone_angle_cos - 0.001);
+                lights.push(Light2DSource {
+                    position,
+                    height: cone_light.height(),
+                    radius: cone_light.radius(),
+                    color,
+                    direction: cone_light.direction(),
+                    hotspot_cone_angle_cos,
+                    cone_angle_cos,
+                    cast_shadows,
+                });
+            }
+        }
+
+        let segments = collect_occluders(graph, &lights);
+
+        let mut shadow_map = [0.0; SHADOW_MAP_SIZE];
+        for (i, light) in lights.iter().enumerate() {
+            data.position_radius[i] = Vector4::new(
+                light.position.x,
+                light.position.y,
+                light.height,
+                light.radius,
+            );
+            data.color[i] = light.color.to_homogeneous();
+            data.parameters[i] = Vector4::new(
+                light.direction.x,
+                light.direction.y,
+                light.hotspot_cone_angle_cos,
+                light.cone_angle_cos,
+            );
+
+            if light.cast_shadows && !segments.is_empty() {
+                rasterize_shadow_map(light.position, light.radius, &segments, &mut shadow_map);
+                let offset = i * SHADOW_MAP_SIZE / 4;
+                for (dest, src) in data.shadow_map[offset..(offset + SHADOW_MAP_SIZE / 4)]
+                    .iter_mut()
+                    .zip(shadow_map.chunks_exact(4))
+                {
+                    *dest = Vector4::new(src[0], src[1], src[2], src[3]);
+                }
+            }
+        }
+
+        data.count = lights.len();
+
+        data
+    }
+}
+
+fn collect_occluders(graph: &Graph, lights: &[Light2DSource]) -> Vec<LineSegment2<f32>> {
+    let mut segments = Vec::new();
+
+    // Find a rectangle, that contains every shadow casting light.
+    let mut bounds: Option<Rect<f32>> = None;
+    for light in lights.iter().filter(|l| l.cast_shadows) {
+        let light_bounds = Rect::new(
+            light.position.x - light.radius,
+            light.position.y - light.radius,
+            2.0 * light.radius,
+            2.0 * light.radius,
+        );
+        match bounds.as_mut() {
+            Some(bounds) => bounds.extend_to_contain(light_bounds),
+            None => bounds = Some(light_bounds),
+        }
+    }
+
+    let Some(bounds) = bounds else {
+        return segments;
+    };
+
+    for node in graph.linear_iter() {
+        if !node.is_globally_enabled() {
+            continue;
+        }
+
+        if let Some(occluder) = node.cast::<Occluder>() {
+            occluder.world_segments(&mut segments);
+        } else if let Some(tile_map) = node.cast::<TileMap>() {
+            tile_map.collect_light_occluders(bounds, &mut segments);
+        }
+    }
+
+    segments
+}
@@ -33,23 +33,30 @@ pub mod framework;
 pub mod bundle;
 pub mod cache;
 pub mod debug_renderer;
+pub mod light_2d;
 pub mod storage;
 pub mod ui_renderer;
 pub mod visibility;
 
 mod bloom;
+mod dof;
 mod flat_shader;
 mod forward_renderer;
 mod fxaa;
 mod gbuffer;
+mod gpu_particles;
 mod hdr;
 mod light;
 mod light_volume;
+mod motion_blur;
 mod occlusion;
+mod reflection_probe;
 mod shadow;
 mod skybox_shader;
 mod ssao;
+mod ssr;
 mod stats;
+mod taa;
 
 use crate::{
     asset::{event::ResourceEvent, manager::ResourceManager},
@@ -71,12 +78,15 @@ use crate::{
     material::shader::{Shader, ShaderDefinition},
     renderer::{
         bloom::BloomRenderer,
-        bundle::{ObserverInfo, RenderDataBundleStorage, RenderDataBundleStorageOptions},
+        bundle::{
+            MotionData, ObserverInfo, RenderDataBundleStorage, RenderDataBundleStorageOptions,
+        },
         cache::{
             geometry::GeometryCache, shader::ShaderCache, texture::TextureCache,
             uniform::UniformBufferCache, uniform::UniformMemoryAllocator,
         },
         debug_renderer::DebugRenderer,
+        dof::{DepthOfFieldRenderContext, DepthOfFieldRenderer},
         flat_shader::FlatShader,
         forward_renderer::{ForwardRenderContext, ForwardRenderer},
         framework::{
@@ -94,13 +104,22 @@ use crate::{
         },
         fxaa::FxaaRenderer,
         gbuffer::{GBuffer, GBufferRenderContext},
+        gpu_particles::GpuParticleStorage,
         hdr::HighDynamicRangeRenderer,
         light::{DeferredLightRenderer, DeferredRendererContext},
+        motion_blur::{MotionBlurRenderContext, MotionBlurRenderer},
+        reflection_probe::ReflectionProbeStorage,
+        ssr::{ScreenSpaceReflectionsRenderer, SsrRenderContext},
+        taa::{jitter_projection, TaaHistory, TaaRenderContext, TaaRenderer},
         ui_renderer::{UiRenderContext, UiRenderer},
         visibility::VisibilityCache,
     },
     resource::texture::{Texture, TextureKind, TextureResource},
-    scene::{camera::Camera, mesh::surface::SurfaceData, Scene, SceneContainer},
+    scene::{
+        camera::{AntiAliasing, Camera},
+        mesh::surface::SurfaceData,
+        Scene, SceneContainer,
+    },
 };
 use fxhash::FxHashMap;
 use fyrox_graphics::buffer::GpuBuffer;
@@ -231,11 +250,17 @@ pub struct QualitySettings {
     /// occlusion will be in your scene.
     pub ssao_radius: f32,
 
+    /// Whether to use screen space reflections or not. Each camera has its own reflection
+    /// settings, but this one is able to globally disable screen space reflections.
+    #[serde(default)]
+    pub use_ssr: bool,
+
     /// Global switch to enable or disable light scattering. Each light can have
     /// its own scatter switch, but this one is able to globally disable scatter.
     pub light_scatter_enabled: bool,
 
-    /// Whether to use Fast Approximate AntiAliasing or not.
+    /// Whether to use Fast Approximate AntiAliasing or not. It is applied only for the cameras
+    /// that use [`crate::scene::camera::AntiAliasing::Fxaa`] mode.
     pub fxaa: bool,
 
     /// Whether to use Parallax Mapping or not.
@@ -280,6 +305,8 @@ impl QualitySettings {
             use_ssao: true,
             ssao_radius: 0.5,
 
+            use_ssr: true,
+
             light_scatter_enabled: true,
 
             point_shadow_map_precision: ShadowMapPrecision::Full,
@@ -316,6 +343,8 @@ impl QualitySettings {
             use_ssao: true,
             ssao_radius: 0.5,
 
+            use_ssr: false,
+
             light_scatter_enabled: true,
 
             point_shadow_map_precision: ShadowMapPrecision::Full,
@@ -357,6 +386,8 @@ impl QualitySettings {
             use_ssao: true,
             ssao_radius: 0.5,
 
+            use_ssr: false,
+
             light_scatter_enabled: false,
 
             point_shadow_map_precision: ShadowMapPrecision::Half,
@@ -398,6 +429,8 @@ impl QualitySettings {
             use_ssao: false,
             ssao_radius: 0.5,
 
+            use_ssr: false,
+
             light_scatter_enabled: false,
 
             point_shadow_map_precision: ShadowMapPrecision::Half,
@@ -486,6 +519,9 @@ pub struct AssociatedSceneData {
     /// Intermediate high dynamic range frame buffer.
     pub hdr_scene_framebuffer: GpuFrameBuffer,
 
+    /// Additional high dynamic range frame buffer for post processing.
+    pub hdr_temp_framebuffer: GpuFrameBuffer,
+
     /// Final frame of the scene. Tone mapped + gamma corrected.
     pub ldr_scene_framebuffer: GpuFrameBuffer,
 
@@ -500,6 +536,13 @@ pub struct AssociatedSceneData {
     /// bleeding effect (glow effect).
     pub bloom_renderer: BloomRenderer,
 
+    /// Screen-space reflections renderer has to be created per scene, because it contains
+    /// scene-sized render targets.
+    pub ssr_renderer: ScreenSpaceReflectionsRenderer,
+
+    /// History of the previous frames for temporal anti-aliasing.
+    pub taa_history: TaaHistory,
+
     /// Rendering statistics for a scene.
     pub statistics: SceneStatistics,
 }
@@ -523,7 +566,15 @@ impl AssociatedSceneData {
             }),
             vec![Attachment {
                 kind: AttachmentKind::Color,
-                texture: hdr_frame_texture,
+                texture: hdr_frame_texture.clone(),
+            }],
+        )?;
+
+        let hdr_temp_framebuffer = server.create_frame_buffer(
+            None,
+            vec![Attachment {
+                kind: AttachmentKind::Color,
+                texture: server.create_2d_render_target(PixelKind::RGBA16F, width, height)?,
             }],
         )?;
 
@@ -567,7 +618,15 @@ impl AssociatedSceneData {
             gbuffer: GBuffer::new(server, width, height)?,
             hdr_renderer: HighDynamicRangeRenderer::new(server)?,
             bloom_renderer: BloomRenderer::new(server, width, height)?,
+            ssr_renderer: ScreenSpaceReflectionsRenderer::new(
+                server,
+                width,
+                height,
+                hdr_frame_texture,
+            )?,
+            taa_history: TaaHistory::new(width, height),
             hdr_scene_framebuffer,
+            hdr_temp_framebuffer,
             ldr_scene_framebuffer,
             ldr_temp_framebuffer,
             statistics: Default::default(),
@@ -598,6 +657,13 @@ impl AssociatedSceneData {
             .clone()
     }
 
+    /// Returns high-dynamic range frame buffer texture (post processing frame).
+    pub fn hdr_temp_frame_texture(&self) -> GpuTexture {
+        self.hdr_temp_framebuffer.color_attachments()[0]
+            .texture
+            .clone()
+    }
+
     /// Returns low-dynamic range frame buffer texture (final frame).
     pub fn ldr_scene_frame_texture(&self) -> GpuTexture {
         self.ldr_scene_framebuffer.color_attachments()[0]
@@ -694,6 +760,9 @@ pub struct Renderer {
     geometry_cache: GeometryCache,
     forward_renderer: ForwardRenderer,
     fxaa_renderer: FxaaRenderer,
+    taa_renderer: TaaRenderer,
+    dof_renderer: DepthOfFieldRenderer,
+    motion_blur_renderer: MotionBlurRenderer,
     texture_event_receiver: Receiver<ResourceEvent>,
     shader_event_receiver: Receiver<ResourceEvent>,
     // TextureId -> FrameBuffer mapping. This mapping is used for temporal frame buffers
@@ -702,6 +771,8 @@ pub struct Renderer {
     uniform_memory_allocator: UniformMemoryAllocator,
     /// Visibility cache based on occlusion query.
     pub visibility_cache: VisibilityCache,
+    reflection_probes: ReflectionProbeStorage,
+    gpu_particles: GpuParticleStorage,
     /// Graphics server.
     pub server: SharedGraphicsServer,
 }
@@ -1046,6 +1117,10 @@ impl Renderer {
             forward_renderer: ForwardRenderer::new(),
             ui_frame_buffers: Default::default(),
             fxaa_renderer: FxaaRenderer::new(&*server)?,
+            taa_renderer: TaaRenderer::new(&*server)?,
+            dof_renderer: DepthOfFieldRenderer::new(&*server)?,
+            motion_blur_renderer: MotionBlurRenderer::new(&*server)?,
+            gpu_particles: GpuParticleStorage::new(&*server)?,
             statistics: Statistics::default(),
             shader_event_receiver,
             texture_event_receiver,
@@ -1054,6 +1129,7 @@ impl Renderer {
             uniform_buffer_cache: UniformBufferCache::new(server.clone()),
             server,
             visibility_cache: Default::default(),
+            reflection_probes: Default::default(),
             uniform_memory_allocator,
         })
     }
@@ -1296,6 +1372,9 @@ impl Renderer {
     ) -> Result<&AssociatedSceneData, FrameworkError> {
         let graph = &scene.graph;
 
+        self.update_reflection_probes(scene_handle, scene, elapsed_time)?;
+        self.update_gpu_particles(scene_handle, scene)?;
+
         let backbuffer_width = self.frame_size.0 as f32;
         let backbuffer_height = self.frame_size.1 as f32;
 
@@ -1377,6 +1456,31 @@ impl Renderer {
 
             let viewport = camera.viewport_pixels(frame_size);
 
+            // Temporal anti-aliasing requires the projection to be shifted by a sub-pixel offset
+            // each frame. The offset is applied only to the geometry rendering, everything else
+            // uses unjittered matrices.
+            let use_taa = camera.anti_aliasing() == AntiAliasing::Taa;
+            let view_projection = camera.view_projection_matrix();
+            let projection_matrix = if use_taa {
+                jitter_projection(
+                    camera.projection_matrix(),
+                    scene_associated_data.taa_history.jitter(),
+                    viewport,
+                )
+            } else {
+                camera.projection_matrix()
+            };
+
+            // Velocity buffer is needed only for temporal effects.
+            let use_motion = use_taa || camera.motion_blur().enabled;
+            let previous_view_projection = if use_motion {
+                scene_associated_data
+                    .taa_history
+                    .previous_view_projection(camera_handle)
+            } else {
+                None
+            };
+
             let bundle_storage = RenderDataBundleStorage::from_graph(
                 graph,
                 elapsed_time,
@@ -1385,7 +1489,7 @@ impl Renderer {
                     z_near: camera.projection().z_near(),
                     z_far: camera.projection().z_far(),
                     view_matrix: camera.view_matrix(),
-                    projection_matrix: camera.projection_matrix(),
+                    projection_matrix,
                 },
                 GBUFFER_PASS_NAME.clone(),
                 RenderDataBundleStorageOptions {
@@ -1413,12 +1517,40 @@ impl Renderer {
                     uniform_memory_allocator: &mut self.uniform_memory_allocator,
                     screen_space_debug_renderer: &mut self.screen_space_debug_renderer,
                     unit_quad: &*self.quad,
+                    motion: if use_motion {
+                        Some(MotionData {
+                            view_projection,
+                            previous_view_projection: previous_view_projection
+                                .unwrap_or(view_projection),
+                            previous_transforms: &scene_associated_data
+                                .taa_history
+                                .previous_transforms,
+                        })
+                    } else {
+                        None
+                    },
                 })?;
 
             server.set_polygon_fill_mode(PolygonFace::FrontAndBack, PolygonFillMode::Fill);
 
+            if use_motion {
+                scene_associated_data.taa_history.register_camera(
+                    camera_handle,
+                    view_projection,
+                    use_taa,
+                );
+            }
+
             scene_associated_data.copy_depth_stencil_to_scene_framebuffer();
 
+            let reflection_probes = self.reflection_probes.collect(
+                server,
+                &mut self.texture_cache,
+                scene_handle,
+                scene,
+                camera,
+            );
+
             scene_associated_data.hdr_scene_framebuffer.clear(
                 viewport,
                 Some(
@@ -1431,6 +1563,9 @@ impl Renderer {
                 Some(0),
             );
 
+            let ssr_settings = camera.screen_space_reflections();
+            let use_ssr = self.quality_settings.use_ssr && ssr_settings.enabled;
+
             let (pass_stats, light_stats) =
                 self.deferred_light_renderer
                     .render(DeferredRendererContext {
@@ -1445,16 +1580,40 @@ impl Renderer {
                         textures: &mut self.texture_cache,
                         geometry_cache: &mut self.geometry_cache,
                         frame_buffer: &scene_associated_data.hdr_scene_framebuffer,
+                        ambient_frame_buffer: if use_ssr {
+                            Some(scene_associated_data.ssr_renderer.lighting_framebuffer())
+                        } else {
+                            None
+                        },
                         shader_cache: &mut self.shader_cache,
                         fallback_resources: &self.fallback_resources,
                         uniform_buffer_cache: &mut self.uniform_buffer_cache,
                         visibility_cache,
                         uniform_memory_allocator: &mut self.uniform_memory_allocator,
+                        reflection_probes: &reflection_probes,
                     })?;
 
             scene_associated_data.statistics += light_stats;
             scene_associated_data.statistics += pass_stats;
 
+            if use_ssr {
+                let frame_texture = scene_associated_data.hdr_scene_frame_texture();
+
+                scene_associated_data.statistics +=
+                    scene_associated_data
+                        .ssr_renderer
+                        .render(SsrRenderContext {
+                            gbuffer: &scene_associated_data.gbuffer,
+                            frame_texture,
+                            frame_buffer: &scene_associated_data.hdr_scene_framebuffer,
+                            viewport,
+                            settings: ssr_settings,
+                            view_matrix: camera.view_matrix(),
+                            projection_matrix,
+                            uniform_buffer_cache: &mut self.uniform_buffer_cache,
+                        })?;
+            }
+
             let depth = scene_associated_data.gbuffer.depth();
 
             scene_associated_data.statistics +=
@@ -1470,6 +1629,7 @@ impl Renderer {
                     fallback_resources: &self.fallback_resources,
                     scene_depth: depth,
                     ambient_light: scene.rendering_options.ambient_lighting_color,
+                    fog: scene.rendering_options.fog,
                     uniform_memory_allocator: &mut self.uniform_memory_allocator,
                 })?;
 
@@ -1502,6 +1662,57 @@ impl Renderer {
 
             let quad = &self.quad;
 
+            // Apply cinematic effects of the camera.
+            let depth_of_field = camera.depth_of_field();
+            if depth_of_field.enabled {
+                scene_associated_data.statistics +=
+                    self.dof_renderer.render(DepthOfFieldRenderContext {
+                        viewport,
+                        frame_texture: scene_associated_data.hdr_scene_frame_texture(),
+                        depth_texture: scene_associated_data.gbuffer.depth(),
+                        frame_buffer: &scene_associated_data.hdr_temp_framebuffer,
+                        projection: camera.projection(),
+                        projection_matrix,
+                        settings: depth_of_field,
+                        uniform_buffer_cache: &mut self.uniform_buffer_cache,
+                    })?;
+
+                scene_associated_data.statistics += blit_pixels(
+                    &mut self.uniform_buffer_cache,
+                    &scene_associated_data.hdr_scene_framebuffer,
+                    scene_associated_data.hdr_temp_frame_texture(),
+                    &self.flat_shader,
+                    viewport,
+                    &**quad,
+                )?;
+            }
+
+            let motion_blur = camera.motion_blur();
+            if motion_blur.enabled {
+                scene_associated_data.statistics +=
+                    self.motion_blur_renderer.render(MotionBlurRenderContext {
+                        viewport,
+                        frame_texture: scene_associated_data.hdr_scene_frame_texture(),
+                        velocity_texture: scene_associated_data.gbuffer.velocity_texture(),
+                        depth_texture: scene_associated_data.gbuffer.depth(),
+                        frame_buffer: &scene_associated_data.hdr_temp_framebuffer,
+                        view_projection,
+                        previous_view_projection: previous_view_projection
+                            .unwrap_or(view_projection),
+                        settings: motion_blur,
+                        uniform_buffer_cache: &mut self.uniform_buffer_cache,
+                    })?;
+
+                scene_associated_data.statistics += blit_pixels(
+                    &mut self.uniform_buffer_cache,
+                    &scene_associated_data.hdr_scene_framebuffer,
+                    scene_associated_data.hdr_temp_frame_texture(),
+                    &self.flat_shader,
+                    viewport,
+                    &**quad,
+                )?;
+            }
+
             // Prepare glow map.
             scene_associated_data.statistics += scene_associated_data.bloom_renderer.render(
                 &**quad,
@@ -1525,8 +1736,49 @@ impl Renderer {
                 &mut self.uniform_buffer_cache,
             )?;
 
+            // Apply temporal anti-aliasing if needed.
+            if use_taa {
+                let history_framebuffer = scene_associated_data
+                    .taa_history
+                    .framebuffer(server, camera_handle)?;
+                scene_associated_data.statistics += self.taa_renderer.render(TaaRenderContext {
+                    viewport,
+                    frame_texture: scene_associated_data.ldr_scene_frame_texture(),
+                    history_texture: history_framebuffer.color_attachments()[0].texture.clone(),
+                    velocity_texture: scene_associated_data.gbuffer.velocity_texture(),
+                    depth_texture: scene_associated_data.gbuffer.depth(),
+                    view_projection,
+                    previous_view_projection: previous_view_projection.filter(|_| {
+                        scene_associated_data
+                            .taa_history
+                            .has_previous_frame(camera_handle)
+                    }),
+                    frame_buffer: &scene_associated_data.ldr_temp_framebuffer,
+                    uniform_buffer_cache: &mut self.uniform_buffer_cache,
+                })?;
+
+                let quad = &self.quad;
+                let temp_frame_texture = scene_associated_data.ldr_temp_frame_texture();
+                scene_associated_data.statistics += blit_pixels(
+                    &mut self.uniform_buffer_cache,
+                    &scene_associated_data.ldr_scene_framebuffer,
+                    temp_frame_texture.clone(),
+                    &self.flat_shader,
+                    viewport,
+                    &**quad,
+                )?;
+                scene_associated_data.statistics += blit_pixels(
+                    &mut self.uniform_buffer_cache,
+                    &history_framebuffer,
+                    temp_frame_texture,
+                    &self.flat_shader,
+                    viewport,
+                    &**quad,
+                )?;
+            }
+
             // Apply FXAA if needed.
-            if self.quality_settings.fxaa {
+            if camera.anti_aliasing() == AntiAliasing::Fxaa && self.quality_settings.fxaa {
                 scene_associated_data.statistics += self.fxaa_renderer.render(
                     viewport,
                     scene_associated_data.ldr_scene_frame_texture(),
@@ -1583,6 +1835,8 @@ impl Renderer {
             }
         }
 
+        scene_associated_data.taa_history.end_frame(graph);
+
         self.visibility_cache.update(graph);
 
         // Optionally render everything into back buffer.
@@ -1620,6 +1874,10 @@ impl Renderer {
         // Make sure to drop associated data for destroyed scenes.
         self.scene_data_map
             .retain(|h, _| scenes.is_valid_handle(*h));
+        self.reflection_probes
+            .retain_scenes(|h| scenes.is_valid_handle(h));
+        self.gpu_particles
+            .retain_scenes(|h| scenes.is_valid_handle(h));
 
         // We have to invalidate resource bindings cache because some textures or programs,
         // or other GL resources can be destroyed and then on their "names" some new resource
@@ -0,0 +1,164 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Per-object motion blur post effect. Each pixel is blurred along its screen-space velocity,
+//! that is taken from the velocity buffer of the G-Buffer.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector2},
+        math::Rect,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        cache::uniform::UniformBufferCache,
+        framework::{
+            buffer::BufferUsage,
+            error::FrameworkError,
+            framebuffer::{BufferLocation, GpuFrameBuffer, ResourceBindGroup, ResourceBinding},
+            geometry_buffer::GpuGeometryBuffer,
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::GpuTexture,
+            server::GraphicsServer,
+            uniform::StaticUniformBuffer,
+            DrawParameters, ElementRange, GeometryBufferExt,
+        },
+        make_viewport_matrix, RenderPassStatistics,
+    },
+    scene::{camera::MotionBlur, mesh::surface::SurfaceData},
+};
+
+struct MotionBlurShader {
+    program: GpuProgram,
+    uniform_buffer_binding: usize,
+    frame_texture: UniformLocation,
+    velocity_texture: UniformLocation,
+    depth_texture: UniformLocation,
+}
+
+impl MotionBlurShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/motion_blur_fs.glsl");
+        let vertex_source = include_str!("shaders/motion_blur_vs.glsl");
+
+        let program = server.create_program("MotionBlurShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            frame_texture: program.uniform_location(&ImmutableString::new("frameTexture"))?,
+            velocity_texture: program.uniform_location(&ImmutableString::new("velocityTexture"))?,
+            depth_texture: program.uniform_location(&ImmutableString::new("depthTexture"))?,
+            program,
+        })
+    }
+}
+
+pub(crate) struct MotionBlurRenderContext<'a> {
+    pub viewport: Rect<i32>,
+    pub frame_texture: GpuTexture,
+    pub velocity_texture: GpuTexture,
+    pub depth_texture: GpuTexture,
+    pub frame_buffer: &'a GpuFrameBuffer,
+    /// Unjittered view-projection matrix of the current frame.
+    pub view_projection: Matrix4<f32>,
+    /// Unjittered view-projection matrix of the previous frame.
+    pub previous_view_projection: Matrix4<f32>,
+    pub settings: &'a MotionBlur,
+    pub uniform_buffer_cache: &'a mut UniformBufferCache,
+}
+
+pub struct MotionBlurRenderer {
+    shader: MotionBlurShader,
+    quad: GpuGeometryBuffer,
+}
+
+impl MotionBlurRenderer {
+    pub fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: MotionBlurShader::new(server)?,
+            quad: GpuGeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                BufferUsage::StaticDraw,
+                server,
+            )?,
+        })
+    }
+
+    pub(crate) fn render(
+        &self,
+        args: MotionBlurRenderContext,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let MotionBlurRenderContext {
+            viewport,
+            frame_texture,
+            velocity_texture,
+            depth_texture,
+            frame_buffer,
+            view_projection,
+            previous_view_projection,
+            settings,
+            uniform_buffer_cache,
+        } = args;
+
+        let mut statistics = RenderPassStatistics::default();
+
+        statistics += frame_buffer.draw(
+            &*self.quad,
+            viewport,
+            &*self.shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: None,
+                blend: None,
+                stencil_op: Default::default(),
+                scissor_box: None,
+            },
+            &[ResourceBindGroup {
+                bindings: &[
+                    ResourceBinding::texture(&frame_texture, &self.shader.frame_texture),
+                    ResourceBinding::texture(&velocity_texture, &self.shader.velocity_texture),
+                    ResourceBinding::texture(&depth_texture, &self.shader.depth_texture),
+                    ResourceBinding::Buffer {
+                        buffer: uniform_buffer_cache.write(
+                            StaticUniformBuffer::<512>::new()
+                                .with(&make_viewport_matrix(viewport))
+                                .with(&view_projection.try_inverse().unwrap_or_default())
+                                .with(&previous_view_projection)
+                                .with(&Vector2::new(viewport.w() as f32, viewport.h() as f32))
+                                .with(&settings.shutter)
+                                .with(&settings.max_blur_radius)
+                                .with(&(settings.sample_count.max(2) as i32)),
+                        )?,
+                        binding: BufferLocation::Auto {
+                            shader_location: self.shader.uniform_buffer_binding,
+                        },
+                        data_usage: Default::default(),
+                    },
+                ],
+            }],
+            ElementRange::Full,
+        )?;
+
+        Ok(statistics)
+    }
+}
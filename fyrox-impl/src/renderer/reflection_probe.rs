@@ -0,0 +1,549 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reflection probes capture their surroundings into cube maps, which are then used by the ambient
+//! lighting pass as a source of specular reflections. See [`ReflectionProbe`] docs for more info.
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::{
+        algebra::{Matrix4, Vector2, Vector3, Vector4},
+        math::{frustum::Frustum, Rect},
+        pool::Handle,
+    },
+    graph::SceneGraph,
+    renderer::{
+        blit_pixels,
+        bundle::{ObserverInfo, RenderDataBundleStorage, RenderDataBundleStorageOptions},
+        cache::texture::TextureCache,
+        forward_renderer::ForwardRenderContext,
+        framework::{
+            error::FrameworkError,
+            framebuffer::{Attachment, AttachmentKind, GpuFrameBuffer},
+            gpu_texture::{
+                CubeMapFace, GpuTexture, GpuTextureDescriptor, GpuTextureKind, MagnificationFilter,
+                MinificationFilter, PixelKind, WrapMode,
+            },
+            server::GraphicsServer,
+        },
+        gbuffer::{GBuffer, GBufferRenderContext},
+        light::DeferredRendererContext,
+        Renderer, SceneStatistics, GBUFFER_PASS_NAME,
+    },
+    resource::texture::{
+        TextureKind, TextureMinificationFilter, TexturePixelKind, TextureResource,
+        TextureResourceExtension, TextureWrapMode,
+    },
+    scene::{
+        base::BaseBuilder,
+        camera::{Camera, CameraBuilder, PerspectiveProjection, Projection, SkyBoxKind},
+        node::Node,
+        reflection_probe::{ReflectionProbe, ReflectionProbeShape},
+        Scene,
+    },
+};
+use fxhash::FxHashMap;
+
+/// Max amount of reflection probes that could affect a single frame.
+pub(crate) const MAX_REFLECTION_PROBES: usize = 4;
+
+/// Look and up vectors of every face of a cube map. Must match the ones used by point shadows,
+/// otherwise the faces will be flipped.
+const CUBE_MAP_FACES: [(CubeMapFace, Vector3<f32>, Vector3<f32>); 6] = [
+    (
+        CubeMapFace::PositiveX,
+        Vector3::new(1.0, 0.0, 0.0),
+        Vector3::new(0.0, -1.0, 0.0),
+    ),
+    (
+        CubeMapFace::NegativeX,
+        Vector3::new(-1.0, 0.0, 0.0),
+        Vector3::new(0.0, -1.0, 0.0),
+    ),
+    (
+        CubeMapFace::PositiveY,
+        Vector3::new(0.0, 1.0, 0.0),
+        Vector3::new(0.0, 0.0, 1.0),
+    ),
+    (
+        CubeMapFace::NegativeY,
+        Vector3::new(0.0, -1.0, 0.0),
+        Vector3::new(0.0, 0.0, -1.0),
+    ),
+    (
+        CubeMapFace::PositiveZ,
+        Vector3::new(0.0, 0.0, 1.0),
+        Vector3::new(0.0, -1.0, 0.0),
+    ),
+    (
+        CubeMapFace::NegativeZ,
+        Vector3::new(0.0, 0.0, -1.0),
+        Vector3::new(0.0, -1.0, 0.0),
+    ),
+];
+
+/// A set of render targets, that is used to capture the scene from the position of a probe.
+pub(crate) struct ReflectionProbeCapture {
+    gbuffer: GBuffer,
+    hdr_framebuffer: GpuFrameBuffer,
+    cube_map_framebuffer: GpuFrameBuffer,
+    resolution: usize,
+}
+
+impl ReflectionProbeCapture {
+    fn new(server: &dyn GraphicsServer, resolution: usize) -> Result<Self, FrameworkError> {
+        let depth_stencil =
+            server.create_2d_render_target(PixelKind::D24S8, resolution, resolution)?;
+        let hdr_frame_texture =
+            server.create_2d_render_target(PixelKind::RGBA16F, resolution, resolution)?;
+        let hdr_framebuffer = server.create_frame_buffer(
+            Some(Attachment {
+                kind: AttachmentKind::DepthStencil,
+                texture: depth_stencil,
+            }),
+            vec![Attachment {
+                kind: AttachmentKind::Color,
+                texture: hdr_frame_texture,
+            }],
+        )?;
+
+        let cube_map = server.create_texture(GpuTextureDescriptor {
+            kind: GpuTextureKind::Cube {
+                width: resolution,
+                height: resolution,
+            },
+            pixel_kind: PixelKind::RGBA16F,
+            min_filter: MinificationFilter::LinearMipMapLinear,
+            mag_filter: MagnificationFilter::Linear,
+            s_wrap_mode: WrapMode::ClampToEdge,
+            t_wrap_mode: WrapMode::ClampToEdge,
+            r_wrap_mode: WrapMode::ClampToEdge,
+            ..Default::default()
+        })?;
+        let cube_map_framebuffer = server.create_frame_buffer(
+            None,
+            vec![Attachment {
+                kind: AttachmentKind::Color,
+                texture: cube_map,
+            }],
+        )?;
+
+        Ok(Self {
+            gbuffer: GBuffer::new(server, resolution, resolution)?,
+            hdr_framebuffer,
+            cube_map_framebuffer,
+            resolution,
+        })
+    }
+
+    fn hdr_frame_texture(&self) -> GpuTexture {
+        self.hdr_framebuffer.color_attachments()[0].texture.clone()
+    }
+
+    fn cube_map(&self) -> GpuTexture {
+        self.cube_map_framebuffer.color_attachments()[0]
+            .texture
+            .clone()
+    }
+}
+
+/// Stores captured cube maps of all reflection probes of all scenes.
+#[derive(Default)]
+pub(crate) struct ReflectionProbeStorage {
+    captures: FxHashMap<(Handle<Scene>, Handle<Node>), ReflectionProbeCapture>,
+}
+
+impl ReflectionProbeStorage {
+    /// Removes captures of the probes that were deleted or baked.
+    fn update(&mut self, scene_handle: Handle<Scene>, scene: &Scene) {
+        self.captures.retain(|(capture_scene, probe), _| {
+            *capture_scene != scene_handle
+                || scene
+                    .graph
+                    .try_get_of_type::<ReflectionProbe>(*probe)
+                    .is_some_and(|probe| probe.baked_cube_map().is_none())
+        })
+    }
+
+    pub(crate) fn retain_scenes(&mut self, mut func: impl FnMut(Handle<Scene>) -> bool) {
+        self.captures.retain(|(scene, _), _| func(*scene))
+    }
+
+    /// Collects up to [`MAX_REFLECTION_PROBES`] probes that are visible from the given camera,
+    /// sorted by distance to the camera.
+    pub(crate) fn collect(
+        &self,
+        server: &dyn GraphicsServer,
+        texture_cache: &mut TextureCache,
+        scene_handle: Handle<Scene>,
+        scene: &Scene,
+        camera: &Camera,
+    ) -> Vec<ReflectionProbeData> {
+        let frustum = Frustum::from_view_projection_matrix(camera.view_projection_matrix())
+            .unwrap_or_default();
+        let camera_position = camera.global_position();
+
+        let mut probes = scene
+            .graph
+            .pair_iter()
+            .filter_map(|(handle, node)| {
+                let probe = node.cast::<ReflectionProbe>()?;
+                if !probe.is_globally_enabled()
+                    || !frustum.is_intersects_aabb(&probe.world_bounding_box())
+                {
+                    return None;
+                }
+                Some((handle, probe))
+            })
+            .collect::<Vec<_>>();
+
+        probes.sort_by(|(_, a), (_, b)| {
+            let a = a.global_position().metric_distance(&camera_position);
+            let b = b.global_position().metric_distance(&camera_position);
+            a.total_cmp(&b)
+        });
+
+        let mut result = Vec::new();
+        for (handle, probe) in probes {
+            if result.len() >= MAX_REFLECTION_PROBES {
+                break;
+            }
+
+            let cube_map = if let Some(baked) = probe.baked_cube_map() {
+                let Some(gpu_texture) = texture_cache.get(server, baked) else {
+                    continue;
+                };
+                // Baked cube maps are stored with a single mip level, generate the rest on GPU.
+                if gpu_texture.max_level() == 0 {
+                    gpu_texture.generate_mipmap();
+                }
+                gpu_texture.clone()
+            } else if let Some(capture) = self.captures.get(&(scene_handle, handle)) {
+                capture.cube_map()
+            } else {
+                continue;
+            };
+
+            let transform = probe.global_transform_without_scaling();
+            let position = probe.global_position();
+            let shape = match probe.shape() {
+                ReflectionProbeShape::Box { half_extents } => half_extents.push(0.0),
+                ReflectionProbeShape::Sphere { radius } => Vector4::new(*radius, 0.0, 0.0, 1.0),
+            };
+
+            result.push(ReflectionProbeData {
+                position: position.push(cube_map.max_level() as f32),
+                cube_map,
+                world_to_local: transform.try_inverse().unwrap_or_default(),
+                shape,
+                params: Vector4::new(
+                    probe.blend_distance(),
+                    probe.intensity(),
+                    if probe.is_box_projection_enabled() {
+                        1.0
+                    } else {
+                        0.0
+                    },
+                    0.0,
+                ),
+            });
+        }
+
+        result
+    }
+}
+
+/// A reflection probe prepared for the ambient lighting pass.
+pub(crate) struct ReflectionProbeData {
+    pub cube_map: GpuTexture,
+    pub world_to_local: Matrix4<f32>,
+    /// xyz - world position of the probe, w - max lod of the cube map.
+    pub position: Vector4<f32>,
+    /// Box - (half extents, 0.0), sphere - (radius, 0.0, 0.0, 1.0).
+    pub shape: Vector4<f32>,
+    /// x - blend distance, y - intensity, z - box projection, w - unused.
+    pub params: Vector4<f32>,
+}
+
+impl Renderer {
+    /// Captures the scene for every reflection probe that requested it.
+    pub(crate) fn update_reflection_probes(
+        &mut self,
+        scene_handle: Handle<Scene>,
+        scene: &Scene,
+        elapsed_time: f32,
+    ) -> Result<(), FrameworkError> {
+        self.reflection_probes.update(scene_handle, scene);
+
+        for (probe_handle, probe) in scene.graph.pair_iter().filter_map(|(handle, node)| {
+            node.cast::<ReflectionProbe>()
+                .filter(|probe| probe.is_globally_enabled() && probe.needs_capture())
+                .map(|probe| (handle, probe))
+        }) {
+            probe.reset_capture_request();
+
+            let resolution = probe.resolution() as usize;
+            let key = (scene_handle, probe_handle);
+            let mut capture = match self.reflection_probes.captures.remove(&key) {
+                Some(capture) if capture.resolution == resolution => capture,
+                _ => ReflectionProbeCapture::new(&*self.server, resolution)?,
+            };
+            let result = self.capture_reflection_probe(
+                &mut capture,
+                scene,
+                probe_handle,
+                elapsed_time,
+                &mut |_| {},
+            );
+            self.reflection_probes.captures.insert(key, capture);
+            self.statistics += result?;
+        }
+
+        Ok(())
+    }
+
+    fn capture_reflection_probe(
+        &mut self,
+        capture: &mut ReflectionProbeCapture,
+        scene: &Scene,
+        probe_handle: Handle<Node>,
+        elapsed_time: f32,
+        on_face_captured: &mut dyn FnMut(&GpuTexture),
+    ) -> Result<SceneStatistics, FrameworkError> {
+        let mut statistics = SceneStatistics::default();
+
+        let graph = &scene.graph;
+        let Some(probe) = graph.try_get_of_type::<ReflectionProbe>(probe_handle) else {
+            return Ok(statistics);
+        };
+
+        let server = &*self.server;
+        let size = capture.resolution;
+        let viewport = Rect::new(0, 0, size as i32, size as i32);
+
+        // Use the sky and the environment of the first active camera, so the probe will look
+        // consistent with the rest of the scene.
+        let reference_camera = graph
+            .linear_iter()
+            .filter_map(|node| node.cast::<Camera>())
+            .find(|camera| camera.is_globally_enabled() && camera.is_enabled());
+        let mut camera = CameraBuilder::new(BaseBuilder::new())
+            .with_projection(Projection::Perspective(PerspectiveProjection {
+                fov: std::f32::consts::FRAC_PI_2,
+                z_near: probe.z_near(),
+                z_far: probe.z_far(),
+            }))
+            .with_specific_skybox(
+                reference_camera
+                    .and_then(|camera| camera.skybox_ref().cloned())
+                    .map_or(SkyBoxKind::None, SkyBoxKind::Specific),
+            )
+            .build_camera();
+        camera.set_environment(reference_camera.and_then(|camera| camera.environment_map()));
+
+        // Screen-space effects do not make much sense for tiny capture frames, and occlusion
+        // culling data is gathered for cameras only.
+        let mut quality_settings = self.quality_settings;
+        quality_settings.use_ssao = false;
+        quality_settings.use_occlusion_culling = false;
+        quality_settings.use_light_occlusion_culling = false;
+
+        let position = probe.global_position();
+        let visibility_cache = self.visibility_cache.get_or_register(graph, probe_handle);
+
+        for (face, look, up) in CUBE_MAP_FACES {
+            let side = up.cross(&look);
+            camera.global_transform.set(Matrix4::new(
+                side.x, up.x, look.x, position.x, //
+                side.y, up.y, look.y, position.y, //
+                side.z, up.z, look.z, position.z, //
+                0.0, 0.0, 0.0, 1.0,
+            ));
+            camera.calculate_matrices(Vector2::new(size as f32, size as f32));
+
+            let bundle_storage = RenderDataBundleStorage::from_graph(
+                graph,
+                elapsed_time,
+                ObserverInfo {
+                    observer_position: position,
+                    z_near: probe.z_near(),
+                    z_far: probe.z_far(),
+                    view_matrix: camera.view_matrix(),
+                    projection_matrix: camera.projection_matrix(),
+                },
+                GBUFFER_PASS_NAME.clone(),
+                RenderDataBundleStorageOptions {
+                    collect_lights: true,
+                },
+            );
+
+            statistics += capture.gbuffer.fill(GBufferRenderContext {
+                server,
+                camera: &camera,
+                geom_cache: &mut self.geometry_cache,
+                bundle_storage: &bundle_storage,
+                texture_cache: &mut self.texture_cache,
+                shader_cache: &mut self.shader_cache,
+                quality_settings: &quality_settings,
+                fallback_resources: &self.fallback_resources,
+                graph,
+                uniform_buffer_cache: &mut self.uniform_buffer_cache,
+                uniform_memory_allocator: &mut self.uniform_memory_allocator,
+                screen_space_debug_renderer: &mut self.screen_space_debug_renderer,
+                unit_quad: &*self.quad,
+                motion: None,
+            })?;
+
+            capture.gbuffer.framebuffer().blit_to(
+                &*capture.hdr_framebuffer,
+                0,
+                0,
+                capture.gbuffer.width,
+                capture.gbuffer.height,
+                0,
+                0,
+                capture.gbuffer.width,
+                capture.gbuffer.height,
+                false,
+                true,
+                true,
+            );
+
+            capture.hdr_framebuffer.clear(
+                viewport,
+                Some(
+                    scene
+                        .rendering_options
+                        .clear_color
+                        .unwrap_or(self.backbuffer_clear_color),
+                ),
+                None,
+                Some(0),
+            );
+
+            // Other probes are not used when capturing, otherwise the result would depend on
+            // the order of captures.
+            let (pass_stats, light_stats) =
+                self.deferred_light_renderer
+                    .render(DeferredRendererContext {
+                        elapsed_time,
+                        server,
+                        scene,
+                        camera: &camera,
+                        gbuffer: &mut capture.gbuffer,
+                        ambient_color: scene.rendering_options.ambient_lighting_color,
+                        render_data_bundle: &bundle_storage,
+                        settings: &quality_settings,
+                        textures: &mut self.texture_cache,
+                        geometry_cache: &mut self.geometry_cache,
+                        frame_buffer: &capture.hdr_framebuffer,
+                        ambient_frame_buffer: None,
+                        shader_cache: &mut self.shader_cache,
+                        fallback_resources: &self.fallback_resources,
+                        uniform_buffer_cache: &mut self.uniform_buffer_cache,
+                        visibility_cache,
+                        uniform_memory_allocator: &mut self.uniform_memory_allocator,
+                        reflection_probes: &[],
+                    })?;
+            statistics += pass_stats;
+            statistics += light_stats;
+
+            statistics += self.forward_renderer.render(ForwardRenderContext {
+                state: server,
+                geom_cache: &mut self.geometry_cache,
+                texture_cache: &mut self.texture_cache,
+                shader_cache: &mut self.shader_cache,
+                bundle_storage: &bundle_storage,
+                framebuffer: &capture.hdr_framebuffer,
+                viewport,
+                quality_settings: &quality_settings,
+                fallback_resources: &self.fallback_resources,
+                scene_depth: capture.gbuffer.depth(),
+                ambient_light: scene.rendering_options.ambient_lighting_color,
+                fog: scene.rendering_options.fog,
+                uniform_memory_allocator: &mut self.uniform_memory_allocator,
+            })?;
+
+            let hdr_frame_texture = capture.hdr_frame_texture();
+            capture.cube_map_framebuffer.set_cubemap_face(0, face);
+            statistics += blit_pixels(
+                &mut self.uniform_buffer_cache,
+                &capture.cube_map_framebuffer,
+                hdr_frame_texture.clone(),
+                &self.flat_shader,
+                viewport,
+                &*self.quad,
+            )?;
+            on_face_captured(&hdr_frame_texture);
+        }
+
+        // Rough surfaces use lower mip levels of the cube map as blurred reflections.
+        capture.cube_map().generate_mipmap();
+
+        Ok(statistics)
+    }
+
+    /// Captures the scene from the position of the given reflection probe and stores the result
+    /// in a cube map texture. The texture could then be assigned to the probe via
+    /// [`ReflectionProbe::set_baked_cube_map`], so the probe won't be captured at runtime anymore.
+    /// The texture is embedded and will be saved together with the scene.
+    pub fn bake_reflection_probe(
+        &mut self,
+        scene: &Scene,
+        probe_handle: Handle<Node>,
+    ) -> Result<TextureResource, FrameworkError> {
+        let resolution = scene
+            .graph
+            .try_get_of_type::<ReflectionProbe>(probe_handle)
+            .map(|probe| probe.resolution() as usize)
+            .ok_or_else(|| FrameworkError::Custom("Not a reflection probe!".to_string()))?;
+
+        let mut capture = ReflectionProbeCapture::new(&*self.server, resolution)?;
+
+        let mut bytes = Vec::with_capacity(6 * 6 * resolution * resolution);
+        self.capture_reflection_probe(&mut capture, scene, probe_handle, 0.0, &mut |frame| {
+            // Faces are captured in the order that cube map textures use. Alpha channel is not
+            // needed, so RGBA16F pixels are converted to RGB16F.
+            for pixel in frame.get_image(0).chunks_exact(8) {
+                bytes.extend_from_slice(&pixel[..6]);
+            }
+        })?;
+
+        let cube_map = TextureResource::from_bytes(
+            TextureKind::Cube {
+                width: resolution as u32,
+                height: resolution as u32,
+            },
+            TexturePixelKind::RGB16F,
+            bytes,
+            ResourceKind::Embedded,
+        )
+        .ok_or_else(|| FrameworkError::Custom("Unable to create a cube map!".to_string()))?;
+
+        let mut cube_map_ref = cube_map.data_ref();
+        cube_map_ref.set_s_wrap_mode(TextureWrapMode::ClampToEdge);
+        cube_map_ref.set_t_wrap_mode(TextureWrapMode::ClampToEdge);
+        cube_map_ref.set_r_wrap_mode(TextureWrapMode::ClampToEdge);
+        cube_map_ref.set_minification_filter(TextureMinificationFilter::LinearMipMapLinear);
+        drop(cube_map_ref);
+
+        Ok(cube_map)
+    }
+}
@@ -1,22 +1,153 @@
 uniform sampler2D diffuseTexture;
 uniform sampler2D aoSampler;
 uniform sampler2D ambientTexture;
+uniform sampler2D depthTexture;
+uniform sampler2D normalTexture;
+uniform sampler2D materialTexture;
+uniform samplerCube environmentTexture;
+uniform samplerCube probeTexture0;
+uniform samplerCube probeTexture1;
+uniform samplerCube probeTexture2;
+uniform samplerCube probeTexture3;
 
 layout (std140) uniform Uniforms {
     mat4 worldViewProjection;
+    mat4 invViewProj;
     vec4 ambientColor;
+    vec3 cameraPosition;
+    int probeCount;
+    float environmentMaxLod;
+    bool environmentIsSRGB;
+    // World-to-local transforms of reflection probes (without scaling).
+    mat4 probeWorldToLocal[4];
+    // xyz - world position of a probe, w - max lod of its cube map.
+    vec4 probePosition[4];
+    // Box - (half extents, 0.0), sphere - (radius, 0.0, 0.0, 1.0).
+    vec4 probeShape[4];
+    // x - blend distance, y - intensity, z - box projection flag.
+    vec4 probeParams[4];
 };
 
-out vec4 FragColor;
+layout(location = 0) out vec4 FragColor;
+// Image-based specular lighting, that was added to the frame. Screen-space reflections replace it
+// with the traced reflections.
+layout(location = 1) out vec4 SpecularColor;
 in vec2 texCoord;
 
+vec3 SampleProbe(int index, vec3 direction, float lod)
+{
+    // Samplers cannot be indexed dynamically in GLSL 3.30.
+    if (index == 0) {
+        return textureLod(probeTexture0, direction, lod).rgb;
+    } else if (index == 1) {
+        return textureLod(probeTexture1, direction, lod).rgb;
+    } else if (index == 2) {
+        return textureLod(probeTexture2, direction, lod).rgb;
+    }
+    return textureLod(probeTexture3, direction, lod).rgb;
+}
+
+// Returns influence of a probe in [0; 1] range. The influence fades out near the edges of the
+// influence volume of the probe.
+float ProbeInfluence(int index, vec3 worldPosition)
+{
+    vec3 localPosition = (probeWorldToLocal[index] * vec4(worldPosition, 1.0)).xyz;
+    vec4 shape = probeShape[index];
+    float distanceToEdge;
+    if (shape.w < 0.5) {
+        vec3 d = shape.xyz - abs(localPosition);
+        distanceToEdge = min(d.x, min(d.y, d.z));
+    } else {
+        distanceToEdge = shape.x - length(localPosition);
+    }
+    return clamp(distanceToEdge / max(probeParams[index].x, 0.0001), 0.0, 1.0);
+}
+
+// Returns a direction to fetch a cube map of the probe with. If box projection is enabled, the
+// reflection ray is intersected with the influence volume, so the reflections are parallax-correct.
+vec3 ProbeDirection(int index, vec3 worldPosition, vec3 reflection)
+{
+    if (probeParams[index].z < 0.5) {
+        return reflection;
+    }
+
+    vec4 shape = probeShape[index];
+    vec3 center = probePosition[index].xyz;
+    if (shape.w < 0.5) {
+        vec3 localPosition = (probeWorldToLocal[index] * vec4(worldPosition, 1.0)).xyz;
+        vec3 localReflection = mat3(probeWorldToLocal[index]) * reflection;
+        vec3 firstPlane = (shape.xyz - localPosition) / localReflection;
+        vec3 secondPlane = (-shape.xyz - localPosition) / localReflection;
+        vec3 furthestPlane = max(firstPlane, secondPlane);
+        float t = min(furthestPlane.x, min(furthestPlane.y, furthestPlane.z));
+        return worldPosition + reflection * t - center;
+    } else {
+        float minT, maxT;
+        if (S_RaySphereIntersection(worldPosition, reflection, center, shape.x, minT, maxT)) {
+            return worldPosition + reflection * maxT - center;
+        }
+        return reflection;
+    }
+}
+
+vec3 FresnelSchlickRoughness(float cosTheta, vec3 F0, float roughness)
+{
+    return F0 + (max(vec3(1.0 - roughness), F0) - F0) * pow(clamp(1.0 - cosTheta, 0.0, 1.0), 5.0);
+}
+
 void main()
 {
     float ambientOcclusion = texture(aoSampler, texCoord).r;
     vec4 ambientPixel = texture(ambientTexture, texCoord);
-    FragColor = (ambientColor + ambientPixel) * S_SRGBToLinear(texture(diffuseTexture, texCoord));
+    vec4 albedo = S_SRGBToLinear(texture(diffuseTexture, texCoord));
+    FragColor = (ambientColor + ambientPixel) * albedo;
+    vec3 specularLighting = vec3(0.0);
+
+    // Image-based specular lighting from reflection probes and the environment map.
+    float depth = texture(depthTexture, texCoord).r;
+    if (depth < 1.0) {
+        vec3 material = texture(materialTexture, texCoord).rgb;
+        float metallic = material.x;
+        float roughness = material.y;
+
+        vec3 fragmentPosition = S_UnProject(vec3(texCoord, depth), invViewProj);
+        vec3 N = normalize(texture(normalTexture, texCoord).xyz * 2.0 - 1.0);
+        vec3 V = normalize(cameraPosition - fragmentPosition);
+        vec3 R = reflect(-V, N);
+
+        vec3 specular = vec3(0.0);
+        float totalWeight = 0.0;
+        for (int i = 0; i < probeCount; ++i) {
+            float weight = ProbeInfluence(i, fragmentPosition);
+            if (weight > 0.0) {
+                vec3 direction = ProbeDirection(i, fragmentPosition, R);
+                float lod = roughness * probePosition[i].w;
+                specular += weight * probeParams[i].y * SampleProbe(i, direction, lod);
+                totalWeight += weight;
+            }
+        }
+        if (totalWeight > 1.0) {
+            specular /= totalWeight;
+            totalWeight = 1.0;
+        }
+
+        // The rest is filled with the environment map.
+        vec4 environment = textureLod(environmentTexture, R, roughness * environmentMaxLod);
+        if (environmentIsSRGB) {
+            environment = S_SRGBToLinear(environment);
+        }
+        specular += (1.0 - totalWeight) * environment.rgb;
+
+        vec3 F0 = mix(vec3(0.04), albedo.rgb, metallic);
+        vec3 F = FresnelSchlickRoughness(max(dot(N, V), 0.0), F0, roughness);
+        specularLighting = F * specular;
+        FragColor.rgb += specularLighting;
+    }
+
     FragColor.rgb *= ambientOcclusion;
     FragColor.a = ambientPixel.a;
 
-    // TODO: Implement IBL.
+    // The frame is blended using the alpha of the ambient pixel, store the exact amount of
+    // specular lighting that will end up in the frame.
+    SpecularColor = vec4(specularLighting * ambientOcclusion * ambientPixel.a, 1.0);
 }
\ No newline at end of file
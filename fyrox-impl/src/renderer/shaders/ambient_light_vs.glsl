@@ -3,7 +3,16 @@ layout (location = 1) in vec2 vertexTexCoord;
 
 layout (std140) uniform Uniforms {
     mat4 worldViewProjection;
+    mat4 invViewProj;
     vec4 ambientColor;
+    vec3 cameraPosition;
+    int probeCount;
+    float environmentMaxLod;
+    bool environmentIsSRGB;
+    mat4 probeWorldToLocal[4];
+    vec4 probePosition[4];
+    vec4 probeShape[4];
+    vec4 probeParams[4];
 };
 
 out vec2 texCoord;
@@ -0,0 +1,64 @@
+uniform sampler2D frameTexture;
+uniform sampler2D depthTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 inverseProjectionMatrix;
+    vec2 inverseScreenSize;
+    float focusDistance;
+    float cocScale;
+    float maxBlurRadius;
+};
+
+in vec2 texCoord;
+out vec4 fragColor;
+
+#define SAMPLE_COUNT 48
+#define GOLDEN_ANGLE 2.39996323
+
+float Distance(vec2 screenCoord)
+{
+    float depth = texture(depthTexture, screenCoord).r;
+    return -S_UnProject(vec3(screenCoord, depth), inverseProjectionMatrix).z;
+}
+
+// Returns radius of the circle of confusion (in pixels) for the given distance.
+float CircleOfConfusion(float distance)
+{
+    float diameter = cocScale * abs(distance - focusDistance) / max(distance, 0.0001);
+    return min(0.5 * diameter, maxBlurRadius);
+}
+
+void main()
+{
+    vec4 center = texture(frameTexture, texCoord);
+    float centerDistance = Distance(texCoord);
+    float centerCoc = CircleOfConfusion(centerDistance);
+
+    vec3 color = center.rgb;
+    float totalWeight = 1.0;
+    for (int i = 1; i < SAMPLE_COUNT; ++i) {
+        // Points of golden angle spiral are uniformly distributed over the disc, which gives
+        // round bokeh.
+        float radius = sqrt(float(i) / float(SAMPLE_COUNT)) * maxBlurRadius;
+        float angle = float(i) * GOLDEN_ANGLE;
+        vec2 sampleCoord = texCoord + vec2(cos(angle), sin(angle)) * radius * inverseScreenSize;
+
+        float sampleDistance = Distance(sampleCoord);
+        float sampleCoc = CircleOfConfusion(sampleDistance);
+
+        // Background samples can't be blurrier than the current pixel, this prevents sharp objects
+        // in focus from bleeding on the blurry background.
+        if (sampleDistance > centerDistance) {
+            sampleCoc = min(sampleCoc, centerCoc);
+        }
+
+        // The sample contributes only if its circle of confusion covers the current pixel.
+        float weight = smoothstep(radius - 1.0, radius + 1.0, sampleCoc);
+
+        color += texture(frameTexture, sampleCoord).rgb * weight;
+        totalWeight += weight;
+    }
+
+    fragColor = vec4(color / totalWeight, center.a);
+}
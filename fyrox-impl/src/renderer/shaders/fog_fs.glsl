@@ -0,0 +1,42 @@
+uniform sampler2D depthTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 invViewProj;
+    vec4 fogColor;
+    vec3 cameraPosition;
+    int fogMode;
+    float fogStart;
+    float fogEnd;
+    float fogDensity;
+    float fogHeight;
+    float fogHeightFalloff;
+};
+
+out vec4 FragColor;
+in vec2 texCoord;
+
+void main()
+{
+    float depth = texture(depthTexture, texCoord).r;
+
+    // Skip the sky.
+    if (depth >= 1.0) {
+        discard;
+    }
+
+    vec3 fragmentPosition = S_UnProject(vec3(texCoord, depth), invViewProj);
+
+    float fogFactor = S_FogFactor(
+        fogMode,
+        cameraPosition,
+        fragmentPosition,
+        fogStart,
+        fogEnd,
+        fogDensity,
+        fogHeight,
+        fogHeightFalloff
+    );
+
+    FragColor = vec4(fogColor.rgb, fogFactor * fogColor.a);
+}
@@ -0,0 +1,23 @@
+layout (location = 0) in vec3 vertexPosition;
+layout (location = 1) in vec2 vertexTexCoord;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 invViewProj;
+    vec4 fogColor;
+    vec3 cameraPosition;
+    int fogMode;
+    float fogStart;
+    float fogEnd;
+    float fogDensity;
+    float fogHeight;
+    float fogHeightFalloff;
+};
+
+out vec2 texCoord;
+
+void main()
+{
+    texCoord = vertexTexCoord;
+    gl_Position = worldViewProjection * vec4(vertexPosition, 1.0);
+}
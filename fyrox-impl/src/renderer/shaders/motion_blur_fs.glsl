@@ -0,0 +1,53 @@
+uniform sampler2D frameTexture;
+uniform sampler2D velocityTexture;
+uniform sampler2D depthTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 inverseViewProjection;
+    mat4 previousViewProjection;
+    vec2 screenSize;
+    float shutter;
+    float maxBlurRadius;
+    int sampleCount;
+};
+
+in vec2 texCoord;
+out vec4 fragColor;
+
+void main()
+{
+    vec4 center = texture(frameTexture, texCoord);
+
+    vec2 velocity;
+    float depth = texture(depthTexture, texCoord).r;
+    if (depth < 1.0) {
+        velocity = texture(velocityTexture, texCoord).xy;
+    } else {
+        // There's no geometry (sky, background), reproject the far plane using camera motion only.
+        vec3 worldPosition = S_UnProject(vec3(texCoord, 1.0), inverseViewProjection);
+        vec4 previousClipPosition = previousViewProjection * vec4(worldPosition, 1.0);
+        vec2 previousTexCoord = previousClipPosition.xy / previousClipPosition.w * 0.5 + 0.5;
+        velocity = texCoord - previousTexCoord;
+    }
+
+    // The velocity is measured per frame, only a fraction of it is visible during the exposure.
+    velocity *= shutter;
+
+    float blurLength = length(velocity * screenSize);
+    if (blurLength < 0.5) {
+        fragColor = center;
+        return;
+    }
+    if (blurLength > maxBlurRadius) {
+        velocity *= maxBlurRadius / blurLength;
+    }
+
+    vec3 color = vec3(0.0);
+    for (int i = 0; i < sampleCount; ++i) {
+        float t = float(i) / float(sampleCount - 1) - 0.5;
+        color += texture(frameTexture, texCoord - velocity * t).rgb;
+    }
+
+    fragColor = vec4(color / float(sampleCount), center.a);
+}
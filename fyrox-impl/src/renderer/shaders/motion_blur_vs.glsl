@@ -0,0 +1,20 @@
+layout (location = 0) in vec3 vertexPosition;
+layout (location = 1) in vec2 vertexTexCoord;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 inverseViewProjection;
+    mat4 previousViewProjection;
+    vec2 screenSize;
+    float shutter;
+    float maxBlurRadius;
+    int sampleCount;
+};
+
+out vec2 texCoord;
+
+void main()
+{
+    texCoord = vertexTexCoord;
+    gl_Position = worldViewProjection * vec4(vertexPosition, 1.0);
+}
@@ -0,0 +1,58 @@
+// Advances the state of the particles by a single time step. Each particle occupies 5 consecutive
+// texels of the state texture:
+// 0 - xyz - position, w - age.
+// 1 - xyz - velocity, w - lifetime.
+// 2 - x - size, y - size modifier, z - rotation, w - rotation speed.
+// 3 - rgba - color.
+// 4 - rgba - tint.
+// Every fragment writes a single texel, so each texel must replicate the calculations it depends
+// on. The order of the calculations must match the CPU simulation.
+
+uniform sampler2D stateTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    // xyz - acceleration multiplied by squared time step, w - time step.
+    vec4 accelerationDt;
+    vec4 colorOverLifetime[32];
+};
+
+out vec4 FragColor;
+
+void main()
+{
+    ivec2 texel = ivec2(gl_FragCoord.xy);
+    int component = texel.x % 5;
+    ivec2 firstTexel = ivec2(texel.x - component, texel.y);
+
+    vec4 current = texelFetch(stateTexture, texel, 0);
+    vec4 positionAge = texelFetch(stateTexture, firstTexel, 0);
+    vec4 velocityLifetime = texelFetch(stateTexture, firstTexel + ivec2(1, 0), 0);
+
+    float dt = accelerationDt.w;
+    float lifetime = velocityLifetime.w;
+    float age = positionAge.w + dt;
+
+    // Dead particles stay dead until a new particle is spawned in their slot.
+    if (positionAge.w >= lifetime || age >= lifetime) {
+        FragColor = component == 0 ? vec4(positionAge.xyz, max(age, lifetime)) : current;
+        return;
+    }
+
+    vec3 velocity = velocityLifetime.xyz + accelerationDt.xyz;
+
+    if (component == 0) {
+        FragColor = vec4(positionAge.xyz + velocity, age);
+    } else if (component == 1) {
+        FragColor = vec4(velocity, lifetime);
+    } else if (component == 2) {
+        FragColor = vec4(max(current.x + current.y * dt, 0.0), current.y, current.z + current.w * dt, current.w);
+    } else if (component == 3) {
+        vec4 tint = texelFetch(stateTexture, firstTexel + ivec2(4, 0), 0);
+        float k = clamp(age / lifetime, 0.0, 1.0) * 31.0;
+        int index = min(int(k), 30);
+        FragColor = mix(colorOverLifetime[index], colorOverLifetime[index + 1], k - float(index)) * tint;
+    } else {
+        FragColor = current;
+    }
+}
@@ -0,0 +1,12 @@
+layout (location = 0) in vec3 vertexPosition;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    vec4 accelerationDt;
+    vec4 colorOverLifetime[32];
+};
+
+void main()
+{
+    gl_Position = worldViewProjection * vec4(vertexPosition, 1.0);
+}
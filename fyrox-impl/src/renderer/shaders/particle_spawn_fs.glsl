@@ -0,0 +1,8 @@
+in vec4 value;
+
+out vec4 FragColor;
+
+void main()
+{
+    FragColor = value;
+}
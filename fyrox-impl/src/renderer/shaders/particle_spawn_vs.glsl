@@ -0,0 +1,17 @@
+// Each point writes a single texel of the state texture of a particle system.
+
+layout (location = 0) in vec2 texelPosition;
+layout (location = 1) in vec4 texelValue;
+
+layout (std140) uniform Uniforms {
+    vec2 inverseTextureSize;
+};
+
+out vec4 value;
+
+void main()
+{
+    value = texelValue;
+    gl_PointSize = 1.0;
+    gl_Position = vec4((texelPosition + 0.5) * inverseTextureSize * 2.0 - 1.0, 0.0, 1.0);
+}
@@ -0,0 +1,57 @@
+uniform sampler2D reflectionTexture;
+uniform sampler2D depthTexture;
+uniform sampler2D diffuseTexture;
+uniform sampler2D normalTexture;
+uniform sampler2D materialTexture;
+uniform sampler2D specularTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 inverseViewProjection;
+    vec3 cameraPosition;
+    float intensity;
+    float maxLod;
+};
+
+in vec2 texCoord;
+out vec4 fragColor;
+
+void main()
+{
+    float depth = texture(depthTexture, texCoord).r;
+    if (depth >= 1.0) {
+        discard;
+    }
+
+    vec4 material = texture(materialTexture, texCoord);
+    float metallic = material.x;
+    float roughness = material.y;
+
+    // Rough surfaces fetch reflections from coarser mip levels, which gives blurry reflections.
+    // Alpha contains the confidence of the hit, the color is premultiplied by it.
+    vec4 reflection = textureLod(reflectionTexture, texCoord, roughness * maxLod);
+    if (reflection.a < 0.001) {
+        discard;
+    }
+    vec3 reflectedColor = reflection.rgb / reflection.a;
+
+    vec3 worldPosition = S_UnProject(vec3(texCoord, depth), inverseViewProjection);
+    vec3 N = normalize(texture(normalTexture, texCoord).xyz * 2.0 - 1.0);
+    vec3 V = normalize(cameraPosition - worldPosition);
+    vec3 albedo = S_SRGBToLinear(texture(diffuseTexture, texCoord)).rgb;
+
+    vec3 F0 = mix(vec3(0.04), albedo, metallic);
+    float NdotV = max(dot(N, V), 0.0);
+    vec3 F = F0 + (max(vec3(1.0 - roughness), F0) - F0) * pow(1.0 - NdotV, 5.0);
+
+    float smoothness = 1.0 - roughness;
+    float weight = clamp(reflection.a * smoothness * smoothness, 0.0, 1.0);
+
+    // The frame already contains image-based specular lighting of the ambient pass (sky box,
+    // reflection probes). Replace it with the traced reflections according to the confidence of
+    // the hit, otherwise the pixel will get both reflections. The frame is in floating-point
+    // format, so the additive blending works with negative values as well.
+    vec3 environmentSpecular = texture(specularTexture, texCoord).rgb;
+    vec3 tracedSpecular = reflectedColor * F * intensity;
+    fragColor = vec4(weight * (tracedSpecular - environmentSpecular), 0.0);
+}
@@ -0,0 +1,102 @@
+uniform sampler2D frameTexture;
+uniform sampler2D depthTexture;
+uniform sampler2D normalTexture;
+uniform sampler2D materialTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 projectionMatrix;
+    mat4 inverseProjectionMatrix;
+    mat4 viewMatrix;
+    mat4 inverseViewMatrix;
+    int maxSteps;
+    float maxDistance;
+    float thickness;
+};
+
+in vec2 texCoord;
+out vec4 fragColor;
+
+// Amount of binary search iterations to refine the hit point.
+#define REFINEMENT_STEPS 6
+
+vec3 GetViewPosition(vec2 screenCoord)
+{
+    float depth = texture(depthTexture, screenCoord).r;
+    return S_UnProject(vec3(screenCoord, depth), inverseProjectionMatrix);
+}
+
+bool IsOffScreen(vec3 screenPosition)
+{
+    return any(lessThan(screenPosition, vec3(0.0))) || any(greaterThan(screenPosition, vec3(1.0)));
+}
+
+void main()
+{
+    float depth = texture(depthTexture, texCoord).r;
+    if (depth >= 1.0) {
+        // Nothing to reflect on the background.
+        fragColor = vec4(0.0);
+        return;
+    }
+
+    vec3 viewPosition = S_UnProject(vec3(texCoord, depth), inverseProjectionMatrix);
+    vec3 worldNormal = normalize(texture(normalTexture, texCoord).xyz * 2.0 - 1.0);
+    vec3 viewNormal = normalize(mat3(viewMatrix) * worldNormal);
+    vec3 reflectedDir = normalize(reflect(normalize(viewPosition), viewNormal));
+
+    // Very rough surfaces have almost invisible reflections, do not waste time tracing them.
+    float roughness = texture(materialTexture, texCoord).g;
+    if (roughness > 0.9) {
+        fragColor = vec4(0.0);
+        return;
+    }
+
+    vec3 rayStep = reflectedDir * (maxDistance / float(maxSteps));
+    // Start a bit above the surface to prevent self-intersection.
+    vec3 rayPosition = viewPosition + viewNormal * 0.01;
+
+    for (int i = 0; i < maxSteps; ++i) {
+        rayPosition += rayStep;
+
+        // The ray went behind the camera.
+        if (rayPosition.z >= 0.0) {
+            break;
+        }
+
+        vec3 screenPosition = S_Project(rayPosition, projectionMatrix);
+        if (IsOffScreen(screenPosition)) {
+            break;
+        }
+
+        float delta = GetViewPosition(screenPosition.xy).z - rayPosition.z;
+        if (delta > 0.0 && delta < thickness) {
+            // The ray is behind a surface, find precise intersection point using binary search.
+            vec3 refinementStep = rayStep;
+            for (int j = 0; j < REFINEMENT_STEPS; ++j) {
+                refinementStep *= 0.5;
+                screenPosition = S_Project(rayPosition, projectionMatrix);
+                if (GetViewPosition(screenPosition.xy).z - rayPosition.z > 0.0) {
+                    rayPosition -= refinementStep;
+                } else {
+                    rayPosition += refinementStep;
+                }
+            }
+
+            vec2 hitCoord = S_Project(rayPosition, projectionMatrix).xy;
+
+            // Fade out reflections near the screen edges and at the end of the ray to hide
+            // discontinuities between traced reflections and the fallback.
+            vec2 edgeFade = smoothstep(0.0, 0.1, hitCoord) * (1.0 - smoothstep(0.9, 1.0, hitCoord));
+            float confidence = edgeFade.x * edgeFade.y * (1.0 - float(i) / float(maxSteps));
+
+            // Color is premultiplied by the confidence, so the reflections could be correctly
+            // blurred using mip levels.
+            fragColor = vec4(texture(frameTexture, hitCoord).rgb * confidence, confidence);
+            return;
+        }
+    }
+
+    // The ray missed, image-based reflections of the ambient lighting pass will be used instead.
+    fragColor = vec4(0.0);
+}
@@ -0,0 +1,22 @@
+layout (location = 0) in vec3 vertexPosition;
+layout (location = 1) in vec2 vertexTexCoord;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 projectionMatrix;
+    mat4 inverseProjectionMatrix;
+    mat4 viewMatrix;
+    mat4 inverseViewMatrix;
+    int maxSteps;
+    float maxDistance;
+    float thickness;
+    bool hasSkyBox;
+};
+
+out vec2 texCoord;
+
+void main()
+{
+    texCoord = vertexTexCoord;
+    gl_Position = worldViewProjection * vec4(vertexPosition, 1.0);
+}
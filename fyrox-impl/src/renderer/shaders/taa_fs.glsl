@@ -0,0 +1,70 @@
+uniform sampler2D currentTexture;
+uniform sampler2D historyTexture;
+uniform sampler2D velocityTexture;
+uniform sampler2D depthTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 inverseViewProjection;
+    mat4 previousViewProjection;
+    vec2 inverseScreenSize;
+    float currentFrameWeight;
+    bool historyValid;
+};
+
+in vec2 texCoord;
+out vec4 fragColor;
+
+void main()
+{
+    vec3 current = texture(currentTexture, texCoord).rgb;
+
+    if (!historyValid) {
+        fragColor = vec4(current, 1.0);
+        return;
+    }
+
+    // Gather color bounds of 3x3 neighbourhood for history clamping and find the closest to
+    // the camera pixel, its velocity gives better results on the edges of moving objects.
+    vec3 minColor = current;
+    vec3 maxColor = current;
+    float closestDepth = 1.0;
+    vec2 closestTexCoord = texCoord;
+    for (int y = -1; y <= 1; ++y) {
+        for (int x = -1; x <= 1; ++x) {
+            vec2 sampleTexCoord = texCoord + vec2(x, y) * inverseScreenSize;
+
+            vec3 color = texture(currentTexture, sampleTexCoord).rgb;
+            minColor = min(minColor, color);
+            maxColor = max(maxColor, color);
+
+            float depth = texture(depthTexture, sampleTexCoord).r;
+            if (depth < closestDepth) {
+                closestDepth = depth;
+                closestTexCoord = sampleTexCoord;
+            }
+        }
+    }
+
+    vec2 velocity;
+    if (closestDepth < 1.0) {
+        velocity = texture(velocityTexture, closestTexCoord).xy;
+    } else {
+        // There's no geometry (sky, background), reproject the far plane using camera motion only.
+        vec3 worldPosition = S_UnProject(vec3(texCoord, 1.0), inverseViewProjection);
+        vec4 previousClipPosition = previousViewProjection * vec4(worldPosition, 1.0);
+        vec2 previousTexCoord = previousClipPosition.xy / previousClipPosition.w * 0.5 + 0.5;
+        velocity = texCoord - previousTexCoord;
+    }
+
+    vec2 historyTexCoord = texCoord - velocity;
+    if (any(lessThan(historyTexCoord, vec2(0.0))) || any(greaterThan(historyTexCoord, vec2(1.0)))) {
+        // History is off-screen, nothing to blend with.
+        fragColor = vec4(current, 1.0);
+        return;
+    }
+
+    vec3 history = clamp(texture(historyTexture, historyTexCoord).rgb, minColor, maxColor);
+
+    fragColor = vec4(mix(history, current, currentFrameWeight), 1.0);
+}
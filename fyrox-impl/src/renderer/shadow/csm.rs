@@ -284,6 +284,8 @@ impl CsmRenderer {
                     fallback_resources,
                     ambient_light: Color::WHITE, // TODO
                     scene_depth: None,
+                    fog: Default::default(),
+                    motion: None,
                 },
             )?;
         }
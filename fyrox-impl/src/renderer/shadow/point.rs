@@ -252,6 +252,8 @@ impl PointShadowMapRenderer {
                     fallback_resources,
                     ambient_light: Color::WHITE, // TODO
                     scene_depth: None,
+                    fog: Default::default(),
+                    motion: None,
                 },
             )?;
         }
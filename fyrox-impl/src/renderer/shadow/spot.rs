@@ -175,6 +175,8 @@ impl SpotShadowMapRenderer {
                 fallback_resources,
                 ambient_light: Color::WHITE, // TODO
                 scene_depth: None,
+                fog: Default::default(),
+                motion: None,
             },
         )?;
 
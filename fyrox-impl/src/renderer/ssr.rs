@@ -0,0 +1,346 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Screen-space reflections (SSR). Reflected rays are ray-marched in view space against the depth
+//! buffer of the G-Buffer, the lit frame is sampled at the hit points. The result is rendered in
+//! half resolution, then a mip chain is generated for it, so rough surfaces could fetch blurrier
+//! reflections from the coarser mips when the reflections are composed with the frame. The traced
+//! reflections replace image-based specular lighting (sky box, reflection probes) of the ambient
+//! lighting pass according to the confidence of the hit, rays that miss keep image-based
+//! reflections.
+
+use crate::{
+    core::{algebra::Matrix4, color::Color, math::Rect, sstorage::ImmutableString},
+    renderer::{
+        cache::uniform::UniformBufferCache,
+        framework::{
+            buffer::BufferUsage,
+            error::FrameworkError,
+            framebuffer::{
+                Attachment, AttachmentKind, BufferLocation, GpuFrameBuffer, ResourceBindGroup,
+                ResourceBinding,
+            },
+            geometry_buffer::GpuGeometryBuffer,
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                GpuTexture, GpuTextureDescriptor, GpuTextureKind, MagnificationFilter,
+                MinificationFilter, PixelKind, WrapMode,
+            },
+            server::GraphicsServer,
+            uniform::StaticUniformBuffer,
+            BlendFactor, BlendFunc, BlendParameters, DrawParameters, ElementRange,
+            GeometryBufferExt,
+        },
+        gbuffer::GBuffer,
+        make_viewport_matrix, RenderPassStatistics,
+    },
+    scene::{camera::ScreenSpaceReflections, mesh::surface::SurfaceData},
+};
+
+struct TraceShader {
+    program: GpuProgram,
+    uniform_buffer_binding: usize,
+    frame_texture: UniformLocation,
+    depth_texture: UniformLocation,
+    normal_texture: UniformLocation,
+    material_texture: UniformLocation,
+}
+
+impl TraceShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/ssr_fs.glsl");
+        let vertex_source = include_str!("shaders/ssr_vs.glsl");
+
+        let program = server.create_program("SsrShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            frame_texture: program.uniform_location(&ImmutableString::new("frameTexture"))?,
+            depth_texture: program.uniform_location(&ImmutableString::new("depthTexture"))?,
+            normal_texture: program.uniform_location(&ImmutableString::new("normalTexture"))?,
+            material_texture: program.uniform_location(&ImmutableString::new("materialTexture"))?,
+            program,
+        })
+    }
+}
+
+struct CompositeShader {
+    program: GpuProgram,
+    uniform_buffer_binding: usize,
+    reflection_texture: UniformLocation,
+    depth_texture: UniformLocation,
+    diffuse_texture: UniformLocation,
+    normal_texture: UniformLocation,
+    material_texture: UniformLocation,
+    specular_texture: UniformLocation,
+}
+
+impl CompositeShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/ssr_composite_fs.glsl");
+        let vertex_source = include_str!("shaders/ssr_composite_vs.glsl");
+
+        let program =
+            server.create_program("SsrCompositeShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            reflection_texture: program
+                .uniform_location(&ImmutableString::new("reflectionTexture"))?,
+            depth_texture: program.uniform_location(&ImmutableString::new("depthTexture"))?,
+            diffuse_texture: program.uniform_location(&ImmutableString::new("diffuseTexture"))?,
+            normal_texture: program.uniform_location(&ImmutableString::new("normalTexture"))?,
+            material_texture: program.uniform_location(&ImmutableString::new("materialTexture"))?,
+            specular_texture: program.uniform_location(&ImmutableString::new("specularTexture"))?,
+            program,
+        })
+    }
+}
+
+pub(crate) struct SsrRenderContext<'a> {
+    pub gbuffer: &'a GBuffer,
+    /// Lit frame, that will be used to fetch reflected colors.
+    pub frame_texture: GpuTexture,
+    /// Frame buffer to which the reflections will be added.
+    pub frame_buffer: &'a GpuFrameBuffer,
+    pub viewport: Rect<i32>,
+    pub settings: &'a ScreenSpaceReflections,
+    pub view_matrix: Matrix4<f32>,
+    pub projection_matrix: Matrix4<f32>,
+    pub uniform_buffer_cache: &'a mut UniformBufferCache,
+}
+
+pub struct ScreenSpaceReflectionsRenderer {
+    trace_shader: TraceShader,
+    composite_shader: CompositeShader,
+    framebuffer: GpuFrameBuffer,
+    lighting_framebuffer: GpuFrameBuffer,
+    quad: GpuGeometryBuffer,
+    width: i32,
+    height: i32,
+}
+
+impl ScreenSpaceReflectionsRenderer {
+    /// Creates new renderer. `frame_texture` is the HDR frame texture of the scene, it is used to
+    /// create the frame buffer for the ambient lighting pass (see [`Self::lighting_framebuffer`]).
+    pub fn new(
+        server: &dyn GraphicsServer,
+        frame_width: usize,
+        frame_height: usize,
+        frame_texture: GpuTexture,
+    ) -> Result<Self, FrameworkError> {
+        // Reflections are blurry on majority of surfaces anyway, so there's no need to trace them
+        // in full resolution.
+        let width = (frame_width / 2).max(1);
+        let height = (frame_height / 2).max(1);
+
+        let reflection_texture = server.create_texture(GpuTextureDescriptor {
+            kind: GpuTextureKind::Rectangle { width, height },
+            pixel_kind: PixelKind::RGBA16F,
+            min_filter: MinificationFilter::LinearMipMapLinear,
+            mag_filter: MagnificationFilter::Linear,
+            s_wrap_mode: WrapMode::ClampToEdge,
+            t_wrap_mode: WrapMode::ClampToEdge,
+            r_wrap_mode: WrapMode::ClampToEdge,
+            ..Default::default()
+        })?;
+
+        let specular_texture =
+            server.create_2d_render_target(PixelKind::RGBA16F, frame_width, frame_height)?;
+
+        Ok(Self {
+            trace_shader: TraceShader::new(server)?,
+            composite_shader: CompositeShader::new(server)?,
+            framebuffer: server.create_frame_buffer(
+                None,
+                vec![Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: reflection_texture,
+                }],
+            )?,
+            lighting_framebuffer: server.create_frame_buffer(
+                None,
+                vec![
+                    Attachment {
+                        kind: AttachmentKind::Color,
+                        texture: frame_texture,
+                    },
+                    Attachment {
+                        kind: AttachmentKind::Color,
+                        texture: specular_texture,
+                    },
+                ],
+            )?,
+            quad: GpuGeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                BufferUsage::StaticDraw,
+                server,
+            )?,
+            width: width as i32,
+            height: height as i32,
+        })
+    }
+
+    pub fn reflection_texture(&self) -> GpuTexture {
+        self.framebuffer.color_attachments()[0].texture.clone()
+    }
+
+    /// Returns a frame buffer for the ambient lighting pass. Its first attachment is the frame
+    /// texture and the second one receives image-based specular lighting, which is then replaced
+    /// with the traced reflections.
+    pub fn lighting_framebuffer(&self) -> &GpuFrameBuffer {
+        &self.lighting_framebuffer
+    }
+
+    fn specular_texture(&self) -> GpuTexture {
+        self.lighting_framebuffer.color_attachments()[1]
+            .texture
+            .clone()
+    }
+
+    fn max_lod(&self) -> f32 {
+        (self.width.max(self.height).max(1) as f32).log2().floor()
+    }
+
+    pub(crate) fn render(
+        &self,
+        args: SsrRenderContext,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let SsrRenderContext {
+            gbuffer,
+            frame_texture,
+            frame_buffer,
+            viewport,
+            settings,
+            view_matrix,
+            projection_matrix,
+            uniform_buffer_cache,
+        } = args;
+
+        let mut stats = RenderPassStatistics::default();
+
+        let ssr_viewport = Rect::new(0, 0, self.width, self.height);
+
+        self.framebuffer
+            .clear(ssr_viewport, Some(Color::from_rgba(0, 0, 0, 0)), None, None);
+
+        let inv_view = view_matrix.try_inverse().unwrap_or_default();
+        let inv_projection = projection_matrix.try_inverse().unwrap_or_default();
+
+        let shader = &self.trace_shader;
+        stats += self.framebuffer.draw(
+            &*self.quad,
+            ssr_viewport,
+            &*shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: None,
+                blend: None,
+                stencil_op: Default::default(),
+                scissor_box: None,
+            },
+            &[ResourceBindGroup {
+                bindings: &[
+                    ResourceBinding::texture(&frame_texture, &shader.frame_texture),
+                    ResourceBinding::texture(&gbuffer.depth(), &shader.depth_texture),
+                    ResourceBinding::texture(&gbuffer.normal_texture(), &shader.normal_texture),
+                    ResourceBinding::texture(&gbuffer.material_texture(), &shader.material_texture),
+                    ResourceBinding::Buffer {
+                        buffer: uniform_buffer_cache.write(
+                            StaticUniformBuffer::<1024>::new()
+                                .with(&make_viewport_matrix(ssr_viewport))
+                                .with(&projection_matrix)
+                                .with(&inv_projection)
+                                .with(&view_matrix)
+                                .with(&inv_view)
+                                .with(&(settings.max_steps.max(1) as i32))
+                                .with(&settings.max_distance)
+                                .with(&settings.thickness),
+                        )?,
+                        binding: BufferLocation::Auto {
+                            shader_location: shader.uniform_buffer_binding,
+                        },
+                        data_usage: Default::default(),
+                    },
+                ],
+            }],
+            ElementRange::Full,
+        )?;
+
+        // Mip chain is used to blur the reflections on rough surfaces.
+        let reflection_texture = self.reflection_texture();
+        reflection_texture.generate_mipmap();
+
+        let inv_view_proj = (projection_matrix * view_matrix)
+            .try_inverse()
+            .unwrap_or_default();
+        let camera_position = inv_view.column(3).xyz();
+
+        let shader = &self.composite_shader;
+        stats += frame_buffer.draw(
+            &*self.quad,
+            viewport,
+            &*shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: None,
+                blend: Some(BlendParameters {
+                    func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                    ..Default::default()
+                }),
+                stencil_op: Default::default(),
+                scissor_box: None,
+            },
+            &[ResourceBindGroup {
+                bindings: &[
+                    ResourceBinding::texture(&reflection_texture, &shader.reflection_texture),
+                    ResourceBinding::texture(&gbuffer.depth(), &shader.depth_texture),
+                    ResourceBinding::texture(&gbuffer.diffuse_texture(), &shader.diffuse_texture),
+                    ResourceBinding::texture(&gbuffer.normal_texture(), &shader.normal_texture),
+                    ResourceBinding::texture(&gbuffer.material_texture(), &shader.material_texture),
+                    ResourceBinding::texture(&self.specular_texture(), &shader.specular_texture),
+                    ResourceBinding::Buffer {
+                        buffer: uniform_buffer_cache.write(
+                            StaticUniformBuffer::<512>::new()
+                                .with(&make_viewport_matrix(viewport))
+                                .with(&inv_view_proj)
+                                .with(&camera_position)
+                                .with(&settings.intensity)
+                                .with(&self.max_lod()),
+                        )?,
+                        binding: BufferLocation::Auto {
+                            shader_location: shader.uniform_buffer_binding,
+                        },
+                        data_usage: Default::default(),
+                    },
+                ],
+            }],
+            ElementRange::Full,
+        )?;
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,405 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Temporal anti-aliasing (TAA). The camera projection is jittered by a sub-pixel offset each
+//! frame and the current frame is blended with the reprojected history of the previous frames.
+//! Reprojection uses the velocity buffer of the G-Buffer, history color is clamped to the 3x3
+//! neighbourhood of the current pixel to reduce ghosting.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector2, Vector3},
+        math::Rect,
+        pool::Handle,
+        sstorage::ImmutableString,
+    },
+    graph::SceneGraph,
+    renderer::{
+        cache::uniform::UniformBufferCache,
+        framework::{
+            buffer::BufferUsage,
+            error::FrameworkError,
+            framebuffer::{
+                Attachment, AttachmentKind, BufferLocation, GpuFrameBuffer, ResourceBindGroup,
+                ResourceBinding,
+            },
+            geometry_buffer::GpuGeometryBuffer,
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                GpuTexture, GpuTextureDescriptor, GpuTextureKind, MagnificationFilter,
+                MinificationFilter, PixelKind, WrapMode,
+            },
+            server::GraphicsServer,
+            uniform::StaticUniformBuffer,
+            DrawParameters, ElementRange, GeometryBufferExt,
+        },
+        make_viewport_matrix, RenderPassStatistics,
+    },
+    scene::{graph::Graph, mesh::surface::SurfaceData, node::Node},
+};
+use fxhash::FxHashMap;
+use std::collections::hash_map::Entry;
+
+/// Amount of jitter samples in the sequence. The sequence is repeated after this amount of frames.
+const JITTER_SAMPLE_COUNT: u32 = 8;
+
+/// Weight of the current frame in the final image. Lower values give smoother result, but
+/// increase ghosting.
+const CURRENT_FRAME_WEIGHT: f32 = 0.1;
+
+/// Returns `index`-th element of Halton low-discrepancy sequence with the given base.
+fn halton(mut index: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut fraction = 1.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+/// Applies the given sub-pixel jitter (in pixels) to the projection matrix. The offset is applied
+/// in normalized device coordinates, so it works for both perspective and orthographic projections.
+pub fn jitter_projection(
+    projection: Matrix4<f32>,
+    jitter: Vector2<f32>,
+    viewport: Rect<i32>,
+) -> Matrix4<f32> {
+    let offset = Vector3::new(
+        2.0 * jitter.x / viewport.w().max(1) as f32,
+        2.0 * jitter.y / viewport.h().max(1) as f32,
+        0.0,
+    );
+    Matrix4::new_translation(&offset) * projection
+}
+
+#[derive(Clone, Copy)]
+struct CameraHistory {
+    /// Unjittered view-projection matrix of the camera.
+    view_projection: Matrix4<f32>,
+    /// Whether the camera has written its frame in its history texture or not.
+    has_frame: bool,
+}
+
+fn create_history_framebuffer(
+    server: &dyn GraphicsServer,
+    width: usize,
+    height: usize,
+) -> Result<GpuFrameBuffer, FrameworkError> {
+    let history_texture = server.create_texture(GpuTextureDescriptor {
+        kind: GpuTextureKind::Rectangle { width, height },
+        pixel_kind: PixelKind::RGBA8,
+        min_filter: MinificationFilter::Linear,
+        mag_filter: MagnificationFilter::Linear,
+        s_wrap_mode: WrapMode::ClampToEdge,
+        t_wrap_mode: WrapMode::ClampToEdge,
+        r_wrap_mode: WrapMode::ClampToEdge,
+        ..Default::default()
+    })?;
+
+    server.create_frame_buffer(
+        None,
+        vec![Attachment {
+            kind: AttachmentKind::Color,
+            texture: history_texture,
+        }],
+    )
+}
+
+/// Per-scene state of temporal effects. It holds accumulated history of the previous frames for
+/// temporal anti-aliasing and the data required to calculate motion vectors (which are also used
+/// by motion blur).
+pub struct TaaHistory {
+    width: usize,
+    height: usize,
+    /// History frame buffers of the cameras with temporal anti-aliasing. Every camera has its own
+    /// history, so multiple cameras (split screen, render-to-texture, etc.) never reproject frames
+    /// of each other.
+    framebuffers: FxHashMap<Handle<Node>, GpuFrameBuffer>,
+    frame_index: u32,
+    /// Cameras that were rendered with motion vectors in the previous frame.
+    previous_cameras: FxHashMap<Handle<Node>, CameraHistory>,
+    current_cameras: FxHashMap<Handle<Node>, CameraHistory>,
+    /// World transforms of the scene nodes in the previous frame.
+    pub(crate) previous_transforms: FxHashMap<Handle<Node>, Matrix4<f32>>,
+}
+
+impl TaaHistory {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            framebuffers: Default::default(),
+            frame_index: 0,
+            previous_cameras: Default::default(),
+            current_cameras: Default::default(),
+            previous_transforms: Default::default(),
+        }
+    }
+
+    /// Returns sub-pixel jitter (in pixels, in `[-0.5; 0.5]` range) for the current frame.
+    pub fn jitter(&self) -> Vector2<f32> {
+        let index = self.frame_index % JITTER_SAMPLE_COUNT + 1;
+        Vector2::new(halton(index, 2) - 0.5, halton(index, 3) - 0.5)
+    }
+
+    /// Returns unjittered view-projection matrix of the given camera from the previous frame. It
+    /// returns `None` if the camera was not rendered with motion vectors in the previous frame.
+    pub fn previous_view_projection(&self, camera: Handle<Node>) -> Option<Matrix4<f32>> {
+        self.previous_cameras
+            .get(&camera)
+            .map(|history| history.view_projection)
+    }
+
+    /// Returns `true` if the history texture of the given camera contains its previous frame.
+    pub fn has_previous_frame(&self, camera: Handle<Node>) -> bool {
+        self.previous_cameras
+            .get(&camera)
+            .is_some_and(|history| history.has_frame)
+    }
+
+    /// Returns history frame buffer of the given camera, creates a new one if there's no history
+    /// for the camera yet.
+    pub fn framebuffer(
+        &mut self,
+        server: &dyn GraphicsServer,
+        camera: Handle<Node>,
+    ) -> Result<GpuFrameBuffer, FrameworkError> {
+        match self.framebuffers.entry(camera) {
+            Entry::Occupied(entry) => Ok(entry.get().clone()),
+            Entry::Vacant(entry) => Ok(entry
+                .insert(create_history_framebuffer(server, self.width, self.height)?)
+                .clone()),
+        }
+    }
+
+    /// Remembers unjittered view-projection matrix of the camera, that was rendered with motion
+    /// vectors. `has_frame` defines whether the camera writes its frame in its history texture.
+    pub fn register_camera(
+        &mut self,
+        camera: Handle<Node>,
+        view_projection: Matrix4<f32>,
+        has_frame: bool,
+    ) {
+        self.current_cameras.insert(
+            camera,
+            CameraHistory {
+                view_projection,
+                has_frame,
+            },
+        );
+    }
+
+    /// Must be called once all the cameras of the scene were rendered. Stores the current state
+    /// of the scene for reprojection in the next frame.
+    pub fn end_frame(&mut self, graph: &Graph) {
+        self.previous_cameras = std::mem::take(&mut self.current_cameras);
+
+        // Free the history of the cameras that were removed or stopped using TAA.
+        self.framebuffers.retain(|camera, _| {
+            self.previous_cameras
+                .get(camera)
+                .is_some_and(|history| history.has_frame)
+        });
+
+        self.previous_transforms.clear();
+        if !self.previous_cameras.is_empty() {
+            self.previous_transforms.extend(
+                graph
+                    .pair_iter()
+                    .map(|(handle, node)| (handle, node.global_transform())),
+            );
+        }
+
+        self.frame_index = self.frame_index.wrapping_add(1);
+    }
+}
+
+struct TaaShader {
+    program: GpuProgram,
+    uniform_buffer_binding: usize,
+    current_texture: UniformLocation,
+    history_texture: UniformLocation,
+    velocity_texture: UniformLocation,
+    depth_texture: UniformLocation,
+}
+
+impl TaaShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/taa_fs.glsl");
+        let vertex_source = include_str!("shaders/taa_vs.glsl");
+
+        let program = server.create_program("TAAShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            current_texture: program.uniform_location(&ImmutableString::new("currentTexture"))?,
+            history_texture: program.uniform_location(&ImmutableString::new("historyTexture"))?,
+            velocity_texture: program.uniform_location(&ImmutableString::new("velocityTexture"))?,
+            depth_texture: program.uniform_location(&ImmutableString::new("depthTexture"))?,
+            program,
+        })
+    }
+}
+
+pub(crate) struct TaaRenderContext<'a> {
+    pub viewport: Rect<i32>,
+    pub frame_texture: GpuTexture,
+    pub history_texture: GpuTexture,
+    pub velocity_texture: GpuTexture,
+    pub depth_texture: GpuTexture,
+    /// Unjittered view-projection matrix of the current frame.
+    pub view_projection: Matrix4<f32>,
+    /// Unjittered view-projection matrix of the previous frame. `None` means that there's no valid
+    /// history.
+    pub previous_view_projection: Option<Matrix4<f32>>,
+    pub frame_buffer: &'a GpuFrameBuffer,
+    pub uniform_buffer_cache: &'a mut UniformBufferCache,
+}
+
+pub struct TaaRenderer {
+    shader: TaaShader,
+    quad: GpuGeometryBuffer,
+}
+
+impl TaaRenderer {
+    pub fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: TaaShader::new(server)?,
+            quad: GpuGeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                BufferUsage::StaticDraw,
+                server,
+            )?,
+        })
+    }
+
+    pub(crate) fn render(
+        &self,
+        args: TaaRenderContext,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let TaaRenderContext {
+            viewport,
+            frame_texture,
+            history_texture,
+            velocity_texture,
+            depth_texture,
+            view_projection,
+            previous_view_projection,
+            frame_buffer,
+            uniform_buffer_cache,
+        } = args;
+
+        let mut statistics = RenderPassStatistics::default();
+
+        let frame_matrix = make_viewport_matrix(viewport);
+        let inv_view_projection = view_projection.try_inverse().unwrap_or_default();
+        let history_valid = previous_view_projection.is_some();
+        let previous_view_projection = previous_view_projection.unwrap_or(view_projection);
+
+        statistics += frame_buffer.draw(
+            &*self.quad,
+            viewport,
+            &*self.shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: None,
+                blend: None,
+                stencil_op: Default::default(),
+                scissor_box: None,
+            },
+            &[ResourceBindGroup {
+                bindings: &[
+                    ResourceBinding::texture(&frame_texture, &self.shader.current_texture),
+                    ResourceBinding::texture(&history_texture, &self.shader.history_texture),
+                    ResourceBinding::texture(&velocity_texture, &self.shader.velocity_texture),
+                    ResourceBinding::texture(&depth_texture, &self.shader.depth_texture),
+                    ResourceBinding::Buffer {
+                        buffer: uniform_buffer_cache.write(
+                            StaticUniformBuffer::<512>::new()
+                                .with(&frame_matrix)
+                                .with(&inv_view_projection)
+                                .with(&previous_view_projection)
+                                .with(&Vector2::new(
+                                    1.0 / viewport.w() as f32,
+                                    1.0 / viewport.h() as f32,
+                                ))
+                                .with(&CURRENT_FRAME_WEIGHT)
+                                .with(&history_valid),
+                        )?,
+                        binding: BufferLocation::Auto {
+                            shader_location: self.shader.uniform_buffer_binding,
+                        },
+                        data_usage: Default::default(),
+                    },
+                ],
+            }],
+            ElementRange::Full,
+        )?;
+
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector2, Vector4},
+            math::Rect,
+        },
+        renderer::taa::{halton, jitter_projection},
+    };
+
+    #[test]
+    fn test_halton_sequence() {
+        let base2 = (1..5).map(|i| halton(i, 2)).collect::<Vec<_>>();
+        assert_eq!(base2, [0.5, 0.25, 0.75, 0.125]);
+        let base3 = (1..4).map(|i| halton(i, 3)).collect::<Vec<_>>();
+        assert!((base3[0] - 1.0 / 3.0).abs() < 1.0e-6);
+        assert!((base3[1] - 2.0 / 3.0).abs() < 1.0e-6);
+        assert!((base3[2] - 1.0 / 9.0).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_jitter_projection() {
+        let projection = Matrix4::new_perspective(1.0, 1.0, 0.1, 100.0);
+        let jittered = jitter_projection(
+            projection,
+            Vector2::new(0.5, -0.25),
+            Rect::new(0, 0, 100, 50),
+        );
+
+        // The offset is constant in NDC: half a pixel of 100 pixels wide viewport is 0.01 and a
+        // quarter of a pixel of 50 pixels high viewport is -0.01.
+        let point = Vector4::new(1.0, 2.0, -10.0, 1.0);
+        let original = projection * point;
+        let shifted = jittered * point;
+        let original_ndc = original.xyz() / original.w;
+        let shifted_ndc = shifted.xyz() / shifted.w;
+        assert!((shifted_ndc.x - original_ndc.x - 0.01).abs() < 1.0e-5);
+        assert!((shifted_ndc.y - original_ndc.y + 0.01).abs() < 1.0e-5);
+        assert!((shifted_ndc.z - original_ndc.z).abs() < 1.0e-5);
+    }
+}
@@ -26,6 +26,7 @@ use gltf::Gltf;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use crate::asset::cache::ArtifactPipeline;
 use crate::asset::io::ResourceIo;
 use crate::asset::loader;
 use crate::asset::manager::ResourceManager;
@@ -35,11 +36,14 @@ use crate::core::algebra::{Matrix4, Unit};
 use crate::core::log::Log;
 use crate::core::pool::Handle;
 use crate::core::TypeUuidProvider;
+use crate::engine::SerializationContext;
 use crate::graph::BaseSceneGraph;
 use crate::graph::NodeMapping;
 use crate::gui::core::io::FileLoadError;
 use crate::material::MaterialResource;
-use crate::resource::model::{MaterialSearchOptions, Model, ModelImportOptions};
+use crate::resource::model::{
+    loader::ModelArtifactPipeline, MaterialSearchOptions, Model, ModelImportOptions,
+};
 use crate::resource::texture::{TextureError, TextureResource};
 use crate::scene::animation::{AnimationContainer, AnimationPlayerBuilder};
 use crate::scene::base::BaseBuilder;
@@ -264,6 +268,8 @@ pub struct GltfLoader {
     /// ResourceManager is needed so that textures and mesh data can be loaded from additional resources.
     /// The glTF format allows for other assets to be referenced by file path.
     pub resource_manager: ResourceManager,
+    /// Serialization context is needed to load imported models from the import cache.
+    pub serialization_context: Arc<SerializationContext>,
     /// Import options control where this loader should search for additional resources.
     pub default_import_options: ModelImportOptions,
 }
@@ -308,6 +314,14 @@ impl loader::ResourceLoader for GltfLoader {
     fn default_import_options(&self) -> Option<Box<dyn options::BaseImportOptions>> {
         Some(Box::<ModelImportOptions>::default())
     }
+
+    fn artifact_pipeline(&self, _path: &Path) -> Option<Arc<dyn ArtifactPipeline>> {
+        Some(Arc::new(ModelArtifactPipeline {
+            resource_manager: self.resource_manager.clone(),
+            serialization_context: self.serialization_context.clone(),
+            default_import_options: self.default_import_options.clone(),
+        }))
+    }
 }
 
 async fn load(
@@ -424,6 +438,22 @@ async fn load_external_bin(path: &str, context: &ImportContext) -> Result<Vec<u8
     Ok(context.io.load_file(&path).await?)
 }
 
+/// Returns paths of the external buffers of a glTF model with the given content. The importer
+/// reads these files besides the model file itself.
+pub fn external_buffer_paths(model_path: &Path, content: &[u8]) -> Vec<PathBuf> {
+    let (Some(parent), Ok(gltf)) = (model_path.parent(), Gltf::from_slice(content)) else {
+        return Vec::new();
+    };
+    gltf.buffers()
+        .filter_map(|buffer| match buffer.source() {
+            gltf::buffer::Source::Uri(uri) if uri::parse_uri(uri).scheme == Scheme::None => {
+                Some(parent.join(uri))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 fn import_meshes(
     gltf: &Document,
     path: &Path,
@@ -22,19 +22,33 @@
 
 use crate::{
     asset::{
+        cache::ArtifactPipeline,
         io::ResourceIo,
         loader::{
             BoxedImportOptionsLoaderFuture, BoxedLoaderFuture, LoaderPayload, ResourceLoader,
         },
         manager::ResourceManager,
-        options::{try_get_import_settings, try_get_import_settings_opaque, BaseImportOptions},
+        options::{
+            serialize_import_settings, try_get_import_settings, try_get_import_settings_opaque,
+            BaseImportOptions,
+        },
+        ResourceData,
     },
-    core::{uuid::Uuid, TypeUuidProvider},
+    core::{uuid::Uuid, visitor::Visitor, Downcast, TypeUuidProvider},
     engine::SerializationContext,
-    resource::model::{Model, ModelImportOptions},
+    graph::NodeMapping,
+    resource::{
+        gltf,
+        model::{Model, ModelImportOptions},
+    },
+    scene::SceneLoader,
 };
 use fyrox_resource::state::LoadError;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 /// Default implementation for model loading.
 pub struct ModelLoader {
@@ -53,7 +67,7 @@ impl ResourceLoader for ModelLoader {
     }
 
     fn data_type_uuid(&self) -> Uuid {
-        Model::type_uuid()
+        <Model as TypeUuidProvider>::type_uuid()
     }
 
     fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
@@ -95,4 +109,179 @@ impl ResourceLoader for ModelLoader {
     fn default_import_options(&self) -> Option<Box<dyn BaseImportOptions>> {
         Some(Box::<ModelImportOptions>::default())
     }
+
+    fn artifact_pipeline(&self, path: &Path) -> Option<Arc<dyn ArtifactPipeline>> {
+        // Native scenes do not need any conversion.
+        if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("fbx"))
+        {
+            Some(Arc::new(ModelArtifactPipeline {
+                resource_manager: self.resource_manager.clone(),
+                serialization_context: self.serialization_context.clone(),
+                default_import_options: self.default_import_options.clone(),
+            }))
+        } else {
+            None
+        }
+    }
+}
+
+/// Artifact pipeline for the models imported from third-party formats (FBX, glTF). An imported
+/// model is stored as a native scene, which is much faster to load.
+pub struct ModelArtifactPipeline {
+    /// Resource manager to load the resources used by the model.
+    pub resource_manager: ResourceManager,
+    /// Serialization context to create the nodes of the model.
+    pub serialization_context: Arc<SerializationContext>,
+    /// Import options, that are used for the models without options file.
+    pub default_import_options: ModelImportOptions,
+}
+
+impl ArtifactPipeline for ModelArtifactPipeline {
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn extension(&self) -> &str {
+        "rgs"
+    }
+
+    fn default_import_options(&self) -> Option<Vec<u8>> {
+        serialize_import_settings(&self.default_import_options)
+    }
+
+    fn dependencies(&self, source_path: &Path, source: &[u8]) -> Vec<PathBuf> {
+        // glTF models could store their buffers in separate files.
+        if source_path.extension().is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("gltf") || extension.eq_ignore_ascii_case("glb")
+        }) {
+            gltf::external_buffer_paths(source_path, source)
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn save_artifact(&self, data: &mut dyn ResourceData) -> Result<Vec<u8>, Box<dyn Error>> {
+        let model = Downcast::as_any_mut(data)
+            .downcast_mut::<Model>()
+            .ok_or("The resource data is not a model!")?;
+        let mut visitor = Visitor::new();
+        model.scene.save("Scene", &mut visitor)?;
+        Ok(visitor.save_binary_to_vec()?)
+    }
+
+    fn load_artifact(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+        let serialization_context = self.serialization_context.clone();
+
+        Box::pin(async move {
+            let scene =
+                SceneLoader::from_file(&path, &*io, serialization_context, resource_manager)
+                    .await
+                    .map_err(LoadError::new)?
+                    .0
+                    .finish()
+                    .await;
+
+            // Third-party formats do not have persistent unique ids, so the imported models use
+            // names to map their nodes.
+            Ok(LoaderPayload::new(Model::new(NodeMapping::UseNames, scene)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::{cache::ArtifactPipeline, io::FsResourceIo, manager::ResourceManager},
+        core::{algebra::Matrix4, futures::executor::block_on},
+        engine::{self, SerializationContext},
+        graph::{NodeMapping, SceneGraph},
+        resource::model::{loader::ModelArtifactPipeline, Model},
+        scene::{
+            base::BaseBuilder,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                Mesh, MeshBuilder,
+            },
+            Scene, SceneLoader,
+        },
+    };
+    use fyrox_resource::untyped::ResourceKind;
+    use std::{path::Path, sync::Arc};
+
+    #[test]
+    fn test_model_artifact_round_trip() {
+        let path =
+            &std::env::temp_dir().join(format!("fyrox_model_artifact_{}.rgs", std::process::id()));
+
+        let serialization_context = Arc::new(SerializationContext::new());
+        let resource_manager = ResourceManager::new(Arc::new(Default::default()));
+        engine::initialize_resource_manager_loaders(
+            &resource_manager,
+            serialization_context.clone(),
+        );
+        let pipeline = ModelArtifactPipeline {
+            resource_manager,
+            serialization_context,
+            default_import_options: Default::default(),
+        };
+
+        let mut scene = Scene::new();
+        MeshBuilder::new(BaseBuilder::new().with_name("Mesh"))
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+                ResourceKind::Embedded,
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .build(&mut scene.graph);
+        let mut model = Model::new(NodeMapping::UseNames, scene);
+        std::fs::write(path, pipeline.save_artifact(&mut model).unwrap()).unwrap();
+
+        assert!(
+            block_on(pipeline.load_artifact(path.to_path_buf(), Arc::new(FsResourceIo))).is_ok()
+        );
+
+        let loaded = block_on(
+            block_on(SceneLoader::from_file(
+                path,
+                &FsResourceIo,
+                pipeline.serialization_context.clone(),
+                pipeline.resource_manager.clone(),
+            ))
+            .unwrap()
+            .0
+            .finish(),
+        );
+        let (_, mesh) = loaded.graph.find_by_name_from_root("Mesh").unwrap();
+        assert_eq!(mesh.cast::<Mesh>().unwrap().surfaces().len(), 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_gltf_external_buffers_are_dependencies() {
+        let resource_manager = ResourceManager::new(Arc::new(Default::default()));
+        let pipeline = ModelArtifactPipeline {
+            resource_manager,
+            serialization_context: Arc::new(SerializationContext::new()),
+            default_import_options: Default::default(),
+        };
+
+        let gltf = br#"{
+            "asset": { "version": "2.0" },
+            "buffers": [
+                { "uri": "model.bin", "byteLength": 4 },
+                { "uri": "data:application/octet-stream;base64,AAAAAA==", "byteLength": 4 }
+            ]
+        }"#;
+        assert_eq!(
+            pipeline.dependencies(Path::new("models/model.gltf"), gltf),
+            vec![Path::new("models/model.bin").to_path_buf()]
+        );
+        assert!(pipeline
+            .dependencies(Path::new("models/model.fbx"), gltf)
+            .is_empty());
+    }
 }
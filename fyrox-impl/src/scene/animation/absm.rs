@@ -24,6 +24,7 @@
 use crate::scene::node::constructor::NodeConstructor;
 use crate::{
     core::{
+        algebra::{Point3, Vector3},
         math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
         reflect::prelude::*,
@@ -32,6 +33,7 @@ use crate::{
         variable::InheritableVariable,
         visitor::prelude::*,
     },
+    generic_animation::machine::Parameter,
     scene::{
         animation::prelude::*,
         base::{Base, BaseBuilder},
@@ -56,6 +58,9 @@ pub type BlendAnimations =
 /// Scene specific animation blending state machine BlendAnimationsByIndex node.
 pub type BlendAnimationsByIndex =
     crate::generic_animation::machine::node::blend::BlendAnimationsByIndex<Handle<Node>>;
+/// Scene specific animation blending state machine BlendAdditive node.
+pub type BlendAdditive =
+    crate::generic_animation::machine::node::blend::BlendAdditive<Handle<Node>>;
 /// Scene specific animation blending state machine BlendPose node.
 pub type BlendPose = crate::generic_animation::machine::node::blend::BlendPose<Handle<Node>>;
 /// Scene specific animation blending state machine IndexedBlendInput node.
@@ -66,6 +71,12 @@ pub type BlendSpace = crate::generic_animation::machine::node::blendspace::Blend
 /// Scene specific animation blending state machine blend space point.
 pub type BlendSpacePoint =
     crate::generic_animation::machine::node::blendspace::BlendSpacePoint<Handle<Node>>;
+/// Scene specific animation blending state machine two-bone IK node.
+pub type TwoBoneIk = crate::generic_animation::machine::node::ik::TwoBoneIk<Handle<Node>>;
+/// Scene specific animation blending state machine FABRIK chain node.
+pub type FabrikChain = crate::generic_animation::machine::node::ik::FabrikChain<Handle<Node>>;
+/// Scene specific animation blending state machine look-at node.
+pub type LookAt = crate::generic_animation::machine::node::ik::LookAt<Handle<Node>>;
 /// Scene specific animation blending state machine layer mask.
 pub type LayerMask = crate::generic_animation::machine::mask::LayerMask<Handle<Node>>;
 /// Scene specific animation blending state machine layer mask.
@@ -106,12 +117,14 @@ pub type AnimationEventsSource =
 pub mod prelude {
     pub use super::{
         AndNode, AnimationBlendingStateMachine, AnimationBlendingStateMachineBuilder,
-        AnimationEventsSource, BasePoseNode, BlendAnimations, BlendAnimationsByIndex, BlendPose,
-        BlendSpace, BlendSpacePoint, Event, IndexedBlendInput, LayerAnimationEventsCollection,
-        LayerMask, LogicNode, Machine, MachineLayer, NotNode, OrNode, PlayAnimation, PoseNode,
-        RootMotionSettings, State, StateAction, StateActionWrapper, Transition, XorNode,
+        AnimationEventsSource, BasePoseNode, BlendAdditive, BlendAnimations,
+        BlendAnimationsByIndex, BlendPose, BlendSpace, BlendSpacePoint, Event, FabrikChain,
+        IndexedBlendInput, LayerAnimationEventsCollection, LayerMask, LogicNode, LookAt, Machine,
+        MachineLayer, NotNode, OrNode, PlayAnimation, PoseNode, RootMotionSettings, State,
+        StateAction, StateActionWrapper, Transition, TwoBoneIk, XorNode,
     };
     pub use crate::generic_animation::machine::{
+        layer::LayerBlendMode,
         node::AnimationEventCollectionStrategy,
         parameter::{Parameter, ParameterContainer, ParameterDefinition, PoseWeight},
     };
@@ -136,6 +149,25 @@ impl LayerMaskExt for LayerMask {
     }
 }
 
+/// Creates a position parameter for IK pose nodes ([`TwoBoneIk`], [`FabrikChain`], [`LookAt`]) from
+/// the given world-space position. IK nodes work in the local space of the parent of the first bone
+/// of a chain, so the position is transformed into this space. If the bone or its parent does not
+/// exist, the position is used as is.
+pub fn make_ik_target(
+    graph: &Graph,
+    first_bone: Handle<Node>,
+    position: Vector3<f32>,
+) -> Parameter {
+    Parameter::Position(
+        graph
+            .try_get(first_bone)
+            .and_then(|bone| graph.try_get(bone.parent()))
+            .and_then(|parent| parent.global_transform().try_inverse())
+            .map(|inv| inv.transform_point(&Point3::from(position)).coords)
+            .unwrap_or(position),
+    )
+}
+
 /// Animation blending state machine (ABSM) is a node that takes multiple animations from an animation player and
 /// mixes them in arbitrary way into one animation. Usually, ABSMs are used to animate humanoid characters in games,
 /// by blending multiple states with one or more animations. More info about state machines can be found in
@@ -69,7 +69,7 @@ pub mod prelude {
         container::{TrackDataContainer, TrackValueKind},
         signal::AnimationSignal,
         value::{BoundValueCollection, TrackValue, ValueBinding, ValueType},
-        AnimationEvent,
+        AdditiveSettings, AnimationEvent,
     };
 }
 
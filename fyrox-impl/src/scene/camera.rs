@@ -321,6 +321,144 @@ pub enum Exposure {
 
 uuid_provider!(Exposure = "0e35ee3d-8baa-4b0c-b3dd-6c31a08c121e");
 
+/// Anti-aliasing method that is used to smooth jagged edges of the frame rendered by a camera.
+#[derive(
+    Visit, Copy, Clone, Default, PartialEq, Eq, Debug, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum AntiAliasing {
+    /// No anti-aliasing.
+    None,
+    /// Fast approximate anti-aliasing. It is a cheap post effect that smooths edges in the final
+    /// frame. It tends to blur thin geometry and textures. Keep in mind, that FXAA could also be
+    /// disabled globally in the quality settings of the renderer. This is default option.
+    #[default]
+    Fxaa,
+    /// Temporal anti-aliasing. The camera projection is jittered by a sub-pixel offset every
+    /// frame and the result is accumulated over multiple frames using motion vectors. It handles
+    /// thin geometry (foliage, wires, etc.) much better than FXAA, but may produce slight ghosting
+    /// on fast-moving objects.
+    Taa,
+}
+
+uuid_provider!(AntiAliasing = "3b1f7d2e-9c64-4f1a-8e5b-6d0c2a7f4e91");
+
+/// Screen-space reflections (SSR) settings of a camera. SSR traces reflected rays against the
+/// depth buffer of the rendered frame and uses the color of the hit point as a reflection. Rays
+/// that leave the screen or do not hit anything keep the reflections of the environment map and
+/// reflection probes. Rough surfaces get blurrier reflections. Keep in mind, that SSR could also be disabled globally in
+/// the quality settings of the renderer.
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct ScreenSpaceReflections {
+    /// Whether the screen-space reflections are enabled for the camera or not. Disabled by
+    /// default.
+    pub enabled: bool,
+
+    /// Maximum amount of ray marching steps per pixel. Larger values give more precise
+    /// reflections, but are more expensive.
+    #[reflect(min_value = 1.0, max_value = 256.0)]
+    pub max_steps: u32,
+
+    /// Maximum distance (in meters) that a reflected ray could travel.
+    #[reflect(min_value = 0.0)]
+    pub max_distance: f32,
+
+    /// Assumed thickness (in meters) of the objects in the depth buffer. A ray is considered
+    /// hitting an object only if it is behind the object's surface by at most this distance.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub thickness: f32,
+
+    /// Intensity of the reflections.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub intensity: f32,
+}
+
+uuid_provider!(ScreenSpaceReflections = "a4c3b8e2-5f17-4d6b-9e0a-71d2c8f45b39");
+
+impl Default for ScreenSpaceReflections {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_steps: 64,
+            max_distance: 32.0,
+            thickness: 0.5,
+            intensity: 1.0,
+        }
+    }
+}
+
+/// Depth of field settings of a camera. The effect simulates a thin lens of a real camera: objects
+/// at the focus distance are sharp, everything closer or farther is blurred with a disc-shaped
+/// (bokeh) blur. The size of the blur is defined by the circle of confusion, which is calculated
+/// using the focus distance, the f-stop and the focal length. The focal length is derived from the
+/// field of view of a perspective camera (assuming full-frame 35 mm sensor), orthographic cameras use
+/// 50 mm lens.
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct DepthOfField {
+    /// Whether the depth of field is enabled for the camera or not.
+    pub enabled: bool,
+
+    /// Distance (in meters) from the camera to the plane in focus.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub focus_distance: f32,
+
+    /// Ratio of the focal length to the diameter of the aperture. Lower values give shallower depth
+    /// of field (stronger blur).
+    #[reflect(min_value = 0.5, step = 0.1)]
+    pub f_stop: f32,
+
+    /// Maximum radius (in pixels) of the blur.
+    #[reflect(min_value = 0.0, max_value = 64.0)]
+    pub max_blur_radius: f32,
+}
+
+uuid_provider!(DepthOfField = "3e2c7a51-8b0d-4f96-a1c4-5d7e9b2f6c08");
+
+impl Default for DepthOfField {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            focus_distance: 10.0,
+            f_stop: 2.8,
+            max_blur_radius: 16.0,
+        }
+    }
+}
+
+/// Motion blur settings of a camera. Moving objects (and the whole frame when the camera moves) are
+/// blurred along their screen-space velocity. The velocity is taken from the velocity buffer, so
+/// each object has its own blur.
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct MotionBlur {
+    /// Whether the motion blur is enabled for the camera or not.
+    pub enabled: bool,
+
+    /// Fraction of the frame time during which the virtual shutter is open. 0.5 corresponds to
+    /// 180 degrees shutter of film cameras.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub shutter: f32,
+
+    /// Maximum length (in pixels) of the blur.
+    #[reflect(min_value = 0.0, max_value = 128.0)]
+    pub max_blur_radius: f32,
+
+    /// Amount of samples taken along the velocity of each pixel.
+    #[reflect(min_value = 2.0, max_value = 64.0)]
+    pub sample_count: u32,
+}
+
+uuid_provider!(MotionBlur = "c81f4d3a-2e95-4b70-8a6d-f0b3e17c5d92");
+
+impl Default for MotionBlur {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            shutter: 0.5,
+            max_blur_radius: 32.0,
+            sample_count: 12,
+        }
+    }
+}
+
 impl Default for Exposure {
     fn default() -> Self {
         Self::Auto {
@@ -382,6 +520,22 @@ pub struct Camera {
     #[reflect(setter = "set_color_grading_enabled")]
     color_grading_enabled: InheritableVariable<bool>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_anti_aliasing")]
+    anti_aliasing: InheritableVariable<AntiAliasing>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_screen_space_reflections")]
+    screen_space_reflections: InheritableVariable<ScreenSpaceReflections>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_depth_of_field")]
+    depth_of_field: InheritableVariable<DepthOfField>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_motion_blur")]
+    motion_blur: InheritableVariable<MotionBlur>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     view_matrix: Matrix4<f32>,
@@ -734,6 +888,52 @@ impl Camera {
     pub fn exposure(&self) -> Exposure {
         *self.exposure
     }
+
+    /// Sets new anti-aliasing method. See [`AntiAliasing`] docs for more info.
+    pub fn set_anti_aliasing(&mut self, anti_aliasing: AntiAliasing) -> AntiAliasing {
+        self.anti_aliasing
+            .set_value_and_mark_modified(anti_aliasing)
+    }
+
+    /// Returns current anti-aliasing method.
+    pub fn anti_aliasing(&self) -> AntiAliasing {
+        *self.anti_aliasing
+    }
+
+    /// Sets new screen-space reflections settings. See [`ScreenSpaceReflections`] docs for more
+    /// info.
+    pub fn set_screen_space_reflections(
+        &mut self,
+        settings: ScreenSpaceReflections,
+    ) -> ScreenSpaceReflections {
+        self.screen_space_reflections
+            .set_value_and_mark_modified(settings)
+    }
+
+    /// Returns current screen-space reflections settings.
+    pub fn screen_space_reflections(&self) -> &ScreenSpaceReflections {
+        &self.screen_space_reflections
+    }
+
+    /// Sets new depth of field settings. See [`DepthOfField`] docs for more info.
+    pub fn set_depth_of_field(&mut self, settings: DepthOfField) -> DepthOfField {
+        self.depth_of_field.set_value_and_mark_modified(settings)
+    }
+
+    /// Returns current depth of field settings.
+    pub fn depth_of_field(&self) -> &DepthOfField {
+        &self.depth_of_field
+    }
+
+    /// Sets new motion blur settings. See [`MotionBlur`] docs for more info.
+    pub fn set_motion_blur(&mut self, settings: MotionBlur) -> MotionBlur {
+        self.motion_blur.set_value_and_mark_modified(settings)
+    }
+
+    /// Returns current motion blur settings.
+    pub fn motion_blur(&self) -> &MotionBlur {
+        &self.motion_blur
+    }
 }
 
 impl ConstructorProvider<Node, Graph> for Camera {
@@ -1075,6 +1275,10 @@ pub struct CameraBuilder {
     color_grading_lut: Option<ColorGradingLut>,
     color_grading_enabled: bool,
     projection: Projection,
+    anti_aliasing: AntiAliasing,
+    screen_space_reflections: ScreenSpaceReflections,
+    depth_of_field: DepthOfField,
+    motion_blur: MotionBlur,
 }
 
 impl CameraBuilder {
@@ -1093,6 +1297,10 @@ impl CameraBuilder {
             color_grading_lut: None,
             color_grading_enabled: false,
             projection: Projection::default(),
+            anti_aliasing: Default::default(),
+            screen_space_reflections: Default::default(),
+            depth_of_field: Default::default(),
+            motion_blur: Default::default(),
         }
     }
 
@@ -1168,6 +1376,30 @@ impl CameraBuilder {
         self
     }
 
+    /// Sets desired anti-aliasing method.
+    pub fn with_anti_aliasing(mut self, anti_aliasing: AntiAliasing) -> Self {
+        self.anti_aliasing = anti_aliasing;
+        self
+    }
+
+    /// Sets desired screen-space reflections settings.
+    pub fn with_screen_space_reflections(mut self, settings: ScreenSpaceReflections) -> Self {
+        self.screen_space_reflections = settings;
+        self
+    }
+
+    /// Sets desired depth of field settings.
+    pub fn with_depth_of_field(mut self, settings: DepthOfField) -> Self {
+        self.depth_of_field = settings;
+        self
+    }
+
+    /// Sets desired motion blur settings.
+    pub fn with_motion_blur(mut self, settings: MotionBlur) -> Self {
+        self.motion_blur = settings;
+        self
+    }
+
     /// Creates new instance of camera.
     pub fn build_camera(self) -> Camera {
         Camera {
@@ -1188,6 +1420,10 @@ impl CameraBuilder {
             exposure: self.exposure.into(),
             color_grading_lut: self.color_grading_lut.into(),
             color_grading_enabled: self.color_grading_enabled.into(),
+            anti_aliasing: self.anti_aliasing.into(),
+            screen_space_reflections: self.screen_space_reflections.into(),
+            depth_of_field: self.depth_of_field.into(),
+            motion_blur: self.motion_blur.into(),
         }
     }
 
@@ -1594,3 +1830,105 @@ impl SkyBox {
         self.back.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::manager::ResourceManager,
+        core::{
+            math::Rect,
+            variable::InheritableVariable,
+            visitor::{Visit, VisitResult, Visitor},
+        },
+        resource::texture::TextureResource,
+        scene::{
+            base::{Base, BaseBuilder},
+            camera::{
+                AntiAliasing, Camera, CameraBuilder, ColorGradingLut, DepthOfField, Exposure,
+                MotionBlur, Projection, ScreenSpaceReflections, SkyBox,
+            },
+        },
+    };
+    use std::sync::Arc;
+
+    // Layout of the camera before anti-aliasing method and post effects were added.
+    #[derive(Visit)]
+    struct LegacyCamera {
+        base: Base,
+        projection: InheritableVariable<Projection>,
+        viewport: InheritableVariable<Rect<f32>>,
+        enabled: InheritableVariable<bool>,
+        sky_box: InheritableVariable<Option<SkyBox>>,
+        environment: InheritableVariable<Option<TextureResource>>,
+        exposure: InheritableVariable<Exposure>,
+        color_grading_lut: InheritableVariable<Option<ColorGradingLut>>,
+        color_grading_enabled: InheritableVariable<bool>,
+    }
+
+    fn save_and_load(entity: &mut impl Visit) -> Camera {
+        let mut visitor = Visitor::new();
+        entity.visit("Camera", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        visitor
+            .blackboard
+            .register(Arc::new(ResourceManager::new(Arc::new(Default::default()))));
+        let mut camera = Camera::default();
+        camera.visit("Camera", &mut visitor).unwrap();
+        camera
+    }
+
+    #[test]
+    fn test_legacy_camera_has_post_effects_disabled() {
+        let mut legacy = LegacyCamera {
+            base: BaseBuilder::new().with_name("LegacyCamera").build_base(),
+            projection: Default::default(),
+            viewport: Rect::new(0.0, 0.0, 1.0, 1.0).into(),
+            enabled: true.into(),
+            sky_box: Default::default(),
+            environment: Default::default(),
+            exposure: Default::default(),
+            color_grading_lut: Default::default(),
+            color_grading_enabled: false.into(),
+        };
+
+        let camera = save_and_load(&mut legacy);
+        assert_eq!(camera.name(), "LegacyCamera");
+        assert_eq!(camera.anti_aliasing(), AntiAliasing::Fxaa);
+        assert!(!camera.screen_space_reflections().enabled);
+        assert!(!camera.depth_of_field().enabled);
+        assert!(!camera.motion_blur().enabled);
+    }
+
+    #[test]
+    fn test_camera_post_effects_save_load() {
+        let ssr = ScreenSpaceReflections {
+            enabled: true,
+            max_steps: 32,
+            ..Default::default()
+        };
+        let dof = DepthOfField {
+            enabled: true,
+            focus_distance: 3.0,
+            ..Default::default()
+        };
+        let motion_blur = MotionBlur {
+            enabled: true,
+            shutter: 0.25,
+            ..Default::default()
+        };
+        let mut camera = CameraBuilder::new(BaseBuilder::new())
+            .with_anti_aliasing(AntiAliasing::Taa)
+            .with_screen_space_reflections(ssr)
+            .with_depth_of_field(dof)
+            .with_motion_blur(motion_blur)
+            .build_camera();
+
+        let loaded = save_and_load(&mut camera);
+        assert_eq!(loaded.anti_aliasing(), AntiAliasing::Taa);
+        assert_eq!(loaded.screen_space_reflections(), &ssr);
+        assert_eq!(loaded.depth_of_field(), &dof);
+        assert_eq!(loaded.motion_blur(), &motion_blur);
+    }
+}
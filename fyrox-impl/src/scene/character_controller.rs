@@ -0,0 +1,964 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Character controller is a kinematic physics entity that moves a character through the world,
+//! handling stairs, slopes and ground snapping. See [`CharacterController`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        log::Log,
+        math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    graph::{constructor::ConstructorProvider, BaseSceneGraph, SceneGraph},
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::Collider,
+        graph::Graph,
+        node::{constructor::NodeConstructor, Node, NodeTrait, SyncContext, UpdateContext},
+        Scene,
+    },
+};
+use rapier3d::{
+    control::{CharacterAutostep, CharacterLength, KinematicCharacterController},
+    prelude::RigidBodyHandle,
+};
+use std::{
+    cell::Cell,
+    fmt::{Debug, Formatter},
+    ops::{Deref, DerefMut},
+};
+
+/// Information about a single collision that happened while the character was moving.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharacterCollision {
+    /// A handle of the collider the character has collided with.
+    pub collider: Handle<Node>,
+    /// A contact point on the surface of the collider (in world coordinates).
+    pub position: Vector3<f32>,
+    /// A normal of the surface of the collider at the contact point (in world coordinates).
+    pub normal: Vector3<f32>,
+    /// A part of the desired translation, that was applied before the collision.
+    pub translation_applied: Vector3<f32>,
+    /// A part of the desired translation, that was left after the collision.
+    pub translation_remaining: Vector3<f32>,
+}
+
+/// Result of a single movement of a character controller.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharacterMovement {
+    /// The actual translation (in world coordinates) that was applied to the character.
+    pub translation: Vector3<f32>,
+    /// `true` if the character stands on a ground after the movement.
+    pub grounded: bool,
+    /// `true` if the character is sliding down a slope that is too steep to climb.
+    pub is_sliding_down_slope: bool,
+    /// A list of collisions that happened during the movement.
+    pub collisions: Vec<CharacterCollision>,
+}
+
+/// Character controller is a kinematic physics entity that moves a character using a set of
+/// "desired" translations and resolves collisions with the environment. Unlike a dynamic rigid
+/// body, it is not affected by any forces (including gravity) and it moves exactly as you tell it,
+/// except for the cases when it is blocked by obstacles. The controller automatically climbs stairs
+/// (up to [`CharacterController::max_step_height`]), refuses to climb slopes that are too steep,
+/// slides along walls and can snap to the ground when going down the stairs or slopes.
+///
+/// The controller must have at least one [`Collider`] as a direct child node, the shape of the
+/// first collider is used as the shape of the character. Capsules are the best choice in most
+/// cases.
+///
+/// # Movement
+///
+/// Use [`CharacterController::set_desired_translation`] (or [`CharacterController::move_by`])
+/// every frame to tell the controller where the character should move. The translation is
+/// defined in world coordinates and it is consumed on the next update of the scene graph. Since
+/// the controller is not affected by gravity, you should add gravity to the desired translation
+/// manually:
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     graph::SceneGraph,
+/// #     scene::{character_controller::CharacterController, graph::Graph, node::Node},
+/// # };
+/// fn move_character(controller: Handle<Node>, graph: &mut Graph, walk: Vector3<f32>, dt: f32) {
+///     if let Some(controller) = graph.try_get_mut_of_type::<CharacterController>(controller) {
+///         let gravity = if controller.is_grounded() {
+///             Vector3::default()
+///         } else {
+///             Vector3::new(0.0, -9.81 * dt, 0.0)
+///         };
+///         controller.set_desired_translation(walk * dt + gravity);
+///     }
+/// }
+/// ```
+///
+/// # Pushing rigid bodies
+///
+/// The controller can push dynamic rigid bodies it collides with, this is controlled by
+/// [`CharacterController::set_push_rigid_bodies`]. The strength of the push depends on the
+/// [`CharacterController::mass`] of the character.
+#[derive(Visit, Reflect, ComponentProvider)]
+pub struct CharacterController {
+    base: Base,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.005,
+        description = "A small gap to preserve between the character and its surroundings."
+    )]
+    #[reflect(setter = "set_offset")]
+    pub(crate) offset: InheritableVariable<f32>,
+
+    #[reflect(description = "Should the character try to slide along the obstacles?")]
+    #[reflect(setter = "set_slide")]
+    pub(crate) slide: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Max height of a step the character can climb. Zero disables stairs climbing."
+    )]
+    #[reflect(setter = "set_max_step_height")]
+    pub(crate) max_step_height: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Min width of free space that must be available after a step."
+    )]
+    #[reflect(setter = "set_min_step_width")]
+    pub(crate) min_step_width: InheritableVariable<f32>,
+
+    #[reflect(description = "Can the character climb on dynamic rigid bodies as on steps?")]
+    #[reflect(setter = "set_step_on_dynamic_bodies")]
+    pub(crate) step_on_dynamic_bodies: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.571,
+        step = 0.01,
+        description = "Max angle (in radians) of a slope the character can climb."
+    )]
+    #[reflect(setter = "set_max_slope_climb_angle")]
+    pub(crate) max_slope_climb_angle: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.571,
+        step = 0.01,
+        description = "Min angle (in radians) of a slope the character will automatically slide down."
+    )]
+    #[reflect(setter = "set_min_slope_slide_angle")]
+    pub(crate) min_slope_slide_angle: InheritableVariable<f32>,
+
+    #[reflect(
+        description = "Max distance to the ground at which the character will be snapped \
+        to it. `None` disables ground snapping."
+    )]
+    #[reflect(setter = "set_snap_to_ground")]
+    pub(crate) snap_to_ground: InheritableVariable<Option<f32>>,
+
+    #[reflect(description = "Should the character push dynamic rigid bodies it collides with?")]
+    #[reflect(setter = "set_push_rigid_bodies")]
+    pub(crate) push_rigid_bodies: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Mass of the character. It is used to calculate impulses applied to dynamic \
+        rigid bodies."
+    )]
+    #[reflect(setter = "set_mass")]
+    pub(crate) mass: InheritableVariable<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    desired_translation: Vector3<f32>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    last_movement: CharacterMovement,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native: Cell<RigidBodyHandle>,
+}
+
+impl Debug for CharacterController {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CharacterController")
+    }
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        CharacterControllerBuilder::new(BaseBuilder::new()).build_character_controller()
+    }
+}
+
+impl Deref for CharacterController {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for CharacterController {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Clone for CharacterController {
+    fn clone(&self) -> Self {
+        Self {
+            base: self.base.clone(),
+            offset: self.offset.clone(),
+            slide: self.slide.clone(),
+            max_step_height: self.max_step_height.clone(),
+            min_step_width: self.min_step_width.clone(),
+            step_on_dynamic_bodies: self.step_on_dynamic_bodies.clone(),
+            max_slope_climb_angle: self.max_slope_climb_angle.clone(),
+            min_slope_slide_angle: self.min_slope_slide_angle.clone(),
+            snap_to_ground: self.snap_to_ground.clone(),
+            push_rigid_bodies: self.push_rigid_bodies.clone(),
+            mass: self.mass.clone(),
+            desired_translation: self.desired_translation,
+            last_movement: self.last_movement.clone(),
+            // Do not copy. The copy will have its own native representation.
+            native: Cell::new(RigidBodyHandle::invalid()),
+        }
+    }
+}
+
+impl TypeUuidProvider for CharacterController {
+    fn type_uuid() -> Uuid {
+        uuid!("0d2ab3c2-2b4e-4a8f-9d4c-7a3a4e0e8b61")
+    }
+}
+
+impl CharacterController {
+    /// Sets a small gap to preserve between the character and its surroundings. It should be
+    /// small, but not zero, to prevent numerical issues when the character is touching obstacles.
+    pub fn set_offset(&mut self, offset: f32) -> f32 {
+        self.offset.set_value_and_mark_modified(offset.max(0.0))
+    }
+
+    /// Returns current offset of the character. See [`Self::set_offset`] for more info.
+    pub fn offset(&self) -> f32 {
+        *self.offset
+    }
+
+    /// Sets whether the character should slide along obstacles or stop at the first contact.
+    pub fn set_slide(&mut self, slide: bool) -> bool {
+        self.slide.set_value_and_mark_modified(slide)
+    }
+
+    /// Returns `true` if the character slides along obstacles, `false` - otherwise.
+    pub fn is_slide(&self) -> bool {
+        *self.slide
+    }
+
+    /// Sets max height of a step the character can climb. Zero value disables stairs climbing.
+    pub fn set_max_step_height(&mut self, height: f32) -> f32 {
+        self.max_step_height
+            .set_value_and_mark_modified(height.max(0.0))
+    }
+
+    /// Returns max height of a step the character can climb.
+    pub fn max_step_height(&self) -> f32 {
+        *self.max_step_height
+    }
+
+    /// Sets min width of free space that must be available on a step for the character to climb
+    /// it.
+    pub fn set_min_step_width(&mut self, width: f32) -> f32 {
+        self.min_step_width
+            .set_value_and_mark_modified(width.max(0.0))
+    }
+
+    /// Returns min width of free space that must be available on a step.
+    pub fn min_step_width(&self) -> f32 {
+        *self.min_step_width
+    }
+
+    /// Sets whether the character can climb on dynamic rigid bodies as on steps.
+    pub fn set_step_on_dynamic_bodies(&mut self, state: bool) -> bool {
+        self.step_on_dynamic_bodies
+            .set_value_and_mark_modified(state)
+    }
+
+    /// Returns `true` if the character can climb on dynamic rigid bodies, `false` - otherwise.
+    pub fn is_step_on_dynamic_bodies(&self) -> bool {
+        *self.step_on_dynamic_bodies
+    }
+
+    /// Sets max angle (in radians) between the up vector and the normal of a slope the character
+    /// can climb.
+    pub fn set_max_slope_climb_angle(&mut self, angle: f32) -> f32 {
+        self.max_slope_climb_angle
+            .set_value_and_mark_modified(angle)
+    }
+
+    /// Returns max angle (in radians) of a slope the character can climb.
+    pub fn max_slope_climb_angle(&self) -> f32 {
+        *self.max_slope_climb_angle
+    }
+
+    /// Sets min angle (in radians) between the up vector and the normal of a slope at which the
+    /// character will automatically slide down.
+    pub fn set_min_slope_slide_angle(&mut self, angle: f32) -> f32 {
+        self.min_slope_slide_angle
+            .set_value_and_mark_modified(angle)
+    }
+
+    /// Returns min angle (in radians) of a slope the character will automatically slide down.
+    pub fn min_slope_slide_angle(&self) -> f32 {
+        *self.min_slope_slide_angle
+    }
+
+    /// Sets max distance to the ground at which the character will be snapped to it. This is
+    /// useful to keep the character on the ground when it goes down the stairs or slopes. `None`
+    /// disables ground snapping.
+    pub fn set_snap_to_ground(&mut self, distance: Option<f32>) -> Option<f32> {
+        self.snap_to_ground.set_value_and_mark_modified(distance)
+    }
+
+    /// Returns max distance to the ground at which the character will be snapped to it.
+    pub fn snap_to_ground(&self) -> Option<f32> {
+        *self.snap_to_ground
+    }
+
+    /// Sets whether the character should push dynamic rigid bodies it collides with.
+    pub fn set_push_rigid_bodies(&mut self, state: bool) -> bool {
+        self.push_rigid_bodies.set_value_and_mark_modified(state)
+    }
+
+    /// Returns `true` if the character pushes dynamic rigid bodies, `false` - otherwise.
+    pub fn is_push_rigid_bodies(&self) -> bool {
+        *self.push_rigid_bodies
+    }
+
+    /// Sets mass of the character. It is used to calculate impulses applied to dynamic rigid
+    /// bodies when [`Self::is_push_rigid_bodies`] is `true`.
+    pub fn set_mass(&mut self, mass: f32) -> f32 {
+        self.mass.set_value_and_mark_modified(mass.max(0.0))
+    }
+
+    /// Returns mass of the character.
+    pub fn mass(&self) -> f32 {
+        *self.mass
+    }
+
+    /// Sets desired translation (in world coordinates) of the character for the next update of
+    /// the scene graph. The translation will be consumed on the next update and the actual
+    /// translation could be different because of obstacles.
+    pub fn set_desired_translation(&mut self, translation: Vector3<f32>) {
+        self.desired_translation = translation;
+    }
+
+    /// Adds the given translation (in world coordinates) to the desired translation of the
+    /// character. See [`Self::set_desired_translation`] for more info.
+    pub fn move_by(&mut self, translation: Vector3<f32>) {
+        self.desired_translation += translation;
+    }
+
+    /// Returns current desired translation of the character, that will be applied on the next
+    /// update of the scene graph.
+    pub fn desired_translation(&self) -> Vector3<f32> {
+        self.desired_translation
+    }
+
+    /// Returns `true` if the character was standing on the ground after the last movement.
+    pub fn is_grounded(&self) -> bool {
+        self.last_movement.grounded
+    }
+
+    /// Returns `true` if the character was sliding down a slope, that is too steep to climb,
+    /// during the last movement.
+    pub fn is_sliding_down_slope(&self) -> bool {
+        self.last_movement.is_sliding_down_slope
+    }
+
+    /// Returns a list of collisions that happened during the last movement.
+    pub fn collisions(&self) -> &[CharacterCollision] {
+        &self.last_movement.collisions
+    }
+
+    /// Returns the full result of the last movement of the character.
+    pub fn last_movement(&self) -> &CharacterMovement {
+        &self.last_movement
+    }
+
+    pub(crate) fn native_controller(&self) -> KinematicCharacterController {
+        KinematicCharacterController {
+            offset: CharacterLength::Absolute(*self.offset),
+            slide: *self.slide,
+            autostep: if *self.max_step_height > 0.0 {
+                Some(CharacterAutostep {
+                    max_height: CharacterLength::Absolute(*self.max_step_height),
+                    min_width: CharacterLength::Absolute(*self.min_step_width),
+                    include_dynamic_bodies: *self.step_on_dynamic_bodies,
+                })
+            } else {
+                None
+            },
+            max_slope_climb_angle: *self.max_slope_climb_angle,
+            min_slope_slide_angle: *self.min_slope_slide_angle,
+            snap_to_ground: self.snap_to_ground.map(CharacterLength::Absolute),
+            ..Default::default()
+        }
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for CharacterController {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Character Controller", |_| {
+                CharacterControllerBuilder::new(
+                    BaseBuilder::new().with_name("Character Controller"),
+                )
+                .build_node()
+                .into()
+            })
+            .with_group("Physics")
+    }
+}
+
+impl NodeTrait for CharacterController {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn on_removed_from_graph(&mut self, graph: &mut Graph) {
+        graph.physics.remove_body(self.native.get());
+        self.native.set(RigidBodyHandle::invalid());
+
+        Log::info(format!(
+            "Native character controller body was removed for node: {}",
+            self.name()
+        ));
+    }
+
+    fn sync_native(&self, self_handle: Handle<Node>, context: &mut SyncContext) {
+        context
+            .physics
+            .sync_to_character_controller_node(self_handle, self);
+    }
+
+    fn on_global_transform_changed(
+        &self,
+        new_global_transform: &Matrix4<f32>,
+        context: &mut SyncContext,
+    ) {
+        if !m4x4_approx_eq(new_global_transform, &self.global_transform()) {
+            context
+                .physics
+                .set_kinematic_body_position(self.native.get(), new_global_transform);
+        }
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let desired_translation = std::mem::take(&mut self.desired_translation);
+
+        let Some(movement) = context
+            .physics
+            .move_character(self, desired_translation, context.dt)
+        else {
+            return;
+        };
+
+        if movement.translation != Vector3::default() {
+            // Character controller can be root node of a scene, in this case it does not have a
+            // parent.
+            let inv_parent_transform = context
+                .nodes
+                .try_borrow(self.parent)
+                .map(|p| p.global_transform())
+                .unwrap_or_else(Matrix4::identity)
+                .try_inverse()
+                .unwrap_or_else(Matrix4::identity);
+            let local_translation = inv_parent_transform.transform_vector(&movement.translation);
+            let new_position = **self.local_transform().position() + local_translation;
+            self.local_transform_mut().set_position(new_position);
+        }
+
+        self.last_movement = movement;
+    }
+
+    fn validate(&self, scene: &Scene) -> Result<(), String> {
+        for &child in self.children() {
+            if scene.graph.try_get_of_type::<Collider>(child).is_some() {
+                return Ok(());
+            }
+        }
+
+        Err(
+            "The 3D character controller must have at least one 3D collider as a \
+        direct child node to work correctly!"
+                .to_string(),
+        )
+    }
+}
+
+/// Allows you to create character controller in declarative manner.
+pub struct CharacterControllerBuilder {
+    base_builder: BaseBuilder,
+    offset: f32,
+    slide: bool,
+    max_step_height: f32,
+    min_step_width: f32,
+    step_on_dynamic_bodies: bool,
+    max_slope_climb_angle: f32,
+    min_slope_slide_angle: f32,
+    snap_to_ground: Option<f32>,
+    push_rigid_bodies: bool,
+    mass: f32,
+}
+
+impl CharacterControllerBuilder {
+    /// Creates new character controller builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            offset: 0.01,
+            slide: true,
+            max_step_height: 0.3,
+            min_step_width: 0.1,
+            step_on_dynamic_bodies: true,
+            max_slope_climb_angle: 45.0f32.to_radians(),
+            min_slope_slide_angle: 30.0f32.to_radians(),
+            snap_to_ground: Some(0.2),
+            push_rigid_bodies: true,
+            mass: 70.0,
+        }
+    }
+
+    /// Sets the desired gap between the character and its surroundings.
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets whether the character should slide along obstacles or not.
+    pub fn with_slide(mut self, slide: bool) -> Self {
+        self.slide = slide;
+        self
+    }
+
+    /// Sets the desired max height of a step the character can climb.
+    pub fn with_max_step_height(mut self, height: f32) -> Self {
+        self.max_step_height = height;
+        self
+    }
+
+    /// Sets the desired min width of free space that must be available on a step.
+    pub fn with_min_step_width(mut self, width: f32) -> Self {
+        self.min_step_width = width;
+        self
+    }
+
+    /// Sets whether the character can climb on dynamic rigid bodies as on steps.
+    pub fn with_step_on_dynamic_bodies(mut self, state: bool) -> Self {
+        self.step_on_dynamic_bodies = state;
+        self
+    }
+
+    /// Sets the desired max angle (in radians) of a slope the character can climb.
+    pub fn with_max_slope_climb_angle(mut self, angle: f32) -> Self {
+        self.max_slope_climb_angle = angle;
+        self
+    }
+
+    /// Sets the desired min angle (in radians) of a slope the character will slide down.
+    pub fn with_min_slope_slide_angle(mut self, angle: f32) -> Self {
+        self.min_slope_slide_angle = angle;
+        self
+    }
+
+    /// Sets the desired max distance to the ground at which the character will be snapped to it.
+    pub fn with_snap_to_ground(mut self, distance: Option<f32>) -> Self {
+        self.snap_to_ground = distance;
+        self
+    }
+
+    /// Sets whether the character should push dynamic rigid bodies or not.
+    pub fn with_push_rigid_bodies(mut self, state: bool) -> Self {
+        self.push_rigid_bodies = state;
+        self
+    }
+
+    /// Sets the desired mass of the character.
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Creates CharacterController node but does not add it to the graph.
+    pub fn build_character_controller(self) -> CharacterController {
+        CharacterController {
+            base: self.base_builder.build_base(),
+            offset: self.offset.into(),
+            slide: self.slide.into(),
+            max_step_height: self.max_step_height.into(),
+            min_step_width: self.min_step_width.into(),
+            step_on_dynamic_bodies: self.step_on_dynamic_bodies.into(),
+            max_slope_climb_angle: self.max_slope_climb_angle.into(),
+            min_slope_slide_angle: self.min_slope_slide_angle.into(),
+            snap_to_ground: self.snap_to_ground.into(),
+            push_rigid_bodies: self.push_rigid_bodies.into(),
+            mass: self.mass.into(),
+            desired_translation: Default::default(),
+            last_movement: Default::default(),
+            native: Cell::new(RigidBodyHandle::invalid()),
+        }
+    }
+
+    /// Creates CharacterController node but does not add it to the graph.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_character_controller())
+    }
+
+    /// Creates CharacterController node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{UnitQuaternion, Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            character_controller::{CharacterController, CharacterControllerBuilder},
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            node::Node,
+            rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn add_box(
+        graph: &mut Graph,
+        body_type: RigidBodyType,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        half_extents: Vector3<f32>,
+    ) -> Handle<Node> {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(
+                half_extents.x,
+                half_extents.y,
+                half_extents.z,
+            ))
+            .build(graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .with_local_rotation(rotation)
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_body_type(body_type)
+        .build(graph)
+    }
+
+    // Creates a floor, whose top is at zero height.
+    fn add_floor(graph: &mut Graph) -> Handle<Node> {
+        add_box(
+            graph,
+            RigidBodyType::Static,
+            Vector3::new(0.0, -0.5, 0.0),
+            UnitQuaternion::identity(),
+            Vector3::new(10.0, 0.5, 10.0),
+        )
+    }
+
+    fn add_character(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        builder: impl FnOnce(CharacterControllerBuilder) -> CharacterControllerBuilder,
+    ) -> Handle<Node> {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::capsule_y(0.5, 0.3))
+            .build(graph);
+        let character = builder(CharacterControllerBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                )
+                .with_children(&[collider]),
+        ))
+        .build(graph);
+
+        // Native bodies and colliders are created on first updates.
+        graph.update(Vector2::new(800.0, 600.0), DT, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), DT, Default::default());
+
+        character
+    }
+
+    fn move_character(
+        graph: &mut Graph,
+        character: Handle<Node>,
+        translation: Vector3<f32>,
+        frames: usize,
+    ) -> Vector3<f32> {
+        for _ in 0..frames {
+            graph[character]
+                .cast_mut::<CharacterController>()
+                .unwrap()
+                .set_desired_translation(translation);
+            graph.update(Vector2::new(800.0, 600.0), DT, Default::default());
+        }
+        **graph[character].local_transform().position()
+    }
+
+    // A character standing on the floor has this height.
+    const STANDING_HEIGHT: f32 = 0.8;
+
+    #[test]
+    fn test_character_controller_lands_on_ground() {
+        let mut graph = Graph::new();
+
+        let floor_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(5.0, 0.5, 5.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, -0.5, 0.0))
+                        .build(),
+                )
+                .with_children(&[floor_collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        let character_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::capsule_y(0.5, 0.3))
+            .build(&mut graph);
+        let character = CharacterControllerBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 1.5, 0.0))
+                        .build(),
+                )
+                .with_children(&[character_collider]),
+        )
+        .build(&mut graph);
+
+        let dt = 1.0 / 60.0;
+
+        // Native bodies and colliders are created on first updates.
+        graph.update(Vector2::new(800.0, 600.0), dt, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), dt, Default::default());
+
+        graph[character]
+            .cast_mut::<CharacterController>()
+            .unwrap()
+            .set_desired_translation(Vector3::new(0.0, -3.0, 0.0));
+        graph.update(Vector2::new(800.0, 600.0), dt, Default::default());
+
+        let controller = graph[character].cast::<CharacterController>().unwrap();
+        assert!(controller.is_grounded());
+        assert!(!controller.collisions().is_empty());
+        assert_eq!(controller.collisions()[0].collider, floor_collider);
+        assert_eq!(controller.desired_translation(), Vector3::default());
+        let y = controller.local_transform().position().y;
+        assert!((0.79..0.85).contains(&y), "{y}");
+    }
+
+    fn step_test(max_step_height: f32) -> Vector3<f32> {
+        let mut graph = Graph::new();
+        add_floor(&mut graph);
+        // A step with the height of 0.2 and its front edge at x = 1.0.
+        add_box(
+            &mut graph,
+            RigidBodyType::Static,
+            Vector3::new(3.0, 0.1, 0.0),
+            UnitQuaternion::identity(),
+            Vector3::new(2.0, 0.1, 2.0),
+        );
+        let character = add_character(
+            &mut graph,
+            Vector3::new(0.0, STANDING_HEIGHT, 0.0),
+            |builder| builder.with_max_step_height(max_step_height),
+        );
+        move_character(&mut graph, character, Vector3::new(0.05, -0.01, 0.0), 60)
+    }
+
+    #[test]
+    fn test_character_controller_climbs_steps() {
+        let position = step_test(0.3);
+        assert!(position.x > 2.0, "{position}");
+        assert!(position.y > STANDING_HEIGHT + 0.15, "{position}");
+
+        let position = step_test(0.1);
+        assert!(position.x < 1.0, "{position}");
+        assert!(position.y < STANDING_HEIGHT + 0.05, "{position}");
+    }
+
+    fn slope_test(max_slope_climb_angle: f32) -> Vector3<f32> {
+        let mut graph = Graph::new();
+        add_floor(&mut graph);
+        // A 60 degrees ramp, that starts at x = 1.0.
+        let angle = 60.0f32.to_radians();
+        let half_length = 3.0;
+        add_box(
+            &mut graph,
+            RigidBodyType::Static,
+            Vector3::new(
+                1.0 + half_length * angle.cos(),
+                half_length * angle.sin(),
+                0.0,
+            ),
+            UnitQuaternion::from_axis_angle(&Vector3::z_axis(), angle),
+            Vector3::new(half_length, 0.01, 2.0),
+        );
+        let character = add_character(
+            &mut graph,
+            Vector3::new(0.0, STANDING_HEIGHT, 0.0),
+            |builder| {
+                builder
+                    .with_max_step_height(0.0)
+                    .with_max_slope_climb_angle(max_slope_climb_angle.to_radians())
+                    .with_min_slope_slide_angle(max_slope_climb_angle.to_radians())
+            },
+        );
+        move_character(&mut graph, character, Vector3::new(0.05, 0.0, 0.0), 60)
+    }
+
+    #[test]
+    fn test_character_controller_max_slope() {
+        let position = slope_test(70.0);
+        assert!(position.y > STANDING_HEIGHT + 0.5, "{position}");
+
+        let position = slope_test(45.0);
+        assert!(position.y < STANDING_HEIGHT + 0.2, "{position}");
+    }
+
+    fn snap_test(snap_to_ground: Option<f32>) -> (Vector3<f32>, bool) {
+        let mut graph = Graph::new();
+        add_floor(&mut graph);
+        // A ledge with the height of 0.15 and its edge at x = 0.5.
+        add_box(
+            &mut graph,
+            RigidBodyType::Static,
+            Vector3::new(-2.0, 0.075, 0.0),
+            UnitQuaternion::identity(),
+            Vector3::new(2.5, 0.075, 2.0),
+        );
+        let character = add_character(
+            &mut graph,
+            Vector3::new(0.0, STANDING_HEIGHT + 0.15, 0.0),
+            |builder| {
+                builder
+                    .with_max_step_height(0.0)
+                    .with_snap_to_ground(snap_to_ground)
+            },
+        );
+        // Stand on the ledge first and then walk off it. Snapping works only when the character
+        // moves down, so a tiny bit of "gravity" is added.
+        move_character(&mut graph, character, Vector3::new(0.0, -0.01, 0.0), 2);
+        let position = move_character(&mut graph, character, Vector3::new(0.05, -0.001, 0.0), 30);
+        let grounded = graph[character]
+            .cast::<CharacterController>()
+            .unwrap()
+            .is_grounded();
+        (position, grounded)
+    }
+
+    #[test]
+    fn test_character_controller_snaps_to_ground() {
+        let (position, grounded) = snap_test(Some(0.3));
+        assert!(position.x > 1.0, "{position}");
+        assert!(grounded);
+        assert!(position.y < STANDING_HEIGHT + 0.05, "{position}");
+
+        let (position, grounded) = snap_test(None);
+        assert!(position.x > 1.0, "{position}");
+        assert!(!grounded);
+        assert!(position.y > STANDING_HEIGHT + 0.1, "{position}");
+    }
+
+    fn push_test(push_rigid_bodies: bool) -> Vector3<f32> {
+        let mut graph = Graph::new();
+        add_floor(&mut graph);
+        let crate_body = add_box(
+            &mut graph,
+            RigidBodyType::Dynamic,
+            Vector3::new(1.0, 0.25, 0.0),
+            UnitQuaternion::identity(),
+            Vector3::new(0.25, 0.25, 0.25),
+        );
+        let character = add_character(
+            &mut graph,
+            Vector3::new(0.0, STANDING_HEIGHT, 0.0),
+            |builder| {
+                builder
+                    .with_max_step_height(0.0)
+                    .with_push_rigid_bodies(push_rigid_bodies)
+            },
+        );
+        move_character(&mut graph, character, Vector3::new(0.05, -0.01, 0.0), 30);
+        graph[crate_body]
+            .cast::<RigidBody>()
+            .unwrap()
+            .global_position()
+    }
+
+    #[test]
+    fn test_character_controller_pushes_rigid_bodies() {
+        let position = push_test(true);
+        assert!(position.x > 1.1, "{position}");
+
+        let position = push_test(false);
+        assert!(position.x < 1.05, "{position}");
+    }
+}
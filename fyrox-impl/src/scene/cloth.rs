@@ -0,0 +1,1301 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Cloth is a physics entity that simulates a surface of a mesh as a piece of fabric. See
+//! [`Cloth`] docs for more info.
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::{
+        algebra::{Matrix4, Point3, Vector3},
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    graph::{constructor::ConstructorProvider, BaseSceneGraph},
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::{Graph, NodePool},
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait, VertexWriteTrait},
+            surface::{SurfaceData, SurfaceResource},
+            Mesh,
+        },
+        node::{constructor::NodeConstructor, Node, NodeTrait, UpdateContext},
+        Scene,
+    },
+};
+use fxhash::{FxHashMap, FxHashSet};
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// A channel of vertex colors.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum ColorChannel {
+    /// Red channel.
+    #[default]
+    Red,
+    /// Green channel.
+    Green,
+    /// Blue channel.
+    Blue,
+    /// Alpha channel.
+    Alpha,
+}
+
+uuid_provider!(ColorChannel = "9e0c3f1d-6a7b-4f2e-8c5d-2b1a0e9f8d7c");
+
+/// Defines how vertices of a cloth are attached to the mesh. Pinned vertices follow the mesh
+/// (including its skinning), free vertices are simulated. Every vertex has a pin weight in
+/// `[0; 1]` range, where 1.0 means fully pinned vertex and 0.0 - fully simulated one.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum ClothPinning {
+    /// No vertices are pinned, the cloth falls freely.
+    #[default]
+    None,
+    /// Pin weight of a vertex is the sum of its bone weights. This way, vertices skinned to the
+    /// skeleton follow the animation and vertices without bones are simulated.
+    SkinWeights,
+    /// Pin weight of a vertex is taken from the given channel of its color.
+    VertexColor {
+        /// A channel of vertex colors that contains pin weights.
+        channel: ColorChannel,
+    },
+}
+
+uuid_provider!(ClothPinning = "2d8b5e7a-1c4f-4a9e-b3d6-7f0e2c9a8b15");
+
+#[derive(Clone, Debug)]
+struct ClothParticle {
+    position: Vector3<f32>,
+    prev_position: Vector3<f32>,
+    // Position in the local coordinates of the mesh in bind pose.
+    rest_position: Vector3<f32>,
+    pin_weight: f32,
+    bone_indices: [u8; 4],
+    bone_weights: [f32; 4],
+}
+
+#[derive(Clone, Debug)]
+struct DistanceConstraint {
+    a: usize,
+    b: usize,
+    rest_length: f32,
+    is_bending: bool,
+}
+
+/// Runtime state of the simulation. It is created from the surface of the mesh on the first
+/// update, so it is never saved nor cloned.
+#[derive(Default, Debug)]
+pub(crate) struct ClothState {
+    particles: Vec<ClothParticle>,
+    constraints: Vec<DistanceConstraint>,
+    // Indices of particles for each vertex of the surface. Vertices with the same position (for
+    // example, on UV seams) share the same particle.
+    vertex_particles: Vec<usize>,
+    // Triangles of the surface in terms of particles.
+    triangles: Vec<[usize; 3]>,
+    // Bones of the original surface, the simulated surface is rendered without bones, because
+    // its vertices are written in the local coordinates of the mesh.
+    bones: Vec<Handle<Node>>,
+    // The mesh and the index of its surface, that renders the simulated data.
+    mesh: Handle<Node>,
+    surface_index: usize,
+    // The original data of the surface, the simulation is restarted if it is replaced.
+    source: Option<SurfaceResource>,
+    // Unique copy of the surface data, that is modified by the simulation. It is rendered instead
+    // of the original data, which stays untouched.
+    data: Option<SurfaceResource>,
+    time: f32,
+}
+
+impl Clone for ClothState {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl ClothState {
+    fn new(data: &SurfaceData, bones: Vec<Handle<Node>>, pinning: ClothPinning) -> Self {
+        let mut state = Self {
+            bones,
+            ..Default::default()
+        };
+
+        // Weld vertices with the same position.
+        let mut particle_map = FxHashMap::default();
+        for view in data.vertex_buffer.iter() {
+            let position = view
+                .read_3_f32(VertexAttributeUsage::Position)
+                .unwrap_or_default();
+            let bone_indices = view
+                .read_4_u8(VertexAttributeUsage::BoneIndices)
+                .unwrap_or_default();
+            let bone_weights = view
+                .read_4_f32(VertexAttributeUsage::BoneWeight)
+                .unwrap_or_default();
+            let pin_weight = match pinning {
+                ClothPinning::None => 0.0,
+                ClothPinning::SkinWeights => bone_weights.sum(),
+                ClothPinning::VertexColor { channel } => {
+                    let color = view
+                        .read_4_u8(VertexAttributeUsage::Color)
+                        .unwrap_or_default();
+                    let value = match channel {
+                        ColorChannel::Red => color.x,
+                        ColorChannel::Green => color.y,
+                        ColorChannel::Blue => color.z,
+                        ColorChannel::Alpha => color.w,
+                    };
+                    value as f32 / 255.0
+                }
+            }
+            .clamp(0.0, 1.0);
+
+            // Adding zero turns negative zeros into positive ones.
+            let key = (position + Vector3::repeat(0.0)).map(f32::to_bits);
+            let index = *particle_map.entry(key).or_insert_with(|| {
+                state.particles.push(ClothParticle {
+                    position,
+                    prev_position: position,
+                    rest_position: position,
+                    pin_weight: 0.0,
+                    bone_indices: bone_indices.into(),
+                    bone_weights: bone_weights.into(),
+                });
+                state.particles.len() - 1
+            });
+            let particle = &mut state.particles[index];
+            particle.pin_weight = particle.pin_weight.max(pin_weight);
+            state.vertex_particles.push(index);
+        }
+
+        // Every edge of a triangle is a stretch constraint, every pair of triangles that share an
+        // edge produces a bending constraint between their opposite vertices.
+        let mut edges = FxHashMap::<(usize, usize), usize>::default();
+        let mut bending = FxHashSet::<(usize, usize)>::default();
+        for triangle in data.geometry_buffer.iter() {
+            let Some(triangle) = triangle
+                .0
+                .iter()
+                .map(|&i| state.vertex_particles.get(i as usize).copied())
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let triangle = [triangle[0], triangle[1], triangle[2]];
+            if triangle[0] == triangle[1]
+                || triangle[1] == triangle[2]
+                || triangle[0] == triangle[2]
+            {
+                continue;
+            }
+            for i in 0..3 {
+                let (a, b, opposite) = (triangle[i], triangle[(i + 1) % 3], triangle[(i + 2) % 3]);
+                let edge = (a.min(b), a.max(b));
+                if let Some(&other) = edges.get(&edge) {
+                    if other != opposite {
+                        bending.insert((other.min(opposite), other.max(opposite)));
+                    }
+                } else {
+                    edges.insert(edge, opposite);
+                }
+            }
+            state.triangles.push(triangle);
+        }
+
+        let mut edges = edges.into_keys().collect::<Vec<_>>();
+        edges.sort_unstable();
+        let mut bending = bending.into_iter().collect::<Vec<_>>();
+        bending.sort_unstable();
+        for (pairs, is_bending) in [(edges, false), (bending, true)] {
+            for (a, b) in pairs {
+                state.constraints.push(DistanceConstraint {
+                    a,
+                    b,
+                    rest_length: 0.0,
+                    is_bending,
+                });
+            }
+        }
+
+        state
+    }
+
+    // Calculates the positions of the particles that they would have without the simulation.
+    fn target_positions(
+        &self,
+        nodes: &NodePool,
+        mesh_transform: &Matrix4<f32>,
+    ) -> Vec<Vector3<f32>> {
+        let bone_matrices = self
+            .bones
+            .iter()
+            .map(|&bone| {
+                nodes
+                    .try_borrow(bone)
+                    .map_or_else(Matrix4::identity, |bone| {
+                        bone.global_transform() * bone.inv_bind_pose_transform()
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        self.particles
+            .iter()
+            .map(|particle| {
+                let position = Point3::from(particle.rest_position);
+                let mut skinned_position = Vector3::default();
+                let mut total_weight = 0.0;
+                for (&index, &weight) in particle.bone_indices.iter().zip(&particle.bone_weights) {
+                    if let Some(bone_matrix) = bone_matrices.get(index as usize) {
+                        skinned_position += bone_matrix.transform_point(&position).coords * weight;
+                        total_weight += weight;
+                    }
+                }
+                if total_weight > 0.0 {
+                    skinned_position / total_weight
+                } else {
+                    mesh_transform.transform_point(&position).coords
+                }
+            })
+            .collect()
+    }
+
+    // Returns area-weighted normals of the particles.
+    fn normals(&self) -> Vec<Vector3<f32>> {
+        let mut normals = vec![Vector3::default(); self.particles.len()];
+        for &[a, b, c] in self.triangles.iter() {
+            let pa = self.particles[a].position;
+            let normal =
+                (self.particles[b].position - pa).cross(&(self.particles[c].position - pa));
+            for i in [a, b, c] {
+                normals[i] += normal;
+            }
+        }
+        normals
+    }
+}
+
+/// Cloth is a physics entity that simulates a surface of a [`Mesh`] as a piece of fabric using
+/// position-based dynamics. It is suitable for capes, flags, curtains and so on. Every vertex of
+/// the surface is a particle that is affected by gravity and wind, edges of the triangles keep the
+/// particles at their initial distances and pairs of adjacent triangles resist bending.
+///
+/// # Pinning
+///
+/// A cloth that is not attached to anything just falls on the ground, so some of its vertices
+/// usually should be pinned to the mesh, see [`ClothPinning`] for the options. Pinned vertices
+/// follow the mesh and its skinning, so a cape could be pinned to the shoulders of an animated
+/// character using skin weights.
+///
+/// # Collisions
+///
+/// The particles collide with the colliders of the scene (except sensors), the cloth keeps
+/// [`Cloth::collision_margin`] distance from them. Collisions with other cloths and
+/// self-collisions are not supported.
+///
+/// # Limitations
+///
+/// The simulation works with its own copy of the vertex data, which is rendered instead of the
+/// surface data without skinning, because the simulation writes the vertices in the local
+/// coordinates of the mesh. The copy exists only at runtime, the surface data and its bones are
+/// left untouched and saved as is. The mesh must not use static batching.
+#[derive(Visit, Reflect, ComponentProvider, Clone, Debug)]
+pub struct Cloth {
+    base: Base,
+
+    #[reflect(description = "A mesh, a surface of which will be simulated.")]
+    #[reflect(setter = "set_mesh")]
+    pub(crate) mesh: InheritableVariable<Handle<Node>>,
+
+    #[reflect(description = "An index of the surface of the mesh, that will be simulated.")]
+    #[reflect(setter = "set_surface_index")]
+    pub(crate) surface_index: InheritableVariable<u32>,
+
+    #[reflect(description = "Defines how the vertices are attached to the mesh.")]
+    #[reflect(setter = "set_pinning")]
+    pub(crate) pinning: InheritableVariable<ClothPinning>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.01,
+        description = "Resistance of the cloth to stretching."
+    )]
+    #[reflect(setter = "set_stretch_stiffness")]
+    pub(crate) stretch_stiffness: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.01,
+        description = "Resistance of the cloth to bending."
+    )]
+    #[reflect(setter = "set_bending_stiffness")]
+    pub(crate) bending_stiffness: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.01,
+        description = "A fraction of the velocity of the particles, that is lost every frame."
+    )]
+    #[reflect(setter = "set_damping")]
+    pub(crate) damping: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 1.0,
+        max_value = 64.0,
+        step = 1.0,
+        description = "Amount of solver iterations per frame. More iterations make the cloth \
+        stiffer, but slower to simulate."
+    )]
+    #[reflect(setter = "set_iterations")]
+    pub(crate) iterations: InheritableVariable<u32>,
+
+    #[reflect(
+        step = 0.1,
+        description = "A multiplier for the gravity of the physics world."
+    )]
+    #[reflect(setter = "set_gravity_scale")]
+    pub(crate) gravity_scale: InheritableVariable<f32>,
+
+    #[reflect(description = "Velocity of the wind (in world coordinates).")]
+    #[reflect(setter = "set_wind")]
+    pub(crate) wind: InheritableVariable<Vector3<f32>>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.01,
+        description = "Relative variation of the strength of the wind over time and space."
+    )]
+    #[reflect(setter = "set_wind_turbulence")]
+    pub(crate) wind_turbulence: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.01,
+        description = "Defines how strongly the air affects the cloth."
+    )]
+    #[reflect(setter = "set_drag")]
+    pub(crate) drag: InheritableVariable<f32>,
+
+    #[reflect(description = "Whether the cloth collides with the colliders of the scene.")]
+    #[reflect(setter = "set_collisions_enabled")]
+    pub(crate) collisions_enabled: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.005,
+        description = "Min distance between the cloth and the colliders."
+    )]
+    #[reflect(setter = "set_collision_margin")]
+    pub(crate) collision_margin: InheritableVariable<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    state: ClothState,
+}
+
+impl Default for Cloth {
+    fn default() -> Self {
+        ClothBuilder::new(BaseBuilder::new()).build_cloth()
+    }
+}
+
+impl Deref for Cloth {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Cloth {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Cloth {
+    fn type_uuid() -> Uuid {
+        uuid!("b7e3a0c9-5d1f-4e82-9a64-0c8f2d7b3e51")
+    }
+}
+
+impl Cloth {
+    /// Sets a mesh, a surface of which will be simulated. The simulation is restarted.
+    pub fn set_mesh(&mut self, mesh: Handle<Node>) -> Handle<Node> {
+        self.reset();
+        self.mesh.set_value_and_mark_modified(mesh)
+    }
+
+    /// Returns current mesh of the cloth.
+    pub fn mesh(&self) -> Handle<Node> {
+        *self.mesh
+    }
+
+    /// Sets an index of the surface of the mesh, that will be simulated. The simulation is
+    /// restarted.
+    pub fn set_surface_index(&mut self, index: u32) -> u32 {
+        self.reset();
+        self.surface_index.set_value_and_mark_modified(index)
+    }
+
+    /// Returns an index of the simulated surface of the mesh.
+    pub fn surface_index(&self) -> u32 {
+        *self.surface_index
+    }
+
+    /// Sets new pinning mode of the vertices. The simulation is restarted.
+    pub fn set_pinning(&mut self, pinning: ClothPinning) -> ClothPinning {
+        self.reset();
+        self.pinning.set_value_and_mark_modified(pinning)
+    }
+
+    /// Returns current pinning mode of the vertices.
+    pub fn pinning(&self) -> ClothPinning {
+        *self.pinning
+    }
+
+    /// Sets resistance of the cloth to stretching in `[0; 1]` range.
+    pub fn set_stretch_stiffness(&mut self, stiffness: f32) -> f32 {
+        self.stretch_stiffness
+            .set_value_and_mark_modified(stiffness.clamp(0.0, 1.0))
+    }
+
+    /// Returns resistance of the cloth to stretching.
+    pub fn stretch_stiffness(&self) -> f32 {
+        *self.stretch_stiffness
+    }
+
+    /// Sets resistance of the cloth to bending in `[0; 1]` range. Small values make the cloth
+    /// look like silk, large values - like leather.
+    pub fn set_bending_stiffness(&mut self, stiffness: f32) -> f32 {
+        self.bending_stiffness
+            .set_value_and_mark_modified(stiffness.clamp(0.0, 1.0))
+    }
+
+    /// Returns resistance of the cloth to bending.
+    pub fn bending_stiffness(&self) -> f32 {
+        *self.bending_stiffness
+    }
+
+    /// Sets a fraction of the velocity of the particles, that is lost every frame.
+    pub fn set_damping(&mut self, damping: f32) -> f32 {
+        self.damping
+            .set_value_and_mark_modified(damping.clamp(0.0, 1.0))
+    }
+
+    /// Returns a fraction of the velocity of the particles, that is lost every frame.
+    pub fn damping(&self) -> f32 {
+        *self.damping
+    }
+
+    /// Sets amount of solver iterations per frame.
+    pub fn set_iterations(&mut self, iterations: u32) -> u32 {
+        self.iterations
+            .set_value_and_mark_modified(iterations.max(1))
+    }
+
+    /// Returns amount of solver iterations per frame.
+    pub fn iterations(&self) -> u32 {
+        *self.iterations
+    }
+
+    /// Sets a multiplier for the gravity of the physics world.
+    pub fn set_gravity_scale(&mut self, scale: f32) -> f32 {
+        self.gravity_scale.set_value_and_mark_modified(scale)
+    }
+
+    /// Returns a multiplier for the gravity of the physics world.
+    pub fn gravity_scale(&self) -> f32 {
+        *self.gravity_scale
+    }
+
+    /// Sets velocity of the wind (in world coordinates).
+    pub fn set_wind(&mut self, wind: Vector3<f32>) -> Vector3<f32> {
+        self.wind.set_value_and_mark_modified(wind)
+    }
+
+    /// Returns velocity of the wind.
+    pub fn wind(&self) -> Vector3<f32> {
+        *self.wind
+    }
+
+    /// Sets relative variation of the strength of the wind in `[0; 1]` range. It makes the cloth
+    /// flutter instead of being stretched by a constant force.
+    pub fn set_wind_turbulence(&mut self, turbulence: f32) -> f32 {
+        self.wind_turbulence
+            .set_value_and_mark_modified(turbulence.clamp(0.0, 1.0))
+    }
+
+    /// Returns relative variation of the strength of the wind.
+    pub fn wind_turbulence(&self) -> f32 {
+        *self.wind_turbulence
+    }
+
+    /// Sets a coefficient that defines how strongly the air (and the wind) affects the cloth.
+    pub fn set_drag(&mut self, drag: f32) -> f32 {
+        self.drag.set_value_and_mark_modified(drag.max(0.0))
+    }
+
+    /// Returns current drag coefficient.
+    pub fn drag(&self) -> f32 {
+        *self.drag
+    }
+
+    /// Sets whether the cloth collides with the colliders of the scene or not.
+    pub fn set_collisions_enabled(&mut self, enabled: bool) -> bool {
+        self.collisions_enabled.set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns `true` if the cloth collides with the colliders of the scene.
+    pub fn is_collisions_enabled(&self) -> bool {
+        *self.collisions_enabled
+    }
+
+    /// Sets min distance between the cloth and the colliders.
+    pub fn set_collision_margin(&mut self, margin: f32) -> f32 {
+        self.collision_margin
+            .set_value_and_mark_modified(margin.max(0.0))
+    }
+
+    /// Returns min distance between the cloth and the colliders.
+    pub fn collision_margin(&self) -> f32 {
+        *self.collision_margin
+    }
+
+    /// Restarts the simulation, all the particles will be moved to their initial positions on
+    /// the next update.
+    pub fn reset(&mut self) {
+        // Keep the rest of the state, so the surface, that was simulated before, could be
+        // released on the next update.
+        self.state.particles.clear();
+    }
+
+    /// Returns current positions of the simulated vertices (in world coordinates). Vertices of
+    /// the surface with the same position share the same simulated vertex.
+    pub fn positions(&self) -> impl Iterator<Item = Vector3<f32>> + '_ {
+        self.state
+            .particles
+            .iter()
+            .map(|particle| particle.position)
+    }
+
+    fn simulate(&mut self, targets: &[Vector3<f32>], gravity: Vector3<f32>, dt: f32) {
+        let state = &mut self.state;
+        state.time += dt;
+
+        // Aerodynamic forces are calculated per triangle and distributed between its vertices.
+        let mut accelerations = vec![gravity * *self.gravity_scale; state.particles.len()];
+        if *self.drag > 0.0 && dt > 0.0 {
+            let mut air_forces = vec![(Vector3::default(), 0u32); state.particles.len()];
+            for &[a, b, c] in state.triangles.iter() {
+                let [pa, pb, pc] = [a, b, c].map(|i| &state.particles[i]);
+                let Some(normal) = (pb.position - pa.position)
+                    .cross(&(pc.position - pa.position))
+                    .try_normalize(f32::EPSILON)
+                else {
+                    continue;
+                };
+                let center = (pa.position + pb.position + pc.position) / 3.0;
+                let velocity = (pa.position - pa.prev_position + pb.position - pb.prev_position
+                    + pc.position
+                    - pc.prev_position)
+                    / (3.0 * dt);
+                let gust = 1.0
+                    + *self.wind_turbulence
+                        * (state.time * 2.3 + center.x * 0.7 + center.z * 0.4).sin()
+                        * (state.time * 1.7 + center.y * 0.9).cos();
+                let relative_velocity = *self.wind * gust - velocity;
+                let force = normal * normal.dot(&relative_velocity) * *self.drag;
+                for i in [a, b, c] {
+                    air_forces[i].0 += force;
+                    air_forces[i].1 += 1;
+                }
+            }
+            for (acceleration, (force, count)) in accelerations.iter_mut().zip(air_forces) {
+                if count > 0 {
+                    *acceleration += force / count as f32;
+                }
+            }
+        }
+
+        // Integration.
+        let velocity_factor = 1.0 - *self.damping;
+        for ((particle, target), acceleration) in
+            state.particles.iter_mut().zip(targets).zip(accelerations)
+        {
+            if particle.pin_weight >= 1.0 {
+                particle.prev_position = *target;
+                particle.position = *target;
+            } else {
+                let velocity = (particle.position - particle.prev_position) * velocity_factor;
+                particle.prev_position = particle.position;
+                particle.position += velocity + acceleration * dt * dt;
+            }
+        }
+
+        // Constraints. Stiffness is adjusted so that the result does not depend on the amount of
+        // iterations.
+        let iterations = (*self.iterations).max(1);
+        let stiffness = |k: f32| 1.0 - (1.0 - k.clamp(0.0, 1.0)).powf(1.0 / iterations as f32);
+        let stretch_stiffness = stiffness(*self.stretch_stiffness);
+        let bending_stiffness = stiffness(*self.bending_stiffness);
+        for _ in 0..iterations {
+            for constraint in state.constraints.iter() {
+                let [a, b] = [constraint.a, constraint.b].map(|i| &state.particles[i]);
+                let (wa, wb) = (1.0 - a.pin_weight, 1.0 - b.pin_weight);
+                let total_weight = wa + wb;
+                if total_weight <= f32::EPSILON {
+                    continue;
+                }
+                let delta = b.position - a.position;
+                let length = delta.norm();
+                if length <= f32::EPSILON {
+                    continue;
+                }
+                let k = if constraint.is_bending {
+                    bending_stiffness
+                } else {
+                    stretch_stiffness
+                };
+                let correction =
+                    delta * ((length - constraint.rest_length) / (length * total_weight) * k);
+                state.particles[constraint.a].position += correction * wa;
+                state.particles[constraint.b].position -= correction * wb;
+            }
+
+            // Partially pinned vertices are attracted to the mesh.
+            for (particle, target) in state.particles.iter_mut().zip(targets) {
+                if particle.pin_weight > 0.0 {
+                    particle.position = particle.position.lerp(target, particle.pin_weight);
+                }
+            }
+        }
+    }
+
+    fn write_vertices(&self, mesh: &mut Mesh) {
+        let Some(data) = self.state.data.as_ref() else {
+            return;
+        };
+        let inv_transform = mesh
+            .global_transform()
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
+        let normals = self.state.normals();
+
+        let mut data = data.data_ref();
+        let has_normals = data
+            .vertex_buffer
+            .has_attribute(VertexAttributeUsage::Normal);
+        let mut vertex_buffer = data.vertex_buffer.modify();
+        for (mut view, &index) in vertex_buffer
+            .iter_mut()
+            .zip(self.state.vertex_particles.iter())
+        {
+            let position = inv_transform
+                .transform_point(&Point3::from(self.state.particles[index].position))
+                .coords;
+            let _ = view.write_3_f32(VertexAttributeUsage::Position, position);
+            if has_normals {
+                let normal = inv_transform
+                    .transform_vector(&normals[index])
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vector3::y);
+                let _ = view.write_3_f32(VertexAttributeUsage::Normal, normal);
+            }
+        }
+        drop(vertex_buffer);
+        drop(data);
+
+        mesh.invalidate_bounding_box();
+    }
+
+    // Makes the surface, that was simulated before, render its own data again.
+    fn release_surface(&self, mesh: Option<&mut Node>) {
+        let Some(mesh) = mesh.and_then(|node| node.cast_mut::<Mesh>()) else {
+            return;
+        };
+        let Some(surface) = mesh.surfaces_mut().get_mut(self.state.surface_index) else {
+            return;
+        };
+        if self.state.data.is_some() && surface.simulated_data == self.state.data {
+            surface.simulated_data = None;
+            mesh.invalidate_bounding_box();
+        }
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for Cloth {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Cloth", |_| {
+                ClothBuilder::new(BaseBuilder::new().with_name("Cloth"))
+                    .build_node()
+                    .into()
+            })
+            .with_group("Physics")
+    }
+}
+
+impl NodeTrait for Cloth {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let surface_index = *self.surface_index as usize;
+        let Some((mesh_transform, surface_data, simulated_data, bones)) = context
+            .nodes
+            .try_borrow(*self.mesh)
+            .and_then(|node| node.cast::<Mesh>())
+            .and_then(|mesh| {
+                let surface = mesh.surfaces().get(surface_index)?;
+                Some((
+                    mesh.global_transform(),
+                    surface.data(),
+                    surface.simulated_data.clone(),
+                    surface.bones().to_vec(),
+                ))
+            })
+        else {
+            self.release_surface(context.nodes.try_borrow_mut(self.state.mesh));
+            self.state = Default::default();
+            return;
+        };
+
+        if !surface_data.is_ok() {
+            return;
+        }
+
+        // The surface data could be replaced by someone else, in this case the simulation must
+        // be restarted with a new copy of the data.
+        let is_initialized = !self.state.particles.is_empty()
+            && self.state.mesh == *self.mesh
+            && self.state.surface_index == surface_index
+            && self.state.source.as_ref() == Some(&surface_data)
+            && self.state.data.is_some()
+            && self.state.data == simulated_data;
+        if !is_initialized {
+            self.release_surface(context.nodes.try_borrow_mut(self.state.mesh));
+
+            let data = surface_data.data_ref().clone();
+            let mut state = ClothState::new(&data, bones, *self.pinning);
+            state.mesh = *self.mesh;
+            state.surface_index = surface_index;
+            state.source = Some(surface_data);
+            state.data = Some(SurfaceResource::new_ok(ResourceKind::Embedded, data));
+            self.state = state;
+
+            let targets = self.state.target_positions(context.nodes, &mesh_transform);
+            for (particle, target) in self.state.particles.iter_mut().zip(targets) {
+                particle.position = target;
+                particle.prev_position = target;
+            }
+            for constraint in self.state.constraints.iter_mut() {
+                constraint.rest_length = (self.state.particles[constraint.a].position
+                    - self.state.particles[constraint.b].position)
+                    .norm();
+            }
+
+            if let Some(surface) = context
+                .nodes
+                .try_borrow_mut(*self.mesh)
+                .and_then(|node| node.cast_mut::<Mesh>())
+                .and_then(|mesh| mesh.surfaces_mut().get_mut(surface_index))
+            {
+                surface.simulated_data.clone_from(&self.state.data);
+            }
+        }
+
+        let targets = self.state.target_positions(context.nodes, &mesh_transform);
+        // Large time steps make the simulation unstable.
+        let dt = context.dt.min(1.0 / 30.0);
+        self.simulate(&targets, *context.physics.gravity, dt);
+
+        if *self.collisions_enabled {
+            context.physics.push_points_out_of_colliders(
+                self.state
+                    .particles
+                    .iter_mut()
+                    .filter(|particle| particle.pin_weight < 1.0)
+                    .map(|particle| &mut particle.position),
+                *self.collision_margin,
+            );
+        }
+
+        if let Some(mesh) = context
+            .nodes
+            .try_borrow_mut(*self.mesh)
+            .and_then(|node| node.cast_mut::<Mesh>())
+        {
+            self.write_vertices(mesh);
+        }
+    }
+
+    fn on_removed_from_graph(&mut self, graph: &mut Graph) {
+        self.release_surface(graph.try_get_mut(self.state.mesh));
+    }
+
+    fn validate(&self, scene: &Scene) -> Result<(), String> {
+        let Some(mesh) = scene
+            .graph
+            .try_get(self.mesh())
+            .and_then(|node| node.cast::<Mesh>())
+        else {
+            return Err("Cloth has invalid or unassigned handle to a mesh, \
+            the cloth will not operate!"
+                .to_string());
+        };
+
+        if mesh.surfaces().len() <= self.surface_index() as usize {
+            return Err(format!(
+                "The mesh of the cloth has no surface with index {}!",
+                self.surface_index()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Allows you to create cloth in declarative manner.
+pub struct ClothBuilder {
+    base_builder: BaseBuilder,
+    mesh: Handle<Node>,
+    surface_index: u32,
+    pinning: ClothPinning,
+    stretch_stiffness: f32,
+    bending_stiffness: f32,
+    damping: f32,
+    iterations: u32,
+    gravity_scale: f32,
+    wind: Vector3<f32>,
+    wind_turbulence: f32,
+    drag: f32,
+    collisions_enabled: bool,
+    collision_margin: f32,
+}
+
+impl ClothBuilder {
+    /// Creates new cloth builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            mesh: Default::default(),
+            surface_index: 0,
+            pinning: Default::default(),
+            stretch_stiffness: 1.0,
+            bending_stiffness: 0.1,
+            damping: 0.01,
+            iterations: 8,
+            gravity_scale: 1.0,
+            wind: Default::default(),
+            wind_turbulence: 0.3,
+            drag: 0.5,
+            collisions_enabled: true,
+            collision_margin: 0.02,
+        }
+    }
+
+    /// Sets the desired mesh of the cloth.
+    pub fn with_mesh(mut self, mesh: Handle<Node>) -> Self {
+        self.mesh = mesh;
+        self
+    }
+
+    /// Sets the desired index of the simulated surface of the mesh.
+    pub fn with_surface_index(mut self, index: u32) -> Self {
+        self.surface_index = index;
+        self
+    }
+
+    /// Sets the desired pinning mode of the vertices.
+    pub fn with_pinning(mut self, pinning: ClothPinning) -> Self {
+        self.pinning = pinning;
+        self
+    }
+
+    /// Sets the desired resistance of the cloth to stretching.
+    pub fn with_stretch_stiffness(mut self, stiffness: f32) -> Self {
+        self.stretch_stiffness = stiffness;
+        self
+    }
+
+    /// Sets the desired resistance of the cloth to bending.
+    pub fn with_bending_stiffness(mut self, stiffness: f32) -> Self {
+        self.bending_stiffness = stiffness;
+        self
+    }
+
+    /// Sets the desired damping of the velocity of the particles.
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Sets the desired amount of solver iterations per frame.
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the desired multiplier for the gravity.
+    pub fn with_gravity_scale(mut self, scale: f32) -> Self {
+        self.gravity_scale = scale;
+        self
+    }
+
+    /// Sets the desired velocity of the wind.
+    pub fn with_wind(mut self, wind: Vector3<f32>) -> Self {
+        self.wind = wind;
+        self
+    }
+
+    /// Sets the desired variation of the strength of the wind.
+    pub fn with_wind_turbulence(mut self, turbulence: f32) -> Self {
+        self.wind_turbulence = turbulence;
+        self
+    }
+
+    /// Sets the desired drag coefficient.
+    pub fn with_drag(mut self, drag: f32) -> Self {
+        self.drag = drag;
+        self
+    }
+
+    /// Sets whether the cloth collides with the colliders or not.
+    pub fn with_collisions_enabled(mut self, enabled: bool) -> Self {
+        self.collisions_enabled = enabled;
+        self
+    }
+
+    /// Sets the desired min distance between the cloth and the colliders.
+    pub fn with_collision_margin(mut self, margin: f32) -> Self {
+        self.collision_margin = margin;
+        self
+    }
+
+    /// Creates Cloth node but does not add it to the graph.
+    pub fn build_cloth(self) -> Cloth {
+        Cloth {
+            base: self.base_builder.build_base(),
+            mesh: self.mesh.into(),
+            surface_index: self.surface_index.into(),
+            pinning: self.pinning.into(),
+            stretch_stiffness: self.stretch_stiffness.into(),
+            bending_stiffness: self.bending_stiffness.into(),
+            damping: self.damping.into(),
+            iterations: self.iterations.into(),
+            gravity_scale: self.gravity_scale.into(),
+            wind: self.wind.into(),
+            wind_turbulence: self.wind_turbulence.into(),
+            drag: self.drag.into(),
+            collisions_enabled: self.collisions_enabled.into(),
+            collision_margin: self.collision_margin.into(),
+            state: Default::default(),
+        }
+    }
+
+    /// Creates Cloth node but does not add it to the graph.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_cloth())
+    }
+
+    /// Creates Cloth node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::{manager::ResourceManager, untyped::ResourceKind},
+        core::{
+            algebra::{Vector2, Vector3, Vector4},
+            math::TriangleDefinition,
+            visitor::{Visit, Visitor, VisitorFlags},
+        },
+        graph::BaseSceneGraph,
+        material::Material,
+        scene::{
+            base::BaseBuilder,
+            cloth::{Cloth, ClothBuilder, ClothPinning, ColorChannel},
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            mesh::{
+                buffer::{
+                    TriangleBuffer, VertexAttributeDataType, VertexAttributeDescriptor,
+                    VertexAttributeUsage, VertexBuffer, VertexReadTrait, VertexWriteTrait,
+                },
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                vertex::StaticVertex,
+                Mesh, MeshBuilder,
+            },
+            pivot::PivotBuilder,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+    use std::sync::Arc;
+
+    // Horizontal grid of 2x2 meters with two pinned corners.
+    fn make_grid(size: usize) -> SurfaceData {
+        let mut vertices = Vec::new();
+        for z in 0..size {
+            for x in 0..size {
+                let position = Vector2::new(x as f32, z as f32) * 2.0 / (size - 1) as f32;
+                vertices.push(StaticVertex::from_pos_uv_normal(
+                    Vector3::new(position.x - 1.0, 0.0, position.y - 1.0),
+                    position,
+                    Vector3::y(),
+                ));
+            }
+        }
+        let mut triangles = Vec::new();
+        for z in 0..size - 1 {
+            for x in 0..size - 1 {
+                let i = (z * size + x) as u32;
+                let size = size as u32;
+                triangles.push(TriangleDefinition([i, i + size, i + 1]));
+                triangles.push(TriangleDefinition([i + 1, i + size, i + size + 1]));
+            }
+        }
+        let mut data = SurfaceData::new(
+            VertexBuffer::new(vertices.len(), vertices).unwrap(),
+            TriangleBuffer::new(triangles),
+        );
+
+        let mut vertex_buffer = data.vertex_buffer.modify();
+        vertex_buffer
+            .add_attribute(
+                VertexAttributeDescriptor {
+                    usage: VertexAttributeUsage::Color,
+                    data_type: VertexAttributeDataType::U8,
+                    size: 4,
+                    divisor: 0,
+                    shader_location: 4,
+                    normalized: true,
+                },
+                [0u8; 4],
+            )
+            .unwrap();
+        for (i, mut view) in vertex_buffer.iter_mut().enumerate() {
+            if i == 0 || i == size - 1 {
+                view.write_4_u8(VertexAttributeUsage::Color, Vector4::new(255, 0, 0, 255))
+                    .unwrap();
+            }
+        }
+        drop(vertex_buffer);
+
+        data
+    }
+
+    #[test]
+    fn test_pinned_cloth_falls_on_the_floor() {
+        let mut graph = Graph::new();
+
+        let floor_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(50.0, 0.5, 50.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, -0.5, 0.0))
+                        .build(),
+                )
+                .with_children(&[floor_collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        let size = 9;
+        let data = SurfaceResource::new_ok(ResourceKind::Embedded, make_grid(size));
+        let mesh = MeshBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 1.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(data.clone()).build()])
+        .build(&mut graph);
+
+        let margin = 0.02;
+        let cloth = ClothBuilder::new(BaseBuilder::new())
+            .with_mesh(mesh)
+            .with_pinning(ClothPinning::VertexColor {
+                channel: ColorChannel::Red,
+            })
+            .with_collision_margin(margin)
+            .build(&mut graph);
+
+        let dt = 1.0 / 60.0;
+        for _ in 0..120 {
+            graph.update(Vector2::new(800.0, 600.0), dt, Default::default());
+        }
+
+        let cloth_ref = graph[cloth].cast::<Cloth>().unwrap();
+        let positions = cloth_ref.positions().collect::<Vec<_>>();
+        assert_eq!(positions.len(), size * size);
+        // Pinned corners stay in place.
+        for i in [0, size - 1] {
+            assert!((positions[i].y - 1.0).abs() < 1.0e-4, "{:?}", positions[i]);
+        }
+        // The rest falls, but stays above the floor.
+        let center = positions[size * size / 2];
+        assert!(center.y < 0.5, "{center:?}");
+        for position in positions.iter() {
+            assert!(position.y > margin * 0.5, "{position:?}");
+        }
+        // The far edge lies on the floor.
+        let far_corner = positions[size * size - 1];
+        assert!(far_corner.y < 0.1, "{far_corner:?}");
+
+        // The mesh renders its own copy of the surface data, which is updated every frame.
+        let surface = &graph[mesh].as_mesh().surfaces()[0];
+        assert_eq!(surface.data(), data);
+        assert_ne!(surface.render_data(), &data);
+        let surface_data = surface.render_data().data_ref();
+        let vertex = surface_data.vertex_buffer.get(size * size - 1).unwrap();
+        let local_position = vertex.read_3_f32(VertexAttributeUsage::Position).unwrap();
+        assert!((local_position.y - (far_corner.y - 1.0)).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_simulated_surface_is_not_saved() {
+        let mut graph = Graph::new();
+
+        let bone = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let size = 5;
+        let data = SurfaceResource::new_ok(ResourceKind::Embedded, make_grid(size));
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(data.clone())
+                .with_bones(vec![bone])
+                .build()])
+            .build(&mut graph);
+        let cloth = ClothBuilder::new(BaseBuilder::new())
+            .with_mesh(mesh)
+            .build(&mut graph);
+
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let surface = &graph[mesh].as_mesh().surfaces()[0];
+        assert_eq!(surface.data(), data);
+        assert_eq!(surface.bones(), &[bone]);
+        assert!(surface.render_bones().is_empty());
+        let simulated_data = surface.render_data().data_ref();
+        let vertex = simulated_data.vertex_buffer.get(0).unwrap();
+        assert!(vertex.read_3_f32(VertexAttributeUsage::Position).unwrap().y < 0.0);
+        drop(simulated_data);
+
+        let mut visitor = Visitor::new();
+        visitor.flags = VisitorFlags::SERIALIZE_EVERYTHING;
+        graph[mesh]
+            .as_mesh_mut()
+            .visit("Mesh", &mut visitor)
+            .unwrap();
+        let binary = visitor.save_binary_to_vec().unwrap();
+
+        let resource_manager = ResourceManager::new(Arc::new(Default::default()));
+        {
+            let state = resource_manager.state();
+            state.constructors_container.add::<SurfaceData>();
+            state.constructors_container.add::<Material>();
+        }
+        let mut visitor = Visitor::load_from_memory(&binary).unwrap();
+        visitor.blackboard.register(Arc::new(resource_manager));
+        let mut loaded = Mesh::default();
+        loaded.visit("Mesh", &mut visitor).unwrap();
+
+        let surface = &loaded.surfaces()[0];
+        assert_eq!(surface.bones(), &[bone]);
+        assert_eq!(surface.render_data(), surface.data_ref());
+        let loaded_data = surface.data_ref().data_ref();
+        let original_data = data.data_ref();
+        assert_eq!(
+            loaded_data.vertex_buffer.vertex_count(),
+            original_data.vertex_buffer.vertex_count()
+        );
+        for (loaded, original) in loaded_data
+            .vertex_buffer
+            .iter()
+            .zip(original_data.vertex_buffer.iter())
+        {
+            assert_eq!(
+                loaded.read_3_f32(VertexAttributeUsage::Position).unwrap(),
+                original.read_3_f32(VertexAttributeUsage::Position).unwrap()
+            );
+        }
+        drop(original_data);
+
+        // The mesh renders its own data again when the cloth is removed.
+        graph.remove_node(cloth);
+        let surface = &graph[mesh].as_mesh().surfaces()[0];
+        assert_eq!(surface.render_data(), &data);
+        assert_eq!(surface.render_bones(), &[bone]);
+    }
+}
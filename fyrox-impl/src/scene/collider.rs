@@ -37,6 +37,7 @@ use crate::{
     },
     scene::{
         base::{Base, BaseBuilder},
+        character_controller::CharacterController,
         graph::{
             physics::{CoefficientCombineRule, ContactPair, IntersectionPair, PhysicsWorld},
             Graph,
@@ -203,7 +204,8 @@ uuid_provider!(GeometrySource = "6fea7c72-c488-48a1-935f-2752a8a10e9a");
 /// Arbitrary triangle mesh shape.
 #[derive(Default, Clone, Debug, Visit, Reflect, PartialEq, Eq)]
 pub struct TrimeshShape {
-    /// Geometry sources for the shape.
+    /// Geometry sources for the shape. A source could be either a mesh or a spline with extrusion
+    /// (see [`crate::scene::spline::SplineExtrusion`]).
     pub sources: Vec<GeometrySource>,
 }
 
@@ -917,14 +919,16 @@ impl NodeTrait for Collider {
     fn validate(&self, scene: &Scene) -> Result<(), String> {
         let mut message = String::new();
 
-        if scene
-            .graph
-            .try_get(self.parent())
+        let parent = scene.graph.try_get(self.parent());
+        if parent
             .and_then(|p| p.component_ref::<RigidBody>())
             .is_none()
+            && parent
+                .and_then(|p| p.component_ref::<CharacterController>())
+                .is_none()
         {
-            message += "3D Collider must be a direct child of a 3D Rigid Body node, \
-            otherwise it will not have any effect!";
+            message += "3D Collider must be a direct child of a 3D Rigid Body or a 3D Character \
+            Controller node, otherwise it will not have any effect!";
         }
 
         match &*self.shape {
@@ -41,6 +41,7 @@ use crate::{
         base::{Base, BaseBuilder},
         collider::InteractionGroups,
         dim2::{
+            character_controller::CharacterController,
             physics::{ContactPair, IntersectionPair, PhysicsWorld},
             rigidbody::RigidBody,
         },
@@ -638,14 +639,16 @@ impl NodeTrait for Collider {
     fn validate(&self, scene: &Scene) -> Result<(), String> {
         let mut message = String::new();
 
-        if scene
-            .graph
-            .try_get(self.parent())
+        let parent = scene.graph.try_get(self.parent());
+        if parent
             .and_then(|p| p.component_ref::<RigidBody>())
             .is_none()
+            && parent
+                .and_then(|p| p.component_ref::<CharacterController>())
+                .is_none()
         {
-            message += "2D Collider must be a direct child of a 3D Rigid Body node, \
-            otherwise it will not have any effect!";
+            message += "2D Collider must be a direct child of a 2D Rigid Body or a 2D Character \
+            Controller node, otherwise it will not have any effect!";
         }
 
         match &*self.shape {
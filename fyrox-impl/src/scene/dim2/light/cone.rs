@@ -0,0 +1,301 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! 2D cone light emits light in a circle sector along its local Y axis. See [`ConeLight`] docs
+//! for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector2, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::{Line, SceneDrawingContext},
+        dim2::light::{BaseLight, BaseLightBuilder},
+        graph::Graph,
+        node::{constructor::NodeConstructor, Node, NodeTrait},
+    },
+};
+use fyrox_graph::{constructor::ConstructorProvider, BaseSceneGraph};
+use std::ops::{Deref, DerefMut};
+
+/// 2D cone light emits light in a circle sector along its local Y axis, it could be imagined as
+/// a flashlight. The sector is defined by two angles:
+///
+/// 1) Hotspot cone angle - the full angle of the sector where the intensity of the light is at
+/// max.
+/// 2) Cone angle - the full angle of the sector at which intensity of the light is zero. Intensity
+/// between these two angles has smooth transition.
+///
+/// To change the direction of the light, rotate the node around Z axis.
+#[derive(Debug, Reflect, Clone, Visit, ComponentProvider)]
+pub struct ConeLight {
+    #[component(include)]
+    base_light: BaseLight,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_radius")]
+    radius: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_height")]
+    height: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, max_value = 6.2831, step = 0.1)]
+    #[reflect(setter = "set_cone_angle")]
+    cone_angle: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, max_value = 6.2831, step = 0.1)]
+    #[reflect(setter = "set_hotspot_cone_angle")]
+    hotspot_cone_angle: InheritableVariable<f32>,
+}
+
+impl Deref for ConeLight {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base_light.base
+    }
+}
+
+impl DerefMut for ConeLight {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base_light.base
+    }
+}
+
+impl TypeUuidProvider for ConeLight {
+    fn type_uuid() -> Uuid {
+        uuid!("52f2d3e6-6b1a-411f-ba01-cfe1f740e851")
+    }
+}
+
+impl ConeLight {
+    /// Returns a reference to base light.
+    pub fn base_light_ref(&self) -> &BaseLight {
+        &self.base_light
+    }
+
+    /// Returns a reference to base light.
+    pub fn base_light_mut(&mut self) -> &mut BaseLight {
+        &mut self.base_light
+    }
+
+    /// Sets radius of the light.
+    #[inline]
+    pub fn set_radius(&mut self, radius: f32) -> f32 {
+        self.radius.set_value_and_mark_modified(radius.abs())
+    }
+
+    /// Returns radius of the light.
+    #[inline]
+    pub fn radius(&self) -> f32 {
+        *self.radius
+    }
+
+    /// Sets height of the light above XY plane. It is used for normal mapping only.
+    #[inline]
+    pub fn set_height(&mut self, height: f32) -> f32 {
+        self.height.set_value_and_mark_modified(height.abs())
+    }
+
+    /// Returns height of the light above XY plane.
+    #[inline]
+    pub fn height(&self) -> f32 {
+        *self.height
+    }
+
+    /// Sets the full angle (in radians) of the sector at which intensity of the light is zero.
+    #[inline]
+    pub fn set_cone_angle(&mut self, cone_angle: f32) -> f32 {
+        self.cone_angle
+            .set_value_and_mark_modified(cone_angle.clamp(0.0, std::f32::consts::TAU))
+    }
+
+    /// Returns the full angle (in radians) of the sector at which intensity of the light is zero.
+    #[inline]
+    pub fn cone_angle(&self) -> f32 {
+        *self.cone_angle
+    }
+
+    /// Sets the full angle (in radians) of the sector at which intensity of the light is at max.
+    #[inline]
+    pub fn set_hotspot_cone_angle(&mut self, hotspot_cone_angle: f32) -> f32 {
+        self.hotspot_cone_angle
+            .set_value_and_mark_modified(hotspot_cone_angle.clamp(0.0, std::f32::consts::TAU))
+    }
+
+    /// Returns the full angle (in radians) of the sector at which intensity of the light is at max.
+    #[inline]
+    pub fn hotspot_cone_angle(&self) -> f32 {
+        *self.hotspot_cone_angle
+    }
+
+    /// Returns world-space direction of the light on XY plane.
+    #[inline]
+    pub fn direction(&self) -> Vector2<f32> {
+        self.up_vector()
+            .xy()
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector2::y)
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for ConeLight {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Cone Light 2D", |_| {
+                ConeLightBuilder::new(BaseLightBuilder::new(
+                    BaseBuilder::new().with_name("ConeLight2D"),
+                ))
+                .build_node()
+                .into()
+            })
+            .with_group("Light 2D")
+    }
+}
+
+impl NodeTrait for ConeLight {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::from_radius(*self.radius)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        // Discard scaling part, light emission distance does not affected by scaling.
+        self.local_bounding_box()
+            .transform(&self.global_transform_without_scaling())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let position = self.global_position();
+        let direction = self.direction();
+        let direction_angle = direction.y.atan2(direction.x);
+        let half_cone_angle = self.cone_angle() * 0.5;
+        let begin_angle = direction_angle - half_cone_angle;
+        let end_angle = direction_angle + half_cone_angle;
+        ctx.draw_circle_segment(
+            Default::default(),
+            self.radius(),
+            30,
+            begin_angle,
+            end_angle,
+            Matrix4::new_translation(&position),
+            Color::GREEN,
+        );
+        for angle in [begin_angle, end_angle] {
+            ctx.add_line(Line {
+                begin: position,
+                end: position + Vector3::new(angle.cos(), angle.sin(), 0.0).scale(self.radius()),
+                color: Color::GREEN,
+            });
+        }
+    }
+}
+
+impl Default for ConeLight {
+    fn default() -> Self {
+        Self {
+            base_light: Default::default(),
+            radius: InheritableVariable::new_modified(5.0),
+            height: InheritableVariable::new_modified(1.0),
+            cone_angle: InheritableVariable::new_modified(60.0f32.to_radians()),
+            hotspot_cone_angle: InheritableVariable::new_modified(45.0f32.to_radians()),
+        }
+    }
+}
+
+/// Allows you to build 2D cone light in declarative manner.
+pub struct ConeLightBuilder {
+    base_light_builder: BaseLightBuilder,
+    radius: f32,
+    height: f32,
+    cone_angle: f32,
+    hotspot_cone_angle: f32,
+}
+
+impl ConeLightBuilder {
+    /// Creates new builder instance.
+    pub fn new(base_light_builder: BaseLightBuilder) -> Self {
+        Self {
+            base_light_builder,
+            radius: 5.0,
+            height: 1.0,
+            cone_angle: 60.0f32.to_radians(),
+            hotspot_cone_angle: 45.0f32.to_radians(),
+        }
+    }
+
+    /// Sets desired radius.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets desired height of the light above XY plane.
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets desired full angle (in radians) of the sector at which intensity of the light is zero.
+    pub fn with_cone_angle(mut self, cone_angle: f32) -> Self {
+        self.cone_angle = cone_angle;
+        self
+    }
+
+    /// Sets desired full angle (in radians) of the sector at which intensity of the light is at max.
+    pub fn with_hotspot_cone_angle(mut self, hotspot_cone_angle: f32) -> Self {
+        self.hotspot_cone_angle = hotspot_cone_angle;
+        self
+    }
+
+    /// Builds new instance of 2D cone light.
+    pub fn build_cone_light(self) -> ConeLight {
+        ConeLight {
+            base_light: self.base_light_builder.build(),
+            radius: self.radius.into(),
+            height: self.height.into(),
+            cone_angle: self.cone_angle.into(),
+            hotspot_cone_angle: self.hotspot_cone_angle.into(),
+        }
+    }
+
+    /// Builds new instance of 2D cone light node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_cone_light())
+    }
+
+    /// Builds new instance of 2D cone light and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
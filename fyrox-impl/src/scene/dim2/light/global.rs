@@ -0,0 +1,141 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! 2D global light lights up everything on the scene uniformly. See [`GlobalLight`] docs for more
+//! info.
+
+use crate::{
+    core::{
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        dim2::light::{BaseLight, BaseLightBuilder},
+        graph::Graph,
+        node::{constructor::NodeConstructor, Node, NodeTrait},
+    },
+};
+use fyrox_graph::{constructor::ConstructorProvider, BaseSceneGraph};
+use std::ops::{Deref, DerefMut};
+
+/// 2D global light lights up everything on the scene uniformly, it has no position and it does not
+/// cast shadows. It could be used as an ambient light for 2D scenes, for example to simulate time
+/// of day. Lighting from every enabled global light is added to the ambient lighting of the scene.
+#[derive(Default, Debug, Reflect, Clone, Visit, ComponentProvider)]
+pub struct GlobalLight {
+    #[component(include)]
+    base_light: BaseLight,
+}
+
+impl Deref for GlobalLight {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base_light.base
+    }
+}
+
+impl DerefMut for GlobalLight {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base_light.base
+    }
+}
+
+impl TypeUuidProvider for GlobalLight {
+    fn type_uuid() -> Uuid {
+        uuid!("8a2491a8-51e4-4ed1-911d-894270634304")
+    }
+}
+
+impl GlobalLight {
+    /// Returns a reference to base light.
+    pub fn base_light_ref(&self) -> &BaseLight {
+        &self.base_light
+    }
+
+    /// Returns a reference to base light.
+    pub fn base_light_mut(&mut self) -> &mut BaseLight {
+        &mut self.base_light
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for GlobalLight {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Global Light 2D", |_| {
+                GlobalLightBuilder::new(
+                    BaseLightBuilder::new(BaseBuilder::new().with_name("GlobalLight2D"))
+                        .with_intensity(0.25),
+                )
+                .build_node()
+                .into()
+            })
+            .with_group("Light 2D")
+    }
+}
+
+impl NodeTrait for GlobalLight {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::unit()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+/// Allows you to build 2D global light in declarative manner.
+pub struct GlobalLightBuilder {
+    base_light_builder: BaseLightBuilder,
+}
+
+impl GlobalLightBuilder {
+    /// Creates new builder instance.
+    pub fn new(base_light_builder: BaseLightBuilder) -> Self {
+        Self { base_light_builder }
+    }
+
+    /// Builds new instance of 2D global light.
+    pub fn build_global_light(self) -> GlobalLight {
+        GlobalLight {
+            base_light: self.base_light_builder.build(),
+        }
+    }
+
+    /// Builds new instance of 2D global light node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_global_light())
+    }
+
+    /// Builds new instance of 2D global light and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
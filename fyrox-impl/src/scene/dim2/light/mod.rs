@@ -0,0 +1,157 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Contains all structures and methods to create and manage 2D lights.
+//!
+//! 2D lights are used to light up 2D scenes made of [`crate::scene::dim2::rectangle::Rectangle`]
+//! and [`crate::scene::tilemap::TileMap`] nodes. Unlike 3D lights, they work on XY plane only and
+//! they do not require any geometry besides flat sprites. Fyrox engine supports three kinds of 2D
+//! light sources:
+//!
+//! 1) Point - emits light in all directions in a circle.
+//! 2) Cone - emits light in a cone (a circle sector) along its local Y axis.
+//! 3) Global - lights up everything on the scene uniformly, it could be used as an ambient light.
+//!
+//! Point and cone lights can cast shadows from [`crate::scene::dim2::occluder::Occluder`] nodes
+//! and from tile maps (see [`crate::scene::tilemap::TileMap::set_light_occluder_layer`]). Shadow
+//! casting can be disabled per light by [`Base::set_cast_shadows`] method.
+//!
+//! ## Normal maps
+//!
+//! Standard 2D materials (`standard2d.shader` and `tile.shader`) have `normalTexture` property,
+//! that could be used to add volume to flat sprites. The light height (see
+//! [`point::PointLight::set_height`]) defines how steep the light rays fall on the sprite's
+//! surface.
+
+use crate::{
+    core::{
+        color::Color,
+        reflect::prelude::*,
+        variable::InheritableVariable,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    scene::base::{Base, BaseBuilder},
+};
+use std::ops::{Deref, DerefMut};
+
+pub mod cone;
+pub mod global;
+pub mod point;
+
+/// Base 2D light scene node. It contains common properties of 2D light such as color and intensity.
+/// Exact behavior defined by specific light kind.
+#[derive(Debug, Reflect, Clone, Visit)]
+pub struct BaseLight {
+    base: Base,
+
+    #[reflect(setter = "set_color")]
+    color: InheritableVariable<Color>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_intensity")]
+    intensity: InheritableVariable<f32>,
+}
+
+impl Deref for BaseLight {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for BaseLight {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Default for BaseLight {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            color: InheritableVariable::new_modified(Color::WHITE),
+            intensity: InheritableVariable::new_modified(1.0),
+        }
+    }
+}
+
+impl BaseLight {
+    /// Sets color of light, alpha component of color is ignored.
+    #[inline]
+    pub fn set_color(&mut self, color: Color) -> Color {
+        self.color.set_value_and_mark_modified(color)
+    }
+
+    /// Returns current color of light source.
+    #[inline]
+    pub fn color(&self) -> Color {
+        *self.color
+    }
+
+    /// Sets new light intensity. Default is 1.0.
+    pub fn set_intensity(&mut self, intensity: f32) -> f32 {
+        self.intensity.set_value_and_mark_modified(intensity)
+    }
+
+    /// Returns current intensity of the light.
+    pub fn intensity(&self) -> f32 {
+        *self.intensity
+    }
+}
+
+/// 2D light scene node builder. Provides easy declarative way of creating 2D light scene nodes.
+pub struct BaseLightBuilder {
+    base_builder: BaseBuilder,
+    color: Color,
+    intensity: f32,
+}
+
+impl BaseLightBuilder {
+    /// Creates new instance of 2D light scene node builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            color: Color::WHITE,
+            intensity: 1.0,
+        }
+    }
+
+    /// Sets light color.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets desired light intensity.
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Creates new instance of base 2D light.
+    pub fn build(self) -> BaseLight {
+        BaseLight {
+            base: self.base_builder.build_base(),
+            color: self.color.into(),
+            intensity: self.intensity.into(),
+        }
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! 2D point light emits light in all directions in a circle. It has radius at which intensity of
+//! the light will be zero. See [`PointLight`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::Matrix4,
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::SceneDrawingContext,
+        dim2::light::{BaseLight, BaseLightBuilder},
+        graph::Graph,
+        node::{constructor::NodeConstructor, Node, NodeTrait},
+    },
+};
+use fyrox_graph::{constructor::ConstructorProvider, BaseSceneGraph};
+use std::ops::{Deref, DerefMut};
+
+/// 2D point light emits light in all directions in a circle. Intensity of the light fades out
+/// smoothly towards the radius of the light.
+///
+/// ## Height
+///
+/// Point light has height above XY plane, which is used to calculate lighting of normal-mapped
+/// sprites. The higher the light, the less visible surface details are. The height does not
+/// affect the distance attenuation.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{color::Color, pool::Handle},
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         dim2::light::{point::PointLightBuilder, BaseLightBuilder},
+/// #         graph::Graph,
+/// #         node::Node,
+/// #     },
+/// # };
+/// fn create_torch(graph: &mut Graph) -> Handle<Node> {
+///     PointLightBuilder::new(
+///         BaseLightBuilder::new(BaseBuilder::new().with_name("Torch"))
+///             .with_color(Color::opaque(255, 200, 120)),
+///     )
+///     .with_radius(4.0)
+///     .build(graph)
+/// }
+/// ```
+#[derive(Debug, Reflect, Clone, Visit, ComponentProvider)]
+pub struct PointLight {
+    #[component(include)]
+    base_light: BaseLight,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_radius")]
+    radius: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_height")]
+    height: InheritableVariable<f32>,
+}
+
+impl Deref for PointLight {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base_light.base
+    }
+}
+
+impl DerefMut for PointLight {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base_light.base
+    }
+}
+
+impl TypeUuidProvider for PointLight {
+    fn type_uuid() -> Uuid {
+        uuid!("b66864c4-fb3e-4547-b2ed-f716422d8b62")
+    }
+}
+
+impl PointLight {
+    /// Returns a reference to base light.
+    pub fn base_light_ref(&self) -> &BaseLight {
+        &self.base_light
+    }
+
+    /// Returns a reference to base light.
+    pub fn base_light_mut(&mut self) -> &mut BaseLight {
+        &mut self.base_light
+    }
+
+    /// Sets radius of the light.
+    #[inline]
+    pub fn set_radius(&mut self, radius: f32) -> f32 {
+        self.radius.set_value_and_mark_modified(radius.abs())
+    }
+
+    /// Returns radius of the light.
+    #[inline]
+    pub fn radius(&self) -> f32 {
+        *self.radius
+    }
+
+    /// Sets height of the light above XY plane. It is used for normal mapping only.
+    #[inline]
+    pub fn set_height(&mut self, height: f32) -> f32 {
+        self.height.set_value_and_mark_modified(height.abs())
+    }
+
+    /// Returns height of the light above XY plane.
+    #[inline]
+    pub fn height(&self) -> f32 {
+        *self.height
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for PointLight {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Point Light 2D", |_| {
+                PointLightBuilder::new(BaseLightBuilder::new(
+                    BaseBuilder::new().with_name("PointLight2D"),
+                ))
+                .build_node()
+                .into()
+            })
+            .with_group("Light 2D")
+    }
+}
+
+impl NodeTrait for PointLight {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::from_radius(*self.radius)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        // Discard scaling part, light emission distance does not affected by scaling.
+        self.local_bounding_box()
+            .transform(&self.global_transform_without_scaling())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        ctx.draw_circle(
+            Default::default(),
+            self.radius(),
+            30,
+            Matrix4::new_translation(&self.global_position()),
+            Color::GREEN,
+        );
+    }
+}
+
+impl Default for PointLight {
+    fn default() -> Self {
+        Self {
+            base_light: Default::default(),
+            radius: InheritableVariable::new_modified(5.0),
+            height: InheritableVariable::new_modified(1.0),
+        }
+    }
+}
+
+/// Allows you to build 2D point light in declarative manner.
+pub struct PointLightBuilder {
+    base_light_builder: BaseLightBuilder,
+    radius: f32,
+    height: f32,
+}
+
+impl PointLightBuilder {
+    /// Creates new builder instance.
+    pub fn new(base_light_builder: BaseLightBuilder) -> Self {
+        Self {
+            base_light_builder,
+            radius: 5.0,
+            height: 1.0,
+        }
+    }
+
+    /// Sets desired radius.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets desired height of the light above XY plane.
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Builds new instance of 2D point light.
+    pub fn build_point_light(self) -> PointLight {
+        PointLight {
+            base_light: self.base_light_builder.build(),
+            radius: self.radius.into(),
+            height: self.height.into(),
+        }
+    }
+
+    /// Builds new instance of 2D point light node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_point_light())
+    }
+
+    /// Builds new instance of 2D point light and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
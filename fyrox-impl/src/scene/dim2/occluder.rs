@@ -0,0 +1,296 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Occluder is a 2D scene node that blocks light from 2D light sources. See [`Occluder`] docs for
+//! more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector2, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, segment::LineSegment2},
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::{Line, SceneDrawingContext},
+        graph::Graph,
+        node::{constructor::NodeConstructor, Node, NodeTrait},
+    },
+};
+use fyrox_graph::{constructor::ConstructorProvider, BaseSceneGraph};
+use std::ops::{Deref, DerefMut};
+
+/// Occluder is a 2D scene node that blocks light from 2D light sources (see
+/// [`crate::scene::dim2::light`] module docs) and makes them cast shadows. The shape of an occluder
+/// is defined by a closed polygon in local coordinates of the node. Every edge of the polygon
+/// blocks the light, which means that the polygon does not need to be convex.
+///
+/// Tile maps can generate occluders automatically from tile colliders, see
+/// [`crate::scene::tilemap::TileMap::set_light_occluder_layer`] for more info.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector2, pool::Handle},
+/// #     scene::{base::BaseBuilder, dim2::occluder::OccluderBuilder, graph::Graph, node::Node},
+/// # };
+/// fn create_triangle_occluder(graph: &mut Graph) -> Handle<Node> {
+///     OccluderBuilder::new(BaseBuilder::new())
+///         .with_polygon(vec![
+///             Vector2::new(-0.5, -0.5),
+///             Vector2::new(0.0, 0.5),
+///             Vector2::new(0.5, -0.5),
+///         ])
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Reflect, Clone, Visit, ComponentProvider)]
+pub struct Occluder {
+    base: Base,
+
+    #[reflect(setter = "set_polygon")]
+    polygon: InheritableVariable<Vec<Vector2<f32>>>,
+}
+
+impl Deref for Occluder {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Occluder {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Occluder {
+    fn type_uuid() -> Uuid {
+        uuid!("fd7013f6-f8cb-445c-8474-6874dcab1299")
+    }
+}
+
+fn unit_square() -> Vec<Vector2<f32>> {
+    vec![
+        Vector2::new(-0.5, -0.5),
+        Vector2::new(-0.5, 0.5),
+        Vector2::new(0.5, 0.5),
+        Vector2::new(0.5, -0.5),
+    ]
+}
+
+impl Default for Occluder {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            polygon: InheritableVariable::new_modified(unit_square()),
+        }
+    }
+}
+
+impl Occluder {
+    /// Sets new polygon of the occluder. The polygon is defined in local coordinates of the node
+    /// and it is always closed (the last point is connected with the first one).
+    pub fn set_polygon(&mut self, polygon: Vec<Vector2<f32>>) -> Vec<Vector2<f32>> {
+        self.polygon.set_value_and_mark_modified(polygon)
+    }
+
+    /// Returns a reference to the current polygon of the occluder.
+    pub fn polygon(&self) -> &[Vector2<f32>] {
+        &self.polygon
+    }
+
+    /// Calculates world-space edges of the occluder polygon and adds them to the given list. Only
+    /// XY components of the world-space coordinates are used.
+    pub fn world_segments(&self, segments: &mut Vec<LineSegment2<f32>>) {
+        if self.polygon.len() < 2 {
+            return;
+        }
+
+        let global_transform = self.global_transform();
+        let world_points = self.polygon.iter().map(|point| {
+            global_transform
+                .transform_point(&Point3::new(point.x, point.y, 0.0))
+                .xy()
+                .coords
+        });
+
+        let mut first = None;
+        let mut previous = None;
+        for point in world_points {
+            if let Some(previous) = previous {
+                segments.push(LineSegment2::new(&previous, &point));
+            } else {
+                first = Some(point);
+            }
+            previous = Some(point);
+        }
+
+        if let (Some(first), Some(last)) = (first, previous) {
+            if self.polygon.len() > 2 {
+                segments.push(LineSegment2::new(&last, &first));
+            }
+        }
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for Occluder {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Occluder 2D", |_| {
+                OccluderBuilder::new(BaseBuilder::new().with_name("Occluder2D"))
+                    .build_node()
+                    .into()
+            })
+            .with_group("Light 2D")
+    }
+}
+
+impl NodeTrait for Occluder {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        if self.polygon.is_empty() {
+            return AxisAlignedBoundingBox::collapsed();
+        }
+
+        let mut aabb = AxisAlignedBoundingBox::default();
+        for point in self.polygon.iter() {
+            aabb.add_point(Vector3::new(point.x, point.y, 0.0));
+        }
+        aabb
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let mut segments = Vec::new();
+        self.world_segments(&mut segments);
+        let z = self.global_position().z;
+        for segment in segments {
+            ctx.add_line(Line {
+                begin: Vector3::new(segment.start.x, segment.start.y, z),
+                end: Vector3::new(segment.end.x, segment.end.y, z),
+                color: Color::DARK_ORANGE,
+            });
+        }
+    }
+}
+
+/// Allows you to create 2D light occluders in declarative manner.
+pub struct OccluderBuilder {
+    base_builder: BaseBuilder,
+    polygon: Vec<Vector2<f32>>,
+}
+
+impl OccluderBuilder {
+    /// Creates new occluder builder. By default, the polygon of the occluder is a unit square.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            polygon: unit_square(),
+        }
+    }
+
+    /// Sets the desired polygon of the occluder. See [`Occluder::set_polygon`] for more info.
+    pub fn with_polygon(mut self, polygon: Vec<Vector2<f32>>) -> Self {
+        self.polygon = polygon;
+        self
+    }
+
+    /// Creates new [`Occluder`] instance.
+    pub fn build_occluder(self) -> Occluder {
+        Occluder {
+            base: self.base_builder.build_base(),
+            polygon: self.polygon.into(),
+        }
+    }
+
+    /// Creates new [`Occluder`] node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_occluder())
+    }
+
+    /// Creates new [`Occluder`] node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            dim2::occluder::{Occluder, OccluderBuilder},
+            graph::Graph,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_occluder_world_segments() {
+        let mut graph = Graph::new();
+
+        let occluder = OccluderBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(2.0, 1.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_polygon(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+        ])
+        .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        let mut segments = Vec::new();
+        graph[occluder]
+            .cast::<Occluder>()
+            .unwrap()
+            .world_segments(&mut segments);
+
+        // The polygon must be closed.
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].start, Vector2::new(2.0, 1.0));
+        assert_eq!(segments[0].end, Vector2::new(3.0, 1.0));
+        assert_eq!(segments[2].start, Vector2::new(2.0, 2.0));
+        assert_eq!(segments[2].end, Vector2::new(2.0, 1.0));
+    }
+}
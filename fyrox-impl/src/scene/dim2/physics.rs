@@ -343,6 +343,7 @@ fn tile_map_to_collider_shape(
     let mut triangles = Vec::new();
 
     let collider_uuid = tile_set.collider_name_to_uuid(collider_name)?;
+    let layout = tile_map.grid_layout();
     let tile_data = tile_map.tiles()?.data_ref();
     let tile_data = tile_data.as_loaded_ref()?;
     for (position, handle) in tile_data.iter() {
@@ -351,8 +352,8 @@ fn tile_map_to_collider_shape(
         };
 
         if let Some(collider) = tile_definition.colliders.get(&collider_uuid) {
-            let position = position.cast::<f32>().to_homogeneous();
-            collider.build_collider_shape(
+            layout.build_collider_shape(
+                collider,
                 &global_transform,
                 position,
                 &mut vertices,
@@ -1067,6 +1068,126 @@ impl PhysicsWorld {
         }
     }
 
+    pub(crate) fn set_kinematic_body_position(
+        &mut self,
+        body: RigidBodyHandle,
+        new_global_transform: &Matrix4<f32>,
+    ) {
+        if let Some(native) = self.bodies.get_mut(body) {
+            native
+                .set_next_kinematic_position(isometry_from_global_transform(new_global_transform));
+        }
+    }
+
+    pub(crate) fn sync_to_character_controller_node(
+        &mut self,
+        handle: Handle<Node>,
+        controller_node: &dim2::character_controller::CharacterController,
+    ) {
+        if !controller_node.is_globally_enabled() {
+            self.remove_body(controller_node.native.get());
+            controller_node.native.set(Default::default());
+            return;
+        }
+
+        // Character controller is backed by a kinematic rigid body, that is used to attach
+        // colliders to it and to push other rigid bodies. All the other properties are used
+        // directly when the controller moves.
+        if controller_node.native.get() == RigidBodyHandle::invalid() {
+            let body = RigidBodyBuilder::kinematic_position_based()
+                .position(isometry_from_global_transform(
+                    &controller_node.global_transform(),
+                ))
+                .build();
+
+            controller_node.native.set(self.add_body(handle, body));
+
+            Log::writeln(
+                MessageKind::Information,
+                format!(
+                    "Native character controller body was created for node {}",
+                    controller_node.name()
+                ),
+            );
+        }
+    }
+
+    pub(crate) fn move_character(
+        &mut self,
+        controller_node: &dim2::character_controller::CharacterController,
+        desired_translation: Vector2<f32>,
+        dt: f32,
+    ) -> Option<dim2::character_controller::CharacterMovement> {
+        if !*self.enabled {
+            return None;
+        }
+
+        let body_handle = controller_node.native.get();
+        let body = self.bodies.get(body_handle)?;
+        // The first collider defines the shape of the character.
+        let collider = self.colliders.get(*body.colliders().first()?)?;
+        let character_shape = collider.shared_shape().clone();
+        let character_position =
+            isometry_from_global_transform(&controller_node.global_transform())
+                * collider.position_wrt_parent().cloned().unwrap_or_default();
+
+        let controller = controller_node.native_controller();
+        let filter = rapier2d::pipeline::QueryFilter::new()
+            .exclude_rigid_body(body_handle)
+            .exclude_sensors();
+
+        let mut query = self.query.borrow_mut();
+        query.update(&self.colliders);
+
+        let mut native_collisions = Vec::new();
+        let movement = controller.move_shape(
+            dt,
+            &self.bodies,
+            &self.colliders,
+            &query,
+            character_shape.as_ref(),
+            &character_position,
+            desired_translation,
+            filter,
+            |collision| native_collisions.push(collision),
+        );
+
+        if controller_node.is_push_rigid_bodies() {
+            controller.solve_character_collision_impulses(
+                dt,
+                &mut self.bodies,
+                &self.colliders,
+                &query,
+                character_shape.as_ref(),
+                controller_node.mass(),
+                &native_collisions,
+                filter,
+            );
+        }
+
+        let collisions = native_collisions
+            .iter()
+            .filter_map(|collision| {
+                Some(dim2::character_controller::CharacterCollision {
+                    collider: Handle::decode_from_u128(
+                        self.colliders.get(collision.handle)?.user_data,
+                    ),
+                    position: collision.hit.witness1.coords,
+                    normal: *collision.hit.normal1,
+                    translation_applied: collision.translation_applied,
+                    translation_remaining: collision.translation_remaining,
+                })
+            })
+            .collect();
+
+        Some(dim2::character_controller::CharacterMovement {
+            translation: movement.translation,
+            grounded: movement.grounded,
+            is_sliding_down_slope: movement.is_sliding_down_slope,
+            collisions,
+        })
+    }
+
     pub(crate) fn sync_to_collider_node(
         &mut self,
         nodes: &NodePool,
@@ -1136,12 +1257,17 @@ impl PhysicsWorld {
                     }
                 }
             }
-        } else if let Some(parent_body) = nodes
-            .try_borrow(collider_node.parent())
-            .and_then(|n| n.cast::<dim2::rigidbody::RigidBody>())
+        } else if let Some(rigid_body_native) =
+            nodes.try_borrow(collider_node.parent()).and_then(|n| {
+                n.cast::<dim2::rigidbody::RigidBody>()
+                    .map(|b| b.native.get())
+                    .or_else(|| {
+                        n.cast::<dim2::character_controller::CharacterController>()
+                            .map(|c| c.native.get())
+                    })
+            })
         {
-            if parent_body.native.get() != RigidBodyHandle::invalid() {
-                let rigid_body_native = parent_body.native.get();
+            if rigid_body_native != RigidBodyHandle::invalid() {
                 let inv_global_transform = isometric_global_transform(nodes, handle)
                     .try_inverse()
                     .unwrap();
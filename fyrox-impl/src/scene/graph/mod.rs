@@ -80,6 +80,7 @@ use fxhash::{FxHashMap, FxHashSet};
 use fyrox_graph::SceneGraphNode;
 use std::{
     any::{Any, TypeId},
+    collections::VecDeque,
     fmt::Debug,
     ops::{Index, IndexMut},
     sync::mpsc::{channel, Receiver, Sender},
@@ -1317,6 +1318,116 @@ impl Graph {
         self.pool.forget_ticket(ticket);
     }
 
+    /// Puts a node at a previously reserved handle (see [`Self::take_reserve`] and
+    /// [`Self::take_reserve_sub_graph`]). Unlike [`Self::put_back`], the node is treated as a new
+    /// one - its scripts will be initialized and it will be attached to the root of the graph.
+    /// Every handle to the node that was taken from the reserved slot becomes valid again.
+    #[inline]
+    pub fn put_at_reserved(&mut self, ticket: Ticket<Node>, mut node: Node) -> Handle<Node> {
+        let children = std::mem::take(&mut node.children);
+        let handle = self.pool.put_back(ticket, node);
+        self.connect_node(handle, children);
+        handle
+    }
+
+    /// Makes a reserved handle vacant again, without the need to provide a node.
+    #[inline]
+    pub fn forget_reserved(&mut self, ticket: Ticket<Node>) {
+        self.pool.forget_ticket(ticket);
+    }
+
+    /// Moves a sub-graph starting from the given node of the `other` graph into this graph and
+    /// attaches it to the given parent (or to the root of the graph if the parent is
+    /// [`Handle::NONE`]). The `reserved` callback is called for every moved node and it may return
+    /// a ticket of a reserved handle in this graph, at which the node will be put. This allows
+    /// you to restore a previously extracted sub-graph with the same handles. Handles inside the
+    /// moved nodes are remapped to the new ones. Returns a handle of the new root of the sub-graph
+    /// and old-to-new handle mapping.
+    pub fn merge_sub_graph<F>(
+        &mut self,
+        other: &mut Graph,
+        root: Handle<Node>,
+        parent: Handle<Node>,
+        mut reserved: F,
+    ) -> (Handle<Node>, NodeHandleMap<Node>)
+    where
+        F: FnMut(&Node) -> Option<Ticket<Node>>,
+    {
+        other.isolate_node(root);
+
+        let mut old_new_mapping = NodeHandleMap::default();
+        let mut links = Vec::new();
+        let mut queue = VecDeque::from([root]);
+        while let Some(old_handle) = queue.pop_front() {
+            let (ticket, node) = other.take_reserve_internal(old_handle);
+            let node = other.forget_ticket(ticket, node);
+            queue.extend(node.children.iter().cloned());
+
+            let old_parent = node.parent;
+            let node = clear_links(node);
+            let new_handle = match reserved(&node) {
+                Some(ticket) => self.put_at_reserved(ticket, node),
+                None => self.add_node(node),
+            };
+
+            old_new_mapping.insert(old_handle, new_handle);
+            if old_handle != root {
+                links.push((new_handle, old_parent));
+            }
+        }
+
+        // Remap handles before restoring the links, because old and new handles may collide.
+        for &new_handle in old_new_mapping.inner().values() {
+            self.isolate_node(new_handle);
+        }
+        remap_handles(&old_new_mapping, self);
+
+        // Parents are always moved before their children, so the order of children is preserved.
+        for (new_handle, mut parent) in links {
+            old_new_mapping.map(&mut parent);
+            self.link_nodes(new_handle, parent);
+        }
+
+        let new_root = old_new_mapping.inner()[&root];
+        if parent.is_some() {
+            self.link_nodes(new_root, parent);
+        }
+
+        (new_root, old_new_mapping)
+    }
+
+    // Finishes insertion of a node that was just put in the pool.
+    fn connect_node(&mut self, handle: Handle<Node>, children: Vec<Handle<Node>>) {
+        let script_count = self.pool[handle].scripts.len();
+
+        if self.root.is_none() {
+            self.root = handle;
+        } else {
+            self.link_nodes(handle, self.root);
+        }
+
+        for child in children {
+            self.link_nodes(child, handle);
+        }
+
+        self.event_broadcaster.broadcast(GraphEvent::Added(handle));
+        for i in 0..script_count {
+            self.script_message_sender
+                .send(NodeScriptMessage::InitializeScript {
+                    handle,
+                    script_index: i,
+                })
+                .unwrap();
+        }
+
+        let script_message_sender = self.script_message_sender.clone();
+        let message_sender = self.message_sender.clone();
+        let node = &mut self.pool[handle];
+        node.on_connected_to_graph(handle, message_sender, script_message_sender);
+
+        self.instance_id_map.insert(node.instance_id, handle);
+    }
+
     /// Returns the number of nodes in the graph.
     #[inline]
     pub fn node_count(&self) -> u32 {
@@ -1678,38 +1789,9 @@ impl BaseSceneGraph for Graph {
 
     #[inline]
     fn add_node(&mut self, mut node: Self::Node) -> Handle<Self::Node> {
-        let children = node.children.clone();
-        node.children.clear();
-        let script_count = node.scripts.len();
+        let children = std::mem::take(&mut node.children);
         let handle = self.pool.spawn(node);
-
-        if self.root.is_none() {
-            self.root = handle;
-        } else {
-            self.link_nodes(handle, self.root);
-        }
-
-        for child in children {
-            self.link_nodes(child, handle);
-        }
-
-        self.event_broadcaster.broadcast(GraphEvent::Added(handle));
-        for i in 0..script_count {
-            self.script_message_sender
-                .send(NodeScriptMessage::InitializeScript {
-                    handle,
-                    script_index: i,
-                })
-                .unwrap();
-        }
-
-        let script_message_sender = self.script_message_sender.clone();
-        let message_sender = self.message_sender.clone();
-        let node = &mut self.pool[handle];
-        node.on_connected_to_graph(handle, message_sender, script_message_sender);
-
-        self.instance_id_map.insert(node.instance_id, handle);
-
+        self.connect_node(handle, children);
         handle
     }
 
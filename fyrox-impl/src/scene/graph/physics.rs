@@ -50,11 +50,13 @@ use crate::{
         },
         node::{Node, NodeTrait},
         rigidbody::{self, ApplyAction, RigidBodyMassPropertiesType},
+        spline::Spline,
         terrain::{Chunk, Terrain},
     },
     utils::raw_mesh::{RawMeshBuilder, RawVertex},
 };
 use rapier3d::{
+    control::DynamicRayCastVehicleController,
     dynamics::{
         CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJointHandle, ImpulseJointSet,
         IslandManager, JointAxesMask, MultibodyJointHandle, MultibodyJointSet, RigidBody,
@@ -480,53 +482,63 @@ fn make_trimesh(
     let root_inv_transform = owner_inv_transform;
 
     for &source in sources {
-        if let Some(mesh) = nodes.try_borrow(source.0).and_then(|n| n.cast::<Mesh>()) {
-            let global_transform = root_inv_transform * mesh.global_transform();
-
-            for surface in mesh.surfaces() {
-                let shared_data = surface.data();
-                let shared_data = shared_data.data_ref();
-
-                let vertices = &shared_data.vertex_buffer;
-                for triangle in shared_data.geometry_buffer.iter() {
-                    let a = RawVertex::from(
-                        global_transform
-                            .transform_point(&Point3::from(
-                                vertices
-                                    .get(triangle[0] as usize)
-                                    .unwrap()
-                                    .read_3_f32(VertexAttributeUsage::Position)
-                                    .unwrap(),
-                            ))
-                            .coords,
-                    );
-                    let b = RawVertex::from(
-                        global_transform
-                            .transform_point(&Point3::from(
-                                vertices
-                                    .get(triangle[1] as usize)
-                                    .unwrap()
-                                    .read_3_f32(VertexAttributeUsage::Position)
-                                    .unwrap(),
-                            ))
-                            .coords,
-                    );
-                    let c = RawVertex::from(
-                        global_transform
-                            .transform_point(&Point3::from(
-                                vertices
-                                    .get(triangle[2] as usize)
-                                    .unwrap()
-                                    .read_3_f32(VertexAttributeUsage::Position)
-                                    .unwrap(),
-                            ))
-                            .coords,
-                    );
+        let Some(node) = nodes.try_borrow(source.0) else {
+            continue;
+        };
 
-                    mesh_builder.insert(a);
-                    mesh_builder.insert(b);
-                    mesh_builder.insert(c);
-                }
+        let global_transform = root_inv_transform * node.global_transform();
+
+        // Splines provide their extruded geometry, which is useful for roads, pipes, etc.
+        let surfaces = if let Some(mesh) = node.cast::<Mesh>() {
+            mesh.surfaces().iter().map(|s| s.data()).collect::<Vec<_>>()
+        } else if let Some(spline) = node.cast::<Spline>() {
+            spline.extrusion_surface().into_iter().collect()
+        } else {
+            continue;
+        };
+
+        for shared_data in surfaces {
+            let shared_data = shared_data.data_ref();
+
+            let vertices = &shared_data.vertex_buffer;
+            for triangle in shared_data.geometry_buffer.iter() {
+                let a = RawVertex::from(
+                    global_transform
+                        .transform_point(&Point3::from(
+                            vertices
+                                .get(triangle[0] as usize)
+                                .unwrap()
+                                .read_3_f32(VertexAttributeUsage::Position)
+                                .unwrap(),
+                        ))
+                        .coords,
+                );
+                let b = RawVertex::from(
+                    global_transform
+                        .transform_point(&Point3::from(
+                            vertices
+                                .get(triangle[1] as usize)
+                                .unwrap()
+                                .read_3_f32(VertexAttributeUsage::Position)
+                                .unwrap(),
+                        ))
+                        .coords,
+                );
+                let c = RawVertex::from(
+                    global_transform
+                        .transform_point(&Point3::from(
+                            vertices
+                                .get(triangle[2] as usize)
+                                .unwrap()
+                                .read_3_f32(VertexAttributeUsage::Position)
+                                .unwrap(),
+                        ))
+                        .coords,
+                );
+
+                mesh_builder.insert(a);
+                mesh_builder.insert(b);
+                mesh_builder.insert(c);
             }
         }
     }
@@ -1308,6 +1320,57 @@ impl PhysicsWorld {
         );
     }
 
+    /// Casts multiple rays at once and returns the closest intersection (if any) for each ray in
+    /// the `results` array. Unlike [`Self::cast_ray`], this method updates internal acceleration
+    /// structure only once for all the rays, which makes it much faster when a lot of rays must be
+    /// cast (for example, for particle collisions). `sort_results` flag of the options is ignored.
+    pub fn cast_rays_closest(
+        &self,
+        rays: &[RayCastOptions],
+        results: &mut Vec<Option<Intersection>>,
+    ) {
+        let time = instant::Instant::now();
+
+        let mut query = self.query.borrow_mut();
+        query.update(&self.colliders);
+
+        results.clear();
+        results.extend(rays.iter().map(|opts| {
+            let ray = Ray::new(
+                opts.ray_origin,
+                opts.ray_direction
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default(),
+            );
+            query
+                .cast_ray_and_get_normal(
+                    &self.bodies,
+                    &self.colliders,
+                    &ray,
+                    opts.max_len,
+                    true,
+                    rapier3d::pipeline::QueryFilter::new().groups(InteractionGroups::new(
+                        u32_to_group(opts.groups.memberships.0),
+                        u32_to_group(opts.groups.filter.0),
+                    )),
+                )
+                .map(|(handle, intersection)| Intersection {
+                    collider: Handle::decode_from_u128(
+                        self.colliders.get(handle).unwrap().user_data,
+                    ),
+                    normal: intersection.normal,
+                    position: ray.point_at(intersection.time_of_impact),
+                    feature: intersection.feature.into(),
+                    toi: intersection.time_of_impact,
+                })
+        }));
+
+        self.performance_statistics.total_ray_cast_time.set(
+            self.performance_statistics.total_ray_cast_time.get()
+                + (instant::Instant::now() - time),
+        );
+    }
+
     /// Casts a shape at a constant linear velocity and retrieve the first collider it hits.
     ///
     /// This is similar to ray-casting except that we are casting a whole shape instead of just a
@@ -1678,6 +1741,212 @@ impl PhysicsWorld {
         }
     }
 
+    pub(crate) fn set_kinematic_body_position(
+        &mut self,
+        body: RigidBodyHandle,
+        new_global_transform: &Matrix4<f32>,
+    ) {
+        if let Some(native) = self.bodies.get_mut(body) {
+            native
+                .set_next_kinematic_position(isometry_from_global_transform(new_global_transform));
+        }
+    }
+
+    pub(crate) fn sync_to_character_controller_node(
+        &mut self,
+        handle: Handle<Node>,
+        controller_node: &scene::character_controller::CharacterController,
+    ) {
+        if !controller_node.is_globally_enabled() {
+            self.remove_body(controller_node.native.get());
+            controller_node.native.set(Default::default());
+            return;
+        }
+
+        // Character controller is backed by a kinematic rigid body, that is used to attach
+        // colliders to it and to push other rigid bodies. All the other properties are used
+        // directly when the controller moves.
+        if controller_node.native.get() == RigidBodyHandle::invalid() {
+            let body = RigidBodyBuilder::kinematic_position_based()
+                .position(isometry_from_global_transform(
+                    &controller_node.global_transform(),
+                ))
+                .build();
+
+            controller_node.native.set(self.add_body(handle, body));
+
+            Log::writeln(
+                MessageKind::Information,
+                format!(
+                    "Native character controller body was created for node {}",
+                    controller_node.name()
+                ),
+            );
+        }
+    }
+
+    pub(crate) fn move_character(
+        &mut self,
+        controller_node: &scene::character_controller::CharacterController,
+        desired_translation: Vector3<f32>,
+        dt: f32,
+    ) -> Option<scene::character_controller::CharacterMovement> {
+        if !*self.enabled {
+            return None;
+        }
+
+        let body_handle = controller_node.native.get();
+        let body = self.bodies.get(body_handle)?;
+        // The first collider defines the shape of the character.
+        let collider = self.colliders.get(*body.colliders().first()?)?;
+        let character_shape = collider.shared_shape().clone();
+        let character_position =
+            isometry_from_global_transform(&controller_node.global_transform())
+                * collider.position_wrt_parent().cloned().unwrap_or_default();
+
+        let controller = controller_node.native_controller();
+        let filter = rapier3d::pipeline::QueryFilter::new()
+            .exclude_rigid_body(body_handle)
+            .exclude_sensors();
+
+        let mut query = self.query.borrow_mut();
+        query.update(&self.colliders);
+
+        let mut native_collisions = Vec::new();
+        let movement = controller.move_shape(
+            dt,
+            &self.bodies,
+            &self.colliders,
+            &query,
+            character_shape.as_ref(),
+            &character_position,
+            desired_translation,
+            filter,
+            |collision| native_collisions.push(collision),
+        );
+
+        if controller_node.is_push_rigid_bodies() {
+            controller.solve_character_collision_impulses(
+                dt,
+                &mut self.bodies,
+                &self.colliders,
+                &query,
+                character_shape.as_ref(),
+                controller_node.mass(),
+                &native_collisions,
+                filter,
+            );
+        }
+
+        let collisions = native_collisions
+            .iter()
+            .filter_map(|collision| {
+                Some(scene::character_controller::CharacterCollision {
+                    collider: Handle::decode_from_u128(
+                        self.colliders.get(collision.handle)?.user_data,
+                    ),
+                    position: collision.hit.witness1.coords,
+                    normal: *collision.hit.normal1,
+                    translation_applied: collision.translation_applied,
+                    translation_remaining: collision.translation_remaining,
+                })
+            })
+            .collect();
+
+        Some(scene::character_controller::CharacterMovement {
+            translation: movement.translation,
+            grounded: movement.grounded,
+            is_sliding_down_slope: movement.is_sliding_down_slope,
+            collisions,
+        })
+    }
+
+    pub(crate) fn update_vehicle(
+        &mut self,
+        controller: &mut DynamicRayCastVehicleController,
+        dt: f32,
+    ) -> Option<Vec<scene::vehicle::VehicleWheelState>> {
+        if !*self.enabled || self.bodies.get(controller.chassis).is_none() {
+            return None;
+        }
+
+        let filter = rapier3d::pipeline::QueryFilter::new()
+            .exclude_rigid_body(controller.chassis)
+            .exclude_sensors();
+
+        let mut query = self.query.borrow_mut();
+        query.update(&self.colliders);
+
+        controller.update_vehicle(dt, &mut self.bodies, &self.colliders, &query, filter);
+
+        Some(
+            controller
+                .wheels()
+                .iter()
+                .map(|wheel| {
+                    let info = wheel.raycast_info();
+                    scene::vehicle::VehicleWheelState {
+                        suspension_length: info.suspension_length,
+                        steering: wheel.steering,
+                        rotation: wheel.rotation,
+                        is_in_contact: info.is_in_contact,
+                        contact_point: info.contact_point_ws.coords,
+                        contact_normal: info.contact_normal_ws,
+                        ground: info
+                            .ground_object
+                            .and_then(|collider| self.colliders.get(collider))
+                            .map(|collider| Handle::decode_from_u128(collider.user_data))
+                            .unwrap_or_default(),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Pushes the given points out of the colliders, so every point is at least `margin` units
+    /// away from the surface of the closest collider. Sensors are ignored.
+    pub(crate) fn push_points_out_of_colliders<'a>(
+        &self,
+        points: impl Iterator<Item = &'a mut Vector3<f32>>,
+        margin: f32,
+    ) {
+        if !*self.enabled {
+            return;
+        }
+
+        let filter = rapier3d::pipeline::QueryFilter::new().exclude_sensors();
+
+        let mut query = self.query.borrow_mut();
+        query.update(&self.colliders);
+
+        for point in points {
+            let Some((_, projection)) = query.project_point(
+                &self.bodies,
+                &self.colliders,
+                &Point3::from(*point),
+                false,
+                filter,
+            ) else {
+                continue;
+            };
+
+            let offset = projection.point.coords - *point;
+            let distance = offset.norm();
+            if distance <= f32::EPSILON {
+                continue;
+            }
+            // The direction from the surface towards the outside of the collider.
+            let normal = if projection.is_inside {
+                offset / distance
+            } else {
+                -offset / distance
+            };
+            if projection.is_inside || distance < margin {
+                *point = projection.point.coords + normal * margin;
+            }
+        }
+    }
+
     pub(crate) fn sync_to_collider_node(
         &mut self,
         nodes: &NodePool,
@@ -1750,15 +2019,20 @@ impl PhysicsWorld {
                     }
                 }
             }
-        } else if let Some(parent_body) = nodes
-            .try_borrow(collider_node.parent())
-            .and_then(|n| n.cast::<scene::rigidbody::RigidBody>())
+        } else if let Some(rigid_body_native) =
+            nodes.try_borrow(collider_node.parent()).and_then(|n| {
+                n.cast::<scene::rigidbody::RigidBody>()
+                    .map(|b| b.native.get())
+                    .or_else(|| {
+                        n.cast::<scene::character_controller::CharacterController>()
+                            .map(|c| c.native.get())
+                    })
+            })
         {
-            if parent_body.native.get() != RigidBodyHandle::invalid() {
+            if rigid_body_native != RigidBodyHandle::invalid() {
                 let inv_global_transform = isometric_global_transform(nodes, handle)
                     .try_inverse()
                     .unwrap();
-                let rigid_body_native = parent_body.native.get();
                 if let Some(shape) = collider_shape_into_native_shape(
                     collider_node.shape(),
                     inv_global_transform,
@@ -419,6 +419,16 @@ impl Mesh {
         self.surfaces.get_value_mut_silent()
     }
 
+    /// Marks the bounding boxes of the mesh as outdated and recalculates the world-space one. It
+    /// must be called when vertices of the surfaces were modified in place.
+    pub(crate) fn invalidate_bounding_box(&self) {
+        self.local_bounding_box_dirty.set(true);
+        self.world_bounding_box.set(
+            self.local_bounding_box()
+                .transform(&self.global_transform()),
+        );
+    }
+
     /// Removes all surfaces from mesh.
     #[inline]
     pub fn clear_surfaces(&mut self) {
@@ -460,9 +470,8 @@ impl Mesh {
     pub fn accurate_world_bounding_box(&self, graph: &Graph) -> AxisAlignedBoundingBox {
         let mut bounding_box = AxisAlignedBoundingBox::default();
         for surface in self.surfaces.iter() {
-            let data = surface.data();
-            let data = data.data_ref();
-            if surface.bones().is_empty() {
+            let data = surface.render_data().data_ref();
+            if surface.render_bones().is_empty() {
                 for view in data.vertex_buffer.iter() {
                     let Ok(vertex_pos) = view.read_3_f32(VertexAttributeUsage::Position) else {
                         break;
@@ -480,7 +489,7 @@ impl Mesh {
 
                 // Precalculate bone matrices first to speed up calculations.
                 let bone_matrices = surface
-                    .bones()
+                    .render_bones()
                     .iter()
                     .map(|&b| {
                         let bone_node = &graph[b];
@@ -619,8 +628,7 @@ impl NodeTrait for Mesh {
                 }
             } else {
                 for surface in self.surfaces.iter() {
-                    let data = surface.data();
-                    let data = data.data_ref();
+                    let data = surface.render_data().data_ref();
                     extend_aabb_from_vertex_buffer(&data.vertex_buffer, &mut bounding_box);
                 }
             }
@@ -646,14 +654,14 @@ impl NodeTrait for Mesh {
         _new_global_transform: &Matrix4<f32>,
         context: &mut SyncContext,
     ) {
-        if self.surfaces.iter().any(|s| !s.bones.is_empty()) {
+        if self.surfaces.iter().any(|s| !s.render_bones().is_empty()) {
             let mut world_aabb = self
                 .local_bounding_box()
                 .transform(&self.global_transform());
 
             // Special case for skinned meshes.
             for surface in self.surfaces.iter() {
-                for &bone in surface.bones() {
+                for &bone in surface.render_bones() {
                     if let Some(node) = context.nodes.try_borrow(bone) {
                         world_aabb.add_point(node.global_position())
                     }
@@ -704,7 +712,7 @@ impl NodeTrait for Mesh {
             RdcControlFlow::Break
         } else {
             for surface in self.surfaces().iter() {
-                let is_skinned = !surface.bones.is_empty();
+                let is_skinned = !surface.render_bones().is_empty();
 
                 let world = if is_skinned {
                     Matrix4::identity()
@@ -716,9 +724,9 @@ impl NodeTrait for Mesh {
                     BatchingMode::None => BatchingMode::None,
                     BatchingMode::Static => BatchingMode::Static,
                     BatchingMode::Dynamic => {
-                        let surface_data_guard = surface.data_ref().data_ref();
+                        let surface_data_guard = surface.render_data().data_ref();
                         if self.blend_shapes().is_empty()
-                            && surface.bones().is_empty()
+                            && surface.render_bones().is_empty()
                             && surface_data_guard.vertex_buffer.vertex_count() < 256
                         {
                             BatchingMode::Dynamic
@@ -730,7 +738,7 @@ impl NodeTrait for Mesh {
 
                 match batching_mode {
                     BatchingMode::None => {
-                        let surface_data = surface.data_ref();
+                        let surface_data = surface.render_data();
                         let substitute_material = surface_data
                             .data_ref()
                             .blend_shapes_container
@@ -755,7 +763,7 @@ impl NodeTrait for Mesh {
                             SurfaceInstanceData {
                                 world_transform: world,
                                 bone_matrices: surface
-                                    .bones
+                                    .render_bones()
                                     .iter()
                                     .map(|bone_handle| {
                                         if let Some(bone_node) = ctx.graph.try_get(*bone_handle) {
@@ -777,7 +785,7 @@ impl NodeTrait for Mesh {
                         );
                     }
                     BatchingMode::Dynamic => {
-                        let surface_data_guard = surface.data_ref().data_ref();
+                        let surface_data_guard = surface.render_data().data_ref();
 
                         ctx.storage.push_triangles(
                             &surface_data_guard
@@ -25,6 +25,7 @@ use crate::{
     asset::{
         io::ResourceIo,
         loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+        memory::ResourceMemoryUsage,
         state::LoadError,
         untyped::ResourceKind,
         Resource, ResourceData,
@@ -249,6 +250,16 @@ impl ResourceData for SurfaceData {
     fn can_be_saved(&self) -> bool {
         true
     }
+
+    fn memory_usage(&self) -> ResourceMemoryUsage {
+        // The data is uploaded to the GPU as is.
+        let size = self.vertex_buffer.raw_data().len()
+            + std::mem::size_of_val(self.geometry_buffer.triangles_ref());
+        ResourceMemoryUsage {
+            cpu: size,
+            gpu: size,
+        }
+    }
 }
 
 impl SurfaceData {
@@ -1265,6 +1276,12 @@ pub struct Surface {
     // associated with vertex in `bones` array and store it as bone index in vertex.
     #[reflect(hidden)]
     pub(crate) vertex_weights: Vec<VertexWeightSet>,
+
+    // Runtime-only data that is rendered instead of the actual data of the surface. It is used by
+    // the cloth simulation, which writes the vertices in the local coordinates of the mesh, so the
+    // surface is rendered without skinning. It is neither saved nor cloned.
+    #[reflect(hidden)]
+    pub(crate) simulated_data: Option<SurfaceResource>,
 }
 
 uuid_provider!(Surface = "485caf12-4e7d-4b1a-b6bd-0681fd92f789");
@@ -1283,6 +1300,7 @@ impl Clone for Surface {
             bones: self.bones.clone(),
             unique_material: self.unique_material.clone(),
             vertex_weights: self.vertex_weights.clone(),
+            simulated_data: None,
         }
     }
 }
@@ -1322,6 +1340,7 @@ impl Default for Surface {
             vertex_weights: Default::default(),
             bones: Default::default(),
             unique_material: Default::default(),
+            simulated_data: None,
         }
     }
 }
@@ -1361,6 +1380,23 @@ impl Surface {
         &self.data
     }
 
+    /// Returns the data that is used to render the surface. It is the simulated data, if the
+    /// surface is simulated by a cloth, or the current data otherwise.
+    #[inline]
+    pub(crate) fn render_data(&self) -> &SurfaceResource {
+        self.simulated_data.as_ref().unwrap_or(&self.data)
+    }
+
+    /// Returns the bones that are used to render the surface. Simulated surfaces are not skinned.
+    #[inline]
+    pub(crate) fn render_bones(&self) -> &[Handle<Node>] {
+        if self.simulated_data.is_some() {
+            &[]
+        } else {
+            &self.bones
+        }
+    }
+
     /// Returns current material of the surface.
     pub fn material(&self) -> &MaterialResource {
         &self.material
@@ -1436,6 +1472,7 @@ impl SurfaceBuilder {
                 })
                 .into(),
             vertex_weights: Default::default(),
+            simulated_data: None,
             bones: self.bones.into(),
             unique_material: self.unique_material.into(),
         }
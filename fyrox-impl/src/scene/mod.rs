@@ -28,6 +28,8 @@ pub mod accel;
 pub mod animation;
 pub mod base;
 pub mod camera;
+pub mod character_controller;
+pub mod cloth;
 pub mod collider;
 pub mod debug;
 pub mod decal;
@@ -41,12 +43,16 @@ pub mod node;
 pub mod particle_system;
 pub mod pivot;
 pub mod ragdoll;
+pub mod reflection_probe;
 pub mod rigidbody;
 pub mod sound;
+pub mod spline;
 pub mod sprite;
+pub mod streaming;
 pub mod terrain;
 pub mod tilemap;
 pub mod transform;
+pub mod vehicle;
 
 use crate::renderer::framework::PolygonFillMode;
 use crate::{
@@ -58,6 +64,7 @@ use crate::{
         log::{Log, MessageKind},
         pool::{Handle, Pool, Ticket},
         reflect::prelude::*,
+        uuid_provider,
         visitor::{Visit, VisitError, VisitResult, Visitor},
     },
     engine::SerializationContext,
@@ -84,6 +91,7 @@ use std::{
     path::PathBuf,
     sync::Arc,
 };
+use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 /// A container for navigational meshes.
 #[derive(Default, Clone, Debug, Visit)]
@@ -182,6 +190,11 @@ pub struct SceneRenderingOptions {
 
     /// Color of the ambient lighting.
     pub ambient_lighting_color: Color,
+
+    /// Global fog settings of the scene. Fog is disabled by default, see [`FogSettings`] docs for
+    /// more info.
+    #[visit(optional)]
+    pub fog: FogSettings,
 }
 
 impl Default for SceneRenderingOptions {
@@ -191,6 +204,7 @@ impl Default for SceneRenderingOptions {
             clear_color: None,
             polygon_rasterization_mode: Default::default(),
             ambient_lighting_color: Color::opaque(100, 100, 100),
+            fog: Default::default(),
         }
     }
 }
@@ -202,6 +216,99 @@ impl Clone for SceneRenderingOptions {
             clear_color: self.clear_color,
             polygon_rasterization_mode: self.polygon_rasterization_mode,
             ambient_lighting_color: self.ambient_lighting_color,
+            fog: self.fog,
+        }
+    }
+}
+
+/// Defines how the fog density changes with the distance from an observer.
+#[derive(
+    Copy, Clone, Default, PartialEq, Eq, Debug, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum FogMode {
+    /// No fog at all.
+    #[default]
+    None,
+    /// Fog amount grows linearly from [`FogSettings::start`] to [`FogSettings::end`].
+    Linear,
+    /// Fog amount is `1 - exp(-density * distance)`.
+    Exponential,
+    /// Fog amount is `1 - exp(-(density * distance)^2)`. It has a sharper transition compared
+    /// to [`FogMode::Exponential`].
+    ExponentialSquared,
+}
+
+uuid_provider!(FogMode = "f4b5dc9b-5a83-4b6e-a8b1-d0b6a0b5e8f2");
+
+/// Global fog settings of a scene. Deferred (opaque) geometry is fogged in a separate pass after
+/// the lighting was calculated, and the built-in 3D forward shaders (standard, sprite, particle
+/// system and glTF shaders) apply the same fog themselves. 2D shaders (`standard2d` and `tile`)
+/// are not fogged, custom forward shaders should use `fyrox_fogData` property group with
+/// `S_FogFactor` function to support the fog (see [`crate::material::shader`] docs for more info).
+///
+/// Exponential fog modes can also be height-based - set [`Self::height_falloff`] to a positive
+/// value to make the fog density decay exponentially above [`Self::height`]. This is useful to
+/// make ground fog in valleys and such.
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct FogSettings {
+    /// Defines how the fog density changes with the distance. See [`FogMode`] docs for more info.
+    pub mode: FogMode,
+
+    /// Color of the fog. Alpha channel defines maximum fog amount.
+    pub color: Color,
+
+    /// Distance from the observer at which the fog starts.
+    #[reflect(min_value = 0.0)]
+    pub start: f32,
+
+    /// Distance from the observer at which the fog reaches its maximum. Used only by
+    /// [`FogMode::Linear`].
+    #[reflect(min_value = 0.0)]
+    pub end: f32,
+
+    /// Density of the fog. Used only by exponential fog modes.
+    #[reflect(min_value = 0.0, step = 0.001)]
+    pub density: f32,
+
+    /// World-space height (Y coordinate) at which the fog has its nominal density. Used only
+    /// when [`Self::height_falloff`] is greater than zero.
+    pub height: f32,
+
+    /// Defines how fast the fog density decreases with height. Zero means that the fog density
+    /// does not depend on height. Used only by exponential fog modes.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub height_falloff: f32,
+}
+
+uuid_provider!(FogSettings = "0d5f2cb0-6c1e-4a6f-9a4b-2f8c55d3a7e1");
+
+impl Default for FogSettings {
+    fn default() -> Self {
+        Self {
+            mode: FogMode::None,
+            color: Color::opaque(128, 140, 153),
+            start: 10.0,
+            end: 100.0,
+            density: 0.02,
+            height: 0.0,
+            height_falloff: 0.0,
+        }
+    }
+}
+
+impl FogSettings {
+    /// Returns `true` if the fog will be visible, `false` - otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.mode != FogMode::None && self.color.a != 0
+    }
+
+    /// Returns an integer identifier of the fog mode, that is used in shaders.
+    pub fn mode_index(&self) -> i32 {
+        match self.mode {
+            FogMode::None => 0,
+            FogMode::Linear => 1,
+            FogMode::Exponential => 2,
+            FogMode::ExponentialSquared => 3,
         }
     }
 }
@@ -652,3 +759,79 @@ impl IndexMut<Handle<Scene>> for SceneContainer {
         &mut self.pool[index]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::manager::ResourceManager,
+        core::{
+            color::Color,
+            visitor::{Visit, VisitResult, Visitor},
+        },
+        renderer::framework::PolygonFillMode,
+        resource::texture::TextureResource,
+        scene::{FogMode, FogSettings, SceneRenderingOptions},
+    };
+    use std::sync::Arc;
+
+    // Layout of the rendering options before the fog was added.
+    #[derive(Visit)]
+    struct LegacySceneRenderingOptions {
+        render_target: Option<TextureResource>,
+        clear_color: Option<Color>,
+        polygon_rasterization_mode: PolygonFillMode,
+        ambient_lighting_color: Color,
+    }
+
+    fn save_and_load(entity: &mut impl Visit) -> SceneRenderingOptions {
+        let mut visitor = Visitor::new();
+        entity.visit("RenderingOptions", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        visitor
+            .blackboard
+            .register(Arc::new(ResourceManager::new(Arc::new(Default::default()))));
+        let mut options = SceneRenderingOptions::default();
+        options.visit("RenderingOptions", &mut visitor).unwrap();
+        options
+    }
+
+    #[test]
+    fn test_legacy_rendering_options_have_fog_disabled() {
+        let mut legacy = LegacySceneRenderingOptions {
+            render_target: None,
+            clear_color: Some(Color::RED),
+            polygon_rasterization_mode: PolygonFillMode::Line,
+            ambient_lighting_color: Color::opaque(10, 20, 30),
+        };
+
+        let options = save_and_load(&mut legacy);
+        assert_eq!(options.clear_color, Some(Color::RED));
+        assert_eq!(options.polygon_rasterization_mode, PolygonFillMode::Line);
+        assert_eq!(options.ambient_lighting_color, Color::opaque(10, 20, 30));
+        assert_eq!(options.fog, FogSettings::default());
+        assert!(!options.fog.is_enabled());
+    }
+
+    #[test]
+    fn test_fog_settings_save_load() {
+        let mut options = SceneRenderingOptions {
+            fog: FogSettings {
+                mode: FogMode::ExponentialSquared,
+                color: Color::opaque(200, 210, 220),
+                start: 5.0,
+                end: 50.0,
+                density: 0.1,
+                height: 2.0,
+                height_falloff: 0.5,
+            },
+            ..Default::default()
+        };
+
+        let loaded = save_and_load(&mut options);
+        assert_eq!(loaded, options);
+        assert!(loaded.fog.is_enabled());
+        assert_eq!(loaded.fog.mode_index(), 3);
+    }
+}
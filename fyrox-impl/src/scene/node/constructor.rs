@@ -25,6 +25,7 @@ use crate::scene::{
     self,
     animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
     camera::Camera,
+    cloth::Cloth,
     decal::Decal,
     dim2::{self, rectangle::Rectangle},
     light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
@@ -34,10 +35,13 @@ use crate::scene::{
     particle_system::ParticleSystem,
     pivot::Pivot,
     ragdoll::Ragdoll,
+    reflection_probe::ReflectionProbe,
     sound::{listener::Listener, Sound},
+    spline::Spline,
     sprite::Sprite,
     terrain::Terrain,
     tilemap::TileMap,
+    vehicle::Vehicle,
 };
 use fyrox_graph::constructor::{GraphNodeConstructor, GraphNodeConstructorContainer};
 
@@ -55,6 +59,11 @@ pub fn new_node_constructor_container() -> NodeConstructorContainer {
     container.add::<dim2::joint::Joint>();
     container.add::<Rectangle>();
     container.add::<dim2::rigidbody::RigidBody>();
+    container.add::<dim2::character_controller::CharacterController>();
+    container.add::<dim2::light::point::PointLight>();
+    container.add::<dim2::light::cone::ConeLight>();
+    container.add::<dim2::light::global::GlobalLight>();
+    container.add::<dim2::occluder::Occluder>();
     container.add::<DirectionalLight>();
     container.add::<PointLight>();
     container.add::<SpotLight>();
@@ -65,9 +74,11 @@ pub fn new_node_constructor_container() -> NodeConstructorContainer {
     container.add::<Camera>();
     container.add::<scene::collider::Collider>();
     container.add::<Decal>();
+    container.add::<ReflectionProbe>();
     container.add::<scene::joint::Joint>();
     container.add::<Pivot>();
     container.add::<scene::rigidbody::RigidBody>();
+    container.add::<scene::character_controller::CharacterController>();
     container.add::<Sprite>();
     container.add::<Terrain>();
     container.add::<AnimationPlayer>();
@@ -75,6 +86,9 @@ pub fn new_node_constructor_container() -> NodeConstructorContainer {
     container.add::<NavigationalMesh>();
     container.add::<Ragdoll>();
     container.add::<TileMap>();
+    container.add::<Spline>();
+    container.add::<Vehicle>();
+    container.add::<Cloth>();
 
     container
 }
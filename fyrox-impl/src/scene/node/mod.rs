@@ -521,6 +521,7 @@ impl Node {
     define_is_as!(Sprite  => fn is_sprite, fn as_sprite, fn as_sprite_mut);
     define_is_as!(Terrain  => fn is_terrain, fn as_terrain, fn as_terrain_mut);
     define_is_as!(Decal => fn is_decal, fn as_decal, fn as_decal_mut);
+    define_is_as!(scene::reflection_probe::ReflectionProbe => fn is_reflection_probe, fn as_reflection_probe, fn as_reflection_probe_mut);
     define_is_as!(Rectangle => fn is_rectangle, fn as_rectangle, fn as_rectangle_mut);
     define_is_as!(scene::rigidbody::RigidBody  => fn is_rigid_body, fn as_rigid_body, fn as_rigid_body_mut);
     define_is_as!(scene::collider::Collider => fn is_collider, fn as_collider, fn as_collider_mut);
@@ -528,6 +529,8 @@ impl Node {
     define_is_as!(dim2::rigidbody::RigidBody => fn is_rigid_body2d, fn as_rigid_body2d, fn as_rigid_body2d_mut);
     define_is_as!(dim2::collider::Collider => fn is_collider2d, fn as_collider2d, fn as_collider2d_mut);
     define_is_as!(dim2::joint::Joint => fn is_joint2d, fn as_joint2d, fn as_joint2d_mut);
+    define_is_as!(scene::character_controller::CharacterController => fn is_character_controller, fn as_character_controller, fn as_character_controller_mut);
+    define_is_as!(dim2::character_controller::CharacterController => fn is_character_controller2d, fn as_character_controller2d, fn as_character_controller2d_mut);
     define_is_as!(Sound => fn is_sound, fn as_sound, fn as_sound_mut);
     define_is_as!(Listener => fn is_listener, fn as_listener, fn as_listener_mut);
     define_is_as!(NavigationalMesh => fn is_navigational_mesh, fn as_navigational_mesh, fn as_navigational_mesh_mut);
@@ -0,0 +1,97 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Particle collision settings. See [`ParticleCollision`] docs for more info.
+
+use crate::{
+    core::{reflect::prelude::*, type_traits::prelude::*, uuid_provider, visitor::prelude::*},
+    scene::collider::InteractionGroups,
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines what happens with a particle when it collides with something.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "ca951abe-b973-410f-a9ff-2b5c0c774f9a")]
+pub enum ParticleCollisionResponse {
+    /// The particle bounces off the surface, see [`ParticleCollision::restitution`] and
+    /// [`ParticleCollision::friction`].
+    #[default]
+    Bounce,
+    /// The particle dies immediately.
+    Kill,
+}
+
+/// Collision settings of a particle system. When enabled, particles collide with the colliders of
+/// the 3D physics world of the scene. Every particle is treated as a sphere, which radius is
+/// proportional to the size of the particle. Collisions are checked using ray casts along the path
+/// of each particle, so even fast particles cannot pass through thin walls.
+///
+/// Keep in mind, that collisions are quite expensive and should be enabled only for particle
+/// systems with a moderate amount of particles. Collisions are not simulated when a particle
+/// system is rewound (see [`super::ParticleSystem::rewind`]), because the physics world is not
+/// available at this moment.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct ParticleCollision {
+    /// Enables or disables collisions.
+    pub enabled: bool,
+    /// Defines what happens with a particle when it collides with something.
+    pub response: ParticleCollisionResponse,
+    /// Defines how much of the normal velocity is preserved after a bounce. Zero means that the
+    /// particle stops at the surface, one - fully elastic bounce.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub restitution: f32,
+    /// Defines how much of the tangential velocity is lost after a bounce.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub friction: f32,
+    /// Collision radius of a particle relative to its size.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub radius_scale: f32,
+    /// Collision groups that are used to filter out colliders.
+    pub groups: InteractionGroups,
+}
+
+uuid_provider!(ParticleCollision = "037db858-be13-4d3a-b5b6-7fe548f7db2a");
+
+impl Default for ParticleCollision {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            response: Default::default(),
+            restitution: 0.5,
+            friction: 0.1,
+            radius_scale: 0.5,
+            groups: Default::default(),
+        }
+    }
+}
@@ -236,6 +236,7 @@ impl BaseEmitter {
         particle.lifetime = 0.0;
         particle.initial_lifetime = self.lifetime.random(rng);
         particle.color = Color::WHITE;
+        particle.tint = Color::WHITE;
         particle.size = self.size.random(rng);
         particle.size_modifier = self.size_modifier.random(rng);
         particle.velocity = Vector3::new(
@@ -0,0 +1,607 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Mesh emitter spawns particles on the surface of a [`Mesh`] node. The particles are distributed
+//! uniformly over the area of the surface, optionally following the skinning of the mesh and
+//! inheriting the normals and colors of its vertices. It could be used to create burning
+//! characters, dissolving props and so on.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector3},
+        color::Color,
+        numeric_range::RangeExt,
+        pool::Handle,
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
+    scene::{
+        graph::NodePool,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            surface::SurfaceResource,
+            Mesh,
+        },
+        node::Node,
+        particle_system::{
+            emitter::{
+                base::{BaseEmitter, BaseEmitterBuilder},
+                Emit, Emitter,
+            },
+            particle::Particle,
+            ParticleSystemRng,
+        },
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// A triangle of the surface of the mesh in the local coordinates of the particle system.
+#[derive(Clone, Debug)]
+struct SampleTriangle {
+    positions: [Vector3<f32>; 3],
+    normals: [Vector3<f32>; 3],
+    colors: [Color; 3],
+}
+
+/// Triangles of the mesh with their cumulative areas, that are used to pick a random point on the
+/// surface. The samples are rebuilt by the particle system, so they're never saved nor cloned.
+#[derive(Default, Debug)]
+struct MeshEmitterSamples {
+    triangles: Vec<SampleTriangle>,
+    cumulative_areas: Vec<f32>,
+    // Describes the state of the mesh the samples were built from.
+    transform: Matrix4<f32>,
+    surfaces: Vec<(SurfaceResource, u64, u64)>,
+}
+
+impl Clone for MeshEmitterSamples {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for MeshEmitterSamples {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl MeshEmitterSamples {
+    fn total_area(&self) -> f32 {
+        self.cumulative_areas.last().copied().unwrap_or_default()
+    }
+
+    fn clear(&mut self) {
+        self.triangles.clear();
+        self.cumulative_areas.clear();
+        self.surfaces.clear();
+    }
+
+    fn add_triangle(&mut self, triangle: SampleTriangle) {
+        let [a, b, c] = triangle.positions;
+        let area = (b - a).cross(&(c - a)).norm() * 0.5;
+        if area <= f32::EPSILON {
+            return;
+        }
+        self.cumulative_areas.push(self.total_area() + area);
+        self.triangles.push(triangle);
+    }
+}
+
+/// See module docs.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct MeshEmitter {
+    emitter: BaseEmitter,
+    mesh: Handle<Node>,
+    follow_skinning: bool,
+    #[reflect(step = 0.001)]
+    normal_velocity: f32,
+    inherit_color: bool,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    samples: MeshEmitterSamples,
+}
+
+impl Default for MeshEmitter {
+    fn default() -> Self {
+        Self {
+            emitter: Default::default(),
+            mesh: Default::default(),
+            follow_skinning: true,
+            normal_velocity: 0.0,
+            inherit_color: false,
+            samples: Default::default(),
+        }
+    }
+}
+
+impl Deref for MeshEmitter {
+    type Target = BaseEmitter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.emitter
+    }
+}
+
+impl DerefMut for MeshEmitter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.emitter
+    }
+}
+
+impl MeshEmitter {
+    /// Returns a handle of the mesh, on surface of which the particles are spawned.
+    pub fn mesh(&self) -> Handle<Node> {
+        self.mesh
+    }
+
+    /// Sets a handle of the mesh, on surface of which the particles will be spawned. The emitter
+    /// spawns particles at its position, if the handle does not point to a mesh.
+    pub fn set_mesh(&mut self, mesh: Handle<Node>) {
+        self.mesh = mesh;
+    }
+
+    /// Returns `true` if the emitter follows the skinning of the mesh, `false` - otherwise.
+    pub fn is_following_skinning(&self) -> bool {
+        self.follow_skinning
+    }
+
+    /// Defines whether the emitter should follow the skinning of the mesh or not. If disabled, the
+    /// particles are spawned on the surface in bind pose. Keep in mind, that skinned surfaces must
+    /// be processed on CPU every frame, which could be slow for high-poly meshes.
+    pub fn set_follow_skinning(&mut self, follow_skinning: bool) {
+        self.follow_skinning = follow_skinning;
+        // Force the samples to be rebuilt on next update.
+        self.samples.clear();
+    }
+
+    /// Returns initial speed of the particles along the normal of the surface.
+    pub fn normal_velocity(&self) -> f32 {
+        self.normal_velocity
+    }
+
+    /// Sets initial speed of the particles along the normal of the surface at their spawn point.
+    /// It is added to the random velocity of the particles.
+    pub fn set_normal_velocity(&mut self, normal_velocity: f32) {
+        self.normal_velocity = normal_velocity;
+    }
+
+    /// Returns `true` if the particles inherit the color of the vertices, `false` - otherwise.
+    pub fn is_inheriting_color(&self) -> bool {
+        self.inherit_color
+    }
+
+    /// Defines whether the particles should inherit the color of the vertices of the mesh or not.
+    /// The color is used as a tint of the particles (see [`Particle::tint`]).
+    pub fn set_inherit_color(&mut self, inherit_color: bool) {
+        self.inherit_color = inherit_color;
+    }
+
+    /// Updates the surface samples using the current state of the mesh. `inv_global_transform`
+    /// is an inverse global transform of the particle system.
+    pub(crate) fn update(&mut self, nodes: &NodePool, inv_global_transform: &Matrix4<f32>) {
+        let Some(mesh) = nodes
+            .try_borrow(self.mesh)
+            .and_then(|node| node.cast::<Mesh>())
+        else {
+            self.samples.clear();
+            return;
+        };
+
+        let is_skinned = self.follow_skinning
+            && mesh
+                .surfaces()
+                .iter()
+                .any(|surface| !surface.bones().is_empty());
+        let transform = inv_global_transform * mesh.global_transform();
+
+        // Static meshes are processed only when they are moved or modified.
+        let surfaces = mesh
+            .surfaces()
+            .iter()
+            .map(|surface| {
+                let data = surface.data();
+                let data_ref = data.data_ref();
+                let counters = (
+                    data_ref.vertex_buffer.modifications_count(),
+                    data_ref.geometry_buffer.modifications_count(),
+                );
+                drop(data_ref);
+                (data, counters.0, counters.1)
+            })
+            .collect::<Vec<_>>();
+        if !is_skinned && self.samples.transform == transform && self.samples.surfaces == surfaces {
+            return;
+        }
+
+        self.samples.clear();
+        self.samples.transform = transform;
+        self.samples.surfaces = surfaces;
+
+        for surface in mesh.surfaces() {
+            let bone_matrices = if self.follow_skinning {
+                surface
+                    .bones()
+                    .iter()
+                    .map(|&bone| {
+                        nodes
+                            .try_borrow(bone)
+                            .map_or_else(Matrix4::identity, |bone| {
+                                inv_global_transform
+                                    * bone.global_transform()
+                                    * bone.inv_bind_pose_transform()
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                Vec::new()
+            };
+
+            let data = surface.data();
+            let data = data.data_ref();
+
+            let mut vertices = Vec::with_capacity(data.vertex_buffer.vertex_count() as usize);
+            for view in data.vertex_buffer.iter() {
+                let Ok(position) = view.read_3_f32(VertexAttributeUsage::Position) else {
+                    break;
+                };
+                let normal = view
+                    .read_3_f32(VertexAttributeUsage::Normal)
+                    .unwrap_or_default();
+                let color = view
+                    .read_4_u8(VertexAttributeUsage::Color)
+                    .map_or(Color::WHITE, |c| Color::from_rgba(c.x, c.y, c.z, c.w));
+
+                let (position, normal) = if bone_matrices.is_empty() {
+                    (
+                        transform.transform_point(&Point3::from(position)).coords,
+                        transform.transform_vector(&normal),
+                    )
+                } else {
+                    let mut skinned_position = Vector3::default();
+                    let mut skinned_normal = Vector3::default();
+                    if let (Ok(bone_indices), Ok(bone_weights)) = (
+                        view.read_4_u8(VertexAttributeUsage::BoneIndices),
+                        view.read_4_f32(VertexAttributeUsage::BoneWeight),
+                    ) {
+                        for (&bone_index, &weight) in bone_indices.iter().zip(bone_weights.iter()) {
+                            if let Some(bone_matrix) = bone_matrices.get(bone_index as usize) {
+                                skinned_position += bone_matrix
+                                    .transform_point(&Point3::from(position))
+                                    .coords
+                                    .scale(weight);
+                                skinned_normal +=
+                                    bone_matrix.transform_vector(&normal).scale(weight);
+                            }
+                        }
+                    }
+                    (skinned_position, skinned_normal)
+                };
+
+                vertices.push((position, normal, color));
+            }
+
+            for triangle in data.geometry_buffer.iter() {
+                let (Some(a), Some(b), Some(c)) = (
+                    vertices.get(triangle[0] as usize),
+                    vertices.get(triangle[1] as usize),
+                    vertices.get(triangle[2] as usize),
+                ) else {
+                    continue;
+                };
+                self.samples.add_triangle(SampleTriangle {
+                    positions: [a.0, b.0, c.0],
+                    normals: [a.1, b.1, c.1],
+                    colors: [a.2, b.2, c.2],
+                });
+            }
+        }
+    }
+}
+
+impl Emit for MeshEmitter {
+    fn emit(&self, particle: &mut Particle, rng: &mut ParticleSystemRng) {
+        self.emitter.emit(particle, rng);
+
+        let total_area = self.samples.total_area();
+        if total_area <= 0.0 {
+            particle.position = self.position();
+            return;
+        }
+
+        // Pick a triangle with probability proportional to its area.
+        let area = (0.0..total_area).random(rng);
+        let index = self
+            .samples
+            .cumulative_areas
+            .partition_point(|&cumulative_area| cumulative_area <= area)
+            .min(self.samples.triangles.len() - 1);
+        let triangle = &self.samples.triangles[index];
+
+        // Uniformly distributed barycentric coordinates.
+        let mut u = (0.0..1.0).random(rng);
+        let mut v = (0.0..1.0).random(rng);
+        if u + v > 1.0 {
+            u = 1.0 - u;
+            v = 1.0 - v;
+        }
+        let w = 1.0 - u - v;
+
+        let [a, b, c] = triangle.positions;
+        particle.position = self.position() + a.scale(w) + b.scale(u) + c.scale(v);
+
+        let [na, nb, nc] = triangle.normals;
+        let normal = (na.scale(w) + nb.scale(u) + nc.scale(v))
+            .try_normalize(f32::EPSILON)
+            .or_else(|| (b - a).cross(&(c - a)).try_normalize(f32::EPSILON))
+            .unwrap_or_default();
+        particle.velocity += normal.scale(self.normal_velocity);
+
+        if self.inherit_color {
+            let [ca, cb, cc] = triangle.colors;
+            particle.tint = Color::from(
+                ca.as_frgba().scale(w) + cb.as_frgba().scale(u) + cc.as_frgba().scale(v),
+            );
+        }
+    }
+}
+
+/// Mesh emitter builder allows you to construct mesh emitter in declarative manner.
+/// This is typical implementation of Builder pattern.
+pub struct MeshEmitterBuilder {
+    base: BaseEmitterBuilder,
+    mesh: Handle<Node>,
+    follow_skinning: bool,
+    normal_velocity: f32,
+    inherit_color: bool,
+}
+
+impl MeshEmitterBuilder {
+    /// Creates new mesh emitter builder.
+    pub fn new(base: BaseEmitterBuilder) -> Self {
+        Self {
+            base,
+            mesh: Default::default(),
+            follow_skinning: true,
+            normal_velocity: 0.0,
+            inherit_color: false,
+        }
+    }
+
+    /// Sets desired mesh of the emitter.
+    pub fn with_mesh(mut self, mesh: Handle<Node>) -> Self {
+        self.mesh = mesh;
+        self
+    }
+
+    /// Sets whether the emitter should follow the skinning of the mesh or not.
+    pub fn with_follow_skinning(mut self, follow_skinning: bool) -> Self {
+        self.follow_skinning = follow_skinning;
+        self
+    }
+
+    /// Sets desired initial speed of the particles along the normal of the surface.
+    pub fn with_normal_velocity(mut self, normal_velocity: f32) -> Self {
+        self.normal_velocity = normal_velocity;
+        self
+    }
+
+    /// Sets whether the particles should inherit the color of the vertices or not.
+    pub fn with_inherit_color(mut self, inherit_color: bool) -> Self {
+        self.inherit_color = inherit_color;
+        self
+    }
+
+    /// Creates new mesh emitter.
+    pub fn build(self) -> Emitter {
+        Emitter::Mesh(MeshEmitter {
+            emitter: self.base.build(),
+            mesh: self.mesh,
+            follow_skinning: self.follow_skinning,
+            normal_velocity: self.normal_velocity,
+            inherit_color: self.inherit_color,
+            samples: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::{
+            algebra::{Matrix4, Vector2, Vector3, Vector4},
+            color::Color,
+            math::TriangleDefinition,
+        },
+        scene::{
+            base::BaseBuilder,
+            graph::NodePool,
+            mesh::{
+                buffer::{TriangleBuffer, VertexBuffer},
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                vertex::AnimatedVertex,
+                MeshBuilder,
+            },
+            particle_system::{
+                emitter::{
+                    mesh::{MeshEmitter, SampleTriangle},
+                    Emit,
+                },
+                particle::Particle,
+                ParticleSystemRng,
+            },
+            pivot::PivotBuilder,
+        },
+    };
+
+    fn triangle(a: Vector3<f32>, b: Vector3<f32>, c: Vector3<f32>) -> SampleTriangle {
+        SampleTriangle {
+            positions: [a, b, c],
+            normals: [Vector3::z(); 3],
+            colors: [Color::WHITE; 3],
+        }
+    }
+
+    #[test]
+    fn test_degenerate_triangles_are_skipped() {
+        let mut emitter = MeshEmitter::default();
+
+        // Collinear and coincident vertices.
+        emitter.samples.add_triangle(triangle(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+        ));
+        emitter
+            .samples
+            .add_triangle(triangle(Vector3::x(), Vector3::x(), Vector3::x()));
+        assert!(emitter.samples.triangles.is_empty());
+        assert_eq!(emitter.samples.total_area(), 0.0);
+
+        // Particles are spawned at the position of the emitter, if there's no surface.
+        let mut particle = Particle::default();
+        emitter.emit(&mut particle, &mut ParticleSystemRng::new(0));
+        assert_eq!(particle.position, Vector3::default());
+
+        emitter.samples.add_triangle(triangle(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        ));
+        assert_eq!(emitter.samples.triangles.len(), 1);
+        assert_eq!(emitter.samples.cumulative_areas, [1.0]);
+    }
+
+    #[test]
+    fn test_area_weighted_triangle_picking() {
+        let mut emitter = MeshEmitter::default();
+        // Area = 1.0
+        emitter.samples.add_triangle(triangle(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        ));
+        // Area = 3.0
+        emitter.samples.add_triangle(triangle(
+            Vector3::new(10.0, 0.0, 0.0),
+            Vector3::new(16.0, 0.0, 0.0),
+            Vector3::new(10.0, 1.0, 0.0),
+        ));
+        assert_eq!(emitter.samples.cumulative_areas, [1.0, 4.0]);
+
+        let mut rng = ParticleSystemRng::new(123);
+        let count = 10000;
+        let mut on_large = 0;
+        for _ in 0..count {
+            let mut particle = Particle::default();
+            emitter.emit(&mut particle, &mut rng);
+            let p = particle.position;
+            assert_eq!(p.z, 0.0);
+            if p.x >= 10.0 {
+                assert!(p.x <= 16.0 && (p.x - 10.0) / 6.0 + p.y <= 1.0 + f32::EPSILON);
+                on_large += 1;
+            } else {
+                assert!(p.x >= 0.0 && p.x / 2.0 + p.y <= 1.0 + f32::EPSILON);
+            }
+        }
+
+        let ratio = on_large as f32 / count as f32;
+        assert!((ratio - 0.75).abs() < 0.03, "{ratio}");
+    }
+
+    #[test]
+    fn test_skinned_positions_are_blended() {
+        let mut nodes = NodePool::new();
+
+        let bone_a = nodes.spawn(PivotBuilder::new(BaseBuilder::new()).build_node());
+        let bone_b = nodes.spawn(PivotBuilder::new(BaseBuilder::new()).build_node());
+        nodes[bone_a]
+            .global_transform
+            .set(Matrix4::new_translation(&Vector3::new(0.0, 0.0, 0.0)));
+        nodes[bone_b]
+            .global_transform
+            .set(Matrix4::new_translation(&Vector3::new(2.0, 0.0, 4.0)));
+
+        let vertex = |position: Vector3<f32>| AnimatedVertex {
+            position,
+            tex_coord: Vector2::default(),
+            normal: Vector3::z(),
+            tangent: Vector4::default(),
+            bone_weights: [0.5, 0.5, 0.0, 0.0],
+            bone_indices: [0, 1, 0, 0],
+        };
+        let vertices = vec![
+            vertex(Vector3::new(0.0, 0.0, 0.0)),
+            vertex(Vector3::new(1.0, 0.0, 0.0)),
+            vertex(Vector3::new(0.0, 1.0, 0.0)),
+        ];
+        let data = SurfaceData::new(
+            VertexBuffer::new(vertices.len(), vertices).unwrap(),
+            TriangleBuffer::new(vec![TriangleDefinition([0, 1, 2])]),
+        );
+        let mesh = nodes.spawn(
+            MeshBuilder::new(BaseBuilder::new())
+                .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+                    ResourceKind::Embedded,
+                    data,
+                ))
+                .with_bones(vec![bone_a, bone_b])
+                .build()])
+                .build_node(),
+        );
+
+        let mut emitter = MeshEmitter::default();
+        emitter.set_mesh(mesh);
+
+        // Each vertex is affected by both bones equally.
+        emitter.update(&nodes, &Matrix4::identity());
+        assert_eq!(
+            emitter.samples.triangles[0].positions,
+            [
+                Vector3::new(1.0, 0.0, 2.0),
+                Vector3::new(2.0, 0.0, 2.0),
+                Vector3::new(1.0, 1.0, 2.0),
+            ]
+        );
+
+        // Skinned samples are rebuilt every frame.
+        nodes[bone_a]
+            .global_transform
+            .set(Matrix4::new_translation(&Vector3::new(2.0, 0.0, 4.0)));
+        emitter.update(&nodes, &Matrix4::identity());
+        assert_eq!(
+            emitter.samples.triangles[0].positions[0],
+            Vector3::new(2.0, 0.0, 4.0)
+        );
+
+        // The bind pose is used when skinning is ignored.
+        emitter.set_follow_skinning(false);
+        emitter.update(&nodes, &Matrix4::identity());
+        assert_eq!(
+            emitter.samples.triangles[0].positions,
+            [
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            ]
+        );
+    }
+}
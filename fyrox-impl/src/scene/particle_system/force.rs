@@ -0,0 +1,239 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Force fields affect velocity of particles in a particle system. See [`ForceField`] docs for more
+//! info.
+
+use crate::core::{
+    algebra::{Matrix4, Point3, Vector3},
+    reflect::prelude::*,
+    uuid_provider,
+    visitor::prelude::*,
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Force field affects velocity of every particle of a particle system. All the positions and
+/// directions are defined in local coordinates of the particle system, radius of a field is not
+/// affected by scaling of the particle system.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect, AsRefStr, EnumString, VariantNames)]
+pub enum ForceField {
+    /// Pulls particles towards a point. Negative strength pushes particles away from the point.
+    Attractor {
+        /// Position of the attractor.
+        position: Vector3<f32>,
+        /// Acceleration of particles (in m/s²) near the attractor.
+        strength: f32,
+        /// Radius of influence of the attractor. The force fades out linearly towards the edge of
+        /// the radius. Zero radius means infinite influence without fading.
+        radius: f32,
+    },
+    /// Rotates particles around an axis.
+    Vortex {
+        /// A point on the axis of the vortex.
+        position: Vector3<f32>,
+        /// Direction of the axis of the vortex. Particles rotate counterclockwise around the axis
+        /// when the strength is positive.
+        axis: Vector3<f32>,
+        /// Tangential acceleration of particles (in m/s²) near the axis of the vortex.
+        strength: f32,
+        /// Radius of influence of the vortex. The force fades out linearly towards the edge of the
+        /// radius. Zero radius means infinite influence without fading.
+        radius: f32,
+    },
+    /// Applies a smooth pseudo-random acceleration to particles, which makes their motion chaotic.
+    /// Useful for smoke, dust, snow, etc.
+    Turbulence {
+        /// Maximum acceleration of particles (in m/s²).
+        strength: f32,
+        /// Spatial frequency of the noise. Higher values make the motion more chaotic.
+        frequency: f32,
+        /// Speed at which the noise changes over time.
+        speed: f32,
+    },
+}
+
+uuid_provider!(ForceField = "d975366c-4eb5-4fd1-92ef-58bf980b920c");
+
+impl Default for ForceField {
+    fn default() -> Self {
+        Self::Attractor {
+            position: Default::default(),
+            strength: 1.0,
+            radius: 5.0,
+        }
+    }
+}
+
+fn falloff(distance: f32, radius: f32) -> f32 {
+    if radius > 0.0 {
+        (1.0 - distance / radius).max(0.0)
+    } else {
+        1.0
+    }
+}
+
+fn hash(x: i32, y: i32, z: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x8da6b343)
+        ^ (y as u32).wrapping_mul(0xd8163841)
+        ^ (z as u32).wrapping_mul(0xcb1ab31f);
+    h = (h ^ (h >> 13)).wrapping_mul(0x5bd1e995);
+    h ^= h >> 15;
+    h as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+// Smooth value noise in [-1; 1] range.
+fn value_noise(p: Vector3<f32>) -> f32 {
+    let cell = p.map(|c| c.floor());
+    let (x, y, z) = (cell.x as i32, cell.y as i32, cell.z as i32);
+    let f = (p - cell).map(|t| t * t * (3.0 - 2.0 * t));
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let plane = |dz: i32| {
+        lerp(
+            lerp(hash(x, y, z + dz), hash(x + 1, y, z + dz), f.x),
+            lerp(hash(x, y + 1, z + dz), hash(x + 1, y + 1, z + dz), f.x),
+            f.y,
+        )
+    };
+    lerp(plane(0), plane(1), f.z)
+}
+
+impl ForceField {
+    /// Calculates acceleration of a particle at the given position. `time` is used to animate
+    /// turbulence.
+    pub fn acceleration(&self, point: Vector3<f32>, time: f32) -> Vector3<f32> {
+        match self {
+            ForceField::Attractor {
+                position,
+                strength,
+                radius,
+            } => {
+                let delta = position - point;
+                let distance = delta.norm();
+                delta
+                    .try_normalize(f32::EPSILON)
+                    .map(|direction| direction * *strength * falloff(distance, *radius))
+                    .unwrap_or_default()
+            }
+            ForceField::Vortex {
+                position,
+                axis,
+                strength,
+                radius,
+            } => {
+                let Some(axis) = axis.try_normalize(f32::EPSILON) else {
+                    return Vector3::default();
+                };
+                let offset = point - position;
+                let radial = offset - axis.scale(offset.dot(&axis));
+                let distance = radial.norm();
+                axis.cross(&radial)
+                    .try_normalize(f32::EPSILON)
+                    .map(|direction| direction * *strength * falloff(distance, *radius))
+                    .unwrap_or_default()
+            }
+            ForceField::Turbulence {
+                strength,
+                frequency,
+                speed,
+            } => {
+                let p = point * *frequency + Vector3::repeat(time * *speed);
+                // Sample the noise at three distant points to get three independent components.
+                Vector3::new(
+                    value_noise(p),
+                    value_noise(p + Vector3::new(31.416, 47.853, 12.793)),
+                    value_noise(p + Vector3::new(-19.151, 73.156, 52.235)),
+                ) * *strength
+            }
+        }
+    }
+
+    /// Returns a copy of the force field transformed using the given matrix.
+    pub fn transform(&self, matrix: &Matrix4<f32>) -> Self {
+        match self {
+            ForceField::Attractor {
+                position,
+                strength,
+                radius,
+            } => ForceField::Attractor {
+                position: matrix.transform_point(&Point3::from(*position)).coords,
+                strength: *strength,
+                radius: *radius,
+            },
+            ForceField::Vortex {
+                position,
+                axis,
+                strength,
+                radius,
+            } => ForceField::Vortex {
+                position: matrix.transform_point(&Point3::from(*position)).coords,
+                axis: matrix.transform_vector(axis),
+                strength: *strength,
+                radius: *radius,
+            },
+            ForceField::Turbulence { .. } => self.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        scene::particle_system::force::{value_noise, ForceField},
+    };
+
+    #[test]
+    fn test_attractor_and_vortex() {
+        let attractor = ForceField::Attractor {
+            position: Vector3::new(1.0, 0.0, 0.0),
+            strength: 2.0,
+            radius: 0.0,
+        };
+        assert_eq!(
+            attractor.acceleration(Vector3::default(), 0.0),
+            Vector3::new(2.0, 0.0, 0.0)
+        );
+
+        let vortex = ForceField::Vortex {
+            position: Vector3::default(),
+            axis: Vector3::y(),
+            strength: 1.0,
+            radius: 2.0,
+        };
+        // Half of the radius gives half of the strength.
+        let acceleration = vortex.acceleration(Vector3::new(1.0, 5.0, 0.0), 0.0);
+        assert!((acceleration - Vector3::new(0.0, 0.0, -0.5)).norm() < 1.0e-6);
+        assert_eq!(
+            vortex.acceleration(Vector3::new(3.0, 0.0, 0.0), 0.0),
+            Vector3::default()
+        );
+    }
+
+    #[test]
+    fn test_value_noise_range_and_continuity() {
+        let mut previous = value_noise(Vector3::new(0.0, 0.3, -0.7));
+        for i in 1..1000 {
+            let value = value_noise(Vector3::new(i as f32 * 0.01, 0.3, -0.7));
+            assert!((-1.0..=1.0).contains(&value));
+            assert!((value - previous).abs() < 0.1);
+            previous = value;
+        }
+    }
+}
@@ -0,0 +1,362 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Bookkeeping of particle systems that are simulated on GPU. See [`super::ParticleSimulation`]
+//! docs for more info.
+//!
+//! The state of every particle is stored in a floating-point texture, each particle occupies
+//! [`TEXELS_PER_PARTICLE`] consecutive texels in a row:
+//!
+//! - `xyz` - position, `w` - age (in seconds).
+//! - `xyz` - velocity, `w` - lifetime (in seconds).
+//! - `x` - size, `y` - size modifier, `z` - rotation, `w` - rotation speed.
+//! - `rgba` - color.
+//! - `rgba` - tint.
+//!
+//! A particle is dead, if its age is greater or equal than its lifetime. Particles are spawned on
+//! CPU using the emitters of the particle system, so the CPU knows which slots of the texture are
+//! occupied. Spawned particles and simulation steps are recorded as commands, that are executed
+//! by the renderer.
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::{
+        algebra::{Vector2, Vector3, Vector4},
+        math::TriangleDefinition,
+        parking_lot::Mutex,
+    },
+    resource::texture::{TextureResource, TextureResourceExtension},
+    scene::{
+        mesh::{
+            buffer::{
+                TriangleBuffer, VertexAttributeDataType, VertexAttributeDescriptor,
+                VertexAttributeUsage, VertexBuffer, VertexTrait,
+            },
+            surface::{SurfaceData, SurfaceResource},
+        },
+        particle_system::particle::Particle,
+    },
+};
+use bytemuck::{Pod, Zeroable};
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// Amount of particles stored in a single row of the state texture.
+pub(crate) const PARTICLES_PER_ROW: u32 = 256;
+
+/// Amount of texels used to store the state of a single particle.
+pub(crate) const TEXELS_PER_PARTICLE: u32 = 5;
+
+/// Max amount of simulation steps that could wait for the renderer. If a scene is not rendered
+/// for a long time, the state will be reset instead of accumulating the commands indefinitely.
+const MAX_PENDING_STEPS: usize = 1024;
+
+/// Amount of samples of the color gradient, that are used by the simulation shader.
+pub(crate) const COLOR_OVER_LIFETIME_SAMPLES: usize = 32;
+
+/// Returns the size of the state texture (in texels) for the given amount of particles.
+pub(crate) fn state_texture_size(capacity: u32) -> (u32, u32) {
+    (
+        PARTICLES_PER_ROW * TEXELS_PER_PARTICLE,
+        capacity.div_ceil(PARTICLES_PER_ROW).max(1),
+    )
+}
+
+/// Returns the coordinates of the first texel of the particle with the given index.
+pub(crate) fn particle_texel(slot: u32) -> Vector2<u32> {
+    Vector2::new(
+        (slot % PARTICLES_PER_ROW) * TEXELS_PER_PARTICLE,
+        slot / PARTICLES_PER_ROW,
+    )
+}
+
+/// A vertex of a particle quad. The actual position of the vertex is calculated in the vertex
+/// shader using the state of the particle.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub(crate) struct GpuParticleVertex {
+    /// Coordinates of the first texel of the particle in the state texture.
+    pub state_texel: Vector3<f32>,
+    /// A corner of the particle quad.
+    pub corner: Vector2<f32>,
+}
+
+impl VertexTrait for GpuParticleVertex {
+    fn layout() -> &'static [VertexAttributeDescriptor] {
+        &[
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Position,
+                data_type: VertexAttributeDataType::F32,
+                size: 3,
+                divisor: 0,
+                shader_location: 0,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::TexCoord0,
+                data_type: VertexAttributeDataType::F32,
+                size: 2,
+                divisor: 0,
+                shader_location: 1,
+                normalized: false,
+            },
+        ]
+    }
+}
+
+fn make_particles_surface(capacity: u32) -> SurfaceResource {
+    let mut vertices = Vec::with_capacity(capacity as usize * 4);
+    let mut triangles = Vec::with_capacity(capacity as usize * 2);
+    for slot in 0..capacity {
+        let texel = particle_texel(slot).cast::<f32>();
+        let state_texel = Vector3::new(texel.x, texel.y, 0.0);
+        for corner in [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(0.0, 1.0),
+        ] {
+            vertices.push(GpuParticleVertex {
+                state_texel,
+                corner,
+            });
+        }
+        let base = slot * 4;
+        triangles.push(TriangleDefinition([base, base + 1, base + 2]));
+        triangles.push(TriangleDefinition([base, base + 2, base + 3]));
+    }
+    SurfaceResource::new_ok(
+        ResourceKind::Embedded,
+        SurfaceData::new(
+            VertexBuffer::new(vertices.len(), vertices).unwrap(),
+            TriangleBuffer::new(triangles),
+        ),
+    )
+}
+
+/// Initial state of a particle that must be written to the state texture.
+#[derive(Clone, Debug)]
+pub(crate) struct GpuParticleSpawn {
+    /// Index of the simulation step before which the particle must be spawned.
+    pub step: usize,
+    /// Index of the particle in the state texture.
+    pub slot: u32,
+    /// The state of the particle, see module docs for the layout.
+    pub state: [Vector4<f32>; TEXELS_PER_PARTICLE as usize],
+}
+
+/// A set of commands for the renderer, that were recorded since the last frame.
+#[derive(Default, Clone, Debug)]
+pub(crate) struct GpuParticleCommands {
+    /// If set, the state texture must be cleared before executing other commands.
+    pub reset: bool,
+    /// Time steps of the simulation.
+    pub steps: Vec<f32>,
+    /// Particles to spawn, sorted by simulation step.
+    pub spawns: Vec<GpuParticleSpawn>,
+}
+
+/// Everything the renderer needs to update the state texture of a particle system.
+pub(crate) struct GpuParticleUpdate {
+    /// The state texture, see module docs for the layout.
+    pub texture: TextureResource,
+    /// Max amount of particles in the state texture.
+    pub capacity: u32,
+    /// Acceleration of the particles.
+    pub acceleration: Vector3<f32>,
+    /// Uniformly distributed samples of the color gradient of the particle system.
+    pub color_over_lifetime: [Vector4<f32>; COLOR_OVER_LIFETIME_SAMPLES],
+    /// The commands, that were recorded since the last update.
+    pub commands: GpuParticleCommands,
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct GpuParticleState {
+    capacity: u32,
+    time: f32,
+    free_slots: Vec<u32>,
+    // Min-heap of (expiration time, slot, emitter index). Time is never negative, so the order
+    // of its bit representation matches the order of the values.
+    alive: BinaryHeap<Reverse<(u32, u32, u32)>>,
+    texture: Option<TextureResource>,
+    surface: Option<SurfaceResource>,
+    commands: GpuParticleCommands,
+}
+
+impl GpuParticleState {
+    /// Re-creates the state if the capacity has changed. Returns `true` if all the particles were
+    /// lost.
+    pub fn set_capacity(&mut self, capacity: u32) -> bool {
+        if self.capacity == capacity && self.texture.is_some() {
+            return false;
+        }
+
+        let (width, height) = state_texture_size(capacity);
+        *self = Self {
+            capacity,
+            free_slots: (0..capacity).rev().collect(),
+            texture: Some(TextureResource::new_render_target(width, height)),
+            surface: Some(make_particles_surface(capacity)),
+            commands: GpuParticleCommands {
+                reset: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        true
+    }
+
+    /// Kills all the particles, `on_killed` is called with an emitter index of every alive
+    /// particle.
+    pub fn clear(&mut self, mut on_killed: impl FnMut(u32)) {
+        for Reverse((_, slot, emitter)) in self.alive.drain() {
+            self.free_slots.push(slot);
+            on_killed(emitter);
+        }
+        self.time = 0.0;
+        self.commands = GpuParticleCommands {
+            reset: true,
+            ..Default::default()
+        };
+    }
+
+    /// Starts a new simulation step and returns its index. `on_expired` is called with an
+    /// emitter index of every particle that has died.
+    pub fn begin_step(&mut self, dt: f32, mut on_expired: impl FnMut(u32)) -> usize {
+        if self.commands.steps.len() >= MAX_PENDING_STEPS {
+            self.clear(&mut on_expired);
+        }
+
+        self.time += dt;
+        while let Some(Reverse((expiration, slot, emitter))) = self.alive.peek().copied() {
+            if f32::from_bits(expiration) > self.time {
+                break;
+            }
+            self.alive.pop();
+            self.free_slots.push(slot);
+            on_expired(emitter);
+        }
+
+        self.commands.steps.push(dt);
+        self.commands.steps.len() - 1
+    }
+
+    /// Tries to find a free slot for the given particle. Returns `false` if there's no free space.
+    pub fn spawn(&mut self, step: usize, emitter_index: u32, particle: &Particle) -> bool {
+        let Some(slot) = self.free_slots.pop() else {
+            return false;
+        };
+
+        let expiration = (self.time + particle.initial_lifetime.max(0.0)).to_bits();
+        self.alive.push(Reverse((expiration, slot, emitter_index)));
+
+        self.commands.spawns.push(GpuParticleSpawn {
+            step,
+            slot,
+            state: [
+                particle.position.push(particle.lifetime),
+                particle.velocity.push(particle.initial_lifetime),
+                Vector4::new(
+                    particle.size,
+                    particle.size_modifier,
+                    particle.rotation,
+                    particle.rotation_speed,
+                ),
+                particle.color.as_frgba(),
+                particle.tint.as_frgba(),
+            ],
+        });
+
+        true
+    }
+
+    /// Returns max amount of particles.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    pub fn texture(&self) -> Option<&TextureResource> {
+        self.texture.as_ref()
+    }
+
+    pub fn surface(&self) -> Option<&SurfaceResource> {
+        self.surface.as_ref()
+    }
+
+    /// Takes the commands, that were recorded since the last call.
+    pub fn take_commands(&mut self) -> GpuParticleCommands {
+        std::mem::take(&mut self.commands)
+    }
+}
+
+/// A wrapper that allows the renderer to take the commands of a particle system. A copy of a
+/// particle system starts with a new state, because the state texture cannot be shared.
+#[derive(Default, Debug)]
+pub(crate) struct GpuParticleStateWrapper(pub Mutex<GpuParticleState>);
+
+impl Clone for GpuParticleStateWrapper {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scene::particle_system::{
+        gpu::{particle_texel, state_texture_size, GpuParticleState},
+        particle::Particle,
+    };
+
+    #[test]
+    fn test_slot_allocation() {
+        let mut state = GpuParticleState::default();
+        assert!(state.set_capacity(2));
+        assert!(!state.set_capacity(2));
+
+        let particle = Particle::default().with_initial_lifetime(1.0);
+        let step = state.begin_step(0.5, |_| unreachable!());
+        assert!(state.spawn(step, 0, &particle));
+        assert!(state.spawn(step, 1, &particle));
+        assert!(!state.spawn(step, 1, &particle));
+        assert_eq!(state.alive.len(), 2);
+
+        let mut expired = Vec::new();
+        state.begin_step(0.5, |emitter| expired.push(emitter));
+        assert!(expired.is_empty());
+        state.begin_step(0.5, |emitter| expired.push(emitter));
+        expired.sort();
+        assert_eq!(expired, [0, 1]);
+        assert_eq!(state.alive.len(), 0);
+
+        let commands = state.take_commands();
+        assert!(commands.reset);
+        assert_eq!(commands.steps.len(), 3);
+        assert_eq!(commands.spawns.len(), 2);
+        assert!(state.take_commands().steps.is_empty());
+    }
+
+    #[test]
+    fn test_state_texture_layout() {
+        assert_eq!(state_texture_size(1), (1280, 1));
+        assert_eq!(state_texture_size(257), (1280, 2));
+        assert_eq!(particle_texel(257).x, 5);
+        assert_eq!(particle_texel(257).y, 1);
+    }
+}
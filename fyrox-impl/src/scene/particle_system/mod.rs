@@ -26,7 +26,7 @@ use crate::scene::particle_system::emitter::base::BaseEmitterBuilder;
 use crate::scene::particle_system::emitter::sphere::SphereEmitterBuilder;
 use crate::{
     core::{
-        algebra::{Point3, Vector2, Vector3},
+        algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3, Vector4},
         color::Color,
         color_gradient::ColorGradient,
         math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
@@ -34,22 +34,39 @@ use crate::{
         reflect::prelude::*,
         type_traits::prelude::*,
         uuid::{uuid, Uuid},
-        value_as_u8_slice,
+        uuid_provider, value_as_u8_slice,
         variable::InheritableVariable,
         visitor::prelude::*,
     },
-    material::{self, Material, MaterialResource},
+    material::{
+        self,
+        shader::{ShaderResource, ShaderResourceExtension},
+        Material, MaterialResource, MaterialResourceBinding, MaterialResourceExtension,
+        MaterialTextureBinding,
+    },
     rand::{prelude::StdRng, Error, RngCore, SeedableRng},
-    renderer::{self, bundle::RenderContext},
+    renderer::{
+        self,
+        bundle::{RenderContext, SurfaceInstanceData},
+        framework::ElementRange,
+    },
     scene::{
         base::{Base, BaseBuilder},
-        graph::Graph,
+        debug::{Line, SceneDrawingContext},
+        graph::{
+            physics::{PhysicsWorld, RayCastOptions},
+            Graph,
+        },
         mesh::{buffer::VertexTrait, RenderPath},
         node::{Node, NodeTrait, RdcControlFlow, UpdateContext},
         particle_system::{
+            collision::{ParticleCollision, ParticleCollisionResponse},
             draw::Vertex,
             emitter::{Emit, Emitter},
+            force::ForceField,
+            gpu::{GpuParticleStateWrapper, GpuParticleUpdate, COLOR_OVER_LIFETIME_SAMPLES},
             particle::Particle,
+            sub_emitter::{ParticleEvent, SubEmitter, SubEmitterTrigger},
         },
     },
 };
@@ -62,9 +79,13 @@ use std::{
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+pub mod collision;
 pub(crate) mod draw;
 pub mod emitter;
+pub mod force;
+pub(crate) mod gpu;
 pub mod particle;
+pub mod sub_emitter;
 
 /// Pseudo-random numbers generator for particle systems.
 #[derive(Debug, Clone, Reflect)]
@@ -147,6 +168,13 @@ impl Visit for ParticleSystemRng {
 /// Particle system can contain multiple particle emitters, each emitter has its own
 /// set of properties and it defines law of change of particle parameters over time.
 ///
+/// # Forces, collisions and sub-emitters
+///
+/// Besides the global acceleration, particles could be affected by a set of force fields
+/// (see [`ForceField`]), they could collide with the colliders of the physics world (see
+/// [`ParticleCollision`]) and they could trigger other particle systems when they are born, die
+/// or collide with something (see [`SubEmitter`]).
+///
 /// # Performance
 ///
 /// In general particle system can be considered as heavy visual effect, but total impact
@@ -155,6 +183,9 @@ impl Visit for ParticleSystemRng {
 /// enough, alternatively amount of particles can be defined by some coefficient based on
 /// graphics quality settings.
 ///
+/// Particles could also be simulated on GPU (see [`ParticleSimulation`]), which allows to have
+/// hundreds of thousands of particles at the cost of some features.
+///
 /// # Example
 ///
 /// Simple smoke effect can be create like so:
@@ -261,7 +292,26 @@ pub struct ParticleSystem {
     )]
     coordinate_system: InheritableVariable<CoordinateSystem>,
 
+    /// A set of force fields that affect velocity of the particles. See [`ForceField`] docs for
+    /// more info.
+    pub force_fields: InheritableVariable<Vec<ForceField>>,
+
+    #[reflect(setter = "set_collision")]
+    collision: InheritableVariable<ParticleCollision>,
+
+    /// A set of sub-emitters of the particle system. See [`SubEmitter`] docs for more info.
+    pub sub_emitters: InheritableVariable<Vec<SubEmitter>>,
+
+    #[reflect(setter = "set_simulation")]
+    simulation: InheritableVariable<ParticleSimulation>,
+
     rng: ParticleSystemRng,
+
+    #[reflect(hidden)]
+    time: f32,
+
+    #[reflect(hidden)]
+    gpu_state: GpuParticleStateWrapper,
 }
 
 /// Coordinate system for particles generated by a particle system.
@@ -299,6 +349,62 @@ pub enum CoordinateSystem {
     World,
 }
 
+/// Multiplies two colors component-wise. White is an identity color, so the color stays exactly the
+/// same.
+fn modulate_color(color: Color, tint: Color) -> Color {
+    let modulate = |a: u8, b: u8| ((a as u16 * b as u16 + 127) / 255) as u8;
+    Color::from_rgba(
+        modulate(color.r, tint.r),
+        modulate(color.g, tint.g),
+        modulate(color.b, tint.b),
+        modulate(color.a, tint.a),
+    )
+}
+
+/// Defines where the particles of a particle system are simulated.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum ParticleSimulation {
+    /// Particles are simulated on CPU. This mode supports every feature of particle systems, but
+    /// it is suitable only for a few thousands of particles, because the particles are simulated
+    /// and uploaded to GPU every frame.
+    #[default]
+    Cpu,
+    /// Particles are spawned on CPU by the emitters of the particle system, but their motion is
+    /// simulated on GPU. It allows to have hundreds of thousands of particles, but has a few
+    /// limitations:
+    ///
+    /// - Force fields, collisions and sub-emitters are not supported.
+    /// - Particles are not sorted back-to-front, so the material should use an order-independent
+    /// blending (for example, additive).
+    /// - Particles are not accessible from CPU, [`ParticleSystem::particles`] returns an empty
+    /// slice.
+    ///
+    /// The standard particle system shader is automatically replaced with its GPU counterpart
+    /// (see [`ShaderResourceExtension::standard_gpu_particle_system`]), custom shaders must read
+    /// the state of particles from `particleState` texture (see the standard shader for reference).
+    Gpu {
+        /// Max amount of particles that could be simulated at once. Emitters won't spawn new
+        /// particles when this limit is reached.
+        #[reflect(min_value = 1.0)]
+        max_particles: u32,
+    },
+}
+
+uuid_provider!(ParticleSimulation = "7fabc3a8-c454-4442-9d80-51eee193c2db");
+
 impl Visit for ParticleSystem {
     fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
         let mut region = visitor.enter_region(name)?;
@@ -316,6 +422,10 @@ impl Visit for ParticleSystem {
         let _ = self
             .coordinate_system
             .visit("CoordinateSystem", &mut region);
+        let _ = self.force_fields.visit("ForceFields", &mut region);
+        let _ = self.collision.visit("Collision", &mut region);
+        let _ = self.sub_emitters.visit("SubEmitters", &mut region);
+        let _ = self.simulation.visit("Simulation", &mut region);
 
         // Backward compatibility.
         if region.is_reading() {
@@ -405,6 +515,7 @@ impl ParticleSystem {
     }
 
     /// Returns a reference to a slice to the current set of particles, generated by the particle system.
+    /// The slice is always empty if the particles are simulated on GPU.
     pub fn particles(&self) -> &[Particle] {
         &self.particles
     }
@@ -413,12 +524,29 @@ impl ParticleSystem {
     pub fn clear_particles(&mut self) {
         self.particles.clear();
         self.free_particles.clear();
+        self.time = 0.0;
+        self.gpu_state.0.lock().clear(|_| ());
         for emitter in self.emitters.get_value_mut_silent().iter_mut() {
             emitter.alive_particles = 0;
             emitter.spawned_particles = 0;
         }
     }
 
+    /// Sets where the particles of the particle system are simulated. See [`ParticleSimulation`]
+    /// docs for more info. Existing particles are lost when the mode is changed.
+    pub fn set_simulation(&mut self, simulation: ParticleSimulation) -> ParticleSimulation {
+        let old = self.simulation.set_value_and_mark_modified(simulation);
+        if old != simulation {
+            self.clear_particles();
+        }
+        old
+    }
+
+    /// Returns where the particles of the particle system are simulated.
+    pub fn simulation(&self) -> ParticleSimulation {
+        *self.simulation
+    }
+
     /// Sets the new material for the particle system.
     pub fn set_material(&mut self, material: MaterialResource) -> MaterialResource {
         self.material.set_value_and_mark_modified(material)
@@ -434,14 +562,111 @@ impl ParticleSystem {
         &self.material
     }
 
-    fn tick(&mut self, dt: f32) {
+    fn insert_particle(&mut self, particle: Particle) {
+        if let Some(free_index) = self.free_particles.pop() {
+            self.particles[free_index as usize] = particle;
+        } else {
+            self.particles.push(particle);
+        }
+    }
+
+    fn kill_particle(&mut self, index: usize) {
+        let particle = &mut self.particles[index];
+        if !particle.alive {
+            return;
+        }
+        if let Some(emitter) = self
+            .emitters
+            .get_value_mut_and_mark_modified()
+            .get_mut(particle.emitter_index as usize)
+        {
+            emitter.alive_particles = emitter.alive_particles.saturating_sub(1);
+        }
+        particle.alive = false;
+        particle.lifetime = particle.initial_lifetime;
+        self.free_particles.push(index as u32);
+    }
+
+    fn has_sub_emitters(&self, trigger: SubEmitterTrigger) -> bool {
+        self.sub_emitters.iter().any(|s| s.trigger == trigger)
+    }
+
+    fn tick_gpu(&mut self, dt: f32, max_particles: u32) {
+        let mut gpu_state = self.gpu_state.0.lock();
+        let emitters = self.emitters.get_value_mut_silent();
+
+        if gpu_state.set_capacity(max_particles) {
+            for emitter in emitters.iter_mut() {
+                emitter.alive_particles = 0;
+            }
+        }
+
+        for emitter in emitters.iter_mut() {
+            emitter.tick(dt);
+        }
+
+        let step = gpu_state.begin_step(dt, |emitter_index| {
+            if let Some(emitter) = emitters.get_mut(emitter_index as usize) {
+                emitter.alive_particles = emitter.alive_particles.saturating_sub(1);
+            }
+        });
+
+        let global_transform = self.base.global_transform();
+
+        for (i, emitter) in emitters.iter_mut().enumerate() {
+            for _ in 0..emitter.particles_to_spawn {
+                let mut particle = Particle {
+                    emitter_index: i as u32,
+                    ..Particle::default()
+                };
+                emitter.emit(&mut particle, &mut self.rng);
+                if *self.coordinate_system == CoordinateSystem::World {
+                    particle.position = global_transform
+                        .transform_point(&particle.position.into())
+                        .coords;
+                }
+                if !gpu_state.spawn(step, i as u32, &particle) {
+                    break;
+                }
+                emitter.alive_particles += 1;
+            }
+        }
+    }
+
+    fn tick(&mut self, dt: f32, physics: Option<&PhysicsWorld>, events: &mut Vec<ParticleEvent>) {
+        if let ParticleSimulation::Gpu { max_particles } = *self.simulation {
+            self.tick_gpu(dt, max_particles);
+            return;
+        }
+
         for emitter in self.emitters.get_value_mut_silent().iter_mut() {
             emitter.tick(dt);
         }
 
+        self.time += dt;
+
         let global_transform = self.global_transform();
+        let is_local = *self.coordinate_system == CoordinateSystem::Local;
+        let to_world = |particle: &Particle| {
+            if is_local {
+                (
+                    global_transform
+                        .transform_point(&Point3::from(particle.position))
+                        .coords,
+                    global_transform.transform_vector(&particle.velocity),
+                )
+            } else {
+                (particle.position, particle.velocity)
+            }
+        };
+
+        let birth_events = self.has_sub_emitters(SubEmitterTrigger::Birth);
+        let death_events = self.has_sub_emitters(SubEmitterTrigger::Death);
+        let collision_events = self.has_sub_emitters(SubEmitterTrigger::Collision);
 
-        for (i, emitter) in self.emitters.get_value_mut_silent().iter_mut().enumerate() {
+        for i in 0..self.emitters.len() {
+            let emitter = &mut self.emitters.get_value_mut_silent()[i];
+            let mut spawned = Vec::with_capacity(emitter.particles_to_spawn as usize);
             for _ in 0..emitter.particles_to_spawn {
                 let mut particle = Particle {
                     emitter_index: i as u32,
@@ -449,48 +674,235 @@ impl ParticleSystem {
                 };
                 emitter.alive_particles += 1;
                 emitter.emit(&mut particle, &mut self.rng);
-                if *self.coordinate_system == CoordinateSystem::World {
+                if !is_local {
                     particle.position = global_transform
                         .transform_point(&particle.position.into())
                         .coords;
                 }
-                if let Some(free_index) = self.free_particles.pop() {
-                    self.particles[free_index as usize] = particle;
-                } else {
-                    self.particles.push(particle);
+                spawned.push(particle);
+            }
+            for particle in spawned {
+                if birth_events {
+                    let (position, velocity) = to_world(&particle);
+                    events.push(ParticleEvent {
+                        trigger: SubEmitterTrigger::Birth,
+                        position,
+                        velocity,
+                    });
                 }
+                self.insert_particle(particle);
             }
         }
 
+        // Force fields are defined in local coordinates of the particle system, so they must be
+        // transformed to world space if the particles are in world space.
+        let force_fields = self
+            .force_fields
+            .iter()
+            .map(|force_field| {
+                if is_local {
+                    force_field.clone()
+                } else {
+                    force_field.transform(&global_transform)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let physics = physics.filter(|_| self.collision.enabled);
+        let mut rays = Vec::new();
+        let mut ray_particles = Vec::new();
+
         let acceleration_offset = self.acceleration.scale(dt * dt);
 
-        for (i, particle) in self.particles.iter_mut().enumerate() {
-            if particle.alive {
-                particle.lifetime += dt;
-                if particle.lifetime >= particle.initial_lifetime {
-                    self.free_particles.push(i as u32);
-                    if let Some(emitter) = self
-                        .emitters
-                        .get_value_mut_and_mark_modified()
-                        .get_mut(particle.emitter_index as usize)
-                    {
-                        emitter.alive_particles = emitter.alive_particles.saturating_sub(1);
+        for i in 0..self.particles.len() {
+            let particle = &mut self.particles[i];
+            if !particle.alive {
+                continue;
+            }
+
+            particle.lifetime += dt;
+            if particle.lifetime >= particle.initial_lifetime {
+                if death_events {
+                    let (position, velocity) = to_world(particle);
+                    events.push(ParticleEvent {
+                        trigger: SubEmitterTrigger::Death,
+                        position,
+                        velocity,
+                    });
+                }
+                self.kill_particle(i);
+            } else {
+                particle.velocity += acceleration_offset;
+                for force_field in force_fields.iter() {
+                    particle.velocity += force_field
+                        .acceleration(particle.position, self.time)
+                        .scale(dt * dt);
+                }
+                let (old_position, _) = to_world(particle);
+                particle.position += particle.velocity;
+                particle.size += particle.size_modifier * dt;
+                if particle.size < 0.0 {
+                    particle.size = 0.0;
+                }
+                particle.rotation += particle.rotation_speed * dt;
+
+                let k = particle.lifetime / particle.initial_lifetime;
+                particle.color =
+                    modulate_color(self.color_over_lifetime.get_color(k), particle.tint);
+
+                if physics.is_some() {
+                    let (new_position, _) = to_world(particle);
+                    let delta = new_position - old_position;
+                    let distance = delta.norm();
+                    if distance > f32::EPSILON {
+                        rays.push(RayCastOptions {
+                            ray_origin: Point3::from(old_position),
+                            ray_direction: delta,
+                            max_len: distance + particle.size * self.collision.radius_scale,
+                            groups: self.collision.groups,
+                            sort_results: false,
+                        });
+                        ray_particles.push(i);
                     }
-                    particle.alive = false;
-                    particle.lifetime = particle.initial_lifetime;
-                } else {
-                    particle.velocity += acceleration_offset;
-                    particle.position += particle.velocity;
-                    particle.size += particle.size_modifier * dt;
-                    if particle.size < 0.0 {
-                        particle.size = 0.0;
+                }
+            }
+        }
+
+        let Some(physics) = physics else {
+            return;
+        };
+
+        if rays.is_empty() {
+            return;
+        }
+
+        let mut intersections = Vec::with_capacity(rays.len());
+        physics.cast_rays_closest(&rays, &mut intersections);
+
+        let inv_global_transform = global_transform
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
+
+        for (index, intersection) in ray_particles.into_iter().zip(intersections) {
+            // A ray that starts inside a collider hits it immediately, skip such hits to let the
+            // particle escape from it.
+            let Some(intersection) = intersection.filter(|i| i.toi > f32::EPSILON) else {
+                continue;
+            };
+
+            let (_, velocity) = to_world(&self.particles[index]);
+
+            if collision_events {
+                events.push(ParticleEvent {
+                    trigger: SubEmitterTrigger::Collision,
+                    position: intersection.position.coords,
+                    velocity,
+                });
+            }
+
+            match self.collision.response {
+                ParticleCollisionResponse::Bounce => {
+                    let normal = intersection.normal;
+                    let mut velocity = velocity;
+                    let normal_speed = velocity.dot(&normal);
+                    if normal_speed < 0.0 {
+                        let normal_velocity = normal.scale(normal_speed);
+                        let tangential_velocity = velocity - normal_velocity;
+                        velocity = tangential_velocity.scale(1.0 - self.collision.friction)
+                            - normal_velocity.scale(self.collision.restitution);
                     }
-                    particle.rotation += particle.rotation_speed * dt;
 
-                    let k = particle.lifetime / particle.initial_lifetime;
-                    particle.color = self.color_over_lifetime.get_color(k);
+                    let particle = &mut self.particles[index];
+                    let position = intersection.position.coords
+                        + normal.scale(particle.size * self.collision.radius_scale);
+                    if is_local {
+                        particle.position = inv_global_transform
+                            .transform_point(&Point3::from(position))
+                            .coords;
+                        particle.velocity = inv_global_transform.transform_vector(&velocity);
+                    } else {
+                        particle.position = position;
+                        particle.velocity = velocity;
+                    }
                 }
+                ParticleCollisionResponse::Kill => {
+                    if death_events {
+                        events.push(ParticleEvent {
+                            trigger: SubEmitterTrigger::Death,
+                            position: intersection.position.coords,
+                            velocity,
+                        });
+                    }
+                    self.kill_particle(index);
+                }
+            }
+        }
+    }
+
+    /// Spawns `count` particles using an emitter with the given index at the given position (in
+    /// world coordinates). The particles are initialized by the emitter as usual, but they are
+    /// moved so the emitter is located at the given position. `velocity` (in world coordinates) is
+    /// added to the velocity of every spawned particle. This method is used by sub-emitters, but
+    /// it could also be used to spawn particles on demand (for example, to create impact effects).
+    /// The limit of particles of the emitter is respected. This method does nothing if the particles
+    /// are simulated on GPU.
+    pub fn emit_at(
+        &mut self,
+        emitter_index: usize,
+        count: u32,
+        position: Vector3<f32>,
+        velocity: Vector3<f32>,
+    ) {
+        if let ParticleSimulation::Gpu { .. } = *self.simulation {
+            return;
+        }
+
+        let global_transform = self.global_transform();
+        let is_local = *self.coordinate_system == CoordinateSystem::Local;
+        let (position, velocity) = if is_local {
+            let inv_global_transform = global_transform
+                .try_inverse()
+                .unwrap_or_else(Matrix4::identity);
+            (
+                inv_global_transform
+                    .transform_point(&Point3::from(position))
+                    .coords,
+                inv_global_transform.transform_vector(&velocity),
+            )
+        } else {
+            (position, velocity)
+        };
+
+        let Some(emitter) = self.emitters.get_value_mut_silent().get_mut(emitter_index) else {
+            return;
+        };
+
+        let mut spawned = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            if emitter
+                .max_particles()
+                .is_some_and(|max| emitter.alive_particles >= max)
+            {
+                break;
             }
+            let mut particle = Particle {
+                emitter_index: emitter_index as u32,
+                ..Particle::default()
+            };
+            emitter.alive_particles += 1;
+            emitter.emit(&mut particle, &mut self.rng);
+            let offset = particle.position - emitter.position();
+            particle.position = if is_local {
+                position + offset
+            } else {
+                position + global_transform.transform_vector(&offset)
+            };
+            particle.velocity += velocity;
+            spawned.push(particle);
+        }
+
+        for particle in spawned {
+            self.insert_particle(particle);
         }
     }
 
@@ -503,7 +915,7 @@ impl ParticleSystem {
 
         let mut t = 0.0;
         while t < time {
-            self.tick(dt);
+            self.tick(dt, None, &mut Vec::new());
             t += dt;
         }
     }
@@ -534,6 +946,88 @@ impl ParticleSystem {
         *self.coordinate_system
     }
 
+    /// Sets new collision settings of the particle system. See [`ParticleCollision`] docs for more
+    /// info.
+    pub fn set_collision(&mut self, collision: ParticleCollision) -> ParticleCollision {
+        self.collision.set_value_and_mark_modified(collision)
+    }
+
+    /// Returns current collision settings of the particle system.
+    pub fn collision(&self) -> &ParticleCollision {
+        &self.collision
+    }
+
+    fn collect_gpu_render_data(&self, ctx: &mut RenderContext, particle_alpha_factor: f32) {
+        let gpu_state = self.gpu_state.0.lock();
+        let (Some(texture), Some(surface)) = (gpu_state.texture(), gpu_state.surface()) else {
+            return;
+        };
+
+        // The state of the particles is bound to a copy of the material, the same way as blend
+        // shapes of meshes.
+        let material =
+            if self.material.data_ref().shader() == &ShaderResource::standard_particle_system() {
+                let mut gpu_material = Material::standard_gpu_particle_system();
+                for (name, binding) in self.material.data_ref().bindings() {
+                    gpu_material.bind(name.clone(), binding.clone());
+                }
+                MaterialResource::new(gpu_material)
+            } else {
+                self.material.deep_copy()
+            };
+        let mut material_ref = material.data_ref();
+        material_ref.bind(
+            "particleState",
+            MaterialResourceBinding::Texture(MaterialTextureBinding {
+                value: Some(texture.clone()),
+            }),
+        );
+        material_ref.set_property("alphaFactor", particle_alpha_factor);
+        drop(material_ref);
+
+        let world_transform = if *self.coordinate_system == CoordinateSystem::Local {
+            self.global_transform()
+        } else {
+            Matrix4::identity()
+        };
+
+        ctx.storage.push(
+            surface,
+            &material,
+            RenderPath::Forward,
+            ctx.calculate_sorting_index(self.global_position()),
+            SurfaceInstanceData {
+                world_transform,
+                bone_matrices: Default::default(),
+                blend_shapes_weights: Default::default(),
+                element_range: ElementRange::Full,
+                node_handle: self.handle(),
+            },
+        );
+    }
+
+    /// Takes the commands for GPU simulation, that were recorded since the last call, along with
+    /// the parameters of the simulation.
+    pub(crate) fn take_gpu_update(&self) -> Option<GpuParticleUpdate> {
+        let ParticleSimulation::Gpu { .. } = *self.simulation else {
+            return None;
+        };
+
+        let mut gpu_state = self.gpu_state.0.lock();
+        let texture = gpu_state.texture()?.clone();
+        Some(GpuParticleUpdate {
+            texture,
+            capacity: gpu_state.capacity(),
+            acceleration: *self.acceleration,
+            color_over_lifetime: std::array::from_fn(|i| {
+                let k = i as f32 / (COLOR_OVER_LIFETIME_SAMPLES - 1) as f32;
+                let color = self.color_over_lifetime.get_color(k).as_frgba();
+                Vector4::new(color.x, color.y, color.z, color.w)
+            }),
+            commands: gpu_state.take_commands(),
+        })
+    }
+
     fn is_distance_clipped(&self, point: &Vector3<f32>) -> bool {
         point.metric_distance(&self.global_position())
             > (*self.visible_distance + Self::FADEOUT_MARGIN)
@@ -581,7 +1075,83 @@ impl NodeTrait for ParticleSystem {
         let dt = context.dt;
 
         if *self.is_playing {
-            self.tick(dt);
+            let inv_global_transform = self
+                .global_transform()
+                .try_inverse()
+                .unwrap_or_else(Matrix4::identity);
+            for emitter in self.emitters.get_value_mut_silent().iter_mut() {
+                if let Emitter::Mesh(mesh_emitter) = emitter {
+                    mesh_emitter.update(context.nodes, &inv_global_transform);
+                }
+            }
+
+            let mut events = Vec::new();
+            self.tick(dt, Some(context.physics), &mut events);
+
+            for event in events {
+                for sub_emitter in self.sub_emitters.iter() {
+                    if sub_emitter.trigger != event.trigger {
+                        continue;
+                    }
+
+                    if let Some(target) = context
+                        .nodes
+                        .try_borrow_mut(sub_emitter.particle_system)
+                        .and_then(|node| node.cast_mut::<ParticleSystem>())
+                    {
+                        target.emit_at(
+                            sub_emitter.emitter as usize,
+                            sub_emitter.count,
+                            event.position,
+                            event.velocity.scale(sub_emitter.inherit_velocity),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let global_transform = self.global_transform();
+
+        for force_field in self.force_fields.iter() {
+            match force_field.transform(&global_transform) {
+                ForceField::Attractor {
+                    position, radius, ..
+                } => {
+                    ctx.draw_wire_sphere(position, radius, 16, Color::ORANGE);
+                }
+                ForceField::Vortex {
+                    position,
+                    axis,
+                    radius,
+                    ..
+                } => {
+                    let Some(axis) = axis.try_normalize(f32::EPSILON) else {
+                        continue;
+                    };
+                    let rotation = UnitQuaternion::rotation_between(&Vector3::z(), &axis)
+                        .unwrap_or_else(|| {
+                            UnitQuaternion::from_axis_angle(
+                                &Vector3::x_axis(),
+                                std::f32::consts::PI,
+                            )
+                        });
+                    ctx.draw_circle(
+                        Default::default(),
+                        radius,
+                        16,
+                        Matrix4::new_translation(&position) * rotation.to_homogeneous(),
+                        Color::ORANGE,
+                    );
+                    ctx.add_line(Line {
+                        begin: position,
+                        end: position + axis,
+                        color: Color::ORANGE,
+                    });
+                }
+                ForceField::Turbulence { .. } => (),
+            }
         }
     }
 
@@ -607,6 +1177,11 @@ impl NodeTrait for ParticleSystem {
             1.0
         };
 
+        if let ParticleSimulation::Gpu { .. } = *self.simulation {
+            self.collect_gpu_render_data(ctx, particle_alpha_factor);
+            return RdcControlFlow::Continue;
+        }
+
         let mut sorted_particles = Vec::new();
         for (i, particle) in self.particles.iter().enumerate() {
             if particle.alive {
@@ -733,6 +1308,10 @@ pub struct ParticleSystemBuilder {
     rng: ParticleSystemRng,
     visible_distance: f32,
     coordinate_system: CoordinateSystem,
+    force_fields: Vec<ForceField>,
+    collision: ParticleCollision,
+    sub_emitters: Vec<SubEmitter>,
+    simulation: ParticleSimulation,
 }
 
 impl ParticleSystemBuilder {
@@ -752,6 +1331,10 @@ impl ParticleSystemBuilder {
             rng: ParticleSystemRng::default(),
             visible_distance: 30.0,
             coordinate_system: Default::default(),
+            force_fields: Default::default(),
+            collision: Default::default(),
+            sub_emitters: Default::default(),
+            simulation: Default::default(),
         }
     }
 
@@ -811,6 +1394,30 @@ impl ParticleSystemBuilder {
         self
     }
 
+    /// Sets the desired force fields of the particle system.
+    pub fn with_force_fields(mut self, force_fields: Vec<ForceField>) -> Self {
+        self.force_fields = force_fields;
+        self
+    }
+
+    /// Sets the desired collision settings of the particle system.
+    pub fn with_collision(mut self, collision: ParticleCollision) -> Self {
+        self.collision = collision;
+        self
+    }
+
+    /// Sets the desired sub-emitters of the particle system.
+    pub fn with_sub_emitters(mut self, sub_emitters: Vec<SubEmitter>) -> Self {
+        self.sub_emitters = sub_emitters;
+        self
+    }
+
+    /// Sets where the particles of the particle system are simulated.
+    pub fn with_simulation(mut self, simulation: ParticleSimulation) -> Self {
+        self.simulation = simulation;
+        self
+    }
+
     fn build_particle_system(self) -> ParticleSystem {
         ParticleSystem {
             base: self.base_builder.build_base(),
@@ -824,6 +1431,12 @@ impl ParticleSystemBuilder {
             rng: self.rng,
             visible_distance: self.visible_distance.into(),
             coordinate_system: self.coordinate_system.into(),
+            force_fields: self.force_fields.into(),
+            collision: self.collision.into(),
+            sub_emitters: self.sub_emitters.into(),
+            simulation: self.simulation.into(),
+            time: 0.0,
+            gpu_state: Default::default(),
         }
     }
 
@@ -837,3 +1450,204 @@ impl ParticleSystemBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                MeshBuilder,
+            },
+            particle_system::{
+                collision::{ParticleCollision, ParticleCollisionResponse},
+                emitter::{
+                    base::BaseEmitterBuilder, mesh::MeshEmitterBuilder,
+                    sphere::SphereEmitterBuilder,
+                },
+                particle::Particle,
+                sub_emitter::{SubEmitter, SubEmitterTrigger},
+                ParticleSimulation, ParticleSystem, ParticleSystemBuilder,
+            },
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_mesh_emitter_spawns_on_surface() {
+        let mut graph = Graph::new();
+        let mesh = MeshBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(10.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+            ResourceKind::Embedded,
+            SurfaceData::make_unit_xy_quad(),
+        ))
+        .build()])
+        .build(&mut graph);
+        let handle = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_acceleration(Vector3::default())
+            .with_emitters(vec![MeshEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(100)
+                    .with_x_velocity_range(0.0..0.0)
+                    .with_y_velocity_range(0.0..0.0)
+                    .with_z_velocity_range(0.0..0.0),
+            )
+            .with_mesh(mesh)
+            .with_normal_velocity(0.5)
+            .build()])
+            .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 0.1, Default::default());
+
+        let particles = graph[handle].cast::<ParticleSystem>().unwrap().particles();
+        assert_eq!(particles.len(), 10);
+        for particle in particles {
+            assert!((10.0..=11.0).contains(&particle.position.x));
+            assert!((0.0..=1.0).contains(&particle.position.y));
+            assert!((particle.velocity.z - 0.5).abs() < 1.0e-5);
+            assert!((particle.position.z - 0.5).abs() < 1.0e-5);
+        }
+    }
+
+    fn create_ground(graph: &mut Graph) {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(5.0, 0.5, 5.0))
+            .build(graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_body_type(RigidBodyType::Static)
+            .build(graph);
+    }
+
+    fn falling_particle() -> Particle {
+        Particle::default()
+            .with_position(Vector3::new(0.0, 1.05, 0.0))
+            .with_velocity(Vector3::new(0.0, -0.2, 0.0))
+            .with_size(0.2)
+            .with_initial_lifetime(10.0)
+    }
+
+    #[test]
+    fn test_particle_bounce() {
+        let mut graph = Graph::new();
+        create_ground(&mut graph);
+        let particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_acceleration(Vector3::default())
+            .with_particles(vec![falling_particle()])
+            .with_collision(ParticleCollision {
+                enabled: true,
+                restitution: 1.0,
+                ..Default::default()
+            })
+            .build(&mut graph);
+
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+            let particle = &graph[particle_system]
+                .cast::<ParticleSystem>()
+                .unwrap()
+                .particles()[0];
+            assert!(particle.alive);
+            assert!(particle.position.y >= 0.5);
+        }
+
+        let particle = &graph[particle_system]
+            .cast::<ParticleSystem>()
+            .unwrap()
+            .particles()[0];
+        assert!(particle.velocity.y > 0.0);
+    }
+
+    #[test]
+    fn test_particle_kill_triggers_sub_emitter() {
+        let mut graph = Graph::new();
+        create_ground(&mut graph);
+        let target = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_playing(false)
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new().with_spawn_rate(0),
+            )
+            .with_radius(0.0)
+            .build()])
+            .build(&mut graph);
+        let source = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_acceleration(Vector3::default())
+            .with_particles(vec![falling_particle()])
+            .with_collision(ParticleCollision {
+                enabled: true,
+                response: ParticleCollisionResponse::Kill,
+                ..Default::default()
+            })
+            .with_sub_emitters(vec![SubEmitter {
+                trigger: SubEmitterTrigger::Collision,
+                particle_system: target,
+                count: 3,
+                ..Default::default()
+            }])
+            .build(&mut graph);
+
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        assert!(!graph[source].cast::<ParticleSystem>().unwrap().particles()[0].alive);
+        let target = graph[target].cast::<ParticleSystem>().unwrap();
+        assert_eq!(target.particles().iter().filter(|p| p.alive).count(), 3);
+        for particle in target.particles() {
+            assert!((particle.position.y - 0.5).abs() < 1.0e-3);
+        }
+    }
+
+    #[test]
+    fn test_gpu_simulation_respects_capacity() {
+        let mut graph = Graph::new();
+        let handle = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_simulation(ParticleSimulation::Gpu { max_particles: 16 })
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(100)
+                    .with_lifetime_range(0.25..0.26),
+            )
+            .build()])
+            .build(&mut graph);
+
+        let alive_particles = |graph: &Graph| {
+            graph[handle].cast::<ParticleSystem>().unwrap().emitters[0].alive_particles
+        };
+
+        graph.update(Vector2::new(800.0, 600.0), 0.1, Default::default());
+        assert_eq!(alive_particles(&graph), 10);
+        graph.update(Vector2::new(800.0, 600.0), 0.1, Default::default());
+        assert_eq!(alive_particles(&graph), 16);
+        // The oldest particles die, and their slots are reused.
+        graph.update(Vector2::new(800.0, 600.0), 0.1, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 0.1, Default::default());
+        assert_eq!(alive_particles(&graph), 16);
+
+        let particle_system = graph[handle].cast_mut::<ParticleSystem>().unwrap();
+        assert!(particle_system.particles().is_empty());
+        let update = particle_system.take_gpu_update().unwrap();
+        assert_eq!(update.capacity, 16);
+        assert_eq!(update.commands.steps.len(), 4);
+        assert!(particle_system
+            .take_gpu_update()
+            .unwrap()
+            .commands
+            .steps
+            .is_empty());
+
+        particle_system.clear_particles();
+        assert_eq!(alive_particles(&graph), 0);
+    }
+}
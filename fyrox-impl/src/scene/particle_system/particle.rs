@@ -45,6 +45,10 @@ pub struct Particle {
     pub rotation: f32,
     /// Color of particle.
     pub color: Color,
+    /// A color, that is multiplied with the color of the particle system gradient. It allows
+    /// emitters to set individual colors of particles (for example, colors of vertices of a mesh).
+    #[visit(optional)]
+    pub tint: Color,
 
     pub(super) alive: bool,
     pub(super) emitter_index: u32,
@@ -69,6 +73,7 @@ impl Default for Particle {
             rotation: 0.0,
             emitter_index: 0,
             color: Color::WHITE,
+            tint: Color::WHITE,
             sqr_distance_to_camera: Cell::new(0.0),
         }
     }
@@ -122,4 +127,10 @@ impl Particle {
         self.color = color;
         self
     }
+
+    /// Sets new tint in builder manner.
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
 }
@@ -0,0 +1,106 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sub-emitters spawn particles in other particle systems when some event happens with particles
+//! of a particle system. See [`SubEmitter`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::Vector3, pool::Handle, reflect::prelude::*, type_traits::prelude::*,
+        uuid_provider, visitor::prelude::*,
+    },
+    scene::node::Node,
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// An event of a particle that triggers a sub-emitter.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "75ba8da2-4428-4fc8-881c-5f66de2d1240")]
+pub enum SubEmitterTrigger {
+    /// A particle was just spawned.
+    Birth,
+    /// A particle has died, either because its lifetime has ended or because it was killed by a
+    /// collision.
+    #[default]
+    Death,
+    /// A particle has collided with something.
+    Collision,
+}
+
+/// Sub-emitter spawns particles in another particle system when a particle of its particle system
+/// is born, dies or collides with something. It could be used to create fireworks (death),
+/// sparks on impacts (collision), smoke trails (birth), etc.
+///
+/// Spawned particles are initialized by the specified emitter of the target particle system, but
+/// they are placed at the position of the particle that has triggered the sub-emitter. The target
+/// particle system must be a different node; sub-emitters of the target system are not triggered
+/// by the birth of such particles.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct SubEmitter {
+    /// An event that triggers the sub-emitter.
+    pub trigger: SubEmitterTrigger,
+    /// A handle of the particle system that will receive spawned particles.
+    pub particle_system: Handle<Node>,
+    /// Index of an emitter of the target particle system that is used to initialize spawned
+    /// particles.
+    pub emitter: u32,
+    /// Amount of particles spawned per event.
+    pub count: u32,
+    /// Fraction of velocity of the source particle that is added to the velocity of spawned
+    /// particles.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub inherit_velocity: f32,
+}
+
+uuid_provider!(SubEmitter = "03dd0aa6-9c53-4c42-8ab9-ed4a4ef34e7a");
+
+impl Default for SubEmitter {
+    fn default() -> Self {
+        Self {
+            trigger: Default::default(),
+            particle_system: Default::default(),
+            emitter: 0,
+            count: 5,
+            inherit_velocity: 0.0,
+        }
+    }
+}
+
+/// An event that has happened with a particle, all the values are in world coordinates.
+#[derive(Copy, Clone, Debug)]
+pub(super) struct ParticleEvent {
+    pub trigger: SubEmitterTrigger,
+    pub position: Vector3<f32>,
+    pub velocity: Vector3<f32>,
+}
@@ -0,0 +1,577 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reflection probe is a source of specular reflections for a part of a scene. For more info see
+//! [`ReflectionProbe`].
+
+use crate::{
+    core::{
+        algebra::Vector3,
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    resource::texture::TextureResource,
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::SceneDrawingContext,
+        graph::Graph,
+        node::{constructor::NodeConstructor, Node, NodeTrait},
+    },
+};
+use fyrox_graph::{constructor::ConstructorProvider, BaseSceneGraph};
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Shape of the influence volume of a reflection probe. Influence volume defines a part of the
+/// scene that will use the probe as a source of specular reflections.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect, AsRefStr, EnumString, VariantNames)]
+pub enum ReflectionProbeShape {
+    /// Oriented box with the given half extents. Box projection works best with this shape,
+    /// because rooms are usually box-shaped.
+    Box {
+        /// Half extents of the box along each local axis of the probe.
+        half_extents: Vector3<f32>,
+    },
+    /// Sphere with the given radius.
+    Sphere {
+        /// Radius of the sphere.
+        radius: f32,
+    },
+}
+
+uuid_provider!(ReflectionProbeShape = "6a4e4cc3-31bb-4f6c-b0a3-2b3a5e0b7a61");
+
+impl Default for ReflectionProbeShape {
+    fn default() -> Self {
+        Self::Box {
+            half_extents: Vector3::repeat(5.0),
+        }
+    }
+}
+
+impl ReflectionProbeShape {
+    /// Returns local-space bounding box of the shape.
+    pub fn bounding_box(&self) -> AxisAlignedBoundingBox {
+        match self {
+            ReflectionProbeShape::Box { half_extents } => {
+                AxisAlignedBoundingBox::from_min_max(-*half_extents, *half_extents)
+            }
+            ReflectionProbeShape::Sphere { radius } => AxisAlignedBoundingBox::from_radius(*radius),
+        }
+    }
+}
+
+/// Defines when a reflection probe captures its surroundings.
+#[derive(
+    Copy, Clone, Default, Debug, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum ReflectionProbeUpdateMode {
+    /// The probe captures the scene once it is rendered for the first time and then only when
+    /// a capture was requested via [`ReflectionProbe::request_capture`].
+    #[default]
+    OnDemand,
+    /// The probe captures the scene every frame. This is very expensive, since the scene is
+    /// rendered six times more for every such probe.
+    EachFrame,
+}
+
+uuid_provider!(ReflectionProbeUpdateMode = "d5a1f0b3-4c7e-4b2a-9e36-7f0f2b8c1d45");
+
+/// Reflection probe is a scene node that captures its surroundings into a cube map, which is then
+/// used by the renderer as a source of specular reflections (image-based lighting) for every pixel
+/// inside the influence volume of the probe. Without probes, the only source of reflections is
+/// the environment map (or the skybox) of a camera, which makes indoor scenes look flat, because
+/// they reflect the sky.
+///
+/// # Influence volume
+///
+/// Every probe has an influence volume (see [`ReflectionProbeShape`]), which is defined in the
+/// local coordinates of the probe (scaling is ignored). Reflections of a probe smoothly fade out
+/// near the edges of the volume, the width of the transition zone is defined by the blend
+/// distance. Pixels that are inside of multiple probes use a weighted mix of all these probes,
+/// the rest is filled with the camera's environment.
+///
+/// # Box projection
+///
+/// Cube maps are infinitely far by their nature, which is fine for distant environment, but
+/// looks wrong in a room, because reflections "slide" over surfaces when the camera moves. Box
+/// projection (parallax correction) fixes this by intersecting a reflection ray with the
+/// influence volume and sampling the cube map in the direction of the intersection point.
+///
+/// # Capturing and baking
+///
+/// The renderer captures the scene from the position of the probe in six directions. This happens
+/// on demand by default (once and then on every [`ReflectionProbe::request_capture`] call), but
+/// could be done every frame (see [`ReflectionProbeUpdateMode`]). Captured cube maps live on GPU
+/// only; to keep them with the scene, the probe could be baked into a cube map texture (see
+/// `Renderer::bake_reflection_probe`), which is then assigned via
+/// [`ReflectionProbe::set_baked_cube_map`]. Baked probes are never captured by the renderer.
+///
+/// # Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         graph::Graph,
+/// #         node::Node,
+/// #         reflection_probe::{ReflectionProbeBuilder, ReflectionProbeShape},
+/// #     },
+/// # };
+/// fn create_room_probe(graph: &mut Graph) -> Handle<Node> {
+///     ReflectionProbeBuilder::new(BaseBuilder::new())
+///         .with_shape(ReflectionProbeShape::Box {
+///             half_extents: Vector3::new(4.0, 2.0, 6.0),
+///         })
+///         .with_box_projection(true)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Visit, Clone, Reflect, ComponentProvider)]
+pub struct ReflectionProbe {
+    base: Base,
+
+    #[reflect(setter = "set_shape")]
+    shape: InheritableVariable<ReflectionProbeShape>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_blend_distance")]
+    blend_distance: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_box_projection")]
+    box_projection: InheritableVariable<bool>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_intensity")]
+    intensity: InheritableVariable<f32>,
+
+    #[reflect(min_value = 16.0, max_value = 2048.0)]
+    #[reflect(setter = "set_resolution")]
+    resolution: InheritableVariable<u32>,
+
+    #[reflect(min_value = 0.0, step = 0.01)]
+    #[reflect(setter = "set_z_near")]
+    z_near: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 1.0)]
+    #[reflect(setter = "set_z_far")]
+    z_far: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_update_mode")]
+    update_mode: InheritableVariable<ReflectionProbeUpdateMode>,
+
+    #[reflect(setter = "set_baked_cube_map")]
+    baked_cube_map: InheritableVariable<Option<TextureResource>>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    capture_requested: Cell<bool>,
+}
+
+impl Deref for ReflectionProbe {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for ReflectionProbe {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Default for ReflectionProbe {
+    fn default() -> Self {
+        ReflectionProbeBuilder::new(BaseBuilder::new()).build_reflection_probe()
+    }
+}
+
+impl TypeUuidProvider for ReflectionProbe {
+    fn type_uuid() -> Uuid {
+        uuid!("7e1c9f9e-2a8d-4a4e-8f0a-43c1c5b1e6a2")
+    }
+}
+
+impl ReflectionProbe {
+    /// Sets new shape of the influence volume of the probe.
+    pub fn set_shape(&mut self, shape: ReflectionProbeShape) -> ReflectionProbeShape {
+        self.shape.set_value_and_mark_modified(shape)
+    }
+
+    /// Returns current shape of the influence volume of the probe.
+    pub fn shape(&self) -> &ReflectionProbeShape {
+        &self.shape
+    }
+
+    /// Sets the width of the zone near the edges of the influence volume, in which reflections
+    /// of the probe smoothly fade out.
+    pub fn set_blend_distance(&mut self, distance: f32) -> f32 {
+        self.blend_distance
+            .set_value_and_mark_modified(distance.max(0.0))
+    }
+
+    /// Returns the width of the fade out zone near the edges of the influence volume.
+    pub fn blend_distance(&self) -> f32 {
+        *self.blend_distance
+    }
+
+    /// Enables or disables box projection (parallax correction) of reflections.
+    pub fn set_box_projection(&mut self, enabled: bool) -> bool {
+        self.box_projection.set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns `true` if box projection is enabled, `false` - otherwise.
+    pub fn is_box_projection_enabled(&self) -> bool {
+        *self.box_projection
+    }
+
+    /// Sets new intensity of the reflections of the probe.
+    pub fn set_intensity(&mut self, intensity: f32) -> f32 {
+        self.intensity
+            .set_value_and_mark_modified(intensity.max(0.0))
+    }
+
+    /// Returns current intensity of the reflections of the probe.
+    pub fn intensity(&self) -> f32 {
+        *self.intensity
+    }
+
+    /// Sets new size of a face of the cube map. The value is clamped to `[16; 2048]` range.
+    /// Changing the resolution forces the probe to capture the scene again.
+    pub fn set_resolution(&mut self, resolution: u32) -> u32 {
+        self.request_capture();
+        self.resolution
+            .set_value_and_mark_modified(resolution.clamp(16, 2048))
+    }
+
+    /// Returns current size of a face of the cube map.
+    pub fn resolution(&self) -> u32 {
+        *self.resolution
+    }
+
+    /// Sets new near clipping plane distance that is used when capturing the scene.
+    pub fn set_z_near(&mut self, z_near: f32) -> f32 {
+        self.request_capture();
+        self.z_near.set_value_and_mark_modified(z_near)
+    }
+
+    /// Returns near clipping plane distance that is used when capturing the scene.
+    pub fn z_near(&self) -> f32 {
+        *self.z_near
+    }
+
+    /// Sets new far clipping plane distance that is used when capturing the scene.
+    pub fn set_z_far(&mut self, z_far: f32) -> f32 {
+        self.request_capture();
+        self.z_far.set_value_and_mark_modified(z_far)
+    }
+
+    /// Returns far clipping plane distance that is used when capturing the scene.
+    pub fn z_far(&self) -> f32 {
+        *self.z_far
+    }
+
+    /// Sets new update mode of the probe. See [`ReflectionProbeUpdateMode`] docs for more info.
+    pub fn set_update_mode(
+        &mut self,
+        mode: ReflectionProbeUpdateMode,
+    ) -> ReflectionProbeUpdateMode {
+        self.update_mode.set_value_and_mark_modified(mode)
+    }
+
+    /// Returns current update mode of the probe.
+    pub fn update_mode(&self) -> ReflectionProbeUpdateMode {
+        *self.update_mode
+    }
+
+    /// Sets a baked cube map texture of the probe. When it is set, the renderer uses it instead of
+    /// capturing the scene. Set it to [`None`] to capture the scene again.
+    pub fn set_baked_cube_map(
+        &mut self,
+        cube_map: Option<TextureResource>,
+    ) -> Option<TextureResource> {
+        self.request_capture();
+        self.baked_cube_map.set_value_and_mark_modified(cube_map)
+    }
+
+    /// Returns a reference to the baked cube map texture of the probe (if any).
+    pub fn baked_cube_map(&self) -> Option<&TextureResource> {
+        self.baked_cube_map.as_ref()
+    }
+
+    /// Asks the renderer to capture the scene from the position of the probe on the next frame.
+    /// Has no effect, if the probe has a baked cube map.
+    pub fn request_capture(&self) {
+        self.capture_requested.set(true);
+    }
+
+    /// Returns `true` if the renderer should capture the scene for this probe, `false` -
+    /// otherwise. The flag is reset by the renderer after the capture.
+    pub fn needs_capture(&self) -> bool {
+        self.baked_cube_map.is_none()
+            && (self.capture_requested.get()
+                || *self.update_mode == ReflectionProbeUpdateMode::EachFrame)
+    }
+
+    pub(crate) fn reset_capture_request(&self) {
+        self.capture_requested.set(false);
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for ReflectionProbe {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Reflection Probe", |_| {
+                ReflectionProbeBuilder::new(BaseBuilder::new().with_name("ReflectionProbe"))
+                    .build_node()
+                    .into()
+            })
+            .with_group("Light")
+    }
+}
+
+impl NodeTrait for ReflectionProbe {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.shape.bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        // Discard scaling part, the influence volume is not affected by scaling.
+        self.local_bounding_box()
+            .transform(&self.global_transform_without_scaling())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        match *self.shape {
+            ReflectionProbeShape::Box { .. } => ctx.draw_oob(
+                &self.local_bounding_box(),
+                self.global_transform_without_scaling(),
+                Color::opaque(0, 200, 255),
+            ),
+            ReflectionProbeShape::Sphere { radius } => ctx.draw_wire_sphere(
+                self.global_position(),
+                radius,
+                30,
+                Color::opaque(0, 200, 255),
+            ),
+        }
+    }
+}
+
+/// Allows you to create a reflection probe in a declarative manner.
+pub struct ReflectionProbeBuilder {
+    base_builder: BaseBuilder,
+    shape: ReflectionProbeShape,
+    blend_distance: f32,
+    box_projection: bool,
+    intensity: f32,
+    resolution: u32,
+    z_near: f32,
+    z_far: f32,
+    update_mode: ReflectionProbeUpdateMode,
+    baked_cube_map: Option<TextureResource>,
+}
+
+impl ReflectionProbeBuilder {
+    /// Creates new builder instance.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            shape: Default::default(),
+            blend_distance: 1.0,
+            box_projection: false,
+            intensity: 1.0,
+            resolution: 128,
+            z_near: 0.025,
+            z_far: 128.0,
+            update_mode: Default::default(),
+            baked_cube_map: None,
+        }
+    }
+
+    /// Sets desired shape of the influence volume.
+    pub fn with_shape(mut self, shape: ReflectionProbeShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets desired blend distance.
+    pub fn with_blend_distance(mut self, distance: f32) -> Self {
+        self.blend_distance = distance;
+        self
+    }
+
+    /// Enables or disables box projection.
+    pub fn with_box_projection(mut self, enabled: bool) -> Self {
+        self.box_projection = enabled;
+        self
+    }
+
+    /// Sets desired intensity of the reflections.
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Sets desired size of a face of the cube map.
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Sets desired near clipping plane distance.
+    pub fn with_z_near(mut self, z_near: f32) -> Self {
+        self.z_near = z_near;
+        self
+    }
+
+    /// Sets desired far clipping plane distance.
+    pub fn with_z_far(mut self, z_far: f32) -> Self {
+        self.z_far = z_far;
+        self
+    }
+
+    /// Sets desired update mode.
+    pub fn with_update_mode(mut self, mode: ReflectionProbeUpdateMode) -> Self {
+        self.update_mode = mode;
+        self
+    }
+
+    /// Sets baked cube map.
+    pub fn with_baked_cube_map(mut self, cube_map: TextureResource) -> Self {
+        self.baked_cube_map = Some(cube_map);
+        self
+    }
+
+    /// Creates new reflection probe.
+    pub fn build_reflection_probe(self) -> ReflectionProbe {
+        ReflectionProbe {
+            base: self.base_builder.build_base(),
+            shape: self.shape.into(),
+            blend_distance: self.blend_distance.into(),
+            box_projection: self.box_projection.into(),
+            intensity: self.intensity.into(),
+            resolution: self.resolution.clamp(16, 2048).into(),
+            z_near: self.z_near.into(),
+            z_far: self.z_far.into(),
+            update_mode: self.update_mode.into(),
+            baked_cube_map: self.baked_cube_map.into(),
+            capture_requested: Cell::new(true),
+        }
+    }
+
+    /// Creates new reflection probe node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_reflection_probe())
+    }
+
+    /// Creates new reflection probe node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::Vector3,
+            visitor::{Visit, Visitor},
+        },
+        scene::{
+            base::BaseBuilder,
+            reflection_probe::{
+                ReflectionProbe, ReflectionProbeBuilder, ReflectionProbeShape,
+                ReflectionProbeUpdateMode,
+            },
+        },
+    };
+
+    #[test]
+    fn test_reflection_probe_save_load() {
+        let shape = ReflectionProbeShape::Sphere { radius: 3.0 };
+        let mut probe = ReflectionProbeBuilder::new(BaseBuilder::new())
+            .with_shape(shape.clone())
+            .with_blend_distance(0.5)
+            .with_box_projection(true)
+            .with_intensity(2.0)
+            .with_resolution(64)
+            .with_update_mode(ReflectionProbeUpdateMode::EachFrame)
+            .build_reflection_probe();
+
+        let mut visitor = Visitor::new();
+        probe.visit("Probe", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut loaded = ReflectionProbe::default();
+        loaded.visit("Probe", &mut visitor).unwrap();
+
+        assert_eq!(loaded.shape(), &shape);
+        assert_eq!(loaded.blend_distance(), 0.5);
+        assert!(loaded.is_box_projection_enabled());
+        assert_eq!(loaded.intensity(), 2.0);
+        assert_eq!(loaded.resolution(), 64);
+        assert_eq!(loaded.update_mode(), ReflectionProbeUpdateMode::EachFrame);
+        assert!(loaded.baked_cube_map().is_none());
+    }
+
+    #[test]
+    fn test_reflection_probe_capture_requests() {
+        let probe = ReflectionProbe::default();
+        // New probes are captured once.
+        assert!(probe.needs_capture());
+        probe.reset_capture_request();
+        assert!(!probe.needs_capture());
+        probe.request_capture();
+        assert!(probe.needs_capture());
+    }
+
+    #[test]
+    fn test_reflection_probe_shape_bounds() {
+        let bounds = ReflectionProbeShape::Box {
+            half_extents: Vector3::new(1.0, 2.0, 3.0),
+        }
+        .bounding_box();
+        assert_eq!(bounds.min, Vector3::new(-1.0, -2.0, -3.0));
+        assert_eq!(bounds.max, Vector3::new(1.0, 2.0, 3.0));
+
+        let bounds = ReflectionProbeShape::Sphere { radius: 2.0 }.bounding_box();
+        assert_eq!(bounds.min, Vector3::repeat(-2.0));
+        assert_eq!(bounds.max, Vector3::repeat(2.0));
+    }
+}
@@ -0,0 +1,1146 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Spline is a smooth curve in space defined by a set of control points. It can be used for camera
+//! rails, patrol routes, roads, pipes, etc. See [`Spline`] docs for more info.
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::{
+        algebra::{Point3, Vector2, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+        parking_lot::Mutex,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    material::{Material, MaterialResource},
+    renderer::{
+        self,
+        bundle::{RenderContext, SurfaceInstanceData},
+        framework::ElementRange,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::{Line, SceneDrawingContext},
+        graph::Graph,
+        mesh::{
+            buffer::{TriangleBuffer, VertexBuffer},
+            surface::{SurfaceData, SurfaceResource},
+            vertex::StaticVertex,
+            RenderPath,
+        },
+        node::{constructor::NodeConstructor, Node, NodeTrait, RdcControlFlow},
+    },
+};
+use fyrox_graph::{constructor::ConstructorProvider, BaseSceneGraph};
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines how the curve passes through (or near) the control points of a spline.
+#[derive(
+    Copy,
+    Clone,
+    Default,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum SplineKind {
+    /// Uniform Catmull-Rom spline. The curve passes through every control point and tangents are
+    /// calculated automatically from the neighbouring points, tangents of the control points are
+    /// ignored.
+    #[default]
+    CatmullRom,
+    /// Cubic Bezier spline. The curve passes through every control point and its shape between two
+    /// points is defined by the outgoing tangent of the first point and the incoming tangent of the
+    /// second one.
+    Bezier,
+}
+
+uuid_provider!(SplineKind = "421e6be3-22dd-421b-aa19-5eeb589b9b05");
+
+/// Optional geometry that is generated along a spline. The geometry is rendered with the material
+/// of the spline and it can also be used as a source of a trimesh collider (see
+/// [`crate::scene::collider::TrimeshShape`]).
+#[derive(
+    Copy, Clone, Default, Debug, PartialEq, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum SplineExtrusion {
+    /// No geometry is generated, the spline is just a curve.
+    #[default]
+    None,
+    /// A closed tube around the curve, useful for pipes, cables, etc.
+    Tube {
+        /// Radius of the tube.
+        radius: f32,
+        /// Amount of sides of the tube. Must be at least 3.
+        sides: u32,
+    },
+    /// A flat strip along the curve, useful for roads, rivers, etc. The strip is kept as
+    /// horizontal (perpendicular to the local Y axis) as possible.
+    Ribbon {
+        /// Width of the strip.
+        width: f32,
+    },
+}
+
+uuid_provider!(SplineExtrusion = "60347924-afcd-4e98-9088-6e402a7c7143");
+
+impl SplineExtrusion {
+    /// Returns the maximum distance from the curve to the generated geometry.
+    pub fn extent(&self) -> f32 {
+        match self {
+            SplineExtrusion::None => 0.0,
+            SplineExtrusion::Tube { radius, .. } => radius.abs(),
+            SplineExtrusion::Ribbon { width } => width.abs() * 0.5,
+        }
+    }
+}
+
+/// A control point of a spline. All the values are defined in the local coordinates of the spline
+/// node.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Visit, Reflect)]
+pub struct SplinePoint {
+    /// Position of the point.
+    pub position: Vector3<f32>,
+    /// Incoming tangent of the point relative to its position. It is used only by
+    /// [`SplineKind::Bezier`] splines.
+    pub in_tangent: Vector3<f32>,
+    /// Outgoing tangent of the point relative to its position. It is used only by
+    /// [`SplineKind::Bezier`] splines.
+    pub out_tangent: Vector3<f32>,
+}
+
+uuid_provider!(SplinePoint = "3edef7a6-561a-426f-8997-05e939053dd1");
+
+impl SplinePoint {
+    /// Creates a new control point at the given position with zero tangents.
+    pub fn new(position: Vector3<f32>) -> Self {
+        Self {
+            position,
+            in_tangent: Default::default(),
+            out_tangent: Default::default(),
+        }
+    }
+
+    /// Sets symmetric tangents of the point, the incoming tangent is the negated outgoing one.
+    pub fn with_tangent(mut self, out_tangent: Vector3<f32>) -> Self {
+        self.in_tangent = -out_tangent;
+        self.out_tangent = out_tangent;
+        self
+    }
+}
+
+/// A result of [`Spline::closest_point`] query.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct SplineClosestPoint {
+    /// Normalized parameter of the closest point in `[0; 1]` range.
+    pub parameter: f32,
+    /// Distance along the spline from its beginning to the closest point.
+    pub distance: f32,
+    /// Position of the closest point.
+    pub position: Vector3<f32>,
+}
+
+#[derive(Copy, Clone)]
+struct Curve<'a> {
+    points: &'a [SplinePoint],
+    kind: SplineKind,
+    closed: bool,
+}
+
+impl Curve<'_> {
+    fn segment_count(&self) -> usize {
+        match self.points.len() {
+            0 | 1 => 0,
+            count if self.closed => count,
+            count => count - 1,
+        }
+    }
+
+    // Returns a position of a point with the given index. Indices outside of the valid range are
+    // wrapped for closed curves and extrapolated for open ones.
+    fn position(&self, index: isize) -> Vector3<f32> {
+        let count = self.points.len() as isize;
+        if self.closed {
+            self.points[index.rem_euclid(count) as usize].position
+        } else if index < 0 {
+            self.points[0].position * 2.0 - self.points[1].position
+        } else if index >= count {
+            self.points[count as usize - 1].position * 2.0
+                - self.points[count as usize - 2].position
+        } else {
+            self.points[index as usize].position
+        }
+    }
+
+    // Every segment of both kinds of curves is converted to a cubic Bezier curve.
+    fn bezier(&self, segment: usize) -> [Vector3<f32>; 4] {
+        match self.kind {
+            SplineKind::CatmullRom => {
+                let i = segment as isize;
+                let p0 = self.position(i - 1);
+                let p1 = self.position(i);
+                let p2 = self.position(i + 1);
+                let p3 = self.position(i + 2);
+                [p1, p1 + (p2 - p0) / 6.0, p2 - (p3 - p1) / 6.0, p2]
+            }
+            SplineKind::Bezier => {
+                let begin = &self.points[segment];
+                let end = &self.points[(segment + 1) % self.points.len()];
+                [
+                    begin.position,
+                    begin.position + begin.out_tangent,
+                    end.position + end.in_tangent,
+                    end.position,
+                ]
+            }
+        }
+    }
+
+    fn locate(&self, parameter: f32) -> (usize, f32) {
+        let count = self.segment_count();
+        let scaled = parameter.clamp(0.0, 1.0) * count as f32;
+        let segment = (scaled as usize).min(count - 1);
+        (segment, scaled - segment as f32)
+    }
+
+    fn point_at(&self, parameter: f32) -> Vector3<f32> {
+        match self.points.len() {
+            0 => Vector3::default(),
+            1 => self.points[0].position,
+            _ => {
+                let (segment, t) = self.locate(parameter);
+                let [p0, p1, p2, p3] = self.bezier(segment);
+                let s = 1.0 - t;
+                p0 * (s * s * s)
+                    + p1 * (3.0 * s * s * t)
+                    + p2 * (3.0 * s * t * t)
+                    + p3 * (t * t * t)
+            }
+        }
+    }
+
+    fn derivative_at(&self, parameter: f32) -> Vector3<f32> {
+        if self.points.len() < 2 {
+            return Vector3::default();
+        }
+        let (segment, t) = self.locate(parameter);
+        let [p0, p1, p2, p3] = self.bezier(segment);
+        let s = 1.0 - t;
+        ((p1 - p0) * (3.0 * s * s) + (p2 - p1) * (6.0 * s * t) + (p3 - p2) * (3.0 * t * t))
+            * self.segment_count() as f32
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ArcLengthSample {
+    parameter: f32,
+    distance: f32,
+    position: Vector3<f32>,
+}
+
+#[derive(Clone, Default, Debug)]
+struct SplineCacheData {
+    // A copy of the properties that were used to build the arc length table. It is used to detect
+    // changes made in any way, including modifications via reflection.
+    points: Vec<SplinePoint>,
+    kind: SplineKind,
+    closed: bool,
+    resolution: u32,
+    samples: Vec<ArcLengthSample>,
+    surface: Option<(SplineExtrusion, SurfaceResource)>,
+}
+
+#[derive(Default, Debug)]
+struct SplineCache(Mutex<SplineCacheData>);
+
+impl Clone for SplineCache {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().clone()))
+    }
+}
+
+/// Spline is a smooth curve in space defined by a set of control points (see [`SplinePoint`]).
+/// The curve is defined in the local coordinates of the node, so it can be moved, rotated and
+/// scaled as any other node. 2D splines are just splines with zero Z coordinate of every point.
+///
+/// ## Kinds
+///
+/// There are two kinds of splines (see [`SplineKind`]): Catmull-Rom splines calculate tangents
+/// automatically and are the easiest to edit, Bezier splines use explicit tangents of every
+/// control point and give full control over the shape of the curve. Both kinds of splines could
+/// be open or closed (see [`Spline::set_closed`]).
+///
+/// ## Arc length
+///
+/// The "natural" parameter of a spline does not move along the curve with constant speed,
+/// because control points could be placed unevenly. Every spline has an internal table that maps
+/// distances along the curve to parameters, which allows sampling the spline uniformly (see
+/// [`Spline::position_at_distance`] and [`Spline::sample_uniform`]). This is exactly what is
+/// needed to move an object along the curve with constant speed. The table is built lazily and
+/// rebuilt automatically when the spline changes, its precision is defined by
+/// [`Spline::set_resolution`]. All the distances are measured in local coordinates of the node.
+///
+/// ## Extrusion
+///
+/// A spline could optionally generate a tube or a flat strip along the curve (see
+/// [`SplineExtrusion`]), which is rendered using the material of the spline. The same geometry
+/// could be used by a trimesh collider, by specifying the spline as a geometry source. This is
+/// useful for roads, pipes, cables and so on.
+///
+/// ## Example
+///
+/// The following example moves a node along a closed patrol route with constant speed:
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         graph::Graph,
+/// #         node::Node,
+/// #         spline::{Spline, SplineBuilder, SplinePoint},
+/// #     },
+/// # };
+/// fn create_route(graph: &mut Graph) -> Handle<Node> {
+///     SplineBuilder::new(BaseBuilder::new())
+///         .with_points(vec![
+///             SplinePoint::new(Vector3::new(0.0, 0.0, 0.0)),
+///             SplinePoint::new(Vector3::new(10.0, 0.0, 0.0)),
+///             SplinePoint::new(Vector3::new(10.0, 0.0, 10.0)),
+///             SplinePoint::new(Vector3::new(0.0, 0.0, 10.0)),
+///         ])
+///         .with_closed(true)
+///         .build(graph)
+/// }
+///
+/// fn move_along_route(graph: &mut Graph, route: Handle<Node>, guard: Handle<Node>, time: f32) {
+///     let speed = 2.0;
+///     let position = graph[route]
+///         .cast::<Spline>()
+///         .unwrap()
+///         .world_position_at_distance(speed * time);
+///     graph[guard].local_transform_mut().set_position(position);
+/// }
+/// ```
+#[derive(Debug, Reflect, Clone, Visit, ComponentProvider)]
+pub struct Spline {
+    base: Base,
+
+    #[reflect(setter = "set_points")]
+    points: InheritableVariable<Vec<SplinePoint>>,
+
+    #[reflect(setter = "set_kind")]
+    kind: InheritableVariable<SplineKind>,
+
+    #[reflect(setter = "set_closed")]
+    closed: InheritableVariable<bool>,
+
+    #[reflect(
+        setter = "set_resolution",
+        min_value = 1.0,
+        description = "Amount of samples per segment of the curve."
+    )]
+    resolution: InheritableVariable<u32>,
+
+    #[reflect(setter = "set_extrusion")]
+    extrusion: InheritableVariable<SplineExtrusion>,
+
+    material: InheritableVariable<MaterialResource>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    cache: SplineCache,
+}
+
+impl Deref for Spline {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Spline {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Spline {
+    fn type_uuid() -> Uuid {
+        uuid!("a59c77fe-93a5-45fc-b04e-f7092f303910")
+    }
+}
+
+fn default_points() -> Vec<SplinePoint> {
+    vec![
+        SplinePoint::new(Vector3::new(-1.0, 0.0, 0.0)).with_tangent(Vector3::new(0.0, 0.0, 0.5)),
+        SplinePoint::new(Vector3::new(0.0, 0.0, 1.0)).with_tangent(Vector3::new(0.5, 0.0, 0.0)),
+        SplinePoint::new(Vector3::new(1.0, 0.0, 0.0)).with_tangent(Vector3::new(0.0, 0.0, -0.5)),
+    ]
+}
+
+impl Default for Spline {
+    fn default() -> Self {
+        SplineBuilder::new(BaseBuilder::new()).build_spline()
+    }
+}
+
+impl Spline {
+    /// Default amount of samples per segment of the curve.
+    pub const DEFAULT_RESOLUTION: u32 = 16;
+
+    /// Sets new control points of the spline.
+    pub fn set_points(&mut self, points: Vec<SplinePoint>) -> Vec<SplinePoint> {
+        self.points.set_value_and_mark_modified(points)
+    }
+
+    /// Returns a reference to the control points of the spline.
+    pub fn points(&self) -> &[SplinePoint] {
+        &self.points
+    }
+
+    /// Returns a mutable reference to the control points of the spline.
+    pub fn points_mut(&mut self) -> &mut Vec<SplinePoint> {
+        self.points.get_value_mut_and_mark_modified()
+    }
+
+    /// Sets new kind of the spline. See [`SplineKind`] docs for more info.
+    pub fn set_kind(&mut self, kind: SplineKind) -> SplineKind {
+        self.kind.set_value_and_mark_modified(kind)
+    }
+
+    /// Returns current kind of the spline.
+    pub fn kind(&self) -> SplineKind {
+        *self.kind
+    }
+
+    /// Defines whether the spline is closed or not. The last point of a closed spline is connected
+    /// with the first one.
+    pub fn set_closed(&mut self, closed: bool) -> bool {
+        self.closed.set_value_and_mark_modified(closed)
+    }
+
+    /// Returns `true` if the spline is closed, `false` - otherwise.
+    pub fn is_closed(&self) -> bool {
+        *self.closed
+    }
+
+    /// Sets amount of samples per segment of the curve. Higher values increase precision of the
+    /// arc length queries and smoothness of the generated geometry. The value is clamped to at
+    /// least one sample.
+    pub fn set_resolution(&mut self, resolution: u32) -> u32 {
+        self.resolution
+            .set_value_and_mark_modified(resolution.max(1))
+    }
+
+    /// Returns amount of samples per segment of the curve.
+    pub fn resolution(&self) -> u32 {
+        *self.resolution
+    }
+
+    /// Sets new extrusion of the spline. See [`SplineExtrusion`] docs for more info.
+    pub fn set_extrusion(&mut self, extrusion: SplineExtrusion) -> SplineExtrusion {
+        self.extrusion.set_value_and_mark_modified(extrusion)
+    }
+
+    /// Returns current extrusion of the spline.
+    pub fn extrusion(&self) -> SplineExtrusion {
+        *self.extrusion
+    }
+
+    /// Sets new material that is used to render the extruded geometry of the spline.
+    pub fn set_material(&mut self, material: MaterialResource) -> MaterialResource {
+        self.material.set_value_and_mark_modified(material)
+    }
+
+    /// Returns a reference to the material that is used to render the extruded geometry.
+    pub fn material(&self) -> &MaterialResource {
+        &self.material
+    }
+
+    fn curve(&self) -> Curve<'_> {
+        Curve {
+            points: &self.points,
+            kind: *self.kind,
+            closed: *self.closed,
+        }
+    }
+
+    fn with_samples<R>(&self, func: impl FnOnce(&[ArcLengthSample]) -> R) -> R {
+        let mut cache = self.cache.0.lock();
+
+        if cache.samples.is_empty()
+            || cache.points != *self.points
+            || cache.kind != *self.kind
+            || cache.closed != *self.closed
+            || cache.resolution != *self.resolution
+        {
+            *cache = SplineCacheData {
+                points: (*self.points).clone(),
+                kind: *self.kind,
+                closed: *self.closed,
+                resolution: *self.resolution,
+                samples: build_arc_length_table(&self.curve(), *self.resolution),
+                surface: None,
+            };
+        }
+
+        func(&cache.samples)
+    }
+
+    /// Returns total length of the spline.
+    pub fn length(&self) -> f32 {
+        self.with_samples(|samples| samples.last().map_or(0.0, |s| s.distance))
+    }
+
+    /// Returns a position on the curve for the given normalized parameter in `[0; 1]` range. Keep
+    /// in mind, that the parameter is not proportional to the distance along the curve, use
+    /// [`Self::position_at_distance`] if you need uniform motion.
+    pub fn sample(&self, parameter: f32) -> Vector3<f32> {
+        self.curve().point_at(parameter)
+    }
+
+    /// Returns normalized tangent of the curve for the given normalized parameter in `[0; 1]`
+    /// range. Returns zero vector if the tangent cannot be calculated.
+    pub fn tangent(&self, parameter: f32) -> Vector3<f32> {
+        self.curve()
+            .derivative_at(parameter)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default()
+    }
+
+    /// Converts the given distance along the curve into normalized parameter of the curve. The
+    /// distance is wrapped for closed splines and clamped for open ones.
+    pub fn parameter_at_distance(&self, distance: f32) -> f32 {
+        let closed = *self.closed;
+        self.with_samples(|samples| {
+            let length = samples.last().map_or(0.0, |s| s.distance);
+            if length <= f32::EPSILON {
+                return 0.0;
+            }
+
+            let distance = if closed {
+                distance.rem_euclid(length)
+            } else {
+                distance.clamp(0.0, length)
+            };
+
+            let index = samples
+                .partition_point(|s| s.distance < distance)
+                .clamp(1, samples.len() - 1);
+            let begin = &samples[index - 1];
+            let end = &samples[index];
+            let span = end.distance - begin.distance;
+            if span <= f32::EPSILON {
+                begin.parameter
+            } else {
+                let k = (distance - begin.distance) / span;
+                begin.parameter + (end.parameter - begin.parameter) * k
+            }
+        })
+    }
+
+    /// Returns a distance along the curve from its beginning to the point with the given
+    /// normalized parameter.
+    pub fn distance_at_parameter(&self, parameter: f32) -> f32 {
+        self.with_samples(|samples| {
+            if samples.len() < 2 {
+                return 0.0;
+            }
+            let scaled = parameter.clamp(0.0, 1.0) * (samples.len() - 1) as f32;
+            let index = (scaled as usize).min(samples.len() - 2);
+            let k = scaled - index as f32;
+            let begin = samples[index].distance;
+            begin + (samples[index + 1].distance - begin) * k
+        })
+    }
+
+    /// Returns a position on the curve at the given distance from its beginning. The distance is
+    /// wrapped for closed splines and clamped for open ones.
+    pub fn position_at_distance(&self, distance: f32) -> Vector3<f32> {
+        self.sample(self.parameter_at_distance(distance))
+    }
+
+    /// Returns normalized tangent of the curve at the given distance from its beginning.
+    pub fn tangent_at_distance(&self, distance: f32) -> Vector3<f32> {
+        self.tangent(self.parameter_at_distance(distance))
+    }
+
+    /// Returns `count` points evenly distributed along the curve. The first and the last points of
+    /// an open spline are its ends; the points of a closed spline do not repeat the first point.
+    pub fn sample_uniform(&self, count: usize) -> Vec<Vector3<f32>> {
+        let length = self.length();
+        let divisor = if *self.closed {
+            count
+        } else {
+            count.saturating_sub(1)
+        }
+        .max(1);
+        (0..count)
+            .map(|i| self.position_at_distance(length * i as f32 / divisor as f32))
+            .collect()
+    }
+
+    /// Finds a point on the curve that is closest to the given point. Both the given point and the
+    /// result are in local coordinates of the spline.
+    pub fn closest_point(&self, point: Vector3<f32>) -> SplineClosestPoint {
+        let curve = self.curve();
+        let (t_min, t_max) = self.with_samples(|samples| {
+            if samples.len() < 2 {
+                return (0.0, 0.0);
+            }
+
+            // Find the closest piece of the piecewise-linear approximation first.
+            let mut closest_index = 0;
+            let mut closest_distance = f32::MAX;
+            for (index, pair) in samples.windows(2).enumerate() {
+                let direction = pair[1].position - pair[0].position;
+                let length_sqr = direction.norm_squared();
+                let k = if length_sqr <= f32::EPSILON {
+                    0.0
+                } else {
+                    ((point - pair[0].position).dot(&direction) / length_sqr).clamp(0.0, 1.0)
+                };
+                let distance = (pair[0].position + direction * k - point).norm_squared();
+                if distance < closest_distance {
+                    closest_distance = distance;
+                    closest_index = index;
+                }
+            }
+
+            (
+                samples[closest_index.saturating_sub(1)].parameter,
+                samples[(closest_index + 2).min(samples.len() - 1)].parameter,
+            )
+        });
+
+        // Then refine the result on the actual curve using golden-section search.
+        let ratio = (5.0f32.sqrt() - 1.0) * 0.5;
+        let distance_to = |t: f32| (curve.point_at(t) - point).norm_squared();
+        let (mut a, mut b) = (t_min, t_max);
+        for _ in 0..32 {
+            let c = b - (b - a) * ratio;
+            let d = a + (b - a) * ratio;
+            if distance_to(c) < distance_to(d) {
+                b = d;
+            } else {
+                a = c;
+            }
+        }
+        let parameter = (a + b) * 0.5;
+
+        SplineClosestPoint {
+            parameter,
+            distance: self.distance_at_parameter(parameter),
+            position: curve.point_at(parameter),
+        }
+    }
+
+    /// Same as [`Self::position_at_distance`], but returns the position in world coordinates.
+    /// The distance is still measured in local coordinates of the spline.
+    pub fn world_position_at_distance(&self, distance: f32) -> Vector3<f32> {
+        self.global_transform()
+            .transform_point(&Point3::from(self.position_at_distance(distance)))
+            .coords
+    }
+
+    /// Same as [`Self::tangent_at_distance`], but returns the tangent in world coordinates.
+    pub fn world_tangent_at_distance(&self, distance: f32) -> Vector3<f32> {
+        self.global_transform()
+            .transform_vector(&self.tangent_at_distance(distance))
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default()
+    }
+
+    /// Same as [`Self::closest_point`], but the given point and the position of the result are in
+    /// world coordinates. The distance is still measured in local coordinates of the spline.
+    pub fn world_closest_point(&self, point: Vector3<f32>) -> SplineClosestPoint {
+        let transform = self.global_transform();
+        let local_point = transform
+            .try_inverse()
+            .unwrap_or_default()
+            .transform_point(&Point3::from(point))
+            .coords;
+        let mut result = self.closest_point(local_point);
+        result.position = transform
+            .transform_point(&Point3::from(result.position))
+            .coords;
+        result
+    }
+
+    /// Returns the geometry generated along the curve (see [`SplineExtrusion`]), or `None` if the
+    /// extrusion is disabled or the spline has less than two points. The geometry is defined in
+    /// local coordinates of the spline and it is cached until the spline is changed.
+    pub fn extrusion_surface(&self) -> Option<SurfaceResource> {
+        let extrusion = *self.extrusion;
+        if extrusion == SplineExtrusion::None {
+            return None;
+        }
+
+        // Make sure that the arc length table is up-to-date, it also invalidates the surface.
+        self.with_samples(|_| ());
+
+        let mut cache = self.cache.0.lock();
+        if let Some((cached_extrusion, surface)) = cache.surface.as_ref() {
+            if *cached_extrusion == extrusion {
+                return Some(surface.clone());
+            }
+        }
+
+        let data = make_extrusion(&self.curve(), &cache.samples, extrusion)?;
+        let surface = SurfaceResource::new_ok(ResourceKind::Embedded, data);
+        cache.surface = Some((extrusion, surface.clone()));
+        Some(surface)
+    }
+}
+
+fn build_arc_length_table(curve: &Curve, resolution: u32) -> Vec<ArcLengthSample> {
+    let total = curve.segment_count() * resolution.max(1) as usize;
+    if total == 0 {
+        return vec![ArcLengthSample {
+            parameter: 0.0,
+            distance: 0.0,
+            position: curve.point_at(0.0),
+        }];
+    }
+
+    let mut samples = Vec::with_capacity(total + 1);
+    let mut distance = 0.0;
+    let mut previous = curve.point_at(0.0);
+    for i in 0..=total {
+        let parameter = i as f32 / total as f32;
+        let position = curve.point_at(parameter);
+        distance += (position - previous).norm();
+        previous = position;
+        samples.push(ArcLengthSample {
+            parameter,
+            distance,
+            position,
+        });
+    }
+    samples
+}
+
+fn make_extrusion(
+    curve: &Curve,
+    samples: &[ArcLengthSample],
+    extrusion: SplineExtrusion,
+) -> Option<SurfaceData> {
+    if samples.len() < 2 {
+        return None;
+    }
+
+    // Calculate a frame for every sample. The side vector is kept perpendicular to the Y axis, so
+    // ribbons stay flat. Degenerate cases reuse the frame of the previous sample.
+    let mut frames = Vec::with_capacity(samples.len());
+    let mut tangent = Vector3::x();
+    let mut side = Vector3::z();
+    for sample in samples {
+        tangent = curve
+            .derivative_at(sample.parameter)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or(tangent);
+        side = tangent
+            .cross(&Vector3::y())
+            .try_normalize(f32::EPSILON)
+            .unwrap_or(side);
+        let up = side.cross(&tangent);
+        frames.push((side, up));
+    }
+
+    let mut vertices = Vec::new();
+    let mut triangles = Vec::new();
+
+    match extrusion {
+        SplineExtrusion::None => return None,
+        SplineExtrusion::Tube { radius, sides } => {
+            if radius <= 0.0 || sides < 3 {
+                return None;
+            }
+
+            let ring = sides as usize + 1;
+            let circumference = 2.0 * std::f32::consts::PI * radius;
+            for (sample, (side, up)) in samples.iter().zip(frames.iter()) {
+                for j in 0..ring {
+                    let angle = 2.0 * std::f32::consts::PI * j as f32 / sides as f32;
+                    let normal = side.scale(angle.cos()) + up.scale(angle.sin());
+                    vertices.push(StaticVertex::from_pos_uv_normal(
+                        sample.position + normal.scale(radius),
+                        Vector2::new(j as f32 / sides as f32, sample.distance / circumference),
+                        normal,
+                    ));
+                }
+            }
+
+            for i in 0..samples.len() - 1 {
+                for j in 0..sides as usize {
+                    let a = (i * ring + j) as u32;
+                    let b = ((i + 1) * ring + j) as u32;
+                    triangles.push(TriangleDefinition([a, b, a + 1]));
+                    triangles.push(TriangleDefinition([b, b + 1, a + 1]));
+                }
+            }
+        }
+        SplineExtrusion::Ribbon { width } => {
+            if width <= 0.0 {
+                return None;
+            }
+
+            let half_width = width * 0.5;
+            for (sample, (side, up)) in samples.iter().zip(frames.iter()) {
+                let v = sample.distance / width;
+                vertices.push(StaticVertex::from_pos_uv_normal(
+                    sample.position - side.scale(half_width),
+                    Vector2::new(0.0, v),
+                    *up,
+                ));
+                vertices.push(StaticVertex::from_pos_uv_normal(
+                    sample.position + side.scale(half_width),
+                    Vector2::new(1.0, v),
+                    *up,
+                ));
+            }
+
+            for i in 0..samples.len() as u32 - 1 {
+                let left = i * 2;
+                let right = left + 1;
+                triangles.push(TriangleDefinition([left, right, left + 2]));
+                triangles.push(TriangleDefinition([right, right + 2, left + 2]));
+            }
+        }
+    }
+
+    let mut data = SurfaceData::new(
+        VertexBuffer::new(vertices.len(), vertices).unwrap(),
+        TriangleBuffer::new(triangles),
+    );
+    data.calculate_tangents().unwrap();
+    Some(data)
+}
+
+impl ConstructorProvider<Node, Graph> for Spline {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>().with_variant("Spline", |_| {
+            SplineBuilder::new(BaseBuilder::new().with_name("Spline"))
+                .build_node()
+                .into()
+        })
+    }
+}
+
+impl NodeTrait for Spline {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        if self.points.is_empty() {
+            return AxisAlignedBoundingBox::collapsed();
+        }
+
+        let mut aabb = self.with_samples(|samples| {
+            AxisAlignedBoundingBox::from_points(
+                &samples.iter().map(|s| s.position).collect::<Vec<_>>(),
+            )
+        });
+        let extent = self.extrusion.extent();
+        aabb.inflate(Vector3::repeat(2.0 * extent));
+        aabb
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) -> RdcControlFlow {
+        if !self.should_be_rendered(ctx.frustum) {
+            return RdcControlFlow::Continue;
+        }
+
+        if renderer::is_shadow_pass(ctx.render_pass_name) && !self.cast_shadows() {
+            return RdcControlFlow::Continue;
+        }
+
+        if let Some(surface) = self.extrusion_surface() {
+            ctx.storage.push(
+                &surface,
+                &self.material,
+                RenderPath::Deferred,
+                self.material.key(),
+                SurfaceInstanceData {
+                    world_transform: self.global_transform(),
+                    bone_matrices: Default::default(),
+                    blend_shapes_weights: Default::default(),
+                    element_range: ElementRange::Full,
+                    node_handle: self.handle(),
+                },
+            );
+        }
+
+        RdcControlFlow::Continue
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let transform = self.global_transform();
+        let to_world = |point: Vector3<f32>| transform.transform_point(&Point3::from(point)).coords;
+
+        self.with_samples(|samples| {
+            for pair in samples.windows(2) {
+                ctx.add_line(Line {
+                    begin: to_world(pair[0].position),
+                    end: to_world(pair[1].position),
+                    color: Color::GREEN,
+                });
+            }
+        });
+
+        if *self.kind == SplineKind::Bezier {
+            for point in self.points.iter() {
+                for tangent in [point.in_tangent, point.out_tangent] {
+                    ctx.add_line(Line {
+                        begin: to_world(point.position),
+                        end: to_world(point.position + tangent),
+                        color: Color::ORANGE,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Allows you to create splines in declarative manner.
+pub struct SplineBuilder {
+    base_builder: BaseBuilder,
+    points: Vec<SplinePoint>,
+    kind: SplineKind,
+    closed: bool,
+    resolution: u32,
+    extrusion: SplineExtrusion,
+    material: MaterialResource,
+}
+
+impl SplineBuilder {
+    /// Creates new spline builder. By default, the spline is an open Catmull-Rom arc of three
+    /// points without extrusion.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            points: default_points(),
+            kind: Default::default(),
+            closed: false,
+            resolution: Spline::DEFAULT_RESOLUTION,
+            extrusion: Default::default(),
+            material: MaterialResource::new_ok(Default::default(), Material::standard()),
+        }
+    }
+
+    /// Sets the desired control points of the spline.
+    pub fn with_points(mut self, points: Vec<SplinePoint>) -> Self {
+        self.points = points;
+        self
+    }
+
+    /// Sets the desired kind of the spline.
+    pub fn with_kind(mut self, kind: SplineKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Sets whether the spline is closed or not.
+    pub fn with_closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    /// Sets the desired amount of samples per segment. See [`Spline::set_resolution`] for more
+    /// info.
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution.max(1);
+        self
+    }
+
+    /// Sets the desired extrusion of the spline.
+    pub fn with_extrusion(mut self, extrusion: SplineExtrusion) -> Self {
+        self.extrusion = extrusion;
+        self
+    }
+
+    /// Sets the desired material of the extruded geometry.
+    pub fn with_material(mut self, material: MaterialResource) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Creates new [`Spline`] instance.
+    pub fn build_spline(self) -> Spline {
+        Spline {
+            base: self.base_builder.build_base(),
+            points: self.points.into(),
+            kind: self.kind.into(),
+            closed: self.closed.into(),
+            resolution: self.resolution.into(),
+            extrusion: self.extrusion.into(),
+            material: self.material.into(),
+            cache: Default::default(),
+        }
+    }
+
+    /// Creates new [`Spline`] node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_spline())
+    }
+
+    /// Creates new [`Spline`] node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        scene::{
+            base::BaseBuilder,
+            spline::{SplineBuilder, SplineExtrusion, SplineKind, SplinePoint},
+        },
+    };
+
+    fn line() -> Vec<SplinePoint> {
+        vec![
+            SplinePoint::new(Vector3::new(0.0, 0.0, 0.0)),
+            SplinePoint::new(Vector3::new(1.0, 0.0, 0.0)),
+            SplinePoint::new(Vector3::new(4.0, 0.0, 0.0)),
+        ]
+    }
+
+    #[test]
+    fn test_spline_arc_length() {
+        let spline = SplineBuilder::new(BaseBuilder::new())
+            .with_points(line())
+            .build_spline();
+
+        assert!((spline.length() - 4.0).abs() < 1.0e-3);
+        // Control points are placed unevenly, but the distance must be uniform.
+        for distance in [0.0, 0.5, 2.0, 3.5, 4.0] {
+            let position = spline.position_at_distance(distance);
+            assert!(
+                (position.x - distance).abs() < 1.0e-2,
+                "{distance} {position}"
+            );
+        }
+        // Distances outside of the open spline are clamped.
+        assert_eq!(
+            spline.position_at_distance(10.0),
+            Vector3::new(4.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_spline_closed_passes_through_points() {
+        let points = vec![
+            SplinePoint::new(Vector3::new(0.0, 0.0, 0.0)),
+            SplinePoint::new(Vector3::new(1.0, 0.0, 0.0)),
+            SplinePoint::new(Vector3::new(1.0, 1.0, 0.0)),
+            SplinePoint::new(Vector3::new(0.0, 1.0, 0.0)),
+        ];
+        for kind in [SplineKind::CatmullRom, SplineKind::Bezier] {
+            let spline = SplineBuilder::new(BaseBuilder::new())
+                .with_points(points.clone())
+                .with_kind(kind)
+                .with_closed(true)
+                .build_spline();
+
+            for (i, point) in points.iter().enumerate() {
+                let position = spline.sample(i as f32 / points.len() as f32);
+                assert!((position - point.position).norm() < 1.0e-5);
+            }
+            assert!((spline.sample(1.0) - points[0].position).norm() < 1.0e-5);
+
+            // Closed splines wrap the distance.
+            let length = spline.length();
+            let a = spline.position_at_distance(0.25 * length);
+            let b = spline.position_at_distance(1.25 * length);
+            assert!((a - b).norm() < 1.0e-4);
+        }
+    }
+
+    #[test]
+    fn test_spline_closest_point() {
+        let spline = SplineBuilder::new(BaseBuilder::new())
+            .with_points(line())
+            .build_spline();
+
+        let result = spline.closest_point(Vector3::new(2.5, 3.0, 0.0));
+        assert!((result.position - Vector3::new(2.5, 0.0, 0.0)).norm() < 1.0e-3);
+        assert!((result.distance - 2.5).abs() < 1.0e-2);
+
+        let result = spline.closest_point(Vector3::new(-2.0, 0.0, 1.0));
+        assert!(result.parameter < 1.0e-3);
+    }
+
+    #[test]
+    fn test_spline_extrusion() {
+        let mut spline = SplineBuilder::new(BaseBuilder::new())
+            .with_points(line())
+            .with_resolution(4)
+            .build_spline();
+        assert!(spline.extrusion_surface().is_none());
+
+        spline.set_extrusion(SplineExtrusion::Ribbon { width: 2.0 });
+        let surface = spline.extrusion_surface().unwrap();
+        {
+            let data = surface.data_ref();
+            // Two segments with 4 samples each give 9 samples in total.
+            assert_eq!(data.vertex_buffer.vertex_count(), 18);
+            assert_eq!(data.geometry_buffer.len(), 16);
+        }
+
+        // The surface is cached until the spline changes.
+        assert_eq!(spline.extrusion_surface().unwrap().key(), surface.key());
+        spline.points_mut()[2].position.x = 5.0;
+        assert_ne!(spline.extrusion_surface().unwrap().key(), surface.key());
+
+        spline.set_extrusion(SplineExtrusion::Tube {
+            radius: 0.5,
+            sides: 6,
+        });
+        let surface = spline.extrusion_surface().unwrap();
+        let data = surface.data_ref();
+        assert_eq!(data.vertex_buffer.vertex_count(), 9 * 7);
+        assert_eq!(data.geometry_buffer.len(), 8 * 6 * 2);
+    }
+}
@@ -0,0 +1,659 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Distance-based streaming of scene parts. See [`SceneStreamer`] docs for more info.
+
+use crate::{
+    asset::manager::{ResourceManager, ResourceWaitContext},
+    core::{
+        algebra::Vector3,
+        log::Log,
+        pool::{Handle, Pool, Ticket},
+    },
+    graph::BaseSceneGraph,
+    resource::model::{Model, ModelResource, ModelResourceExtension},
+    scene::{graph::Graph, node::Node, Scene},
+};
+use fxhash::FxHashMap;
+use std::{cmp::Ordering, collections::BinaryHeap, path::PathBuf};
+
+/// A set of handles of an unloaded cell, that are reserved in the graph. Every node is identified
+/// by its original handle in the model resource.
+#[derive(Default)]
+struct ReservedHandles(FxHashMap<Handle<Node>, Ticket<Node>>);
+
+impl ReservedHandles {
+    fn release(&mut self, graph: &mut Graph) {
+        for (_, ticket) in self.0.drain() {
+            graph.forget_reserved(ticket);
+        }
+    }
+}
+
+impl Drop for ReservedHandles {
+    fn drop(&mut self) {
+        if !self.0.is_empty() {
+            Log::warn(format!(
+                "{} reserved handles of a streaming cell were not released and will be leaked. \
+                Use SceneStreamer::clear to release them.",
+                self.0.len()
+            ));
+
+            for (_, ticket) in self.0.drain() {
+                std::mem::forget(ticket);
+            }
+        }
+    }
+}
+
+enum CellState {
+    Unloaded,
+    Loading {
+        model: ModelResource,
+        dependencies: Option<ResourceWaitContext>,
+    },
+    Loaded {
+        root: Handle<Node>,
+    },
+    Failed,
+}
+
+/// Current state of a [`StreamingCell`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StreamingCellState {
+    /// The cell is not present in the scene.
+    Unloaded,
+    /// The model of the cell or its dependencies are being loaded.
+    Loading,
+    /// The cell is instantiated in the scene.
+    Loaded,
+    /// The model of the cell has failed to load. Such cells are ignored by the streamer.
+    Failed,
+}
+
+/// A part of a scene that is loaded from a separate model resource when a streaming source comes
+/// close to it.
+pub struct StreamingCell {
+    model: PathBuf,
+    center: Vector3<f32>,
+    load_radius: f32,
+    unload_radius: f32,
+    priority: i32,
+    parent: Handle<Node>,
+    state: CellState,
+    reserved: ReservedHandles,
+}
+
+impl StreamingCell {
+    /// Creates a new cell, that instantiates the model at the given path when a streaming source
+    /// is closer than `load_radius` to the `center` of the cell. By default, the unload radius is
+    /// 20% larger than the load radius, this prevents the cell from loading and unloading on every
+    /// frame when the source is moving near the boundary of the cell.
+    pub fn new(model: impl Into<PathBuf>, center: Vector3<f32>, load_radius: f32) -> Self {
+        Self {
+            model: model.into(),
+            center,
+            load_radius,
+            unload_radius: load_radius * 1.2,
+            priority: 0,
+            parent: Handle::NONE,
+            state: CellState::Unloaded,
+            reserved: Default::default(),
+        }
+    }
+
+    /// Sets the distance at which the cell will be unloaded. It will be clamped to be at least
+    /// the load radius.
+    pub fn with_unload_radius(mut self, unload_radius: f32) -> Self {
+        self.unload_radius = unload_radius;
+        self
+    }
+
+    /// Sets the priority of the cell. Cells with higher priority are loaded first, cells with the
+    /// same priority are loaded in order of their distance to the streaming source.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets a node to which the instance of the cell will be attached. By default, it is attached
+    /// to the root of the scene.
+    pub fn with_parent(mut self, parent: Handle<Node>) -> Self {
+        self.parent = parent;
+        self
+    }
+
+    /// Returns a path to the model of the cell.
+    pub fn model(&self) -> &PathBuf {
+        &self.model
+    }
+
+    /// Returns the center of the cell in world coordinates.
+    pub fn center(&self) -> Vector3<f32> {
+        self.center
+    }
+
+    /// Returns the distance at which the cell starts loading.
+    pub fn load_radius(&self) -> f32 {
+        self.load_radius
+    }
+
+    /// Returns the distance at which the cell will be unloaded.
+    pub fn unload_radius(&self) -> f32 {
+        self.unload_radius.max(self.load_radius)
+    }
+
+    /// Returns the priority of the cell.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Returns current state of the cell.
+    pub fn state(&self) -> StreamingCellState {
+        match self.state {
+            CellState::Unloaded => StreamingCellState::Unloaded,
+            CellState::Loading { .. } => StreamingCellState::Loading,
+            CellState::Loaded { .. } => StreamingCellState::Loaded,
+            CellState::Failed => StreamingCellState::Failed,
+        }
+    }
+
+    /// Returns a handle of the instance root of the cell, or [`Handle::NONE`] if the cell is not
+    /// loaded.
+    pub fn root(&self) -> Handle<Node> {
+        match self.state {
+            CellState::Loaded { root } => root,
+            _ => Handle::NONE,
+        }
+    }
+
+    fn unload(&mut self, graph: &mut Graph) {
+        if let CellState::Loaded { root } = std::mem::replace(&mut self.state, CellState::Unloaded)
+        {
+            if !graph.is_valid_handle(root) {
+                return;
+            }
+
+            let sub_graph = graph.take_reserve_sub_graph(root);
+            for (ticket, node) in std::iter::once(sub_graph.root).chain(sub_graph.descendants) {
+                // Nodes that were added to the instance at runtime cannot be matched with the
+                // nodes of a new instance, so their handles are released.
+                let original_handle = node.original_handle_in_resource();
+                if original_handle.is_some() && !self.reserved.0.contains_key(&original_handle) {
+                    self.reserved.0.insert(original_handle, ticket);
+                } else {
+                    graph.forget_reserved(ticket);
+                }
+            }
+        }
+    }
+
+    fn instantiate(&mut self, model: &ModelResource, graph: &mut Graph) -> Handle<Node> {
+        let mut scratch = Graph::new();
+        let instance_root = {
+            let data = model.data_ref();
+            let resource_root = data.get_scene().graph.get_root();
+            let (root, _) = ModelResource::instantiate_from(
+                model.clone(),
+                &data,
+                resource_root,
+                &mut scratch,
+                &mut |_, _| {},
+            );
+            root
+        };
+        scratch[instance_root].is_resource_instance_root = true;
+
+        let reserved = &mut self.reserved.0;
+        let (root, _) = graph.merge_sub_graph(&mut scratch, instance_root, self.parent, |node| {
+            reserved.remove(&node.original_handle_in_resource())
+        });
+
+        // The model has changed and some of its nodes were removed.
+        self.reserved.release(graph);
+
+        root
+    }
+}
+
+/// An event that happened to a streaming cell during [`SceneStreamer::update`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StreamingEvent {
+    /// A cell was instantiated in the scene.
+    Loaded {
+        /// A handle of the cell.
+        cell: Handle<StreamingCell>,
+        /// A handle of the instance root of the cell.
+        root: Handle<Node>,
+    },
+    /// A cell was removed from the scene.
+    Unloaded {
+        /// A handle of the cell.
+        cell: Handle<StreamingCell>,
+    },
+    /// A model of the cell has failed to load.
+    Failed {
+        /// A handle of the cell.
+        cell: Handle<StreamingCell>,
+    },
+}
+
+struct QueueEntry {
+    priority: i32,
+    distance: f32,
+    cell: Handle<StreamingCell>,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    // The greatest entry has the highest priority and the lowest distance.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.distance.total_cmp(&self.distance))
+    }
+}
+
+/// Scene streamer loads and unloads parts of a scene (cells) depending on their distance to a
+/// streaming source (usually a camera or a player). It allows you to split a large level into
+/// a number of separate model resources, instead of loading the entire level at once.
+///
+/// ## Loading
+///
+/// A cell within its load radius is put in a priority queue, ordered by priority of the cell and
+/// its distance to the source. The streamer requests the model of the cell from the resource
+/// manager and, once the model is loaded, waits until all resources used by the model (textures,
+/// sounds, etc.) are loaded as well. Only then the model is instantiated in the scene, so the cell
+/// appears fully loaded. The amount of concurrent loads and instantiations per frame is limited,
+/// which allows you to spread the work over multiple frames.
+///
+/// ## Unloading
+///
+/// A cell beyond its unload radius is removed from the scene, the farthest cells are removed
+/// first. Handles of the nodes of the cell remain reserved in the graph, and when the cell is
+/// loaded again, its nodes are put at the same handles. This means that handles to the nodes of a
+/// cell, stored in scripts, are invalid while the cell is unloaded, but become valid again when
+/// the cell is loaded back. It is still recommended to check the handles using
+/// `try_get` before use. The state of unloaded nodes is not preserved, the nodes are
+/// re-created from the model. Nodes that were added to the instance of a cell at runtime are lost
+/// on unloading.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox_impl::{
+///     asset::manager::ResourceManager,
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{
+///         node::Node,
+///         streaming::{SceneStreamer, StreamingCell, StreamingEvent},
+///         Scene,
+///     },
+/// };
+///
+/// fn create_streamer(camera: Handle<Node>) -> SceneStreamer {
+///     let mut streamer = SceneStreamer::new(camera);
+///     streamer.add_cell(StreamingCell::new(
+///         "data/level/cell_0_0.rgs",
+///         Vector3::new(0.0, 0.0, 0.0),
+///         100.0,
+///     ));
+///     streamer.add_cell(
+///         StreamingCell::new("data/level/cell_1_0.rgs", Vector3::new(200.0, 0.0, 0.0), 100.0)
+///             .with_priority(1),
+///     );
+///     streamer
+/// }
+///
+/// fn update(streamer: &mut SceneStreamer, scene: &mut Scene, resource_manager: &ResourceManager) {
+///     for event in streamer.update(scene, resource_manager) {
+///         if let StreamingEvent::Loaded { root, .. } = event {
+///             println!("{} is loaded!", scene.graph[root].name());
+///         }
+///     }
+/// }
+/// ```
+///
+/// ## Cleanup
+///
+/// Reserved handles are bound to the graph in which they were reserved. Call
+/// [`SceneStreamer::clear`] before destroying the streamer, if its scene continues to exist.
+/// Otherwise, the reserved handles will be leaked.
+pub struct SceneStreamer {
+    source: Handle<Node>,
+    cells: Pool<StreamingCell>,
+    max_concurrent_loads: usize,
+    max_instantiations_per_frame: usize,
+    max_unloads_per_frame: usize,
+}
+
+impl SceneStreamer {
+    /// Creates a new streamer, that uses the position of the given node as a streaming source.
+    pub fn new(source: Handle<Node>) -> Self {
+        Self {
+            source,
+            cells: Default::default(),
+            max_concurrent_loads: 4,
+            max_instantiations_per_frame: 1,
+            max_unloads_per_frame: 1,
+        }
+    }
+
+    /// Sets a new streaming source.
+    pub fn set_source(&mut self, source: Handle<Node>) {
+        self.source = source;
+    }
+
+    /// Returns current streaming source.
+    pub fn source(&self) -> Handle<Node> {
+        self.source
+    }
+
+    /// Sets the max amount of cells, that could be loaded at the same time.
+    pub fn set_max_concurrent_loads(&mut self, amount: usize) {
+        self.max_concurrent_loads = amount.max(1);
+    }
+
+    /// Returns the max amount of cells, that could be loaded at the same time.
+    pub fn max_concurrent_loads(&self) -> usize {
+        self.max_concurrent_loads
+    }
+
+    /// Sets the max amount of cells, that could be instantiated in a single [`Self::update`] call.
+    pub fn set_max_instantiations_per_frame(&mut self, amount: usize) {
+        self.max_instantiations_per_frame = amount.max(1);
+    }
+
+    /// Returns the max amount of cells, that could be instantiated in a single [`Self::update`]
+    /// call.
+    pub fn max_instantiations_per_frame(&self) -> usize {
+        self.max_instantiations_per_frame
+    }
+
+    /// Sets the max amount of cells, that could be unloaded in a single [`Self::update`] call.
+    pub fn set_max_unloads_per_frame(&mut self, amount: usize) {
+        self.max_unloads_per_frame = amount.max(1);
+    }
+
+    /// Returns the max amount of cells, that could be unloaded in a single [`Self::update`] call.
+    pub fn max_unloads_per_frame(&self) -> usize {
+        self.max_unloads_per_frame
+    }
+
+    /// Adds a new cell to the streamer. The cell will be loaded on the next [`Self::update`] call,
+    /// if the streaming source is close enough.
+    pub fn add_cell(&mut self, cell: StreamingCell) -> Handle<StreamingCell> {
+        self.cells.spawn(cell)
+    }
+
+    /// Removes the cell from the streamer and from the scene.
+    pub fn remove_cell(&mut self, cell: Handle<StreamingCell>, scene: &mut Scene) {
+        if let Some(mut cell) = self.cells.try_free(cell) {
+            let root = cell.root();
+            if scene.graph.is_valid_handle(root) {
+                scene.graph.remove_node(root);
+            }
+            cell.reserved.release(&mut scene.graph);
+        }
+    }
+
+    /// Returns a reference to the cell.
+    pub fn cell(&self, cell: Handle<StreamingCell>) -> Option<&StreamingCell> {
+        self.cells.try_borrow(cell)
+    }
+
+    /// Returns an iterator over all the cells and their handles.
+    pub fn cells(&self) -> impl Iterator<Item = (Handle<StreamingCell>, &StreamingCell)> {
+        self.cells.pair_iter()
+    }
+
+    /// Removes all the cells from the streamer and from the scene and releases all the reserved
+    /// handles.
+    pub fn clear(&mut self, scene: &mut Scene) {
+        let handles = self.cells.pair_iter().map(|(h, _)| h).collect::<Vec<_>>();
+        for handle in handles {
+            self.remove_cell(handle, scene);
+        }
+    }
+
+    /// Loads and unloads cells depending on their distance to the streaming source. This method
+    /// should be called every frame. Returns a list of events, that happened during the update.
+    pub fn update(
+        &mut self,
+        scene: &mut Scene,
+        resource_manager: &ResourceManager,
+    ) -> Vec<StreamingEvent> {
+        let mut events = Vec::new();
+
+        let Some(source) = scene.graph.try_get(self.source) else {
+            return events;
+        };
+        let source_position = source.global_position();
+
+        let mut load_queue = BinaryHeap::new();
+        let mut unload_queue = BinaryHeap::new();
+        let mut loading_count = 0;
+        for (handle, cell) in self.cells.pair_iter_mut() {
+            let distance = cell.center.metric_distance(&source_position);
+            let entry = QueueEntry {
+                priority: cell.priority,
+                distance,
+                cell: handle,
+            };
+            match cell.state {
+                CellState::Unloaded if distance <= cell.load_radius => load_queue.push(entry),
+                CellState::Loading { .. } if distance > cell.unload_radius() => {
+                    // Cancel loading, the model will be dropped if it is not used elsewhere.
+                    cell.state = CellState::Unloaded;
+                }
+                CellState::Loading { .. } => {
+                    loading_count += 1;
+                    load_queue.push(entry);
+                }
+                CellState::Loaded { .. } if distance > cell.unload_radius() => {
+                    // The farthest cells are unloaded first.
+                    unload_queue.push(QueueEntry {
+                        priority: 0,
+                        distance: -distance,
+                        cell: handle,
+                    })
+                }
+                _ => (),
+            }
+        }
+
+        for entry in std::iter::from_fn(|| unload_queue.pop()).take(self.max_unloads_per_frame) {
+            self.cells[entry.cell].unload(&mut scene.graph);
+            events.push(StreamingEvent::Unloaded { cell: entry.cell });
+        }
+
+        let mut instantiation_count = 0;
+        while let Some(entry) = load_queue.pop() {
+            let cell = &mut self.cells[entry.cell];
+            match cell.state {
+                CellState::Unloaded if loading_count < self.max_concurrent_loads => {
+                    loading_count += 1;
+                    cell.state = CellState::Loading {
+                        model: resource_manager.request::<Model>(&cell.model),
+                        dependencies: None,
+                    };
+                }
+                CellState::Loading {
+                    ref model,
+                    ref mut dependencies,
+                } => {
+                    if model.is_loading() {
+                        continue;
+                    }
+
+                    if !model.is_ok() {
+                        Log::err(format!(
+                            "Unable to load streaming cell {}!",
+                            cell.model.display()
+                        ));
+                        cell.state = CellState::Failed;
+                        events.push(StreamingEvent::Failed { cell: entry.cell });
+                        continue;
+                    }
+
+                    let dependencies = dependencies.get_or_insert_with(|| {
+                        ResourceWaitContext::new(
+                            model.data_ref().get_scene().collect_used_resources(),
+                        )
+                    });
+
+                    if dependencies.is_all_loaded()
+                        && instantiation_count < self.max_instantiations_per_frame
+                    {
+                        instantiation_count += 1;
+                        let model = model.clone();
+                        let root = cell.instantiate(&model, &mut scene.graph);
+                        cell.state = CellState::Loaded { root };
+                        events.push(StreamingEvent::Loaded {
+                            cell: entry.cell,
+                            root,
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::manager::ResourceManager,
+        core::{algebra::Vector3, visitor::Visitor},
+        engine::{self, SerializationContext},
+        graph::{BaseSceneGraph, SceneGraph},
+        scene::{
+            base::BaseBuilder,
+            pivot::PivotBuilder,
+            streaming::{SceneStreamer, StreamingCell, StreamingCellState, StreamingEvent},
+            Scene,
+        },
+    };
+    use std::{sync::Arc, time::Duration};
+
+    fn update_until(
+        streamer: &mut SceneStreamer,
+        scene: &mut Scene,
+        resource_manager: &ResourceManager,
+        mut predicate: impl FnMut(&StreamingEvent) -> bool,
+    ) -> StreamingEvent {
+        for _ in 0..1000 {
+            if let Some(event) = streamer
+                .update(scene, resource_manager)
+                .into_iter()
+                .find(&mut predicate)
+            {
+                return event;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("Streaming timed out!")
+    }
+
+    #[test]
+    fn test_scene_streaming_preserves_handles() {
+        let path =
+            &std::env::temp_dir().join(format!("fyrox_streaming_cell_{}.rgs", std::process::id()));
+
+        let mut cell_scene = Scene::new();
+        let child =
+            PivotBuilder::new(BaseBuilder::new().with_name("Child")).build(&mut cell_scene.graph);
+        PivotBuilder::new(BaseBuilder::new().with_name("Cell").with_children(&[child]))
+            .build(&mut cell_scene.graph);
+        let mut visitor = Visitor::new();
+        cell_scene.save("Scene", &mut visitor).unwrap();
+        visitor.save_binary(path).unwrap();
+
+        let resource_manager = ResourceManager::new(Arc::new(Default::default()));
+        engine::initialize_resource_manager_loaders(
+            &resource_manager,
+            Arc::new(SerializationContext::new()),
+        );
+
+        let mut scene = Scene::new();
+        let source = PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        let mut streamer = SceneStreamer::new(source);
+        let cell = streamer.add_cell(StreamingCell::new(path, Vector3::default(), 10.0));
+
+        let event = update_until(&mut streamer, &mut scene, &resource_manager, |e| {
+            matches!(e, StreamingEvent::Loaded { .. })
+        });
+        let StreamingEvent::Loaded { root, .. } = event else {
+            unreachable!()
+        };
+        let (cell_node, _) = scene.graph.find_by_name(root, "Cell").unwrap();
+        let (child, _) = scene.graph.find_by_name(root, "Child").unwrap();
+        assert_eq!(
+            streamer.cell(cell).unwrap().state(),
+            StreamingCellState::Loaded
+        );
+
+        // Move the source away.
+        scene.graph[source]
+            .local_transform_mut()
+            .set_position(Vector3::new(100.0, 0.0, 0.0));
+        scene.graph.update_hierarchical_data();
+        update_until(&mut streamer, &mut scene, &resource_manager, |e| {
+            matches!(e, StreamingEvent::Unloaded { .. })
+        });
+        assert!(scene.graph.try_get(root).is_none());
+        assert!(scene.graph.try_get(child).is_none());
+
+        // Move the source back, the cell must be loaded at the same handles.
+        scene.graph[source]
+            .local_transform_mut()
+            .set_position(Vector3::default());
+        scene.graph.update_hierarchical_data();
+        let event = update_until(&mut streamer, &mut scene, &resource_manager, |e| {
+            matches!(e, StreamingEvent::Loaded { .. })
+        });
+        assert_eq!(event, StreamingEvent::Loaded { cell, root });
+        assert_eq!(scene.graph[cell_node].name(), "Cell");
+        assert_eq!(scene.graph[cell_node].parent(), root);
+        assert_eq!(scene.graph[child].name(), "Child");
+        assert_eq!(scene.graph[child].parent(), cell_node);
+
+        streamer.clear(&mut scene);
+        assert!(scene.graph.try_get(root).is_none());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -73,7 +73,8 @@ impl TileMapEffect for TileCursorEffect {
 /// Renders borders of the given material around the given positions in the tile map.
 #[derive(Debug)]
 pub struct TileSelectionEffect {
-    /// This vector is added to the positions before rendering.
+    /// The positions are translated by this offset before rendering.
+    /// See [`TileGridLayout::translate`].
     pub offset: Option<Vector2<i32>>,
     /// The positions at which to draw the borders
     pub positions: FxHashSet<Vector2<i32>>,
@@ -86,8 +87,9 @@ pub struct TileSelectionEffect {
 impl TileMapEffect for TileSelectionEffect {
     fn render_special_tiles(&self, context: &mut TileMapRenderContext) {
         if let (Some(material), Some(offset)) = (self.material.as_ref(), self.offset) {
+            let layout = context.layout();
             for &position in self.positions.iter() {
-                let position = position + offset;
+                let position = layout.translate(offset, position);
                 push_highlight(position, material, self.thickness, context);
             }
         }
@@ -115,7 +117,8 @@ impl TileMapEffect for TileEraseEffect {
 pub struct TileOverlayEffect {
     /// True if the tiles are to be drawn. If false, then this effect does nothing.
     pub active: bool,
-    /// Vector to be added to the positions of the tiles before rendering
+    /// The offset by which the positions of the tiles are translated before rendering.
+    /// See [`TileGridLayout::translate`].
     pub offset: Vector2<i32>,
     /// The tiles to render
     pub tiles: FxHashMap<Vector2<i32>, TileDefinitionHandle>,
@@ -126,8 +129,9 @@ impl TileMapEffect for TileOverlayEffect {
         if !self.active {
             return;
         }
+        let layout = context.layout();
         for (&position, &handle) in self.tiles.iter() {
-            let position = position + self.offset;
+            let position = layout.translate(self.offset, position);
             if context.is_tile_visible(position) {
                 context.draw_tile(position, handle);
                 context.set_tile_visible(position, false);
@@ -184,32 +188,28 @@ fn push_highlight(
     ctx: &mut TileMapRenderContext,
 ) {
     let transform = ctx.transform();
-    let position = position.cast::<f32>();
-    let t = thickness;
-    let vertices = [
-        (0.0, 1.0),
-        (1.0, 1.0),
-        (1.0, 0.0),
-        (0.0, 0.0),
-        (t, 1.0 - t),
-        (1.0 - t, 1.0 - t),
-        (1.0 - t, t),
-        (t, t),
-    ]
-    .map(|(x, y)| Vector2::new(x, y))
-    .map(|p| make_highlight_vertex(transform, position + p));
+    let layout = ctx.layout();
+    let position = layout.cell_origin(position);
+    // The inner border is the cell shape that is shrunk towards the center of the cell,
+    // so for square cells the border has exactly the given thickness.
+    let center = Vector2::repeat(0.5);
+    let scale = 1.0 - 2.0 * thickness;
+    let outer = layout.cell_shape().collect::<Vec<_>>();
+    let count = outer.len() as u32;
+    let vertices = outer
+        .iter()
+        .copied()
+        .chain(outer.iter().map(|&p| center + (p - center) * scale))
+        .map(|p| make_highlight_vertex(transform, position + p))
+        .collect::<Vec<_>>();
 
-    let triangles = [
-        [0, 4, 5],
-        [0, 1, 5],
-        [1, 5, 6],
-        [1, 2, 6],
-        [2, 6, 7],
-        [2, 3, 7],
-        [3, 7, 4],
-        [3, 0, 4],
-    ]
-    .map(TriangleDefinition);
+    let triangles = (0..count)
+        .flat_map(|i| {
+            let next = (i + 1) % count;
+            [[i, count + i, count + next], [i, next, count + next]]
+        })
+        .map(TriangleDefinition)
+        .collect::<Vec<_>>();
 
     let sort_index = ctx
         .context
@@ -228,7 +228,7 @@ fn push_highlight(
             vertex_buffer.push_vertices(&vertices).unwrap();
 
             triangle_buffer
-                .push_triangles_iter_with_offset(start_vertex_index, triangles.into_iter());
+                .push_triangles_iter_with_offset(start_vertex_index, triangles.iter().copied());
         },
     );
 }
@@ -239,12 +239,16 @@ fn push_cursor(
     ctx: &mut TileMapRenderContext,
 ) {
     let transform = ctx.transform();
-    let position = position.cast::<f32>();
-    let vertices = [(0.0, 1.0), (1.0, 1.0), (1.0, 0.0), (0.0, 0.0)]
-        .map(|(x, y)| Vector2::new(x, y))
-        .map(|p| make_highlight_vertex(transform, position + p));
+    let layout = ctx.layout();
+    let position = layout.cell_origin(position);
+    let vertices = layout
+        .cell_shape()
+        .map(|p| make_highlight_vertex(transform, position + p))
+        .collect::<Vec<_>>();
 
-    let triangles = [[0, 1, 2], [0, 2, 3]].map(TriangleDefinition);
+    let triangles = (1..vertices.len() as u32 - 1)
+        .map(|i| TriangleDefinition([0, i, i + 1]))
+        .collect::<Vec<_>>();
 
     let sort_index = ctx.context.calculate_sorting_index(ctx.position());
 
@@ -260,7 +264,7 @@ fn push_cursor(
             vertex_buffer.push_vertices(&vertices).unwrap();
 
             triangle_buffer
-                .push_triangles_iter_with_offset(start_vertex_index, triangles.into_iter());
+                .push_triangles_iter_with_offset(start_vertex_index, triangles.iter().copied());
         },
     );
 }
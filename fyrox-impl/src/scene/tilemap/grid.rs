@@ -0,0 +1,386 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Grid layouts of a tile map. A [`TileGridLayout`] defines where each cell of a tile map is
+//! located in the local space of the tile map, the shape of the cells, and how a local-space
+//! point is converted back into cell coordinates.
+//!
+//! Whatever the layout is, the bounding box of every cell is a unit square, and tiles are rendered
+//! to fill that square. This means that a tile set that was made for an orthogonal grid could be
+//! used as-is with any other layout, and the proportions of the cells could be adjusted by scaling
+//! the tile map node. For example, the classic 2:1 isometric look could be achieved by scaling
+//! an isometric tile map by `0.5` along its Y axis.
+//!
+//! Staggered and hexagonal layouts shift every odd row (or column) by a half of a cell, so a
+//! plain addition of two cell positions does not preserve the shape of a group of cells. Use
+//! [`TileGridLayout::translate`] and [`TileGridLayout::offset`] to move groups of cells around,
+//! for example when a brush is stamped on a tile map.
+
+use crate::core::{
+    algebra::{Matrix4, Point2, Point3, Vector2, Vector3},
+    reflect::prelude::*,
+    type_traits::prelude::*,
+    visitor::prelude::*,
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+use super::{OptionTileRect, TileCollider, TileRect};
+
+const ORTHOGONAL_SHAPE: [(f32, f32); 4] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+const DIAMOND_SHAPE: [(f32, f32); 4] = [(0.5, 0.0), (1.0, 0.5), (0.5, 1.0), (0.0, 0.5)];
+const POINTY_HEX_SHAPE: [(f32, f32); 6] = [
+    (0.5, 0.0),
+    (1.0, 0.25),
+    (1.0, 0.75),
+    (0.5, 1.0),
+    (0.0, 0.75),
+    (0.0, 0.25),
+];
+const FLAT_HEX_SHAPE: [(f32, f32); 6] = [
+    (0.25, 0.0),
+    (0.75, 0.0),
+    (1.0, 0.5),
+    (0.75, 1.0),
+    (0.25, 1.0),
+    (0.0, 0.5),
+];
+
+/// The arrangement of the cells of a tile map. See [module docs](self) for more info.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "f87fa5e6-ff18-40dd-a92c-bdf0b2e09a25")]
+pub enum TileGridLayout {
+    /// Square cells arranged in rows and columns. Cell `(x, y)` occupies the square from `(x, y)`
+    /// to `(x + 1, y + 1)`.
+    #[default]
+    Orthogonal,
+    /// Diamond-shaped cells, where the X axis of the grid goes up and to the right and
+    /// the Y axis of the grid goes up and to the left, so the whole map forms a big diamond.
+    Isometric,
+    /// Diamond-shaped cells arranged in rows, where every odd row is shifted by a half of a cell to
+    /// the right, so the whole map forms a rectangle. Rows are a half of a cell apart.
+    StaggeredIsometric,
+    /// Hexagonal cells with a vertex at the top, arranged in rows, where every odd row is shifted
+    /// by a half of a cell to the right. Rows are three quarters of a cell apart.
+    HexagonalPointyTop,
+    /// Hexagonal cells with a flat edge at the top, arranged in columns, where every odd column
+    /// is shifted by a half of a cell upwards. Columns are three quarters of a cell apart.
+    HexagonalFlatTop,
+}
+
+#[inline]
+fn parity(v: i32) -> i32 {
+    v & 1
+}
+
+/// Picks a cell of a hexagonal grid with rows along the `minor` axis, which are staggered along
+/// the `major` axis. Returns `(minor, major)` cell coordinates.
+fn pick_hex(minor: f32, major: f32) -> (i32, i32) {
+    let row = (major / 0.75).floor() as i32;
+    let mut result = (0, row);
+    let mut best = f32::MAX;
+    // A point within a row band could only belong to the row itself or the row below it.
+    for r in [row - 1, row] {
+        let shift = 0.5 * parity(r) as f32;
+        let c = (minor - shift).floor() as i32;
+        let d_minor = minor - (c as f32 + shift + 0.5);
+        let d_major = major - (0.75 * r as f32 + 0.5);
+        let distance = d_major.abs() + 0.5 * d_minor.abs();
+        if distance < best {
+            best = distance;
+            result = (c, r);
+        }
+    }
+    result
+}
+
+impl TileGridLayout {
+    /// True if this is [`TileGridLayout::Orthogonal`] layout.
+    #[inline]
+    pub fn is_orthogonal(&self) -> bool {
+        matches!(self, Self::Orthogonal)
+    }
+
+    /// The position of the bottom-left corner of the bounding box of the given cell, in the local
+    /// space of the tile map. The bounding box is always a unit square.
+    pub fn cell_origin(&self, cell: Vector2<i32>) -> Vector2<f32> {
+        let (x, y) = (cell.x as f32, cell.y as f32);
+        match self {
+            Self::Orthogonal => Vector2::new(x, y),
+            Self::Isometric => Vector2::new(0.5 * (x - y), 0.5 * (x + y)),
+            Self::StaggeredIsometric => Vector2::new(x + 0.5 * parity(cell.y) as f32, 0.5 * y),
+            Self::HexagonalPointyTop => Vector2::new(x + 0.5 * parity(cell.y) as f32, 0.75 * y),
+            Self::HexagonalFlatTop => Vector2::new(0.75 * x, y + 0.5 * parity(cell.x) as f32),
+        }
+    }
+
+    /// The position of the center of the given cell, in the local space of the tile map.
+    #[inline]
+    pub fn cell_center(&self, cell: Vector2<i32>) -> Vector2<f32> {
+        self.cell_origin(cell).add_scalar(0.5)
+    }
+
+    /// The vertices of the cell shape relative to the bottom-left corner of the cell's bounding
+    /// box, in counter-clockwise order.
+    pub fn cell_shape(&self) -> impl Iterator<Item = Vector2<f32>> {
+        let shape: &'static [(f32, f32)] = match self {
+            Self::Orthogonal => &ORTHOGONAL_SHAPE,
+            Self::Isometric | Self::StaggeredIsometric => &DIAMOND_SHAPE,
+            Self::HexagonalPointyTop => &POINTY_HEX_SHAPE,
+            Self::HexagonalFlatTop => &FLAT_HEX_SHAPE,
+        };
+        shape.iter().map(|&(x, y)| Vector2::new(x, y))
+    }
+
+    /// The vertices of the given cell in the local space of the tile map, in counter-clockwise order.
+    pub fn cell_polygon(&self, cell: Vector2<i32>) -> Vec<Vector2<f32>> {
+        let origin = self.cell_origin(cell);
+        self.cell_shape().map(|v| origin + v).collect()
+    }
+
+    /// Finds the cell that contains the given point in the local space of the tile map.
+    pub fn local_to_cell(&self, point: Vector2<f32>) -> Vector2<i32> {
+        match self {
+            Self::Orthogonal => Vector2::new(point.x.floor() as i32, point.y.floor() as i32),
+            Self::Isometric => Vector2::new(
+                (point.x + point.y - 1.0).round() as i32,
+                (point.y - point.x).round() as i32,
+            ),
+            Self::StaggeredIsometric => {
+                let diamond = Self::Isometric.local_to_cell(point);
+                Self::diamond_to_staggered(diamond)
+            }
+            Self::HexagonalPointyTop => {
+                let (x, y) = pick_hex(point.x, point.y);
+                Vector2::new(x, y)
+            }
+            Self::HexagonalFlatTop => {
+                let (y, x) = pick_hex(point.y, point.x);
+                Vector2::new(x, y)
+            }
+        }
+    }
+
+    fn staggered_to_diamond(cell: Vector2<i32>) -> Vector2<i32> {
+        let p = parity(cell.y);
+        Vector2::new(cell.x + (cell.y + p) / 2, (cell.y - p) / 2 - cell.x)
+    }
+
+    fn diamond_to_staggered(cell: Vector2<i32>) -> Vector2<i32> {
+        let y = cell.x + cell.y;
+        Vector2::new((cell.x - cell.y - parity(y)) / 2, y)
+    }
+
+    /// Converts a cell position to a coordinate system where a translation of a group of cells
+    /// is a plain vector addition.
+    fn cell_to_linear(self, cell: Vector2<i32>) -> Vector2<i32> {
+        match self {
+            Self::Orthogonal | Self::Isometric => cell,
+            Self::StaggeredIsometric => Self::staggered_to_diamond(cell),
+            Self::HexagonalPointyTop => {
+                Vector2::new(cell.x - (cell.y - parity(cell.y)) / 2, cell.y)
+            }
+            Self::HexagonalFlatTop => Vector2::new(cell.x, cell.y - (cell.x - parity(cell.x)) / 2),
+        }
+    }
+
+    fn linear_to_cell(self, cell: Vector2<i32>) -> Vector2<i32> {
+        match self {
+            Self::Orthogonal | Self::Isometric => cell,
+            Self::StaggeredIsometric => Self::diamond_to_staggered(cell),
+            Self::HexagonalPointyTop => {
+                Vector2::new(cell.x + (cell.y - parity(cell.y)) / 2, cell.y)
+            }
+            Self::HexagonalFlatTop => Vector2::new(cell.x, cell.y + (cell.x - parity(cell.x)) / 2),
+        }
+    }
+
+    /// Moves the given cell by the given offset, so that a group of cells keeps its shape.
+    /// The offset is the position of the target cell relative to the cell `(0, 0)`. For orthogonal
+    /// and isometric layouts this is the same as `cell + offset`.
+    pub fn translate(&self, cell: Vector2<i32>, offset: Vector2<i32>) -> Vector2<i32> {
+        self.linear_to_cell(self.cell_to_linear(cell) + self.cell_to_linear(offset))
+    }
+
+    /// Calculates the offset that moves `from` cell to `to` cell, so that
+    /// `self.translate(from, self.offset(from, to)) == to`.
+    pub fn offset(&self, from: Vector2<i32>, to: Vector2<i32>) -> Vector2<i32> {
+        self.linear_to_cell(self.cell_to_linear(to) - self.cell_to_linear(from))
+    }
+
+    /// Calculates a rectangle of cell positions that contains every cell that intersects with
+    /// the given rectangle in the local space of the tile map.
+    pub fn cells_in_local_rect(&self, min: Vector2<f32>, max: Vector2<f32>) -> OptionTileRect {
+        let mut rect = OptionTileRect::default();
+        for corner in [
+            min,
+            Vector2::new(max.x, min.y),
+            max,
+            Vector2::new(min.x, max.y),
+        ] {
+            rect.push(self.local_to_cell(corner));
+        }
+        if self.is_orthogonal() {
+            rect
+        } else {
+            rect.map(|r| r.inflate(1, 1)).into()
+        }
+    }
+
+    /// Calculates the local-space bounding box of all cells within the given rectangle of cell
+    /// positions. Returns the minimum and the maximum corners of the box.
+    pub fn local_bounds(&self, rect: TileRect) -> (Vector2<f32>, Vector2<f32>) {
+        let mut min = Vector2::repeat(f32::MAX);
+        let mut max = Vector2::repeat(f32::MIN);
+        let last = rect.position + rect.size - Vector2::repeat(1);
+        for corner in [
+            rect.position,
+            Vector2::new(last.x, rect.position.y),
+            last,
+            Vector2::new(rect.position.x, last.y),
+        ] {
+            let origin = self.cell_origin(corner);
+            min = min.inf(&origin);
+            max = max.sup(&origin.add_scalar(1.0));
+        }
+        if !self.is_orthogonal() {
+            // Staggered rows and columns could stick out by a half of a cell.
+            min.add_scalar_mut(-0.5);
+            max.add_scalar_mut(0.5);
+        }
+        (min, max)
+    }
+
+    /// Generates the mesh of the given tile collider for the given cell. `transform` maps
+    /// the local space of the tile map to the desired space of the mesh. A rectangle collider
+    /// fills the whole cell shape, while custom colliders are stretched over the bounding box of
+    /// the cell, just as the tiles are.
+    pub fn build_collider_shape(
+        &self,
+        collider: &TileCollider,
+        transform: &Matrix4<f32>,
+        cell: Vector2<i32>,
+        vertices: &mut Vec<Point2<f32>>,
+        triangles: &mut Vec<[u32; 3]>,
+    ) {
+        let origin = self.cell_origin(cell);
+        if collider.is_rectangle() && !self.is_orthogonal() {
+            let start = vertices.len() as u32;
+            for v in self.cell_shape() {
+                let point = Point3::new(origin.x + v.x, origin.y + v.y, 1.0);
+                vertices.push(transform.transform_point(&point).xy());
+            }
+            let count = vertices.len() as u32 - start;
+            for i in 1..count - 1 {
+                triangles.push([start, start + i, start + i + 1]);
+            }
+        } else {
+            collider.build_collider_shape(
+                transform,
+                Vector3::new(origin.x, origin.y, 1.0),
+                vertices,
+                triangles,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::VariantNames;
+
+    fn all_layouts() -> impl Iterator<Item = TileGridLayout> {
+        TileGridLayout::VARIANTS
+            .iter()
+            .map(|name| name.parse::<TileGridLayout>().unwrap())
+    }
+
+    fn is_inside(polygon: &[Vector2<f32>], point: Vector2<f32>) -> bool {
+        (0..polygon.len()).all(|i| {
+            let a = polygon[i];
+            let b = polygon[(i + 1) % polygon.len()];
+            (b - a).perp(&(point - a)) >= 0.0
+        })
+    }
+
+    #[test]
+    fn test_local_to_cell() {
+        for layout in all_layouts() {
+            for cell in TileRect::new(-3, -3, 7, 7).iter() {
+                let polygon = layout.cell_polygon(cell);
+                let center = layout.cell_center(cell);
+                assert_eq!(layout.local_to_cell(center), cell, "{layout:?}");
+                assert!(is_inside(&polygon, center), "{layout:?}");
+                // Points near the vertices must still be inside the cell.
+                for v in polygon.iter() {
+                    let point = v.lerp(&center, 0.05);
+                    assert_eq!(layout.local_to_cell(point), cell, "{layout:?} {point:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_translate() {
+        for layout in all_layouts() {
+            let shape = [Vector2::new(0, 0), Vector2::new(1, 0), Vector2::new(0, 1)];
+            for origin in TileRect::new(-3, -3, 7, 7).iter() {
+                let moved = shape.map(|p| layout.translate(origin, p));
+                // The shape must be moved rigidly in the local space.
+                let delta = layout.cell_center(moved[0]) - layout.cell_center(shape[0]);
+                for (p, m) in shape.iter().zip(moved.iter()) {
+                    let diff = layout.cell_center(*m) - layout.cell_center(*p) - delta;
+                    assert!(diff.norm() < 1.0e-4, "{layout:?}");
+                }
+                for p in shape {
+                    let target = layout.translate(origin, p);
+                    assert_eq!(layout.offset(origin, target), p, "{layout:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cells_in_local_rect() {
+        for layout in all_layouts() {
+            let rect = layout.cells_in_local_rect(Vector2::new(-2.0, -2.0), Vector2::new(3.0, 3.0));
+            for cell in TileRect::new(-10, -10, 21, 21).iter() {
+                let center = layout.cell_center(cell);
+                if center.x > -2.0 && center.x < 3.0 && center.y > -2.0 && center.y < 3.0 {
+                    assert!(rect.contains(cell), "{layout:?} {cell:?}");
+                }
+            }
+        }
+    }
+}
@@ -24,7 +24,9 @@
 pub mod brush;
 mod data;
 mod effect;
+mod grid;
 mod property;
+mod terrain;
 mod tile_collider;
 mod tile_rect;
 mod tile_source;
@@ -35,12 +37,14 @@ mod update;
 use brush::*;
 pub use data::*;
 pub use effect::*;
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
 use fyrox_core::{
-    math::{frustum::Frustum, plane::Plane, ray::Ray},
+    math::{frustum::Frustum, plane::Plane, ray::Ray, segment::LineSegment2, Rect},
     parking_lot::Mutex,
 };
 use fyrox_resource::Resource;
+pub use grid::*;
+pub use terrain::*;
 pub use tile_collider::*;
 pub use tile_rect::*;
 pub use tile_source::*;
@@ -51,7 +55,7 @@ pub use update::*;
 use crate::{
     asset::{untyped::ResourceKind, ResourceDataRef},
     core::{
-        algebra::{Matrix4, Vector2, Vector3},
+        algebra::{Matrix4, Point3, Vector2, Vector3},
         color::Color,
         math::{aabb::AxisAlignedBoundingBox, Matrix4Ext, TriangleDefinition},
         pool::Handle,
@@ -112,6 +116,8 @@ pub struct TileMapRenderContext<'a, 'b> {
     transform: Matrix4<f32>,
     /// The visible tile positions.
     bounds: OptionTileRect,
+    /// The layout of the cells of the TileMap.
+    layout: TileGridLayout,
     hidden_tiles: &'a mut FxHashSet<Vector2<i32>>,
     tile_set: OptionTileSet<'a>,
 }
@@ -133,6 +139,10 @@ impl TileMapRenderContext<'_, '_> {
     pub fn visible_bounds(&self) -> OptionTileRect {
         self.bounds
     }
+    /// The layout of the cells of the [`TileMap`]
+    pub fn layout(&self) -> TileGridLayout {
+        self.layout
+    }
     /// Set a position to false in order to prevent later effects from rendering
     /// a tile at this position. All positions are true by default.
     /// Normally, once a tile has been rendered at a position, the position
@@ -185,7 +195,7 @@ impl TileMapRenderContext<'_, '_> {
     }
 
     fn push_color_tile(&mut self, position: Vector2<i32>, color: Color) {
-        let position = position.cast::<f32>();
+        let position = self.layout.cell_origin(position);
         let vertices = [(0.0, 1.0), (1.0, 1.0), (1.0, 0.0), (0.0, 0.0)]
             .map(|(x, y)| Vector2::new(x, y))
             .map(|p| make_rect_vertex(&self.transform, position + p, color));
@@ -218,7 +228,7 @@ impl TileMapRenderContext<'_, '_> {
         bounds: &TileBounds,
         color: Color,
     ) {
-        let position = position.cast::<f32>();
+        let position = self.layout.cell_origin(position);
         let uvs = [
             bounds.right_top_corner,
             bounds.left_top_corner,
@@ -893,7 +903,14 @@ pub struct TileMap {
     #[reflect(hidden)]
     pub tiles: InheritableVariable<Option<TileMapDataResource>>,
     tile_scale: InheritableVariable<Vector2<f32>>,
+    /// The arrangement of the cells of the tile map. See [`TileGridLayout`] docs for more info.
+    #[reflect(setter = "set_grid_layout")]
+    grid_layout: InheritableVariable<TileGridLayout>,
     active_brush: InheritableVariable<Option<TileMapBrushResource>>,
+    /// The name of a collider layer of the tile set, which is used to generate light occluders
+    /// for 2D lights. Empty name means that the tile map does not block light.
+    #[reflect(setter = "set_light_occluder_layer")]
+    light_occluder_layer: InheritableVariable<ImmutableString>,
     /// Temporary space to store which tiles are invisible during `collect_render_data`.
     /// This is part of how [`TileMapEffect`] can prevent a tile from being rendered.
     #[reflect(hidden)]
@@ -920,7 +937,11 @@ impl Visit for TileMap {
         self.base.visit("Base", &mut region)?;
         self.tile_set.visit("TileSet", &mut region)?;
         self.tile_scale.visit("TileScale", &mut region)?;
+        let _ = self.grid_layout.visit("GridLayout", &mut region);
         self.active_brush.visit("ActiveBrush", &mut region)?;
+        let _ = self
+            .light_occluder_layer
+            .visit("LightOccluderLayer", &mut region);
         match version {
             0 => {
                 let mut tiles = InheritableVariable::new_non_modified(Tiles::default());
@@ -1140,6 +1161,19 @@ impl TileMap {
         self.tile_scale.set_value_and_mark_modified(tile_scale);
     }
 
+    /// Returns the arrangement of the cells of the tile map.
+    #[inline]
+    pub fn grid_layout(&self) -> TileGridLayout {
+        *self.grid_layout
+    }
+
+    /// Sets new arrangement of the cells of the tile map. Returns the previous layout. The tiles
+    /// keep their cell positions, so they will be moved to the new places of their cells.
+    #[inline]
+    pub fn set_grid_layout(&mut self, layout: TileGridLayout) -> TileGridLayout {
+        self.grid_layout.set_value_and_mark_modified(layout)
+    }
+
     /// Inserts a tile in the tile map. Returns previous tile, located at the same position as
     /// the new one (if any).
     #[inline]
@@ -1177,6 +1211,153 @@ impl TileMap {
         self.active_brush.set_value_and_mark_modified(brush);
     }
 
+    /// Returns the name of the collider layer that is used to generate light occluders.
+    #[inline]
+    pub fn light_occluder_layer(&self) -> &ImmutableString {
+        &self.light_occluder_layer
+    }
+
+    /// Sets the name of a collider layer of the tile set, which will be used to generate light
+    /// occluders for 2D lights (see [`crate::scene::dim2::light`] module docs). Outer edges of
+    /// the colliders of every tile block the light. Empty name disables occluder generation.
+    #[inline]
+    pub fn set_light_occluder_layer(&mut self, layer: ImmutableString) -> ImmutableString {
+        self.light_occluder_layer.set_value_and_mark_modified(layer)
+    }
+
+    /// Generates world-space light occluder segments from the tile colliders of the light occluder
+    /// layer (see [`Self::set_light_occluder_layer`]). Only tiles that intersect with the given
+    /// world-space rectangle are used. Edges that are shared by two collider triangles are inner
+    /// edges and they are discarded.
+    pub fn collect_light_occluders(
+        &self,
+        bounds: Rect<f32>,
+        segments: &mut Vec<LineSegment2<f32>>,
+    ) {
+        if self.light_occluder_layer.is_empty() {
+            return;
+        }
+        let Some(tile_set) = self.tile_set.as_ref() else {
+            return;
+        };
+        let tile_set = tile_set.data_ref();
+        let Some(tile_set) = tile_set.as_loaded_ref() else {
+            return;
+        };
+        let Some(collider_uuid) = tile_set.collider_name_to_uuid(&self.light_occluder_layer) else {
+            return;
+        };
+        let Some(tiles) = self.tiles.as_ref().map(|r| r.data_ref()) else {
+            return;
+        };
+        let Some(tiles) = tiles.as_loaded_ref() else {
+            return;
+        };
+
+        let transform = self.tile_map_transform();
+        let Some(inv_transform) = transform.try_inverse() else {
+            return;
+        };
+
+        let z = self.global_position().z;
+        let mut min = Vector2::repeat(f32::MAX);
+        let mut max = Vector2::repeat(f32::MIN);
+        for corner in [
+            bounds.left_top_corner(),
+            bounds.right_top_corner(),
+            bounds.right_bottom_corner(),
+            bounds.left_bottom_corner(),
+        ] {
+            let local = inv_transform
+                .transform_point(&Point3::new(corner.x, corner.y, z))
+                .xy();
+            min = min.inf(&local.coords);
+            max = max.sup(&local.coords);
+        }
+        let layout = self.grid_layout();
+        let grid_bounds = layout.cells_in_local_rect(min, max);
+
+        let mut vertices = Vec::new();
+        let mut triangles = Vec::new();
+        for (position, handle) in tiles.bounded_iter(grid_bounds) {
+            if !grid_bounds.contains(position) {
+                continue;
+            }
+            let Some(tile_definition) = tile_set.get_tile_data(handle.into()) else {
+                continue;
+            };
+            if let Some(collider) = tile_definition.colliders.get(&collider_uuid) {
+                layout.build_collider_shape(
+                    collider,
+                    &transform,
+                    position,
+                    &mut vertices,
+                    &mut triangles,
+                );
+            }
+        }
+
+        // Vertices of adjacent tiles may differ slightly, so quantize them to find shared edges.
+        let key = |index: u32| {
+            let v = vertices[index as usize];
+            ((v.x * 1024.0).round() as i32, (v.y * 1024.0).round() as i32)
+        };
+        let mut edges = FxHashMap::default();
+        for triangle in triangles.iter() {
+            for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+                let (a, b) = (triangle[a], triangle[b]);
+                let (key_a, key_b) = (key(a), key(b));
+                let edge_key = if key_a < key_b {
+                    (key_a, key_b)
+                } else {
+                    (key_b, key_a)
+                };
+                edges
+                    .entry(edge_key)
+                    .and_modify(|(count, _)| *count += 1)
+                    .or_insert((1, (a, b)));
+            }
+        }
+
+        segments.extend(
+            edges
+                .into_values()
+                .filter(|(count, _)| *count == 1)
+                .map(|(_, (a, b))| {
+                    LineSegment2::new(&vertices[a as usize].coords, &vertices[b as usize].coords)
+                }),
+        );
+    }
+
+    /// Paints the given terrain at the given cells and automatically chooses tiles for the painted
+    /// cells and their neighbours, using the terrain settings of the tile set (see [`TileSetTerrain`]).
+    /// Terrain `0` erases the terrain tiles at the given cells. Returns `false` if the tile set or the
+    /// tiles are not loaded, or the tile set has no terrain property.
+    pub fn paint_terrain(
+        &mut self,
+        positions: impl IntoIterator<Item = Vector2<i32>>,
+        terrain: i8,
+    ) -> bool {
+        let Some(tile_set) = self.tile_set.as_ref() else {
+            return false;
+        };
+        let mut tile_set = TileSetRef::new(tile_set);
+        let tile_set = tile_set.as_loaded();
+        let Some(autotiler) = tile_set.as_ref().and_then(TerrainAutotiler::new) else {
+            return false;
+        };
+        let Some(mut tiles) = self.tiles.as_ref().map(|r| r.data_ref()) else {
+            return false;
+        };
+        let Some(tiles) = tiles.as_loaded_mut() else {
+            return false;
+        };
+        let mut update = TransTilesUpdate::default();
+        update.draw_terrain(&*tiles, &autotiler, positions, terrain);
+        tiles.swap_tiles(&mut update.build_tiles_update(&tile_set));
+        true
+    }
+
     /// Calculates bounding rectangle in grid coordinates.
     #[inline]
     pub fn bounding_rect(&self) -> OptionTileRect {
@@ -1191,21 +1372,35 @@ impl TileMap {
 
     /// Calculates grid-space position (tile coordinates) from world-space. Could be used to find
     /// tile coordinates from arbitrary point in world space. It is especially useful, if the tile
-    /// map is rotated or shifted.
+    /// map is rotated or shifted. The grid layout of the tile map is taken into account, so the
+    /// result is the cell whose shape contains the point.
     #[inline]
     pub fn world_to_grid(&self, world_position: Vector3<f32>) -> Vector2<i32> {
         let inv_global_transform = self.tile_map_transform().try_inverse().unwrap_or_default();
         let local_space_position = inv_global_transform.transform_point(&world_position.into());
-        Vector2::new(
-            local_space_position.x.floor() as i32,
-            local_space_position.y.floor() as i32,
-        )
+        self.grid_layout()
+            .local_to_cell(local_space_position.coords.xy())
     }
 
-    /// Calculates world-space position from grid-space position (tile coordinates).
+    /// Calculates world-space position from grid-space position (tile coordinates). The result is
+    /// the corner of the cell's bounding box, that has the minimal local coordinates.
     #[inline]
     pub fn grid_to_world(&self, grid_position: Vector2<i32>) -> Vector3<f32> {
-        let v3 = grid_position.cast::<f32>().to_homogeneous();
+        let v3 = self
+            .grid_layout()
+            .cell_origin(grid_position)
+            .to_homogeneous();
+        self.tile_map_transform().transform_point(&v3.into()).coords
+    }
+
+    /// Calculates world-space position of the center of the cell at the given grid-space position
+    /// (tile coordinates).
+    #[inline]
+    pub fn grid_center_to_world(&self, grid_position: Vector2<i32>) -> Vector3<f32> {
+        let v3 = self
+            .grid_layout()
+            .cell_center(grid_position)
+            .to_homogeneous();
         self.tile_map_transform().transform_point(&v3.into()).coords
     }
 
@@ -1252,11 +1447,15 @@ impl TileMap {
         let Some(right_bottom) = right_bottom_ray.plane_intersection_point(&plane) else {
             return None.into();
         };
-        let mut bounds = OptionTileRect::default();
+        let inv_transform = self.tile_map_transform().try_inverse().unwrap_or_default();
+        let mut min = Vector2::repeat(f32::MAX);
+        let mut max = Vector2::repeat(f32::MIN);
         for corner in [left_top, right_top, left_bottom, right_bottom] {
-            bounds.push(self.world_to_grid(corner))
+            let local = inv_transform.transform_point(&corner.into()).coords.xy();
+            min = min.inf(&local);
+            max = max.sup(&local);
         }
-        bounds
+        self.grid_layout().cells_in_local_rect(min, max)
     }
 }
 
@@ -1267,7 +1466,9 @@ impl Default for TileMap {
             tile_set: Default::default(),
             tiles: Default::default(),
             tile_scale: Vector2::repeat(1.0).into(),
+            grid_layout: Default::default(),
             active_brush: Default::default(),
+            light_occluder_layer: Default::default(),
             hidden_tiles: Mutex::default(),
             before_effects: Vec::default(),
             after_effects: Vec::default(),
@@ -1282,7 +1483,9 @@ impl Clone for TileMap {
             tile_set: self.tile_set.clone(),
             tiles: self.tiles.clone(),
             tile_scale: self.tile_scale.clone(),
+            grid_layout: self.grid_layout.clone(),
             active_brush: self.active_brush.clone(),
+            light_occluder_layer: self.light_occluder_layer.clone(),
             hidden_tiles: Mutex::default(),
             before_effects: self.before_effects.clone(),
             after_effects: self.after_effects.clone(),
@@ -1322,8 +1525,9 @@ impl NodeTrait for TileMap {
             return AxisAlignedBoundingBox::default();
         };
 
-        let mut min_pos = rect.position.cast::<f32>().to_homogeneous();
-        let mut max_pos = (rect.position + rect.size).cast::<f32>().to_homogeneous();
+        let (min_pos, max_pos) = self.grid_layout().local_bounds(rect);
+        let mut min_pos = min_pos.to_homogeneous();
+        let mut max_pos = max_pos.to_homogeneous();
         min_pos.x *= -1.0;
         max_pos.x *= -1.0;
         let (min, max) = min_pos.inf_sup(&max_pos);
@@ -1371,6 +1575,7 @@ impl NodeTrait for TileMap {
             hidden_tiles: &mut hidden_tiles,
             context: ctx,
             bounds,
+            layout: self.grid_layout(),
             tile_set,
         };
 
@@ -1384,7 +1589,29 @@ impl NodeTrait for TileMap {
         let Some(tiles) = tiles.as_loaded_ref() else {
             return RdcControlFlow::Continue;
         };
-        if bounds.is_some() {
+        if !tile_render_context.layout.is_orthogonal() {
+            // Cells of other layouts overlap each other's bounding boxes, so the tiles must be
+            // drawn from the back (top) rows to the front (bottom) rows.
+            let layout = tile_render_context.layout;
+            let mut visible = if bounds.is_some() {
+                tiles
+                    .bounded_iter(bounds)
+                    .filter(|(position, _)| bounds.contains(*position))
+                    .collect::<Vec<_>>()
+            } else {
+                tiles.iter().collect::<Vec<_>>()
+            };
+            visible.sort_by(|(a, _), (b, _)| {
+                let (a, b) = (layout.cell_origin(*a), layout.cell_origin(*b));
+                b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x))
+            });
+            for (position, handle) in visible {
+                if tile_render_context.is_tile_visible(position) {
+                    let handle = tile_render_context.get_animated_version(handle);
+                    tile_render_context.draw_tile(position, handle);
+                }
+            }
+        } else if bounds.is_some() {
             for (position, handle) in tiles.bounded_iter(bounds) {
                 if bounds.contains(position) && tile_render_context.is_tile_visible(position) {
                     let handle = tile_render_context.get_animated_version(handle);
@@ -1423,6 +1650,8 @@ pub struct TileMapBuilder {
     tile_set: Option<TileSetResource>,
     tiles: TileMapData,
     tile_scale: Vector2<f32>,
+    grid_layout: TileGridLayout,
+    light_occluder_layer: ImmutableString,
     before_effects: Vec<TileMapEffectRef>,
     after_effects: Vec<TileMapEffectRef>,
 }
@@ -1435,6 +1664,8 @@ impl TileMapBuilder {
             tile_set: None,
             tiles: TileMapData::default(),
             tile_scale: Vector2::repeat(1.0),
+            grid_layout: Default::default(),
+            light_occluder_layer: Default::default(),
             before_effects: Default::default(),
             after_effects: Default::default(),
         }
@@ -1460,6 +1691,19 @@ impl TileMapBuilder {
         self
     }
 
+    /// Sets the arrangement of the cells of the tile map. See [`TileGridLayout`] docs for more info.
+    pub fn with_grid_layout(mut self, layout: TileGridLayout) -> Self {
+        self.grid_layout = layout;
+        self
+    }
+
+    /// Sets the name of the collider layer that will be used to generate light occluders. See
+    /// [`TileMap::set_light_occluder_layer`] for more info.
+    pub fn with_light_occluder_layer(mut self, layer: ImmutableString) -> Self {
+        self.light_occluder_layer = layer;
+        self
+    }
+
     /// Adds an effect to the tile map which will run before the tiles render.
     pub fn with_before_effect(mut self, effect: TileMapEffectRef) -> Self {
         self.before_effects.push(effect);
@@ -1479,7 +1723,9 @@ impl TileMapBuilder {
             tile_set: self.tile_set.into(),
             tiles: Some(Resource::new_ok(ResourceKind::Embedded, self.tiles)).into(),
             tile_scale: self.tile_scale.into(),
+            grid_layout: self.grid_layout.into(),
             active_brush: Default::default(),
+            light_occluder_layer: self.light_occluder_layer.into(),
             hidden_tiles: Mutex::default(),
             before_effects: self.before_effects,
             after_effects: self.after_effects,
@@ -0,0 +1,317 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Terrain-based autotiling. A tile set could declare a nine-slice property as its terrain property
+//! (see [`TileSetTerrain`]). The center value of the property is the terrain of the tile, and the
+//! other eight values are *peering bits* - the terrain that the tile expects to see in the neighbour
+//! cell in the respective direction. For example, a grass tile that has water to the left of it has
+//! `1` (grass) in its center and `2` (water) on the left edge and on both left corners of the property.
+//! Zero means "no terrain" and negative peering values match any terrain.
+//!
+//! When a terrain is painted on a tile map, [`TerrainAutotiler`] chooses tiles for the painted cells
+//! and all their neighbours, so the peering bits of every tile match the actual terrains around
+//! it as close as possible. If there are multiple equally good tiles, one of them is chosen randomly
+//! using tile weights, which allows to have random variants of the same tile.
+
+use crate::{
+    core::{algebra::Vector2, reflect::prelude::*, visitor::prelude::*, Uuid},
+    fxhash::FxHashMap,
+};
+
+use super::{
+    tileset::{NineI8, TileSet, TileSetPropertyValue},
+    TileDefinitionHandle,
+};
+
+/// Terrain settings of a tile set. See [module docs](self) for more info.
+#[derive(Clone, Default, Debug, PartialEq, Reflect, Visit)]
+pub struct TileSetTerrain {
+    /// UUID of a nine-slice property layer that stores terrains and peering bits of tiles.
+    /// `None` disables autotiling.
+    pub peering_property: Option<Uuid>,
+    /// UUID of an integer or float property layer that stores weights of tiles. Weights are used
+    /// to choose randomly between equally good tiles. Tiles that do not have a value for the
+    /// property have the weight of `1.0`.
+    pub weight_property: Option<Uuid>,
+}
+
+/// A tile that could be placed by [`TerrainAutotiler`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TerrainTile {
+    /// The handle of the tile in the tile set.
+    pub handle: TileDefinitionHandle,
+    /// The terrain of the tile (center) and its peering bits.
+    pub bits: NineI8,
+    /// Relative probability of the tile to be chosen among equally good tiles.
+    pub weight: f32,
+}
+
+const CENTER: usize = 4;
+
+/// Chooses tiles by the terrains around them. See [module docs](self) for more info.
+#[derive(Clone, Debug, Default)]
+pub struct TerrainAutotiler {
+    tiles: Vec<TerrainTile>,
+    terrains: FxHashMap<TileDefinitionHandle, i8>,
+}
+
+impl TerrainAutotiler {
+    /// Collects terrain tiles from the given tile set, using its terrain settings. Returns `None`
+    /// if the tile set has no terrain property.
+    pub fn new(tile_set: &TileSet) -> Option<Self> {
+        let peering_property = tile_set.terrain.peering_property?;
+        let weight_property = tile_set.terrain.weight_property;
+        let mut autotiler = Self::default();
+        for page in tile_set.page_keys() {
+            for tile in tile_set.keys_on_page(page) {
+                let Some(handle) = TileDefinitionHandle::try_new(page, tile) else {
+                    continue;
+                };
+                let Some(data) = tile_set.tile_data(handle) else {
+                    continue;
+                };
+                let Some(TileSetPropertyValue::NineSlice(bits)) =
+                    data.properties.get(&peering_property)
+                else {
+                    continue;
+                };
+                let weight = match weight_property.and_then(|uuid| data.properties.get(&uuid)) {
+                    Some(TileSetPropertyValue::I32(weight)) => *weight as f32,
+                    Some(TileSetPropertyValue::F32(weight)) => *weight,
+                    _ => 1.0,
+                };
+                autotiler.add_tile(TerrainTile {
+                    handle,
+                    bits: *bits,
+                    weight,
+                });
+            }
+        }
+        Some(autotiler)
+    }
+
+    /// Adds a new tile that could be placed by the autotiler.
+    pub fn add_tile(&mut self, tile: TerrainTile) {
+        self.terrains.insert(tile.handle, tile.bits.0[CENTER]);
+        if tile.bits.0[CENTER] != 0 {
+            self.tiles.push(tile);
+        }
+    }
+
+    /// Returns a list of all tiles known to the autotiler.
+    pub fn tiles(&self) -> &[TerrainTile] {
+        &self.tiles
+    }
+
+    /// Returns the terrain of the given tile. Tiles that do not belong to any terrain have
+    /// terrain `0`.
+    pub fn terrain_of(&self, handle: TileDefinitionHandle) -> i8 {
+        self.terrains.get(&handle).copied().unwrap_or_default()
+    }
+
+    /// Calculates how good the peering bits of a tile match the given pattern of terrains. Edges
+    /// are more important than corners. Returns `None` if the terrain of the tile does not match.
+    pub fn match_score(bits: &NineI8, pattern: &NineI8) -> Option<u32> {
+        if bits.0[CENTER] != pattern.0[CENTER] {
+            return None;
+        }
+        let mut edges = 0;
+        let mut corners = 0;
+        for (i, (bit, terrain)) in bits.0.iter().zip(pattern.0.iter()).enumerate() {
+            let score = if bit == terrain {
+                2
+            } else if *bit < 0 {
+                1
+            } else {
+                0
+            };
+            if i == CENTER {
+                continue;
+            } else if i % 2 == 1 {
+                edges += score;
+            } else {
+                corners += score;
+            }
+        }
+        // Any edge match is better than all corner matches.
+        Some(edges * 16 + corners)
+    }
+
+    /// Finds the best tile for the given pattern of terrains, where the center of the pattern is the
+    /// terrain of the cell and the other values are the terrains of its neighbours. `position` is used
+    /// to choose randomly between equally good tiles, the same position always gets the same tile.
+    /// If `current` tile is among the best tiles, it is returned, so the existing variants of tiles
+    /// are preserved.
+    pub fn find_tile(
+        &self,
+        pattern: &NineI8,
+        position: Vector2<i32>,
+        current: Option<TileDefinitionHandle>,
+    ) -> Option<TileDefinitionHandle> {
+        let mut best_score = 0;
+        let mut candidates = Vec::new();
+        for tile in self.tiles.iter() {
+            let Some(score) = Self::match_score(&tile.bits, pattern) else {
+                continue;
+            };
+            if candidates.is_empty() || score > best_score {
+                best_score = score;
+                candidates.clear();
+                candidates.push(tile);
+            } else if score == best_score {
+                candidates.push(tile);
+            }
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+        if let Some(current) = current {
+            if candidates.iter().any(|tile| tile.handle == current) {
+                return Some(current);
+            }
+        }
+        let random = position_hash(position);
+        let total_weight = candidates.iter().map(|t| t.weight.max(0.0)).sum::<f32>();
+        if total_weight <= 0.0 {
+            return Some(candidates[(random * candidates.len() as f32) as usize].handle);
+        }
+        let mut threshold = random * total_weight;
+        for tile in candidates.iter() {
+            let weight = tile.weight.max(0.0);
+            if threshold < weight {
+                return Some(tile.handle);
+            }
+            threshold -= weight;
+        }
+        candidates
+            .iter()
+            .rev()
+            .find(|t| t.weight > 0.0)
+            .map(|t| t.handle)
+    }
+}
+
+/// Maps a position to a pseudo-random number in `[0; 1)` range.
+fn position_hash(position: Vector2<i32>) -> f32 {
+    let mut hash = ((position.x as u32 as u64) << 32) | position.y as u32 as u64;
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+    (hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::tilemap::{TileMapData, TileSource, TilesUpdate, TransTilesUpdate};
+
+    const GRASS: i8 = 1;
+
+    fn h(x: i16) -> TileDefinitionHandle {
+        TileDefinitionHandle::new(0, 0, x, 0)
+    }
+
+    // Builds a "blob" of grass from a single center tile and four edge tiles. Peering bits
+    // are defined from bottom-left to top-right.
+    fn apply(tiles: &mut TileMapData, update: TransTilesUpdate) {
+        let mut tiles_update = TilesUpdate::default();
+        for (position, value) in update.iter() {
+            tiles_update.insert(*position, value.map(|(_, handle)| handle));
+        }
+        tiles.swap_tiles(&mut tiles_update);
+    }
+
+    fn make_autotiler() -> TerrainAutotiler {
+        let mut autotiler = TerrainAutotiler::default();
+        for (x, bits, weight) in [
+            // Isolated grass.
+            (0, [0, 0, 0, 0, GRASS, 0, 0, 0, 0], 1.0),
+            // Grass surrounded by grass.
+            (1, [GRASS; 9], 1.0),
+            // Rare variant of the previous one.
+            (2, [GRASS; 9], 0.0),
+            // Grass with grass to the right.
+            (3, [-1, 0, -1, 0, GRASS, GRASS, -1, 0, -1], 1.0),
+            // Grass with grass to the left.
+            (4, [-1, 0, -1, GRASS, GRASS, 0, -1, 0, -1], 1.0),
+        ] {
+            autotiler.add_tile(TerrainTile {
+                handle: h(x),
+                bits: NineI8(bits),
+                weight,
+            });
+        }
+        autotiler
+    }
+
+    #[test]
+    fn test_find_tile() {
+        let autotiler = make_autotiler();
+        let pattern = NineI8([0, 0, 0, 0, GRASS, 0, 0, 0, 0]);
+        assert_eq!(
+            autotiler.find_tile(&pattern, Vector2::new(0, 0), None),
+            Some(h(0))
+        );
+        let pattern = NineI8([GRASS; 9]);
+        // The tile with zero weight is never chosen, unless it is already there.
+        for x in 0..10 {
+            assert_eq!(
+                autotiler.find_tile(&pattern, Vector2::new(x, 0), None),
+                Some(h(1))
+            );
+        }
+        assert_eq!(
+            autotiler.find_tile(&pattern, Vector2::new(0, 0), Some(h(2))),
+            Some(h(2))
+        );
+        // No tiles for this terrain.
+        let pattern = NineI8([0, 0, 0, 0, 2, 0, 0, 0, 0]);
+        assert_eq!(
+            autotiler.find_tile(&pattern, Vector2::new(0, 0), None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_draw_terrain() {
+        let autotiler = make_autotiler();
+        let mut tiles = TileMapData::default();
+        let mut update = TransTilesUpdate::default();
+        update.draw_terrain(&tiles, &autotiler, [Vector2::new(0, 0)], GRASS);
+        apply(&mut tiles, update);
+        assert_eq!(tiles.get_at(Vector2::new(0, 0)), Some(h(0)));
+
+        // Painting next to the existing tile must update it as well.
+        let mut update = TransTilesUpdate::default();
+        update.draw_terrain(&tiles, &autotiler, [Vector2::new(1, 0)], GRASS);
+        apply(&mut tiles, update);
+        assert_eq!(tiles.get_at(Vector2::new(0, 0)), Some(h(3)));
+        assert_eq!(tiles.get_at(Vector2::new(1, 0)), Some(h(4)));
+
+        // Erasing the terrain restores the isolated tile.
+        let mut update = TransTilesUpdate::default();
+        update.draw_terrain(&tiles, &autotiler, [Vector2::new(1, 0)], 0);
+        apply(&mut tiles, update);
+        assert_eq!(tiles.get_at(Vector2::new(0, 0)), Some(h(0)));
+        assert_eq!(tiles.get_at(Vector2::new(1, 0)), None);
+    }
+}
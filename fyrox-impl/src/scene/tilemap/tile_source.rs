@@ -428,6 +428,17 @@ impl Stamp {
     pub fn build<I: Iterator<Item = (Vector2<i32>, TileDefinitionHandle)> + Clone>(
         &mut self,
         source: I,
+    ) {
+        self.build_in_layout(TileGridLayout::Orthogonal, source);
+    }
+    /// Clear this stamp and fill it with the given tiles, which are arranged using the given
+    /// grid layout. The tiles are moved so that their center is (0,0) in a way that preserves
+    /// their shape in the layout. See [`TileGridLayout::offset`].
+    /// The transform is set to identity.
+    pub fn build_in_layout<I: Iterator<Item = (Vector2<i32>, TileDefinitionHandle)> + Clone>(
+        &mut self,
+        layout: TileGridLayout,
+        source: I,
     ) {
         self.clear();
         let mut rect = OptionTileRect::default();
@@ -439,7 +450,7 @@ impl Stamp {
         };
         let center = rect.center();
         for (p, h) in source {
-            self.insert(p - center, h);
+            self.insert(layout.offset(center, p), h);
         }
     }
     /// Rotate the stamp by the given number of 90-degree turns.
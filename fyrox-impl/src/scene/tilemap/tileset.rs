@@ -1230,6 +1230,9 @@ pub struct TileSet {
     pub colliders: Vec<TileSetColliderLayer>,
     /// Property types in the order in which the layers should be presented in the editor.
     pub properties: Vec<TileSetPropertyLayer>,
+    /// Terrain settings that are used for autotiling, see [`TileSetTerrain`].
+    #[visit(optional)]
+    pub terrain: TileSetTerrain,
     /// A count of changes since last save. New changes add +1. Reverting to previous
     /// states add -1. Reverting to a state before the last save can result in negative
     /// values. Saving is unnecessary whenever this value is 0.
@@ -39,7 +39,7 @@
 
 use super::*;
 use crate::core::{algebra::Vector2, color::Color, log::Log, type_traits::prelude::*};
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use fyrox_core::swap_hash_map_entry;
 use std::{
     borrow::Cow,
@@ -674,16 +674,39 @@ impl TransTilesUpdate {
     /// Draws the given tiles on the tile map
     #[inline]
     pub fn draw_tiles(&mut self, origin: Vector2<i32>, brush: &Stamp) {
+        self.draw_tiles_in_layout(TileGridLayout::Orthogonal, origin, brush);
+    }
+    /// Draws the given tiles on the tile map, where the positions of the tiles are translated
+    /// to the origin using the given grid layout. See [`TileGridLayout::translate`].
+    pub fn draw_tiles_in_layout(
+        &mut self,
+        layout: TileGridLayout,
+        origin: Vector2<i32>,
+        brush: &Stamp,
+    ) {
         let trans = brush.transformation();
         for (local_position, handle) in brush.iter() {
-            self.insert(origin + local_position, Some((trans, *handle)));
+            self.insert(
+                layout.translate(origin, local_position),
+                Some((trans, *handle)),
+            );
         }
     }
     /// Erases the tiles under the given brush.
     #[inline]
     pub fn erase_stamp(&mut self, origin: Vector2<i32>, brush: &Stamp) {
+        self.erase_stamp_in_layout(TileGridLayout::Orthogonal, origin, brush);
+    }
+    /// Erases the tiles under the given brush, where the positions of the brush are translated
+    /// to the origin using the given grid layout. See [`TileGridLayout::translate`].
+    pub fn erase_stamp_in_layout(
+        &mut self,
+        layout: TileGridLayout,
+        origin: Vector2<i32>,
+        brush: &Stamp,
+    ) {
         for local_position in brush.keys() {
-            self.insert(origin + local_position, None);
+            self.insert(layout.translate(origin, local_position), None);
         }
     }
     /// Erases the given tile.
@@ -720,6 +743,67 @@ impl TransTilesUpdate {
         }
     }
 
+    /// Paints the given terrain at the given positions and chooses matching tiles for the painted
+    /// cells and all their neighbours using the given autotiler. Terrain `0` erases the terrain tiles.
+    /// Neighbour cells that do not belong to any terrain are left untouched. See [`TerrainAutotiler`]
+    /// docs for more info.
+    pub fn draw_terrain<T: TileSource>(
+        &mut self,
+        tiles: &T,
+        autotiler: &TerrainAutotiler,
+        positions: impl IntoIterator<Item = Vector2<i32>>,
+        terrain: i8,
+    ) {
+        let mut painted = FxHashMap::default();
+        let mut affected = FxHashSet::default();
+        for position in positions {
+            painted.insert(position, terrain);
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    affected.insert(position + Vector2::new(x, y));
+                }
+            }
+        }
+
+        let current_tile = |position: Vector2<i32>| match self.get(&position) {
+            Some(value) => value.map(|(_, handle)| handle),
+            None => tiles.get_at(position),
+        };
+        let terrain_at = |position: Vector2<i32>| {
+            painted.get(&position).copied().unwrap_or_else(|| {
+                current_tile(position)
+                    .map(|handle| autotiler.terrain_of(handle))
+                    .unwrap_or_default()
+            })
+        };
+
+        let mut changes = Vec::new();
+        for position in affected {
+            let current = current_tile(position);
+            let mut pattern = NineI8::default();
+            for (i, bit) in pattern.0.iter_mut().enumerate() {
+                let offset = TileSetPropertyValue::index_to_nine_position(i).cast::<i32>();
+                *bit = terrain_at(position + offset - Vector2::new(1, 1));
+            }
+            if pattern.0[4] == 0 {
+                if painted.contains_key(&position) && current.is_some() {
+                    changes.push((position, None));
+                }
+            } else if let Some(handle) = autotiler.find_tile(&pattern, position, current) {
+                if Some(handle) != current {
+                    changes.push((position, Some(handle)));
+                }
+            }
+        }
+
+        for (position, handle) in changes {
+            self.insert(
+                position,
+                handle.map(|handle| (OrthoTransformation::default(), handle)),
+            );
+        }
+    }
+
     /// Fills in a rectangle using special brush with 3x3 tiles. It puts
     /// corner tiles in the respective corners of the target rectangle and draws lines between each
     /// corner using middle tiles.
@@ -0,0 +1,858 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Vehicle is a physics entity that simulates a wheeled vehicle using ray-cast wheels. See
+//! [`Vehicle`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Rotation3, UnitQuaternion, Vector3},
+        math::{
+            aabb::AxisAlignedBoundingBox,
+            curve::{Curve, CurveKey, CurveKeyKind},
+            Matrix4Ext,
+        },
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    graph::{constructor::ConstructorProvider, BaseSceneGraph, SceneGraphNode},
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{constructor::NodeConstructor, Node, NodeTrait, UpdateContext},
+        rigidbody::RigidBody,
+        Scene,
+    },
+};
+use rapier3d::control::{DynamicRayCastVehicleController, WheelTuning};
+use std::{
+    fmt::{Debug, Formatter},
+    ops::{Deref, DerefMut},
+};
+
+/// A wheel of a [`Vehicle`]. Positions and directions are defined in the local coordinates of the
+/// chassis rigid body of the vehicle.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct VehicleWheel {
+    /// A point on the chassis to which the suspension of the wheel is attached.
+    pub connection_point: Vector3<f32>,
+
+    /// Direction of the suspension. The ground is searched along this direction.
+    pub direction: Vector3<f32>,
+
+    /// Rotation axis of the wheel. Together with [`Self::direction`] it defines the forward
+    /// direction of the wheel.
+    pub axle: Vector3<f32>,
+
+    /// Length of the suspension spring at rest.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub rest_length: f32,
+
+    /// Radius of the wheel.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub radius: f32,
+
+    /// Max distance the suspension can travel from its rest length in both directions.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub max_suspension_travel: f32,
+
+    /// Stiffness of the suspension spring. Increase it if the suspension is too soft to carry
+    /// the vehicle.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub suspension_stiffness: f32,
+
+    /// Damping of the suspension when it is being compressed.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub suspension_compression: f32,
+
+    /// Damping of the suspension when it is being released. Increase it if the suspension
+    /// overshoots.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub suspension_damping: f32,
+
+    /// Max force the suspension can apply to the chassis.
+    #[reflect(min_value = 0.0, step = 10.0)]
+    pub max_suspension_force: f32,
+
+    /// Defines the traction of the tire. Large values make braking and acceleration more
+    /// instant, but they could flip the vehicle.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub friction_slip: f32,
+
+    /// A multiplier for the sideways friction of the tire.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub side_friction_stiffness: f32,
+
+    /// Whether the wheel is turned by [`Vehicle::set_steering`] or not.
+    pub steered: bool,
+
+    /// Whether the wheel is driven by the engine or not.
+    pub driven: bool,
+
+    /// Whether the wheel is affected by [`Vehicle::set_brake`] or not.
+    pub braked: bool,
+
+    /// An optional node that will be moved and rotated together with the wheel. See [`Vehicle`]
+    /// docs for more info.
+    pub visual: Handle<Node>,
+}
+
+uuid_provider!(VehicleWheel = "a5b6c1f3-2f0e-4c1d-8e8a-36a8e3f0b1d2");
+
+impl Default for VehicleWheel {
+    fn default() -> Self {
+        let tuning = WheelTuning::default();
+        Self {
+            connection_point: Default::default(),
+            direction: Vector3::new(0.0, -1.0, 0.0),
+            axle: Vector3::new(-1.0, 0.0, 0.0),
+            rest_length: 0.3,
+            radius: 0.35,
+            max_suspension_travel: 0.2,
+            suspension_stiffness: 30.0,
+            suspension_compression: tuning.suspension_compression,
+            suspension_damping: tuning.suspension_damping,
+            max_suspension_force: tuning.max_suspension_force,
+            friction_slip: tuning.friction_slip,
+            side_friction_stiffness: tuning.side_friction_stiffness,
+            steered: false,
+            driven: true,
+            braked: true,
+            visual: Default::default(),
+        }
+    }
+}
+
+impl VehicleWheel {
+    /// Creates a new wheel attached to the given point of the chassis.
+    pub fn new(connection_point: Vector3<f32>) -> Self {
+        Self {
+            connection_point,
+            ..Default::default()
+        }
+    }
+
+    /// Sets whether the wheel is steered or not.
+    pub fn with_steered(mut self, steered: bool) -> Self {
+        self.steered = steered;
+        self
+    }
+
+    /// Sets whether the wheel is driven by the engine or not.
+    pub fn with_driven(mut self, driven: bool) -> Self {
+        self.driven = driven;
+        self
+    }
+
+    /// Sets whether the wheel has brakes or not.
+    pub fn with_braked(mut self, braked: bool) -> Self {
+        self.braked = braked;
+        self
+    }
+
+    /// Sets the radius of the wheel.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the rest length of the suspension.
+    pub fn with_rest_length(mut self, rest_length: f32) -> Self {
+        self.rest_length = rest_length;
+        self
+    }
+
+    /// Sets the node that will follow the wheel.
+    pub fn with_visual(mut self, visual: Handle<Node>) -> Self {
+        self.visual = visual;
+        self
+    }
+
+    /// Returns local transform of the wheel in the coordinates of the chassis for the given
+    /// suspension length, steering angle and rotation of the wheel around its axle.
+    pub fn local_transform(
+        &self,
+        suspension_length: f32,
+        steering: f32,
+        rotation: f32,
+    ) -> (Vector3<f32>, UnitQuaternion<f32>) {
+        let direction = self
+            .direction
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(|| -Vector3::y());
+        let axle = self
+            .axle
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(|| -Vector3::x());
+        (
+            self.connection_point + direction * suspension_length,
+            UnitQuaternion::from_scaled_axis(-direction * steering)
+                * UnitQuaternion::from_scaled_axis(axle * -rotation),
+        )
+    }
+}
+
+/// Runtime state of a wheel of a [`Vehicle`] after the last update.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VehicleWheelState {
+    /// Current length of the suspension.
+    pub suspension_length: f32,
+    /// Current steering angle of the wheel (in radians).
+    pub steering: f32,
+    /// Current rotation of the wheel around its axle (in radians).
+    pub rotation: f32,
+    /// `true` if the wheel touches the ground.
+    pub is_in_contact: bool,
+    /// A point (in world coordinates) at which the wheel touches the ground.
+    pub contact_point: Vector3<f32>,
+    /// A normal (in world coordinates) of the ground at the contact point.
+    pub contact_normal: Vector3<f32>,
+    /// A handle of the collider the wheel stands on.
+    pub ground: Handle<Node>,
+}
+
+#[derive(Default)]
+pub(crate) struct NativeVehicle(pub Option<DynamicRayCastVehicleController>);
+
+impl Clone for NativeVehicle {
+    fn clone(&self) -> Self {
+        // Do not copy. The copy will have its own native representation.
+        Self(None)
+    }
+}
+
+impl Debug for NativeVehicle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NativeVehicle")
+    }
+}
+
+/// Vehicle is a physics entity that simulates a wheeled vehicle on top of a dynamic [`RigidBody`]
+/// (chassis). Every wheel is represented by a ray, that is cast from the chassis to find the
+/// ground. Suspension of the wheel pushes the chassis up, and the friction of the tire pushes the
+/// chassis forward (when the wheel is driven by the engine), slows it down (when the wheel is
+/// braking) and prevents it from sliding sideways.
+///
+/// The chassis must be a dynamic rigid body with at least one collider. The collider of the
+/// chassis should not reach the ground, otherwise it will drag on it. Positions of the wheels are
+/// defined in the local coordinates of the chassis, the forward direction of the vehicle is the
+/// local `+Z` axis of the chassis and the up direction is the local `+Y` axis.
+///
+/// # Controls
+///
+/// The vehicle is controlled by three inputs: [`Vehicle::set_throttle`],
+/// [`Vehicle::set_steering`] and [`Vehicle::set_brake`]. The inputs are kept until changed, so
+/// they could be set only when the player presses or releases a key. The torque of the engine
+/// is defined by [`Vehicle::torque_curve`], that maps the speed of the vehicle (in m/s) to the
+/// torque (in N·m), the torque is evenly distributed between the driven wheels.
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::pool::Handle,
+/// #     graph::SceneGraph,
+/// #     scene::{graph::Graph, node::Node, vehicle::Vehicle},
+/// # };
+/// fn drive(vehicle: Handle<Node>, graph: &mut Graph, forward: bool, left: bool, right: bool) {
+///     if let Some(vehicle) = graph.try_get_mut_of_type::<Vehicle>(vehicle) {
+///         vehicle.set_throttle(if forward { 1.0 } else { 0.0 });
+///         vehicle.set_steering(left as i32 as f32 - right as i32 as f32);
+///         vehicle.set_brake(if forward { 0.0 } else { 0.3 });
+///     }
+/// }
+/// ```
+///
+/// # Wheel visuals
+///
+/// Every wheel could have a visual node (usually a mesh of the wheel), that is moved along the
+/// suspension and rotated according to the steering angle and the rotation of the wheel. The
+/// visual node should be a child of the chassis and its model should be oriented so that its
+/// rotation axis matches [`VehicleWheel::axle`]. Local position and rotation of the visual node
+/// are overwritten every frame, its scale is kept as is.
+#[derive(Visit, Reflect, ComponentProvider, Clone)]
+pub struct Vehicle {
+    base: Base,
+
+    #[reflect(description = "A dynamic rigid body that is used as the chassis of the vehicle.")]
+    #[reflect(setter = "set_chassis")]
+    pub(crate) chassis: InheritableVariable<Handle<Node>>,
+
+    #[reflect(description = "Wheels of the vehicle.")]
+    #[reflect(setter = "set_wheels")]
+    pub(crate) wheels: InheritableVariable<Vec<VehicleWheel>>,
+
+    #[reflect(
+        description = "Torque of the engine (in N·m) as a function of the speed of the \
+        vehicle (in m/s)."
+    )]
+    #[reflect(setter = "set_torque_curve")]
+    pub(crate) torque_curve: InheritableVariable<Curve>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.571,
+        step = 0.01,
+        description = "Max steering angle (in radians) of the steered wheels."
+    )]
+    #[reflect(setter = "set_max_steering_angle")]
+    pub(crate) max_steering_angle: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.1,
+        description = "Brake force applied to every wheel with brakes at full brake input."
+    )]
+    #[reflect(setter = "set_max_brake_force")]
+    pub(crate) max_brake_force: InheritableVariable<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    throttle: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    steering: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    brake: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    speed: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    wheel_states: Vec<VehicleWheelState>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native: NativeVehicle,
+}
+
+impl Debug for Vehicle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Vehicle")
+    }
+}
+
+impl Default for Vehicle {
+    fn default() -> Self {
+        VehicleBuilder::new(BaseBuilder::new()).build_vehicle()
+    }
+}
+
+impl Deref for Vehicle {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Vehicle {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Vehicle {
+    fn type_uuid() -> Uuid {
+        uuid!("6c0f1e4a-8b3d-4f5e-9a27-d1c4b8e2f703")
+    }
+}
+
+impl Vehicle {
+    /// Sets the chassis of the vehicle. The handle should point to a dynamic [`RigidBody`] node,
+    /// otherwise the vehicle will have no effect!
+    pub fn set_chassis(&mut self, handle: Handle<Node>) -> Handle<Node> {
+        self.chassis.set_value_and_mark_modified(handle)
+    }
+
+    /// Returns current chassis of the vehicle.
+    pub fn chassis(&self) -> Handle<Node> {
+        *self.chassis
+    }
+
+    /// Sets new wheels of the vehicle.
+    pub fn set_wheels(&mut self, wheels: Vec<VehicleWheel>) -> Vec<VehicleWheel> {
+        self.wheels.set_value_and_mark_modified(wheels)
+    }
+
+    /// Returns a slice with the wheels of the vehicle.
+    pub fn wheels(&self) -> &[VehicleWheel] {
+        &self.wheels
+    }
+
+    /// Returns a mutable reference to the wheels of the vehicle.
+    pub fn wheels_mut(&mut self) -> &mut Vec<VehicleWheel> {
+        self.wheels.get_value_mut_and_mark_modified()
+    }
+
+    /// Sets new torque curve of the engine. The curve maps the speed of the vehicle (in m/s) to
+    /// the torque (in N·m).
+    pub fn set_torque_curve(&mut self, curve: Curve) -> Curve {
+        self.torque_curve.set_value_and_mark_modified(curve)
+    }
+
+    /// Returns current torque curve of the engine.
+    pub fn torque_curve(&self) -> &Curve {
+        &self.torque_curve
+    }
+
+    /// Sets max steering angle (in radians) of the steered wheels.
+    pub fn set_max_steering_angle(&mut self, angle: f32) -> f32 {
+        self.max_steering_angle
+            .set_value_and_mark_modified(angle.max(0.0))
+    }
+
+    /// Returns max steering angle (in radians) of the steered wheels.
+    pub fn max_steering_angle(&self) -> f32 {
+        *self.max_steering_angle
+    }
+
+    /// Sets brake force, that is applied to every wheel with brakes when the brake input is 1.0.
+    pub fn set_max_brake_force(&mut self, force: f32) -> f32 {
+        self.max_brake_force
+            .set_value_and_mark_modified(force.max(0.0))
+    }
+
+    /// Returns brake force, that is applied to every wheel with brakes at full brake input.
+    pub fn max_brake_force(&self) -> f32 {
+        *self.max_brake_force
+    }
+
+    /// Sets throttle input in `[-1; 1]` range. Negative values make the vehicle move backwards.
+    pub fn set_throttle(&mut self, throttle: f32) {
+        self.throttle = throttle.clamp(-1.0, 1.0);
+    }
+
+    /// Returns current throttle input.
+    pub fn throttle(&self) -> f32 {
+        self.throttle
+    }
+
+    /// Sets steering input in `[-1; 1]` range. Positive values turn the steered wheels
+    /// counterclockwise around the up axis of the chassis.
+    pub fn set_steering(&mut self, steering: f32) {
+        self.steering = steering.clamp(-1.0, 1.0);
+    }
+
+    /// Returns current steering input.
+    pub fn steering(&self) -> f32 {
+        self.steering
+    }
+
+    /// Sets brake input in `[0; 1]` range.
+    pub fn set_brake(&mut self, brake: f32) {
+        self.brake = brake.clamp(0.0, 1.0);
+    }
+
+    /// Returns current brake input.
+    pub fn brake(&self) -> f32 {
+        self.brake
+    }
+
+    /// Returns forward speed of the vehicle (in m/s) after the last update. The speed is
+    /// negative if the vehicle moves backwards.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Returns runtime state of the wheels after the last update. The states have the same order
+    /// as the wheels.
+    pub fn wheel_states(&self) -> &[VehicleWheelState] {
+        &self.wheel_states
+    }
+
+    fn sync_controller(&mut self, chassis_body: rapier3d::prelude::RigidBodyHandle) {
+        let is_valid = self.native.0.as_ref().is_some_and(|controller| {
+            controller.chassis == chassis_body && controller.wheels().len() == self.wheels.len()
+        });
+        if !is_valid {
+            let mut controller = DynamicRayCastVehicleController::new(chassis_body);
+            controller.index_up_axis = 1;
+            controller.index_forward_axis = 2;
+            for wheel in self.wheels.iter() {
+                controller.add_wheel(
+                    Point3::from(wheel.connection_point),
+                    wheel.direction,
+                    wheel.axle,
+                    wheel.rest_length,
+                    wheel.radius,
+                    &WheelTuning::default(),
+                );
+            }
+            self.native.0 = Some(controller);
+        }
+
+        let Some(controller) = self.native.0.as_mut() else {
+            return;
+        };
+
+        let driven_count = self.wheels.iter().filter(|w| w.driven).count().max(1);
+        let torque = self
+            .torque_curve
+            .value_at(controller.current_vehicle_speed.abs());
+
+        for (wheel, native) in self.wheels.iter().zip(controller.wheels_mut()) {
+            native.chassis_connection_point_cs = Point3::from(wheel.connection_point);
+            native.direction_cs = wheel
+                .direction
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(|| -Vector3::y());
+            native.axle_cs = wheel
+                .axle
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(|| -Vector3::x());
+            native.suspension_rest_length = wheel.rest_length;
+            native.radius = wheel.radius;
+            native.max_suspension_travel = wheel.max_suspension_travel;
+            native.suspension_stiffness = wheel.suspension_stiffness;
+            native.damping_compression = wheel.suspension_compression;
+            native.damping_relaxation = wheel.suspension_damping;
+            native.max_suspension_force = wheel.max_suspension_force;
+            native.friction_slip = wheel.friction_slip;
+            native.side_friction_stiffness = wheel.side_friction_stiffness;
+            native.steering = if wheel.steered {
+                self.steering * *self.max_steering_angle
+            } else {
+                0.0
+            };
+            native.engine_force = if wheel.driven && wheel.radius > 0.0 {
+                self.throttle * torque / (wheel.radius * driven_count as f32)
+            } else {
+                0.0
+            };
+            native.brake = if wheel.braked {
+                self.brake * *self.max_brake_force
+            } else {
+                0.0
+            };
+        }
+    }
+
+    fn update_visuals(&self, context: &mut UpdateContext) {
+        let Some(chassis_transform) = context
+            .nodes
+            .try_borrow(*self.chassis)
+            .map(|chassis| chassis.global_transform())
+        else {
+            return;
+        };
+
+        for (wheel, state) in self.wheels.iter().zip(self.wheel_states.iter()) {
+            let Some(visual) = context.nodes.try_borrow(wheel.visual) else {
+                continue;
+            };
+
+            // Transform from the space of the chassis to the space of the parent of the visual.
+            let relative_transform = if visual.parent() == *self.chassis {
+                Matrix4::identity()
+            } else {
+                context
+                    .nodes
+                    .try_borrow(visual.parent())
+                    .map(|parent| parent.global_transform())
+                    .unwrap_or_else(Matrix4::identity)
+                    .try_inverse()
+                    .unwrap_or_else(Matrix4::identity)
+                    * chassis_transform
+            };
+
+            let (position, rotation) =
+                wheel.local_transform(state.suspension_length, state.steering, state.rotation);
+            let position = relative_transform
+                .transform_point(&Point3::from(position))
+                .coords;
+            let rotation = UnitQuaternion::from(Rotation3::from_matrix_eps(
+                &relative_transform.basis(),
+                f32::EPSILON,
+                16,
+                Rotation3::identity(),
+            )) * rotation;
+
+            if let Some(visual) = context.nodes.try_borrow_mut(wheel.visual) {
+                visual
+                    .local_transform_mut()
+                    .set_position(position)
+                    .set_rotation(rotation);
+            }
+        }
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for Vehicle {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Vehicle", |_| {
+                VehicleBuilder::new(BaseBuilder::new().with_name("Vehicle"))
+                    .build_node()
+                    .into()
+            })
+            .with_group("Physics")
+    }
+}
+
+impl NodeTrait for Vehicle {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let Some(chassis_body) = context
+            .nodes
+            .try_borrow(*self.chassis)
+            .and_then(|chassis| chassis.component_ref::<RigidBody>())
+            .map(|body| body.native.get())
+        else {
+            self.native.0 = None;
+            self.wheel_states.clear();
+            return;
+        };
+
+        self.sync_controller(chassis_body);
+
+        let Some(controller) = self.native.0.as_mut() else {
+            return;
+        };
+        let Some(wheel_states) = context.physics.update_vehicle(controller, context.dt) else {
+            return;
+        };
+        self.speed = controller.current_vehicle_speed;
+        self.wheel_states = wheel_states;
+
+        self.update_visuals(context);
+    }
+
+    fn validate(&self, scene: &Scene) -> Result<(), String> {
+        if let Some(chassis) = scene.graph.try_get(self.chassis()) {
+            if chassis.component_ref::<RigidBody>().is_none() {
+                return Err(
+                    "Chassis of a Vehicle must be an instance of 3D Rigid Body!".to_string()
+                );
+            }
+        } else {
+            return Err("Vehicle has invalid or unassigned handle to a chassis, \
+            the vehicle will not operate!"
+                .to_string());
+        }
+
+        if self.wheels.is_empty() {
+            return Err("Vehicle has no wheels, the vehicle will not operate!".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Allows you to create vehicle in declarative manner.
+pub struct VehicleBuilder {
+    base_builder: BaseBuilder,
+    chassis: Handle<Node>,
+    wheels: Vec<VehicleWheel>,
+    torque_curve: Curve,
+    max_steering_angle: f32,
+    max_brake_force: f32,
+}
+
+impl VehicleBuilder {
+    /// Creates new vehicle builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            chassis: Default::default(),
+            wheels: Default::default(),
+            torque_curve: Curve::from(vec![
+                CurveKey::new(0.0, 400.0, CurveKeyKind::Linear),
+                CurveKey::new(20.0, 400.0, CurveKeyKind::Linear),
+                CurveKey::new(60.0, 0.0, CurveKeyKind::Linear),
+            ]),
+            max_steering_angle: 35.0f32.to_radians(),
+            max_brake_force: 5.0,
+        }
+    }
+
+    /// Sets the desired chassis of the vehicle.
+    pub fn with_chassis(mut self, chassis: Handle<Node>) -> Self {
+        self.chassis = chassis;
+        self
+    }
+
+    /// Sets the desired wheels of the vehicle.
+    pub fn with_wheels(mut self, wheels: Vec<VehicleWheel>) -> Self {
+        self.wheels = wheels;
+        self
+    }
+
+    /// Sets the desired torque curve of the engine.
+    pub fn with_torque_curve(mut self, curve: Curve) -> Self {
+        self.torque_curve = curve;
+        self
+    }
+
+    /// Sets the desired max steering angle (in radians).
+    pub fn with_max_steering_angle(mut self, angle: f32) -> Self {
+        self.max_steering_angle = angle;
+        self
+    }
+
+    /// Sets the desired max brake force.
+    pub fn with_max_brake_force(mut self, force: f32) -> Self {
+        self.max_brake_force = force;
+        self
+    }
+
+    /// Creates Vehicle node but does not add it to the graph.
+    pub fn build_vehicle(self) -> Vehicle {
+        Vehicle {
+            base: self.base_builder.build_base(),
+            chassis: self.chassis.into(),
+            wheels: self.wheels.into(),
+            torque_curve: self.torque_curve.into(),
+            max_steering_angle: self.max_steering_angle.into(),
+            max_brake_force: self.max_brake_force.into(),
+            throttle: 0.0,
+            steering: 0.0,
+            brake: 0.0,
+            speed: 0.0,
+            wheel_states: Default::default(),
+            native: Default::default(),
+        }
+    }
+
+    /// Creates Vehicle node but does not add it to the graph.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_vehicle())
+    }
+
+    /// Creates Vehicle node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            pivot::PivotBuilder,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+            vehicle::{Vehicle, VehicleBuilder, VehicleWheel},
+        },
+    };
+
+    #[test]
+    fn test_vehicle_rests_on_wheels_and_drives_forward() {
+        let mut graph = Graph::new();
+
+        let floor_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(50.0, 0.5, 50.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, -0.5, 0.0))
+                        .build(),
+                )
+                .with_children(&[floor_collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        let chassis_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.8, 0.2, 1.5))
+            .with_density(Some(500.0))
+            .build(&mut graph);
+        let visual = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let chassis = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 1.0, 0.0))
+                        .build(),
+                )
+                .with_children(&[chassis_collider, visual]),
+        )
+        .with_body_type(RigidBodyType::Dynamic)
+        .with_can_sleep(false)
+        .build(&mut graph);
+
+        let wheels = [(-0.8, 1.2), (0.8, 1.2), (-0.8, -1.2), (0.8, -1.2)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (x, z))| {
+                let wheel = VehicleWheel::new(Vector3::new(x, 0.0, z)).with_steered(z > 0.0);
+                if i == 0 {
+                    wheel.with_visual(visual)
+                } else {
+                    wheel
+                }
+            })
+            .collect();
+        let vehicle = VehicleBuilder::new(BaseBuilder::new())
+            .with_chassis(chassis)
+            .with_wheels(wheels)
+            .build(&mut graph);
+
+        let dt = 1.0 / 60.0;
+        for _ in 0..180 {
+            graph.update(Vector2::new(800.0, 600.0), dt, Default::default());
+        }
+
+        let vehicle_ref = graph[vehicle].cast::<Vehicle>().unwrap();
+        assert_eq!(vehicle_ref.wheel_states().len(), 4);
+        assert!(vehicle_ref.wheel_states().iter().all(|s| s.is_in_contact));
+        assert_eq!(vehicle_ref.wheel_states()[0].ground, floor_collider);
+        let height = graph[chassis].global_position().y;
+        assert!((0.4..0.7).contains(&height), "{height}");
+        // The visual node follows the wheel.
+        let visual_position = **graph[visual].local_transform().position();
+        assert_eq!(visual_position.x, -0.8);
+        assert!(visual_position.y < 0.0);
+
+        graph[vehicle]
+            .cast_mut::<Vehicle>()
+            .unwrap()
+            .set_throttle(1.0);
+        for _ in 0..90 {
+            graph.update(Vector2::new(800.0, 600.0), dt, Default::default());
+        }
+
+        let speed = graph[vehicle].cast::<Vehicle>().unwrap().speed();
+        assert!(speed > 1.0, "{speed}");
+        let position = graph[chassis].global_position();
+        assert!(position.z > 0.5, "{position}");
+        assert!(position.x.abs() < 0.1, "{position}");
+    }
+}
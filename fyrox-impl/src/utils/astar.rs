@@ -78,7 +78,7 @@ impl VertexData {
 pub trait VertexDataProvider: Deref<Target = VertexData> + DerefMut + PositionProvider {}
 
 /// A default graph vertex with no additional data.
-#[derive(Default, PartialEq, Debug)]
+#[derive(Clone, Default, PartialEq, Debug)]
 pub struct GraphVertex {
     /// Data of the vertex.
     pub data: VertexData,
@@ -482,14 +482,16 @@ impl<T: VertexDataProvider> Graph<T> {
                 .get(current_index)
                 .ok_or(PathError::InvalidIndex(current_index))?;
 
+            // breaks if end is found, even if the path to it is longer than the best partial
+            // path so far (this happens when the path has to go around obstacles)
+            if current_index == to {
+                best_path = current_path;
+                break;
+            }
+
             // updates best path
             if current_path > best_path {
                 best_path = current_path.clone();
-
-                // breaks if end is found
-                if current_index == to {
-                    break;
-                }
             }
 
             // evaluates path scores one level deeper and adds the paths to the heap
@@ -746,6 +748,32 @@ mod test {
         assert_eq!(pathfinder.vertex(3).unwrap().neighbours, vec![2, 1]);
     }
 
+    #[test]
+    fn test_path_around_dead_end() {
+        let mut pathfinder = Graph::new();
+
+        // The first vertex is connected to a dead end, which is very close to the destination, and
+        // to a long detour, that leads to the destination.
+        pathfinder.add_vertex(GraphVertex::new(Vector3::new(0.0, 0.0, 0.0)));
+        pathfinder.add_vertex(GraphVertex::new(Vector3::new(1.0, 0.0, 0.0)));
+        pathfinder.add_vertex(GraphVertex::new(Vector3::new(0.0, 5.0, 0.0)));
+        pathfinder.add_vertex(GraphVertex::new(Vector3::new(2.0, 0.0, 0.0)));
+
+        pathfinder.link_bidirect(0, 1);
+        pathfinder.link_bidirect(0, 2);
+        pathfinder.link_bidirect(2, 3);
+
+        // The destination has worse score than the dead end, but the full path must be returned
+        // anyway instead of the partial one that ends in the dead end.
+        let mut path = Vec::new();
+        assert!(matches!(
+            pathfinder.build_indexed_path(0, 3, &mut path),
+            Ok(PathKind::Full)
+        ));
+        // Paths are stored from the end to the beginning.
+        assert_eq!(path, [3, 2, 0]);
+    }
+
     #[ignore = "takes multiple seconds to run"]
     #[test]
     /// Tests A*'s speed when finding a direct path with no obsticles
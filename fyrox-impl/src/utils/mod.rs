@@ -25,6 +25,7 @@
 pub mod astar;
 pub mod behavior;
 pub mod lightmap;
+pub mod navgrid;
 pub mod navmesh;
 pub mod raw_mesh;
 pub mod uvgen;
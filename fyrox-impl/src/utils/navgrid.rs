@@ -0,0 +1,940 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Contains 2D navigation grid and an agent that can follow paths on it.
+//!
+//! Navigation grid (navgrid) is a uniform grid of cells, where each cell is either walkable or
+//! blocked. It is a natural fit for tile-based games, where the grid could be generated directly
+//! from a [`TileMap`] - tiles with colliders on a specific collider layer are considered obstacles
+//! and/or tiles could be marked walkable using a tile set property. The grid could also be built
+//! from arbitrary polygonal areas and obstacles, see [`NavGrid::from_polygons`].
+//!
+//! Walkable cells are connected with their neighbours (including diagonal ones, if enabled) into
+//! an [`astar::Graph`], which is used to search paths. Raw paths that go through cell centers are
+//! then smoothed by [`NavGridAgent`] using line-of-sight checks (string pulling), so the agent
+//! moves along straight lines wherever possible.
+//!
+//! Cells could be changed at any time (for example, when a door was opened or a tile was removed),
+//! see [`NavGrid::set_walkable`] and [`NavGrid::sync_with_tile_map`]. Every change increments
+//! the revision of the grid, and agents automatically recalculate their paths when they see a new
+//! revision.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector2, Vector3},
+        visitor::prelude::*,
+        ImmutableString,
+    },
+    scene::tilemap::{tileset::TileSetPropertyValue, TileMap, TileRect},
+    utils::astar::{Graph, GraphVertex, PathError, PathKind},
+};
+
+const NEIGHBOUR_OFFSETS: [Vector2<i32>; 8] = [
+    Vector2::new(1, 0),
+    Vector2::new(-1, 0),
+    Vector2::new(0, 1),
+    Vector2::new(0, -1),
+    Vector2::new(1, 1),
+    Vector2::new(-1, 1),
+    Vector2::new(1, -1),
+    Vector2::new(-1, -1),
+];
+
+/// A set of rules that defines which tiles of a tile map are walkable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileMapNavOptions {
+    /// A name of a collider layer of the tile set. Tiles that have a collider on this layer are
+    /// considered obstacles. Empty name means that colliders are ignored.
+    pub obstacle_collider_layer: ImmutableString,
+    /// A name of a property of the tile set. Tiles are walkable only if the value of the property
+    /// is non-zero (or non-empty for strings). Empty name means that properties are ignored and
+    /// every tile (including empty cells) without an obstacle collider is walkable.
+    pub walkable_property: ImmutableString,
+    /// Amount of cells that will be added around the bounds of the tile map. Could be useful
+    /// if agents should be able to walk around the tile map.
+    pub padding: i32,
+    /// Whether the agents can move diagonally or not. Diagonal moves never cut corners of
+    /// blocked cells.
+    pub allow_diagonal: bool,
+}
+
+impl Default for TileMapNavOptions {
+    fn default() -> Self {
+        Self {
+            obstacle_collider_layer: Default::default(),
+            walkable_property: Default::default(),
+            padding: 0,
+            allow_diagonal: true,
+        }
+    }
+}
+
+fn is_property_value_set(value: &TileSetPropertyValue) -> bool {
+    match value {
+        TileSetPropertyValue::I32(value) => *value != 0,
+        TileSetPropertyValue::F32(value) => *value != 0.0,
+        TileSetPropertyValue::String(value) => !value.is_empty(),
+        TileSetPropertyValue::NineSlice(value) => value.0.iter().any(|v| *v != 0),
+    }
+}
+
+fn is_point_inside_polygon(point: Vector2<f32>, polygon: &[Vector2<f32>]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[j];
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// See module docs.
+#[derive(Clone, Debug, Visit)]
+#[visit(optional)]
+pub struct NavGrid {
+    origin: Vector2<i32>,
+    size: Vector2<i32>,
+    walkable: Vec<bool>,
+    transform: Matrix4<f32>,
+    inv_transform: Matrix4<f32>,
+    allow_diagonal: bool,
+    revision: u64,
+    graph: Graph<GraphVertex>,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        Self::new(TileRect::new(0, 0, 0, 0), Matrix4::identity(), true)
+    }
+}
+
+impl PartialEq for NavGrid {
+    fn eq(&self, other: &Self) -> bool {
+        self.origin == other.origin
+            && self.size == other.size
+            && self.walkable == other.walkable
+            && self.transform == other.transform
+    }
+}
+
+impl NavGrid {
+    /// Creates new navigation grid, that covers the given cells. `transform` maps grid space, where
+    /// each cell is a unit square, to world space. Every cell of the new grid is walkable.
+    pub fn new(bounds: TileRect, transform: Matrix4<f32>, allow_diagonal: bool) -> Self {
+        let size = Vector2::new(bounds.w().max(0), bounds.h().max(0));
+        let mut grid = Self {
+            origin: bounds.position,
+            size,
+            walkable: vec![true; (size.x * size.y) as usize],
+            inv_transform: transform.try_inverse().unwrap_or_else(Matrix4::identity),
+            transform,
+            allow_diagonal,
+            revision: 0,
+            graph: Graph::new(),
+        };
+        grid.rebuild_graph();
+        grid
+    }
+
+    /// Creates new navigation grid from the tiles of the given tile map. The grid covers the
+    /// bounds of the tile map (plus the padding) and its cells match the tiles. See
+    /// [`TileMapNavOptions`] docs for more info about which tiles are considered walkable.
+    /// Navigation grids are always orthogonal, so the tile map should use
+    /// [`crate::scene::tilemap::TileGridLayout::Orthogonal`] layout.
+    pub fn from_tile_map(tile_map: &TileMap, options: &TileMapNavOptions) -> Self {
+        let bounds = tile_map
+            .bounding_rect()
+            .map(|rect| rect.inflate(options.padding, options.padding))
+            .unwrap_or_else(|| TileRect::new(0, 0, 0, 0));
+        let mut grid = Self::new(
+            bounds,
+            tile_map.tile_map_transform(),
+            options.allow_diagonal,
+        );
+        grid.sync_with_tile_map(tile_map, options);
+        grid
+    }
+
+    /// Creates new navigation grid from a set of polygonal areas. The grid covers the bounds of all
+    /// walkable areas with the cells of the given size. A cell is walkable if its center is inside
+    /// any of walkable areas and outside all obstacles. Polygons are defined in world space.
+    pub fn from_polygons(
+        cell_size: f32,
+        walkable_areas: &[Vec<Vector2<f32>>],
+        obstacles: &[Vec<Vector2<f32>>],
+        allow_diagonal: bool,
+    ) -> Self {
+        let cell_size = cell_size.max(f32::EPSILON);
+        let mut min = Vector2::repeat(f32::MAX);
+        let mut max = Vector2::repeat(-f32::MAX);
+        for point in walkable_areas.iter().flatten() {
+            min = min.inf(point);
+            max = max.sup(point);
+        }
+        if min.x > max.x || min.y > max.y {
+            return Self::default();
+        }
+        let size = ((max - min) / cell_size).map(|c| (c.ceil() as i32).max(1));
+        let transform = Matrix4::new_translation(&Vector3::new(min.x, min.y, 0.0))
+            * Matrix4::new_scaling(cell_size);
+
+        let mut grid = Self::new(
+            TileRect::new(0, 0, size.x, size.y),
+            transform,
+            allow_diagonal,
+        );
+        for i in 0..grid.walkable.len() {
+            let center = grid.cell_center(grid.index_to_cell(i));
+            grid.walkable[i] = walkable_areas
+                .iter()
+                .any(|area| is_point_inside_polygon(center, area))
+                && !obstacles
+                    .iter()
+                    .any(|obstacle| is_point_inside_polygon(center, obstacle));
+        }
+        grid.rebuild_graph();
+        grid
+    }
+
+    /// Re-evaluates every cell of the grid using the tiles of the given tile map and updates the
+    /// cells that have changed. Returns `true` if at least one cell has changed. This method should
+    /// be called when tiles of the tile map have changed, so the agents could avoid new obstacles.
+    pub fn sync_with_tile_map(&mut self, tile_map: &TileMap, options: &TileMapNavOptions) -> bool {
+        let mut walkable = Vec::with_capacity(self.walkable.len());
+        {
+            let tile_set = tile_map.tile_set().map(|t| t.data_ref());
+            let tile_set = tile_set.as_ref().and_then(|t| t.as_loaded_ref());
+            let tiles = tile_map.tiles().map(|t| t.data_ref());
+            let tiles = tiles.as_ref().and_then(|t| t.as_loaded_ref());
+            let collider_uuid =
+                tile_set.and_then(|t| t.collider_name_to_uuid(&options.obstacle_collider_layer));
+            let property_uuid =
+                tile_set.and_then(|t| t.property_name_to_uuid(&options.walkable_property));
+
+            for i in 0..self.walkable.len() {
+                let position = self.index_to_cell(i);
+                let tile_data = tiles
+                    .and_then(|tiles| tiles.get(position))
+                    .zip(tile_set)
+                    .and_then(|(handle, tile_set)| tile_set.get_tile_data(handle.into()));
+                let has_obstacle = collider_uuid.is_some_and(|uuid| {
+                    tile_data
+                        .and_then(|data| data.colliders.get(&uuid))
+                        .is_some_and(|collider| !collider.is_none())
+                });
+                let is_walkable = if options.walkable_property.is_empty() {
+                    true
+                } else {
+                    property_uuid
+                        .zip(tile_data)
+                        .and_then(|(uuid, data)| data.properties.get(&uuid))
+                        .is_some_and(is_property_value_set)
+                };
+                walkable.push(is_walkable && !has_obstacle);
+            }
+        }
+
+        let mut changed = false;
+        for (i, is_walkable) in walkable.into_iter().enumerate() {
+            if self.walkable[i] != is_walkable {
+                self.set_walkable(self.index_to_cell(i), is_walkable);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Marks every cell whose center is inside the given world-space polygon as walkable or blocked.
+    /// Could be used to add or remove dynamic obstacles. Returns `true` if at least one cell has changed.
+    pub fn set_polygon_walkable(&mut self, polygon: &[Vector2<f32>], walkable: bool) -> bool {
+        let mut changed = false;
+        for i in 0..self.walkable.len() {
+            let cell = self.index_to_cell(i);
+            if self.walkable[i] != walkable
+                && is_point_inside_polygon(self.cell_center(cell), polygon)
+            {
+                changed |= self.set_walkable(cell, walkable);
+            }
+        }
+        changed
+    }
+
+    /// Marks the given cell as walkable or blocked and updates links between the cell and its
+    /// neighbours. Returns `true` if the cell has changed.
+    pub fn set_walkable(&mut self, cell: Vector2<i32>, walkable: bool) -> bool {
+        let Some(index) = self.cell_to_index(cell) else {
+            return false;
+        };
+        if self.walkable[index] == walkable {
+            return false;
+        }
+        self.walkable[index] = walkable;
+        // Diagonal links of the neighbours depend on the cell as well.
+        for y in -1..=1 {
+            for x in -1..=1 {
+                self.relink(cell + Vector2::new(x, y));
+            }
+        }
+        self.revision += 1;
+        true
+    }
+
+    /// Returns `true` if the given cell is inside the grid and it is walkable.
+    pub fn is_walkable(&self, cell: Vector2<i32>) -> bool {
+        self.cell_to_index(cell)
+            .is_some_and(|index| self.walkable[index])
+    }
+
+    /// Returns the bounds of the grid.
+    pub fn bounds(&self) -> TileRect {
+        TileRect::new(self.origin.x, self.origin.y, self.size.x, self.size.y)
+    }
+
+    /// Returns the transform that maps grid space to world space.
+    pub fn transform(&self) -> &Matrix4<f32> {
+        &self.transform
+    }
+
+    /// Returns revision of the grid, it is incremented every time when any cell has changed.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Returns a reference to the underlying graph. Every cell of the grid has a respective vertex
+    /// in the graph, blocked cells have no neighbours.
+    pub fn graph(&self) -> &Graph<GraphVertex> {
+        &self.graph
+    }
+
+    /// Returns a cell that contains the given world-space point. The cell could be outside the grid.
+    pub fn world_to_cell(&self, point: Vector2<f32>) -> Vector2<i32> {
+        self.world_to_grid(point).map(|c| c.floor() as i32)
+    }
+
+    /// Returns world-space position of the center of the given cell.
+    pub fn cell_center(&self, cell: Vector2<i32>) -> Vector2<f32> {
+        self.grid_to_world(cell.cast::<f32>().add_scalar(0.5))
+    }
+
+    /// Searches for a walkable cell that is closest to the given world-space point.
+    pub fn closest_walkable_cell(&self, point: Vector2<f32>) -> Option<Vector2<i32>> {
+        let cell = self.world_to_cell(point);
+        if self.is_walkable(cell) {
+            return Some(cell);
+        }
+        let bounds = self.bounds();
+        let max_distance = (cell.x - bounds.x())
+            .abs()
+            .max((cell.x - bounds.x() - bounds.w()).abs())
+            .max((cell.y - bounds.y()).abs())
+            .max((cell.y - bounds.y() - bounds.h()).abs());
+        for ring in 1..=max_distance {
+            let mut closest = None;
+            let mut closest_distance = f32::MAX;
+            for y in -ring..=ring {
+                let step = if y.abs() == ring { 1 } else { 2 * ring };
+                for x in (-ring..=ring).step_by(step as usize) {
+                    let candidate = cell + Vector2::new(x, y);
+                    if self.is_walkable(candidate) {
+                        let distance = self.cell_center(candidate).metric_distance(&point);
+                        if distance < closest_distance {
+                            closest_distance = distance;
+                            closest = Some(candidate);
+                        }
+                    }
+                }
+            }
+            if closest.is_some() {
+                return closest;
+            }
+        }
+        None
+    }
+
+    /// Checks whether a body with the given radius could move along a straight line between the
+    /// given world-space points without touching blocked cells.
+    pub fn has_line_of_sight(&self, from: Vector2<f32>, to: Vector2<f32>, radius: f32) -> bool {
+        if !self.is_segment_walkable(self.world_to_grid(from), self.world_to_grid(to)) {
+            return false;
+        }
+        let dir = to - from;
+        let len = dir.norm();
+        if radius <= 0.0 || len <= f32::EPSILON {
+            return true;
+        }
+        let offset = Vector2::new(-dir.y, dir.x).scale(radius / len);
+        [offset, -offset].into_iter().all(|offset| {
+            self.is_segment_walkable(
+                self.world_to_grid(from + offset),
+                self.world_to_grid(to + offset),
+            )
+        })
+    }
+
+    /// Tries to build a path between the given world-space points. The path goes through the
+    /// centers of the cells, use [`NavGridAgent`] to get a smooth path. If the points are in
+    /// blocked cells, closest walkable cells will be used instead.
+    pub fn build_path(
+        &self,
+        from: Vector2<f32>,
+        to: Vector2<f32>,
+        path: &mut Vec<Vector2<f32>>,
+    ) -> Result<PathKind, PathError> {
+        path.clear();
+
+        let from_cell = self.closest_walkable_cell(from).ok_or(PathError::Empty)?;
+        let to_cell = self.closest_walkable_cell(to).ok_or(PathError::Empty)?;
+
+        let mut indices = Vec::new();
+        let path_kind = self.graph.build_indexed_path(
+            self.cell_to_index(from_cell).unwrap(),
+            self.cell_to_index(to_cell).unwrap(),
+            &mut indices,
+        )?;
+
+        path.extend(
+            indices
+                .iter()
+                .rev()
+                .map(|index| self.cell_center(self.index_to_cell(*index))),
+        );
+
+        Ok(path_kind)
+    }
+
+    fn world_to_grid(&self, point: Vector2<f32>) -> Vector2<f32> {
+        let z = self.transform[(2, 3)];
+        self.inv_transform
+            .transform_point(&Point3::new(point.x, point.y, z))
+            .xy()
+            .coords
+    }
+
+    fn grid_to_world(&self, point: Vector2<f32>) -> Vector2<f32> {
+        self.transform
+            .transform_point(&Point3::new(point.x, point.y, 0.0))
+            .xy()
+            .coords
+    }
+
+    fn cell_to_index(&self, cell: Vector2<i32>) -> Option<usize> {
+        let local = cell - self.origin;
+        if local.x >= 0 && local.y >= 0 && local.x < self.size.x && local.y < self.size.y {
+            Some((local.y * self.size.x + local.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    fn index_to_cell(&self, index: usize) -> Vector2<i32> {
+        let index = index as i32;
+        self.origin + Vector2::new(index % self.size.x, index / self.size.x)
+    }
+
+    fn is_segment_walkable(&self, from: Vector2<f32>, to: Vector2<f32>) -> bool {
+        let mut cell = from.map(|c| c.floor() as i32);
+        let end = to.map(|c| c.floor() as i32);
+        let dir = to - from;
+        let step = dir.map(|c| {
+            if c > 0.0 {
+                1
+            } else if c < 0.0 {
+                -1
+            } else {
+                0
+            }
+        });
+        let boundary = |cell: i32, from: f32, dir: f32, step: i32| {
+            if step == 0 {
+                f32::MAX
+            } else {
+                ((cell + step.max(0)) as f32 - from) / dir
+            }
+        };
+        let mut t_max = Vector2::new(
+            boundary(cell.x, from.x, dir.x, step.x),
+            boundary(cell.y, from.y, dir.y, step.y),
+        );
+        let t_delta = dir.map(|c| if c == 0.0 { f32::MAX } else { 1.0 / c.abs() });
+
+        let max_steps = (end - cell).abs().sum() + 1;
+        for _ in 0..=max_steps {
+            if !self.is_walkable(cell) {
+                return false;
+            }
+            if cell == end {
+                return true;
+            }
+            if (t_max.x - t_max.y).abs() <= 1.0e-5 {
+                // The segment goes exactly through a corner, do not let it slip between
+                // two diagonal obstacles.
+                if !self.is_walkable(cell + Vector2::new(step.x, 0))
+                    || !self.is_walkable(cell + Vector2::new(0, step.y))
+                {
+                    return false;
+                }
+                cell += step;
+                t_max += t_delta;
+            } else if t_max.x < t_max.y {
+                cell.x += step.x;
+                t_max.x += t_delta.x;
+            } else {
+                cell.y += step.y;
+                t_max.y += t_delta.y;
+            }
+        }
+        // Numerical issues, be conservative.
+        false
+    }
+
+    fn relink(&mut self, cell: Vector2<i32>) {
+        let Some(index) = self.cell_to_index(cell) else {
+            return;
+        };
+        let mut neighbours = Vec::new();
+        if self.walkable[index] {
+            for offset in NEIGHBOUR_OFFSETS {
+                let is_diagonal = offset.x != 0 && offset.y != 0;
+                if is_diagonal
+                    && (!self.allow_diagonal
+                        || !self.is_walkable(cell + Vector2::new(offset.x, 0))
+                        || !self.is_walkable(cell + Vector2::new(0, offset.y)))
+                {
+                    continue;
+                }
+                if let Some(neighbour) = self.cell_to_index(cell + offset) {
+                    if self.walkable[neighbour] {
+                        neighbours.push(neighbour as u32);
+                    }
+                }
+            }
+        }
+        self.graph.vertices[index].neighbours = neighbours;
+    }
+
+    fn rebuild_graph(&mut self) {
+        let vertices = (0..self.walkable.len())
+            .map(|i| {
+                let center = self
+                    .transform
+                    .transform_point(&Point3::from(
+                        self.index_to_cell(i)
+                            .cast::<f32>()
+                            .add_scalar(0.5)
+                            .to_homogeneous(),
+                    ))
+                    .coords;
+                GraphVertex::new(center)
+            })
+            .collect();
+        self.graph.set_vertices(vertices);
+        self.graph.max_search_iterations = (self.walkable.len() as i32).max(1000);
+        for i in 0..self.walkable.len() {
+            self.relink(self.index_to_cell(i));
+        }
+        self.revision += 1;
+    }
+}
+
+/// Navigation grid agent is a "pathfinding unit" that performs navigation on a [`NavGrid`]. It
+/// builds a path through the cells of the grid and then straightens it, so the agent moves along
+/// straight lines where possible. The path is recalculated automatically when the target has moved
+/// or when the grid has changed.
+#[derive(Visit, Clone, Debug)]
+#[visit(optional)]
+pub struct NavGridAgent {
+    path: Vec<Vector2<f32>>,
+    current: u32,
+    position: Vector2<f32>,
+    last_warp_position: Vector2<f32>,
+    target: Vector2<f32>,
+    last_target_position: Vector2<f32>,
+    recalculation_threshold: f32,
+    speed: f32,
+    path_dirty: bool,
+    radius: f32,
+    interpolator: f32,
+    grid_revision: u64,
+}
+
+impl Default for NavGridAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NavGridAgent {
+    /// Creates new navigation grid agent.
+    pub fn new() -> Self {
+        Self {
+            path: vec![],
+            current: 0,
+            position: Default::default(),
+            last_warp_position: Default::default(),
+            target: Default::default(),
+            last_target_position: Default::default(),
+            recalculation_threshold: 0.25,
+            speed: 1.5,
+            path_dirty: true,
+            radius: 0.2,
+            interpolator: 0.0,
+            grid_revision: 0,
+        }
+    }
+
+    /// Returns agent's position.
+    pub fn position(&self) -> Vector2<f32> {
+        self.position
+    }
+
+    /// Returns agent's path that will be followed.
+    pub fn path(&self) -> &[Vector2<f32>] {
+        &self.path
+    }
+
+    /// Sets new speed of agent's movement.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// Returns current agent's movement speed.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets a new path recalculation threshold (in meters). See [`NavmeshAgent::set_threshold`]
+    /// for more info.
+    ///
+    /// [`NavmeshAgent::set_threshold`]: crate::utils::navmesh::NavmeshAgent::set_threshold
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.recalculation_threshold = threshold;
+    }
+
+    /// Returns the current path recalculation threshold (in meters).
+    pub fn threshold(&self) -> f32 {
+        self.recalculation_threshold
+    }
+
+    /// Sets a new radius of the agent. Path smoothing keeps the agent at this distance from
+    /// blocked cells, which prevents the agent from cutting corners. The default value is
+    /// 0.2 meters.
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius;
+    }
+
+    /// Returns the current radius of the agent. See [`Self::set_radius`] for more info.
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+}
+
+impl NavGridAgent {
+    /// Calculates path from point A to point B. In most cases there is no need to use this method
+    /// directly, because `update` will call it anyway if target position has moved or the grid
+    /// has changed.
+    pub fn calculate_path(
+        &mut self,
+        grid: &NavGrid,
+        src_point: Vector2<f32>,
+        dest_point: Vector2<f32>,
+    ) -> Result<PathKind, PathError> {
+        self.current = 0;
+        self.interpolator = 0.0;
+        self.grid_revision = grid.revision();
+
+        let mut raw_path = Vec::new();
+        let path_kind = match grid.build_path(src_point, dest_point, &mut raw_path) {
+            Ok(path_kind) => path_kind,
+            Err(err) => {
+                self.path.clear();
+                return Err(err);
+            }
+        };
+
+        // Use exact end points if they're reachable, otherwise stick to cell centers.
+        if grid.is_walkable(grid.world_to_cell(src_point)) {
+            raw_path[0] = src_point;
+        }
+        if path_kind == PathKind::Full && grid.is_walkable(grid.world_to_cell(dest_point)) {
+            *raw_path.last_mut().unwrap() = dest_point;
+        }
+
+        self.straighten_path(grid, &raw_path);
+
+        Ok(path_kind)
+    }
+
+    fn straighten_path(&mut self, grid: &NavGrid, raw_path: &[Vector2<f32>]) {
+        self.path.clear();
+        self.path.push(raw_path[0]);
+
+        let mut anchor = 0;
+        while anchor + 1 < raw_path.len() {
+            let mut next = anchor + 1;
+            for candidate in anchor + 2..raw_path.len() {
+                if grid.has_line_of_sight(raw_path[anchor], raw_path[candidate], self.radius) {
+                    next = candidate;
+                } else {
+                    break;
+                }
+            }
+            self.path.push(raw_path[next]);
+            anchor = next;
+        }
+    }
+
+    /// Performs single update tick that moves agent to the target along the path (which is
+    /// automatically recalculated if target's position has changed or the grid has changed).
+    pub fn update(&mut self, dt: f32, grid: &NavGrid) -> Result<PathKind, PathError> {
+        if self.path_dirty || self.grid_revision != grid.revision() {
+            self.calculate_path(grid, self.position, self.target)?;
+            self.path_dirty = false;
+        }
+
+        if let Some(source) = self.path.get(self.current as usize) {
+            if let Some(destination) = self.path.get((self.current + 1) as usize) {
+                let len = destination.metric_distance(source);
+                self.position = source.lerp(destination, self.interpolator.clamp(0.0, 1.0));
+                self.interpolator += (self.speed * dt) / len.max(f32::EPSILON);
+                if self.interpolator >= 1.0 {
+                    self.current += 1;
+                    self.interpolator = 0.0;
+                } else if self.interpolator < 0.0 {
+                    self.current = self.current.saturating_sub(1);
+                    self.interpolator = 1.0;
+                }
+            }
+        }
+
+        Ok(PathKind::Full)
+    }
+
+    /// Returns current steering target which in most cases next path point from which
+    /// agent is close to.
+    pub fn steering_target(&self) -> Option<Vector2<f32>> {
+        self.path
+            .get(self.current as usize + 1)
+            .or_else(|| self.path.last())
+            .cloned()
+    }
+
+    /// Sets new target for the agent.
+    pub fn set_target(&mut self, new_target: Vector2<f32>) {
+        if new_target.metric_distance(&self.last_target_position) >= self.recalculation_threshold {
+            self.path_dirty = true;
+            self.last_target_position = new_target;
+        }
+
+        self.target = new_target;
+    }
+
+    /// Returns current target of the agent.
+    pub fn target(&self) -> Vector2<f32> {
+        self.target
+    }
+
+    /// Sets new position of the agent.
+    pub fn set_position(&mut self, new_position: Vector2<f32>) {
+        if new_position.metric_distance(&self.last_warp_position) >= self.recalculation_threshold {
+            self.path_dirty = true;
+            self.last_warp_position = new_position;
+        }
+
+        self.position = new_position;
+    }
+}
+
+/// Allows you to build agent in declarative manner.
+pub struct NavGridAgentBuilder {
+    position: Vector2<f32>,
+    target: Vector2<f32>,
+    recalculation_threshold: f32,
+    speed: f32,
+    radius: f32,
+}
+
+impl Default for NavGridAgentBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NavGridAgentBuilder {
+    /// Creates new builder instance.
+    pub fn new() -> Self {
+        Self {
+            position: Default::default(),
+            target: Default::default(),
+            recalculation_threshold: 0.25,
+            speed: 1.5,
+            radius: 0.2,
+        }
+    }
+
+    /// Sets new desired position of the agent being built.
+    pub fn with_position(mut self, position: Vector2<f32>) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Sets new desired target of the agent being built.
+    pub fn with_target(mut self, position: Vector2<f32>) -> Self {
+        self.target = position;
+        self
+    }
+
+    /// Sets new desired recalculation threshold (in meters) of the agent being built.
+    pub fn with_recalculation_threshold(mut self, threshold: f32) -> Self {
+        self.recalculation_threshold = threshold;
+        self
+    }
+
+    /// Sets new desired movement speed of the agent being built.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Sets new desired radius of the agent being built.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Build the agent.
+    pub fn build(self) -> NavGridAgent {
+        NavGridAgent {
+            position: self.position,
+            last_warp_position: self.position,
+            target: self.target,
+            last_target_position: self.target,
+            recalculation_threshold: self.recalculation_threshold,
+            speed: self.speed,
+            radius: self.radius,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Matrix4, Vector2},
+        scene::tilemap::TileRect,
+        utils::{
+            astar::PathKind,
+            navgrid::{NavGrid, NavGridAgentBuilder},
+        },
+    };
+
+    // 5x5 grid with a wall in the middle column that has a gap at the top.
+    fn make_grid() -> NavGrid {
+        let mut grid = NavGrid::new(TileRect::new(0, 0, 5, 5), Matrix4::identity(), true);
+        for y in 0..4 {
+            assert!(grid.set_walkable(Vector2::new(2, y), false));
+        }
+        grid
+    }
+
+    #[test]
+    fn test_navgrid_links() {
+        let grid = make_grid();
+        let graph = grid.graph();
+        // Blocked cell has no links.
+        assert!(graph.vertices[2].neighbours.is_empty());
+        // Diagonal links do not cut corners of blocked cells.
+        let index = |x: i32, y: i32| (y * 5 + x) as u32;
+        let neighbours = &graph.vertices[index(1, 3) as usize].neighbours;
+        assert!(neighbours.contains(&index(1, 4)));
+        assert!(!neighbours.contains(&index(2, 4)));
+        assert!(!neighbours.contains(&index(2, 3)));
+    }
+
+    #[test]
+    fn test_navgrid_line_of_sight() {
+        let grid = make_grid();
+        assert!(grid.has_line_of_sight(Vector2::new(0.5, 0.5), Vector2::new(1.5, 3.5), 0.0));
+        assert!(!grid.has_line_of_sight(Vector2::new(0.5, 0.5), Vector2::new(4.5, 0.5), 0.0));
+        assert!(grid.has_line_of_sight(Vector2::new(0.5, 4.5), Vector2::new(4.5, 4.5), 0.0));
+        assert!(!grid.has_line_of_sight(Vector2::new(0.5, 4.5), Vector2::new(4.5, 4.5), 0.6));
+    }
+
+    #[test]
+    fn test_navgrid_agent() {
+        let mut grid = make_grid();
+        let mut agent = NavGridAgentBuilder::new()
+            .with_position(Vector2::new(0.5, 0.5))
+            .with_target(Vector2::new(4.5, 0.5))
+            .with_radius(0.0)
+            .build();
+
+        assert_eq!(agent.update(0.0, &grid).unwrap(), PathKind::Full);
+        // The agent must go around the wall through the gap.
+        assert_eq!(
+            agent.path(),
+            &[
+                Vector2::new(0.5, 0.5),
+                Vector2::new(1.5, 4.5),
+                Vector2::new(3.5, 4.5),
+                Vector2::new(4.5, 0.5),
+            ]
+        );
+
+        // Open a passage in the wall, the agent must pick it up.
+        grid.set_walkable(Vector2::new(2, 0), true);
+        agent.update(0.0, &grid).unwrap();
+        assert_eq!(
+            agent.path(),
+            &[Vector2::new(0.5, 0.5), Vector2::new(4.5, 0.5)]
+        );
+    }
+
+    #[test]
+    fn test_navgrid_from_polygons() {
+        let area = vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(4.0, 0.0),
+            Vector2::new(4.0, 4.0),
+            Vector2::new(0.0, 4.0),
+        ];
+        let obstacle = vec![
+            Vector2::new(1.0, 1.0),
+            Vector2::new(3.0, 1.0),
+            Vector2::new(3.0, 3.0),
+            Vector2::new(1.0, 3.0),
+        ];
+        let grid = NavGrid::from_polygons(1.0, &[area], &[obstacle], true);
+        assert_eq!(grid.bounds(), TileRect::new(0, 0, 4, 4));
+        assert!(grid.is_walkable(Vector2::new(0, 0)));
+        assert!(!grid.is_walkable(Vector2::new(1, 1)));
+        assert!(!grid.is_walkable(Vector2::new(2, 2)));
+        assert_eq!(
+            grid.closest_walkable_cell(Vector2::new(1.2, 1.6)),
+            Some(Vector2::new(0, 1))
+        );
+    }
+}
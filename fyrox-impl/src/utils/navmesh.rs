@@ -46,6 +46,8 @@ use fxhash::{FxBuildHasher, FxHashMap};
 use fyrox_core::math::octree::{Octree, OctreeNode};
 use std::ops::{Deref, DerefMut};
 
+pub mod generator;
+
 #[derive(Clone, Debug, Default, Visit)]
 struct Vertex {
     triangle_index: usize,
@@ -0,0 +1,1052 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Automatic navigational mesh generation from arbitrary scene geometry. The generator is voxel-based
+//! and works in a similar fashion as [Recast](https://github.com/recastnavigation/recastnavigation):
+//!
+//! 1) Input triangles are rasterized into a height field of solid spans.
+//! 2) Spans that are too steep, too low for an agent or too close to obstacles are marked as
+//! non-walkable.
+//! 3) Walkable cells are merged into large rectangles, which are then triangulated and connected
+//! with each other.
+//!
+//! See [`NavmeshInputData`] and [`NavmeshBakeSettings`] docs for more info and usage examples.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3},
+        math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+        pool::Handle,
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
+    graph::{BaseSceneGraph, SceneGraph},
+    scene::{
+        collider::{Collider, ColliderShape},
+        graph::Graph,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            surface::SurfaceData,
+            Mesh,
+        },
+        node::Node,
+        terrain::Terrain,
+    },
+    utils::navmesh::Navmesh,
+};
+use fxhash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// A set of parameters that defines how a navigational mesh will be generated.
+#[derive(Clone, Debug, PartialEq, Reflect, Visit, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NavmeshBakeSettings {
+    /// Horizontal size of a single voxel (in meters). Smaller values produce more precise
+    /// navigational meshes, but increase generation time and memory consumption.
+    #[reflect(min_value = 0.01, step = 0.05)]
+    pub cell_size: f32,
+    /// Vertical size of a single voxel (in meters).
+    #[reflect(min_value = 0.01, step = 0.05)]
+    pub cell_height: f32,
+    /// Radius of an agent (in meters). Walkable areas will be shrunk by this value, so an agent
+    /// won't intersect with walls.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub agent_radius: f32,
+    /// Height of an agent (in meters). Areas with lower ceiling height will be excluded from the
+    /// navigational mesh.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub agent_height: f32,
+    /// Maximum slope angle (in degrees) of a surface that is considered walkable.
+    #[reflect(min_value = 0.0, max_value = 90.0, step = 1.0)]
+    pub max_slope: f32,
+    /// Maximum height (in meters) of a ledge an agent can step on.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub step_height: f32,
+    /// Minimum area (in cells) of an isolated walkable region. Smaller regions will be discarded.
+    pub min_region_area: u32,
+}
+
+impl Default for NavmeshBakeSettings {
+    fn default() -> Self {
+        Self {
+            cell_size: 0.2,
+            cell_height: 0.1,
+            agent_radius: 0.4,
+            agent_height: 1.8,
+            max_slope: 45.0,
+            step_height: 0.4,
+            min_region_area: 8,
+        }
+    }
+}
+
+/// A set of triangles (in world coordinates) that will be used to generate a navigational mesh. It
+/// could be filled manually, or collected from a scene graph using [`NavmeshInputData::from_graph`].
+/// It is used to split the preparation step from the actual generation; to be able to put heavy
+/// generation in a separate thread.
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     scene::{mesh::Mesh, terrain::Terrain, Scene},
+/// #     utils::navmesh::{
+/// #         generator::{NavmeshBakeSettings, NavmeshInputData},
+/// #         Navmesh,
+/// #     },
+/// # };
+/// fn bake_navmesh(scene: &Scene) -> Navmesh {
+///     // Use meshes and terrains only.
+///     let input = NavmeshInputData::from_graph(&scene.graph, |_, node| {
+///         node.cast::<Mesh>().is_some() || node.cast::<Terrain>().is_some()
+///     });
+///     input.bake(&NavmeshBakeSettings::default())
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NavmeshInputData {
+    vertices: Vec<Vector3<f32>>,
+    triangles: Vec<TriangleDefinition>,
+}
+
+fn surface_data_triangles(
+    data: &SurfaceData,
+    transform: &Matrix4<f32>,
+    mut func: impl FnMut([Vector3<f32>; 3]),
+) {
+    let vertex_buffer = &data.vertex_buffer;
+    let fetch = |index: u32| {
+        vertex_buffer
+            .get(index as usize)
+            .and_then(|v| v.read_3_f32(VertexAttributeUsage::Position).ok())
+            .map(|p| transform.transform_point(&Point3::from(p)).coords)
+    };
+
+    for triangle in data.geometry_buffer.iter() {
+        if let (Some(a), Some(b), Some(c)) =
+            (fetch(triangle[0]), fetch(triangle[1]), fetch(triangle[2]))
+        {
+            func([a, b, c]);
+        }
+    }
+}
+
+impl NavmeshInputData {
+    /// Creates new empty input data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects geometry of every enabled [`Mesh`], [`Terrain`] and [`Collider`] node of the graph,
+    /// that passes the given filter.
+    pub fn from_graph<F>(graph: &Graph, mut filter: F) -> Self
+    where
+        F: FnMut(Handle<Node>, &Node) -> bool,
+    {
+        let mut input = Self::default();
+
+        for (handle, node) in graph.pair_iter() {
+            if !node.is_globally_enabled() || !filter(handle, node) {
+                continue;
+            }
+
+            if let Some(mesh) = node.cast::<Mesh>() {
+                input.add_mesh(mesh);
+            } else if let Some(terrain) = node.cast::<Terrain>() {
+                input.add_terrain(terrain);
+            } else if let Some(collider) = node.cast::<Collider>() {
+                input.add_collider(collider, graph);
+            }
+        }
+
+        input
+    }
+
+    /// Adds a triangle (in world coordinates) to the input data. Vertices of the triangle must be
+    /// in counter-clockwise order, otherwise the triangle will be treated as non-walkable.
+    pub fn add_triangle(&mut self, triangle: [Vector3<f32>; 3]) {
+        let first = self.vertices.len() as u32;
+        self.vertices.extend_from_slice(&triangle);
+        self.triangles
+            .push(TriangleDefinition([first, first + 1, first + 2]));
+    }
+
+    /// Adds every triangle of the given surface data, transformed by the given matrix, to the input data.
+    pub fn add_surface_data(&mut self, data: &SurfaceData, transform: &Matrix4<f32>) {
+        surface_data_triangles(data, transform, |triangle| self.add_triangle(triangle));
+    }
+
+    /// Adds every surface of the given mesh to the input data.
+    pub fn add_mesh(&mut self, mesh: &Mesh) {
+        let global_transform = mesh.global_transform();
+        for surface in mesh.surfaces() {
+            let data = surface.data();
+            self.add_surface_data(&data.data_ref(), &global_transform);
+        }
+    }
+
+    /// Adds the height map of the given terrain to the input data. Holes are excluded.
+    pub fn add_terrain(&mut self, terrain: &Terrain) {
+        let global_transform = terrain.global_transform();
+        let chunk_size = terrain.height_map_size().map(|x| x as i32 - 3);
+        let to_world = |position: Vector2<i32>| {
+            let height = terrain.get_height(position)?;
+            let local = terrain.height_pos_to_local(position);
+            Some(
+                global_transform
+                    .transform_point(&Point3::new(local.x, height, local.y))
+                    .coords,
+            )
+        };
+
+        for chunk in terrain.chunks_ref() {
+            let origin = terrain.chunk_height_pos_origin(chunk.grid_position());
+            for y in 0..chunk_size.y {
+                for x in 0..chunk_size.x {
+                    let position = origin + Vector2::new(x, y);
+
+                    if terrain.holes_enabled()
+                        && terrain
+                            .get_hole_mask(position)
+                            .is_some_and(|mask| mask < 128)
+                    {
+                        continue;
+                    }
+
+                    let (Some(a), Some(b), Some(c), Some(d)) = (
+                        to_world(position),
+                        to_world(position + Vector2::new(1, 0)),
+                        to_world(position + Vector2::new(1, 1)),
+                        to_world(position + Vector2::new(0, 1)),
+                    ) else {
+                        continue;
+                    };
+
+                    self.add_triangle([a, d, c]);
+                    self.add_triangle([a, c, b]);
+                }
+            }
+        }
+    }
+
+    /// Adds the shape of the given collider to the input data. Trimesh, height field and convex
+    /// polyhedron shapes use the graph to fetch their geometry sources.
+    pub fn add_collider(&mut self, collider: &Collider, graph: &Graph) {
+        let transform = collider.global_transform();
+
+        match collider.shape() {
+            ColliderShape::Ball(ball) => {
+                self.add_surface_data(
+                    &SurfaceData::make_sphere(8, 16, ball.radius, &Matrix4::identity()),
+                    &transform,
+                );
+            }
+            ColliderShape::Cylinder(cylinder) => {
+                self.add_surface_data(
+                    &SurfaceData::make_cylinder(
+                        16,
+                        cylinder.radius,
+                        cylinder.half_height * 2.0,
+                        true,
+                        &Matrix4::new_translation(&Vector3::new(0.0, -cylinder.half_height, 0.0)),
+                    ),
+                    &transform,
+                );
+            }
+            ColliderShape::Cone(cone) => {
+                self.add_surface_data(
+                    &SurfaceData::make_cone(
+                        16,
+                        cone.radius,
+                        cone.half_height * 2.0,
+                        &Matrix4::new_translation(&Vector3::new(0.0, -cone.half_height, 0.0)),
+                    ),
+                    &transform,
+                );
+            }
+            ColliderShape::Cuboid(cuboid) => {
+                self.add_surface_data(
+                    &SurfaceData::make_cube(Matrix4::new_nonuniform_scaling(
+                        &cuboid.half_extents.scale(2.0),
+                    )),
+                    &transform,
+                );
+            }
+            ColliderShape::Capsule(capsule) => {
+                // Capsule is approximated by a cylinder with its caps extended by the radius.
+                let axis = capsule.end - capsule.begin;
+                let length = axis.norm();
+                let height = length + capsule.radius * 2.0;
+                let rotation = UnitQuaternion::rotation_between(&Vector3::y(), &axis)
+                    .unwrap_or_else(|| {
+                        UnitQuaternion::from_axis_angle(&Vector3::x_axis(), std::f32::consts::PI)
+                    });
+                let center = (capsule.begin + capsule.end).scale(0.5);
+                self.add_surface_data(
+                    &SurfaceData::make_cylinder(
+                        16,
+                        capsule.radius,
+                        height,
+                        true,
+                        &(Matrix4::new_translation(&center)
+                            * rotation.to_homogeneous()
+                            * Matrix4::new_translation(&Vector3::new(0.0, -height * 0.5, 0.0))),
+                    ),
+                    &transform,
+                );
+            }
+            ColliderShape::Triangle(triangle) => {
+                let [a, b, c] = [triangle.a, triangle.b, triangle.c]
+                    .map(|p| transform.transform_point(&Point3::from(p)).coords);
+                self.add_triangle([a, b, c]);
+            }
+            ColliderShape::Trimesh(trimesh) => {
+                for source in trimesh.sources.iter() {
+                    if let Some(mesh) = graph.try_get(source.0).and_then(|n| n.cast::<Mesh>()) {
+                        self.add_mesh(mesh);
+                    }
+                }
+            }
+            ColliderShape::Heightfield(heightfield) => {
+                if let Some(terrain) = graph
+                    .try_get(heightfield.geometry_source.0)
+                    .and_then(|n| n.cast::<Terrain>())
+                {
+                    self.add_terrain(terrain);
+                }
+            }
+            ColliderShape::Polyhedron(polyhedron) => {
+                if let Some(mesh) = graph
+                    .try_get(polyhedron.geometry_source.0)
+                    .and_then(|n| n.cast::<Mesh>())
+                {
+                    self.add_mesh(mesh);
+                }
+            }
+            // Segments have no area.
+            ColliderShape::Segment(_) => {}
+        }
+    }
+
+    /// Returns a reference to the vertices of the input data.
+    pub fn vertices(&self) -> &[Vector3<f32>] {
+        &self.vertices
+    }
+
+    /// Returns a reference to the triangles of the input data.
+    pub fn triangles(&self) -> &[TriangleDefinition] {
+        &self.triangles
+    }
+
+    /// Generates a new navigational mesh using the given settings. See [`Navmesh::bake`] for more info.
+    pub fn bake(&self, settings: &NavmeshBakeSettings) -> Navmesh {
+        Navmesh::bake(self, settings)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Span {
+    min: i32,
+    max: i32,
+    walkable: bool,
+}
+
+/// Solid height field - a grid of columns, each containing a sorted list of solid spans.
+struct HeightField {
+    width: i32,
+    depth: i32,
+    origin: Vector3<f32>,
+    cell_size: f32,
+    cell_height: f32,
+    columns: Vec<Vec<Span>>,
+}
+
+// Splits a convex polygon by an axis-aligned line, returns (lower, upper) parts.
+fn split_polygon(
+    polygon: &[Vector3<f32>],
+    axis: usize,
+    offset: f32,
+) -> (Vec<Vector3<f32>>, Vec<Vector3<f32>>) {
+    let mut lower = Vec::with_capacity(polygon.len() + 1);
+    let mut upper = Vec::with_capacity(polygon.len() + 1);
+
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        let da = a[axis] - offset;
+        let db = b[axis] - offset;
+
+        if da <= 0.0 {
+            lower.push(a);
+        }
+        if da >= 0.0 {
+            upper.push(a);
+        }
+
+        if (da < 0.0 && db > 0.0) || (da > 0.0 && db < 0.0) {
+            let t = da / (da - db);
+            let p = a.lerp(&b, t);
+            lower.push(p);
+            upper.push(p);
+        }
+    }
+
+    (lower, upper)
+}
+
+impl HeightField {
+    fn new(bounds: &AxisAlignedBoundingBox, cell_size: f32, cell_height: f32) -> Self {
+        let size = bounds.max - bounds.min;
+        let width = ((size.x / cell_size).ceil() as i32).max(1);
+        let depth = ((size.z / cell_size).ceil() as i32).max(1);
+        Self {
+            width,
+            depth,
+            origin: bounds.min,
+            cell_size,
+            cell_height,
+            columns: vec![Vec::new(); (width * depth) as usize],
+        }
+    }
+
+    fn column_index(&self, x: i32, z: i32) -> usize {
+        (z * self.width + x) as usize
+    }
+
+    fn add_span(&mut self, x: i32, z: i32, mut new: Span, merge_threshold: i32) {
+        let index = self.column_index(x, z);
+        let column = &mut self.columns[index];
+
+        // Spans are sorted by their bottom, merge every span that overlaps with the new one.
+        let mut i = 0;
+        while i < column.len() {
+            let span = column[i];
+            if span.min > new.max {
+                break;
+            } else if span.max < new.min {
+                i += 1;
+            } else {
+                if (span.max - new.max).abs() <= merge_threshold {
+                    new.walkable |= span.walkable;
+                } else if span.max > new.max {
+                    new.walkable = span.walkable;
+                }
+                new.min = new.min.min(span.min);
+                new.max = new.max.max(span.max);
+                column.remove(i);
+            }
+        }
+
+        column.insert(i, new);
+    }
+
+    fn rasterize_triangle(
+        &mut self,
+        triangle: [Vector3<f32>; 3],
+        walkable: bool,
+        merge_threshold: i32,
+    ) {
+        let min = triangle[0].inf(&triangle[1]).inf(&triangle[2]);
+        let max = triangle[0].sup(&triangle[1]).sup(&triangle[2]);
+
+        let z0 =
+            (((min.z - self.origin.z) / self.cell_size).floor() as i32).clamp(0, self.depth - 1);
+        let z1 =
+            (((max.z - self.origin.z) / self.cell_size).floor() as i32).clamp(0, self.depth - 1);
+
+        let mut rest = triangle.to_vec();
+        for z in z0..=z1 {
+            let (row, next) =
+                split_polygon(&rest, 2, self.origin.z + (z + 1) as f32 * self.cell_size);
+            rest = next;
+            if row.len() < 3 {
+                continue;
+            }
+
+            let row_min_x = row.iter().map(|p| p.x).fold(f32::MAX, f32::min);
+            let row_max_x = row.iter().map(|p| p.x).fold(f32::MIN, f32::max);
+            let x0 = (((row_min_x - self.origin.x) / self.cell_size).floor() as i32)
+                .clamp(0, self.width - 1);
+            let x1 = (((row_max_x - self.origin.x) / self.cell_size).floor() as i32)
+                .clamp(0, self.width - 1);
+
+            let mut row_rest = row;
+            for x in x0..=x1 {
+                let (cell, next) = split_polygon(
+                    &row_rest,
+                    0,
+                    self.origin.x + (x + 1) as f32 * self.cell_size,
+                );
+                row_rest = next;
+                if cell.len() < 3 {
+                    continue;
+                }
+
+                let cell_min_y = cell.iter().map(|p| p.y).fold(f32::MAX, f32::min);
+                let cell_max_y = cell.iter().map(|p| p.y).fold(f32::MIN, f32::max);
+                let span_min = ((cell_min_y - self.origin.y) / self.cell_height).floor() as i32;
+                let span_max = (((cell_max_y - self.origin.y) / self.cell_height).ceil() as i32)
+                    .max(span_min + 1);
+
+                self.add_span(
+                    x,
+                    z,
+                    Span {
+                        min: span_min,
+                        max: span_max,
+                        walkable,
+                    },
+                    merge_threshold,
+                );
+            }
+        }
+    }
+}
+
+// Directions of the neighbouring cells: -x, +z, +x, -z.
+const DIRECTIONS: [(i32, i32); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Open (walkable) space above a solid span.
+#[derive(Clone, Debug)]
+struct Cell {
+    x: i32,
+    z: i32,
+    floor: i32,
+    ceiling: i32,
+    walkable: bool,
+    neighbours: [Option<usize>; 4],
+}
+
+struct CompactHeightField {
+    width: i32,
+    depth: i32,
+    // Range of cells for each column.
+    columns: Vec<(usize, usize)>,
+    cells: Vec<Cell>,
+}
+
+impl CompactHeightField {
+    fn new(height_field: &HeightField, agent_height: i32, agent_climb: i32) -> Self {
+        let mut columns = Vec::with_capacity(height_field.columns.len());
+        let mut cells = Vec::new();
+
+        for z in 0..height_field.depth {
+            for x in 0..height_field.width {
+                let column = &height_field.columns[height_field.column_index(x, z)];
+                let start = cells.len();
+                for (i, span) in column.iter().enumerate() {
+                    if !span.walkable {
+                        continue;
+                    }
+                    let ceiling = column.get(i + 1).map_or(i32::MAX, |next| next.min);
+                    if ceiling.saturating_sub(span.max) < agent_height {
+                        continue;
+                    }
+                    cells.push(Cell {
+                        x,
+                        z,
+                        floor: span.max,
+                        ceiling,
+                        walkable: true,
+                        neighbours: [None; 4],
+                    });
+                }
+                columns.push((start, cells.len()));
+            }
+        }
+
+        let mut compact = Self {
+            width: height_field.width,
+            depth: height_field.depth,
+            columns,
+            cells,
+        };
+
+        for index in 0..compact.cells.len() {
+            let cell = compact.cells[index].clone();
+            for (direction, (dx, dz)) in DIRECTIONS.iter().enumerate() {
+                let (nx, nz) = (cell.x + dx, cell.z + dz);
+                if nx < 0 || nz < 0 || nx >= compact.width || nz >= compact.depth {
+                    continue;
+                }
+                let (start, end) = compact.columns[(nz * compact.width + nx) as usize];
+                for neighbour_index in start..end {
+                    let neighbour = &compact.cells[neighbour_index];
+                    let bottom = cell.floor.max(neighbour.floor);
+                    let top = cell.ceiling.min(neighbour.ceiling);
+                    if top.saturating_sub(bottom) >= agent_height
+                        && (neighbour.floor - cell.floor).abs() <= agent_climb
+                    {
+                        compact.cells[index].neighbours[direction] = Some(neighbour_index);
+                        break;
+                    }
+                }
+            }
+        }
+
+        compact
+    }
+
+    fn neighbour(&self, cell: usize, direction: usize) -> Option<usize> {
+        self.cells[cell].neighbours[direction].filter(|n| self.cells[*n].walkable)
+    }
+
+    /// Marks every cell that is closer than the given radius to a border as non-walkable.
+    fn erode(&mut self, radius: i32) {
+        if radius <= 0 {
+            return;
+        }
+
+        let mut distances = vec![u16::MAX; self.cells.len()];
+        for (index, distance) in distances.iter_mut().enumerate() {
+            if (0..4).any(|direction| self.neighbour(index, direction).is_none()) {
+                *distance = 0;
+            }
+        }
+
+        let relax = |distances: &mut [u16], index: usize, steps: [usize; 2]| {
+            // Straight neighbour costs 2, diagonal neighbour costs 3.
+            if let Some(neighbour) = self.neighbour(index, steps[0]) {
+                distances[index] = distances[index].min(distances[neighbour].saturating_add(2));
+                if let Some(diagonal) = self.neighbour(neighbour, steps[1]) {
+                    distances[index] = distances[index].min(distances[diagonal].saturating_add(3));
+                }
+            }
+        };
+
+        // Forward pass: -x and -z neighbours.
+        for index in 0..self.cells.len() {
+            relax(&mut distances, index, [0, 3]);
+            relax(&mut distances, index, [3, 2]);
+        }
+
+        // Backward pass: +x and +z neighbours.
+        for index in (0..self.cells.len()).rev() {
+            relax(&mut distances, index, [2, 1]);
+            relax(&mut distances, index, [1, 0]);
+        }
+
+        let threshold = (radius * 2).min(u16::MAX as i32) as u16;
+        for (cell, distance) in self.cells.iter_mut().zip(distances) {
+            if distance < threshold {
+                cell.walkable = false;
+            }
+        }
+    }
+
+    /// Removes isolated walkable regions that have less cells than specified.
+    fn filter_small_regions(&mut self, min_area: usize) {
+        if min_area <= 1 {
+            return;
+        }
+
+        let mut visited = vec![false; self.cells.len()];
+        let mut region = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..self.cells.len() {
+            if visited[start] || !self.cells[start].walkable {
+                continue;
+            }
+
+            region.clear();
+            stack.push(start);
+            visited[start] = true;
+            while let Some(index) = stack.pop() {
+                region.push(index);
+                for direction in 0..4 {
+                    if let Some(neighbour) = self.neighbour(index, direction) {
+                        if !visited[neighbour] {
+                            visited[neighbour] = true;
+                            stack.push(neighbour);
+                        }
+                    }
+                }
+            }
+
+            if region.len() < min_area {
+                for &index in region.iter() {
+                    self.cells[index].walkable = false;
+                }
+            }
+        }
+    }
+}
+
+/// A set of cells with the same floor height, merged into a rectangle.
+struct Rect {
+    x: i32,
+    z: i32,
+    width: i32,
+    depth: i32,
+    floor: i32,
+    // Row-major (x first) list of cells.
+    cells: Vec<usize>,
+}
+
+impl Rect {
+    fn cell_at_corner(&self, cx: i32, cz: i32) -> usize {
+        let x = (cx - self.x).clamp(0, self.width - 1);
+        let z = (cz - self.z).clamp(0, self.depth - 1);
+        self.cells[(z * self.width + x) as usize]
+    }
+
+    // Returns corners of the rectangle in counter-clockwise order (when looking from above).
+    fn perimeter(&self) -> Vec<(i32, i32)> {
+        let (x0, z0, x1, z1) = (self.x, self.z, self.x + self.width, self.z + self.depth);
+        let mut corners = Vec::new();
+        corners.extend((z0..z1).map(|z| (x0, z)));
+        corners.extend((x0..x1).map(|x| (x, z1)));
+        corners.extend((z0 + 1..=z1).rev().map(|z| (x1, z)));
+        corners.extend((x0 + 1..=x1).rev().map(|x| (x, z0)));
+        corners
+    }
+}
+
+fn merge_rects(compact: &CompactHeightField) -> Vec<Rect> {
+    let mut assigned = vec![false; compact.cells.len()];
+    let mut rects = Vec::new();
+
+    let can_take = |assigned: &[bool], cell: Option<usize>, floor: i32| -> Option<usize> {
+        cell.filter(|&c| !assigned[c] && compact.cells[c].floor == floor)
+    };
+
+    for start in 0..compact.cells.len() {
+        if assigned[start] || !compact.cells[start].walkable {
+            continue;
+        }
+
+        let floor = compact.cells[start].floor;
+
+        // Grow along X axis first.
+        let mut row = vec![start];
+        assigned[start] = true;
+        while let Some(next) =
+            can_take(&assigned, compact.neighbour(*row.last().unwrap(), 2), floor)
+        {
+            assigned[next] = true;
+            row.push(next);
+        }
+
+        // Then try to add as much rows as possible along Z axis.
+        let mut cells = row.clone();
+        let mut depth = 1;
+        'row_loop: loop {
+            let mut next_row = Vec::with_capacity(row.len());
+            for (i, &cell) in row.iter().enumerate() {
+                let Some(next) = can_take(&assigned, compact.neighbour(cell, 1), floor) else {
+                    break 'row_loop;
+                };
+                if i > 0 && compact.neighbour(next_row[i - 1], 2) != Some(next) {
+                    break 'row_loop;
+                }
+                next_row.push(next);
+            }
+            for &cell in next_row.iter() {
+                assigned[cell] = true;
+            }
+            cells.extend_from_slice(&next_row);
+            row = next_row;
+            depth += 1;
+        }
+
+        let first = &compact.cells[start];
+        rects.push(Rect {
+            x: first.x,
+            z: first.z,
+            width: (cells.len() / depth) as i32,
+            depth: depth as i32,
+            floor,
+            cells,
+        });
+    }
+
+    rects
+}
+
+/// Disjoint sets of cell corners, every set represents a single vertex shared by multiple cells.
+#[derive(Default)]
+struct CornerSets {
+    parents: Vec<usize>,
+    corners: FxHashMap<((i32, i32), usize), usize>,
+}
+
+impl CornerSets {
+    fn get_or_insert(&mut self, corner: (i32, i32), cell: usize) -> usize {
+        *self.corners.entry((corner, cell)).or_insert_with(|| {
+            self.parents.push(self.parents.len());
+            self.parents.len() - 1
+        })
+    }
+
+    fn find(&mut self, corner: (i32, i32), cell: usize) -> usize {
+        let mut index = self.get_or_insert(corner, cell);
+        while self.parents[index] != index {
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+
+    fn union(&mut self, corner: (i32, i32), a: usize, b: usize) {
+        let a = self.find(corner, a);
+        let b = self.find(corner, b);
+        if a != b {
+            self.parents[b] = a;
+        }
+    }
+}
+
+// Corners of a cell shared with its neighbour in the given direction.
+fn shared_corners(x: i32, z: i32, direction: usize) -> [(i32, i32); 2] {
+    match direction {
+        0 => [(x, z), (x, z + 1)],
+        1 => [(x, z + 1), (x + 1, z + 1)],
+        2 => [(x + 1, z), (x + 1, z + 1)],
+        _ => [(x, z), (x + 1, z)],
+    }
+}
+
+impl Navmesh {
+    /// Generates a new navigational mesh from the given input data. The generator voxelizes the input
+    /// geometry, filters out areas that are not walkable for an agent with the parameters specified in
+    /// the settings, and then builds a simplified triangle mesh from the walkable areas. Keep in mind,
+    /// that this method could be slow for large scenes with small cell size, so consider running it in
+    /// a separate thread.
+    pub fn bake(input: &NavmeshInputData, settings: &NavmeshBakeSettings) -> Navmesh {
+        if input.triangles.is_empty() {
+            return Navmesh::default();
+        }
+
+        let cell_size = settings.cell_size.max(0.01);
+        let cell_height = settings.cell_height.max(0.01);
+        let agent_height = (settings.agent_height / cell_height).ceil().max(1.0) as i32;
+        let agent_climb = (settings.step_height / cell_height).floor() as i32;
+        let agent_radius = (settings.agent_radius / cell_size).ceil() as i32;
+        let walkable_threshold = settings.max_slope.clamp(0.0, 90.0).to_radians().cos();
+
+        let mut bounds = AxisAlignedBoundingBox::from_points(&input.vertices);
+        // Leave some space above the highest surface so it could be walkable.
+        bounds.max.y += settings.agent_height;
+
+        let mut height_field = HeightField::new(&bounds, cell_size, cell_height);
+        for triangle in input.triangles.iter() {
+            let [a, b, c] = triangle.0.map(|i| input.vertices[i as usize]);
+            let walkable = (b - a)
+                .cross(&(c - a))
+                .try_normalize(f32::EPSILON)
+                .is_some_and(|normal| normal.y > walkable_threshold);
+            height_field.rasterize_triangle([a, b, c], walkable, agent_climb);
+        }
+
+        let mut compact = CompactHeightField::new(&height_field, agent_height, agent_climb);
+        compact.erode(agent_radius);
+        compact.filter_small_regions(settings.min_region_area as usize);
+
+        // Every cell corner could be shared by multiple cells, the cells that are connected with
+        // each other must share the same vertex.
+        let mut corner_sets = CornerSets::default();
+        for (index, cell) in compact.cells.iter().enumerate() {
+            if !cell.walkable {
+                continue;
+            }
+            for direction in 0..4 {
+                if let Some(neighbour) = compact.neighbour(index, direction) {
+                    for corner in shared_corners(cell.x, cell.z, direction) {
+                        corner_sets.union(corner, index, neighbour);
+                    }
+                }
+            }
+        }
+
+        let rects = merge_rects(&compact);
+
+        // Collect sets of corners of every rect, only these corners will be used as vertices.
+        #[derive(Default)]
+        struct CornerVertex {
+            height_sum: f32,
+            count: u32,
+            index: Option<u32>,
+            used: bool,
+        }
+
+        let mut corner_vertices = FxHashMap::<usize, CornerVertex>::default();
+        for rect in rects.iter() {
+            for (cx, cz) in rect.perimeter() {
+                let set = corner_sets.find((cx, cz), rect.cell_at_corner(cx, cz));
+                let vertex = corner_vertices.entry(set).or_default();
+                vertex.height_sum += rect.floor as f32;
+                vertex.count += 1;
+                let is_rect_corner = (cx == rect.x || cx == rect.x + rect.width)
+                    && (cz == rect.z || cz == rect.z + rect.depth);
+                vertex.used |= is_rect_corner;
+            }
+        }
+
+        let to_world = |cx: i32, cz: i32, floor: f32| {
+            Vector3::new(
+                bounds.min.x + cx as f32 * cell_size,
+                bounds.min.y + floor * cell_height,
+                bounds.min.z + cz as f32 * cell_size,
+            )
+        };
+
+        let mut vertices = Vec::new();
+        let mut triangles = Vec::new();
+        let mut polygon = Vec::new();
+        for rect in rects.iter() {
+            polygon.clear();
+            for (cx, cz) in rect.perimeter() {
+                let set = corner_sets.find((cx, cz), rect.cell_at_corner(cx, cz));
+                let vertex = corner_vertices.get_mut(&set).unwrap();
+                if !vertex.used {
+                    continue;
+                }
+                let index = *vertex.index.get_or_insert_with(|| {
+                    let index = vertices.len() as u32;
+                    vertices.push(to_world(cx, cz, vertex.height_sum / vertex.count as f32));
+                    index
+                });
+                polygon.push(index);
+            }
+
+            if polygon.len() == 4 {
+                triangles.push(TriangleDefinition([polygon[0], polygon[1], polygon[2]]));
+                triangles.push(TriangleDefinition([polygon[0], polygon[2], polygon[3]]));
+            } else {
+                // Polygon has T-junctions on its edges, triangulate it as a fan around its center
+                // to keep edges shared with neighbouring polygons.
+                let center = vertices.len() as u32;
+                vertices.push(
+                    to_world(rect.x, rect.z, rect.floor as f32)
+                        + Vector3::new(
+                            rect.width as f32 * cell_size * 0.5,
+                            0.0,
+                            rect.depth as f32 * cell_size * 0.5,
+                        ),
+                );
+                for i in 0..polygon.len() {
+                    let next = (i + 1) % polygon.len();
+                    triangles.push(TriangleDefinition([center, polygon[i], polygon[next]]));
+                }
+            }
+        }
+
+        Navmesh::new(triangles, vertices)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        utils::navmesh::generator::{NavmeshBakeSettings, NavmeshInputData},
+    };
+
+    fn add_quad(input: &mut NavmeshInputData, min: Vector3<f32>, max: Vector3<f32>) {
+        let a = Vector3::new(min.x, min.y, min.z);
+        let b = Vector3::new(min.x, min.y, max.z);
+        let c = Vector3::new(max.x, max.y, max.z);
+        let d = Vector3::new(max.x, max.y, min.z);
+        input.add_triangle([a, b, c]);
+        input.add_triangle([a, c, d]);
+    }
+
+    #[test]
+    fn test_bake_flat_plane() {
+        let mut input = NavmeshInputData::new();
+        add_quad(
+            &mut input,
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(10.0, 0.0, 10.0),
+        );
+
+        let navmesh = input.bake(&NavmeshBakeSettings {
+            agent_radius: 0.0,
+            ..Default::default()
+        });
+
+        // A flat plane must be merged into a single quad.
+        assert_eq!(navmesh.triangles().len(), 2);
+        assert_eq!(navmesh.vertices().len(), 4);
+        let (point, _) = navmesh.query_closest(Vector3::new(5.0, 1.0, 5.0)).unwrap();
+        assert!(point.y.abs() <= 0.2);
+    }
+
+    #[test]
+    fn test_bake_steep_slope_is_not_walkable() {
+        let mut input = NavmeshInputData::new();
+        add_quad(
+            &mut input,
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 10.0, 10.0),
+        );
+
+        let navmesh = input.bake(&NavmeshBakeSettings::default());
+
+        assert!(navmesh.triangles().is_empty());
+    }
+
+    #[test]
+    fn test_bake_agent_radius_shrinks_area() {
+        let mut input = NavmeshInputData::new();
+        add_quad(
+            &mut input,
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(4.0, 0.0, 4.0),
+        );
+
+        let settings = NavmeshBakeSettings {
+            agent_radius: 0.4,
+            ..Default::default()
+        };
+        let navmesh = input.bake(&settings);
+
+        assert!(!navmesh.triangles().is_empty());
+        for vertex in navmesh.vertices() {
+            assert!(vertex.x >= 0.39 && vertex.x <= 3.61);
+            assert!(vertex.z >= 0.39 && vertex.z <= 3.61);
+        }
+    }
+
+    #[test]
+    fn test_bake_connected_steps() {
+        let mut input = NavmeshInputData::new();
+        add_quad(
+            &mut input,
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(4.0, 0.0, 4.0),
+        );
+        add_quad(
+            &mut input,
+            Vector3::new(4.0, 0.2, 0.0),
+            Vector3::new(8.0, 0.2, 2.0),
+        );
+
+        let navmesh = input.bake(&NavmeshBakeSettings {
+            agent_radius: 0.0,
+            min_region_area: 0,
+            ..Default::default()
+        });
+
+        let (_, from) = navmesh.query_closest(Vector3::new(1.0, 0.0, 1.0)).unwrap();
+        let (_, to) = navmesh.query_closest(Vector3::new(7.0, 0.2, 1.0)).unwrap();
+        let mut path = Vec::new();
+        navmesh.build_path(from, to, &mut path).unwrap();
+        assert!(!path.is_empty());
+    }
+}
@@ -0,0 +1,634 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Import cache stores engine-native artifacts of source assets, that are expensive to import. See
+//! [`ImportCache`] docs for more info.
+
+use crate::{
+    core::{append_extension, io::FileLoadError, log::Log, visitor::prelude::*, Downcast},
+    io::ResourceIo,
+    loader::{BoxedLoaderFuture, LoaderPayload},
+    options::OPTIONS_EXTENSION,
+    registry::normalize_path,
+    state::LoadError,
+    ResourceData, TypedResourceData,
+};
+use std::{
+    error::Error,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Default location of the import cache. It is inside the `target` folder, so it is ignored by
+/// version control systems and it is removed by `cargo clean`.
+pub const DEFAULT_IMPORT_CACHE_PATH: &str = "target/import_cache";
+
+#[cfg(target_arch = "wasm32")]
+#[doc(hidden)]
+pub trait BaseArtifactPipeline: 'static {}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[doc(hidden)]
+pub trait BaseArtifactPipeline: Send + Sync + 'static {}
+
+impl<T> BaseArtifactPipeline for T where T: ArtifactPipeline {}
+
+/// Artifact pipeline converts the data of a resource, that was imported from a source asset (for
+/// example, an FBX model or a PNG texture), into an engine-native artifact and loads the data back
+/// from the artifact. Resource loaders provide the pipeline via
+/// [`crate::loader::ResourceLoader::artifact_pipeline`].
+pub trait ArtifactPipeline: BaseArtifactPipeline {
+    /// Version of the import process. Change it every time when the loader or the artifact format
+    /// changes, so the existing artifacts will be re-imported.
+    fn version(&self) -> u32;
+
+    /// Extension of the artifact files.
+    fn extension(&self) -> &str;
+
+    /// Checks whether an asset with the given import options (the content of its options file, if
+    /// any) is expensive to import and its artifact should be stored in the cache. The assets that
+    /// are cheap to import are loaded directly, because hashing them costs as much as importing.
+    /// Default implementation always returns `true`.
+    fn is_caching_required(
+        &self,
+        #[allow(unused_variables)] import_options: Option<&[u8]>,
+    ) -> bool {
+        true
+    }
+
+    /// Returns serialized import options, that are used for the assets without an options file.
+    /// They are hashed instead of the missing options file, so a change of the default options
+    /// invalidates the artifacts. Default implementation returns [`None`].
+    fn default_import_options(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Returns paths of the files, that are read by the importer besides the source asset with the
+    /// given path and content (for example, external buffers of glTF models). The content of these
+    /// files is hashed together with the source asset, so a change of any of them invalidates the
+    /// artifact. Default implementation returns an empty list.
+    fn dependencies(
+        &self,
+        #[allow(unused_variables)] source_path: &Path,
+        #[allow(unused_variables)] source: &[u8],
+    ) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Serializes the imported resource data into an artifact.
+    fn save_artifact(&self, data: &mut dyn ResourceData) -> Result<Vec<u8>, Box<dyn Error>>;
+
+    /// Loads the resource data from an artifact at the given path.
+    fn load_artifact(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture;
+}
+
+/// Artifact pipeline, that stores the resource data in binary format using its [`Visit`]
+/// implementation. It is suitable for the resources, whose `Visit` implementation saves all the
+/// data (for example, textures).
+pub struct VisitArtifactPipeline<T> {
+    version: u32,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> VisitArtifactPipeline<T> {
+    /// Creates new pipeline with the given version. See [`ArtifactPipeline::version`] for more
+    /// info.
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> ArtifactPipeline for VisitArtifactPipeline<T>
+where
+    T: TypedResourceData,
+{
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn extension(&self) -> &str {
+        "bin"
+    }
+
+    fn save_artifact(&self, data: &mut dyn ResourceData) -> Result<Vec<u8>, Box<dyn Error>> {
+        let data = Downcast::as_any_mut(data)
+            .downcast_mut::<T>()
+            .ok_or("Unexpected type of resource data!")?;
+        let mut visitor = Visitor::new();
+        data.visit("Data", &mut visitor)?;
+        Ok(visitor.save_binary_to_vec()?)
+    }
+
+    fn load_artifact(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        Box::pin(async move {
+            let bytes = io.load_file(&path).await.map_err(LoadError::new)?;
+            let mut visitor = Visitor::load_from_memory(&bytes).map_err(LoadError::new)?;
+            let mut data = T::default();
+            data.visit("Data", &mut visitor).map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(data))
+        })
+    }
+}
+
+/// Import cache stores engine-native artifacts of source assets in a folder. Source assets (for
+/// example, FBX models or textures with compression) are imported once and then the resource
+/// manager loads their artifacts, which is much faster. Artifacts are identified by the 128-bit
+/// hash of the source file, its import options (or the default import options of the loader, if
+/// there's no options file), the files it depends on and the version of the artifact pipeline, so
+/// any change of these invalidates the artifact and the asset will be imported again. All the file
+/// operations are performed using the [`ResourceIo`] of the resource manager.
+///
+/// The cache is disabled by default, it could be enabled using
+/// [`crate::manager::ResourceManagerState::set_import_cache`]. Only the resource loaders, that
+/// provide an [`ArtifactPipeline`] use the cache.
+#[derive(Clone, Debug)]
+pub struct ImportCache {
+    path: PathBuf,
+}
+
+impl Default for ImportCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_IMPORT_CACHE_PATH)
+    }
+}
+
+/// 128-bit FNV-1a hasher. 64-bit hashes are too short to identify the content of all the assets
+/// of a large project without collisions.
+struct ContentHasher(u128);
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self(0x6c62272e07bb014262b821756295c58d)
+    }
+}
+
+impl ContentHasher {
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u128;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    fn finish(&self) -> u128 {
+        self.0
+    }
+}
+
+impl ImportCache {
+    /// Creates new import cache, that stores artifacts in the given folder.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns a path to the folder with artifacts.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // All artifacts of a source asset have the same prefix, so the outdated ones could be found
+    // and removed.
+    fn artifact_prefix(source_path: &Path) -> String {
+        let mut hasher = ContentHasher::default();
+        hasher.write_bytes(normalize_path(source_path).to_string_lossy().as_bytes());
+        format!("{:032x}-", hasher.finish())
+    }
+
+    async fn load_import_options(source_path: &Path, io: &dyn ResourceIo) -> Option<Vec<u8>> {
+        io.load_file(&append_extension(source_path, OPTIONS_EXTENSION))
+            .await
+            .ok()
+    }
+
+    /// Returns a path of the artifact for the given source asset. It reads the source asset, its
+    /// import options and dependencies to calculate the hash of the content.
+    pub async fn artifact_path(
+        &self,
+        source_path: &Path,
+        pipeline: &dyn ArtifactPipeline,
+        io: &dyn ResourceIo,
+    ) -> Result<PathBuf, LoadError> {
+        let import_options = Self::load_import_options(source_path, io).await;
+        self.artifact_path_with_options(source_path, pipeline, import_options.as_deref(), io)
+            .await
+    }
+
+    async fn artifact_path_with_options(
+        &self,
+        source_path: &Path,
+        pipeline: &dyn ArtifactPipeline,
+        import_options: Option<&[u8]>,
+        io: &dyn ResourceIo,
+    ) -> Result<PathBuf, LoadError> {
+        let mut hasher = ContentHasher::default();
+        hasher.write(&pipeline.version().to_le_bytes());
+        let source = io.load_file(source_path).await.map_err(LoadError::new)?;
+        hasher.write_bytes(&source);
+        let default_import_options = pipeline.default_import_options();
+        if let Some(import_options) = import_options.or(default_import_options.as_deref()) {
+            hasher.write_bytes(import_options);
+        }
+        for dependency in pipeline.dependencies(source_path, &source) {
+            hasher.write_bytes(normalize_path(&dependency).to_string_lossy().as_bytes());
+            hasher.write_bytes(&io.load_file(&dependency).await.map_err(LoadError::new)?);
+        }
+        Ok(self.path.join(format!(
+            "{}{:032x}.{}",
+            Self::artifact_prefix(source_path),
+            hasher.finish(),
+            pipeline.extension()
+        )))
+    }
+
+    async fn save_artifact(
+        &self,
+        source_path: &Path,
+        artifact_path: &Path,
+        pipeline: &dyn ArtifactPipeline,
+        data: &mut dyn ResourceData,
+        io: &dyn ResourceIo,
+    ) -> Result<(), Box<dyn Error>> {
+        let bytes = pipeline.save_artifact(data)?;
+        let io_error = |err| format!("{err:?}");
+
+        // Remove outdated artifacts of the asset.
+        let prefix = Self::artifact_prefix(source_path);
+        if io.is_dir(&self.path).await {
+            for path in io.read_directory(&self.path).await.map_err(io_error)? {
+                if path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+                {
+                    io.delete_file(&path).await.map_err(io_error)?;
+                }
+            }
+        }
+
+        // Write to a temporary file first, so an interrupted write won't leave a broken artifact.
+        let temp_path = append_extension(artifact_path, "tmp");
+        io.write_file(&temp_path, &bytes).await.map_err(io_error)?;
+        io.move_file(&temp_path, artifact_path)
+            .await
+            .map_err(io_error)?;
+        Ok(())
+    }
+
+    /// Loads the resource data from the artifact of the given source asset, or imports the asset
+    /// using the given loader future and saves its artifact in the cache.
+    pub async fn load_or_import(
+        &self,
+        source_path: &Path,
+        pipeline: &dyn ArtifactPipeline,
+        io: Arc<dyn ResourceIo>,
+        import: BoxedLoaderFuture,
+    ) -> Result<LoaderPayload, LoadError> {
+        let import_options = Self::load_import_options(source_path, &*io).await;
+        if !pipeline.is_caching_required(import_options.as_deref()) {
+            return import.await;
+        }
+
+        let artifact_path = match self
+            .artifact_path_with_options(source_path, pipeline, import_options.as_deref(), &*io)
+            .await
+        {
+            Ok(artifact_path) => artifact_path,
+            Err(err) => {
+                Log::warn(format!(
+                    "Unable to calculate artifact path of {}. Reason: {:?}",
+                    source_path.display(),
+                    err
+                ));
+                return import.await;
+            }
+        };
+
+        if io.is_file(&artifact_path).await {
+            match pipeline
+                .load_artifact(artifact_path.clone(), io.clone())
+                .await
+            {
+                Ok(payload) => {
+                    Log::info(format!(
+                        "Resource {} was loaded from the import cache.",
+                        source_path.display()
+                    ));
+                    return Ok(payload);
+                }
+                Err(err) => Log::warn(format!(
+                    "Unable to load artifact {}, the resource will be imported again. Reason: {:?}",
+                    artifact_path.display(),
+                    err
+                )),
+            }
+        }
+
+        let mut payload = import.await?;
+        if let Err(err) = self
+            .save_artifact(source_path, &artifact_path, pipeline, &mut *payload.0, &*io)
+            .await
+        {
+            Log::warn(format!(
+                "Unable to save artifact of {}. Reason: {:?}",
+                source_path.display(),
+                err
+            ));
+        }
+        Ok(payload)
+    }
+
+    /// Removes all artifacts from the cache using the given resource IO.
+    pub async fn clear(&self, io: &dyn ResourceIo) -> Result<(), FileLoadError> {
+        if io.is_dir(&self.path).await {
+            for path in io.read_directory(&self.path).await? {
+                if io.is_file(&path).await {
+                    io.delete_file(&path).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        core::{futures::executor::block_on, reflect::prelude::*, uuid::uuid, TypeUuidProvider},
+        io::FsResourceIo,
+    };
+
+    #[derive(Debug, Default, Reflect, Visit)]
+    struct Stub {
+        value: u32,
+    }
+
+    impl TypeUuidProvider for Stub {
+        fn type_uuid() -> crate::core::uuid::Uuid {
+            uuid!("0b2a8d0c-5f6e-4f36-9a43-3f3c0bd6a3f1")
+        }
+    }
+
+    impl ResourceData for Stub {
+        fn type_uuid(&self) -> crate::core::uuid::Uuid {
+            <Self as TypeUuidProvider>::type_uuid()
+        }
+
+        fn save(&mut self, _path: &Path) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn can_be_saved(&self) -> bool {
+            false
+        }
+    }
+
+    // Caches only the assets with import options.
+    struct OptionalPipeline(VisitArtifactPipeline<Stub>);
+
+    impl ArtifactPipeline for OptionalPipeline {
+        fn version(&self) -> u32 {
+            self.0.version()
+        }
+
+        fn extension(&self) -> &str {
+            self.0.extension()
+        }
+
+        fn is_caching_required(&self, import_options: Option<&[u8]>) -> bool {
+            import_options.is_some()
+        }
+
+        fn save_artifact(&self, data: &mut dyn ResourceData) -> Result<Vec<u8>, Box<dyn Error>> {
+            self.0.save_artifact(data)
+        }
+
+        fn load_artifact(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+            self.0.load_artifact(path, io)
+        }
+    }
+
+    // Reads a file with the same name and `bin` extension besides the source asset.
+    struct DependentPipeline {
+        default_import_options: &'static str,
+        pipeline: VisitArtifactPipeline<Stub>,
+    }
+
+    impl DependentPipeline {
+        fn new(default_import_options: &'static str) -> Self {
+            Self {
+                default_import_options,
+                pipeline: VisitArtifactPipeline::new(1),
+            }
+        }
+    }
+
+    impl ArtifactPipeline for DependentPipeline {
+        fn version(&self) -> u32 {
+            self.pipeline.version()
+        }
+
+        fn extension(&self) -> &str {
+            self.pipeline.extension()
+        }
+
+        fn default_import_options(&self) -> Option<Vec<u8>> {
+            Some(self.default_import_options.as_bytes().to_vec())
+        }
+
+        fn dependencies(&self, source_path: &Path, _source: &[u8]) -> Vec<PathBuf> {
+            vec![source_path.with_extension("bin")]
+        }
+
+        fn save_artifact(&self, data: &mut dyn ResourceData) -> Result<Vec<u8>, Box<dyn Error>> {
+            self.pipeline.save_artifact(data)
+        }
+
+        fn load_artifact(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+            self.pipeline.load_artifact(path, io)
+        }
+    }
+
+    fn load_with(
+        cache: &ImportCache,
+        source_path: &Path,
+        pipeline: &dyn ArtifactPipeline,
+        import: Result<u32, LoadError>,
+    ) -> Result<u32, LoadError> {
+        let payload = block_on(cache.load_or_import(
+            source_path,
+            pipeline,
+            Arc::new(FsResourceIo),
+            Box::pin(async move { Ok(LoaderPayload::new(Stub { value: import? })) }),
+        ))?;
+        Ok(Downcast::as_any(&*payload.0)
+            .downcast_ref::<Stub>()
+            .unwrap()
+            .value)
+    }
+
+    fn load(
+        cache: &ImportCache,
+        source_path: &Path,
+        import: Result<u32, LoadError>,
+    ) -> Result<u32, LoadError> {
+        load_with(
+            cache,
+            source_path,
+            &VisitArtifactPipeline::<Stub>::new(1),
+            import,
+        )
+    }
+
+    #[test]
+    fn import_cache_load_or_import() {
+        let root = &std::env::temp_dir().join("fyrox_import_cache_load_or_import");
+        let _ = std::fs::remove_dir_all(root);
+        std::fs::create_dir_all(root).unwrap();
+        let source_path = root.join("source.txt");
+        std::fs::write(&source_path, "foo").unwrap();
+        let cache = ImportCache::new(root.join("cache"));
+
+        assert_eq!(load(&cache, &source_path, Ok(1)).unwrap(), 1);
+        // The data must be loaded from the artifact, without importing.
+        assert_eq!(
+            load(&cache, &source_path, Err(LoadError::new("No import"))).unwrap(),
+            1
+        );
+
+        // A change of the source or its options invalidates the artifact.
+        std::fs::write(&source_path, "bar").unwrap();
+        assert_eq!(load(&cache, &source_path, Ok(2)).unwrap(), 2);
+        std::fs::write(append_extension(&source_path, OPTIONS_EXTENSION), "()").unwrap();
+        assert_eq!(load(&cache, &source_path, Ok(3)).unwrap(), 3);
+        assert_eq!(
+            load(&cache, &source_path, Err(LoadError::new("No import"))).unwrap(),
+            3
+        );
+
+        // Outdated artifacts must be removed.
+        assert_eq!(std::fs::read_dir(cache.path()).unwrap().count(), 1);
+
+        block_on(cache.clear(&FsResourceIo)).unwrap();
+        assert_eq!(std::fs::read_dir(cache.path()).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn import_cache_skips_cheap_imports() {
+        let root = &std::env::temp_dir().join("fyrox_import_cache_skips_cheap_imports");
+        let _ = std::fs::remove_dir_all(root);
+        std::fs::create_dir_all(root).unwrap();
+        let source_path = root.join("source.txt");
+        std::fs::write(&source_path, "foo").unwrap();
+        let cache = ImportCache::new(root.join("cache"));
+        let pipeline = OptionalPipeline(VisitArtifactPipeline::new(1));
+
+        assert_eq!(
+            load_with(&cache, &source_path, &pipeline, Ok(1)).unwrap(),
+            1
+        );
+        assert!(!cache.path().exists());
+        assert!(load_with(
+            &cache,
+            &source_path,
+            &pipeline,
+            Err(LoadError::new("No import"))
+        )
+        .is_err());
+
+        std::fs::write(append_extension(&source_path, OPTIONS_EXTENSION), "()").unwrap();
+        assert_eq!(
+            load_with(&cache, &source_path, &pipeline, Ok(2)).unwrap(),
+            2
+        );
+        assert_eq!(
+            load_with(
+                &cache,
+                &source_path,
+                &pipeline,
+                Err(LoadError::new("No import"))
+            )
+            .unwrap(),
+            2
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn import_cache_hashes_default_options_and_dependencies() {
+        let root = &std::env::temp_dir().join("fyrox_import_cache_hashes_dependencies");
+        let _ = std::fs::remove_dir_all(root);
+        std::fs::create_dir_all(root).unwrap();
+        let source_path = root.join("source.txt");
+        std::fs::write(&source_path, "foo").unwrap();
+        std::fs::write(source_path.with_extension("bin"), "foo").unwrap();
+        let cache = ImportCache::new(root.join("cache"));
+        let no_import = || Err(LoadError::new("No import"));
+
+        let pipeline = DependentPipeline::new("(a: 1)");
+        assert_eq!(
+            load_with(&cache, &source_path, &pipeline, Ok(1)).unwrap(),
+            1
+        );
+        assert_eq!(
+            load_with(&cache, &source_path, &pipeline, no_import()).unwrap(),
+            1
+        );
+
+        // A change of a dependency invalidates the artifact.
+        std::fs::write(source_path.with_extension("bin"), "bar").unwrap();
+        assert_eq!(
+            load_with(&cache, &source_path, &pipeline, Ok(2)).unwrap(),
+            2
+        );
+        assert_eq!(
+            load_with(&cache, &source_path, &pipeline, no_import()).unwrap(),
+            2
+        );
+
+        // So does a change of the default import options.
+        let pipeline = DependentPipeline::new("(a: 2)");
+        assert_eq!(
+            load_with(&cache, &source_path, &pipeline, Ok(3)).unwrap(),
+            3
+        );
+        assert_eq!(
+            load_with(&cache, &source_path, &pipeline, no_import()).unwrap(),
+            3
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
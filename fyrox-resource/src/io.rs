@@ -21,6 +21,8 @@
 //! Provides an interface for IO operations that a resource loader will use, this facilliates
 //! things such as loading assets within archive files
 
+use crate::registry::normalize_path;
+use fxhash::FxHashSet;
 use fyrox_core::io::FileLoadError;
 use std::future::{ready, Future};
 use std::iter::empty;
@@ -29,6 +31,7 @@ use std::{
     fmt::Debug,
     io::{Cursor, Read, Seek},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 /// Trait for files readers ensuring they implement the required traits
@@ -53,6 +56,28 @@ pub trait ResourceIo: Send + Sync + 'static {
         dest: &'a Path,
     ) -> ResourceIoFuture<'a, Result<(), FileLoadError>>;
 
+    /// Attempts to write the given data to a file at the given path. The file (and its missing
+    /// parent directories) is created if it does not exist, otherwise its content is replaced.
+    ///
+    /// Default implementation returns an error, which is suitable for read-only sources.
+    fn write_file<'a>(
+        &'a self,
+        path: &'a Path,
+        #[allow(unused)] data: &'a [u8],
+    ) -> ResourceIoFuture<'a, Result<(), FileLoadError>> {
+        Box::pin(ready(Err(unsupported_operation("write", path))))
+    }
+
+    /// Attempts to delete a file at the given path.
+    ///
+    /// Default implementation returns an error, which is suitable for read-only sources.
+    fn delete_file<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileLoadError>> {
+        Box::pin(ready(Err(unsupported_operation("delete", path))))
+    }
+
     /// Tries to convert the path to its canonical form (normalize it in other terms). This method
     /// should guarantee correct behaviour for relative paths. Symlinks aren't mandatory to
     /// follow.
@@ -113,6 +138,16 @@ pub trait ResourceIo: Send + Sync + 'static {
     fn is_dir<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool>;
 }
 
+fn unsupported_operation(operation: &str, path: &Path) -> FileLoadError {
+    FileLoadError::Io(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!(
+            "Unable to {operation} {} file, the resource io is read-only!",
+            path.display()
+        ),
+    ))
+}
+
 /// Standard resource IO provider that uses the file system to
 /// load the file bytes
 #[derive(Default)]
@@ -151,6 +186,33 @@ impl ResourceIo for FsResourceIo {
         })
     }
 
+    fn write_file<'a>(
+        &'a self,
+        path: &'a Path,
+        data: &'a [u8],
+    ) -> ResourceIoFuture<'a, Result<(), FileLoadError>> {
+        Box::pin(async move {
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, data)?;
+            Ok(())
+        })
+    }
+
+    fn delete_file<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileLoadError>> {
+        Box::pin(async move {
+            std::fs::remove_file(path)?;
+            Ok(())
+        })
+    }
+
     fn canonicalize_path<'a>(
         &'a self,
         path: &'a Path,
@@ -228,3 +290,214 @@ impl ResourceIo for FsResourceIo {
         Box::pin(fyrox_core::io::is_dir(path))
     }
 }
+
+/// Resource IO, that combines multiple other resource IOs (layers). Layers are ordered by their
+/// priority, a file is loaded from the first layer that has it. It allows to override packed
+/// resources with a patch archive or with loose files in the file system, for example:
+/// `[FsResourceIo, patch.pak, data.pak]`. Directory listings contain the files of all the layers.
+#[derive(Default)]
+pub struct LayeredResourceIo {
+    layers: Vec<Arc<dyn ResourceIo>>,
+}
+
+impl LayeredResourceIo {
+    /// Creates new resource IO without any layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a new layer with a lower priority than the existing ones.
+    pub fn with_layer(mut self, layer: Arc<dyn ResourceIo>) -> Self {
+        self.add_layer(layer);
+        self
+    }
+
+    /// Adds a new layer with a lower priority than the existing ones.
+    pub fn add_layer(&mut self, layer: Arc<dyn ResourceIo>) {
+        self.layers.push(layer);
+    }
+
+    /// Returns a slice with all the layers, ordered by their priority.
+    pub fn layers(&self) -> &[Arc<dyn ResourceIo>] {
+        &self.layers
+    }
+
+    async fn file_layer(&self, path: &Path) -> Option<&dyn ResourceIo> {
+        for layer in self.layers.iter() {
+            if layer.is_file(path).await {
+                return Some(&**layer);
+            }
+        }
+        None
+    }
+
+    async fn merge_directories<'a>(
+        &'a self,
+        path: &'a Path,
+        walk: bool,
+    ) -> Result<PathIter, FileLoadError> {
+        let mut found = false;
+        let mut visited = FxHashSet::default();
+        let mut paths = Vec::new();
+        for layer in self.layers.iter() {
+            if !layer.is_dir(path).await {
+                continue;
+            }
+            found = true;
+            let iter = if walk {
+                layer.walk_directory(path).await?
+            } else {
+                layer.read_directory(path).await?
+            };
+            for path in iter {
+                if visited.insert(normalize_path(&path)) {
+                    paths.push(path);
+                }
+            }
+        }
+        if found {
+            let iter: PathIter = Box::new(paths.into_iter());
+            Ok(iter)
+        } else {
+            Err(FileLoadError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("There's no {} directory!", path.display()),
+            )))
+        }
+    }
+}
+
+impl ResourceIo for LayeredResourceIo {
+    fn load_file<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<Vec<u8>, FileLoadError>> {
+        Box::pin(async move {
+            match self.file_layer(path).await {
+                Some(layer) => layer.load_file(path).await,
+                None => Err(FileLoadError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("There's no {} file!", path.display()),
+                ))),
+            }
+        })
+    }
+
+    fn move_file<'a>(
+        &'a self,
+        source: &'a Path,
+        dest: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileLoadError>> {
+        Box::pin(async move {
+            match self.file_layer(source).await {
+                Some(layer) => layer.move_file(source, dest).await,
+                None => Err(FileLoadError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("There's no {} file!", source.display()),
+                ))),
+            }
+        })
+    }
+
+    fn write_file<'a>(
+        &'a self,
+        path: &'a Path,
+        data: &'a [u8],
+    ) -> ResourceIoFuture<'a, Result<(), FileLoadError>> {
+        Box::pin(async move {
+            // Existing files are overwritten in place, new files go to the top layer.
+            match self
+                .file_layer(path)
+                .await
+                .or(self.layers.first().map(|l| &**l))
+            {
+                Some(layer) => layer.write_file(path, data).await,
+                None => Err(unsupported_operation("write", path)),
+            }
+        })
+    }
+
+    fn delete_file<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileLoadError>> {
+        Box::pin(async move {
+            match self.file_layer(path).await {
+                Some(layer) => layer.delete_file(path).await,
+                None => Err(FileLoadError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("There's no {} file!", path.display()),
+                ))),
+            }
+        })
+    }
+
+    fn canonicalize_path<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<PathBuf, FileLoadError>> {
+        Box::pin(async move {
+            for layer in self.layers.iter() {
+                if layer.exists(path).await {
+                    return layer.canonicalize_path(path).await;
+                }
+            }
+            Ok(path.to_owned())
+        })
+    }
+
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileLoadError>> {
+        Box::pin(self.merge_directories(path, false))
+    }
+
+    fn walk_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileLoadError>> {
+        Box::pin(self.merge_directories(path, true))
+    }
+
+    fn file_reader<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<Box<dyn FileReader>, FileLoadError>> {
+        Box::pin(async move {
+            match self.file_layer(path).await {
+                Some(layer) => layer.file_reader(path).await,
+                None => Err(FileLoadError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("There's no {} file!", path.display()),
+                ))),
+            }
+        })
+    }
+
+    fn exists<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(async move {
+            for layer in self.layers.iter() {
+                if layer.exists(path).await {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+
+    fn is_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(async move { self.file_layer(path).await.is_some() })
+    }
+
+    fn is_dir<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(async move {
+            for layer in self.layers.iter() {
+                if layer.is_dir(path).await {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+}
@@ -20,7 +20,7 @@
 
 //! Resource management
 
-#![forbid(unsafe_code)]
+#![deny(unsafe_code)]
 #![allow(missing_docs)]
 #![allow(clippy::doc_lazy_continuation)]
 #![allow(clippy::mutable_key_type)]
@@ -33,6 +33,7 @@ use crate::{
         visitor::prelude::*,
         TypeUuidProvider,
     },
+    memory::ResourceMemoryUsage,
     state::ResourceState,
     untyped::UntypedResource,
 };
@@ -55,6 +56,7 @@ pub use fyrox_core as core;
 use fyrox_core::log::Log;
 use fyrox_core::{combine_uuids, Downcast};
 
+pub mod cache;
 pub mod constructor;
 pub mod entry;
 pub mod event;
@@ -62,7 +64,10 @@ pub mod graph;
 pub mod io;
 pub mod loader;
 pub mod manager;
+pub mod memory;
 pub mod options;
+pub mod pak;
+pub mod registry;
 pub mod state;
 pub mod untyped;
 
@@ -94,6 +99,13 @@ pub trait ResourceData: Downcast + Debug + Visit + Send + Reflect {
     /// resource type supports saving, for example there might be temporary resource type that is
     /// used only at runtime which does not need saving at all.
     fn can_be_saved(&self) -> bool;
+
+    /// Returns approximate amount of memory used by the resource data. It is used to collect
+    /// memory statistics and to enforce memory budgets of the resource manager. Default
+    /// implementation returns zero usage, which excludes the resource from budgeting.
+    fn memory_usage(&self) -> ResourceMemoryUsage {
+        ResourceMemoryUsage::default()
+    }
 }
 
 /// Extension trait for a resource data of a particular type, which adds additional functionality,
@@ -21,10 +21,16 @@
 //! Resource loader. It manages resource loading.
 
 use crate::{
-    core::uuid::Uuid, io::ResourceIo, options::BaseImportOptions, state::LoadError, ResourceData,
+    cache::ArtifactPipeline, core::uuid::Uuid, io::ResourceIo, options::BaseImportOptions,
+    state::LoadError, ResourceData,
 };
 use fyrox_core::Downcast;
-use std::{future::Future, path::PathBuf, pin::Pin, sync::Arc};
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+};
 
 #[cfg(target_arch = "wasm32")]
 #[doc(hidden)]
@@ -68,6 +74,16 @@ pub trait ResourceLoader: BaseResourceLoader {
     fn default_import_options(&self) -> Option<Box<dyn BaseImportOptions>> {
         None
     }
+
+    /// Returns an artifact pipeline for a resource at the given path, if the resource is expensive
+    /// to import and it should be stored in the import cache. See
+    /// [`crate::cache::ImportCache`] docs for more info.
+    fn artifact_pipeline(
+        &self,
+        #[allow(unused_variables)] path: &Path,
+    ) -> Option<Arc<dyn ArtifactPipeline>> {
+        None
+    }
 }
 
 pub struct LoaderPayload(pub(crate) Box<dyn ResourceData>);
@@ -22,6 +22,7 @@
 //! docs for more info.
 
 use crate::{
+    cache::ImportCache,
     collect_used_resources,
     constructor::ResourceConstructorContainer,
     core::{
@@ -32,6 +33,7 @@ use crate::{
         make_relative_path, notify,
         parking_lot::{Mutex, MutexGuard},
         task::TaskPool,
+        uuid::Uuid,
         watcher::FileSystemWatcher,
         TypeUuidProvider,
     },
@@ -39,7 +41,11 @@ use crate::{
     event::{ResourceEvent, ResourceEventBroadcaster},
     io::{FsResourceIo, ResourceIo},
     loader::{ResourceLoader, ResourceLoadersContainer},
+    memory::{ResourceMemoryUsage, ResourceTypeStatistics},
     options::OPTIONS_EXTENSION,
+    registry::{
+        metadata_path, normalize_path, ResourceMetadata, ResourceRegistry, METADATA_EXTENSION,
+    },
     state::{LoadError, ResourceState},
     untyped::ResourceKind,
     Resource, ResourceData, TypedResourceData, UntypedResource,
@@ -55,6 +61,14 @@ use std::{
     sync::Arc,
 };
 
+fn memory_usage_of(resource: &UntypedResource) -> Option<(Uuid, ResourceMemoryUsage)> {
+    if let ResourceState::Ok(ref data) = resource.0.lock().state {
+        Some((data.type_uuid(), data.memory_usage()))
+    } else {
+        None
+    }
+}
+
 /// A set of resources that can be waited for.
 #[must_use]
 #[derive(Default)]
@@ -63,6 +77,13 @@ pub struct ResourceWaitContext {
 }
 
 impl ResourceWaitContext {
+    /// Creates a new wait context for the given set of resources.
+    pub fn new(resources: impl IntoIterator<Item = UntypedResource>) -> Self {
+        Self {
+            resources: resources.into_iter().collect(),
+        }
+    }
+
     /// Wait until all resources are loaded (or failed to load).
     #[must_use]
     pub fn is_all_loaded(&self) -> bool {
@@ -211,10 +232,20 @@ pub struct ResourceManagerState {
     pub built_in_resources: BuiltInResourcesContainer,
     /// File system abstraction interface. Could be used to support virtual file systems.
     pub resource_io: Arc<dyn ResourceIo>,
+    /// A registry of unique ids of external resources. It is used to find resources that were
+    /// moved. See [`ResourceRegistry`] docs for more info.
+    pub registry: ResourceRegistry,
 
     resources: Vec<TimedEntry<UntypedResource>>,
     task_pool: Arc<TaskPool>,
     watcher: Option<FileSystemWatcher>,
+    // A path from the first half of a rename event, that was split in two events by the watcher.
+    pending_rename: Option<PathBuf>,
+    // Memory budgets (in bytes) of resource types.
+    memory_budgets: FxHashMap<Uuid, usize>,
+    // Total amount of evicted resources of every type.
+    evicted_counts: FxHashMap<Uuid, usize>,
+    import_cache: Option<ImportCache>,
 }
 
 /// Resource manager controls loading and lifetime of resource in the engine. Resource manager can hold
@@ -227,6 +258,14 @@ pub struct ResourceManagerState {
 /// source. To support such kind of resource the manager provides `built_in_resources` hash map where
 /// you can register your own built-in resource and access existing ones.
 ///
+/// ## Memory Budgets
+///
+/// Every resource reports its approximate memory usage (see [`ResourceData::memory_usage`]). It is
+/// possible to set a memory budget for a resource type via [`ResourceManager::set_memory_budget`].
+/// When the resources of a type use more memory than their budget, the manager evicts the resources
+/// that are not used by anyone else (but still cached), least recently used ones first. Use
+/// [`ResourceManagerState::memory_statistics`] to get the memory usage at runtime.
+///
 /// ## Internals
 ///
 /// It is a simple wrapper over [`ResourceManagerState`] that can be shared (cloned). In other words,
@@ -368,6 +407,91 @@ impl ResourceManager {
         self.state().request(path)
     }
 
+    /// Same as [`Self::request_untyped`], but at first tries to find the actual location of the
+    /// resource by its unique id in the resource registry. The given path is used, if the id is
+    /// unknown. See [`ResourceRegistry`] docs for more info.
+    pub fn request_untyped_by_uuid<P>(&self, resource_uuid: Uuid, path: P) -> UntypedResource
+    where
+        P: AsRef<Path>,
+    {
+        self.state().request_by_uuid(resource_uuid, path)
+    }
+
+    /// Recursively searches for resource metadata files in the given directory and fills the
+    /// resource registry. If `create_missing_metadata` is `true`, then every resource without
+    /// metadata will get a new unique id and a respective metadata file will be created. Unique ids
+    /// of already loaded resources are updated as well. Hidden directories and `target` directory
+    /// are skipped (see [`crate::registry::is_excluded_directory`]).
+    pub async fn update_registry(&self, root: impl AsRef<Path>, create_missing_metadata: bool) {
+        let io = self.resource_io();
+        let mut registry = ResourceRegistry::default();
+        let resource_paths = registry.scan(root.as_ref(), &*io).await;
+
+        if create_missing_metadata {
+            let missing_metadata = {
+                let state = self.state();
+                resource_paths
+                    .into_iter()
+                    .filter(|path| {
+                        registry.uuid_of(path).is_none()
+                            && state.registry.uuid_of(path).is_none()
+                            && state.find_loader(path).is_some()
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            for path in missing_metadata {
+                let metadata = ResourceMetadata::new_with_random_id();
+                match metadata.save(&path, &*io).await {
+                    Ok(_) => {
+                        registry.register(metadata.resource_id, &path);
+                    }
+                    Err(err) => Log::err(format!(
+                        "Unable to create metadata file for {} resource. Reason: {:?}",
+                        path.display(),
+                        err
+                    )),
+                }
+            }
+        }
+
+        let mut state = self.state();
+        for (resource_uuid, path) in registry.iter() {
+            state.registry.register(resource_uuid, path);
+        }
+
+        for resource in state.resources.iter() {
+            let mut header = resource.0.lock();
+            if let Some(path) = header.kind.path() {
+                if let Some(resource_uuid) = state.registry.uuid_of(path) {
+                    header.resource_uuid = resource_uuid;
+                }
+            }
+        }
+
+        Log::info(format!(
+            "Resource registry was updated, it has {} resources.",
+            state.registry.len()
+        ));
+    }
+
+    /// Same as [`Self::update_registry`], but the update is performed in background using the task
+    /// pool of the resource manager. Keep in mind, that the resources requested before the update
+    /// is finished are located using their paths only.
+    pub fn update_registry_in_background(
+        &self,
+        root: impl AsRef<Path>,
+        create_missing_metadata: bool,
+    ) {
+        let resource_manager = self.clone();
+        let root = root.as_ref().to_path_buf();
+        self.task_pool().spawn_task(async move {
+            resource_manager
+                .update_registry(root, create_missing_metadata)
+                .await
+        });
+    }
+
     /// Saves given resources in the specified path and registers it in resource manager, so
     /// it will be accessible through it later.
     pub fn register<P, F>(
@@ -396,7 +520,12 @@ impl ResourceManager {
             if !on_register(&mut **data, path.as_ref()) {
                 Err(ResourceRegistrationError::UnableToRegister)
             } else {
+                header.resource_uuid = state.registry.uuid_of(path.as_ref()).unwrap_or_default();
+                let is_identified = !header.resource_uuid.is_nil();
                 drop(header);
+                if !is_identified {
+                    self.spawn_metadata_creation(&state, resource.clone(), path.as_ref());
+                }
                 state.push(resource);
                 Ok(())
             }
@@ -405,6 +534,39 @@ impl ResourceManager {
         }
     }
 
+    /// Creates metadata file with a new unique id for the given resource in background. Only the
+    /// resources that were actually saved get a unique id.
+    fn spawn_metadata_creation(
+        &self,
+        state: &ResourceManagerState,
+        resource: UntypedResource,
+        path: &Path,
+    ) {
+        let resource_manager = self.clone();
+        let io = state.resource_io.clone();
+        let path = path.to_path_buf();
+        state.task_pool.spawn_task(async move {
+            if !io.is_file(&path).await {
+                return;
+            }
+            let metadata = ResourceMetadata::new_with_random_id();
+            match metadata.save(&path, &*io).await {
+                Ok(_) => {
+                    resource_manager
+                        .state()
+                        .registry
+                        .register(metadata.resource_id, &path);
+                    resource.0.lock().resource_uuid = metadata.resource_id;
+                }
+                Err(err) => Log::warn(format!(
+                    "Unable to create metadata file for {} resource. Reason: {:?}",
+                    path.display(),
+                    err
+                )),
+            }
+        });
+    }
+
     /// Attempts to move a resource from its current location to the new path.
     pub async fn move_resource(
         &self,
@@ -498,17 +660,39 @@ impl ResourceManager {
             }
         }
 
-        // Move the file with its optional import options.
+        // Move the file with its optional import options and metadata.
         io.move_file(&existing_path, &new_path).await?;
         let options_path = append_extension(&existing_path, OPTIONS_EXTENSION);
         if io.exists(&options_path).await {
             let new_options_path = append_extension(&new_path, OPTIONS_EXTENSION);
             io.move_file(&options_path, &new_options_path).await?;
         }
+        let existing_metadata_path = metadata_path(&existing_path);
+        if io.exists(&existing_metadata_path).await {
+            io.move_file(&existing_metadata_path, &metadata_path(&new_path))
+                .await?;
+        }
+        self.state().registry.move_path(&existing_path, &new_path);
 
         Ok(())
     }
 
+    /// Sets a memory budget (in bytes) for the resources of the given type. `None` removes the
+    /// budget. See [`ResourceManagerState::set_memory_budget`] for more info.
+    pub fn set_memory_budget<T>(&self, budget: Option<usize>)
+    where
+        T: TypedResourceData,
+    {
+        self.state()
+            .set_memory_budget(<T as TypeUuidProvider>::type_uuid(), budget);
+    }
+
+    /// Returns memory statistics of all resource types. See
+    /// [`ResourceManagerState::memory_statistics`] for more info.
+    pub fn memory_statistics(&self) -> Vec<ResourceTypeStatistics> {
+        self.state().memory_statistics()
+    }
+
     /// Reloads all loaded resources. Normally it should never be called, because it is **very** heavy
     /// method! This method is asynchronous, it uses all available CPU power to reload resources as
     /// fast as possible.
@@ -530,6 +714,11 @@ impl ResourceManagerState {
             built_in_resources: Default::default(),
             // Use the file system resource io by default
             resource_io: Arc::new(FsResourceIo),
+            registry: Default::default(),
+            pending_rename: None,
+            memory_budgets: Default::default(),
+            evicted_counts: Default::default(),
+            import_cache: None,
         }
     }
 
@@ -544,6 +733,17 @@ impl ResourceManagerState {
         self.resource_io = resource_io;
     }
 
+    /// Sets the import cache, that will be used to store engine-native artifacts of the source
+    /// assets. By default there is no cache. See [`ImportCache`] docs for more info.
+    pub fn set_import_cache(&mut self, import_cache: Option<ImportCache>) {
+        self.import_cache = import_cache;
+    }
+
+    /// Returns a reference to the import cache, if any.
+    pub fn import_cache(&self) -> Option<&ImportCache> {
+        self.import_cache.as_ref()
+    }
+
     /// Sets resource watcher which will track any modifications in file system and forcing
     /// the manager to reload changed resources. By default there is no watcher, since it
     /// may be an undesired effect to reload resources at runtime. This is very useful thing
@@ -609,9 +809,13 @@ impl ResourceManagerState {
             }
         });
 
+        self.enforce_memory_budgets();
+
         if let Some(watcher) = self.watcher.as_ref() {
             if let Some(evt) = watcher.try_get_event() {
-                if let notify::EventKind::Modify(_) = evt.kind {
+                if let notify::EventKind::Modify(notify::event::ModifyKind::Name(mode)) = evt.kind {
+                    self.handle_rename_event(mode, &evt.paths);
+                } else if let notify::EventKind::Modify(_) = evt.kind {
                     for path in evt.paths {
                         if let Ok(relative_path) = make_relative_path(path) {
                             if self.try_reload_resource_from_path(&relative_path) {
@@ -629,6 +833,127 @@ impl ResourceManagerState {
         }
     }
 
+    fn handle_rename_event(&mut self, mode: notify::event::RenameMode, paths: &[PathBuf]) {
+        use notify::event::RenameMode;
+
+        // Old path does not exist anymore, so it cannot be canonicalized. Use its parent
+        // directory instead.
+        fn relative_path(path: &Path) -> Option<PathBuf> {
+            make_relative_path(path).ok().or_else(|| {
+                Some(
+                    make_relative_path(path.parent()?)
+                        .ok()?
+                        .join(path.file_name()?),
+                )
+            })
+        }
+
+        let (old_path, new_path) = match (mode, paths) {
+            (RenameMode::Both, [old_path, new_path, ..]) => (old_path.clone(), new_path.clone()),
+            (RenameMode::From, [old_path, ..]) => {
+                self.pending_rename = Some(old_path.clone());
+                return;
+            }
+            (RenameMode::To, [new_path, ..]) => match self.pending_rename.take() {
+                Some(old_path) => (old_path, new_path.clone()),
+                None => return,
+            },
+            (RenameMode::Any | RenameMode::Other, [path, ..]) => {
+                if path.exists() {
+                    match self.pending_rename.take() {
+                        Some(old_path) => (old_path, path.clone()),
+                        None => return,
+                    }
+                } else {
+                    self.pending_rename = Some(path.clone());
+                    return;
+                }
+            }
+            _ => return,
+        };
+
+        if let (Some(old_path), Some(new_path)) =
+            (relative_path(&old_path), relative_path(&new_path))
+        {
+            Log::info(format!(
+                "File {} was moved to {}, updating resource registry...",
+                old_path.display(),
+                new_path.display()
+            ));
+
+            self.move_path(&old_path, &new_path);
+        }
+    }
+
+    /// Updates resource registry and paths of loaded resources after a file or a directory was
+    /// moved from `old_path` to `new_path`. Metadata files of moved resources are moved as well
+    /// (in background), if they were left at the old location. This method is called automatically,
+    /// if the resource manager has a file system watcher (see [`Self::set_watcher`]).
+    pub fn move_path(&mut self, old_path: &Path, new_path: &Path) {
+        let old_path = normalize_path(old_path);
+        let new_path = normalize_path(new_path);
+
+        // Metadata files are moved together with their resources.
+        if old_path
+            .extension()
+            .is_some_and(|ext| ext == METADATA_EXTENSION)
+        {
+            return;
+        }
+
+        let moved_resources = self
+            .registry
+            .iter()
+            .filter_map(|(resource_uuid, path)| {
+                let relative_path = path.strip_prefix(&old_path).ok()?;
+                Some((
+                    resource_uuid,
+                    path.to_path_buf(),
+                    relative_path.to_path_buf(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let mut metadata_moves = Vec::new();
+        for (resource_uuid, path, relative_path) in moved_resources {
+            let moved_path = if relative_path.as_os_str().is_empty() {
+                new_path.clone()
+            } else {
+                new_path.join(relative_path)
+            };
+            self.registry.register(resource_uuid, &moved_path);
+            metadata_moves.push((metadata_path(&path), metadata_path(&moved_path)));
+        }
+
+        if !metadata_moves.is_empty() {
+            let io = self.resource_io.clone();
+            self.task_pool.spawn_task(async move {
+                for (old_metadata_path, new_metadata_path) in metadata_moves {
+                    if io.exists(&old_metadata_path).await && !io.exists(&new_metadata_path).await {
+                        Log::verify(io.move_file(&old_metadata_path, &new_metadata_path).await);
+                    }
+                }
+            });
+        }
+
+        for resource in self.resources.iter() {
+            let mut header = resource.0.lock();
+            let Some(relative_path) = header.kind.path().and_then(|path| {
+                normalize_path(path)
+                    .strip_prefix(&old_path)
+                    .ok()
+                    .map(|p| p.to_path_buf())
+            }) else {
+                continue;
+            };
+            let moved_path = if relative_path.as_os_str().is_empty() {
+                new_path.clone()
+            } else {
+                new_path.join(relative_path)
+            };
+            header.kind.make_external(moved_path);
+        }
+    }
+
     /// Adds a new resource in the container.
     pub fn push(&mut self, resource: UntypedResource) {
         self.event_broadcaster
@@ -671,6 +996,129 @@ impl ResourceManagerState {
         self.resources.iter().map(|entry| &entry.value)
     }
 
+    /// Sets a memory budget (in bytes) for the resources of the given type. `None` removes the
+    /// budget. The budget includes both CPU and GPU memory (see [`ResourceMemoryUsage::total`]).
+    /// When the resources of the type use more memory than the budget, the manager evicts the
+    /// resources that are not used by anyone else, starting from the ones that are unused for
+    /// the longest time. Resources that are in use are never evicted, so the memory usage could
+    /// still exceed the budget.
+    pub fn set_memory_budget(&mut self, type_uuid: Uuid, budget: Option<usize>) {
+        match budget {
+            Some(budget) => {
+                self.memory_budgets.insert(type_uuid, budget);
+            }
+            None => {
+                self.memory_budgets.remove(&type_uuid);
+            }
+        }
+    }
+
+    /// Returns a memory budget (in bytes) of the resources of the given type, if any.
+    pub fn memory_budget(&self, type_uuid: Uuid) -> Option<usize> {
+        self.memory_budgets.get(&type_uuid).copied()
+    }
+
+    /// Returns memory statistics of every type of loaded resources, as well as the types that
+    /// have a memory budget. The statistics are sorted by the total memory usage (descending).
+    pub fn memory_statistics(&self) -> Vec<ResourceTypeStatistics> {
+        let mut statistics = FxHashMap::<Uuid, ResourceTypeStatistics>::default();
+        for type_uuid in self.memory_budgets.keys() {
+            statistics.entry(*type_uuid).or_default();
+        }
+        for entry in self.resources.iter() {
+            if let Some((type_uuid, usage)) = memory_usage_of(&entry.value) {
+                let type_statistics = statistics.entry(type_uuid).or_default();
+                type_statistics.count += 1;
+                type_statistics.memory_usage += usage;
+                if entry.value.use_count() <= 1 {
+                    type_statistics.unused_count += 1;
+                    type_statistics.unused_memory_usage += usage;
+                }
+            }
+        }
+
+        let constructors = self.constructors_container.map.lock();
+        let mut statistics = statistics
+            .into_iter()
+            .map(|(type_uuid, type_statistics)| ResourceTypeStatistics {
+                type_uuid,
+                type_name: constructors
+                    .get(&type_uuid)
+                    .map(|constructor| constructor.type_name.clone())
+                    .unwrap_or_default(),
+                budget: self.memory_budget(type_uuid),
+                evicted_count: self
+                    .evicted_counts
+                    .get(&type_uuid)
+                    .copied()
+                    .unwrap_or_default(),
+                ..type_statistics
+            })
+            .collect::<Vec<_>>();
+        statistics.sort_by(|a, b| {
+            b.memory_usage
+                .total()
+                .cmp(&a.memory_usage.total())
+                .then_with(|| a.type_name.cmp(&b.type_name))
+        });
+        statistics
+    }
+
+    /// Evicts unused resources of the types, that are over their memory budget. See
+    /// [`Self::set_memory_budget`] for more info. Normally, this is called from [`Self::update`].
+    pub fn enforce_memory_budgets(&mut self) {
+        if self.memory_budgets.is_empty() {
+            return;
+        }
+
+        let mut usages = FxHashMap::<Uuid, usize>::default();
+        let mut candidates = Vec::new();
+        for (index, entry) in self.resources.iter().enumerate() {
+            let Some((type_uuid, usage)) = memory_usage_of(&entry.value) else {
+                continue;
+            };
+            if !self.memory_budgets.contains_key(&type_uuid) {
+                continue;
+            }
+            *usages.entry(type_uuid).or_default() += usage.total();
+            if entry.value.use_count() <= 1 && usage.total() > 0 {
+                candidates.push((index, type_uuid, usage.total(), entry.time_to_live));
+            }
+        }
+
+        // Time-to-live of a resource decreases only while it is unused, so the resources with
+        // the lowest time-to-live are the least recently used ones.
+        candidates.sort_by(|a, b| a.3.total_cmp(&b.3));
+
+        let mut evicted = vec![false; self.resources.len()];
+        for (index, type_uuid, size, _) in candidates {
+            let usage = usages.get_mut(&type_uuid).unwrap();
+            if *usage > self.memory_budgets[&type_uuid] {
+                *usage -= size;
+                evicted[index] = true;
+                *self.evicted_counts.entry(type_uuid).or_default() += 1;
+            }
+        }
+
+        let mut index = 0;
+        self.resources.retain(|resource| {
+            let keep = !evicted[index];
+            index += 1;
+            if !keep {
+                if let Some(path) = resource.0.lock().kind.path_owned() {
+                    Log::info(format!(
+                        "Resource {} evicted because its type is over the memory budget!",
+                        path.display()
+                    ));
+
+                    self.event_broadcaster
+                        .broadcast(ResourceEvent::Removed(path));
+                }
+            }
+            keep
+        });
+    }
+
     /// Immediately destroys all resources in the manager that are not used anywhere else.
     pub fn destroy_unused_resources(&mut self) {
         self.resources
@@ -721,6 +1169,9 @@ impl ResourceManagerState {
 
                 if let Some(loader) = self.find_loader(path.as_ref()) {
                     let resource = UntypedResource::new_pending(kind, loader.data_type_uuid());
+                    if let Some(resource_uuid) = self.registry.uuid_of(&path) {
+                        resource.0.lock().resource_uuid = resource_uuid;
+                    }
                     self.spawn_loading_task(path, resource.clone(), loader, false);
                     self.push(resource.clone());
                     resource
@@ -733,6 +1184,29 @@ impl ResourceManagerState {
         }
     }
 
+    /// Tries to load a resource with the given unique id. If the id is not registered in the
+    /// resource registry, the resource is loaded from the given path.
+    pub fn request_by_uuid<P>(&mut self, resource_uuid: Uuid, path: P) -> UntypedResource
+    where
+        P: AsRef<Path>,
+    {
+        if !resource_uuid.is_nil() {
+            if let Some(actual_path) = self.registry.path_of(resource_uuid) {
+                if actual_path != normalize_path(path.as_ref()) {
+                    Log::info(format!(
+                        "Resource {} was moved to {}, using the new path.",
+                        path.as_ref().display(),
+                        actual_path.display()
+                    ));
+                    let actual_path = actual_path.to_path_buf();
+                    return self.request(actual_path);
+                }
+            }
+        }
+
+        self.request(path)
+    }
+
     fn find_loader(&self, path: &Path) -> Option<&dyn ResourceLoader> {
         path.extension().and_then(|extension| {
             self.loaders
@@ -749,9 +1223,22 @@ impl ResourceManagerState {
         reload: bool,
     ) {
         let event_broadcaster = self.event_broadcaster.clone();
-        let loader_future = loader.load(path.clone(), self.resource_io.clone());
+        let io = self.resource_io.clone();
+        let loader_future = loader.load(path.clone(), io.clone());
+        let cache = self
+            .import_cache
+            .clone()
+            .and_then(|cache| Some((cache, loader.artifact_pipeline(&path)?)));
         self.task_pool.spawn_task(async move {
-            match loader_future.await {
+            let result = match cache {
+                Some((cache, pipeline)) => {
+                    cache
+                        .load_or_import(&path, &*pipeline, io, loader_future)
+                        .await
+                }
+                None => loader_future.await,
+            };
+            match result {
                 Ok(data) => {
                     let data = data.0;
 
@@ -895,6 +1382,10 @@ mod test {
         fn can_be_saved(&self) -> bool {
             false
         }
+
+        fn memory_usage(&self) -> ResourceMemoryUsage {
+            ResourceMemoryUsage { cpu: 100, gpu: 0 }
+        }
     }
 
     impl ResourceLoader for Stub {
@@ -1048,6 +1539,49 @@ mod test {
         assert_eq!(state.len(), 0);
     }
 
+    #[test]
+    fn resource_manager_state_memory_budget() {
+        let mut state = new_resource_manager();
+        let type_uuid = <Stub as TypeUuidProvider>::type_uuid();
+
+        let used = UntypedResource::new_ok(PathBuf::from("used.txt").into(), Stub::default());
+        state.push(used.clone());
+        for (name, time_to_live) in [("a.txt", 30.0), ("b.txt", 10.0), ("c.txt", 20.0)] {
+            state.push(UntypedResource::new_ok(
+                PathBuf::from(name).into(),
+                Stub::default(),
+            ));
+            state.resources.last_mut().unwrap().time_to_live = time_to_live;
+        }
+
+        let statistics = state.memory_statistics();
+        assert_eq!(statistics.len(), 1);
+        assert_eq!(statistics[0].count, 4);
+        assert_eq!(statistics[0].unused_count, 3);
+        assert_eq!(statistics[0].memory_usage.total(), 400);
+        assert!(!statistics[0].is_over_budget());
+
+        // Only the least recently used resources must be evicted, the used one must stay.
+        state.set_memory_budget(type_uuid, Some(250));
+        state.enforce_memory_budgets();
+        assert_eq!(state.len(), 2);
+        assert!(state.find("used.txt").is_some());
+        assert!(state.find("a.txt").is_some());
+
+        state.set_memory_budget(type_uuid, Some(0));
+        state.enforce_memory_budgets();
+        assert_eq!(state.len(), 1);
+        assert!(state.find("used.txt").is_some());
+
+        let statistics = state.memory_statistics();
+        assert_eq!(statistics[0].budget, Some(0));
+        assert_eq!(statistics[0].evicted_count, 3);
+        assert!(statistics[0].is_over_budget());
+
+        state.set_memory_budget(type_uuid, None);
+        assert_eq!(state.memory_budget(type_uuid), None);
+    }
+
     #[test]
     fn resource_manager_state_request() {
         let mut state = new_resource_manager();
@@ -1069,6 +1603,59 @@ mod test {
         assert!(!res.is_loading());
     }
 
+    #[test]
+    fn resource_manager_state_request_by_uuid() {
+        let mut state = new_resource_manager();
+        state.loaders.set(Stub {});
+
+        let resource_uuid = Uuid::new_v4();
+        state
+            .registry
+            .register(resource_uuid, Path::new("data/moved.txt"));
+
+        // The registry knows the actual location of the resource.
+        let res = state.request_by_uuid(resource_uuid, "data/old.txt");
+        assert_eq!(res.kind(), ResourceKind::External("data/moved.txt".into()));
+        assert_eq!(res.resource_uuid(), resource_uuid);
+
+        // Unknown id falls back to the path.
+        let res = state.request_by_uuid(Uuid::new_v4(), "data/other.txt");
+        assert_eq!(res.kind(), ResourceKind::External("data/other.txt".into()));
+        assert!(res.resource_uuid().is_nil());
+    }
+
+    #[test]
+    fn resource_manager_state_move_path() {
+        let mut state = new_resource_manager();
+
+        let resource_uuid = Uuid::new_v4();
+        state
+            .registry
+            .register(resource_uuid, Path::new("data/foo/bar.txt"));
+        let resource = UntypedResource::new_load_error(
+            PathBuf::from("./data/foo/bar.txt").into(),
+            Default::default(),
+            Uuid::default(),
+        );
+        state.push(resource.clone());
+
+        state.move_path(Path::new("data/foo"), Path::new("data/baz"));
+        assert_eq!(
+            state.registry.path_of(resource_uuid),
+            Some(Path::new("data/baz/bar.txt"))
+        );
+        assert_eq!(
+            resource.kind(),
+            ResourceKind::External("data/baz/bar.txt".into())
+        );
+
+        state.move_path(Path::new("data/baz/bar.txt"), Path::new("data/bar.txt"));
+        assert_eq!(
+            state.registry.path_of(resource_uuid),
+            Some(Path::new("data/bar.txt"))
+        );
+    }
+
     #[test]
     fn resource_manager_state_try_reload_resource_from_path() {
         let mut state = new_resource_manager();
@@ -0,0 +1,91 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Memory usage tracking of resources. Every resource data reports its approximate memory usage
+//! via [`crate::ResourceData::memory_usage`], the resource manager uses it to collect statistics
+//! and to enforce memory budgets. See [`crate::manager::ResourceManagerState::set_memory_budget`]
+//! for more info.
+
+use crate::core::uuid::Uuid;
+use std::ops::{Add, AddAssign};
+
+/// Approximate amount of memory (in bytes) used by a resource.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceMemoryUsage {
+    /// Amount of memory in RAM.
+    pub cpu: usize,
+    /// Amount of video memory. It is an estimation of the memory, that will be used by the
+    /// renderer to store the data of the resource (textures, vertex buffers, etc.).
+    pub gpu: usize,
+}
+
+impl ResourceMemoryUsage {
+    /// Returns total amount of memory (CPU + GPU).
+    pub fn total(&self) -> usize {
+        self.cpu + self.gpu
+    }
+}
+
+impl Add for ResourceMemoryUsage {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            cpu: self.cpu + rhs.cpu,
+            gpu: self.gpu + rhs.gpu,
+        }
+    }
+}
+
+impl AddAssign for ResourceMemoryUsage {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+/// Memory statistics of resources of a particular type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResourceTypeStatistics {
+    /// Type UUID of the resources.
+    pub type_uuid: Uuid,
+    /// Type name of the resources. Empty if there's no constructor for the type.
+    pub type_name: String,
+    /// Total amount of loaded resources of the type.
+    pub count: usize,
+    /// Amount of loaded resources, that are not used by anyone except the resource manager.
+    /// Such resources are kept in the cache and could be evicted if their type is over budget.
+    pub unused_count: usize,
+    /// Memory used by all loaded resources of the type.
+    pub memory_usage: ResourceMemoryUsage,
+    /// Memory used by the unused resources of the type.
+    pub unused_memory_usage: ResourceMemoryUsage,
+    /// Memory budget (in bytes) of the type, if any.
+    pub budget: Option<usize>,
+    /// Total amount of resources of the type, that were evicted because of the budget.
+    pub evicted_count: usize,
+}
+
+impl ResourceTypeStatistics {
+    /// Returns `true` if the resources of the type use more memory than their budget allows.
+    pub fn is_over_budget(&self) -> bool {
+        self.budget
+            .is_some_and(|budget| self.memory_usage.total() > budget)
+    }
+}
@@ -63,6 +63,24 @@ where
     }
 }
 
+/// Serializes import settings in the format of options files. Returns [`None`] if the settings
+/// could not be serialized.
+pub fn serialize_import_settings<T>(settings: &T) -> Option<Vec<u8>>
+where
+    T: ImportOptions,
+{
+    ron::ser::to_string(settings).ok().map(String::into_bytes)
+}
+
+/// Tries to parse import settings from the content of an options file. Returns [`None`] if the
+/// content is malformed.
+pub fn try_parse_import_settings<T>(bytes: &[u8]) -> Option<T>
+where
+    T: ImportOptions,
+{
+    ron::de::from_bytes::<T>(bytes).ok()
+}
+
 /// Tries to load import settings for a resource. It is not part of ImportOptions trait because
 /// `async fn` is not yet supported for traits.
 pub async fn try_get_import_settings<T>(resource_path: &Path, io: &dyn ResourceIo) -> Option<T>
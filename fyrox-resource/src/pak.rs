@@ -0,0 +1,902 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Packed archive of resources, that is used to ship games with a few large files instead of
+//! thousands of small ones. See [`PakResourceIo`] docs for more info.
+//!
+//! ## Format
+//!
+//! All numbers are little-endian.
+//!
+//! - Header (32 bytes): magic `FPAK`, format version (`u32`), offset of the index (`u64`), size of
+//! the index (`u64`), reserved bytes.
+//! - Data of the entries. Data of every entry starts at an offset, that is a multiple of
+//! [`PAK_DATA_ALIGNMENT`], so uncompressed entries could be used directly from a memory-mapped
+//! archive (see [`PakResourceIo::open_mapped`]).
+//! - Index: amount of entries (`u32`), then for every entry: length of its path (`u32`), the path
+//! (UTF-8, forward slashes), offset of the data (`u64`), size of the stored data (`u64`), size of
+//! the uncompressed data (`u64`), compression method (`u8`).
+
+use crate::{
+    core::{io::FileLoadError, log::Log},
+    io::{FileReader, PathIter, ResourceIo, ResourceIoFuture},
+    registry::normalize_path,
+};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use fxhash::{FxHashMap, FxHashSet};
+use std::{
+    fmt::{Debug, Formatter},
+    fs::File,
+    future::ready,
+    io::{self, Cursor, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Extension of archive files.
+pub const PAK_EXTENSION: &str = "pak";
+
+/// Data of every entry in an archive starts at an offset, that is a multiple of this value.
+pub const PAK_DATA_ALIGNMENT: u64 = 16;
+
+const PAK_MAGIC: [u8; 4] = *b"FPAK";
+const PAK_VERSION: u32 = 1;
+const PAK_HEADER_SIZE: u64 = 32;
+// Deflate can't compress data more than 1032 times, a larger ratio means a corrupt index.
+const PAK_MAX_DEFLATE_RATIO: u64 = 1032;
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn not_found(path: &Path) -> FileLoadError {
+    FileLoadError::Io(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("There's no {} file in the archive!", path.display()),
+    ))
+}
+
+/// Compression method of an archive entry.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PakCompression {
+    /// The data is stored as is.
+    #[default]
+    None,
+    /// The data is compressed using Deflate algorithm.
+    Deflate,
+}
+
+impl PakCompression {
+    fn from_u8(value: u8) -> io::Result<Self> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Deflate),
+            _ => Err(invalid_data(format!("Unknown compression method {value}!"))),
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Deflate => 1,
+        }
+    }
+}
+
+/// Location of a file inside an archive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PakEntry {
+    /// Offset of the data from the beginning of the archive.
+    pub offset: u64,
+    /// Size of the data in the archive.
+    pub stored_size: u64,
+    /// Size of the uncompressed data.
+    pub size: u64,
+    /// Compression method of the data.
+    pub compression: PakCompression,
+}
+
+/// Writes an archive, that could be read by [`PakResourceIo`]. Paths of the files in the archive
+/// are normalized (see [`normalize_path`]) and should be relative to the working directory of
+/// the game, for example `data/textures/foo.png`.
+pub struct PakWriter<W: Write + Seek> {
+    writer: W,
+    entries: Vec<(String, PakEntry)>,
+    paths: FxHashSet<String>,
+    position: u64,
+}
+
+impl<W: Write + Seek> PakWriter<W> {
+    /// Creates new archive writer, that writes the archive to the given destination.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        // The header is written when the archive is finished, when the index location is known.
+        writer.write_all(&[0; PAK_HEADER_SIZE as usize])?;
+        Ok(Self {
+            writer,
+            entries: Default::default(),
+            paths: Default::default(),
+            position: PAK_HEADER_SIZE,
+        })
+    }
+
+    /// Adds a file with the given data to the archive. Compressed data is stored only if it is
+    /// smaller than the uncompressed data. Returns an error if the archive already has a file with
+    /// the same path.
+    pub fn add_file(
+        &mut self,
+        path: &Path,
+        data: &[u8],
+        compression: PakCompression,
+    ) -> io::Result<()> {
+        let path = normalize_path(path).to_string_lossy().to_string();
+        if !self.paths.insert(path.clone()) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("The archive already has {path} file!"),
+            ));
+        }
+
+        let compressed = match compression {
+            PakCompression::None => None,
+            PakCompression::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data)?;
+                Some(encoder.finish()?).filter(|compressed| compressed.len() < data.len())
+            }
+        };
+        let (stored_data, compression) = match compressed.as_ref() {
+            Some(compressed) => (compressed.as_slice(), PakCompression::Deflate),
+            None => (data, PakCompression::None),
+        };
+
+        let padding = self.position.next_multiple_of(PAK_DATA_ALIGNMENT) - self.position;
+        self.writer.write_all(&vec![0; padding as usize])?;
+        self.position += padding;
+
+        self.writer.write_all(stored_data)?;
+        self.entries.push((
+            path,
+            PakEntry {
+                offset: self.position,
+                stored_size: stored_data.len() as u64,
+                size: data.len() as u64,
+                compression,
+            },
+        ));
+        self.position += stored_data.len() as u64;
+
+        Ok(())
+    }
+
+    /// Recursively adds all files from the given directory, that pass the filter. Paths of the
+    /// files in the archive will be the same as in the file system. Returns the amount of added
+    /// files.
+    pub fn add_directory(
+        &mut self,
+        path: &Path,
+        mut filter: impl FnMut(&Path) -> bool,
+        compression: PakCompression,
+    ) -> io::Result<usize> {
+        let mut count = 0;
+        for entry in walkdir::WalkDir::new(path) {
+            let entry = entry?;
+            if entry.file_type().is_file() && filter(entry.path()) {
+                let data = std::fs::read(entry.path())?;
+                self.add_file(entry.path(), &data, compression)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Writes the index of the archive and returns the destination.
+    pub fn finish(mut self) -> io::Result<W> {
+        let index_offset = self.position;
+        let mut index = Vec::new();
+        index.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for (path, entry) in self.entries.iter() {
+            index.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            index.extend_from_slice(path.as_bytes());
+            index.extend_from_slice(&entry.offset.to_le_bytes());
+            index.extend_from_slice(&entry.stored_size.to_le_bytes());
+            index.extend_from_slice(&entry.size.to_le_bytes());
+            index.push(entry.compression.as_u8());
+        }
+        self.writer.write_all(&index)?;
+
+        let mut header = Vec::with_capacity(PAK_HEADER_SIZE as usize);
+        header.extend_from_slice(&PAK_MAGIC);
+        header.extend_from_slice(&PAK_VERSION.to_le_bytes());
+        header.extend_from_slice(&index_offset.to_le_bytes());
+        header.extend_from_slice(&(index.len() as u64).to_le_bytes());
+        header.resize(PAK_HEADER_SIZE as usize, 0);
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&header)?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+struct IndexReader<'a> {
+    data: &'a [u8],
+}
+
+impl IndexReader<'_> {
+    fn bytes(&mut self, count: usize) -> io::Result<&[u8]> {
+        if self.data.len() < count {
+            return Err(invalid_data("Unexpected end of the archive index!"));
+        }
+        let (bytes, rest) = self.data.split_at(count);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
+
+/// Data of an archive, that is fully loaded in memory (or memory-mapped).
+type SharedData = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// A part of shared data.
+struct SharedSlice {
+    data: SharedData,
+    start: usize,
+    end: usize,
+}
+
+impl AsRef<[u8]> for SharedSlice {
+    fn as_ref(&self) -> &[u8] {
+        &(*self.data).as_ref()[self.start..self.end]
+    }
+}
+
+impl Debug for SharedSlice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SharedSlice({}..{})", self.start, self.end)
+    }
+}
+
+/// A reader of an uncompressed entry of an archive file. It has its own file handle, so multiple
+/// entries could be read at the same time.
+#[derive(Debug)]
+struct FileEntryReader {
+    file: File,
+    start: u64,
+    size: u64,
+    position: u64,
+}
+
+impl Read for FileEntryReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.size.saturating_sub(self.position);
+        let count = (buf.len() as u64).min(remaining) as usize;
+        if count == 0 {
+            return Ok(0);
+        }
+        self.file
+            .seek(SeekFrom::Start(self.start + self.position))?;
+        let read = self.file.read(&mut buf[..count])?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for FileEntryReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Invalid seek to a negative position!",
+            )),
+        }
+    }
+}
+
+enum PakSource {
+    File { path: PathBuf },
+    Memory(SharedData),
+}
+
+/// Resource IO, that reads files from a packed archive (see module docs for the format). The
+/// archive is read-only. Use [`PakWriter`] to create an archive and
+/// [`crate::io::LayeredResourceIo`] to combine multiple archives (for example, a patch archive
+/// on top of the main one) or to let the loose files in the file system override the packed ones.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use fyrox_resource::{
+///     io::{FsResourceIo, LayeredResourceIo},
+///     manager::ResourceManager,
+///     pak::PakResourceIo,
+/// };
+/// use std::sync::Arc;
+///
+/// fn use_archives(resource_manager: &ResourceManager) -> std::io::Result<()> {
+///     let io = LayeredResourceIo::new()
+///         // Loose files have the highest priority.
+///         .with_layer(Arc::new(FsResourceIo))
+///         .with_layer(Arc::new(PakResourceIo::open("patch.pak")?))
+///         .with_layer(Arc::new(PakResourceIo::open("data.pak")?));
+///     resource_manager.state().set_resource_io(Arc::new(io));
+///     Ok(())
+/// }
+/// ```
+pub struct PakResourceIo {
+    source: PakSource,
+    entries: FxHashMap<PathBuf, PakEntry>,
+    // Immediate children of every directory in the archive. Root directory has empty path.
+    directories: FxHashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl Debug for PakResourceIo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.source {
+            PakSource::File { ref path } => write!(f, "PakResourceIo({})", path.display()),
+            PakSource::Memory(_) => write!(f, "PakResourceIo(Memory)"),
+        }
+    }
+}
+
+impl PakResourceIo {
+    /// Opens an archive at the given path. Only the index of the archive is read, the files are
+    /// read on demand.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = File::open(&path)?;
+        let archive_size = file.metadata()?.len();
+        let mut header = [0; PAK_HEADER_SIZE as usize];
+        file.read_exact(&mut header)?;
+        let (index_offset, index_size) = Self::read_header(&header)?;
+        let index_range = Self::checked_range(index_offset, index_size, archive_size)
+            .ok_or_else(|| invalid_data("The index is out of bounds of the archive!"))?;
+        let mut index = vec![0; index_range.len()];
+        file.seek(SeekFrom::Start(index_offset))?;
+        file.read_exact(&mut index)?;
+        Self::new(PakSource::File { path }, &index, archive_size)
+    }
+
+    /// Opens an archive at the given path and maps it into memory. Uncompressed files are read
+    /// directly from the mapped memory, without copying them into a separate buffer. The archive
+    /// file must not be modified while it is opened, otherwise the content of the files read from
+    /// it is undefined.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_mapped(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path.as_ref())?;
+        // SAFETY: The mapping is read-only and archives are never modified by the engine. The
+        // contract to not modify the file externally is stated in the docs above.
+        #[allow(unsafe_code)]
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Self::from_data(map)
+    }
+
+    /// Creates an archive from the data in memory. It could be any container of bytes, for
+    /// example a `Vec<u8>` with the content of an archive file or a memory-mapped file.
+    pub fn from_data(data: impl AsRef<[u8]> + Send + Sync + 'static) -> io::Result<Self> {
+        let data: SharedData = Arc::new(data);
+        let bytes = (*data).as_ref();
+        let (index_offset, index_size) = Self::read_header(bytes)?;
+        let index = Self::checked_range(index_offset, index_size, bytes.len() as u64)
+            .ok_or_else(|| invalid_data("The index is out of bounds of the archive!"))?;
+        let index = bytes[index].to_vec();
+        let archive_size = bytes.len() as u64;
+        Self::new(PakSource::Memory(data), &index, archive_size)
+    }
+
+    /// Opens all archives in the given directory (not recursively). The archives are sorted by
+    /// their names in descending order, so `data_patch.pak` will be before `data.pak` and it should
+    /// have higher priority when the archives are layered.
+    pub fn open_all(directory: impl AsRef<Path>) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(directory.as_ref()) else {
+            return Default::default();
+        };
+        let mut paths = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|extension| extension == PAK_EXTENSION)
+            })
+            .collect::<Vec<_>>();
+        paths.sort_by(|a, b| b.cmp(a));
+        paths
+            .into_iter()
+            .filter_map(|path| match Self::open(&path) {
+                Ok(archive) => Some(archive),
+                Err(err) => {
+                    Log::err(format!(
+                        "Unable to open {} archive. Reason: {:?}",
+                        path.display(),
+                        err
+                    ));
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn read_header(data: &[u8]) -> io::Result<(u64, u64)> {
+        let mut reader = IndexReader {
+            data: data
+                .get(..PAK_HEADER_SIZE as usize)
+                .ok_or_else(|| invalid_data("The archive is too small!"))?,
+        };
+        if reader.bytes(4)? != PAK_MAGIC {
+            return Err(invalid_data("The file is not an archive!"));
+        }
+        let version = reader.u32()?;
+        if version != PAK_VERSION {
+            return Err(invalid_data(format!(
+                "Unsupported archive version {version}!"
+            )));
+        }
+        Ok((reader.u64()?, reader.u64()?))
+    }
+
+    // Returns a range of bytes with the given offset and size, if it is within an archive of the
+    // given size.
+    fn checked_range(offset: u64, size: u64, archive_size: u64) -> Option<Range<usize>> {
+        let end = offset
+            .checked_add(size)
+            .filter(|&end| end <= archive_size)?;
+        Some(usize::try_from(offset).ok()?..usize::try_from(end).ok()?)
+    }
+
+    fn new(source: PakSource, index: &[u8], archive_size: u64) -> io::Result<Self> {
+        let mut reader = IndexReader { data: index };
+        let count = reader.u32()?;
+        let mut entries = FxHashMap::default();
+        let mut directories = FxHashMap::<PathBuf, Vec<PathBuf>>::default();
+        directories.insert(PathBuf::new(), Vec::new());
+        for _ in 0..count {
+            let path_len = reader.u32()? as usize;
+            let path = String::from_utf8(reader.bytes(path_len)?.to_vec())
+                .map_err(|err| invalid_data(err.to_string()))?;
+            let entry = PakEntry {
+                offset: reader.u64()?,
+                stored_size: reader.u64()?,
+                size: reader.u64()?,
+                compression: PakCompression::from_u8(reader.u8()?)?,
+            };
+            if Self::checked_range(entry.offset, entry.stored_size, archive_size).is_none() {
+                return Err(invalid_data(format!(
+                    "The data of {path} is out of bounds of the archive!"
+                )));
+            }
+            let is_size_valid = match entry.compression {
+                PakCompression::None => entry.size == entry.stored_size,
+                PakCompression::Deflate => {
+                    entry.size <= entry.stored_size.saturating_mul(PAK_MAX_DEFLATE_RATIO)
+                }
+            };
+            if !is_size_valid {
+                return Err(invalid_data(format!("Invalid size of {path}!")));
+            }
+            let path = PathBuf::from(path);
+
+            // Register all parent directories.
+            let mut child = path.clone();
+            while let Some(parent) = child.parent() {
+                let children = directories.entry(parent.to_path_buf()).or_default();
+                let is_new_parent = children.is_empty();
+                if !children.contains(&child) {
+                    children.push(child.clone());
+                }
+                if !is_new_parent {
+                    break;
+                }
+                child = parent.to_path_buf();
+            }
+
+            entries.insert(path, entry);
+        }
+        for children in directories.values_mut() {
+            children.sort();
+        }
+        Ok(Self {
+            source,
+            entries,
+            directories,
+        })
+    }
+
+    /// Returns an entry of a file at the given path.
+    pub fn entry(&self, path: &Path) -> Option<&PakEntry> {
+        self.entries.get(&normalize_path(path))
+    }
+
+    /// Returns an iterator over paths of all files in the archive.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.entries.keys().map(|path| path.as_path())
+    }
+
+    fn read_stored_data(&self, entry: &PakEntry) -> io::Result<Vec<u8>> {
+        match self.source {
+            PakSource::File { ref path } => {
+                let mut file = File::open(path)?;
+                // The file could be modified after it was opened.
+                let range =
+                    Self::checked_range(entry.offset, entry.stored_size, file.metadata()?.len())
+                        .ok_or_else(|| {
+                            invalid_data("The entry is out of bounds of the archive!")
+                        })?;
+                file.seek(SeekFrom::Start(entry.offset))?;
+                let mut data = vec![0; range.len()];
+                file.read_exact(&mut data)?;
+                Ok(data)
+            }
+            PakSource::Memory(ref data) => Ok(self.memory_slice(data, entry)?.as_ref().to_vec()),
+        }
+    }
+
+    fn memory_slice(&self, data: &SharedData, entry: &PakEntry) -> io::Result<SharedSlice> {
+        let range = Self::checked_range(
+            entry.offset,
+            entry.stored_size,
+            (**data).as_ref().len() as u64,
+        )
+        .ok_or_else(|| invalid_data("The entry is out of bounds of the archive!"))?;
+        Ok(SharedSlice {
+            data: data.clone(),
+            start: range.start,
+            end: range.end,
+        })
+    }
+
+    /// Reads and decompresses a file at the given path.
+    pub fn read(&self, path: &Path) -> Result<Vec<u8>, FileLoadError> {
+        let entry = self.entry(path).ok_or_else(|| not_found(path))?;
+        let stored_data = self.read_stored_data(entry)?;
+        match entry.compression {
+            PakCompression::None => Ok(stored_data),
+            PakCompression::Deflate => {
+                let mut data = Vec::with_capacity(entry.size as usize);
+                DeflateDecoder::new(stored_data.as_slice())
+                    .take(entry.size)
+                    .read_to_end(&mut data)?;
+                if data.len() as u64 != entry.size {
+                    return Err(invalid_data(format!(
+                        "Unexpected size of decompressed {} file!",
+                        path.display()
+                    ))
+                    .into());
+                }
+                Ok(data)
+            }
+        }
+    }
+
+    fn reader(&self, path: &Path) -> Result<Box<dyn FileReader>, FileLoadError> {
+        let entry = self.entry(path).ok_or_else(|| not_found(path))?;
+        match (&self.source, entry.compression) {
+            (PakSource::File { path }, PakCompression::None) => Ok(Box::new(FileEntryReader {
+                file: File::open(path)?,
+                start: entry.offset,
+                size: entry.size,
+                position: 0,
+            })),
+            (PakSource::Memory(data), PakCompression::None) => {
+                Ok(Box::new(Cursor::new(self.memory_slice(data, entry)?)))
+            }
+            (_, PakCompression::Deflate) => Ok(Box::new(Cursor::new(self.read(path)?))),
+        }
+    }
+
+    fn walk(&self, path: &Path, paths: &mut Vec<PathBuf>) {
+        paths.push(path.to_path_buf());
+        if let Some(children) = self.directories.get(path) {
+            for child in children {
+                self.walk(child, paths);
+            }
+        }
+    }
+}
+
+impl ResourceIo for PakResourceIo {
+    fn load_file<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<Vec<u8>, FileLoadError>> {
+        Box::pin(ready(self.read(path)))
+    }
+
+    fn move_file<'a>(
+        &'a self,
+        _source: &'a Path,
+        _dest: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileLoadError>> {
+        Box::pin(ready(Err(FileLoadError::Custom(
+            "Files cannot be moved in an archive!".to_string(),
+        ))))
+    }
+
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileLoadError>> {
+        let result = match self.directories.get(&normalize_path(path)) {
+            Some(children) => {
+                let iter: PathIter = Box::new(children.clone().into_iter());
+                Ok(iter)
+            }
+            None => Err(not_found(path)),
+        };
+        Box::pin(ready(result))
+    }
+
+    fn walk_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileLoadError>> {
+        let path = normalize_path(path);
+        let result = if self.directories.contains_key(&path) || self.entries.contains_key(&path) {
+            let mut paths = Vec::new();
+            self.walk(&path, &mut paths);
+            let iter: PathIter = Box::new(paths.into_iter());
+            Ok(iter)
+        } else {
+            Err(not_found(&path))
+        };
+        Box::pin(ready(result))
+    }
+
+    fn file_reader<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<Box<dyn FileReader>, FileLoadError>> {
+        Box::pin(ready(self.reader(path)))
+    }
+
+    fn exists<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        let path = normalize_path(path);
+        Box::pin(ready(
+            self.entries.contains_key(&path) || self.directories.contains_key(&path),
+        ))
+    }
+
+    fn is_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(ready(self.entries.contains_key(&normalize_path(path))))
+    }
+
+    fn is_dir<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(ready(self.directories.contains_key(&normalize_path(path))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::{FsResourceIo, LayeredResourceIo};
+    use fyrox_core::futures::executor::block_on;
+
+    fn make_archive() -> Vec<u8> {
+        let mut writer = PakWriter::new(Cursor::new(Vec::new())).unwrap();
+        writer
+            .add_file(Path::new("./data/foo.txt"), b"foo", PakCompression::Deflate)
+            .unwrap();
+        writer
+            .add_file(
+                Path::new("data/textures/bar.txt"),
+                &[b'b'; 1000],
+                PakCompression::Deflate,
+            )
+            .unwrap();
+        assert!(writer
+            .add_file(Path::new("data/foo.txt"), b"baz", PakCompression::None)
+            .is_err());
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn pak_read_files() {
+        let io = PakResourceIo::from_data(make_archive()).unwrap();
+
+        // Small file is not compressed, because it does not make it smaller.
+        let foo = io.entry(Path::new("data/foo.txt")).unwrap();
+        assert_eq!(foo.compression, PakCompression::None);
+        assert_eq!(foo.offset % PAK_DATA_ALIGNMENT, 0);
+        let bar = io.entry(Path::new("data/textures/bar.txt")).unwrap();
+        assert_eq!(bar.compression, PakCompression::Deflate);
+        assert!(bar.stored_size < bar.size);
+        assert_eq!(bar.offset % PAK_DATA_ALIGNMENT, 0);
+
+        assert_eq!(
+            block_on(io.load_file(Path::new("data/foo.txt"))).unwrap(),
+            b"foo"
+        );
+        assert_eq!(
+            block_on(io.load_file(Path::new("./data/textures/bar.txt"))).unwrap(),
+            vec![b'b'; 1000]
+        );
+        assert!(block_on(io.load_file(Path::new("data/baz.txt"))).is_err());
+
+        let mut reader = block_on(io.file_reader(Path::new("data/foo.txt"))).unwrap();
+        reader.seek(SeekFrom::Start(1)).unwrap();
+        let mut data = String::new();
+        reader.read_to_string(&mut data).unwrap();
+        assert_eq!(data, "oo");
+    }
+
+    #[test]
+    fn pak_directories() {
+        let io = PakResourceIo::from_data(make_archive()).unwrap();
+
+        assert!(block_on(io.is_dir(Path::new("data"))));
+        assert!(block_on(io.is_dir(Path::new("data/textures"))));
+        assert!(block_on(io.is_file(Path::new("data/foo.txt"))));
+        assert!(!block_on(io.is_file(Path::new("data"))));
+        assert!(block_on(io.exists(Path::new("./data/textures"))));
+        assert!(!block_on(io.exists(Path::new("textures"))));
+
+        let children = block_on(io.read_directory(Path::new("data")))
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            children,
+            [
+                PathBuf::from("data/foo.txt"),
+                PathBuf::from("data/textures")
+            ]
+        );
+
+        let all = block_on(io.walk_directory(Path::new(".")))
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            all,
+            [
+                PathBuf::from(""),
+                PathBuf::from("data"),
+                PathBuf::from("data/foo.txt"),
+                PathBuf::from("data/textures"),
+                PathBuf::from("data/textures/bar.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn pak_file_archive_and_layers() {
+        let root = &std::env::temp_dir().join("fyrox_pak_file_archive_and_layers");
+        let _ = std::fs::remove_dir_all(root);
+        std::fs::create_dir_all(root).unwrap();
+        let archive_path = root.join("data.pak");
+        std::fs::write(&archive_path, make_archive()).unwrap();
+
+        let archive = PakResourceIo::open(&archive_path).unwrap();
+        let mut reader = block_on(archive.file_reader(Path::new("data/foo.txt"))).unwrap();
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"foo");
+
+        // A loose file overrides the packed one.
+        let patch_path = root.join("data/foo.txt");
+        std::fs::create_dir_all(patch_path.parent().unwrap()).unwrap();
+        std::fs::write(&patch_path, "patched").unwrap();
+
+        let io = LayeredResourceIo::new()
+            .with_layer(Arc::new(FsResourceIo))
+            .with_layer(Arc::new(archive));
+        assert_eq!(block_on(io.load_file(&patch_path)).unwrap(), b"patched");
+        assert_eq!(
+            block_on(io.load_file(Path::new("data/textures/bar.txt"))).unwrap(),
+            vec![b'b'; 1000]
+        );
+        assert!(block_on(io.is_dir(Path::new("data/textures"))));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn pak_mapped_archive() {
+        let path = &std::env::temp_dir().join(format!(
+            "fyrox_pak_mapped_archive_{}.pak",
+            std::process::id()
+        ));
+        std::fs::write(path, make_archive()).unwrap();
+
+        let archive = PakResourceIo::open_mapped(path).unwrap();
+        let mut reader = block_on(archive.file_reader(Path::new("data/foo.txt"))).unwrap();
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"foo");
+        assert_eq!(
+            block_on(archive.load_file(Path::new("data/textures/bar.txt"))).unwrap(),
+            vec![b'b'; 1000]
+        );
+
+        drop(reader);
+        drop(archive);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn write_u64(archive: &mut [u8], offset: usize, value: u64) {
+        archive[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn assert_invalid_data(archive: Vec<u8>) {
+        let path = &std::env::temp_dir().join(format!(
+            "fyrox_pak_corrupt_archive_{}.pak",
+            std::process::id()
+        ));
+        std::fs::write(path, &archive).unwrap();
+        let opened = PakResourceIo::open(path);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(opened.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        let loaded = PakResourceIo::from_data(archive);
+        assert_eq!(loaded.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn pak_corrupt_archives() {
+        let archive = make_archive();
+        let index_offset = u64::from_le_bytes(archive[8..16].try_into().unwrap()) as usize;
+        // Offset of the data of the first entry: amount of entries, length of the path, the path.
+        let entry_offset = index_offset + 4 + 4 + "data/foo.txt".len();
+
+        // Truncated archive.
+        assert_invalid_data(archive[..archive.len() - 1].to_vec());
+
+        // The index is out of bounds or its end overflows.
+        for (offset, size) in [(u64::MAX - 1, 16), (PAK_HEADER_SIZE, u64::MAX / 2)] {
+            let mut corrupt = archive.clone();
+            write_u64(&mut corrupt, 8, offset);
+            write_u64(&mut corrupt, 16, size);
+            assert_invalid_data(corrupt);
+        }
+
+        // The data of an entry is out of bounds or its end overflows.
+        for (offset, stored_size) in [(u64::MAX - 1, 3), (PAK_HEADER_SIZE, u64::MAX / 2)] {
+            let mut corrupt = archive.clone();
+            write_u64(&mut corrupt, entry_offset, offset);
+            write_u64(&mut corrupt, entry_offset + 8, stored_size);
+            assert_invalid_data(corrupt);
+        }
+
+        // The size of an uncompressed entry does not match its stored size.
+        let mut corrupt = archive.clone();
+        write_u64(&mut corrupt, entry_offset + 16, u64::MAX);
+        assert_invalid_data(corrupt);
+    }
+}
@@ -0,0 +1,333 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Resource registry assigns stable unique ids to external resources, so references to them
+//! survive moving and renaming of the files. See [`ResourceRegistry`] docs for more info.
+
+use crate::{
+    core::{append_extension, io::FileLoadError, log::Log, replace_slashes, uuid::Uuid},
+    io::ResourceIo,
+};
+use fxhash::FxHashMap;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// Extension of resource metadata file.
+pub const METADATA_EXTENSION: &str = "meta";
+
+/// Normalizes the given path, so the same file will always have the same path in the registry.
+/// It removes `./` components and replaces back slashes with forward slashes.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    replace_slashes(
+        path.components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect::<PathBuf>(),
+    )
+}
+
+/// Returns `true` if the given directory must be skipped when scanning for resources. Hidden
+/// directories (such as `.git`) and `target` directory with build artifacts never contain the
+/// resources of a project.
+pub fn is_excluded_directory(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name == "target" || (name.starts_with('.') && name != "."))
+}
+
+/// Returns a path of metadata file of the resource at the given path.
+pub fn metadata_path(resource_path: &Path) -> PathBuf {
+    append_extension(resource_path, METADATA_EXTENSION)
+}
+
+#[derive(Serialize, Deserialize)]
+struct ResourceMetadataFile {
+    resource_id: String,
+}
+
+/// Metadata of an external resource. It is stored in a separate file next to the resource file
+/// (with additional `.meta` extension) and must be moved together with the resource file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResourceMetadata {
+    /// Unique id of the resource. It never changes, even if the resource is moved or renamed.
+    pub resource_id: Uuid,
+}
+
+impl ResourceMetadata {
+    /// Creates new metadata with random unique id.
+    pub fn new_with_random_id() -> Self {
+        Self {
+            resource_id: Uuid::new_v4(),
+        }
+    }
+
+    /// Tries to load metadata of the resource at the given path.
+    pub async fn load(resource_path: &Path, io: &dyn ResourceIo) -> Result<Self, FileLoadError> {
+        let bytes = io.load_file(&metadata_path(resource_path)).await?;
+        let file = ron::de::from_bytes::<ResourceMetadataFile>(&bytes)
+            .map_err(|err| FileLoadError::Custom(err.to_string()))?;
+        let resource_id = Uuid::parse_str(&file.resource_id)
+            .map_err(|err| FileLoadError::Custom(err.to_string()))?;
+        Ok(Self { resource_id })
+    }
+
+    /// Saves the metadata of the resource at the given path.
+    pub async fn save(
+        &self,
+        resource_path: &Path,
+        io: &dyn ResourceIo,
+    ) -> Result<(), FileLoadError> {
+        let file = ResourceMetadataFile {
+            resource_id: self.resource_id.to_string(),
+        };
+        let string = ron::ser::to_string_pretty(&file, PrettyConfig::default())
+            .map_err(|err| FileLoadError::Custom(err.to_string()))?;
+        io.write_file(&metadata_path(resource_path), string.as_bytes())
+            .await
+    }
+}
+
+/// Resource registry is a two-way map between unique ids of external resources and their paths.
+/// Resource handles save the id of the resource next to its path, and the resource manager uses
+/// the registry to find the actual location of the resource on deserialization. This way the
+/// references to resources are not broken when the resources are moved to another place.
+///
+/// ## Filling
+///
+/// The ids are stored in metadata files next to the resources (see [`ResourceMetadata`]). Use
+/// [`crate::manager::ResourceManager::update_registry`] to scan a directory for metadata files.
+/// The resource manager keeps the registry up to date when a resource is registered or moved by
+/// it, or when its file system watcher reports that a file was renamed.
+///
+/// ## Paths
+///
+/// All paths are normalized using [`normalize_path`], so `./data/foo.png` and `data/foo.png` are
+/// the same path for the registry.
+#[derive(Default, Clone, Debug)]
+pub struct ResourceRegistry {
+    paths: FxHashMap<Uuid, PathBuf>,
+    ids: FxHashMap<PathBuf, Uuid>,
+}
+
+impl ResourceRegistry {
+    /// Associates the given id with the given path. Previous associations of the id and the path
+    /// are removed. Returns the previous path of the resource with the given id, if any.
+    pub fn register(&mut self, resource_id: Uuid, path: &Path) -> Option<PathBuf> {
+        let path = normalize_path(path);
+        if let Some(old_id) = self.ids.remove(&path) {
+            self.paths.remove(&old_id);
+        }
+        let old_path = self.paths.insert(resource_id, path.clone());
+        if let Some(old_path) = old_path.as_ref() {
+            self.ids.remove(old_path);
+        }
+        self.ids.insert(path, resource_id);
+        old_path
+    }
+
+    /// Removes a resource with the given path from the registry. Returns the id of the removed
+    /// resource, if any.
+    pub fn unregister_path(&mut self, path: &Path) -> Option<Uuid> {
+        let resource_id = self.ids.remove(&normalize_path(path))?;
+        self.paths.remove(&resource_id);
+        Some(resource_id)
+    }
+
+    /// Changes the path of a resource. Returns the id of the moved resource, if any.
+    pub fn move_path(&mut self, old_path: &Path, new_path: &Path) -> Option<Uuid> {
+        let resource_id = self.unregister_path(old_path)?;
+        self.register(resource_id, new_path);
+        Some(resource_id)
+    }
+
+    /// Returns the path of a resource with the given id.
+    pub fn path_of(&self, resource_id: Uuid) -> Option<&Path> {
+        self.paths.get(&resource_id).map(|path| path.as_path())
+    }
+
+    /// Returns the id of a resource at the given path.
+    pub fn uuid_of(&self, path: &Path) -> Option<Uuid> {
+        self.ids.get(&normalize_path(path)).cloned()
+    }
+
+    /// Returns total amount of the registered resources.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns `true` if the registry has no resources.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns an iterator over the registered resources (pairs of id and path).
+    pub fn iter(&self) -> impl Iterator<Item = (Uuid, &Path)> {
+        self.paths.iter().map(|(id, path)| (*id, path.as_path()))
+    }
+
+    /// Removes all resources from the registry.
+    pub fn clear(&mut self) {
+        self.paths.clear();
+        self.ids.clear();
+    }
+
+    /// Recursively searches for metadata files in the given directory and registers respective
+    /// resources. Returns a list of resource files (without metadata files) that were found in
+    /// the directory. If two resources have the same id (for example, when a resource was copied
+    /// together with its metadata), only the first one is registered. Excluded directories (see
+    /// [`is_excluded_directory`]) are skipped.
+    pub async fn scan(&mut self, root: &Path, io: &dyn ResourceIo) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        let mut directories = vec![root.to_path_buf()];
+        while let Some(directory) = directories.pop() {
+            let entries = match io.read_directory(&directory).await {
+                Ok(entries) => entries,
+                Err(err) => {
+                    Log::err(format!(
+                        "Unable to scan {} directory for resources. Reason: {:?}",
+                        directory.display(),
+                        err
+                    ));
+                    continue;
+                }
+            };
+            for path in entries {
+                if io.is_dir(&path).await {
+                    if !is_excluded_directory(&path) {
+                        directories.push(path);
+                    }
+                } else {
+                    paths.push(path);
+                }
+            }
+        }
+
+        let mut resource_paths = Vec::new();
+        for path in paths {
+            if path
+                .extension()
+                .map_or(true, |extension| extension != METADATA_EXTENSION)
+            {
+                resource_paths.push(normalize_path(&path));
+                continue;
+            }
+
+            let resource_path = path.with_extension("");
+            match ResourceMetadata::load(&resource_path, io).await {
+                Ok(metadata) => match self.path_of(metadata.resource_id) {
+                    Some(existing_path) if existing_path != normalize_path(&resource_path) => {
+                        Log::warn(format!(
+                            "Resources {} and {} have the same id {}, the latter is ignored!",
+                            existing_path.display(),
+                            resource_path.display(),
+                            metadata.resource_id
+                        ))
+                    }
+                    _ => {
+                        self.register(metadata.resource_id, &resource_path);
+                    }
+                },
+                Err(err) => Log::err(format!(
+                    "Unable to load metadata file {}. Reason: {:?}",
+                    path.display(),
+                    err
+                )),
+            }
+        }
+        resource_paths
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::FsResourceIo;
+    use fyrox_core::futures::executor::block_on;
+
+    #[test]
+    fn registry_register_and_move() {
+        let mut registry = ResourceRegistry::default();
+        let id = Uuid::new_v4();
+
+        assert_eq!(registry.register(id, Path::new("./data/foo.png")), None);
+        assert_eq!(registry.path_of(id), Some(Path::new("data/foo.png")));
+        assert_eq!(registry.uuid_of(Path::new("data/foo.png")), Some(id));
+
+        assert_eq!(
+            registry.move_path(Path::new("data/foo.png"), Path::new("data/bar/foo.png")),
+            Some(id)
+        );
+        assert_eq!(registry.path_of(id), Some(Path::new("data/bar/foo.png")));
+        assert_eq!(registry.uuid_of(Path::new("data/foo.png")), None);
+
+        // Another resource at the same path replaces the old one.
+        let other_id = Uuid::new_v4();
+        registry.register(other_id, Path::new("data/bar/foo.png"));
+        assert_eq!(registry.path_of(id), None);
+        assert_eq!(registry.len(), 1);
+
+        assert_eq!(
+            registry.unregister_path(Path::new("data/bar/foo.png")),
+            Some(other_id)
+        );
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn registry_scan() {
+        let root = &std::env::temp_dir().join("fyrox_registry_scan");
+        let _ = std::fs::remove_dir_all(root);
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+
+        let metadata = ResourceMetadata::new_with_random_id();
+        let resource_path = root.join("sub/foo.txt");
+        std::fs::write(&resource_path, "foo").unwrap();
+        block_on(metadata.save(&resource_path, &FsResourceIo)).unwrap();
+        assert_eq!(
+            block_on(ResourceMetadata::load(&resource_path, &FsResourceIo)).unwrap(),
+            metadata
+        );
+
+        // A resource without metadata.
+        std::fs::write(root.join("bar.txt"), "bar").unwrap();
+
+        // Excluded directories.
+        for excluded in ["target", ".git"] {
+            let excluded_path = root.join(excluded).join("baz.txt");
+            std::fs::create_dir_all(excluded_path.parent().unwrap()).unwrap();
+            std::fs::write(&excluded_path, "baz").unwrap();
+            block_on(ResourceMetadata::new_with_random_id().save(&excluded_path, &FsResourceIo))
+                .unwrap();
+        }
+
+        let mut registry = ResourceRegistry::default();
+        let resources = block_on(registry.scan(root, &FsResourceIo));
+        assert!(resources.contains(&normalize_path(&resource_path)));
+        assert!(resources.contains(&normalize_path(&root.join("bar.txt"))));
+        assert_eq!(resources.len(), 2);
+        assert_eq!(registry.len(), 1);
+        assert_eq!(
+            registry.path_of(metadata.resource_id),
+            Some(normalize_path(&resource_path).as_path())
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
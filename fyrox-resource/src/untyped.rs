@@ -197,6 +197,10 @@ pub struct ResourceHeader {
     pub type_uuid: Uuid,
     /// Kind of the resource. See [`ResourceKind`] for more info.
     pub kind: ResourceKind,
+    /// Unique id of an external resource, that is taken from [`crate::registry::ResourceRegistry`].
+    /// Nil id means that the resource is embedded or it is not registered in the registry. See
+    /// [`crate::registry::ResourceRegistry`] docs for more info.
+    pub resource_uuid: Uuid,
     /// Actual state of the resource. See [`ResourceState`] for more info.
     pub state: ResourceState,
 }
@@ -268,6 +272,17 @@ impl Visit for ResourceHeader {
         self.kind.visit("Kind", &mut region)?;
         self.type_uuid.visit("TypeUuid", &mut region)?;
 
+        if self.kind.is_external() && (region.is_reading() || !self.resource_uuid.is_nil()) {
+            // The id is optional, old versions and unregistered resources do not have it.
+            if self
+                .resource_uuid
+                .visit("ResourceUuid", &mut region)
+                .is_err()
+            {
+                self.resource_uuid = Uuid::nil();
+            }
+        }
+
         if self.kind == ResourceKind::Embedded {
             self.state.visit("State", &mut region)?;
         }
@@ -331,7 +346,10 @@ impl Visit for UntypedResource {
                 .expect("Resource manager must be available when deserializing resources!");
 
             let path = self.kind().path_owned().unwrap();
-            self.0 = resource_manager.request_untyped(path).0;
+            let resource_uuid = self.resource_uuid();
+            self.0 = resource_manager
+                .request_untyped_by_uuid(resource_uuid, path)
+                .0;
         }
 
         Ok(())
@@ -343,6 +361,7 @@ impl Default for UntypedResource {
         Self(Arc::new(Mutex::new(ResourceHeader {
             kind: Default::default(),
             type_uuid: Default::default(),
+            resource_uuid: Default::default(),
             state: ResourceState::new_load_error(LoadError::new(
                 "Default resource state of unknown type.",
             )),
@@ -376,6 +395,7 @@ impl UntypedResource {
         Self(Arc::new(Mutex::new(ResourceHeader {
             kind,
             type_uuid,
+            resource_uuid: Default::default(),
             state: ResourceState::new_pending(),
         })))
     }
@@ -389,6 +409,7 @@ impl UntypedResource {
         Self(Arc::new(Mutex::new(ResourceHeader {
             kind,
             type_uuid: data.type_uuid(),
+            resource_uuid: Default::default(),
             state: ResourceState::new_ok(data),
         })))
     }
@@ -398,6 +419,7 @@ impl UntypedResource {
         Self(Arc::new(Mutex::new(ResourceHeader {
             kind,
             type_uuid,
+            resource_uuid: Default::default(),
             state: ResourceState::new_load_error(error),
         })))
     }
@@ -407,6 +429,12 @@ impl UntypedResource {
         self.0.lock().type_uuid
     }
 
+    /// Returns unique id of the resource, see [`ResourceHeader::resource_uuid`] docs for more
+    /// info.
+    pub fn resource_uuid(&self) -> Uuid {
+        self.0.lock().resource_uuid
+    }
+
     /// Returns true if the resource is still loading.
     pub fn is_loading(&self) -> bool {
         matches!(self.0.lock().state, ResourceState::Pending { .. })
@@ -561,6 +589,31 @@ mod test {
         assert!(r.visit("name", &mut visitor).is_ok());
     }
 
+    #[test]
+    fn visit_for_resource_header_with_resource_uuid() {
+        let resource_uuid = Uuid::new_v4();
+        let mut header = ResourceHeader {
+            kind: PathBuf::from("foo.txt").into(),
+            type_uuid: Uuid::default(),
+            resource_uuid,
+            state: ResourceState::new_pending(),
+        };
+        let mut visitor = Visitor::new();
+        header.visit("Header", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut loaded = ResourceHeader {
+            kind: Default::default(),
+            type_uuid: Default::default(),
+            resource_uuid: Default::default(),
+            state: ResourceState::new_pending(),
+        };
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        loaded.visit("Header", &mut visitor).unwrap();
+        assert_eq!(loaded.kind, header.kind);
+        assert_eq!(loaded.resource_uuid, resource_uuid);
+    }
+
     #[test]
     fn debug_for_untyped_resource() {
         let r = UntypedResource::default();
@@ -658,6 +711,7 @@ mod test {
         let mut r = UntypedResource(Arc::new(Mutex::new(ResourceHeader {
             kind: path.clone().into(),
             type_uuid: Uuid::default(),
+            resource_uuid: Uuid::default(),
             state: ResourceState::Ok(Box::new(stub)),
         })));
         assert!(Pin::new(&mut r).poll(&mut cx).is_ready());
@@ -665,6 +719,7 @@ mod test {
         let mut r = UntypedResource(Arc::new(Mutex::new(ResourceHeader {
             kind: path.clone().into(),
             type_uuid: Uuid::default(),
+            resource_uuid: Uuid::default(),
             state: ResourceState::LoadError {
                 error: Default::default(),
             },
@@ -37,6 +37,7 @@ use fyrox_core::{
 };
 use fyrox_resource::{
     io::{FileReader, ResourceIo},
+    memory::ResourceMemoryUsage,
     Resource, ResourceData, SOUND_BUFFER_RESOURCE_UUID,
 };
 use std::error::Error;
@@ -296,4 +297,11 @@ impl ResourceData for SoundBuffer {
     fn can_be_saved(&self) -> bool {
         false
     }
+
+    fn memory_usage(&self) -> ResourceMemoryUsage {
+        ResourceMemoryUsage {
+            cpu: std::mem::size_of_val(self.samples()),
+            gpu: 0,
+        }
+    }
 }
@@ -57,8 +57,8 @@ use fyrox_core::{
     TypeUuidProvider,
 };
 use fyrox_resource::{
-    embedded_data_source, io::ResourceIo, manager::BuiltInResource, options::ImportOptions,
-    untyped::ResourceKind, Resource, ResourceData, TEXTURE_RESOURCE_UUID,
+    embedded_data_source, io::ResourceIo, manager::BuiltInResource, memory::ResourceMemoryUsage,
+    options::ImportOptions, untyped::ResourceKind, Resource, ResourceData, TEXTURE_RESOURCE_UUID,
 };
 use image::{ColorType, DynamicImage, ImageError, ImageFormat, Pixel};
 use lazy_static::lazy_static;
@@ -341,6 +341,17 @@ impl ResourceData for Texture {
     fn can_be_saved(&self) -> bool {
         true
     }
+
+    fn memory_usage(&self) -> ResourceMemoryUsage {
+        // Render targets do not have any data on CPU side, but they still occupy video memory.
+        let gpu = (0..self.mip_count.max(1) as usize)
+            .map(|mip| bytes_in_mip_level(self.kind, self.pixel_kind, mip) as usize)
+            .sum();
+        ResourceMemoryUsage {
+            cpu: self.bytes.len(),
+            gpu,
+        }
+    }
 }
 
 impl Visit for Texture {
@@ -360,7 +371,7 @@ impl Visit for Texture {
         self.anisotropy.visit("Anisotropy", &mut region)?;
         self.s_wrap_mode.visit("SWrapMode", &mut region)?;
         self.t_wrap_mode.visit("TWrapMode", &mut region)?;
-        let _ = self.t_wrap_mode.visit("RWrapMode", &mut region);
+        let _ = self.r_wrap_mode.visit("RWrapMode", &mut region);
         self.mip_count.visit("MipCount", &mut region)?;
         self.kind.visit("Kind", &mut region)?;
         let mut bytes_view = PodVecView::from_pod_vec(&mut self.bytes);
@@ -1993,3 +2004,31 @@ impl TextureDataRefMut<'_> {
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{Texture, TextureWrapMode};
+    use fyrox_core::visitor::{Visit, Visitor};
+
+    #[test]
+    fn test_wrap_modes_save_load() {
+        let mut texture = Texture {
+            s_wrap_mode: TextureWrapMode::ClampToEdge,
+            t_wrap_mode: TextureWrapMode::MirroredRepeat,
+            r_wrap_mode: TextureWrapMode::ClampToBorder,
+            ..Default::default()
+        };
+
+        let mut visitor = Visitor::new();
+        texture.visit("Texture", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut loaded = Texture::default();
+        loaded.visit("Texture", &mut visitor).unwrap();
+
+        assert_eq!(loaded.s_wrap_mode, TextureWrapMode::ClampToEdge);
+        assert_eq!(loaded.t_wrap_mode, TextureWrapMode::MirroredRepeat);
+        assert_eq!(loaded.r_wrap_mode, TextureWrapMode::ClampToBorder);
+    }
+}
@@ -20,14 +20,20 @@
 
 //! Texture loader.
 
-use crate::{Texture, TextureImportOptions};
+use crate::{CompressionOptions, Texture, TextureImportOptions};
 use fyrox_core::{uuid::Uuid, TypeUuidProvider};
 use fyrox_resource::{
-    io::ResourceIo, loader::BoxedImportOptionsLoaderFuture, loader::BoxedLoaderFuture,
-    loader::LoaderPayload, loader::ResourceLoader, options::try_get_import_settings,
-    options::try_get_import_settings_opaque, options::BaseImportOptions, state::LoadError,
+    cache::ArtifactPipeline, cache::VisitArtifactPipeline, io::ResourceIo,
+    loader::BoxedImportOptionsLoaderFuture, loader::BoxedLoaderFuture, loader::LoaderPayload,
+    loader::ResourceLoader, options::serialize_import_settings, options::try_get_import_settings,
+    options::try_get_import_settings_opaque, options::try_parse_import_settings,
+    options::BaseImportOptions, state::LoadError,
+};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
-use std::{path::PathBuf, sync::Arc};
 
 /// Default implementation for texture loading.
 pub struct TextureLoader {
@@ -76,4 +82,72 @@ impl ResourceLoader for TextureLoader {
     fn default_import_options(&self) -> Option<Box<dyn BaseImportOptions>> {
         Some(Box::<TextureImportOptions>::default())
     }
+
+    fn artifact_pipeline(&self, path: &Path) -> Option<Arc<dyn ArtifactPipeline>> {
+        // DDS textures are already stored in GPU-friendly format with all the mip levels, there's
+        // nothing to cache.
+        if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("dds"))
+        {
+            None
+        } else {
+            Some(Arc::new(TextureArtifactPipeline::new(
+                self.default_import_options.clone(),
+            )))
+        }
+    }
+}
+
+/// Artifact pipeline for textures. Only the textures, whose import options request compression or
+/// generation of mip levels, are stored in the import cache. Other textures are just decoded, which
+/// is as fast as loading their artifacts.
+pub struct TextureArtifactPipeline {
+    default_import_options: TextureImportOptions,
+    pipeline: VisitArtifactPipeline<Texture>,
+}
+
+impl TextureArtifactPipeline {
+    /// Creates new pipeline. Default import options are used for the textures without options
+    /// file.
+    pub fn new(default_import_options: TextureImportOptions) -> Self {
+        Self {
+            default_import_options,
+            // Version 2: the R wrap mode is saved correctly.
+            pipeline: VisitArtifactPipeline::new(2),
+        }
+    }
+}
+
+impl ArtifactPipeline for TextureArtifactPipeline {
+    fn version(&self) -> u32 {
+        self.pipeline.version()
+    }
+
+    fn extension(&self) -> &str {
+        self.pipeline.extension()
+    }
+
+    fn is_caching_required(&self, import_options: Option<&[u8]>) -> bool {
+        let import_options = import_options
+            .and_then(try_parse_import_settings::<TextureImportOptions>)
+            .unwrap_or_else(|| self.default_import_options.clone());
+        import_options.compression != CompressionOptions::NoCompression
+            || import_options.minification_filter.is_using_mip_mapping()
+    }
+
+    fn default_import_options(&self) -> Option<Vec<u8>> {
+        serialize_import_settings(&self.default_import_options)
+    }
+
+    fn save_artifact(
+        &self,
+        data: &mut dyn fyrox_resource::ResourceData,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.pipeline.save_artifact(data)
+    }
+
+    fn load_artifact(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        self.pipeline.load_artifact(path, io)
+    }
 }
@@ -292,6 +292,7 @@ use crate::{
         ButtonState, CursorIcon, KeyboardModifiers, MessageDirection, MouseButton, OsEvent,
         UiMessage,
     },
+    navigation::NavigationDirection,
     popup::{Placement, PopupMessage},
     widget::{Widget, WidgetBuilder, WidgetMessage},
 };
@@ -2734,6 +2735,27 @@ impl UserInterface {
         event_processed
     }
 
+    /// Requests focus navigation in the given direction. It sends [`WidgetMessage::Navigate`] message
+    /// from the current keyboard focus node, the message then can be handled by a parent
+    /// [`navigation::NavigationLayer`] widget. This method is meant to be used by input devices, that
+    /// do not have a keyboard (gamepads, remote controls, etc.). Returns `true` if the request was
+    /// sent, `false` - otherwise.
+    pub fn navigate(&mut self, direction: NavigationDirection) -> bool {
+        if let Some(keyboard_focus_node) = self.try_get(self.keyboard_focus_node) {
+            if keyboard_focus_node.is_globally_visible() {
+                self.send_message(WidgetMessage::navigate(
+                    self.keyboard_focus_node,
+                    MessageDirection::FromWidget,
+                    direction,
+                ));
+
+                return true;
+            }
+        }
+
+        false
+    }
+
     pub fn nodes(&self) -> &Pool<UiNode, WidgetContainer> {
         &self.nodes
     }
@@ -18,8 +18,9 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-//! A widget, that handles keyboard navigation on its descendant widgets using Tab key. See [`NavigationLayer`]
-//! docs for more info and usage examples.
+//! A widget, that handles keyboard navigation on its descendant widgets using Tab key (or any other
+//! input device, that sends [`WidgetMessage::Navigate`] messages). See [`NavigationLayer`] docs for
+//! more info and usage examples.
 
 #![warn(missing_docs)]
 
@@ -77,6 +78,13 @@ use std::ops::{Deref, DerefMut};
 ///
 /// This example shows how to create a simple confirmation dialog, that allows a user to use Tab key
 /// to cycle from one button to another. A focused button then can be "clicked" using Enter key.
+///
+/// ## Gamepads
+///
+/// The navigation layer also handles [`WidgetMessage::Navigate`] messages, which are produced by
+/// [`UserInterface::navigate`]. This method is meant to be used by input devices without a keyboard
+/// (the engine calls it when a user presses D-pad buttons on a gamepad), so the same layout could
+/// be used for controller-driven menus.
 #[derive(Default, Clone, Visit, Reflect, Debug, TypeUuidProvider, ComponentProvider)]
 #[type_uuid(id = "135d347b-5019-4743-906c-6df5c295a3be")]
 pub struct NavigationLayer {
@@ -90,6 +98,15 @@ pub struct NavigationLayer {
 
 crate::define_widget_deref!(NavigationLayer);
 
+/// Direction of focus navigation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NavigationDirection {
+    /// Moves focus to a widget with the next tab index.
+    Next,
+    /// Moves focus to a widget with the previous tab index.
+    Previous,
+}
+
 #[derive(Debug)]
 struct OrderedHandle {
     tab_index: usize,
@@ -100,58 +117,75 @@ impl Control for NavigationLayer {
     fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
         self.widget.handle_routed_message(ui, message);
 
-        if let Some(WidgetMessage::KeyDown(KeyCode::Tab)) = message.data() {
-            // Collect all descendant widgets, that supports Tab navigation.
-            let mut tab_list = Vec::new();
-            for &child in self.children() {
-                for (descendant_handle, descendant_ref) in ui.traverse_iter(child) {
-                    if !*descendant_ref.tab_stop && descendant_ref.is_globally_visible() {
-                        if let Some(tab_index) = *descendant_ref.tab_index {
-                            tab_list.push(OrderedHandle {
-                                tab_index,
-                                handle: descendant_handle,
-                            });
-                        }
+        let direction = match message.data() {
+            Some(WidgetMessage::KeyDown(KeyCode::Tab)) => {
+                if ui.keyboard_modifiers.shift {
+                    NavigationDirection::Previous
+                } else {
+                    NavigationDirection::Next
+                }
+            }
+            Some(WidgetMessage::Navigate(direction)) => *direction,
+            _ => return,
+        };
+
+        self.navigate(ui, direction);
+    }
+}
+
+impl NavigationLayer {
+    fn navigate(&self, ui: &mut UserInterface, direction: NavigationDirection) {
+        // Collect all descendant widgets, that supports Tab navigation.
+        let mut tab_list = Vec::new();
+        for &child in self.children() {
+            for (descendant_handle, descendant_ref) in ui.traverse_iter(child) {
+                if !*descendant_ref.tab_stop && descendant_ref.is_globally_visible() {
+                    if let Some(tab_index) = *descendant_ref.tab_index {
+                        tab_list.push(OrderedHandle {
+                            tab_index,
+                            handle: descendant_handle,
+                        });
                     }
                 }
             }
+        }
 
-            if !tab_list.is_empty() {
-                tab_list.sort_by_key(|entry| entry.tab_index);
+        if !tab_list.is_empty() {
+            tab_list.sort_by_key(|entry| entry.tab_index);
 
-                let focused_index = tab_list
-                    .iter()
-                    .position(|entry| entry.handle == ui.keyboard_focus_node)
-                    .unwrap_or_default();
+            let focused_index = tab_list
+                .iter()
+                .position(|entry| entry.handle == ui.keyboard_focus_node)
+                .unwrap_or_default();
 
-                let next_focused_node_index = if ui.keyboard_modifiers.shift {
+            let next_focused_node_index = match direction {
+                NavigationDirection::Previous => {
                     let count = tab_list.len() as isize;
                     let mut prev = (focused_index as isize).saturating_sub(1);
                     if prev < 0 {
                         prev += count;
                     }
                     (prev % count) as usize
-                } else {
-                    focused_index.saturating_add(1) % tab_list.len()
-                };
-
-                if let Some(entry) = tab_list.get(next_focused_node_index) {
-                    ui.send_message(WidgetMessage::focus(
-                        entry.handle,
-                        MessageDirection::ToWidget,
-                    ));
-
-                    if *self.bring_into_view {
-                        // Find a parent scroll viewer.
-                        if let Some((scroll_viewer, _)) =
-                            ui.find_component_up::<ScrollViewer>(entry.handle)
-                        {
-                            ui.send_message(ScrollViewerMessage::bring_into_view(
-                                scroll_viewer,
-                                MessageDirection::ToWidget,
-                                entry.handle,
-                            ));
-                        }
+                }
+                NavigationDirection::Next => focused_index.saturating_add(1) % tab_list.len(),
+            };
+
+            if let Some(entry) = tab_list.get(next_focused_node_index) {
+                ui.send_message(WidgetMessage::focus(
+                    entry.handle,
+                    MessageDirection::ToWidget,
+                ));
+
+                if *self.bring_into_view {
+                    // Find a parent scroll viewer.
+                    if let Some((scroll_viewer, _)) =
+                        ui.find_component_up::<ScrollViewer>(entry.handle)
+                    {
+                        ui.send_message(ScrollViewerMessage::bring_into_view(
+                            scroll_viewer,
+                            MessageDirection::ToWidget,
+                            entry.handle,
+                        ));
                     }
                 }
             }
@@ -187,11 +221,46 @@ impl NavigationLayerBuilder {
 
 #[cfg(test)]
 mod test {
-    use crate::navigation::NavigationLayerBuilder;
-    use crate::{test::test_widget_deletion, widget::WidgetBuilder};
+    use crate::navigation::{NavigationDirection, NavigationLayerBuilder};
+    use crate::{
+        button::ButtonBuilder,
+        core::algebra::Vector2,
+        message::MessageDirection,
+        test::test_widget_deletion,
+        widget::{WidgetBuilder, WidgetMessage},
+        UserInterface,
+    };
 
     #[test]
     fn test_deletion() {
         test_widget_deletion(|ctx| NavigationLayerBuilder::new(WidgetBuilder::new()).build(ctx));
     }
+
+    #[test]
+    fn test_navigate() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let ctx = &mut ui.build_ctx();
+        let first = ButtonBuilder::new(WidgetBuilder::new().with_tab_index(Some(0))).build(ctx);
+        let second = ButtonBuilder::new(WidgetBuilder::new().with_tab_index(Some(1))).build(ctx);
+        NavigationLayerBuilder::new(WidgetBuilder::new().with_child(first).with_child(second))
+            .build(ctx);
+
+        ui.update(screen_size, 0.0, &Default::default());
+        ui.send_message(WidgetMessage::focus(first, MessageDirection::ToWidget));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.keyboard_focus_node, first);
+
+        assert!(ui.navigate(NavigationDirection::Next));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.keyboard_focus_node, second);
+
+        assert!(ui.navigate(NavigationDirection::Next));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.keyboard_focus_node, first);
+
+        assert!(ui.navigate(NavigationDirection::Previous));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.keyboard_focus_node, second);
+    }
 }
@@ -39,6 +39,7 @@ use crate::{
     core::{parking_lot::Mutex, variable::InheritableVariable},
     define_constructor,
     message::{CursorIcon, Force, KeyCode, MessageDirection, UiMessage},
+    navigation::NavigationDirection,
     style::resource::StyleResourceExt,
     style::Style,
     BuildContext, HorizontalAlignment, LayoutEvent, MouseButton, MouseState, RcUiNodeHandle,
@@ -151,6 +152,13 @@ pub enum WidgetMessage {
     /// Direction: **From UI**.
     KeyUp(KeyCode),
 
+    /// Initiated when widget is in focus and user requests focus navigation using some input device
+    /// that does not have a keyboard (for example, a gamepad). See [`crate::navigation::NavigationLayer`]
+    /// docs for more info.
+    ///
+    /// Direction: **From UI**.
+    Navigate(NavigationDirection),
+
     /// Initiated when widget received focus (when direction is [`MessageDirection::FromWidget`]). In most cases focus is received
     /// by clicking on widget. You can request focus explicitly by sending this message to a widget with [`MessageDirection::ToWidget`]
     ///
@@ -680,6 +688,12 @@ impl WidgetMessage {
         WidgetMessage:KeyUp => fn key_up(KeyCode), layout: false
     );
 
+    define_constructor!(
+        /// Creates [`WidgetMessage::Navigate`] message. This method is for internal use only, and should not
+        /// be used anywhere else.
+        WidgetMessage:Navigate => fn navigate(NavigationDirection), layout: false
+    );
+
     define_constructor!(
         /// Creates [`WidgetMessage::DragStarted`] message. This method is for internal use only, and should not
         /// be used anywhere else.